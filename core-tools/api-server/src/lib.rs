@@ -10,36 +10,277 @@
 //! # Endpoints
 //!
 //! - POST /api/auth - Obtain authentication token
+//! - POST /api/auth/refresh - Exchange a token for a new one
+//! - POST /api/auth/logout - Revoke a token
 //! - POST /api/tasks - Submit a new task
 //! - GET /api/tasks/:id - Get task status
 //! - GET /api/tasks - Get task history
 //! - DELETE /api/tasks/:id - Cancel a task
 //! - GET /api/status - Get server status
+//! - GET /api/health - Get component readiness
+//! - GET /api/events?token=... - Stream task events (Server-Sent Events)
+//!
+//! `/api/health` needs no token so load balancers can poll it. It reports
+//! only booleans and counts, and answers 503 Service Unavailable when the
+//! engine is not healthy.
+//!
+//! `/api/auth` is rate limited per client IP (see `AUTH_MAX_ATTEMPTS`) and
+//! answers 429 Too Many Requests once the limit is reached. Task submissions,
+//! over REST and the WebSocket, go through the engine's rate limiter
+//! (`CoreContext::rate_limit`), keyed on the auth token. REST submissions
+//! answer 429 with the window and current count when a limit is exceeded.
+//!
+//! `/api/events` streams the same events as the WebSocket for clients behind
+//! proxies that block WebSockets, with a keepalive comment every 15 seconds.
+//!
+//! Inbound WebSocket messages are capped at `api_server.max_ws_message_size`
+//! bytes (default `DEFAULT_MAX_WS_MESSAGE_SIZE`). Oversized messages are
+//! rejected while being read and the connection is closed.
+//!
+//! Token length and alphabet come from `[api_server.auth]`: `token_length`
+//! (between `MIN_TOKEN_LENGTH` and `MAX_TOKEN_LENGTH`) and `token_encoding` (`alphanumeric` or
+//! `base64url`). Tokens are kept only as SHA-256 hashes. With
+//! `persist_tokens = true` the hashes are also stored in the `api_tokens`
+//! table, so tokens survive a restart until they expire.
+//!
+//! Task submissions are rate limited per client: a token issued by
+//! `/api/auth` gets its own rate limit source, and tokens refreshed from it
+//! keep that source, so refreshing doesn't reset the count.
+//!
+//! Cross-origin requests are allowed only from the origins listed in
+//! `api_server.allowed_origins` (none by default, i.e. same-origin only).
+//! `"*"` allows any origin, but then never with credentials.
+//!
+//! Every submitted task carries a request id: the client's `X-Request-Id`
+//! header (or `request_id` field over the WebSocket) when it is a sensible
+//! id, otherwise a generated one. It is returned with the task id and tags
+//! the task's log lines and database row.
 
 use axum::{
     extract::{
         ws::{Message, WebSocket},
-        Query, State, WebSocketUpgrade,
+        ConnectInfo, Path, Query, Request, State, WebSocketUpgrade,
+    },
+    http::{header, HeaderMap, HeaderName, HeaderValue, Method, StatusCode},
+    middleware::{self, Next},
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        IntoResponse, Response,
     },
-    http::{HeaderMap, StatusCode},
-    response::{IntoResponse, Response},
     routing::{get, post},
     Json, Router,
 };
+use futures_util::stream;
+use rand::rngs::OsRng;
 use rand::Rng;
-use sdk::{CoreContext, CoreTool, EngineError, ToolInput, ToolOutput};
+use sdk::{
+    CoreContext, CoreTool, EngineError, NoParams, RpcError, RpcRouter, ToolInput, ToolOutput,
+};
 use serde::{Deserialize, Serialize};
 use serde_json::json;
+use sha2::{Digest, Sha256};
+use std::collections::hash_map::Entry;
 use std::collections::HashMap;
-use std::net::{SocketAddr, TcpListener};
-use std::sync::{Arc, Mutex};
+use std::convert::Infallible;
+use std::net::{IpAddr, SocketAddr, TcpListener};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
 use tokio::sync::broadcast;
+use tower_http::cors::{AllowOrigin, Any, CorsLayer};
+
+/// Single task with its final answer, for `GET /api/tasks/:id`
+const TASK_STATUS_QUERY: &str = "SELECT id, input, status, created_at, \
+     (SELECT content FROM task_steps WHERE task_id = tasks.id \
+      AND step_type = 'assistant_message' ORDER BY step_order DESC LIMIT 1) AS result \
+     FROM tasks WHERE id = ?";
+
+/// Interval of `:keepalive` comments on `/api/events`
+const SSE_KEEPALIVE_INTERVAL: Duration = Duration::from_secs(15);
+
+/// How long an authentication token stays valid, in seconds
+const TOKEN_TTL_SECS: u64 = 24 * 60 * 60;
+
+/// Persisted tokens that have not expired yet
+const LOAD_TOKENS_QUERY: &str =
+    "SELECT token_hash, created_at, rate_limit_source FROM api_tokens WHERE created_at > ?";
+
+/// Store a token hash with its creation time and rate limit source
+const INSERT_TOKEN_QUERY: &str =
+    "INSERT INTO api_tokens (token_hash, created_at, rate_limit_source) VALUES (?, ?, ?)";
+
+/// Operation that task submissions are rate limited as
+const TASK_OPERATION: &str = "execute_task";
+
+/// Maximum `/api/auth` requests per client IP within `AUTH_RATE_WINDOW`
+const AUTH_MAX_ATTEMPTS: usize = 10;
+
+/// Sliding window for `/api/auth` rate limiting
+const AUTH_RATE_WINDOW: Duration = Duration::from_secs(60);
+
+/// Default maximum size of an inbound WebSocket message in bytes
+const DEFAULT_MAX_WS_MESSAGE_SIZE: usize = 64 * 1024;
+
+/// Default authentication token length in characters
+const DEFAULT_TOKEN_LENGTH: usize = 32;
+
+/// Shortest token length accepted from config
+const MIN_TOKEN_LENGTH: usize = 32;
+
+/// Longest token length accepted from config
+const MAX_TOKEN_LENGTH: usize = 256;
+
+/// Header a client may use to supply its own request id
+const REQUEST_ID_HEADER: &str = "x-request-id";
+
+/// Length of a generated request id
+const GENERATED_REQUEST_ID_LENGTH: usize = 16;
+
+const ALPHANUMERIC_CHARSET: &[u8] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789";
+
+const BASE64URL_CHARSET: &[u8] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+/// Character set used for authentication tokens
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TokenEncoding {
+    /// `A-Z`, `a-z`, `0-9` (about 5.95 bits per character)
+    Alphanumeric,
+    /// URL-safe base64 alphabet without padding (6 bits per character)
+    Base64Url,
+}
+
+impl TokenEncoding {
+    fn parse(name: &str) -> Option<Self> {
+        match name {
+            "alphanumeric" => Some(Self::Alphanumeric),
+            "base64url" => Some(Self::Base64Url),
+            _ => None,
+        }
+    }
+
+    fn charset(self) -> &'static [u8] {
+        match self {
+            Self::Alphanumeric => ALPHANUMERIC_CHARSET,
+            Self::Base64Url => BASE64URL_CHARSET,
+        }
+    }
+}
+
+/// Token strength settings from `[api_server.auth]`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct TokenConfig {
+    length: usize,
+    encoding: TokenEncoding,
+}
+
+impl TokenConfig {
+    /// Build from raw config values, enforcing `MIN_TOKEN_LENGTH` and
+    /// `MAX_TOKEN_LENGTH`
+    fn new(length: Option<i64>, encoding: Option<&str>) -> Self {
+        let length = match length {
+            Some(length) if length < MIN_TOKEN_LENGTH as i64 => {
+                tracing::warn!(
+                    "api_server.auth.token_length {} is below the minimum, using {}",
+                    length,
+                    MIN_TOKEN_LENGTH
+                );
+                MIN_TOKEN_LENGTH
+            }
+            Some(length) if length > MAX_TOKEN_LENGTH as i64 => {
+                tracing::warn!(
+                    "api_server.auth.token_length {} is above the maximum, using {}",
+                    length,
+                    MAX_TOKEN_LENGTH
+                );
+                MAX_TOKEN_LENGTH
+            }
+            Some(length) => length as usize,
+            None => DEFAULT_TOKEN_LENGTH,
+        };
+
+        let encoding = match encoding {
+            Some(name) => TokenEncoding::parse(name).unwrap_or_else(|| {
+                tracing::warn!(
+                    "Unknown api_server.auth.token_encoding '{}', using alphanumeric",
+                    name
+                );
+                TokenEncoding::Alphanumeric
+            }),
+            None => TokenEncoding::Alphanumeric,
+        };
+
+        Self { length, encoding }
+    }
+
+    /// Read `api_server.auth.token_length` and `api_server.auth.token_encoding`
+    fn from_context(ctx: &CoreContext) -> Self {
+        Self::new(
+            ctx.config.get_i64("api_server.auth.token_length"),
+            ctx.config
+                .get_string("api_server.auth.token_encoding")
+                .as_deref(),
+        )
+    }
+}
+
+impl Default for TokenConfig {
+    fn default() -> Self {
+        Self::new(None, None)
+    }
+}
+
+/// CORS layer allowing cross-origin requests from `origins`
+///
+/// Empty allows none (same-origin only). `"*"` allows any origin without
+/// credentials; otherwise only the listed origins, with credentials.
+/// Preflight requests are answered by the layer itself.
+fn cors_for_origins(origins: &[String]) -> CorsLayer {
+    let cors = CorsLayer::new()
+        .allow_methods([Method::GET, Method::POST])
+        .allow_headers([
+            header::AUTHORIZATION,
+            header::CONTENT_TYPE,
+            HeaderName::from_static(REQUEST_ID_HEADER),
+        ]);
+
+    if origins.iter().any(|origin| origin == "*") {
+        return cors.allow_origin(Any);
+    }
+
+    let allowed: Vec<HeaderValue> = origins
+        .iter()
+        .filter_map(|origin| match HeaderValue::from_str(origin) {
+            Ok(value) => Some(value),
+            Err(_) => {
+                tracing::warn!(
+                    "Ignoring invalid origin '{}' in api_server.allowed_origins",
+                    origin
+                );
+                None
+            }
+        })
+        .collect();
+    if allowed.is_empty() {
+        return cors;
+    }
+    cors.allow_origin(AllowOrigin::list(allowed))
+        .allow_credentials(true)
+}
 
-/// Authentication token
+/// Authentication token, stored under the hash of its value (see
+/// `token_hash`)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 struct AuthToken {
-    token: String,
     created_at: u64,
+    /// Source its task submissions are rate limited as, taken over from the
+    /// token it was refreshed from (see `hash_rate_limit_source`)
+    rate_limit_source: String,
+}
+
+/// Hex SHA-256 of `token`, the key it is stored and looked up under
+fn token_hash(token: &str) -> String {
+    format!("{:x}", Sha256::digest(token.as_bytes()))
 }
 
 /// WebSocket query parameters for authentication
@@ -55,11 +296,56 @@ struct AuthRequest {
 }
 
 /// API response for authentication
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize)]
 struct AuthResponse {
     token: String,
 }
 
+/// In-memory sliding-window limiter for authentication attempts, keyed by IP
+#[derive(Debug)]
+struct AuthRateLimiter {
+    attempts: Mutex<HashMap<IpAddr, Vec<Instant>>>,
+    max_attempts: usize,
+    window: Duration,
+}
+
+impl AuthRateLimiter {
+    fn new(max_attempts: usize, window: Duration) -> Self {
+        Self {
+            attempts: Mutex::new(HashMap::new()),
+            max_attempts,
+            window,
+        }
+    }
+
+    /// Record an attempt from `ip`
+    ///
+    /// Returns the time until the oldest attempt leaves the window if the
+    /// limit has been reached; rejected attempts are not recorded.
+    fn check(&self, ip: IpAddr) -> Result<(), Duration> {
+        let now = Instant::now();
+        let mut attempts = self.attempts.lock().expect("auth attempts lock poisoned");
+
+        // Forget clients whose attempts have all expired
+        attempts.retain(|_, times| {
+            times.retain(|t| now.duration_since(*t) < self.window);
+            !times.is_empty()
+        });
+
+        let times = attempts.entry(ip).or_default();
+        if times.len() >= self.max_attempts {
+            let retry_after = times
+                .first()
+                .map(|oldest| self.window.saturating_sub(now.duration_since(*oldest)))
+                .unwrap_or(self.window);
+            return Err(retry_after);
+        }
+
+        times.push(now);
+        Ok(())
+    }
+}
+
 /// API server state shared across handlers
 #[derive(Clone)]
 struct ServerState {
@@ -68,6 +354,55 @@ struct ServerState {
     connections: Arc<Mutex<Vec<broadcast::Sender<String>>>>,
     auth_tokens: Arc<Mutex<HashMap<String, AuthToken>>>,
     event_tx: broadcast::Sender<String>,
+    max_ws_message_size: usize,
+    token_config: TokenConfig,
+    /// Also store token hashes in the database (`api_server.auth.persist_tokens`)
+    persist_tokens: bool,
+}
+
+impl ServerState {
+    /// Store the hash of a newly issued token, pruning expired ones
+    ///
+    /// A no-op unless tokens are persisted. Failures are logged: the token
+    /// still works until the server restarts.
+    fn persist_token(&self, token: &str, entry: &AuthToken) {
+        if !self.persist_tokens {
+            return;
+        }
+        let result = self
+            .ctx
+            .db
+            .execute(
+                "DELETE FROM api_tokens WHERE created_at <= ?",
+                vec![json!(entry.created_at.saturating_sub(TOKEN_TTL_SECS))],
+            )
+            .and_then(|_| {
+                self.ctx.db.execute(
+                    INSERT_TOKEN_QUERY,
+                    vec![
+                        json!(token_hash(token)),
+                        json!(entry.created_at),
+                        json!(entry.rate_limit_source),
+                    ],
+                )
+            });
+        if let Err(e) = result {
+            tracing::warn!("Failed to persist authentication token: {}", e);
+        }
+    }
+
+    /// Remove the stored hash of a revoked token
+    fn forget_token(&self, token: &str) {
+        if !self.persist_tokens {
+            return;
+        }
+        if let Err(e) = self.ctx.db.execute(
+            "DELETE FROM api_tokens WHERE token_hash = ?",
+            vec![json!(token_hash(token))],
+        ) {
+            tracing::warn!("Failed to remove persisted authentication token: {}", e);
+        }
+    }
 }
 
 /// API server
@@ -115,23 +450,50 @@ impl APIServer {
         let event_tx_clone = event_tx.clone();
 
         // Create server state
+        let persist_tokens = ctx
+            .config
+            .get_bool("api_server.auth.persist_tokens")
+            .unwrap_or(false);
+        let auth_tokens = if persist_tokens {
+            Self::load_persisted_tokens(&ctx)
+        } else {
+            HashMap::new()
+        };
         let state = ServerState {
             ctx: ctx.clone(),
             connections: Arc::new(Mutex::new(Vec::new())),
-            auth_tokens: Arc::new(Mutex::new(HashMap::new())),
+            auth_tokens: Arc::new(Mutex::new(auth_tokens)),
             event_tx: event_tx_clone,
+            max_ws_message_size: Self::max_ws_message_size(&ctx),
+            token_config: TokenConfig::from_context(&ctx),
+            persist_tokens,
         };
 
+        // Brute-force protection for token issuance
+        let auth_limiter = Arc::new(AuthRateLimiter::new(AUTH_MAX_ATTEMPTS, AUTH_RATE_WINDOW));
+
         // Build router with WebSocket and API endpoints
         let app = Router::new()
             .route("/ws", get(websocket_handler))
-            .route("/api/auth", post(auth_handler))
+            .route("/api/events", get(events_handler))
+            .route(
+                "/api/auth",
+                post(auth_handler).layer(middleware::from_fn_with_state(
+                    auth_limiter,
+                    auth_rate_limit,
+                )),
+            )
+            .route("/api/auth/refresh", post(refresh_handler))
+            .route("/api/auth/logout", post(logout_handler))
             .route("/api/submit_task", post(submit_task_handler))
+            .route("/api/tasks/:id", get(task_status_handler))
             .route("/api/history", get(history_handler))
             .route("/api/status", get(status_handler))
+            .route("/api/health", get(health_handler))
             .route("/", get(index_handler))
             .fallback(index_handler)
-            .with_state(state);
+            .with_state(state)
+            .layer(Self::cors_layer(&ctx));
 
         // Convert std TcpListener to tokio
         listener
@@ -147,20 +509,99 @@ impl APIServer {
         tokio::spawn(async move {
             tracing::info!("API server listening on http://{}", addr);
 
-            axum::serve(tokio_listener, app)
-                .with_graceful_shutdown(async move {
-                    shutdown_rx.await.ok();
-                    tracing::info!("API server shutting down gracefully");
-                })
-                .await
-                .unwrap_or_else(|e| {
-                    tracing::error!("API server error: {}", e);
-                });
+            // Client addresses are needed for per-IP auth rate limiting
+            axum::serve(
+                tokio_listener,
+                app.into_make_service_with_connect_info::<SocketAddr>(),
+            )
+            .with_graceful_shutdown(async move {
+                shutdown_rx.await.ok();
+                tracing::info!("API server shutting down gracefully");
+            })
+            .await
+            .unwrap_or_else(|e| {
+                tracing::error!("API server error: {}", e);
+            });
         });
 
         Ok((addr, shutdown_tx, event_tx))
     }
 
+    /// Read `api_server.max_ws_message_size`, falling back to the default
+    fn max_ws_message_size(ctx: &CoreContext) -> usize {
+        match ctx.config.get_i64("api_server.max_ws_message_size") {
+            Some(size) if size > 0 => size as usize,
+            Some(size) => {
+                tracing::warn!(
+                    "Ignoring invalid api_server.max_ws_message_size {}, using {}",
+                    size,
+                    DEFAULT_MAX_WS_MESSAGE_SIZE
+                );
+                DEFAULT_MAX_WS_MESSAGE_SIZE
+            }
+            None => DEFAULT_MAX_WS_MESSAGE_SIZE,
+        }
+    }
+
+    /// Unexpired token hashes stored by a previous run
+    ///
+    /// Falls back to no tokens if the database can't be read.
+    fn load_persisted_tokens(ctx: &CoreContext) -> HashMap<String, AuthToken> {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .expect("Time went backwards")
+            .as_secs();
+        let rows = match ctx.db.query(
+            LOAD_TOKENS_QUERY,
+            vec![json!(now.saturating_sub(TOKEN_TTL_SECS))],
+        ) {
+            Ok(rows) => rows,
+            Err(e) => {
+                tracing::warn!("Failed to load persisted authentication tokens: {}", e);
+                return HashMap::new();
+            }
+        };
+
+        let tokens: HashMap<String, AuthToken> = rows
+            .iter()
+            .filter_map(|row| {
+                let hash = row.get("token_hash")?.as_str()?;
+                let created_at = row.get("created_at")?.as_u64()?;
+                // Stored before tokens kept their source across refreshes
+                let rate_limit_source = match row.get("rate_limit_source") {
+                    Some(serde_json::Value::String(source)) => source.clone(),
+                    _ => hash_rate_limit_source(hash),
+                };
+                let token = AuthToken {
+                    created_at,
+                    rate_limit_source,
+                };
+                Some((hash.to_string(), token))
+            })
+            .collect();
+        tracing::info!("Loaded {} persisted authentication tokens", tokens.len());
+        tokens
+    }
+
+    /// Build the CORS layer from `api_server.allowed_origins`
+    fn cors_layer(ctx: &CoreContext) -> CorsLayer {
+        let origins: Vec<String> = match ctx.config.get("api_server.allowed_origins") {
+            Some(serde_json::Value::Array(values)) => values
+                .iter()
+                .filter_map(|value| value.as_str().map(String::from))
+                .collect(),
+            Some(other) => {
+                tracing::warn!(
+                    "Ignoring api_server.allowed_origins {}: expected a list of origins",
+                    other
+                );
+                Vec::new()
+            }
+            None => Vec::new(),
+        };
+        cors_for_origins(&origins)
+    }
+
     /// Save the port to config.toml (Requirement 17.2)
     fn save_port_to_config(_ctx: &CoreContext, port: u16) -> Result<(), EngineError> {
         // Get the config file path
@@ -168,31 +609,20 @@ impl APIServer {
             .ok_or_else(|| EngineError::Config("Could not determine home directory".to_string()))?;
         let config_path = home.join(".rove").join("config.toml");
 
-        // Read existing config
-        let config_str = std::fs::read_to_string(&config_path)
-            .map_err(|e| EngineError::Config(format!("Failed to read config file: {}", e)))?;
-
-        // Parse as TOML value
-        let mut config: toml::Value = toml::from_str(&config_str)
-            .map_err(|e| EngineError::Config(format!("Failed to parse config: {}", e)))?;
+        // Locked so a concurrent `rove config set` isn't lost
+        sdk::config_file::update_toml(&config_path, |config| {
+            // Add or update api_server section
+            if let Some(table) = config.as_table_mut() {
+                let api_server_section = table
+                    .entry("api_server".to_string())
+                    .or_insert(toml::Value::Table(toml::map::Map::new()));
 
-        // Add or update api_server section
-        if let Some(table) = config.as_table_mut() {
-            let api_server_section = table
-                .entry("api_server".to_string())
-                .or_insert(toml::Value::Table(toml::map::Map::new()));
-
-            if let Some(api_table) = api_server_section.as_table_mut() {
-                api_table.insert("port".to_string(), toml::Value::Integer(port as i64));
+                if let Some(api_table) = api_server_section.as_table_mut() {
+                    api_table.insert("port".to_string(), toml::Value::Integer(port as i64));
+                }
             }
-        }
-
-        // Write back to file
-        let updated_config = toml::to_string_pretty(&config)
-            .map_err(|e| EngineError::Config(format!("Failed to serialize config: {}", e)))?;
-
-        std::fs::write(&config_path, updated_config)
-            .map_err(|e| EngineError::Config(format!("Failed to write config file: {}", e)))?;
+            Ok(())
+        })?;
 
         tracing::info!("Saved API server port {} to config.toml", port);
         Ok(())
@@ -217,36 +647,70 @@ impl APIServer {
     }
 
     /// Generate a new authentication token (Requirement 17.6)
-    fn generate_token() -> String {
-        let mut rng = rand::thread_rng();
-        let token: String = (0..32)
-            .map(|_| {
-                let idx = rng.gen_range(0..62);
-                match idx {
-                    0..=25 => (b'A' + idx) as char,
-                    26..=51 => (b'a' + (idx - 26)) as char,
-                    _ => (b'0' + (idx - 52)) as char,
-                }
-            })
-            .collect();
-        token
+    ///
+    /// Tokens grant API access, so they are drawn from the OS CSPRNG.
+    fn generate_token(config: &TokenConfig) -> String {
+        let mut rng = OsRng;
+        let charset = config.encoding.charset();
+        (0..config.length)
+            .map(|_| charset[rng.gen_range(0..charset.len())] as char)
+            .collect()
+    }
+
+    /// Store a freshly generated token, regenerating on a collision
+    ///
+    /// The token is rate limited as `rate_limit_source`, or with `None` as a
+    /// source of its own. A collision is astronomically unlikely, but
+    /// silently overwriting an existing token would hand one client another
+    /// client's session.
+    fn issue_token(
+        tokens: &mut HashMap<String, AuthToken>,
+        created_at: u64,
+        rate_limit_source: Option<String>,
+        mut generate: impl FnMut() -> String,
+    ) -> String {
+        loop {
+            let token = generate();
+            let hash = token_hash(&token);
+            if let Entry::Vacant(entry) = tokens.entry(hash.clone()) {
+                let rate_limit_source =
+                    rate_limit_source.unwrap_or_else(|| hash_rate_limit_source(&hash));
+                entry.insert(AuthToken {
+                    created_at,
+                    rate_limit_source,
+                });
+                return token;
+            }
+            tracing::warn!("Generated token collided with an existing token, regenerating");
+        }
     }
 
     /// Validate an authentication token (Requirement 17.6)
     fn validate_token(tokens: &HashMap<String, AuthToken>, token: &str) -> bool {
-        if let Some(auth_token) = tokens.get(token) {
+        if let Some(auth_token) = tokens.get(&token_hash(token)) {
             // Check if token is not expired (24 hours)
             let now = std::time::SystemTime::now()
                 .duration_since(std::time::UNIX_EPOCH)
                 .unwrap()
                 .as_secs();
 
-            let age = now - auth_token.created_at;
-            age < 86400 // 24 hours
+            let age = now.saturating_sub(auth_token.created_at);
+            age < TOKEN_TTL_SECS
         } else {
             false
         }
     }
+
+    /// Rate limit source of `token`, or `None` if it is not valid (see
+    /// `validate_token`)
+    fn token_rate_limit_source(tokens: &HashMap<String, AuthToken>, token: &str) -> Option<String> {
+        if !Self::validate_token(tokens, token) {
+            return None;
+        }
+        tokens
+            .get(&token_hash(token))
+            .map(|entry| entry.rate_limit_source.clone())
+    }
 }
 
 impl Default for APIServer {
@@ -298,23 +762,29 @@ impl CoreTool for APIServer {
     }
 
     fn handle(&self, input: ToolInput) -> Result<ToolOutput, EngineError> {
-        // Handle API requests
-        match input.method.as_str() {
-            "get_port" => {
-                if let Some(addr) = self.addr {
-                    Ok(ToolOutput::json(json!({ "port": addr.port() })))
-                } else {
-                    Err(EngineError::ToolError("Server not started".to_string()))
-                }
-            }
-            _ => Err(EngineError::ToolError(format!(
-                "Unknown method: {}",
-                input.method
-            ))),
-        }
+        Ok(rpc_methods().dispatch(self, input))
     }
 }
 
+/// Result of `get_port`
+#[derive(Debug, Serialize)]
+struct PortInfo {
+    port: u16,
+}
+
+/// Methods callable through `CoreTool::handle`
+fn rpc_methods() -> &'static RpcRouter<APIServer> {
+    static METHODS: OnceLock<RpcRouter<APIServer>> = OnceLock::new();
+    METHODS.get_or_init(|| {
+        RpcRouter::new().method("get_port", |server: &APIServer, _: NoParams| {
+            server
+                .addr
+                .map(|addr| PortInfo { port: addr.port() })
+                .ok_or_else(|| RpcError::internal("Server not started"))
+        })
+    })
+}
+
 /// WebSocket handler (Requirement 17.3, 17.6)
 async fn websocket_handler(
     ws: WebSocketUpgrade,
@@ -335,20 +805,33 @@ async fn websocket_handler(
 
     // Validate token
     let tokens = state.auth_tokens.lock().expect("auth_tokens lock poisoned");
-    if !APIServer::validate_token(&tokens, &token) {
+    let Some(source) = APIServer::token_rate_limit_source(&tokens, &token) else {
         return (
             StatusCode::UNAUTHORIZED,
             Json(json!({"error": "Invalid or expired token"})),
         )
             .into_response();
-    }
+    };
     drop(tokens);
 
-    ws.on_upgrade(|socket| handle_websocket(socket, state))
+    limit_message_size(ws, state.max_ws_message_size)
+        .on_upgrade(|socket| handle_websocket(socket, state, source))
+}
+
+/// Cap inbound WebSocket messages and frames at `max_size` bytes
+///
+/// The limit is enforced while reading, so an oversized message is rejected
+/// before it is buffered in full; `handle_websocket` then sees a receive error
+/// and closes the connection.
+fn limit_message_size(ws: WebSocketUpgrade, max_size: usize) -> WebSocketUpgrade {
+    ws.max_message_size(max_size).max_frame_size(max_size)
 }
 
 /// Handle WebSocket connection (Requirement 17.5)
-async fn handle_websocket(mut socket: WebSocket, state: ServerState) {
+///
+/// Task submissions count against the rate limits of `source`, the
+/// connection's auth token.
+async fn handle_websocket(mut socket: WebSocket, state: ServerState, source: String) {
     tracing::info!("New WebSocket connection established");
 
     // Subscribe to event broadcast channel for task streaming (Requirement 17.5)
@@ -375,11 +858,24 @@ async fn handle_websocket(mut socket: WebSocket, state: ServerState) {
                                     }
                                     "submit_task" => {
                                         if let Some(task_input) = json_msg.get("task").and_then(|v| v.as_str()) {
-                                            match state.ctx.agent.submit_task(task_input.to_string()) {
+                                            if let Err(e) = state.ctx.rate_limit.check_and_record(&source, TASK_OPERATION) {
+                                                let error = json!({
+                                                    "type": "error",
+                                                    "message": e.to_string()
+                                                });
+                                                if socket.send(Message::Text(error.to_string())).await.is_err() {
+                                                    break;
+                                                }
+                                                continue;
+                                            }
+                                            let request_id = request_id(json_msg.get("request_id").and_then(|v| v.as_str()));
+                                            tracing::info!(request_id = %request_id, "Task submitted over WebSocket");
+                                            match state.ctx.agent.submit_task_for_request(task_input.to_string(), &request_id) {
                                                 Ok(task_id) => {
                                                     let response = json!({
                                                         "type": "task_submitted",
-                                                        "task_id": task_id
+                                                        "task_id": task_id,
+                                                        "request_id": request_id
                                                     });
                                                     if socket.send(Message::Text(response.to_string())).await.is_err() {
                                                         break;
@@ -409,6 +905,7 @@ async fn handle_websocket(mut socket: WebSocket, state: ServerState) {
                         break;
                     }
                     Some(Err(e)) => {
+                        // Includes messages over `max_ws_message_size`
                         tracing::error!("WebSocket error: {}", e);
                         break;
                     }
@@ -437,37 +934,207 @@ async fn handle_websocket(mut socket: WebSocket, state: ServerState) {
     tracing::info!("WebSocket connection closed");
 }
 
+/// Server-Sent Events handler, for clients that can't use WebSockets
+///
+/// Streams the same events as `/ws`, one `data:` frame per event, with a
+/// `:keepalive` comment every `SSE_KEEPALIVE_INTERVAL` so proxies don't drop
+/// idle connections. Like `/ws`, the token is passed as `?token=`.
+async fn events_handler(
+    Query(query): Query<WsQuery>,
+    State(state): State<ServerState>,
+) -> Response {
+    // Require authentication token (Requirement 17.6)
+    let Some(token) = query.token else {
+        return (
+            StatusCode::UNAUTHORIZED,
+            Json(json!({"error": "Authentication token required"})),
+        )
+            .into_response();
+    };
+
+    // Validate token
+    {
+        let tokens = state.auth_tokens.lock().expect("auth_tokens lock poisoned");
+        if !APIServer::validate_token(&tokens, &token) {
+            return (
+                StatusCode::UNAUTHORIZED,
+                Json(json!({"error": "Invalid or expired token"})),
+            )
+                .into_response();
+        }
+    }
+
+    tracing::info!("New event stream connection established");
+
+    let event_rx = state.event_tx.subscribe();
+    let events = stream::unfold(event_rx, |mut event_rx| async move {
+        loop {
+            match event_rx.recv().await {
+                Ok(event_json) => {
+                    return Some((
+                        Ok::<_, Infallible>(Event::default().data(event_json)),
+                        event_rx,
+                    ))
+                }
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    tracing::warn!("Event stream client lagged, skipped {} events", skipped);
+                }
+                Err(broadcast::error::RecvError::Closed) => return None,
+            }
+        }
+    });
+
+    Sse::new(events)
+        .keep_alive(
+            KeepAlive::new()
+                .interval(SSE_KEEPALIVE_INTERVAL)
+                .text("keepalive"),
+        )
+        .into_response()
+}
+
+/// Per-IP rate limit for `/api/auth`
+///
+/// Rejects requests with 429 Too Many Requests and a `Retry-After` header once
+/// a client has made `AUTH_MAX_ATTEMPTS` attempts within `AUTH_RATE_WINDOW`.
+async fn auth_rate_limit(
+    State(limiter): State<Arc<AuthRateLimiter>>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    request: Request,
+    next: Next,
+) -> Response {
+    match limiter.check(addr.ip()) {
+        Ok(()) => next.run(request).await,
+        Err(retry_after) => {
+            tracing::warn!("Too many authentication attempts from {}", addr.ip());
+            (
+                StatusCode::TOO_MANY_REQUESTS,
+                [(
+                    header::RETRY_AFTER,
+                    retry_after.as_secs().max(1).to_string(),
+                )],
+                Json(json!({"error": "Too many authentication attempts. Try again later."})),
+            )
+                .into_response()
+        }
+    }
+}
+
 /// Authentication endpoint (Requirement 17.6)
 async fn auth_handler(
     State(state): State<ServerState>,
     Json(_payload): Json<AuthRequest>,
 ) -> Result<Json<AuthResponse>, Response> {
-    // Generate a new authentication token
-    let token = APIServer::generate_token();
     let now = std::time::SystemTime::now()
         .duration_since(std::time::UNIX_EPOCH)
         .expect("Time went backwards")
         .as_secs();
 
-    let auth_token = AuthToken {
-        token: token.clone(),
-        created_at: now,
-    };
-
-    // Store the token
-    {
+    // Generate and store a new authentication token
+    let (token, entry) = {
         let mut tokens = state.auth_tokens.lock().expect("auth_tokens lock poisoned");
-        tokens.insert(token.clone(), auth_token);
+        let token = APIServer::issue_token(&mut tokens, now, None, || {
+            APIServer::generate_token(&state.token_config)
+        });
+        let entry = tokens[&token_hash(&token)].clone();
 
         // Clean up expired tokens (older than 24 hours)
-        tokens.retain(|_, t| now - t.created_at < 86400);
-    }
+        tokens.retain(|_, t| now.saturating_sub(t.created_at) < TOKEN_TTL_SECS);
+        (token, entry)
+    };
+    state.persist_token(&token, &entry);
 
     tracing::info!("Generated new authentication token");
 
     Ok(Json(AuthResponse { token }))
 }
 
+/// Bearer token of a request
+fn bearer_token(headers: &HeaderMap) -> Option<&str> {
+    headers
+        .get("Authorization")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+}
+
+/// Response for a request without a Bearer token
+fn missing_authorization() -> Response {
+    (
+        StatusCode::UNAUTHORIZED,
+        Json(json!({"error": "Missing authorization header"})),
+    )
+        .into_response()
+}
+
+/// Token refresh endpoint
+///
+/// Exchanges a valid Bearer token for a new one. The old token is invalid
+/// from then on; the new one is valid for a full 24 hours.
+async fn refresh_handler(
+    State(state): State<ServerState>,
+    headers: HeaderMap,
+) -> Result<Json<AuthResponse>, Response> {
+    let old = bearer_token(&headers).ok_or_else(missing_authorization)?;
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .expect("Time went backwards")
+        .as_secs();
+
+    // Swap under one lock so the old token can't be used to refresh twice.
+    // The new token keeps the old one's rate limit source, so refreshing
+    // doesn't reset the client's task budget.
+    let (token, entry) = {
+        let mut tokens = state.auth_tokens.lock().expect("auth_tokens lock poisoned");
+        let Some(source) = APIServer::token_rate_limit_source(&tokens, old) else {
+            return Err((
+                StatusCode::UNAUTHORIZED,
+                Json(json!({"error": "Invalid or expired token"})),
+            )
+                .into_response());
+        };
+        tokens.remove(&token_hash(old));
+        let token = APIServer::issue_token(&mut tokens, now, Some(source), || {
+            APIServer::generate_token(&state.token_config)
+        });
+        let entry = tokens[&token_hash(&token)].clone();
+        (token, entry)
+    };
+    state.forget_token(old);
+    state.persist_token(&token, &entry);
+
+    tracing::info!("Refreshed authentication token");
+
+    Ok(Json(AuthResponse { token }))
+}
+
+/// Logout endpoint
+///
+/// Revokes the Bearer token immediately.
+async fn logout_handler(
+    State(state): State<ServerState>,
+    headers: HeaderMap,
+) -> Result<Json<serde_json::Value>, Response> {
+    let token = bearer_token(&headers).ok_or_else(missing_authorization)?;
+
+    let removed = state
+        .auth_tokens
+        .lock()
+        .expect("auth_tokens lock poisoned")
+        .remove(&token_hash(token));
+    if removed.is_none() {
+        return Err((
+            StatusCode::UNAUTHORIZED,
+            Json(json!({"error": "Invalid or expired token"})),
+        )
+            .into_response());
+    }
+    state.forget_token(token);
+
+    tracing::info!("Revoked authentication token");
+
+    Ok(Json(json!({ "success": true })))
+}
+
 /// Submit task API endpoint (Requirement 17.8)
 async fn submit_task_handler(
     State(state): State<ServerState>,
@@ -488,21 +1155,16 @@ async fn submit_task_handler(
         })?;
 
     // Validate token
-    {
+    let source = {
         let tokens = state.auth_tokens.lock().expect("auth_tokens lock poisoned");
-        if !APIServer::validate_token(&tokens, token) {
-            return Err((
+        APIServer::token_rate_limit_source(&tokens, token).ok_or_else(|| {
+            (
                 StatusCode::UNAUTHORIZED,
                 Json(json!({"error": "Invalid or expired token"})),
             )
-                .into_response());
-        }
-    }
-
-    // TODO: Apply rate limiting (Requirement 17.8)
-    // This would require access to the RateLimiter through CoreContext
-    // For now, we log that rate limiting should be applied
-    tracing::debug!("Rate limiting check would be applied here");
+                .into_response()
+        })?
+    };
 
     let task_input = payload
         .get("task")
@@ -515,28 +1177,128 @@ async fn submit_task_handler(
                 .into_response()
         })?;
 
-    match state.ctx.agent.submit_task(task_input.to_string()) {
+    // Apply rate limiting (Requirement 17.8)
+    if let Err(e) = state
+        .ctx
+        .rate_limit
+        .check_and_record(&source, TASK_OPERATION)
+    {
+        return Err(rate_limited_response(e));
+    }
+
+    let request_id = request_id(headers.get(REQUEST_ID_HEADER).and_then(|v| v.to_str().ok()));
+    tracing::info!(request_id = %request_id, "Task submitted over REST");
+
+    match state
+        .ctx
+        .agent
+        .submit_task_for_request(task_input.to_string(), &request_id)
+    {
         Ok(task_id) => Ok(Json(json!({
             "success": true,
-            "task_id": task_id
+            "task_id": task_id,
+            "request_id": request_id
         }))),
-        Err(e) => Err((
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(json!({"error": e.to_string()})),
-        )
-            .into_response()),
+        Err(e) => Err(engine_error_response(&e)),
     }
 }
 
-/// Get task history API endpoint (Requirement 17.6)
-async fn history_handler(
-    State(state): State<ServerState>,
-    headers: HeaderMap,
-) -> Result<Json<serde_json::Value>, Response> {
-    // Check authentication (Requirement 17.6)
-    let token = headers
-        .get("Authorization")
-        .and_then(|v| v.to_str().ok())
+/// Status code for an engine error
+///
+/// Features turned off by configuration are 403 Forbidden: the request is
+/// fine, but this deployment won't serve it. Everything else is a 500.
+fn engine_error_status(error: &EngineError) -> StatusCode {
+    match error {
+        EngineError::FeatureDisabled(_) => StatusCode::FORBIDDEN,
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+/// Response for an engine error, with the status from `engine_error_status`
+fn engine_error_response(error: &EngineError) -> Response {
+    (
+        engine_error_status(error),
+        Json(json!({"error": error.to_string()})),
+    )
+        .into_response()
+}
+
+/// Rate limit source of a token issued by `/api/auth`, given its
+/// `token_hash`
+///
+/// Tokens refreshed from it keep this source. Sources are persisted by the
+/// engine's rate limiter (and listed in `security.rate_limit_bypass`), so
+/// they hold the first 16 hex digits of the token's SHA-256 rather than the
+/// token itself. Unlike `DefaultHasher`, these are the same in every build.
+fn hash_rate_limit_source(hash: &str) -> String {
+    format!("api:{}", &hash[..16])
+}
+
+/// Response for a submission refused by the rate limiter
+///
+/// Exceeded limits answer 429 Too Many Requests with the window and the
+/// number of operations already in it; other failures answer 500 so a
+/// broken limiter never lets requests through.
+fn rate_limited_response(error: EngineError) -> Response {
+    match error {
+        EngineError::RateLimitExceeded {
+            count,
+            limit,
+            window,
+            ..
+        } => (
+            StatusCode::TOO_MANY_REQUESTS,
+            Json(json!({
+                "error": "Rate limit exceeded",
+                "window": window,
+                "count": count,
+                "limit": limit,
+            })),
+        )
+            .into_response(),
+        EngineError::CircuitBreakerTripped { count, .. } => (
+            StatusCode::TOO_MANY_REQUESTS,
+            Json(json!({
+                "error": "Circuit breaker tripped",
+                "window": "60 seconds",
+                "count": count,
+            })),
+        )
+            .into_response(),
+        e => {
+            tracing::error!("Rate limit check failed: {}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Rate limit check failed"})),
+            )
+                .into_response()
+        }
+    }
+}
+
+/// Request id for a task: `supplied` if it is a sensible id, otherwise a
+/// freshly generated one
+fn request_id(supplied: Option<&str>) -> String {
+    match supplied {
+        Some(id) if sdk::is_valid_request_id(id) => id.to_string(),
+        _ => {
+            let mut rng = OsRng;
+            (0..GENERATED_REQUEST_ID_LENGTH)
+                .map(|_| ALPHANUMERIC_CHARSET[rng.gen_range(0..ALPHANUMERIC_CHARSET.len())] as char)
+                .collect()
+        }
+    }
+}
+
+/// Get task history API endpoint (Requirement 17.6)
+async fn history_handler(
+    State(state): State<ServerState>,
+    headers: HeaderMap,
+) -> Result<Json<serde_json::Value>, Response> {
+    // Check authentication (Requirement 17.6)
+    let token = headers
+        .get("Authorization")
+        .and_then(|v| v.to_str().ok())
         .and_then(|v| v.strip_prefix("Bearer "))
         .ok_or_else(|| {
             (
@@ -567,9 +1329,58 @@ async fn history_handler(
             "success": true,
             "tasks": rows
         }))),
-        Err(e) => Err((
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(json!({"error": e.to_string()})),
+        Err(e) => Err(engine_error_response(&e)),
+    }
+}
+
+/// Get task status API endpoint (Requirement 17.6)
+///
+/// `result` is the task's final answer, i.e. its last assistant message, or
+/// null while it has none. Answers 404 for an unknown id.
+async fn task_status_handler(
+    State(state): State<ServerState>,
+    Path(task_id): Path<String>,
+    headers: HeaderMap,
+) -> Result<Json<serde_json::Value>, Response> {
+    // Check authentication (Requirement 17.6)
+    let token = headers
+        .get("Authorization")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .ok_or_else(|| {
+            (
+                StatusCode::UNAUTHORIZED,
+                Json(json!({"error": "Missing authorization header"})),
+            )
+                .into_response()
+        })?;
+
+    // Validate token
+    {
+        let tokens = state.auth_tokens.lock().expect("auth_tokens lock poisoned");
+        if !APIServer::validate_token(&tokens, token) {
+            return Err((
+                StatusCode::UNAUTHORIZED,
+                Json(json!({"error": "Invalid or expired token"})),
+            )
+                .into_response());
+        }
+    }
+
+    let rows = state
+        .ctx
+        .db
+        .query(TASK_STATUS_QUERY, vec![json!(task_id)])
+        .map_err(|e| engine_error_response(&e))?;
+
+    match rows.into_iter().next() {
+        Some(task) => Ok(Json(json!({
+            "success": true,
+            "task": task
+        }))),
+        None => Err((
+            StatusCode::NOT_FOUND,
+            Json(json!({"error": format!("Task not found: {}", task_id)})),
         )
             .into_response()),
     }
@@ -583,6 +1394,49 @@ async fn status_handler(State(_state): State<ServerState>) -> Json<serde_json::V
     }))
 }
 
+/// Component readiness API endpoint
+///
+/// Unauthenticated; answers 200 when healthy and 503 otherwise.
+async fn health_handler(State(state): State<ServerState>) -> Response {
+    let (healthy, report) = health_report(&state.ctx);
+    let status = if healthy {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    (status, Json(report)).into_response()
+}
+
+/// Check each component and build the `/api/health` response
+///
+/// The engine is healthy when the database answers, the daemon is not
+/// shutting down, and at least one configured LLM provider is available.
+fn health_report(ctx: &CoreContext) -> (bool, serde_json::Value) {
+    let database = match ctx.db.query("SELECT 1", vec![]) {
+        Ok(_) => true,
+        Err(e) => {
+            tracing::warn!("Health check: database unreachable: {}", e);
+            false
+        }
+    };
+    let engine = ctx.agent.health();
+
+    let providers_ok = engine.providers.is_empty() || engine.providers.values().any(|ok| *ok);
+    let healthy = database && providers_ok && !engine.shutting_down;
+
+    let report = json!({
+        "healthy": healthy,
+        "components": {
+            "database": database,
+            "llm_providers": engine.providers,
+            "plugins_loaded": engine.plugins_loaded,
+            "tools_loaded": engine.tools_loaded,
+            "shutting_down": engine.shutting_down,
+        }
+    });
+    (healthy, report)
+}
+
 /// Fallback handler for serving index.html (Requirement 17.4, 17.7)
 async fn index_handler() -> Response {
     // Serve a simple HTML page
@@ -662,6 +1516,7 @@ async fn index_handler() -> Response {
                 <li>POST /api/submit_task - Submit a new task (requires Bearer token)</li>
                 <li>GET /api/history - Get task history (requires Bearer token)</li>
                 <li>GET /api/status - Get server status</li>
+                <li>GET /api/health - Get component readiness</li>
             </ul>
         </div>
 
@@ -691,11 +1546,44 @@ pub extern "C" fn create_tool() -> *mut dyn CoreTool {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use sdk::{
+        AgentHandle, AgentHandleImpl, BusHandle, BusHandleImpl, ConfigHandle, ConfigHandleImpl,
+        CryptoHandle, CryptoHandleImpl, DbHandle, DbHandleImpl, EngineHealth, NetworkHandle,
+        NetworkHandleImpl, RateLimitHandle, RateLimitHandleImpl,
+    };
+
+    #[test]
+    fn test_handle_dispatches_get_port() {
+        let mut server = APIServer::new();
+
+        let output = server.handle(ToolInput::new("get_port")).unwrap();
+        assert!(!output.success);
+        assert_eq!(output.error.as_deref(), Some("Server not started"));
+        assert_eq!(output.data["code"], json!(RpcError::INTERNAL_ERROR));
+
+        server.addr = Some("127.0.0.1:47800".parse().unwrap());
+        let output = server.handle(ToolInput::new("get_port")).unwrap();
+        assert!(output.success);
+        assert_eq!(output.data, json!({ "port": 47800 }));
+    }
+
+    #[test]
+    fn test_handle_unknown_method() {
+        let output = APIServer::new()
+            .handle(ToolInput::new("shutdown_everything"))
+            .unwrap();
+        assert!(!output.success);
+        assert_eq!(output.data["code"], json!(RpcError::METHOD_NOT_FOUND));
+        assert_eq!(
+            output.error.as_deref(),
+            Some("Unknown method: shutdown_everything")
+        );
+    }
 
     #[test]
     fn test_generate_token() {
-        let token1 = APIServer::generate_token();
-        let token2 = APIServer::generate_token();
+        let token1 = APIServer::generate_token(&TokenConfig::default());
+        let token2 = APIServer::generate_token(&TokenConfig::default());
 
         // Tokens should be 32 characters long
         assert_eq!(token1.len(), 32);
@@ -704,9 +1592,74 @@ mod tests {
         // Tokens should be different
         assert_ne!(token1, token2);
 
-        // Tokens should only contain alphanumeric characters
-        assert!(token1.chars().all(|c| c.is_alphanumeric()));
-        assert!(token2.chars().all(|c| c.is_alphanumeric()));
+        // Tokens should only contain ASCII alphanumeric characters
+        assert!(token1.chars().all(|c| c.is_ascii_alphanumeric()));
+        assert!(token2.chars().all(|c| c.is_ascii_alphanumeric()));
+    }
+
+    #[test]
+    fn test_configured_token_length_is_honored() {
+        let config = TokenConfig::new(Some(64), Some("alphanumeric"));
+        let token = APIServer::generate_token(&config);
+        assert_eq!(token.len(), 64);
+        assert!(token.chars().all(|c| c.is_ascii_alphanumeric()));
+    }
+
+    #[test]
+    fn test_base64url_token_charset() {
+        let config = TokenConfig::new(Some(48), Some("base64url"));
+        assert_eq!(config.encoding, TokenEncoding::Base64Url);
+
+        // Enough samples that every character class shows up
+        let tokens: String = (0..50)
+            .map(|_| APIServer::generate_token(&config))
+            .collect();
+        assert!(tokens
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_'));
+        assert!(tokens.contains('-') || tokens.contains('_'));
+        assert!(!tokens.contains('+') && !tokens.contains('/') && !tokens.contains('='));
+    }
+
+    #[test]
+    fn test_token_config_enforces_minimum_and_defaults() {
+        let short = TokenConfig::new(Some(8), None);
+        assert_eq!(short.length, MIN_TOKEN_LENGTH);
+
+        let long = TokenConfig::new(Some(i64::MAX), None);
+        assert_eq!(long.length, MAX_TOKEN_LENGTH);
+
+        let unknown = TokenConfig::new(None, Some("hex"));
+        assert_eq!(unknown.encoding, TokenEncoding::Alphanumeric);
+        assert_eq!(unknown.length, DEFAULT_TOKEN_LENGTH);
+    }
+
+    /// Entry of `token` issued at `created_at` by `/api/auth`
+    fn auth_token(token: &str, created_at: u64) -> AuthToken {
+        AuthToken {
+            created_at,
+            rate_limit_source: hash_rate_limit_source(&token_hash(token)),
+        }
+    }
+
+    #[test]
+    fn test_issue_token_regenerates_on_collision() {
+        let mut tokens = HashMap::new();
+        tokens.insert(token_hash("taken"), auth_token("taken", 1));
+
+        let mut candidates = vec!["fresh", "taken", "taken"];
+        let mut generated = 0;
+        let token = APIServer::issue_token(&mut tokens, 42, None, || {
+            generated += 1;
+            candidates.pop().unwrap().to_string()
+        });
+
+        assert_eq!(token, "fresh");
+        assert_eq!(generated, 3);
+        assert_eq!(tokens.len(), 2);
+        // The existing token was not overwritten
+        assert_eq!(tokens[&token_hash("taken")].created_at, 1);
+        assert_eq!(tokens[&token_hash("fresh")].created_at, 42);
     }
 
     #[test]
@@ -720,13 +1673,7 @@ mod tests {
             .as_secs();
 
         // Valid token (just created)
-        tokens.insert(
-            token.to_string(),
-            AuthToken {
-                token: token.to_string(),
-                created_at: now,
-            },
-        );
+        tokens.insert(token_hash(token), auth_token(token, now));
 
         assert!(APIServer::validate_token(&tokens, token));
 
@@ -736,11 +1683,8 @@ mod tests {
         // Expired token (25 hours old)
         let old_token = "old_token_123456789012345678901";
         tokens.insert(
-            old_token.to_string(),
-            AuthToken {
-                token: old_token.to_string(),
-                created_at: now - 90000, // 25 hours ago
-            },
+            token_hash(old_token),
+            auth_token(old_token, now - 90000), // 25 hours ago
         );
 
         assert!(!APIServer::validate_token(&tokens, old_token));
@@ -758,11 +1702,8 @@ mod tests {
         // Token that's 23 hours old (should be valid)
         let recent_token = "recent_token_1234567890123456789";
         tokens.insert(
-            recent_token.to_string(),
-            AuthToken {
-                token: recent_token.to_string(),
-                created_at: now - 82800, // 23 hours
-            },
+            token_hash(recent_token),
+            auth_token(recent_token, now - 82800), // 23 hours
         );
 
         assert!(APIServer::validate_token(&tokens, recent_token));
@@ -770,13 +1711,843 @@ mod tests {
         // Token that's 25 hours old (should be invalid)
         let old_token = "old_token_123456789012345678901";
         tokens.insert(
-            old_token.to_string(),
-            AuthToken {
-                token: old_token.to_string(),
-                created_at: now - 90000, // 25 hours
-            },
+            token_hash(old_token),
+            auth_token(old_token, now - 90000), // 25 hours
         );
 
         assert!(!APIServer::validate_token(&tokens, old_token));
     }
+
+    #[test]
+    fn test_auth_rate_limiter_blocks_after_max_attempts() {
+        let limiter = AuthRateLimiter::new(3, Duration::from_secs(60));
+        let ip: IpAddr = "127.0.0.1".parse().unwrap();
+
+        for _ in 0..3 {
+            assert!(limiter.check(ip).is_ok());
+        }
+
+        let retry_after = limiter.check(ip).unwrap_err();
+        assert!(retry_after <= Duration::from_secs(60));
+
+        // Other clients are unaffected
+        let other: IpAddr = "10.0.0.2".parse().unwrap();
+        assert!(limiter.check(other).is_ok());
+    }
+
+    #[test]
+    fn test_auth_rate_limiter_window_expires() {
+        let limiter = AuthRateLimiter::new(1, Duration::from_millis(50));
+        let ip: IpAddr = "127.0.0.1".parse().unwrap();
+
+        assert!(limiter.check(ip).is_ok());
+        assert!(limiter.check(ip).is_err());
+
+        std::thread::sleep(Duration::from_millis(60));
+        assert!(limiter.check(ip).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_rapid_auth_requests_get_429() {
+        let limiter = Arc::new(AuthRateLimiter::new(AUTH_MAX_ATTEMPTS, AUTH_RATE_WINDOW));
+        let app = Router::new().route(
+            "/api/auth",
+            post(|| async { Json(json!({"token": "test"})) })
+                .layer(middleware::from_fn_with_state(limiter, auth_rate_limit)),
+        );
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            axum::serve(
+                listener,
+                app.into_make_service_with_connect_info::<SocketAddr>(),
+            )
+            .await
+            .unwrap();
+        });
+
+        let client = reqwest::Client::new();
+        let url = format!("http://{}/api/auth", addr);
+
+        for _ in 0..AUTH_MAX_ATTEMPTS {
+            let response = client.post(&url).json(&json!({})).send().await.unwrap();
+            assert_eq!(response.status(), reqwest::StatusCode::OK);
+        }
+
+        let response = client.post(&url).json(&json!({})).send().await.unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::TOO_MANY_REQUESTS);
+        assert!(response.headers().contains_key("retry-after"));
+    }
+
+    #[tokio::test]
+    async fn test_oversized_ws_message_is_rejected() {
+        use futures_util::{SinkExt, StreamExt};
+        use tokio_tungstenite::tungstenite::Message as ClientMessage;
+
+        const LIMIT: usize = 1024;
+
+        // Echo server behind the same upgrade limits as `websocket_handler`
+        let app = Router::new().route(
+            "/ws",
+            get(|ws: WebSocketUpgrade| async move {
+                limit_message_size(ws, LIMIT).on_upgrade(|mut socket| async move {
+                    while let Some(Ok(msg)) = socket.recv().await {
+                        if socket.send(msg).await.is_err() {
+                            break;
+                        }
+                    }
+                })
+            }),
+        );
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+
+        let url = format!("ws://{}/ws", addr);
+        let (mut client, _) = tokio_tungstenite::connect_async(&url).await.unwrap();
+
+        // Messages within the limit go through
+        client
+            .send(ClientMessage::Text("small".to_string()))
+            .await
+            .unwrap();
+        let echoed = client.next().await.unwrap().unwrap();
+        assert_eq!(echoed, ClientMessage::Text("small".to_string()));
+
+        // A large submit_task payload is refused and the connection ends
+        let oversized = json!({"type": "submit_task", "task": "x".repeat(10 * LIMIT)});
+        let _ = client
+            .send(ClientMessage::Text(oversized.to_string()))
+            .await;
+
+        let outcome = tokio::time::timeout(Duration::from_secs(5), async {
+            loop {
+                match client.next().await {
+                    Some(Ok(ClientMessage::Text(_))) => return false,
+                    Some(Ok(ClientMessage::Close(_))) | Some(Err(_)) | None => return true,
+                    Some(Ok(_)) => continue,
+                }
+            }
+        })
+        .await
+        .expect("connection was not closed after oversized message");
+        assert!(outcome, "oversized message was echoed back");
+
+        // The server keeps accepting new connections
+        assert!(tokio_tungstenite::connect_async(&url).await.is_ok());
+    }
+
+    /// Tasks a client may submit to the `MockEngine`
+    const MOCK_TASK_LIMIT: i64 = 2;
+
+    /// Engine stand-in with a reachable or unreachable database
+    struct MockEngine {
+        db_reachable: bool,
+        health: EngineHealth,
+        /// Recorded operations, by source
+        operations: Mutex<HashMap<String, i64>>,
+        /// The `api_tokens` table: creation time and rate limit source by
+        /// token hash
+        api_tokens: Mutex<HashMap<String, (u64, Option<String>)>>,
+    }
+
+    impl AgentHandleImpl for MockEngine {
+        fn submit_task(&self, _task_input: String) -> Result<String, EngineError> {
+            Ok("task-1".to_string())
+        }
+
+        fn submit_task_for_request(
+            &self,
+            task_input: String,
+            request_id: &str,
+        ) -> Result<String, EngineError> {
+            if task_input == "take a screenshot" {
+                return Err(EngineError::FeatureDisabled(
+                    "Plugin 'screenshot'".to_string(),
+                ));
+            }
+            Ok(format!("task-for-{}", request_id))
+        }
+
+        fn get_task_status(&self, _task_id: &str) -> Result<String, EngineError> {
+            Ok("completed".to_string())
+        }
+
+        fn health(&self) -> EngineHealth {
+            self.health.clone()
+        }
+    }
+
+    impl DbHandleImpl for MockEngine {
+        fn query(
+            &self,
+            sql: &str,
+            params: Vec<serde_json::Value>,
+        ) -> Result<Vec<serde_json::Value>, EngineError> {
+            if sql == TASK_STATUS_QUERY {
+                // Only task-1 exists
+                return Ok(params
+                    .iter()
+                    .filter(|id| *id == "task-1")
+                    .map(|id| json!({"id": id, "status": "completed", "result": "done"}))
+                    .collect());
+            }
+            if sql == LOAD_TOKENS_QUERY {
+                let cutoff = params[0].as_u64().unwrap();
+                let tokens = self.api_tokens.lock().unwrap();
+                return Ok(tokens
+                    .iter()
+                    .filter(|(_, (created_at, _))| *created_at > cutoff)
+                    .map(|(hash, (created_at, source))| {
+                        json!({
+                            "token_hash": hash,
+                            "created_at": created_at,
+                            "rate_limit_source": source,
+                        })
+                    })
+                    .collect());
+            }
+            if self.db_reachable {
+                Ok(vec![json!({"1": 1})])
+            } else {
+                Err(EngineError::Database("database is locked".to_string()))
+            }
+        }
+
+        fn execute(&self, sql: &str, params: Vec<serde_json::Value>) -> Result<u64, EngineError> {
+            let mut tokens = self.api_tokens.lock().unwrap();
+            let before = tokens.len();
+            match sql {
+                INSERT_TOKEN_QUERY => {
+                    let hash = params[0].as_str().unwrap().to_string();
+                    let source = params[2].as_str().map(String::from);
+                    tokens.insert(hash, (params[1].as_u64().unwrap(), source));
+                    return Ok(1);
+                }
+                "DELETE FROM api_tokens WHERE token_hash = ?" => {
+                    tokens.remove(params[0].as_str().unwrap());
+                }
+                "DELETE FROM api_tokens WHERE created_at <= ?" => {
+                    let cutoff = params[0].as_u64().unwrap();
+                    tokens.retain(|_, (created_at, _)| *created_at > cutoff);
+                }
+                _ => return Err(EngineError::Database(format!("unexpected write: {}", sql))),
+            }
+            Ok((before - tokens.len()) as u64)
+        }
+    }
+
+    impl ConfigHandleImpl for MockEngine {
+        fn get(&self, _key: &str) -> Option<serde_json::Value> {
+            None
+        }
+    }
+
+    impl CryptoHandleImpl for MockEngine {
+        fn sign_data(&self, data: &[u8]) -> Result<Vec<u8>, EngineError> {
+            Ok(data.to_vec())
+        }
+
+        fn verify_signature(&self, _data: &[u8], _signature: &[u8]) -> Result<(), EngineError> {
+            Ok(())
+        }
+
+        fn get_secret(&self, key: &str) -> Result<String, EngineError> {
+            Err(EngineError::KeyringError(key.to_string()))
+        }
+
+        fn scrub_secrets(&self, text: &str) -> String {
+            text.to_string()
+        }
+    }
+
+    impl NetworkHandleImpl for MockEngine {
+        fn http_get(&self, _url: &str) -> Result<Vec<u8>, EngineError> {
+            Ok(Vec::new())
+        }
+
+        fn http_post(&self, _url: &str, _body: Vec<u8>) -> Result<Vec<u8>, EngineError> {
+            Ok(Vec::new())
+        }
+    }
+
+    impl BusHandleImpl for MockEngine {
+        fn subscribe(&self, _event_type: &str) -> Result<(), EngineError> {
+            Ok(())
+        }
+
+        fn publish(
+            &self,
+            _event_type: &str,
+            _payload: serde_json::Value,
+        ) -> Result<(), EngineError> {
+            Ok(())
+        }
+    }
+
+    /// Error of the `MockEngine` for a source with `count` tasks
+    fn mock_limit_exceeded(source: &str, count: i64) -> EngineError {
+        EngineError::RateLimitExceeded {
+            src: source.to_string(),
+            tier: 1,
+            count,
+            limit: MOCK_TASK_LIMIT,
+            window: "1 hour".to_string(),
+        }
+    }
+
+    impl RateLimitHandleImpl for MockEngine {
+        fn check_limit(&self, source: &str, _operation: &str) -> Result<(), EngineError> {
+            let operations = self.operations.lock().unwrap();
+            let count = operations.get(source).copied().unwrap_or(0);
+            if count >= MOCK_TASK_LIMIT {
+                return Err(mock_limit_exceeded(source, count));
+            }
+            Ok(())
+        }
+
+        fn check_and_record(&self, source: &str, _operation: &str) -> Result<(), EngineError> {
+            let mut operations = self.operations.lock().unwrap();
+            let count = operations.entry(source.to_string()).or_default();
+            if *count >= MOCK_TASK_LIMIT {
+                return Err(mock_limit_exceeded(source, *count));
+            }
+            *count += 1;
+            Ok(())
+        }
+
+        fn record_operation(&self, source: &str, _operation: &str) -> Result<(), EngineError> {
+            *self
+                .operations
+                .lock()
+                .unwrap()
+                .entry(source.to_string())
+                .or_default() += 1;
+            Ok(())
+        }
+    }
+
+    fn mock_context(db_reachable: bool) -> CoreContext {
+        let engine = Arc::new(MockEngine {
+            db_reachable,
+            health: EngineHealth {
+                providers: [("ollama".to_string(), true), ("openai".to_string(), false)]
+                    .into_iter()
+                    .collect(),
+                plugins_loaded: 3,
+                tools_loaded: 2,
+                shutting_down: false,
+            },
+            operations: Mutex::new(HashMap::new()),
+            api_tokens: Mutex::new(HashMap::new()),
+        });
+        CoreContext::new(
+            AgentHandle::new(engine.clone()),
+            DbHandle::new(engine.clone()),
+            ConfigHandle::new(engine.clone()),
+            CryptoHandle::new(engine.clone()),
+            NetworkHandle::new(engine.clone()),
+            BusHandle::new(engine.clone()),
+            RateLimitHandle::new(engine),
+        )
+    }
+
+    #[test]
+    fn test_health_report_shape() {
+        let (healthy, report) = health_report(&mock_context(true));
+
+        assert!(healthy);
+        assert_eq!(
+            report,
+            json!({
+                "healthy": true,
+                "components": {
+                    "database": true,
+                    "llm_providers": {"ollama": true, "openai": false},
+                    "plugins_loaded": 3,
+                    "tools_loaded": 2,
+                    "shutting_down": false,
+                }
+            })
+        );
+    }
+
+    #[test]
+    fn test_unreachable_db_is_unhealthy() {
+        let (healthy, report) = health_report(&mock_context(false));
+
+        assert!(!healthy);
+        assert_eq!(report["healthy"], false);
+        assert_eq!(report["components"]["database"], false);
+    }
+
+    #[tokio::test]
+    async fn test_health_endpoint_needs_no_token() {
+        for (db_reachable, expected) in [
+            (true, reqwest::StatusCode::OK),
+            (false, reqwest::StatusCode::SERVICE_UNAVAILABLE),
+        ] {
+            let ctx = mock_context(db_reachable);
+            let state = ServerState {
+                ctx: ctx.clone(),
+                connections: Arc::new(Mutex::new(Vec::new())),
+                auth_tokens: Arc::new(Mutex::new(HashMap::new())),
+                event_tx: broadcast::channel(16).0,
+                max_ws_message_size: DEFAULT_MAX_WS_MESSAGE_SIZE,
+                token_config: TokenConfig::from_context(&ctx),
+                persist_tokens: false,
+            };
+            let app = Router::new()
+                .route("/api/health", get(health_handler))
+                .with_state(state);
+
+            let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let addr = listener.local_addr().unwrap();
+            tokio::spawn(async move {
+                axum::serve(listener, app).await.unwrap();
+            });
+
+            let response = reqwest::get(format!("http://{}/api/health", addr))
+                .await
+                .unwrap();
+            assert_eq!(response.status(), expected);
+            let body: serde_json::Value = response.json().await.unwrap();
+            assert_eq!(body["healthy"], db_reachable);
+        }
+    }
+
+    #[test]
+    fn test_request_id_accepts_only_sensible_ids() {
+        assert_eq!(request_id(Some("req_42-a")), "req_42-a");
+
+        for supplied in [None, Some(""), Some("bad id\n"), Some(&"x".repeat(65)[..])] {
+            let generated = request_id(supplied);
+            assert_eq!(generated.len(), GENERATED_REQUEST_ID_LENGTH);
+            assert!(generated.chars().all(|c| c.is_ascii_alphanumeric()));
+        }
+    }
+
+    /// Serve the task, event and token endpoints, accepting the given tokens
+    async fn serve_submit_task(tokens: &[&str]) -> SocketAddr {
+        serve(state_with_tokens(tokens)).await
+    }
+
+    /// Server state over a mock engine, accepting the given tokens
+    fn state_with_tokens(tokens: &[&str]) -> ServerState {
+        let ctx = mock_context(true);
+        let created_at = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        let auth_tokens = tokens
+            .iter()
+            .map(|token| (token_hash(token), auth_token(token, created_at)))
+            .collect();
+        ServerState {
+            ctx: ctx.clone(),
+            connections: Arc::new(Mutex::new(Vec::new())),
+            auth_tokens: Arc::new(Mutex::new(auth_tokens)),
+            event_tx: broadcast::channel(16).0,
+            max_ws_message_size: DEFAULT_MAX_WS_MESSAGE_SIZE,
+            token_config: TokenConfig::from_context(&ctx),
+            persist_tokens: false,
+        }
+    }
+
+    /// Serve the task, event and token endpoints with `state`
+    async fn serve(state: ServerState) -> SocketAddr {
+        let app = Router::new()
+            .route("/api/events", get(events_handler))
+            .route("/ws", get(websocket_handler))
+            .route("/api/tasks", post(submit_task_handler))
+            .route("/api/tasks/:id", get(task_status_handler))
+            .route("/api/auth/refresh", post(refresh_handler))
+            .route("/api/auth/logout", post(logout_handler))
+            .with_state(state);
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+        addr
+    }
+
+    #[tokio::test]
+    async fn test_submitted_task_carries_request_id() {
+        let addr = serve_submit_task(&["token"]).await;
+
+        let client = reqwest::Client::new();
+        let body: serde_json::Value = client
+            .post(format!("http://{}/api/tasks", addr))
+            .bearer_auth("token")
+            .header("X-Request-Id", "req-42")
+            .json(&json!({"task": "list files"}))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(body["request_id"], "req-42");
+        assert_eq!(body["task_id"], "task-for-req-42");
+
+        // Without the header the server generates an id
+        let body: serde_json::Value = client
+            .post(format!("http://{}/api/tasks", addr))
+            .bearer_auth("token")
+            .json(&json!({"task": "list files"}))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        let generated = body["request_id"].as_str().unwrap();
+        assert_eq!(body["task_id"], format!("task-for-{}", generated));
+    }
+
+    #[tokio::test]
+    async fn test_task_submissions_are_rate_limited_per_token() {
+        let addr = serve_submit_task(&["token-a", "token-b"]).await;
+        let client = reqwest::Client::new();
+        let submit = |token: &'static str| {
+            client
+                .post(format!("http://{}/api/tasks", addr))
+                .bearer_auth(token)
+                .json(&json!({"task": "list files"}))
+                .send()
+        };
+
+        for _ in 0..MOCK_TASK_LIMIT {
+            assert_eq!(
+                submit("token-a").await.unwrap().status(),
+                reqwest::StatusCode::OK
+            );
+        }
+
+        let response = submit("token-a").await.unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::TOO_MANY_REQUESTS);
+        let body: serde_json::Value = response.json().await.unwrap();
+        assert_eq!(body["window"], "1 hour");
+        assert_eq!(body["count"], MOCK_TASK_LIMIT);
+
+        // Other tokens have their own budget
+        assert_eq!(
+            submit("token-b").await.unwrap().status(),
+            reqwest::StatusCode::OK
+        );
+        assert!(!hash_rate_limit_source(&token_hash("token-a")).contains("token-a"));
+    }
+
+    #[tokio::test]
+    async fn test_websocket_task_submissions_are_rate_limited() {
+        use futures_util::{SinkExt, StreamExt};
+        use tokio_tungstenite::tungstenite::Message as ClientMessage;
+
+        let addr = serve_submit_task(&["token"]).await;
+        let url = format!("ws://{}/ws?token=token", addr);
+        let (mut client, _) = tokio_tungstenite::connect_async(&url).await.unwrap();
+
+        let submit = json!({"type": "submit_task", "task": "list files"}).to_string();
+        let mut replies = Vec::new();
+        for _ in 0..=MOCK_TASK_LIMIT {
+            client
+                .send(ClientMessage::Text(submit.clone()))
+                .await
+                .unwrap();
+            let reply = client.next().await.unwrap().unwrap();
+            let reply: serde_json::Value = serde_json::from_str(reply.to_text().unwrap()).unwrap();
+            replies.push(reply["type"].as_str().unwrap().to_string());
+        }
+
+        assert_eq!(replies, ["task_submitted", "task_submitted", "error"]);
+    }
+
+    #[tokio::test]
+    async fn test_task_status_by_id() {
+        let addr = serve_submit_task(&["token"]).await;
+        let client = reqwest::Client::new();
+
+        let response = client
+            .get(format!("http://{}/api/tasks/task-1", addr))
+            .bearer_auth("token")
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::OK);
+        let body: serde_json::Value = response.json().await.unwrap();
+        assert_eq!(body["task"]["id"], "task-1");
+        assert_eq!(body["task"]["result"], "done");
+
+        let response = client
+            .get(format!("http://{}/api/tasks/task-2", addr))
+            .bearer_auth("token")
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::NOT_FOUND);
+
+        let response = client
+            .get(format!("http://{}/api/tasks/task-1", addr))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn test_disabled_feature_maps_to_forbidden() {
+        assert_eq!(
+            engine_error_status(&EngineError::FeatureDisabled("Plugin 'git'".to_string())),
+            StatusCode::FORBIDDEN
+        );
+        assert_eq!(
+            engine_error_status(&EngineError::Database("locked".to_string())),
+            StatusCode::INTERNAL_SERVER_ERROR
+        );
+
+        let addr = serve_submit_task(&["token"]).await;
+        let response = reqwest::Client::new()
+            .post(format!("http://{}/api/tasks", addr))
+            .bearer_auth("token")
+            .json(&json!({"task": "take a screenshot"}))
+            .send()
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), reqwest::StatusCode::FORBIDDEN);
+        let body: serde_json::Value = response.json().await.unwrap();
+        assert_eq!(
+            body["error"],
+            "Plugin 'screenshot' is disabled by configuration"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_token_refresh_and_logout() {
+        let addr = serve_submit_task(&["old-token"]).await;
+        let client = reqwest::Client::new();
+        let post = |path: &str, token: &str| {
+            client
+                .post(format!("http://{}{}", addr, path))
+                .bearer_auth(token)
+                .send()
+        };
+
+        let response = post("/api/auth/refresh", "old-token").await.unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::OK);
+        let new_token = response.json::<AuthResponse>().await.unwrap().token;
+        assert_ne!(new_token, "old-token");
+
+        // The old token is gone, the new one works until logout
+        let response = post("/api/auth/refresh", "old-token").await.unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::UNAUTHORIZED);
+        let response = post("/api/auth/logout", &new_token).await.unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::OK);
+        let response = post("/api/auth/logout", &new_token).await.unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn test_refresh_does_not_reset_the_task_count() {
+        let addr = serve_submit_task(&["old-token"]).await;
+        let client = reqwest::Client::new();
+        let post = |path: &str, token: &str| {
+            client
+                .post(format!("http://{}{}", addr, path))
+                .bearer_auth(token)
+                .json(&json!({"task": "list files"}))
+                .send()
+        };
+
+        for _ in 0..MOCK_TASK_LIMIT {
+            let response = post("/api/tasks", "old-token").await.unwrap();
+            assert_eq!(response.status(), reqwest::StatusCode::OK);
+        }
+        let response = post("/api/auth/refresh", "old-token").await.unwrap();
+        let new_token = response.json::<AuthResponse>().await.unwrap().token;
+        let refreshed = post("/api/auth/refresh", &new_token).await.unwrap();
+        let newest_token = refreshed.json::<AuthResponse>().await.unwrap().token;
+
+        let response = post("/api/tasks", &newest_token).await.unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::TOO_MANY_REQUESTS);
+        let body: serde_json::Value = response.json().await.unwrap();
+        assert_eq!(body["count"], MOCK_TASK_LIMIT);
+    }
+
+    #[tokio::test]
+    async fn test_events_are_streamed_over_sse() {
+        let state = state_with_tokens(&["token"]);
+        let events = state.event_tx.clone();
+        let addr = serve(state).await;
+        let client = reqwest::Client::new();
+
+        let response = client
+            .get(format!("http://{}/api/events", addr))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::UNAUTHORIZED);
+
+        let mut response = client
+            .get(format!("http://{}/api/events?token=token", addr))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::OK);
+        assert_eq!(response.headers()["content-type"], "text/event-stream");
+
+        events
+            .send(r#"{"type":"task_completed","task_id":"task-1"}"#.to_string())
+            .unwrap();
+        let frame = tokio::time::timeout(Duration::from_secs(5), response.chunk())
+            .await
+            .expect("no event within 5s")
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            String::from_utf8_lossy(&frame),
+            "data: {\"type\":\"task_completed\",\"task_id\":\"task-1\"}\n\n"
+        );
+    }
+
+    /// Send a CORS preflight for `POST /api/submit_task` from `origin` to a
+    /// server that allows `allowed_origins`
+    async fn preflight(allowed_origins: &[&str], origin: &str) -> reqwest::Response {
+        let origins: Vec<String> = allowed_origins.iter().map(|o| o.to_string()).collect();
+        let app = Router::new()
+            .route("/api/submit_task", post(submit_task_handler))
+            .with_state(state_with_tokens(&["token"]))
+            .layer(cors_for_origins(&origins));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+
+        reqwest::Client::new()
+            .request(
+                reqwest::Method::OPTIONS,
+                format!("http://{}/api/submit_task", addr),
+            )
+            .header("Origin", origin)
+            .header("Access-Control-Request-Method", "POST")
+            .header(
+                "Access-Control-Request-Headers",
+                "authorization,content-type",
+            )
+            .send()
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_cors_preflight_for_listed_origin() {
+        let response = preflight(&["https://ui.example.com"], "https://ui.example.com").await;
+
+        assert_eq!(response.status(), reqwest::StatusCode::OK);
+        let headers = response.headers();
+        assert_eq!(
+            headers["access-control-allow-origin"],
+            "https://ui.example.com"
+        );
+        assert_eq!(headers["access-control-allow-credentials"], "true");
+        assert!(headers["access-control-allow-methods"]
+            .to_str()
+            .unwrap()
+            .contains("POST"));
+
+        // Origins not on the list get no CORS headers
+        let response = preflight(&["https://ui.example.com"], "https://evil.example.com").await;
+        assert!(response
+            .headers()
+            .get("access-control-allow-origin")
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn test_cors_defaults_to_same_origin_only() {
+        let response = preflight(&[], "https://ui.example.com").await;
+
+        assert!(response
+            .headers()
+            .get("access-control-allow-origin")
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn test_cors_wildcard_never_allows_credentials() {
+        let response = preflight(&["*", "https://ui.example.com"], "https://any.example.com").await;
+
+        assert_eq!(response.status(), reqwest::StatusCode::OK);
+        assert_eq!(response.headers()["access-control-allow-origin"], "*");
+        assert!(response
+            .headers()
+            .get("access-control-allow-credentials")
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn test_persisted_token_survives_restart() {
+        let state = ServerState {
+            persist_tokens: true,
+            ..state_with_tokens(&[])
+        };
+        let ctx = state.ctx.clone();
+
+        let Ok(Json(issued)) = auth_handler(State(state.clone()), Json(AuthRequest {})).await
+        else {
+            panic!("token was not issued");
+        };
+
+        // Only the hash reaches the database
+        let rows = ctx.db.query(LOAD_TOKENS_QUERY, vec![json!(0)]).unwrap();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0]["token_hash"], token_hash(&issued.token));
+        assert!(!rows[0].to_string().contains(&issued.token));
+
+        // A restarted server only knows what it loads from the database
+        let reloaded = APIServer::load_persisted_tokens(&ctx);
+        assert!(APIServer::validate_token(&reloaded, &issued.token));
+        assert_eq!(
+            APIServer::token_rate_limit_source(&reloaded, &issued.token),
+            Some(hash_rate_limit_source(&token_hash(&issued.token)))
+        );
+        assert!(!APIServer::validate_token(&reloaded, "not-a-token"));
+
+        // Revoked tokens are removed from the database too
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            header::AUTHORIZATION,
+            format!("Bearer {}", issued.token).parse().unwrap(),
+        );
+        assert!(logout_handler(State(state), headers).await.is_ok());
+        assert!(APIServer::load_persisted_tokens(&ctx).is_empty());
+    }
+
+    #[test]
+    fn test_expired_persisted_tokens_are_not_loaded() {
+        let ctx = mock_context(true);
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        ctx.db
+            .execute(
+                INSERT_TOKEN_QUERY,
+                vec![
+                    json!(token_hash("old")),
+                    json!(now - TOKEN_TTL_SECS - 1),
+                    json!(null),
+                ],
+            )
+            .unwrap();
+
+        assert!(APIServer::load_persisted_tokens(&ctx).is_empty());
+    }
 }
@@ -33,6 +33,12 @@ use std::net::{SocketAddr, TcpListener};
 use std::sync::{Arc, Mutex};
 use tokio::sync::broadcast;
 
+/// Header a client may use to supply its own request id
+const REQUEST_ID_HEADER: &str = "x-request-id";
+
+/// Length of a generated request id
+const GENERATED_REQUEST_ID_LENGTH: usize = 16;
+
 /// Authentication token
 #[derive(Debug, Clone, Serialize, Deserialize)]
 struct AuthToken {
@@ -373,11 +379,13 @@ async fn handle_websocket(mut socket: WebSocket, state: ServerState) {
                                     }
                                     "submit_task" => {
                                         if let Some(task_input) = json_msg.get("task").and_then(|v| v.as_str()) {
-                                            match state.ctx.agent.submit_task(task_input.to_string()) {
+                                            let request_id = request_id(json_msg.get("request_id").and_then(|v| v.as_str()));
+                                            match state.ctx.agent.submit_task_for_request(task_input.to_string(), &request_id) {
                                                 Ok(task_id) => {
                                                     let response = json!({
                                                         "type": "task_submitted",
-                                                        "task_id": task_id
+                                                        "task_id": task_id,
+                                                        "request_id": request_id
                                                     });
                                                     if socket.send(Message::Text(response.to_string())).await.is_err() {
                                                         break;
@@ -513,10 +521,17 @@ async fn submit_task_handler(
                 .into_response()
         })?;
 
-    match state.ctx.agent.submit_task(task_input.to_string()) {
+    let request_id = request_id(headers.get(REQUEST_ID_HEADER).and_then(|v| v.to_str().ok()));
+
+    match state
+        .ctx
+        .agent
+        .submit_task_for_request(task_input.to_string(), &request_id)
+    {
         Ok(task_id) => Ok(Json(json!({
             "success": true,
-            "task_id": task_id
+            "task_id": task_id,
+            "request_id": request_id
         }))),
         Err(e) => Err((
             StatusCode::INTERNAL_SERVER_ERROR,
@@ -526,6 +541,19 @@ async fn submit_task_handler(
     }
 }
 
+/// Request id for a task: `supplied` if it is a sensible id, otherwise a
+/// freshly generated one
+fn request_id(supplied: Option<&str>) -> String {
+    match supplied {
+        Some(id) if sdk::is_valid_request_id(id) => id.to_string(),
+        _ => {
+            let mut token = UIServer::generate_token();
+            token.truncate(GENERATED_REQUEST_ID_LENGTH);
+            token
+        }
+    }
+}
+
 /// Get task history API endpoint (Requirement 17.6)
 async fn history_handler(
     State(state): State<ServerState>,
@@ -707,6 +735,17 @@ mod tests {
         assert!(token2.chars().all(|c| c.is_alphanumeric()));
     }
 
+    #[test]
+    fn test_request_id_accepts_only_sensible_ids() {
+        assert_eq!(request_id(Some("req_42-a")), "req_42-a");
+
+        for supplied in [None, Some(""), Some("bad id\n"), Some(&"x".repeat(65)[..])] {
+            let generated = request_id(supplied);
+            assert_eq!(generated.len(), GENERATED_REQUEST_ID_LENGTH);
+            assert!(generated.chars().all(|c| c.is_ascii_alphanumeric()));
+        }
+    }
+
     #[test]
     fn test_validate_token() {
         let mut tokens = HashMap::new();
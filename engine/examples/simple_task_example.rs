@@ -43,6 +43,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         anthropic: Default::default(),
         gemini: Default::default(),
         nvidia_nim: Default::default(),
+        provider_priority: Vec::new(),
+        circuit_breaker: Default::default(),
     });
 
     // Create Ollama provider
@@ -0,0 +1,16 @@
+-- 003_task_failures.sql
+-- Dead-letter records for failed tasks, so failure reasons can be queried
+
+-- One row per failed task: why it failed and how far it got
+CREATE TABLE IF NOT EXISTS task_failures (
+    task_id TEXT PRIMARY KEY,
+    reason TEXT NOT NULL,
+    message TEXT NOT NULL,
+    steps INTEGER NOT NULL,
+    cost REAL NOT NULL DEFAULT 0,
+    created_at INTEGER NOT NULL,
+    FOREIGN KEY (task_id) REFERENCES tasks(id) ON DELETE CASCADE
+);
+
+-- Index for grouping recurring failures by reason
+CREATE INDEX IF NOT EXISTS idx_task_failures_reason ON task_failures(reason);
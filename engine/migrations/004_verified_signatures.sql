@@ -0,0 +1,14 @@
+-- 004_verified_signatures.sql
+-- Cache of successful signature checks, so unchanged files skip Ed25519 on startup
+
+-- One row per (file hash, signing key, signature) that verified successfully
+CREATE TABLE IF NOT EXISTS verified_signatures (
+    file_hash TEXT NOT NULL,
+    public_key TEXT NOT NULL,
+    signature TEXT NOT NULL,
+    verified_at INTEGER NOT NULL,
+    PRIMARY KEY (file_hash, public_key, signature)
+);
+
+-- Index for loading the entries for the embedded key
+CREATE INDEX IF NOT EXISTS idx_verified_signatures_key ON verified_signatures(public_key);
@@ -0,0 +1,5 @@
+-- 005_task_usage.sql
+-- Per-task token and cost accounting, summed over every LLM call of the task
+
+ALTER TABLE tasks ADD COLUMN total_tokens INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE tasks ADD COLUMN cost REAL NOT NULL DEFAULT 0;
@@ -0,0 +1,4 @@
+-- 006_task_summary.sql
+-- Optional LLM-written summary of a completed task (conductor.summarize)
+
+ALTER TABLE tasks ADD COLUMN summary TEXT;
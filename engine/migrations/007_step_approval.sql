@@ -0,0 +1,5 @@
+-- 007_step_approval.sql
+-- How each tool call was approved ("auto-approved (<reason>)", "user-confirmed"
+-- or "denied"), so audit reviews can tell automated runs from confirmed ones
+
+ALTER TABLE task_steps ADD COLUMN approval TEXT;
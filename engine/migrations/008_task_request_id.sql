@@ -0,0 +1,5 @@
+-- 008_task_request_id.sql
+-- Correlation id of the request (CLI run, API submission, Telegram message)
+-- that started the task, shared by all of the task's log lines
+
+ALTER TABLE tasks ADD COLUMN request_id TEXT;
@@ -0,0 +1,12 @@
+-- 009_api_tokens.sql
+-- API server auth tokens that survive restarts (api_server.auth.persist_tokens)
+
+-- Only a SHA-256 hash of each token is stored, so a dump of the database
+-- doesn't contain usable tokens
+CREATE TABLE IF NOT EXISTS api_tokens (
+    token_hash TEXT PRIMARY KEY,
+    created_at INTEGER NOT NULL
+);
+
+-- Index for pruning expired tokens
+CREATE INDEX IF NOT EXISTS idx_api_tokens_created_at ON api_tokens(created_at);
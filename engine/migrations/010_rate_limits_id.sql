@@ -0,0 +1,20 @@
+-- 010_rate_limits_id.sql
+-- Key rate_limits rows by a surrogate id. The old (source, tier, timestamp)
+-- primary key rejected a second operation from the same source within one
+-- millisecond, so bursts failed to record instead of counting against the limit.
+
+CREATE TABLE rate_limits_new (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    source TEXT NOT NULL,
+    tier INTEGER NOT NULL,
+    timestamp INTEGER NOT NULL
+);
+
+INSERT INTO rate_limits_new (source, tier, timestamp)
+SELECT source, tier, timestamp FROM rate_limits;
+
+DROP TABLE rate_limits;
+ALTER TABLE rate_limits_new RENAME TO rate_limits;
+
+CREATE INDEX IF NOT EXISTS idx_rate_limits_timestamp ON rate_limits(timestamp);
+CREATE INDEX IF NOT EXISTS idx_rate_limits_source_tier ON rate_limits(source, tier, timestamp DESC);
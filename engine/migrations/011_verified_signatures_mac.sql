@@ -0,0 +1,6 @@
+-- 011_verified_signatures_mac.sql
+-- Authenticate signature cache rows with a keyed hash, so a row written to
+-- the database by anything but the engine is not trusted
+
+-- Rows from before this column have an empty MAC and are never loaded
+ALTER TABLE verified_signatures ADD COLUMN mac TEXT NOT NULL DEFAULT '';
@@ -0,0 +1,7 @@
+-- 012_task_source.sql
+-- Whether the task was requested remotely (Telegram, API), so a resumed task
+-- keeps the privileges of its original request
+
+-- The source of tasks from before this column is unknown; they resume as
+-- remote, the more restricted source
+ALTER TABLE tasks ADD COLUMN remote INTEGER NOT NULL DEFAULT 1;
@@ -0,0 +1,7 @@
+-- 013_api_token_rate_limit_source.sql
+-- Rate limit source of each persisted API token, kept across refreshes so a
+-- refreshed token doesn't start a fresh task budget
+
+-- Tokens from before this column count against a source derived from their
+-- own hash, as they did when they were issued
+ALTER TABLE api_tokens ADD COLUMN rate_limit_source TEXT;
@@ -0,0 +1,7 @@
+-- 014_task_owner.sql
+-- Process that marked each task running, so a task left running by a run
+-- that crashed or was killed can be told apart from one still in progress
+
+-- Tasks from before this column have no owner; if still marked running they
+-- are treated as interrupted
+ALTER TABLE tasks ADD COLUMN owner_pid INTEGER;
@@ -10,31 +10,50 @@
 //! 5. If tool call: execute tool, add result to memory, continue loop
 //! 6. If final answer: return result
 //!
+//! In plan-first mode (`conductor.plan_first`) the first LLM turn only
+//! produces a `ConductorPlan`. The plan can be gated by a `PlanApproval`
+//! callback, and no tool runs until it has been approved. With a
+//! `Tier2BatchApproval` the Tier 2 calls the plan anticipates are then
+//! approved together; only Tier 2 calls outside that set are confirmed one
+//! by one.
+//!
 //! # Limits
 //!
 //! - Max 20 iterations per task
 //! - 30-second timeout per LLM call
 //! - 5MB result size limit
+//! - Optional deadline for the whole task (`with_task_timeout`)
 //!
 //! Requirements: 2.1, 2.2, 2.3, 2.4, 2.5, 2.6, 2.7
 
 use anyhow::{Context, Result};
+use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tokio::time::timeout;
-use tracing::{debug, error, info, warn};
+use tracing::{debug, error, info, info_span, warn, Instrument};
 use uuid::Uuid;
 
-use crate::db::tasks::{StepType, TaskRepository, TaskStatus};
+use crate::conductor::call_depth::{self, DEFAULT_MAX_CALL_DEPTH};
+use crate::conductor::{ConductorPlan, PlannedToolCall, Planner};
+use crate::confirmation::{ConfirmationPolicy, Confirmer, Decision};
+use crate::db::tasks::{
+    FailureReason, StepType, TaskFailure, TaskRepository, TaskStatus, TaskStep,
+};
 use crate::injection_detector::InjectionDetector;
-use crate::llm::router::LLMRouter;
-use crate::llm::{LLMResponse, Message};
+use crate::llm::router::{CallUsage, LLMRouter};
+use crate::llm::{LLMResponse, Message, ToolCall};
 use crate::rate_limiter::RateLimiter;
-use crate::risk_assessor::{Operation, OperationSource, RiskAssessor};
+use crate::risk_assessor::{Operation, OperationSource, RiskAssessor, RiskTier};
+use crate::runtime::{plugin_call, task_cache};
 use crate::tools::ToolRegistry;
 use sdk::errors::EngineError;
 
-use super::{SteeringEngine, WorkingMemory};
+use super::events::{TaskEvent, TaskEventSink};
+use super::tool_cache::ToolResultCache;
+use super::{
+    compose_system_prompt, PromptTemplate, RoutingPreferences, SteeringEngine, WorkingMemory,
+};
 
 /// Maximum number of iterations per task
 const MAX_ITERATIONS: usize = 20;
@@ -45,6 +64,96 @@ const LLM_TIMEOUT_SECS: u64 = 300;
 /// Maximum result size in bytes (5MB)
 const MAX_RESULT_SIZE: usize = 5 * 1024 * 1024;
 
+/// Step order of the plan in plan-first mode, right after the user message
+/// at 0. The steps of the agent loop come after it.
+const PLAN_STEP_ORDER: i64 = 1;
+
+/// A task stopped at its deadline, with the steps it completed
+///
+/// The agent loop is canceled where it was waiting (on an LLM call, a tool
+/// or a confirmation), so `transcript` holds every step persisted before the
+/// deadline. The source is `EngineError::TaskTimedOut`.
+#[derive(Debug, thiserror::Error)]
+#[error("Task {task_id} timed out after {} step(s)", .transcript.len())]
+pub struct TaskTimeout {
+    /// ID of the timed-out task
+    pub task_id: String,
+
+    /// Steps persisted before the deadline
+    pub transcript: Vec<TaskStep>,
+
+    #[source]
+    source: EngineError,
+}
+
+/// Decides whether a plan produced in plan-first mode may be executed
+///
+/// Returning `false` aborts the task before any tool runs.
+pub type PlanApproval = Arc<dyn Fn(&ConductorPlan) -> bool + Send + Sync>;
+
+/// Decides whether the Tier 2 calls anticipated by an approved plan may run
+/// without further confirmation
+///
+/// Called once per plan, on a blocking thread. Returning `false` leaves
+/// every Tier 2 call to be confirmed individually.
+pub type Tier2BatchApproval = Arc<dyn Fn(&[PlannedToolCall]) -> bool + Send + Sync>;
+
+/// How a tool call was approved, recorded with the call for the audit log
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Approval {
+    /// Ran without anyone confirming it
+    AutoApproved(AutoApprovalReason),
+    /// A user explicitly confirmed it
+    UserConfirmed,
+    /// A user confirmed it up front, with the other Tier 2 calls of the plan
+    BatchConfirmed,
+    /// Denied by the user or by policy
+    Denied,
+}
+
+impl Approval {
+    /// Whether the call may run
+    pub fn is_approved(self) -> bool {
+        self != Approval::Denied
+    }
+}
+
+impl std::fmt::Display for Approval {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Approval::AutoApproved(reason) => write!(f, "auto-approved ({})", reason),
+            Approval::UserConfirmed => write!(f, "user-confirmed"),
+            Approval::BatchConfirmed => write!(f, "batch-confirmed"),
+            Approval::Denied => write!(f, "denied"),
+        }
+    }
+}
+
+/// Why a tool call ran without being confirmed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AutoApprovalReason {
+    /// Tier 0 calls never need confirmation
+    Tier0,
+    /// No confirmation is configured for the call's tier
+    ConfirmationDisabled,
+    /// The Tier 1 countdown ran out without being aborted
+    CountdownElapsed,
+    /// A Tier 2 call planned by a local provider, with no confirmer or
+    /// explicit Tier 2 confirmation turned off
+    PlannedLocally,
+}
+
+impl std::fmt::Display for AutoApprovalReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            AutoApprovalReason::Tier0 => "tier 0",
+            AutoApprovalReason::ConfirmationDisabled => "confirmation disabled",
+            AutoApprovalReason::CountdownElapsed => "countdown elapsed",
+            AutoApprovalReason::PlannedLocally => "planned by local provider",
+        })
+    }
+}
+
 /// Task input for agent processing
 #[derive(Debug, Clone)]
 pub struct Task {
@@ -53,16 +162,27 @@ pub struct Task {
 
     /// Source of the task (local or remote)
     pub source: OperationSource,
+
+    /// Correlation id of the request that started the task, recorded with
+    /// the task and attached to all of its log lines
+    pub request_id: String,
 }
 
 impl Task {
-    /// Create a new task
+    /// Create a new task with a fresh request id
     pub fn new(input: impl Into<String>, source: OperationSource) -> Self {
         Self {
             input: input.into(),
             source,
+            request_id: Uuid::new_v4().to_string(),
         }
     }
+
+    /// Use the id the entry point assigned to the request
+    pub fn with_request_id(mut self, request_id: impl Into<String>) -> Self {
+        self.request_id = request_id.into();
+        self
+    }
 }
 
 /// Task result after processing
@@ -82,6 +202,18 @@ pub struct TaskResult {
 
     /// Number of iterations executed
     pub iterations: usize,
+
+    /// Estimated tokens used across all LLM calls
+    pub total_tokens: usize,
+
+    /// Estimated LLM cost in USD
+    pub cost: f64,
+
+    /// Why the router chose the provider of the final answer
+    pub routing: String,
+
+    /// Correlation id of the request that started the task
+    pub request_id: String,
 }
 
 impl TaskResult {
@@ -99,6 +231,10 @@ impl TaskResult {
             provider_used,
             duration_ms,
             iterations,
+            total_tokens: 0,
+            cost: 0.0,
+            routing: String::new(),
+            request_id: String::new(),
         }
     }
 }
@@ -131,6 +267,54 @@ pub struct AgentCore {
 
     /// Steering engine for skill-based behavior shaping
     steering: Option<SteeringEngine>,
+
+    /// Template for the base system prompt
+    prompt_template: PromptTemplate,
+
+    /// Workspace directory substituted into the system prompt
+    workspace: Option<PathBuf>,
+
+    /// Produce and approve a plan before executing tools
+    plan_first: bool,
+
+    /// Gate for the plan in plan-first mode (`None` approves automatically)
+    plan_approval: Option<PlanApproval>,
+
+    /// Asked before the tool calls selected by `confirmation_policy`
+    /// (`None` allows Tier 1 calls and Tier 2 calls planned by a local
+    /// provider, and denies Tier 2 calls planned by a cloud provider)
+    confirmer: Option<Arc<dyn Confirmer>>,
+
+    /// Which tool calls go to the confirmer
+    confirmation_policy: ConfirmationPolicy,
+
+    /// Gate for the Tier 2 calls anticipated by the plan, in plan-first mode
+    tier2_batch_approval: Option<Tier2BatchApproval>,
+
+    /// Tier 2 calls of the current task approved as a batch, each of which
+    /// may run once without confirmation
+    preapproved_tier2: Vec<PlannedToolCall>,
+
+    /// Maximum nesting of the task, its tool calls and plugin calls
+    max_call_depth: usize,
+
+    /// Ask for a short summary of each completed task for the history
+    summarize: bool,
+
+    /// Let the `always_verify` pass send the transcript to cloud providers
+    cloud_verification: bool,
+
+    /// Usage accumulated over the LLM calls of the current task
+    usage: CallUsage,
+
+    /// Tier 0 tool results of the current task
+    tool_cache: ToolResultCache,
+
+    /// Deadline for a whole task (`None` lets it run to its iteration limit)
+    task_timeout: Option<Duration>,
+
+    /// Receiver of progress events (`None` drops them)
+    event_sink: Option<TaskEventSink>,
 }
 
 impl AgentCore {
@@ -156,6 +340,132 @@ impl AgentCore {
             injection_detector,
             current_source: OperationSource::Local,
             steering,
+            prompt_template: PromptTemplate::default(),
+            workspace: None,
+            plan_first: false,
+            plan_approval: None,
+            confirmer: None,
+            confirmation_policy: ConfirmationPolicy::default(),
+            tier2_batch_approval: None,
+            preapproved_tier2: Vec::new(),
+            max_call_depth: DEFAULT_MAX_CALL_DEPTH,
+            summarize: false,
+            cloud_verification: false,
+            usage: CallUsage::default(),
+            tool_cache: ToolResultCache::default(),
+            task_timeout: None,
+            event_sink: None,
+        }
+    }
+
+    /// Use a custom template for the base system prompt
+    pub fn with_prompt_template(mut self, template: PromptTemplate) -> Self {
+        self.prompt_template = template;
+        self
+    }
+
+    /// Set the workspace directory shown to the LLM in the system prompt
+    pub fn with_workspace(mut self, workspace: impl Into<PathBuf>) -> Self {
+        self.workspace = Some(workspace.into());
+        self
+    }
+
+    /// Plan before acting: the first LLM turn produces a plan, which must
+    /// pass `approval` (if given) before execution starts
+    pub fn with_plan_first(mut self, approval: Option<PlanApproval>) -> Self {
+        self.plan_first = true;
+        self.plan_approval = approval;
+        self
+    }
+
+    /// Ask `confirmer` before the Tier 1 and Tier 2 tool calls `policy`
+    /// selects
+    ///
+    /// The confirmer may be the local terminal or a remote client, e.g. the
+    /// UI that submitted the task. A Tier 2 call is shown with an
+    /// LLM-generated summary of what it does.
+    pub fn with_confirmer(
+        mut self,
+        confirmer: Arc<dyn Confirmer>,
+        policy: ConfirmationPolicy,
+    ) -> Self {
+        self.confirmer = Some(confirmer);
+        self.confirmation_policy = policy;
+        self
+    }
+
+    /// In plan-first mode, ask `approval` once for all the Tier 2 calls the
+    /// approved plan anticipates
+    ///
+    /// Approved calls then run without their individual Tier 2
+    /// confirmation; any other Tier 2 call is still confirmed on its own.
+    pub fn with_tier2_batch_approval(mut self, approval: Tier2BatchApproval) -> Self {
+        self.tier2_batch_approval = Some(approval);
+        self
+    }
+
+    /// Limit how deeply the task, its tool calls and plugin calls may nest
+    pub fn with_max_call_depth(mut self, depth: usize) -> Self {
+        self.max_call_depth = depth;
+        self
+    }
+
+    /// After each completed task, have the LLM summarize what was done and
+    /// store the summary with the task
+    pub fn with_task_summary(mut self) -> Self {
+        self.summarize = true;
+        self
+    }
+
+    /// Let the second pass of `always_verify` skills use cloud providers
+    ///
+    /// Verification sends the whole transcript, so by default only local
+    /// providers are asked.
+    pub fn with_cloud_verification(mut self) -> Self {
+        self.cloud_verification = true;
+        self
+    }
+
+    /// Never reuse the results of `tools` within a task, e.g. reads whose
+    /// output may change between calls
+    pub fn with_uncached_tools<I, S>(mut self, tools: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.tool_cache = ToolResultCache::new(tools);
+        self
+    }
+
+    /// Cancel a task that runs longer than `deadline`; it fails with
+    /// `TaskTimeout`
+    pub fn with_task_timeout(mut self, deadline: Duration) -> Self {
+        self.task_timeout = Some(deadline);
+        self
+    }
+
+    /// Record the full conversation of each task, see `transcript`
+    pub fn with_transcript(mut self) -> Self {
+        self.memory.record_transcript();
+        self
+    }
+
+    /// The untrimmed conversation of the last task, including the final
+    /// answer; empty unless built `with_transcript`
+    pub fn transcript(&self) -> &[Message] {
+        self.memory.transcript().unwrap_or_default()
+    }
+
+    /// Report task progress to `sink` as it happens
+    pub fn with_event_sink(mut self, sink: TaskEventSink) -> Self {
+        self.event_sink = Some(sink);
+        self
+    }
+
+    /// Send `event` to the event sink, if any
+    fn emit(&self, event: TaskEvent) {
+        if let Some(ref sink) = self.event_sink {
+            sink(&event);
         }
     }
 
@@ -172,25 +482,175 @@ impl AgentCore {
         let task_id = Uuid::new_v4().to_string();
         let _start_time = Instant::now();
 
-        info!("Starting task {}: {}", task_id, task.input);
+        let span = info_span!("task", request_id = %task.request_id, task_id = %task_id);
+        async {
+            info!("Starting task {}: {}", task_id, task.input);
+            self.usage = CallUsage::default();
+
+            // Create task in database
+            self.task_repo
+                .create_task_for_request(
+                    &task_id,
+                    &task.input,
+                    Some(&task.request_id),
+                    task.source.is_remote(),
+                )
+                .await
+                .context("Failed to create task in database")?;
+
+            self.run_task(&task_id, task, Vec::new()).await
+        }
+        .instrument(span)
+        .await
+    }
 
-        // Create task in database
-        self.task_repo
-            .create_task(&task_id, &task.input)
+    /// Resume a task that failed or was interrupted before it completed
+    ///
+    /// The task's persisted steps are replayed into working memory (tool
+    /// calls without a result are dropped and re-run), and the agent loop
+    /// continues from the next iteration instead of starting over. Token and
+    /// cost totals carry on from the earlier run. The task keeps the source
+    /// of its original request, so a remote task is still assessed as remote.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the task does not exist, has already completed or is still
+    /// running in a live process, or for the same reasons as `process_task`.
+    /// A task left running by a process that has exited is resumed.
+    pub async fn resume_task(&mut self, task_id: &str) -> Result<TaskResult> {
+        let record = self
+            .task_repo
+            .get_task(task_id)
             .await
-            .context("Failed to create task in database")?;
+            .context("Failed to load task")?
+            .ok_or_else(|| anyhow::anyhow!("Task {} not found", task_id))?;
+        match record.status {
+            TaskStatus::Completed => {
+                anyhow::bail!("Task {} already completed and cannot be resumed", task_id)
+            }
+            TaskStatus::Running if !record.is_orphaned() => {
+                anyhow::bail!("Task {} is still running and cannot be resumed", task_id)
+            }
+            TaskStatus::Running => {
+                warn!(
+                    "Task {} was left running by a process that has exited, resuming it",
+                    task_id
+                );
+            }
+            TaskStatus::Pending | TaskStatus::Failed => {}
+        }
+        let steps = self
+            .task_repo
+            .get_task_steps(task_id)
+            .await
+            .context("Failed to load task steps")?;
+
+        info!(
+            "Resuming task {} from {} persisted step(s): {}",
+            task_id,
+            steps.len(),
+            record.input
+        );
+        self.usage = CallUsage {
+            tokens: record.total_tokens.max(0) as usize,
+            cost: record.cost,
+        };
+
+        // Keep the original request id so both runs' logs correlate
+        let source = if record.remote {
+            OperationSource::Remote
+        } else {
+            OperationSource::Local
+        };
+        let mut task = Task::new(record.input, source);
+        if let Some(request_id) = record.request_id {
+            task = task.with_request_id(request_id);
+        }
+        let span = info_span!("task", request_id = %task.request_id, task_id = %task_id);
+        self.run_task(task_id, task, steps).instrument(span).await
+    }
+
+    /// Run a persisted task and record its outcome
+    ///
+    /// `history` holds the steps of an earlier run when resuming, and is
+    /// empty for a new task.
+    async fn run_task(
+        &mut self,
+        task_id: &str,
+        task: Task,
+        history: Vec<TaskStep>,
+    ) -> Result<TaskResult> {
+        let task_id = task_id.to_string();
+        let request_id = task.request_id.clone();
 
         // Update status to running
         self.task_repo
             .update_task_status(&task_id, TaskStatus::Running)
             .await
             .context("Failed to update task status")?;
+        self.emit(TaskEvent::TaskStarted {
+            task_id: task_id.clone(),
+            input: task.input.clone(),
+        });
 
-        // Execute the task and handle result
-        let result = self.execute_task_loop(&task_id, task).await;
+        // Execute the task and handle result; the task is the first call level
+        // and gets its own plugin cache, dropped when it ends. Plugin calls made
+        // for it are assessed with its source. Dropping the loop at the
+        // deadline cancels it wherever it is waiting.
+        let limit = self.max_call_depth;
+        let deadline = self.task_timeout;
+        let started = Instant::now();
+        let source = task.source.clone();
+        let task_loop = call_depth::nested(limit, self.execute_task_loop(&task_id, task, history));
+        let task_loop = plugin_call::with_source(source, task_cache::scope(task_loop));
+        let result = match deadline {
+            Some(deadline) => timeout(deadline, task_loop).await.unwrap_or_else(|_| {
+                warn!("Task {} timed out after {:?}", task_id, deadline);
+                Err(EngineError::TaskTimedOut {
+                    secs: deadline.as_secs(),
+                })
+            }),
+            None => task_loop.await,
+        }
+        .unwrap_or_else(|e| Err(e.into()));
+
+        // The summary keeps to what is left of the deadline and to the
+        // providers the task's skills allow; it never fails the task
+        if let (Ok(task_result), true) = (&result, self.summarize) {
+            let llm_timeout = Duration::from_secs(LLM_TIMEOUT_SECS);
+            let limit = deadline.map_or(llm_timeout, |deadline| {
+                deadline.saturating_sub(started.elapsed()).min(llm_timeout)
+            });
+            let routing = self
+                .steering
+                .as_ref()
+                .map(SteeringEngine::get_routing_prefs)
+                .unwrap_or_default();
+            if let Some(summary) = self
+                .summarize_task(&task_result.answer, &routing, limit)
+                .await
+            {
+                if let Err(e) = self.task_repo.set_summary(&task_id, &summary).await {
+                    warn!("Failed to store summary of task {}: {}", task_id, e);
+                }
+            }
+        }
+
+        // Losing the usage figures must not leave the task marked running
+        if let Err(e) = self
+            .task_repo
+            .record_usage(&task_id, self.usage.tokens as i64, self.usage.cost)
+            .await
+        {
+            warn!("Failed to record usage of task {}: {}", task_id, e);
+        }
 
         match result {
-            Ok(task_result) => {
+            Ok(mut task_result) => {
+                task_result.total_tokens = self.usage.tokens;
+                task_result.cost = self.usage.cost;
+                task_result.request_id = request_id;
+
                 // Complete task in database
                 self.task_repo
                     .complete_task(
@@ -202,82 +662,505 @@ impl AgentCore {
                     .context("Failed to complete task in database")?;
 
                 info!(
-                    "Task {} completed in {}ms after {} iterations",
-                    task_id, task_result.duration_ms, task_result.iterations
+                    "Task {} completed in {}ms after {} iterations (~{} tokens, ${:.4})",
+                    task_id,
+                    task_result.duration_ms,
+                    task_result.iterations,
+                    task_result.total_tokens,
+                    task_result.cost
                 );
 
                 Ok(task_result)
             }
             Err(e) => {
-                // Mark task as failed
+                // Mark task as failed with a dead-letter record of the reason
+                let transcript = self
+                    .task_repo
+                    .get_task_steps(&task_id)
+                    .await
+                    .unwrap_or_default();
+                let failure = TaskFailure {
+                    reason: FailureReason::from_error(&e),
+                    message: format!("{:#}", e),
+                    steps: transcript.len() as i64,
+                    cost: self.usage.cost,
+                };
                 self.task_repo
-                    .fail_task(&task_id)
+                    .record_failure(&task_id, &failure)
                     .await
                     .context("Failed to mark task as failed")?;
 
                 error!("Task {} failed: {}", task_id, e);
-                Err(e)
+                match e.downcast_ref::<EngineError>() {
+                    Some(&EngineError::TaskTimedOut { secs }) => Err(TaskTimeout {
+                        task_id,
+                        transcript,
+                        source: EngineError::TaskTimedOut { secs },
+                    }
+                    .into()),
+                    _ => Err(e),
+                }
             }
         }
     }
 
-    /// Assess the risk tier for a specific tool call and enforce confirmation
+    /// Assess the risk tier for a specific tool call
     ///
     /// Maps tool names to risk assessment operations:
-    /// - read_file, list_dir, file_exists, capture_screen → Tier 0
-    /// - write_file → Tier 1
+    /// - read_file, read_files, tail_file, list_dir, file_exists, capture_screen,
+    ///   git_log, git_diff, git_branch (list) → Tier 0
+    /// - write_file, create_dir, git_commit, git_branch (create, checkout) → Tier 1
+    /// - delete_file → Tier 2
     /// - run_command → Tier 2 (execute_command)
-    fn assess_tool_risk(&self, tool_name: &str, args: &serde_json::Value) -> Result<()> {
-        let op_name = match tool_name {
-            "read_file" | "list_dir" | "file_exists" => "read_file",
-            "write_file" => "write_file",
-            "run_command" => "execute_command",
-            "capture_screen" => "read_file", // Tier 0
-            _ => "execute_task", // Unknown tools default to Tier 0
-        };
-
-        // Extract args for dangerous flag detection
-        let arg_strings: Vec<String> = match args {
-            serde_json::Value::Object(map) => {
-                map.values()
-                    .filter_map(|v| v.as_str().map(|s| s.to_string()))
-                    .collect()
-            }
-            _ => vec![],
-        };
-
-        let operation = Operation::new(op_name, arg_strings, self.current_source.clone());
+    fn assess_tool_risk(&self, tool_name: &str, args: &serde_json::Value) -> Result<RiskTier> {
+        let operation = Operation::for_tool_call(tool_name, args, self.current_source.clone());
         let tier = self.risk_assessor.assess(&operation)
             .context("Failed to assess tool risk")?;
 
         debug!("Tool '{}' assessed as {:?}", tool_name, tier);
 
-        // For now, log the tier. In a full implementation with interactive
-        // confirmation, Tier 1 would show a countdown and Tier 2 would
-        // require explicit "Y" input. Since we're in a non-interactive
-        // agent loop, we log and allow — the risk tier is recorded for audit.
+        // Tier 1 and Tier 2 calls go through `confirm_tool_call`
         match tier {
-            crate::risk_assessor::RiskTier::Tier0 => { /* auto-execute */ }
-            crate::risk_assessor::RiskTier::Tier1 => {
+            RiskTier::Tier0 => { /* auto-execute */ }
+            RiskTier::Tier1 => {
                 info!("Tier 1 operation: {} (write/reversible)", tool_name);
             }
-            crate::risk_assessor::RiskTier::Tier2 => {
+            RiskTier::Tier2 => {
                 warn!("Tier 2 operation: {} (destructive/irreversible)", tool_name);
             }
         }
 
+        Ok(tier)
+    }
+
+    /// Run the confirmation for a tool call's tier, if one applies
+    ///
+    /// `planned_by` is the provider that asked for the call. A Tier 2 call
+    /// from a cloud provider is never run without being confirmed.
+    ///
+    /// Returns how the call was approved, or `Approval::Denied`.
+    async fn confirm_tool_call(
+        &mut self,
+        tier: RiskTier,
+        tool_name: &str,
+        arguments: &str,
+        planned_by: &str,
+        routing: &RoutingPreferences,
+    ) -> Approval {
+        let confirmer = match (tier, &self.confirmer) {
+            (RiskTier::Tier0, _) => return Approval::AutoApproved(AutoApprovalReason::Tier0),
+            (RiskTier::Tier1, Some(confirmer)) if self.confirmation_policy.tier1 => {
+                Arc::clone(confirmer)
+            }
+            (RiskTier::Tier1, _) => {
+                return Approval::AutoApproved(AutoApprovalReason::ConfirmationDisabled)
+            }
+            (RiskTier::Tier2, _) => {
+                return self
+                    .confirm_tier2(tool_name, arguments, planned_by, routing)
+                    .await
+            }
+        };
+
+        // The Tier 1 confirmation is a countdown that proceeds unless aborted
+        let operation = format!("{} {}", tool_name, arguments);
+        match confirmer
+            .confirm(&operation, RiskTier::Tier1, "write/reversible operation")
+            .await
+        {
+            Decision::Proceed => Approval::AutoApproved(AutoApprovalReason::CountdownElapsed),
+            Decision::Abort => Approval::Denied,
+        }
+    }
+
+    /// Run the Tier 2 confirmation for a tool call
+    async fn confirm_tier2(
+        &mut self,
+        tool_name: &str,
+        arguments: &str,
+        planned_by: &str,
+        routing: &RoutingPreferences,
+    ) -> Approval {
+        let args: serde_json::Value = serde_json::from_str(arguments).unwrap_or_default();
+        if let Some(i) = self
+            .preapproved_tier2
+            .iter()
+            .position(|planned| planned.matches(tool_name, &args))
+        {
+            self.preapproved_tier2.remove(i);
+            return Approval::BatchConfirmed;
+        }
+
+        let planned_by_cloud = !self.router.is_local(planned_by);
+        let Some(confirmer) = self.confirmer.clone() else {
+            if planned_by_cloud {
+                warn!(
+                    "Denying Tier 2 call {} planned by cloud provider '{}': nobody to confirm it",
+                    tool_name, planned_by
+                );
+                return Approval::Denied;
+            }
+            return Approval::AutoApproved(AutoApprovalReason::PlannedLocally);
+        };
+        // Without explicit Tier 2 confirmation only cloud-planned calls are
+        // put to the user
+        if !self.confirmation_policy.explicit_tier2 && !planned_by_cloud {
+            return Approval::AutoApproved(AutoApprovalReason::PlannedLocally);
+        }
+
+        let summary = self.summarize_tier2(tool_name, arguments, routing).await;
+        let reason = if planned_by_cloud {
+            format!("{} (planned by cloud provider '{}')", summary, planned_by)
+        } else {
+            summary
+        };
+        let operation = format!("{} {}", tool_name, arguments);
+        match confirmer
+            .confirm(&operation, RiskTier::Tier2, &reason)
+            .await
+        {
+            Decision::Proceed => Approval::UserConfirmed,
+            Decision::Abort => Approval::Denied,
+        }
+    }
+
+    /// Ask an LLM to explain a Tier 2 call for its confirmation prompt
+    ///
+    /// Routed like the task itself, restricted to local providers if the
+    /// confirmation policy asks for it. Falls back to the bare tool call if
+    /// no summary can be generated.
+    async fn summarize_tier2(
+        &mut self,
+        tool_name: &str,
+        arguments: &str,
+        routing: &RoutingPreferences,
+    ) -> String {
+        let fallback = format!("{} {}", tool_name, arguments);
+        let messages = [
+            Message::system(
+                "Explain the following operation to the user who must approve it. \
+                 In one or two sentences, say what it does and what it could \
+                 irreversibly change or destroy. Do not call any tools.",
+            ),
+            Message::user(&fallback),
+        ];
+
+        let mut prefs = routing.clone();
+        prefs.local_only |= self.confirmation_policy.summarize_tier2_locally;
+        let result = timeout(
+            Duration::from_secs(LLM_TIMEOUT_SECS),
+            self.router.call_with_preferences(&messages, &prefs),
+        )
+        .await;
+        match result {
+            Ok(Ok((response, provider))) => {
+                let usage = self.router.estimate_usage(&provider, &messages, &response);
+                self.add_usage(usage);
+                debug!("Tier 2 summary for {} generated by {}", tool_name, provider);
+                match response {
+                    LLMResponse::FinalAnswer(answer) if !answer.content.trim().is_empty() => {
+                        answer.content.trim().to_string()
+                    }
+                    _ => fallback,
+                }
+            }
+            Ok(Err(e)) => {
+                warn!("Failed to summarize Tier 2 call {}: {}", tool_name, e);
+                fallback
+            }
+            Err(_) => {
+                warn!("Summary for Tier 2 call {} timed out", tool_name);
+                fallback
+            }
+        }
+    }
+
+    /// Have a second provider check a final answer, for skills that set
+    /// `always_verify`
+    ///
+    /// The provider that produced the answer is avoided, and so are cloud
+    /// providers unless cloud verification is enabled. Returns the checked
+    /// answer and the verifying provider, or `None` (keeping the original
+    /// answer) if no other provider is available or it does not answer.
+    async fn verify_answer(
+        &mut self,
+        answer: &str,
+        answered_by: &str,
+        routing: &RoutingPreferences,
+    ) -> Option<(String, String)> {
+        let mut prefs = routing.clone();
+        prefs.avoid_providers.push(answered_by.to_string());
+        prefs.local_only |= !self.cloud_verification;
+
+        let mut messages = self.memory.messages().to_vec();
+        messages.push(Message::assistant(answer));
+        messages.push(Message::user(
+            "An active skill requires a second opinion on the answer above. \
+             Check it against the task and the tool results, then reply with \
+             the final answer: unchanged if it is correct, corrected otherwise. \
+             Do not call any tools.",
+        ));
+
+        let result = timeout(
+            Duration::from_secs(LLM_TIMEOUT_SECS),
+            self.router.call_with_preferences(&messages, &prefs),
+        )
+        .await;
+        match result {
+            Ok(Ok((response, provider))) => {
+                let usage = self.router.estimate_usage(&provider, &messages, &response);
+                self.add_usage(usage);
+                match response {
+                    LLMResponse::FinalAnswer(verified) => {
+                        info!("Answer verified by {}", provider);
+                        Some((verified.content, provider))
+                    }
+                    LLMResponse::ToolCall(_) => {
+                        warn!("Verifier {} called a tool, keeping the answer", provider);
+                        None
+                    }
+                }
+            }
+            Ok(Err(e)) => {
+                warn!("Answer verification skipped: {}", e);
+                None
+            }
+            Err(_) => {
+                warn!("Answer verification timed out");
+                None
+            }
+        }
+    }
+
+    /// Ask the LLM for a short summary of the task that just completed
+    ///
+    /// The call follows the task's `routing` preferences and is given at
+    /// most `limit`. Returns `None` (the task still succeeds) if the call
+    /// fails, times out or answers with a tool call.
+    async fn summarize_task(
+        &mut self,
+        answer: &str,
+        routing: &RoutingPreferences,
+        limit: Duration,
+    ) -> Option<String> {
+        let mut messages = self.memory.messages().to_vec();
+        messages.push(Message::assistant(answer));
+        messages.push(Message::user(
+            "The task is complete. Summarize it for the task history in a few \
+             short lines: what was done, which files were changed and which \
+             commands were run. Reply with the summary only. Do not call any \
+             tools.",
+        ));
+
+        let result = timeout(limit, self.router.call_with_preferences(&messages, routing)).await;
+        match result {
+            Ok(Ok((response, provider))) => {
+                let usage = self.router.estimate_usage(&provider, &messages, &response);
+                self.add_usage(usage);
+                match response {
+                    LLMResponse::FinalAnswer(summary) => Some(summary.content.trim().to_string()),
+                    LLMResponse::ToolCall(_) => {
+                        warn!("Summarizer {} called a tool, skipping summary", provider);
+                        None
+                    }
+                }
+            }
+            Ok(Err(e)) => {
+                warn!("Task summary skipped: {}", e);
+                None
+            }
+            Err(_) => {
+                warn!("Task summary timed out");
+                None
+            }
+        }
+    }
+
+    /// Add the estimated usage of one LLM call to the running task total
+    fn add_usage(&mut self, usage: CallUsage) {
+        self.usage.tokens += usage.tokens;
+        self.usage.cost += usage.cost;
+    }
+
+    /// Run the planning turn of plan-first mode
+    ///
+    /// Asks the LLM for a plan, records it as the task step at
+    /// `PLAN_STEP_ORDER`, and waits for approval on a blocking thread. On
+    /// approval the plan is added to working memory so the execution turns
+    /// follow it.
+    async fn plan_phase(&mut self, task_id: &str, goal: &str) -> Result<()> {
+        let mut messages = Planner::plan_messages(goal);
+        if self.tier2_batch_approval.is_some() {
+            // The planner can only anticipate calls of tools it knows about
+            messages.insert(
+                1,
+                Message::system(format!(
+                    "Tools available to the plan:\n\n{}",
+                    self.tools.tool_descriptions()
+                )),
+            );
+        }
+        let response = match timeout(
+            Duration::from_secs(LLM_TIMEOUT_SECS),
+            self.router.call(&messages),
+        )
+        .await
+        {
+            Ok(Ok((response, provider))) => {
+                let usage = self.router.estimate_usage(&provider, &messages, &response);
+                self.add_usage(usage);
+                response
+            }
+            Ok(Err(e)) => {
+                error!("Planning call failed: {}", e);
+                return Err(e.into());
+            }
+            Err(_) => {
+                error!("Planning call timed out after {}s", LLM_TIMEOUT_SECS);
+                return Err(EngineError::LLMTimeout.into());
+            }
+        };
+
+        let plan = Planner::plan_from_response(goal, &response);
+        let plan_text = format_plan(&plan);
+        info!("Task {} plan has {} step(s)", task_id, plan.steps.len());
+
+        self.task_repo
+            .add_task_step(
+                task_id,
+                PLAN_STEP_ORDER,
+                StepType::AssistantMessage,
+                &plan_text,
+            )
+            .await
+            .context("Failed to persist plan")?;
+
+        if let Some(approve) = self.plan_approval.clone() {
+            let plan = plan.clone();
+            let approved = tokio::task::spawn_blocking(move || approve(&plan))
+                .await
+                .context("Plan approval failed")?;
+            if !approved {
+                warn!("Plan for task {} was rejected", task_id);
+                return Err(anyhow::anyhow!("Plan rejected; no steps were executed"));
+            }
+        }
+        self.preapprove_tier2(task_id, &plan).await;
+
+        self.memory.add_message(Message::assistant(&plan_text));
+        self.memory.add_message(Message::user(
+            "The plan is approved. Carry it out step by step.",
+        ));
         Ok(())
     }
 
+    /// Ask the batch approval for the Tier 2 calls `plan` anticipates
+    ///
+    /// Approved calls are kept in `preapproved_tier2` for the rest of the
+    /// task. Does nothing without a `Tier2BatchApproval`. A call whose risk
+    /// can't be assessed is left out of the batch, to be confirmed on its own.
+    async fn preapprove_tier2(&mut self, task_id: &str, plan: &ConductorPlan) {
+        let Some(approve) = self.tier2_batch_approval.clone() else {
+            return;
+        };
+        let mut batch = Vec::new();
+        for call in plan.steps.iter().flat_map(|step| &step.tool_calls) {
+            match self.assess_tool_risk(&call.tool, &call.arguments) {
+                Ok(RiskTier::Tier2) => batch.push(call.clone()),
+                Ok(_) => {}
+                Err(e) => warn!(
+                    "Leaving planned call {} out of the Tier 2 batch: {:#}",
+                    call.tool, e
+                ),
+            }
+        }
+        if batch.is_empty() {
+            return;
+        }
+
+        info!(
+            "Task {} plan anticipates {} Tier 2 call(s)",
+            task_id,
+            batch.len()
+        );
+        let approved = {
+            let batch = batch.clone();
+            tokio::task::spawn_blocking(move || approve(&batch)).await
+        };
+        match approved {
+            Ok(true) => self.preapproved_tier2 = batch,
+            Ok(false) => info!("Tier 2 batch for task {} not approved", task_id),
+            Err(e) => error!("Tier 2 batch approval failed: {}", e),
+        }
+    }
+
+    /// Rebuild working memory from the steps of an earlier run
+    ///
+    /// Adds the approved plan and every completed tool call/result pair in
+    /// the same shape the agent loop uses. Returns the number of completed
+    /// iterations and whether the steps include a plan.
+    fn replay_steps(&mut self, steps: &[TaskStep]) -> Result<(usize, bool)> {
+        let mut iteration = 0;
+        let mut has_plan = false;
+        let mut pending_call: Option<ToolCall> = None;
+
+        for step in steps {
+            match step.step_type {
+                StepType::UserMessage => {}
+                StepType::AssistantMessage
+                    if self.plan_first && step.step_order == PLAN_STEP_ORDER =>
+                {
+                    has_plan = true;
+                    self.memory.add_message(Message::assistant(&step.content));
+                    self.memory.add_message(Message::user(
+                        "The plan is approved. Carry it out step by step.",
+                    ));
+                }
+                StepType::AssistantMessage => {}
+                StepType::ToolCall => {
+                    let tool_call: ToolCall = serde_json::from_str(&step.content)
+                        .context("Failed to parse persisted tool call")?;
+                    pending_call = Some(tool_call);
+                }
+                StepType::ToolResult => {
+                    let Some(tool_call) = pending_call.take() else {
+                        warn!("Skipping tool result without a tool call");
+                        continue;
+                    };
+                    self.memory.add_message(tool_call_message(&tool_call));
+                    let safe_result = self.injection_detector.sanitize(&step.content);
+                    self.memory
+                        .add_message(Message::tool_result(&safe_result, &tool_call.id));
+                    iteration += 1;
+                }
+            }
+        }
+
+        Ok((iteration, has_plan))
+    }
+
     /// Execute the main task loop
     ///
     /// Requirements: 2.1, 2.2, 2.3, 2.4, 2.6, 2.7
-    async fn execute_task_loop(&mut self, task_id: &str, task: Task) -> Result<TaskResult> {
+    ///
+    /// With a non-empty `history` the loop picks up after the last completed
+    /// iteration of an earlier run (see `resume_task`).
+    async fn execute_task_loop(
+        &mut self,
+        task_id: &str,
+        task: Task,
+        history: Vec<TaskStep>,
+    ) -> Result<TaskResult> {
         let start_time = Instant::now();
 
         // Store task source for per-tool risk escalation
         self.current_source = task.source.clone();
 
+        // Report an unusable default provider with setup guidance; this only
+        // fails the task when no other provider could take it (the caller
+        // shows the advisory case at startup)
+        self.router.ensure_default_provider().await?;
+
         // Step 1: Assess risk tier (Requirement 2.1)
         let operation = Operation::new("execute_task", vec![], task.source.clone());
         let risk_tier = self
@@ -301,41 +1184,54 @@ impl AgentCore {
 
         // Initialize working memory with system prompt + user message
         self.memory.clear();
-        let mut system_prompt = self.tools.system_prompt();
+        self.tool_cache.clear();
+        self.preapproved_tier2.clear();
+        let tools = self.tools.tool_descriptions();
 
         // Wire steering directives into system prompt
-        if let Some(ref mut steering) = self.steering {
-            // Auto-activate skills based on task content
-            let risk_tier_u8 = match risk_tier {
-                crate::risk_assessor::RiskTier::Tier0 => 0u8,
-                crate::risk_assessor::RiskTier::Tier1 => 1u8,
-                crate::risk_assessor::RiskTier::Tier2 => 2u8,
-            };
-            steering.auto_activate(&task.input, risk_tier_u8);
-
-            let directives = steering.get_directives();
-            if !directives.system_prefix.is_empty() {
-                system_prompt = format!("{}\n\n{}", directives.system_prefix, system_prompt);
-            }
-            if !directives.system_suffix.is_empty() {
-                system_prompt = format!("{}\n\n{}", system_prompt, directives.system_suffix);
-            }
-
-            debug!("Active skills: {:?}", steering.active_skills());
-        }
+        let system_prompt = compose_system_prompt(
+            &self.prompt_template,
+            self.workspace.as_deref(),
+            &tools,
+            self.steering.as_mut(),
+            &task.input,
+            risk_tier as u8,
+        );
 
         self.memory.add_message(Message::system(&system_prompt));
         let user_message = Message::user(&task.input);
-        self.memory.add_message(user_message.clone());
 
-        // Persist initial user message
-        self.task_repo
-            .add_task_step(task_id, 0, StepType::UserMessage, &task.input)
-            .await
-            .context("Failed to persist user message")?;
+        // Skills activated for this task steer provider selection
+        let routing = self
+            .steering
+            .as_ref()
+            .map(SteeringEngine::get_routing_prefs)
+            .unwrap_or_default();
+        self.memory.add_message(user_message.clone());
 
         let mut iteration = 0;
-        let mut last_provider_used = String::from("unknown");
+        // Iteration n persists its steps at 2n - 1 and 2n, after the plan if
+        // there is one
+        let mut step_offset = 0;
+        if history.is_empty() {
+            // Persist initial user message
+            self.task_repo
+                .add_task_step(task_id, 0, StepType::UserMessage, &task.input)
+                .await
+                .context("Failed to persist user message")?;
+
+            if self.plan_first {
+                self.plan_phase(task_id, &task.input).await?;
+                step_offset = PLAN_STEP_ORDER;
+            }
+        } else {
+            let (completed, has_plan) = self.replay_steps(&history)?;
+            iteration = completed;
+            if has_plan {
+                step_offset = PLAN_STEP_ORDER;
+            }
+            info!("Task {} resumes after iteration {}", task_id, iteration);
+        }
 
         // Step 2: Execute up to MAX_ITERATIONS (Requirement 2.2)
         while iteration < MAX_ITERATIONS {
@@ -344,16 +1240,31 @@ impl AgentCore {
                 "Task {} iteration {}/{}",
                 task_id, iteration, MAX_ITERATIONS
             );
+            self.emit(TaskEvent::StepStarted {
+                task_id: task_id.to_string(),
+                iteration,
+            });
 
             // Step 3: Call LLM with timeout (Requirement 2.3)
             let llm_result = timeout(
                 Duration::from_secs(LLM_TIMEOUT_SECS),
-                self.router.call(self.memory.messages()),
+                self.router
+                    .call_with_preferences(self.memory.messages(), &routing),
             )
             .await;
 
-            let (response, provider_name) = match llm_result {
-                Ok(Ok((response, provider))) => (response, provider),
+            let (response, last_provider_used, mut last_routing) = match llm_result {
+                Ok(Ok((response, provider))) => {
+                    let usage =
+                        self.router
+                            .estimate_usage(&provider, self.memory.messages(), &response);
+                    self.add_usage(usage);
+                    let explanation =
+                        self.router
+                            .explain_routing(self.memory.messages(), &provider, &routing);
+                    debug!("Routing: {}", explanation);
+                    (response, provider, explanation)
+                }
                 Ok(Err(e)) => {
                     error!("LLM call failed: {}", e);
                     return Err(e.into());
@@ -364,8 +1275,6 @@ impl AgentCore {
                 }
             };
 
-            last_provider_used = provider_name;
-
             // Step 4: Handle response (Requirement 2.6, 2.7)
             match response {
                 LLMResponse::ToolCall(tool_call) => {
@@ -373,13 +1282,7 @@ impl AgentCore {
 
                     // Add assistant message to memory before tool result
                     // (Ollama requires user→assistant→tool ordering)
-                    let assistant_msg = Message::assistant(
-                        serde_json::json!({
-                            "function": &tool_call.name,
-                            "arguments": serde_json::from_str::<serde_json::Value>(&tool_call.arguments).unwrap_or_default()
-                        }).to_string()
-                    );
-                    self.memory.add_message(assistant_msg);
+                    self.memory.add_message(tool_call_message(&tool_call));
 
                     // Persist tool call
                     let tool_call_content = serde_json::to_string(&tool_call)
@@ -387,7 +1290,7 @@ impl AgentCore {
                     self.task_repo
                         .add_task_step(
                             task_id,
-                            (iteration * 2 - 1) as i64,
+                            step_offset + (iteration * 2 - 1) as i64,
                             StepType::ToolCall,
                             &tool_call_content,
                         )
@@ -397,13 +1300,64 @@ impl AgentCore {
                     // Assess risk tier for this specific tool call
                     let tool_args: serde_json::Value = serde_json::from_str(&tool_call.arguments)
                         .unwrap_or_default();
-                    self.assess_tool_risk(&tool_call.name, &tool_args)?;
-
-                    // Execute tool via registry
-                    let tool_result = self
-                        .tools
-                        .dispatch(&tool_call.name, &tool_call.arguments)
+                    self.emit(TaskEvent::ToolCall {
+                        task_id: task_id.to_string(),
+                        iteration,
+                        id: tool_call.id.clone(),
+                        name: tool_call.name.clone(),
+                        arguments: tool_args.clone(),
+                    });
+                    let tier = self.assess_tool_risk(&tool_call.name, &tool_args)?;
+
+                    // Execute tool via registry, unless the user denied it; an
+                    // identical Tier 0 call made earlier in the task is served
+                    // from the cache, and anything else may invalidate it
+                    let cached = match tier {
+                        RiskTier::Tier0 => self
+                            .tool_cache
+                            .get(&tool_call.name, &tool_args)
+                            .map(str::to_string),
+                        _ => None,
+                    };
+                    let approval = self
+                        .confirm_tool_call(
+                            tier,
+                            &tool_call.name,
+                            &tool_call.arguments,
+                            &last_provider_used,
+                            &routing,
+                        )
                         .await;
+                    self.task_repo
+                        .set_step_approval(
+                            task_id,
+                            step_offset + (iteration * 2 - 1) as i64,
+                            &approval.to_string(),
+                        )
+                        .await
+                        .context("Failed to record tool call approval")?;
+                    let tool_result = if !approval.is_approved() {
+                        warn!("Tool call {} denied by user", tool_call.name);
+                        format!("Operation denied: the user aborted {}", tool_call.name)
+                    } else if let Some(cached) = cached {
+                        debug!("Tool call {} served from cache", tool_call.name);
+                        cached
+                    } else {
+                        let dispatch = self.tools.dispatch_for_task(
+                            task_id,
+                            &self.current_source,
+                            &tool_call.name,
+                            &tool_call.arguments,
+                        );
+                        let result = call_depth::nested(self.max_call_depth, dispatch).await?;
+                        match tier {
+                            RiskTier::Tier0 => {
+                                self.tool_cache.insert(&tool_call.name, &tool_args, &result)
+                            }
+                            _ => self.tool_cache.clear(),
+                        }
+                        result
+                    };
 
                     // Step 4: Enforce 5MB result size limit (Requirement 2.4)
                     if tool_result.len() > MAX_RESULT_SIZE {
@@ -430,18 +1384,35 @@ impl AgentCore {
                     self.task_repo
                         .add_task_step(
                             task_id,
-                            (iteration * 2) as i64,
+                            step_offset + (iteration * 2) as i64,
                             StepType::ToolResult,
                             &tool_result,
                         )
                         .await
                         .context("Failed to persist tool result")?;
+                    self.emit(TaskEvent::ToolResult {
+                        task_id: task_id.to_string(),
+                        iteration,
+                        id: tool_call.id.clone(),
+                        name: tool_call.name.clone(),
+                        result: tool_result,
+                    });
 
                     // Continue loop
                 }
-                LLMResponse::FinalAnswer(answer) => {
+                LLMResponse::FinalAnswer(mut answer) => {
                     debug!("Final answer received");
 
+                    if routing.always_verify {
+                        if let Some((verified, verifier)) = self
+                            .verify_answer(&answer.content, &last_provider_used, &routing)
+                            .await
+                        {
+                            answer.content = verified;
+                            last_routing = format!("{}; verified by {}", last_routing, verifier);
+                        }
+                    }
+
                     // Step 4: Enforce 5MB result size limit (Requirement 2.4)
                     if answer.content.len() > MAX_RESULT_SIZE {
                         warn!(
@@ -460,24 +1431,35 @@ impl AgentCore {
                     self.task_repo
                         .add_task_step(
                             task_id,
-                            (iteration * 2 - 1) as i64,
+                            step_offset + (iteration * 2 - 1) as i64,
                             StepType::AssistantMessage,
                             &answer.content,
                         )
                         .await
                         .context("Failed to persist final answer")?;
+                    self.emit(TaskEvent::FinalAnswer {
+                        task_id: task_id.to_string(),
+                        answer: answer.content.clone(),
+                        provider: last_provider_used.clone(),
+                    });
+
+                    // The answer ends the transcript; the context needs no copy
+                    self.memory
+                        .add_to_transcript(Message::assistant(&answer.content));
 
                     // Calculate duration
                     let duration_ms = start_time.elapsed().as_millis() as i64;
 
                     // Return result (Requirement 2.5 - persistence happens in process_task)
-                    return Ok(TaskResult::success(
+                    let mut result = TaskResult::success(
                         task_id.to_string(),
                         answer.content,
                         last_provider_used.clone(),
                         duration_ms,
                         iteration,
-                    ));
+                    );
+                    result.routing = last_routing;
+                    return Ok(result);
                 }
             }
         }
@@ -491,22 +1473,82 @@ impl AgentCore {
     }
 }
 
+/// Assistant message recording `tool_call` in working memory
+fn tool_call_message(tool_call: &ToolCall) -> Message {
+    Message::assistant(
+        serde_json::json!({
+            "function": &tool_call.name,
+            "arguments": serde_json::from_str::<serde_json::Value>(&tool_call.arguments).unwrap_or_default()
+        })
+        .to_string(),
+    )
+}
+
+/// Render a plan as a numbered list for the user and the LLM
+pub fn format_plan(plan: &ConductorPlan) -> String {
+    let mut out = String::from("Plan:");
+    for (i, step) in plan.steps.iter().enumerate() {
+        out.push_str(&format!(
+            "\n{}. [{:?}] {} (expect: {})",
+            i + 1,
+            step.step_type,
+            step.description,
+            step.expected_outcome
+        ));
+        for call in &step.tool_calls {
+            out.push_str(&format!("\n   - {} {}", call.tool, call.arguments));
+        }
+    }
+    out
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::agent::events::ndjson_sink;
     use crate::config::LLMConfig;
     use crate::db::Database;
     use crate::llm::router::LLMRouter;
+    use crate::llm::test_provider::TestProvider;
+    use crate::llm::{FinalAnswer, LLMProvider, ToolCall};
+    use crate::tools::FilesystemTool;
+    use async_trait::async_trait;
+    use std::io::Write;
+    use std::sync::Mutex;
     use tempfile::TempDir;
 
+    /// Writer whose output can be inspected after it was handed to a sink
+    #[derive(Clone, Default)]
+    struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
     async fn setup_test_agent() -> (TempDir, AgentCore) {
+        setup_test_agent_with(vec![]).await
+    }
+
+    /// Agent routing to `providers`, the first of which is the default
+    async fn setup_test_agent_with(providers: Vec<Box<dyn LLMProvider>>) -> (TempDir, AgentCore) {
+        let default_provider = providers
+            .first()
+            .map_or("ollama", |provider| provider.name())
+            .to_string();
         let temp_dir = TempDir::new().unwrap();
         let db_path = temp_dir.path().join("test.db");
         let db = Database::new(&db_path).await.unwrap();
         let pool = db.pool().clone();
 
         let llm_config = Arc::new(LLMConfig {
-            default_provider: "ollama".to_string(),
+            default_provider,
             sensitivity_threshold: 0.7,
             complexity_threshold: 0.8,
             ollama: Default::default(),
@@ -514,9 +1556,11 @@ mod tests {
             anthropic: Default::default(),
             gemini: Default::default(),
             nvidia_nim: Default::default(),
+            provider_priority: Vec::new(),
+            circuit_breaker: Default::default(),
         });
 
-        let router = Arc::new(LLMRouter::new(vec![], llm_config));
+        let router = Arc::new(LLMRouter::new(providers, llm_config));
         let risk_assessor = RiskAssessor::new();
         let rate_limiter = Arc::new(RateLimiter::new(pool.clone()));
         let task_repo = Arc::new(TaskRepository::new(pool));
@@ -565,7 +1609,1031 @@ mod tests {
         assert_eq!(agent.memory.messages().len(), 0);
     }
 
-    // Note: Full integration tests would require mock LLM providers
-    // and tool implementations, which are beyond the scope of this task.
-    // These tests verify the basic structure and setup.
+    #[tokio::test]
+    async fn test_events_stream_as_ndjson() {
+        let provider = TestProvider::scripted(
+            "ollama",
+            true,
+            vec![
+                LLMResponse::ToolCall(ToolCall::new(
+                    "call-1",
+                    "read_file",
+                    r#"{"path": "README.md"}"#,
+                )),
+                LLMResponse::FinalAnswer(FinalAnswer::new("The README is empty")),
+            ],
+        );
+        let (_temp_dir, agent) = setup_test_agent_with(vec![Box::new(provider)]).await;
+        let buffer = SharedBuffer::default();
+        let mut agent = agent.with_event_sink(ndjson_sink(buffer.clone()));
+
+        let result = agent
+            .process_task(Task::new("Summarize the README", OperationSource::Local))
+            .await
+            .unwrap();
+
+        let output = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+        let events: Vec<TaskEvent> = output
+            .lines()
+            .map(|line| serde_json::from_str(line).expect("each line is one event"))
+            .collect();
+        let types: Vec<String> = output
+            .lines()
+            .map(|line| {
+                let value: serde_json::Value = serde_json::from_str(line).unwrap();
+                value["type"].as_str().unwrap().to_string()
+            })
+            .collect();
+
+        assert_eq!(
+            types,
+            vec![
+                "task_started",
+                "step_started",
+                "tool_call",
+                "tool_result",
+                "step_started",
+                "final_answer"
+            ]
+        );
+        assert_eq!(
+            events[2],
+            TaskEvent::ToolCall {
+                task_id: result.task_id.clone(),
+                iteration: 1,
+                id: "call-1".to_string(),
+                name: "read_file".to_string(),
+                arguments: serde_json::json!({"path": "README.md"}),
+            }
+        );
+        assert_eq!(
+            events[5],
+            TaskEvent::FinalAnswer {
+                task_id: result.task_id,
+                answer: "The README is empty".to_string(),
+                provider: "ollama".to_string(),
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_tier2_summary_is_generated_locally() {
+        let cloud = TestProvider::scripted(
+            "openai",
+            false,
+            vec![
+                LLMResponse::ToolCall(ToolCall::new(
+                    "call-1",
+                    "run_command",
+                    r#"{"command": "rm -rf build"}"#,
+                )),
+                LLMResponse::FinalAnswer(FinalAnswer::new("The build was not cleaned")),
+            ],
+        );
+        let local = TestProvider::scripted(
+            "ollama",
+            true,
+            vec![LLMResponse::FinalAnswer(FinalAnswer::new(
+                "Deletes the build directory and everything in it.",
+            ))],
+        );
+        let (_temp_dir, agent) =
+            setup_test_agent_with(vec![Box::new(cloud), Box::new(local)]).await;
+        let confirmer = RecordingConfirmer::new(|_, _| Decision::Abort);
+        let policy = ConfirmationPolicy {
+            summarize_tier2_locally: true,
+            ..ConfirmationPolicy::default()
+        };
+        let mut agent = agent.with_confirmer(confirmer.clone(), policy);
+
+        let result = agent
+            .process_task(Task::new("Clean the build", OperationSource::Local))
+            .await
+            .unwrap();
+
+        assert_eq!(result.answer, "The build was not cleaned");
+        let asked = confirmer.asked.lock().unwrap();
+        assert_eq!(asked.len(), 1);
+        assert!(asked[0].0.starts_with("run_command "));
+        assert_eq!(
+            asked[0].2,
+            "Deletes the build directory and everything in it. \
+             (planned by cloud provider 'openai')"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_tier2_summary_avoids_providers_of_active_skills() {
+        let skills = TempDir::new().unwrap();
+        // The default provider panics if it is asked anything
+        let ollama = TestProvider::scripted("ollama", true, vec![]);
+        let openai = TestProvider::scripted(
+            "openai",
+            false,
+            vec![
+                tool_call(
+                    "call-1",
+                    "run_command",
+                    serde_json::json!({"command": "rm -rf build"}),
+                ),
+                LLMResponse::FinalAnswer(FinalAnswer::new("Deletes the build directory.")),
+                LLMResponse::FinalAnswer(FinalAnswer::new("Done")),
+            ],
+        );
+        let (_temp_dir, agent) =
+            setup_test_agent_with(vec![Box::new(ollama), Box::new(openai)]).await;
+        let confirmer = RecordingConfirmer::new(|_, _| Decision::Abort);
+        let mut agent = agent.with_confirmer(confirmer.clone(), ConfirmationPolicy::default());
+        agent.steering = Some(audit_skill(&skills, r#"avoid_providers = ["ollama"]"#).await);
+
+        let result = agent
+            .process_task(Task::new(
+                "Audit and clean the build",
+                OperationSource::Local,
+            ))
+            .await
+            .unwrap();
+
+        assert_eq!(result.answer, "Done");
+        let asked = confirmer.asked.lock().unwrap();
+        assert!(asked[0].2.starts_with("Deletes the build directory."));
+    }
+
+    #[tokio::test]
+    async fn test_cloud_planned_tier2_is_confirmed_without_explicit_tier2() {
+        let cloud = TestProvider::scripted(
+            "openai",
+            false,
+            vec![
+                tool_call(
+                    "call-1",
+                    "run_command",
+                    serde_json::json!({"command": "rm -rf build"}),
+                ),
+                LLMResponse::FinalAnswer(FinalAnswer::new("Deletes the build directory.")),
+                LLMResponse::FinalAnswer(FinalAnswer::new("Done")),
+            ],
+        );
+        let local = TestProvider::scripted(
+            "ollama",
+            true,
+            vec![
+                tool_call(
+                    "call-2",
+                    "run_command",
+                    serde_json::json!({"command": "ls"}),
+                ),
+                LLMResponse::FinalAnswer(FinalAnswer::new("Done")),
+            ],
+        );
+        let policy = ConfirmationPolicy {
+            explicit_tier2: false,
+            ..ConfirmationPolicy::default()
+        };
+
+        // A cloud-planned call still goes to the user
+        let (_temp_dir, agent) = setup_test_agent_with(vec![Box::new(cloud)]).await;
+        let confirmer = RecordingConfirmer::new(|_, _| Decision::Proceed);
+        let mut agent = agent.with_confirmer(confirmer.clone(), policy);
+        let result = agent
+            .process_task(Task::new("Clean the build", OperationSource::Local))
+            .await
+            .unwrap();
+        assert_eq!(confirmer.asked.lock().unwrap().len(), 1);
+        assert_eq!(
+            recorded_approvals(&agent, &result.task_id).await,
+            vec!["user-confirmed"]
+        );
+
+        // A locally planned one runs without asking
+        let (_temp_dir, agent) = setup_test_agent_with(vec![Box::new(local)]).await;
+        let confirmer = RecordingConfirmer::new(|_, _| Decision::Abort);
+        let mut agent = agent.with_confirmer(confirmer.clone(), policy);
+        let result = agent
+            .process_task(Task::new("List the files", OperationSource::Local))
+            .await
+            .unwrap();
+        assert!(confirmer.asked.lock().unwrap().is_empty());
+        assert_eq!(
+            recorded_approvals(&agent, &result.task_id).await,
+            vec!["auto-approved (planned by local provider)"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_cloud_planned_tier2_is_denied_without_confirmation() {
+        let cloud = TestProvider::scripted(
+            "openai",
+            false,
+            vec![
+                LLMResponse::ToolCall(ToolCall::new(
+                    "call-1",
+                    "run_command",
+                    r#"{"command": "rm -rf build"}"#,
+                )),
+                LLMResponse::FinalAnswer(FinalAnswer::new("Done")),
+            ],
+        );
+        let (_temp_dir, agent) = setup_test_agent_with(vec![Box::new(cloud)]).await;
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let sink_events = Arc::clone(&events);
+        let mut agent = agent.with_event_sink(Arc::new(move |event: &TaskEvent| {
+            sink_events.lock().unwrap().push(event.clone());
+        }));
+
+        agent
+            .process_task(Task::new("Clean the build", OperationSource::Local))
+            .await
+            .unwrap();
+
+        let events = events.lock().unwrap();
+        let result = events
+            .iter()
+            .find_map(|event| match event {
+                TaskEvent::ToolResult { result, .. } => Some(result.clone()),
+                _ => None,
+            })
+            .expect("tool result event");
+        assert!(result.starts_with("Operation denied"));
+    }
+
+    /// Steering engine with one skill, auto-activated by tasks mentioning "audit"
+    async fn audit_skill(dir: &TempDir, routing: &str) -> SteeringEngine {
+        let skill = format!(
+            "[meta]\nid = \"audit\"\nname = \"Audit\"\n\n\
+             [activation]\nauto_when = [\"task contains: audit\"]\n\n\
+             [routing]\n{}\n",
+            routing
+        );
+        std::fs::write(dir.path().join("audit.toml"), skill).unwrap();
+        SteeringEngine::new(dir.path()).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_active_skill_avoids_provider() {
+        let skills = TempDir::new().unwrap();
+        // The default provider panics if it is asked anything
+        let ollama = TestProvider::scripted("ollama", true, vec![]);
+        let openai = TestProvider::scripted(
+            "openai",
+            false,
+            vec![LLMResponse::FinalAnswer(FinalAnswer::new("No findings"))],
+        );
+        let (_temp_dir, mut agent) =
+            setup_test_agent_with(vec![Box::new(ollama), Box::new(openai)]).await;
+        agent.steering = Some(audit_skill(&skills, r#"avoid_providers = ["ollama"]"#).await);
+
+        let result = agent
+            .process_task(Task::new("Audit the lockfile", OperationSource::Local))
+            .await
+            .unwrap();
+
+        assert_eq!(result.provider_used, "openai");
+        assert_eq!(result.answer, "No findings");
+    }
+
+    #[tokio::test]
+    async fn test_always_verify_runs_second_pass() {
+        let skills = TempDir::new().unwrap();
+        let ollama = TestProvider::scripted(
+            "ollama",
+            true,
+            vec![LLMResponse::FinalAnswer(FinalAnswer::new("2 issues"))],
+        );
+        let openai = TestProvider::scripted(
+            "openai",
+            false,
+            vec![LLMResponse::FinalAnswer(FinalAnswer::new("3 issues"))],
+        );
+        let (_temp_dir, agent) =
+            setup_test_agent_with(vec![Box::new(ollama), Box::new(openai)]).await;
+        let mut agent = agent.with_cloud_verification();
+        agent.steering = Some(audit_skill(&skills, "always_verify = true").await);
+
+        let result = agent
+            .process_task(Task::new("Audit the lockfile", OperationSource::Local))
+            .await
+            .unwrap();
+
+        assert_eq!(result.provider_used, "ollama");
+        assert_eq!(result.answer, "3 issues");
+        assert!(
+            result.routing.ends_with("; verified by openai"),
+            "{}",
+            result.routing
+        );
+    }
+
+    #[tokio::test]
+    async fn test_always_verify_stays_local_by_default() {
+        let skills = TempDir::new().unwrap();
+        let ollama = TestProvider::scripted(
+            "ollama",
+            true,
+            vec![LLMResponse::FinalAnswer(FinalAnswer::new("2 issues"))],
+        );
+        // The cloud provider panics if it is sent the transcript
+        let openai = TestProvider::scripted("openai", false, vec![]);
+        let (_temp_dir, mut agent) =
+            setup_test_agent_with(vec![Box::new(ollama), Box::new(openai)]).await;
+        agent.steering = Some(audit_skill(&skills, "always_verify = true").await);
+
+        let result = agent
+            .process_task(Task::new("Audit the lockfile", OperationSource::Local))
+            .await
+            .unwrap();
+
+        assert_eq!(result.answer, "2 issues");
+        assert!(
+            !result.routing.contains("verified by"),
+            "{}",
+            result.routing
+        );
+    }
+
+    #[tokio::test]
+    async fn test_interrupted_task_resumes_where_it_stopped() {
+        let provider = TestProvider::scripted(
+            "ollama",
+            true,
+            vec![LLMResponse::FinalAnswer(FinalAnswer::new(
+                "Both files read",
+            ))],
+        );
+        let (_temp_dir, mut agent) = setup_test_agent_with(vec![Box::new(provider)]).await;
+
+        // An earlier run completed two iterations before it was interrupted
+        let repo = Arc::clone(&agent.task_repo);
+        repo.create_task("task-1", "Read both files").await.unwrap();
+        repo.add_task_step("task-1", 0, StepType::UserMessage, "Read both files")
+            .await
+            .unwrap();
+        for (iteration, path) in [(1, "a.txt"), (2, "b.txt")] {
+            let call = ToolCall::new(
+                format!("call-{}", iteration),
+                "read_file",
+                serde_json::json!({ "path": path }).to_string(),
+            );
+            repo.add_task_step(
+                "task-1",
+                iteration * 2 - 1,
+                StepType::ToolCall,
+                &serde_json::to_string(&call).unwrap(),
+            )
+            .await
+            .unwrap();
+            repo.add_task_step(
+                "task-1",
+                iteration * 2,
+                StepType::ToolResult,
+                &format!("contents of {}", path),
+            )
+            .await
+            .unwrap();
+        }
+        repo.update_task_status("task-1", TaskStatus::Failed)
+            .await
+            .unwrap();
+
+        let result = agent.resume_task("task-1").await.unwrap();
+
+        // The only scripted response is the final answer, so no step was replayed
+        assert_eq!(result.answer, "Both files read");
+        assert_eq!(result.iterations, 3);
+        let messages = agent.memory.messages();
+        assert_eq!(messages.len(), 6);
+        assert_eq!(messages[5].content, "contents of b.txt");
+
+        let steps = repo.get_task_steps("task-1").await.unwrap();
+        assert_eq!(steps.len(), 6);
+        assert_eq!(steps[5].step_order, 5);
+        assert_eq!(steps[5].step_type, StepType::AssistantMessage);
+        let task = repo.get_task("task-1").await.unwrap().unwrap();
+        assert_eq!(task.status, TaskStatus::Completed);
+
+        // A completed task can't be resumed again
+        assert!(agent.resume_task("task-1").await.is_err());
+        assert!(agent.resume_task("missing").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_resumed_task_keeps_its_remote_source() {
+        let provider = TestProvider::scripted(
+            "ollama",
+            true,
+            vec![LLMResponse::FinalAnswer(FinalAnswer::new("Done"))],
+        );
+        let (_temp_dir, mut agent) = setup_test_agent_with(vec![Box::new(provider)]).await;
+
+        let repo = Arc::clone(&agent.task_repo);
+        repo.create_task_for_request("task-1", "Clean up the logs", None, true)
+            .await
+            .unwrap();
+        repo.update_task_status("task-1", TaskStatus::Running)
+            .await
+            .unwrap();
+
+        // Another run may still be working on a running task
+        let err = agent.resume_task("task-1").await.unwrap_err();
+        assert!(err.to_string().contains("still running"), "{}", err);
+
+        repo.update_task_status("task-1", TaskStatus::Failed)
+            .await
+            .unwrap();
+        agent.resume_task("task-1").await.unwrap();
+        assert_eq!(agent.current_source, OperationSource::Remote);
+    }
+
+    #[tokio::test]
+    async fn test_task_left_running_by_dead_run_is_resumed() {
+        let provider = TestProvider::scripted(
+            "ollama",
+            true,
+            vec![LLMResponse::FinalAnswer(FinalAnswer::new("Done"))],
+        );
+        let (_temp_dir, mut agent) = setup_test_agent_with(vec![Box::new(provider)]).await;
+
+        // A run that was killed while working on the task
+        let mut dead_run = std::process::Command::new("true").spawn().unwrap();
+        let dead_pid = dead_run.id();
+        dead_run.wait().unwrap();
+
+        let repo = Arc::clone(&agent.task_repo);
+        repo.create_task("task-1", "Clean up the logs")
+            .await
+            .unwrap();
+        repo.mark_running("task-1", dead_pid).await.unwrap();
+        let task = repo.get_task("task-1").await.unwrap().unwrap();
+        assert!(task.is_orphaned());
+
+        let result = agent.resume_task("task-1").await.unwrap();
+        assert_eq!(result.answer, "Done");
+        let task = repo.get_task("task-1").await.unwrap().unwrap();
+        assert_eq!(task.status, TaskStatus::Completed);
+        assert_eq!(task.owner_pid, Some(std::process::id() as i64));
+    }
+
+    #[tokio::test]
+    async fn test_completed_task_summary_is_persisted() {
+        let provider = TestProvider::scripted(
+            "ollama",
+            true,
+            vec![
+                LLMResponse::FinalAnswer(FinalAnswer::new("Renamed the helper")),
+                LLMResponse::FinalAnswer(FinalAnswer::new(
+                    "Renamed `parse` to `parse_config` in src/config.rs\n",
+                )),
+            ],
+        );
+        let (_temp_dir, agent) = setup_test_agent_with(vec![Box::new(provider)]).await;
+        let mut agent = agent.with_task_summary();
+
+        let result = agent
+            .process_task(Task::new("Rename parse", OperationSource::Local))
+            .await
+            .unwrap();
+
+        assert_eq!(result.answer, "Renamed the helper");
+        let task = agent
+            .task_repo
+            .get_task(&result.task_id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            task.summary.as_deref(),
+            Some("Renamed `parse` to `parse_config` in src/config.rs")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_task_summary_avoids_providers_of_active_skills() {
+        let skills = TempDir::new().unwrap();
+        // The default provider panics if it is asked anything
+        let ollama = TestProvider::scripted("ollama", true, vec![]);
+        let openai = TestProvider::scripted(
+            "openai",
+            false,
+            vec![
+                LLMResponse::FinalAnswer(FinalAnswer::new("No findings")),
+                LLMResponse::FinalAnswer(FinalAnswer::new("Audited the repo")),
+            ],
+        );
+        let (_temp_dir, agent) =
+            setup_test_agent_with(vec![Box::new(ollama), Box::new(openai)]).await;
+        let mut agent = agent.with_task_summary();
+        agent.steering = Some(audit_skill(&skills, r#"avoid_providers = ["ollama"]"#).await);
+
+        let result = agent
+            .process_task(Task::new("Audit the repo", OperationSource::Local))
+            .await
+            .unwrap();
+
+        let task = agent
+            .task_repo
+            .get_task(&result.task_id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(task.summary.as_deref(), Some("Audited the repo"));
+    }
+
+    #[tokio::test]
+    async fn test_task_summary_keeps_to_the_deadline() {
+        // The summary call never returns
+        let provider = TestProvider::scripted(
+            "ollama",
+            true,
+            vec![LLMResponse::FinalAnswer(FinalAnswer::new("Done"))],
+        )
+        .stalling();
+        let (_temp_dir, agent) = setup_test_agent_with(vec![Box::new(provider)]).await;
+        let mut agent = agent
+            .with_task_summary()
+            .with_task_timeout(Duration::from_millis(200));
+
+        let result = timeout(
+            Duration::from_secs(5),
+            agent.process_task(Task::new("Say done", OperationSource::Local)),
+        )
+        .await
+        .expect("the summary outlived the task deadline")
+        .unwrap();
+
+        assert_eq!(result.answer, "Done");
+        let task = agent
+            .task_repo
+            .get_task(&result.task_id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(task.status, TaskStatus::Completed);
+        assert_eq!(task.summary, None);
+    }
+
+    #[tokio::test]
+    async fn test_repeated_read_is_served_from_cache() {
+        let workspace = TempDir::new().unwrap();
+        let file = workspace.path().join("a.txt");
+        std::fs::write(&file, "original").unwrap();
+
+        let read = |id: &str| {
+            LLMResponse::ToolCall(ToolCall::new(id, "read_file", r#"{"path": "a.txt"}"#))
+        };
+        let write = LLMResponse::ToolCall(ToolCall::new(
+            "call-3",
+            "write_file",
+            r#"{"path": "a.txt", "content": "rewritten"}"#,
+        ));
+        let provider = TestProvider::scripted(
+            "ollama",
+            true,
+            vec![
+                read("call-1"),
+                read("call-2"),
+                write,
+                read("call-4"),
+                LLMResponse::FinalAnswer(FinalAnswer::new("Done")),
+            ],
+        );
+        // Change the file behind the agent's back after the first read, so a
+        // second dispatch would see it
+        let changed = file.clone();
+        let provider = provider.before_each(move |call| {
+            if call == 2 {
+                std::fs::write(&changed, "changed").unwrap();
+            }
+        });
+        let (_temp_dir, mut agent) = setup_test_agent_with(vec![Box::new(provider)]).await;
+        agent.tools = Arc::new(ToolRegistry {
+            fs: Some(FilesystemTool::new(workspace.path().to_path_buf())),
+            ..ToolRegistry::empty()
+        });
+
+        agent
+            .process_task(Task::new("Read a.txt", OperationSource::Local))
+            .await
+            .unwrap();
+
+        let results: Vec<&str> = agent
+            .memory
+            .messages()
+            .iter()
+            .filter(|message| message.tool_call_id.is_some())
+            .map(|message| message.content.as_str())
+            .collect();
+        assert_eq!(results.len(), 4);
+        assert_eq!(results[0], "original");
+        assert_eq!(results[1], "original");
+        // The write invalidated the cache, so the last read hits the file
+        assert_eq!(results[3], "rewritten");
+    }
+
+    /// Approval recorded for each tool call of `task_id`
+    async fn recorded_approvals(agent: &AgentCore, task_id: &str) -> Vec<String> {
+        agent
+            .task_repo
+            .get_task_steps(task_id)
+            .await
+            .unwrap()
+            .into_iter()
+            .filter(|step| step.step_type == StepType::ToolCall)
+            .map(|step| step.approval.unwrap_or_default())
+            .collect()
+    }
+
+    fn tool_call(id: &str, name: &str, arguments: serde_json::Value) -> LLMResponse {
+        LLMResponse::ToolCall(ToolCall::new(id, name, arguments.to_string()))
+    }
+
+    #[test]
+    fn test_approval_labels() {
+        let labels: Vec<String> = [
+            Approval::AutoApproved(AutoApprovalReason::Tier0),
+            Approval::AutoApproved(AutoApprovalReason::ConfirmationDisabled),
+            Approval::AutoApproved(AutoApprovalReason::CountdownElapsed),
+            Approval::AutoApproved(AutoApprovalReason::PlannedLocally),
+            Approval::UserConfirmed,
+            Approval::BatchConfirmed,
+            Approval::Denied,
+        ]
+        .iter()
+        .map(ToString::to_string)
+        .collect();
+
+        assert_eq!(
+            labels,
+            vec![
+                "auto-approved (tier 0)",
+                "auto-approved (confirmation disabled)",
+                "auto-approved (countdown elapsed)",
+                "auto-approved (planned by local provider)",
+                "user-confirmed",
+                "batch-confirmed",
+                "denied",
+            ]
+        );
+        assert!(!Approval::Denied.is_approved());
+        assert!(Approval::UserConfirmed.is_approved());
+    }
+
+    #[tokio::test]
+    async fn test_confirmed_and_auto_approved_calls_are_recorded() {
+        let write = |id: &str| {
+            tool_call(
+                id,
+                "write_file",
+                serde_json::json!({"path": "notes.txt", "content": "hello"}),
+            )
+        };
+        let provider = TestProvider::scripted(
+            "ollama",
+            true,
+            vec![
+                tool_call(
+                    "call-1",
+                    "read_file",
+                    serde_json::json!({"path": "notes.txt"}),
+                ),
+                write("call-2"),
+                write("call-3"),
+                tool_call(
+                    "call-4",
+                    "run_command",
+                    serde_json::json!({"command": "ls"}),
+                ),
+                // Summary shown in the Tier 2 confirmation
+                LLMResponse::FinalAnswer(FinalAnswer::new("Lists the workspace.")),
+                LLMResponse::FinalAnswer(FinalAnswer::new("Done")),
+            ],
+        );
+        let (_temp_dir, agent) = setup_test_agent_with(vec![Box::new(provider)]).await;
+        // The countdown runs out once, then the user aborts it
+        let confirmer = RecordingConfirmer::new(|tier, asked| match (tier, asked) {
+            (RiskTier::Tier1, 0) => Decision::Proceed,
+            (RiskTier::Tier1, _) => Decision::Abort,
+            _ => Decision::Proceed,
+        });
+        let mut agent = agent.with_confirmer(confirmer, ConfirmationPolicy::default());
+
+        let result = agent
+            .process_task(Task::new("Update the notes", OperationSource::Local))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            recorded_approvals(&agent, &result.task_id).await,
+            vec![
+                "auto-approved (tier 0)",
+                "auto-approved (countdown elapsed)",
+                "denied",
+                "user-confirmed",
+            ]
+        );
+    }
+
+    /// Confirmer that records what it was asked and answers with `decide`,
+    /// given the tier and the number of earlier questions
+    struct RecordingConfirmer {
+        decide: fn(RiskTier, usize) -> Decision,
+        asked: Mutex<Vec<(String, RiskTier, String)>>,
+    }
+
+    impl RecordingConfirmer {
+        fn new(decide: fn(RiskTier, usize) -> Decision) -> Arc<Self> {
+            Arc::new(Self {
+                decide,
+                asked: Mutex::new(Vec::new()),
+            })
+        }
+    }
+
+    #[async_trait]
+    impl Confirmer for RecordingConfirmer {
+        async fn confirm(&self, operation: &str, tier: RiskTier, reason: &str) -> Decision {
+            let mut asked = self.asked.lock().unwrap();
+            let decision = (self.decide)(tier, asked.len());
+            asked.push((operation.to_string(), tier, reason.to_string()));
+            decision
+        }
+    }
+
+    #[tokio::test]
+    async fn test_confirmer_gates_tier1_and_tier2_calls() {
+        let provider = TestProvider::scripted(
+            "ollama",
+            true,
+            vec![
+                tool_call(
+                    "call-1",
+                    "write_file",
+                    serde_json::json!({"path": "notes.txt", "content": "hello"}),
+                ),
+                tool_call(
+                    "call-2",
+                    "run_command",
+                    serde_json::json!({"command": "ls"}),
+                ),
+                // Summary passed to the confirmer as the reason
+                LLMResponse::FinalAnswer(FinalAnswer::new("Lists the workspace.")),
+                LLMResponse::FinalAnswer(FinalAnswer::new("Done")),
+            ],
+        );
+        let (_temp_dir, agent) = setup_test_agent_with(vec![Box::new(provider)]).await;
+        // Tier 1 calls run, Tier 2 calls are denied
+        let confirmer = RecordingConfirmer::new(|tier, _| match tier {
+            RiskTier::Tier2 => Decision::Abort,
+            _ => Decision::Proceed,
+        });
+        let mut agent = agent.with_confirmer(confirmer.clone(), ConfirmationPolicy::default());
+
+        let result = agent
+            .process_task(Task::new("Update the notes", OperationSource::Local))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            recorded_approvals(&agent, &result.task_id).await,
+            vec!["auto-approved (countdown elapsed)", "denied"]
+        );
+        let asked = confirmer.asked.lock().unwrap();
+        assert_eq!(asked.len(), 2);
+        assert!(asked[0].0.starts_with("write_file "));
+        assert_eq!(asked[0].1, RiskTier::Tier1);
+        assert!(asked[1].0.starts_with("run_command "));
+        assert_eq!(asked[1].1, RiskTier::Tier2);
+        assert_eq!(asked[1].2, "Lists the workspace.");
+    }
+
+    #[tokio::test]
+    async fn test_unconfirmed_calls_are_recorded_as_auto_approved() {
+        let provider = TestProvider::scripted(
+            "ollama",
+            true,
+            vec![
+                tool_call(
+                    "call-1",
+                    "write_file",
+                    serde_json::json!({"path": "notes.txt", "content": "hello"}),
+                ),
+                tool_call(
+                    "call-2",
+                    "run_command",
+                    serde_json::json!({"command": "ls"}),
+                ),
+                LLMResponse::FinalAnswer(FinalAnswer::new("Done")),
+            ],
+        );
+        let (_temp_dir, mut agent) = setup_test_agent_with(vec![Box::new(provider)]).await;
+
+        let result = agent
+            .process_task(Task::new("Update the notes", OperationSource::Local))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            recorded_approvals(&agent, &result.task_id).await,
+            vec![
+                "auto-approved (confirmation disabled)",
+                "auto-approved (planned by local provider)",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_batch_approved_tier2_calls_skip_confirmation() {
+        let clean = serde_json::json!({"command": "cargo clean"});
+        let plan = serde_json::json!([
+            {"description": "Clean the build", "step_type": "Execute",
+             "tool_calls": [{"tool": "run_command", "arguments": clean}]},
+            {"description": "Check the result", "step_type": "Verify",
+             "tool_calls": [{"tool": "list_dir", "arguments": {"path": "."}}]},
+        ]);
+        let provider = TestProvider::scripted(
+            "ollama",
+            true,
+            vec![
+                LLMResponse::FinalAnswer(FinalAnswer::new(plan.to_string())),
+                tool_call("call-1", "run_command", clean.clone()),
+                tool_call(
+                    "call-2",
+                    "run_command",
+                    serde_json::json!({"command": "rm -rf target"}),
+                ),
+                // Summary shown in the Tier 2 confirmation of the unlisted call
+                LLMResponse::FinalAnswer(FinalAnswer::new("Deletes the target directory.")),
+                LLMResponse::FinalAnswer(FinalAnswer::new("Done")),
+            ],
+        );
+        let (_temp_dir, agent) = setup_test_agent_with(vec![Box::new(provider)]).await;
+        let batches = Arc::new(Mutex::new(Vec::new()));
+        let seen_batches = Arc::clone(&batches);
+        let confirmer = RecordingConfirmer::new(|_, _| Decision::Proceed);
+        let mut agent = agent
+            .with_plan_first(None)
+            .with_tier2_batch_approval(Arc::new(move |batch: &[PlannedToolCall]| {
+                seen_batches.lock().unwrap().push(batch.to_vec());
+                true
+            }))
+            .with_confirmer(confirmer.clone(), ConfirmationPolicy::default());
+
+        let result = agent
+            .process_task(Task::new("Clean the build", OperationSource::Local))
+            .await
+            .unwrap();
+
+        // Only the Tier 2 call of the plan is put up for batch approval
+        let batches = batches.lock().unwrap().clone();
+        assert_eq!(batches.len(), 1);
+        assert_eq!(
+            batches[0],
+            vec![PlannedToolCall {
+                tool: "run_command".to_string(),
+                arguments: clean,
+            }]
+        );
+        let asked: Vec<String> = confirmer
+            .asked
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(operation, _, _)| operation.clone())
+            .collect();
+        assert_eq!(asked, vec![r#"run_command {"command":"rm -rf target"}"#]);
+        assert_eq!(
+            recorded_approvals(&agent, &result.task_id).await,
+            vec!["batch-confirmed", "user-confirmed"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_task_logs_and_rows_carry_request_id() {
+        let provider = TestProvider::scripted(
+            "ollama",
+            true,
+            vec![
+                tool_call(
+                    "call-1",
+                    "read_file",
+                    serde_json::json!({"path": "notes.txt"}),
+                ),
+                LLMResponse::FinalAnswer(FinalAnswer::new("Done")),
+            ],
+        );
+        let (_temp_dir, mut agent) = setup_test_agent_with(vec![Box::new(provider)]).await;
+        let logs = SharedBuffer::default();
+        let writer = logs.clone();
+        let subscriber = tracing_subscriber::fmt()
+            .with_ansi(false)
+            .with_writer(move || writer.clone())
+            .finish();
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        let result = agent
+            .process_task(
+                Task::new("Read the notes", OperationSource::Local).with_request_id("req-42"),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(result.request_id, "req-42");
+        let task = agent
+            .task_repo
+            .get_task(&result.task_id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(task.request_id.as_deref(), Some("req-42"));
+
+        let logs = String::from_utf8(logs.0.lock().unwrap().clone()).unwrap();
+        assert!(logs.contains("Starting task"));
+        for line in logs.lines() {
+            assert!(line.contains("request_id=req-42"), "{}", line);
+            assert!(line.contains(&result.task_id), "{}", line);
+        }
+    }
+
+    // Each task records its estimated tokens and cost, and the totals add up
+    #[tokio::test]
+    async fn test_task_usage_recorded_and_totalled() {
+        let provider = TestProvider::new("openai", false)
+            .answering("done")
+            .with_cost_per_1k(0.01);
+        let (_temp_dir, mut agent) = setup_test_agent_with(vec![Box::new(provider)]).await;
+
+        let first = agent
+            .process_task(Task::new("First task", OperationSource::Local))
+            .await
+            .unwrap();
+        let second = agent
+            .process_task(Task::new("Second task", OperationSource::Local))
+            .await
+            .unwrap();
+
+        assert!(first.total_tokens > 0);
+        assert!((first.cost - first.total_tokens as f64 / 1000.0 * 0.01).abs() < 1e-9);
+
+        let stored = agent
+            .task_repo
+            .get_task(&first.task_id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(stored.total_tokens, first.total_tokens as i64);
+        assert!((stored.cost - first.cost).abs() < 1e-9);
+
+        let summary = agent.task_repo.cost_summary(None).await.unwrap();
+        assert_eq!(summary.tasks, 2);
+        assert_eq!(
+            summary.total_tokens,
+            (first.total_tokens + second.total_tokens) as i64
+        );
+        assert!((summary.cost - (first.cost + second.cost)).abs() < 1e-9);
+    }
+
+    #[tokio::test]
+    async fn test_task_past_its_deadline_is_canceled() {
+        let provider = TestProvider::scripted(
+            "ollama",
+            true,
+            vec![tool_call(
+                "call-1",
+                "read_file",
+                serde_json::json!({"path": "a.txt"}),
+            )],
+        )
+        .stalling();
+        let (_temp_dir, agent) = setup_test_agent_with(vec![Box::new(provider)]).await;
+        let mut agent = agent.with_task_timeout(Duration::from_millis(200));
+
+        let err = agent
+            .process_task(Task::new("Read a.txt", OperationSource::Local))
+            .await
+            .unwrap_err();
+
+        let timeout = err.downcast_ref::<TaskTimeout>().expect("not a timeout");
+        let step_types: Vec<StepType> = timeout
+            .transcript
+            .iter()
+            .map(|step| step.step_type.clone())
+            .collect();
+        assert_eq!(
+            step_types,
+            vec![
+                StepType::UserMessage,
+                StepType::ToolCall,
+                StepType::ToolResult
+            ]
+        );
+        assert!(format!("{:#}", err).contains("Task timed out after 0s"));
+
+        let task = agent
+            .task_repo
+            .get_task(&timeout.task_id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(task.status, TaskStatus::Failed);
+        assert_eq!(task.failure.unwrap().reason, FailureReason::Timeout);
+    }
 }
@@ -0,0 +1,106 @@
+//! Task progress events
+//!
+//! The agent reports its progress through a `TaskEventSink` while a task
+//! runs: when it starts, at the start of each iteration, for every tool call
+//! and tool result, and when the final answer arrives. `rove run --json`
+//! writes each event as one line of NDJSON (see `ndjson_sink`) so wrappers can
+//! show progress instead of waiting for the result.
+//!
+//! Every event serializes to a self-contained JSON object tagged with a
+//! snake_case `type`, e.g. `{"type":"step_started","task_id":"...","iteration":1}`.
+
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+
+/// Receives the events of a running task
+///
+/// Called inline from the agent loop, so it should not block for long.
+pub type TaskEventSink = Arc<dyn Fn(&TaskEvent) + Send + Sync>;
+
+/// Something that happened while a task was processed
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TaskEvent {
+    /// The task was accepted and persisted
+    TaskStarted { task_id: String, input: String },
+
+    /// An iteration of the agent loop is about to call the LLM
+    StepStarted { task_id: String, iteration: usize },
+
+    /// The LLM asked for a tool call
+    ToolCall {
+        task_id: String,
+        iteration: usize,
+        id: String,
+        name: String,
+        arguments: serde_json::Value,
+    },
+
+    /// A tool call returned (or was denied)
+    ToolResult {
+        task_id: String,
+        iteration: usize,
+        id: String,
+        name: String,
+        result: String,
+    },
+
+    /// The LLM produced its final answer
+    FinalAnswer {
+        task_id: String,
+        answer: String,
+        provider: String,
+    },
+
+    /// The task finished successfully
+    TaskCompleted {
+        task_id: String,
+        provider: String,
+        /// Why the router chose `provider`
+        routing: String,
+        duration_ms: i64,
+        iterations: usize,
+    },
+
+    /// The task failed
+    TaskFailed { error: String },
+}
+
+/// A sink that writes each event to `writer` as one line of JSON
+///
+/// The writer is flushed after every line so consumers see events as they
+/// happen. Write errors are logged and otherwise ignored.
+pub fn ndjson_sink<W: Write + Send + 'static>(writer: W) -> TaskEventSink {
+    let writer = Mutex::new(writer);
+    Arc::new(move |event: &TaskEvent| {
+        let Ok(line) = serde_json::to_string(event) else {
+            return;
+        };
+        let mut writer = match writer.lock() {
+            Ok(writer) => writer,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        if let Err(e) = writeln!(writer, "{}", line).and_then(|_| writer.flush()) {
+            tracing::warn!("Failed to write task event: {}", e);
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_events_are_tagged_with_type() {
+        let event = TaskEvent::StepStarted {
+            task_id: "t1".to_string(),
+            iteration: 2,
+        };
+
+        assert_eq!(
+            serde_json::to_value(&event).unwrap(),
+            serde_json::json!({"type": "step_started", "task_id": "t1", "iteration": 2})
+        );
+    }
+}
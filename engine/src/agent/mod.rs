@@ -5,9 +5,21 @@
 //! history, assesses risk, and coordinates with LLM providers to execute tasks.
 
 pub mod core;
+pub mod events;
+pub mod prompt;
 pub mod steering;
+pub mod tool_cache;
+pub mod transcript;
 pub mod working_memory;
 
-pub use core::{AgentCore, Task, TaskResult};
-pub use steering::{MergedDirectives, RoutingPreferences, SkillFile, SteeringEngine};
+pub use core::{
+    format_plan, AgentCore, Approval, AutoApprovalReason, PlanApproval, Task, TaskResult,
+    TaskTimeout, Tier2BatchApproval,
+};
+pub use events::{ndjson_sink, TaskEvent, TaskEventSink};
+pub use prompt::{compose_system_prompt, PromptContext, PromptTemplate};
+pub use crate::llm::router::RoutingPreferences;
+pub use steering::{MergedDirectives, SkillFile, SteeringEngine};
+pub use tool_cache::{ToolResultCache, DEFAULT_UNCACHED_TOOLS};
+pub use transcript::TranscriptDump;
 pub use working_memory::WorkingMemory;
@@ -0,0 +1,260 @@
+//! System Prompt Template
+//!
+//! The base system prompt is rendered from a plain-text template so users can
+//! change the agent's persona and instructions without recompiling. The
+//! template path is configured with `steering.prompt_template`; when it is not
+//! set or cannot be read, the embedded default is used.
+//!
+//! # Placeholders
+//!
+//! - `{{workspace}}`: the active workspace directory
+//! - `{{tools}}`: descriptions of the enabled tools
+//! - `{{skills}}`: comma-separated list of active skills
+//!
+//! Skill directives (system prefix and suffix) are applied around the rendered
+//! prompt by `compose_system_prompt`, not by the template.
+
+use super::steering::SteeringEngine;
+use std::path::Path;
+use tracing::{debug, warn};
+
+/// Embedded default system prompt template
+pub const DEFAULT_PROMPT_TEMPLATE: &str = include_str!("default_prompt.md");
+
+/// Values substituted into a prompt template
+#[derive(Debug, Clone, Copy)]
+pub struct PromptContext<'a> {
+    /// Active workspace directory, if known
+    pub workspace: Option<&'a Path>,
+
+    /// Rendered tool descriptions
+    pub tools: &'a str,
+
+    /// IDs of the active skills
+    pub skills: &'a [String],
+}
+
+/// A system prompt template with `{{...}}` placeholders
+#[derive(Debug, Clone)]
+pub struct PromptTemplate {
+    template: String,
+}
+
+impl PromptTemplate {
+    /// Create a template from a string
+    pub fn new(template: impl Into<String>) -> Self {
+        Self {
+            template: template.into(),
+        }
+    }
+
+    /// Load a template from `path`, falling back to the embedded default
+    ///
+    /// A missing or unreadable file is logged and never fails task execution.
+    pub fn load(path: Option<&Path>) -> Self {
+        let Some(path) = path else {
+            return Self::default();
+        };
+
+        match std::fs::read_to_string(path) {
+            Ok(template) => Self::new(template),
+            Err(e) => {
+                warn!(
+                    "Failed to read prompt template {:?}: {}. Using the default prompt",
+                    path, e
+                );
+                Self::default()
+            }
+        }
+    }
+
+    /// Raw template text
+    pub fn as_str(&self) -> &str {
+        &self.template
+    }
+
+    /// Substitute the placeholders with values from `context`
+    pub fn render(&self, context: &PromptContext<'_>) -> String {
+        let workspace = context
+            .workspace
+            .map(|path| path.display().to_string())
+            .unwrap_or_else(|| "(not set)".to_string());
+        let skills = if context.skills.is_empty() {
+            "none".to_string()
+        } else {
+            context.skills.join(", ")
+        };
+
+        self.template
+            .replace("{{workspace}}", &workspace)
+            .replace("{{tools}}", context.tools)
+            .replace("{{skills}}", &skills)
+            .trim_end()
+            .to_string()
+    }
+}
+
+impl Default for PromptTemplate {
+    fn default() -> Self {
+        Self::new(DEFAULT_PROMPT_TEMPLATE)
+    }
+}
+
+/// Compose the full system prompt for a task
+///
+/// Auto-activates skills on `steering` for `task_input` and `risk_tier`,
+/// renders `template` and wraps the result in the active skills' system
+/// prefix and suffix. The agent and `rove export-prompt` both go through
+/// this, so the exported prompt is the one the agent would send.
+pub fn compose_system_prompt(
+    template: &PromptTemplate,
+    workspace: Option<&Path>,
+    tools: &str,
+    steering: Option<&mut SteeringEngine>,
+    task_input: &str,
+    risk_tier: u8,
+) -> String {
+    let mut active_skills: Vec<String> = Vec::new();
+    let mut system_prefix = String::new();
+    let mut system_suffix = String::new();
+
+    if let Some(steering) = steering {
+        steering.auto_activate(task_input, risk_tier);
+
+        let directives = steering.get_directives();
+        system_prefix = directives.system_prefix;
+        system_suffix = directives.system_suffix;
+        active_skills = steering.active_skills().to_vec();
+
+        debug!("Active skills: {:?}", active_skills);
+    }
+
+    let mut system_prompt = template.render(&PromptContext {
+        workspace,
+        tools,
+        skills: &active_skills,
+    });
+    if !system_prefix.is_empty() {
+        system_prompt = format!("{}\n\n{}", system_prefix, system_prompt);
+    }
+    if !system_suffix.is_empty() {
+        system_prompt = format!("{}\n\n{}", system_prompt, system_suffix);
+    }
+    system_prompt
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_render_substitutes_placeholders() {
+        let template = PromptTemplate::new("ws={{workspace}}\ntools={{tools}}\nskills={{skills}}");
+        let workspace = PathBuf::from("/tmp/project");
+        let skills = vec!["rust".to_string(), "security".to_string()];
+
+        let rendered = template.render(&PromptContext {
+            workspace: Some(&workspace),
+            tools: "## read_file",
+            skills: &skills,
+        });
+
+        assert_eq!(
+            rendered,
+            "ws=/tmp/project\ntools=## read_file\nskills=rust, security"
+        );
+    }
+
+    #[test]
+    fn test_render_without_workspace_or_skills() {
+        let template = PromptTemplate::new("{{workspace}} | {{skills}}");
+
+        let rendered = template.render(&PromptContext {
+            workspace: None,
+            tools: "",
+            skills: &[],
+        });
+
+        assert_eq!(rendered, "(not set) | none");
+    }
+
+    #[test]
+    fn test_default_template_has_all_placeholders() {
+        let template = PromptTemplate::default();
+        assert!(template.as_str().contains("{{workspace}}"));
+        assert!(template.as_str().contains("{{tools}}"));
+        assert!(template.as_str().contains("{{skills}}"));
+    }
+
+    #[test]
+    fn test_load_custom_template() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("prompt.md");
+        std::fs::write(&path, "You are a pirate. Tools: {{tools}}").unwrap();
+
+        let template = PromptTemplate::load(Some(&path));
+        assert_eq!(template.as_str(), "You are a pirate. Tools: {{tools}}");
+    }
+
+    #[test]
+    fn test_missing_template_falls_back_to_default() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("does-not-exist.md");
+
+        let template = PromptTemplate::load(Some(&path));
+        assert_eq!(template.as_str(), DEFAULT_PROMPT_TEMPLATE);
+    }
+
+    #[test]
+    fn test_no_template_path_uses_default() {
+        let template = PromptTemplate::load(None);
+        assert_eq!(template.as_str(), DEFAULT_PROMPT_TEMPLATE);
+    }
+
+    #[tokio::test]
+    async fn test_composed_prompt_includes_active_skill_directives() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        std::fs::write(
+            temp_dir.path().join("rust.toml"),
+            r#"
+[meta]
+id = "rust"
+name = "Rust"
+
+[activation]
+auto_when = ["task contains: cargo|rust"]
+
+[directives]
+system_prefix = "Prefer idiomatic Rust."
+system_suffix = "Run cargo clippy before finishing."
+"#,
+        )
+        .unwrap();
+        let mut steering = SteeringEngine::new(temp_dir.path()).await.unwrap();
+        let template = PromptTemplate::new("tools={{tools}} skills={{skills}}");
+
+        let prompt = compose_system_prompt(
+            &template,
+            None,
+            "## read_file",
+            Some(&mut steering),
+            "fix the cargo build",
+            0,
+        );
+
+        assert_eq!(
+            prompt,
+            "Prefer idiomatic Rust.\n\ntools=## read_file skills=rust\n\nRun cargo clippy before finishing."
+        );
+    }
+
+    #[test]
+    fn test_composed_prompt_without_steering() {
+        let template = PromptTemplate::new("tools={{tools}} skills={{skills}}");
+
+        let prompt = compose_system_prompt(&template, None, "", None, "fix the cargo build", 0);
+
+        assert_eq!(prompt, "tools= skills=none");
+    }
+}
@@ -11,6 +11,7 @@
 //! - Conflict resolution when multiple skills are active
 //! - Merged directives for context injection
 
+use crate::llm::router::RoutingPreferences;
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -139,21 +140,6 @@ pub struct MergedDirectives {
     pub auto_tags: Vec<String>,
 }
 
-/// Routing preferences merged from active skills
-#[derive(Debug, Clone, Default)]
-pub struct RoutingPreferences {
-    /// Preferred providers (from highest priority skill)
-    pub preferred_providers: Vec<String>,
-    /// Providers to avoid (union of all active)
-    pub avoid_providers: Vec<String>,
-    /// Preferred execution mode (from highest priority skill)
-    pub prefer_mode: Option<String>,
-    /// Whether to always verify (true if any active skill requires it)
-    pub always_verify: bool,
-    /// Minimum score threshold (strictest across all active)
-    pub min_score_threshold: f32,
-}
-
 /// The Steering Engine manages the library of available skills
 pub struct SteeringEngine {
     skills_dir: PathBuf,
@@ -163,6 +149,9 @@ pub struct SteeringEngine {
 
 impl SteeringEngine {
     /// Create a new Steering Engine and load skills from the given directory
+    ///
+    /// A missing directory is treated as an empty skill library. It is not
+    /// created here; `rove skill add` creates it when the first skill is added.
     pub async fn new(skills_dir: &Path) -> Result<Self> {
         let mut engine = Self {
             skills_dir: skills_dir.to_path_buf(),
@@ -170,16 +159,7 @@ impl SteeringEngine {
             active: Vec::new(),
         };
 
-        if skills_dir.exists() && skills_dir.is_dir() {
-            engine.load_all_skills().await?;
-        } else {
-            info!(
-                "Skills directory {} does not exist yet.",
-                skills_dir.display()
-            );
-            fs::create_dir_all(skills_dir).await.ok();
-        }
-
+        engine.load_all_skills().await?;
         Ok(engine)
     }
 
@@ -187,6 +167,15 @@ impl SteeringEngine {
     pub async fn load_all_skills(&mut self) -> Result<()> {
         let mut new_skills = HashMap::new();
 
+        if !self.skills_dir.is_dir() {
+            info!(
+                "Skills directory {} does not exist yet.",
+                self.skills_dir.display()
+            );
+            self.skills = new_skills;
+            return Ok(());
+        }
+
         let mut entries = fs::read_dir(&self.skills_dir)
             .await
             .context("Failed to read skills directory")?;
@@ -249,9 +238,7 @@ impl SteeringEngine {
         if skill_file.activation.priority > MAX_USER_PRIORITY {
             // Built-in skills (sensitive, local-only) can have priority 100
             // but user skills are capped at 90
-            let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or("");
-            let builtin = ["sensitive", "local-only", "local_only"];
-            if !builtin.contains(&stem) {
+            if max_priority(path) == MAX_USER_PRIORITY {
                 warn!(
                     "Skill {} has priority {} > {}, capping to {}",
                     skill_file.meta.id,
@@ -638,6 +625,139 @@ impl SteeringEngine {
     pub fn is_active(&self, skill_id: &str) -> bool {
         self.active.contains(&skill_id.to_lowercase())
     }
+
+    /// Read the raw TOML or Markdown source of a skill for sharing
+    pub async fn export_skill(&self, name: &str) -> Result<String> {
+        let skill = self
+            .get_skill(name)
+            .ok_or_else(|| anyhow::anyhow!("Skill '{}' not found", name))?;
+
+        fs::read_to_string(&skill.file_path)
+            .await
+            .with_context(|| format!("Failed to read {}", skill.file_path.display()))
+    }
+
+    /// Set the `activation.priority` of a TOML skill and save it
+    ///
+    /// The file is rewritten with every other field kept (comments and
+    /// formatting are not). Fails for an unknown skill, a Markdown skill, or
+    /// a priority above 90 (100 for the built-in skills).
+    pub async fn set_priority(&mut self, name: &str, priority: u16) -> Result<()> {
+        let skill = self
+            .skills
+            .get_mut(&name.to_lowercase())
+            .ok_or_else(|| anyhow::anyhow!("Skill '{}' not found", name))?;
+        let Some(ref mut config) = skill.config else {
+            return Err(anyhow::anyhow!(
+                "Skill '{}' is a Markdown skill and has no priority",
+                name
+            ));
+        };
+        let max = max_priority(&skill.file_path);
+        if priority > max {
+            return Err(anyhow::anyhow!(
+                "Priority {} is out of range for skill '{}' (0-{})",
+                priority,
+                name,
+                max
+            ));
+        }
+
+        let path = &skill.file_path;
+        let content = fs::read_to_string(path)
+            .await
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let mut table: toml::Table = toml::from_str(&content)
+            .with_context(|| format!("Failed to parse TOML skill {}", path.display()))?;
+        let activation = table
+            .entry("activation")
+            .or_insert_with(|| toml::Value::Table(toml::Table::new()))
+            .as_table_mut()
+            .ok_or_else(|| anyhow::anyhow!("[activation] in {} is not a table", path.display()))?;
+        activation.insert(
+            "priority".to_string(),
+            toml::Value::Integer(priority.into()),
+        );
+        fs::write(path, toml::to_string(&table)?)
+            .await
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+
+        config.activation.priority = priority;
+        info!("Skill {} priority set to {}", name, priority);
+        Ok(())
+    }
+
+    /// Install a skill file into the skills directory
+    ///
+    /// The file is parsed before anything is written, so an invalid skill is
+    /// rejected. Importing fails if a skill with the same name or file name
+    /// already exists.
+    pub async fn import_skill(&mut self, source: &Path) -> Result<Skill> {
+        let skill = match source.extension().and_then(|s| s.to_str()) {
+            Some("toml") => Self::parse_toml_skill(source).await?,
+            Some("md") => Self::parse_md_skill(source).await?,
+            _ => {
+                return Err(anyhow::anyhow!(
+                    "Unsupported skill file {}: expected a .toml or .md file",
+                    source.display()
+                ))
+            }
+        };
+
+        let key = skill.name.to_lowercase();
+        if let Some(existing) = self.skills.get(&key) {
+            return Err(anyhow::anyhow!(
+                "Skill '{}' already exists at {}",
+                skill.name,
+                existing.file_path.display()
+            ));
+        }
+
+        let file_name = source
+            .file_name()
+            .ok_or_else(|| anyhow::anyhow!("Invalid skill path {}", source.display()))?;
+        let target = self.skills_dir.join(file_name);
+        if target.exists() {
+            return Err(anyhow::anyhow!(
+                "A skill file already exists at {}",
+                target.display()
+            ));
+        }
+
+        fs::create_dir_all(&self.skills_dir)
+            .await
+            .context("Failed to create skills directory")?;
+        fs::copy(source, &target)
+            .await
+            .with_context(|| format!("Failed to write {}", target.display()))?;
+
+        let skill = Skill {
+            file_path: target,
+            ..skill
+        };
+        info!(
+            "Imported skill: {} to {}",
+            skill.name,
+            skill.file_path.display()
+        );
+        self.skills.insert(key, skill.clone());
+
+        Ok(skill)
+    }
+}
+
+/// Highest priority the skill in `path` may have
+///
+/// Built-in skills (sensitive, local-only) can have priority 100, user
+/// skills at most `MAX_USER_PRIORITY`.
+fn max_priority(path: &Path) -> u16 {
+    let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or("");
+    let builtin = ["sensitive", "local-only", "local_only"];
+    if builtin.contains(&stem) {
+        100
+    } else {
+        MAX_USER_PRIORITY
+    }
 }
 
 #[cfg(test)]
@@ -999,7 +1119,141 @@ name = "S2"
         let missing = dir.path().join("nonexistent");
         let engine = SteeringEngine::new(&missing).await.unwrap();
         assert!(engine.list_skills().is_empty());
-        // Directory should have been created
-        assert!(missing.exists());
+        assert!(engine.active_skills().is_empty());
+        // Loading never creates the directory; `skill add` does
+        assert!(!missing.exists());
+    }
+
+    #[tokio::test]
+    async fn test_reload_after_dir_removed() {
+        let dir = tempdir().unwrap();
+        let skills_dir = dir.path().join("skills");
+        std::fs::create_dir(&skills_dir).unwrap();
+        std::fs::write(
+            skills_dir.join("notes.md"),
+            "---\nname: Notes\ndescription: Take notes\n---\nTake notes.",
+        )
+        .unwrap();
+
+        let mut engine = SteeringEngine::new(&skills_dir).await.unwrap();
+        assert_eq!(engine.list_skills().len(), 1);
+
+        std::fs::remove_dir_all(&skills_dir).unwrap();
+        engine.reload().await.unwrap();
+        assert!(engine.list_skills().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_export_import_round_trip() {
+        let source_dir = tempdir().unwrap();
+        let skill_content = r#"[meta]
+id = "reviewer"
+name = "Reviewer"
+description = "Careful code review"
+
+[directives]
+system_prefix = "Review every change carefully."
+"#;
+        fs::write(source_dir.path().join("reviewer.toml"), skill_content)
+            .await
+            .unwrap();
+        let source = SteeringEngine::new(source_dir.path()).await.unwrap();
+
+        let exported = source.export_skill("reviewer").await.unwrap();
+        assert_eq!(exported, skill_content);
+
+        // Import on another machine whose skills directory does not exist yet
+        let share_dir = tempdir().unwrap();
+        let shared_file = share_dir.path().join("reviewer.toml");
+        std::fs::write(&shared_file, &exported).unwrap();
+
+        let target_dir = tempdir().unwrap();
+        let skills_dir = target_dir.path().join("skills");
+        let mut target = SteeringEngine::new(&skills_dir).await.unwrap();
+        let imported = target.import_skill(&shared_file).await.unwrap();
+
+        assert_eq!(imported.name, "Reviewer");
+        assert_eq!(imported.file_path, skills_dir.join("reviewer.toml"));
+        assert!(target.get_skill("reviewer").is_some());
+        assert_eq!(
+            std::fs::read_to_string(skills_dir.join("reviewer.toml")).unwrap(),
+            skill_content
+        );
+
+        // Survives a reload from disk
+        let reloaded = SteeringEngine::new(&skills_dir).await.unwrap();
+        assert!(reloaded.get_skill("reviewer").is_some());
+    }
+
+    #[tokio::test]
+    async fn test_import_rejects_collision() {
+        let dir = tempdir().unwrap();
+        let skill_content = "---\nname: Notes\ndescription: Take notes\n---\nTake notes.";
+        fs::write(dir.path().join("notes.md"), skill_content)
+            .await
+            .unwrap();
+        let mut engine = SteeringEngine::new(dir.path()).await.unwrap();
+
+        // Same skill name under a different file name
+        let share_dir = tempdir().unwrap();
+        let incoming = share_dir.path().join("my-notes.md");
+        std::fs::write(&incoming, skill_content).unwrap();
+
+        let err = engine.import_skill(&incoming).await.unwrap_err();
+        assert!(err.to_string().contains("already exists"));
+        assert!(!dir.path().join("my-notes.md").exists());
+    }
+
+    #[tokio::test]
+    async fn test_import_rejects_invalid_skill() {
+        let dir = tempdir().unwrap();
+        let mut engine = SteeringEngine::new(dir.path()).await.unwrap();
+
+        let share_dir = tempdir().unwrap();
+        let incoming = share_dir.path().join("broken.toml");
+        std::fs::write(&incoming, "this is not = [valid toml").unwrap();
+
+        assert!(engine.import_skill(&incoming).await.is_err());
+        assert!(!dir.path().join("broken.toml").exists());
+        assert!(engine.list_skills().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_set_priority_persists() {
+        let dir = tempdir().unwrap();
+        let skill = r#"
+[meta]
+id = "review"
+name = "Review"
+description = "Careful reviews"
+
+[activation]
+manual = true
+priority = 50
+conflicts_with = ["fast"]
+
+[directives]
+system_prefix = "Review carefully."
+"#;
+        fs::write(dir.path().join("review.toml"), skill)
+            .await
+            .unwrap();
+        let mut engine = SteeringEngine::new(dir.path()).await.unwrap();
+
+        engine.set_priority("review", 75).await.unwrap();
+        assert!(engine.set_priority("review", 91).await.is_err());
+        assert!(engine.set_priority("missing", 10).await.is_err());
+
+        let reloaded = SteeringEngine::new(dir.path()).await.unwrap();
+        let config = reloaded
+            .get_skill("Review")
+            .unwrap()
+            .config
+            .clone()
+            .unwrap();
+        assert_eq!(config.activation.priority, 75);
+        assert_eq!(config.activation.conflicts_with, vec!["fast"]);
+        assert_eq!(config.meta.description, "Careful reviews");
+        assert_eq!(config.directives.system_prefix, "Review carefully.");
     }
 }
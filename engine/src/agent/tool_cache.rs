@@ -0,0 +1,100 @@
+//! Per-task cache of Tier 0 tool results
+//!
+//! Within one task the LLM often repeats a read, e.g. `read_file` on a file
+//! it has already seen. The agent keeps the result of every Tier 0 call,
+//! keyed by tool name and arguments, and answers an identical repeat from the
+//! cache instead of dispatching it again. Errors are not cached, and any
+//! Tier 1 or Tier 2 call clears the cache since it may change what a read
+//! returns. Tools whose output changes on its own (e.g. `capture_screen`) are
+//! never cached; see `conductor.uncached_tools`.
+
+use std::collections::{HashMap, HashSet};
+
+/// Tier 0 tools that are not cached unless configured otherwise
+pub const DEFAULT_UNCACHED_TOOLS: &[&str] = &["capture_screen", "extract_text"];
+
+/// Results of the Tier 0 tool calls of the current task
+#[derive(Debug)]
+pub struct ToolResultCache {
+    entries: HashMap<String, String>,
+    uncached: HashSet<String>,
+}
+
+impl Default for ToolResultCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_UNCACHED_TOOLS.iter().copied())
+    }
+}
+
+impl ToolResultCache {
+    /// Empty cache that never stores results of the tools in `uncached`
+    pub fn new<I, S>(uncached: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self {
+            entries: HashMap::new(),
+            uncached: uncached.into_iter().map(Into::into).collect(),
+        }
+    }
+
+    /// Cached result of calling `tool` with `args`
+    pub fn get(&self, tool: &str, args: &serde_json::Value) -> Option<&str> {
+        self.entries.get(&key(tool, args)).map(String::as_str)
+    }
+
+    /// Remember `result` for `tool` called with `args`
+    ///
+    /// Results of uncached tools and error results are ignored.
+    pub fn insert(&mut self, tool: &str, args: &serde_json::Value, result: &str) {
+        if self.uncached.contains(tool) || result.starts_with("ERROR:") {
+            return;
+        }
+        self.entries.insert(key(tool, args), result.to_string());
+    }
+
+    /// Forget all results
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+}
+
+/// Cache key of a call; object keys serialize sorted, so argument order
+/// doesn't matter
+fn key(tool: &str, args: &serde_json::Value) -> String {
+    format!("{}:{}", tool, args)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_only_identical_calls_hit() {
+        let mut cache = ToolResultCache::default();
+        let args: serde_json::Value =
+            serde_json::from_str(r#"{"path": "a.txt", "include_ignored": false}"#).unwrap();
+        cache.insert("read_file", &args, "contents");
+
+        let reordered = json!({"include_ignored": false, "path": "a.txt"});
+        assert_eq!(cache.get("read_file", &reordered), Some("contents"));
+        assert_eq!(cache.get("read_file", &json!({"path": "b.txt"})), None);
+        assert_eq!(cache.get("list_dir", &args), None);
+
+        cache.clear();
+        assert_eq!(cache.get("read_file", &args), None);
+    }
+
+    #[test]
+    fn test_uncached_tools_and_errors_are_not_stored() {
+        let mut cache = ToolResultCache::default();
+        let args = json!({"path": "a.txt"});
+        cache.insert("read_file", &args, "ERROR: File not found");
+        cache.insert("capture_screen", &json!({}), "Screenshot saved to shot.png");
+
+        assert_eq!(cache.get("read_file", &args), None);
+        assert_eq!(cache.get("capture_screen", &json!({})), None);
+    }
+}
@@ -0,0 +1,122 @@
+//! Transcript dumps for `rove run --dump-transcript`
+//!
+//! A dump is the whole conversation of one task as a single JSON document:
+//! the system prompt, the user's task, every LLM turn (tool calls included),
+//! every tool result, and the final answer. Unlike working memory it is never
+//! trimmed, and it does not depend on the task being persisted. Every message
+//! is passed through `SecretManager::scrub` before it is written, so a dump
+//! can be attached to a bug report.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::path::Path;
+
+use crate::llm::Message;
+use crate::secrets::SecretManager;
+
+/// Contents of a transcript dump
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TranscriptDump {
+    /// Task the conversation belongs to, if it got as far as having one
+    pub task_id: Option<String>,
+
+    /// Error the task failed with; `None` if it completed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+
+    /// The conversation, in order, with secrets scrubbed
+    pub messages: Vec<Message>,
+}
+
+impl TranscriptDump {
+    /// Dump of `messages`, scrubbing secrets from every message and the error
+    pub fn new(task_id: Option<String>, messages: &[Message], error: Option<String>) -> Self {
+        let scrubber = SecretManager::new("rove");
+        Self {
+            task_id,
+            error: error.map(|e| scrubber.scrub(&e)),
+            messages: messages
+                .iter()
+                .map(|message| Message {
+                    content: scrubber.scrub(&message.content),
+                    ..message.clone()
+                })
+                .collect(),
+        }
+    }
+
+    /// Write the dump to `path` as pretty-printed JSON
+    ///
+    /// On Unix a new file is created readable by the owner only.
+    pub fn write(&self, path: &Path) -> Result<()> {
+        let json = serde_json::to_string_pretty(self).context("Failed to serialize transcript")?;
+
+        let mut options = std::fs::OpenOptions::new();
+        options.write(true).create(true).truncate(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+        options
+            .open(path)
+            .and_then(|mut file| file.write_all(json.as_bytes()))
+            .with_context(|| format!("Failed to write transcript to {}", path.display()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm::MessageRole;
+
+    #[test]
+    fn test_dump_parses_and_redacts_secrets() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("transcript.json");
+        let messages = vec![
+            Message::system("You are Rove."),
+            Message::user("Use key sk-abcdefghijklmnopqrstuvwxyz to call the API"),
+            Message::assistant(r#"{"function":"read_file","arguments":{"path":".env.example"}}"#),
+            Message::tool_result("OPENAI_API_KEY=sk-proj-0123456789abcdefghij", "call_1"),
+            Message::assistant("The example config sets an OpenAI key."),
+        ];
+
+        TranscriptDump::new(Some("run-1".to_string()), &messages, None)
+            .write(&path)
+            .unwrap();
+        let dump: TranscriptDump =
+            serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+
+        assert_eq!(dump.task_id.as_deref(), Some("run-1"));
+        assert_eq!(dump.error, None);
+        let roles: Vec<MessageRole> = dump.messages.iter().map(|m| m.role).collect();
+        assert_eq!(
+            roles,
+            [
+                MessageRole::System,
+                MessageRole::User,
+                MessageRole::Assistant,
+                MessageRole::Tool,
+                MessageRole::Assistant,
+            ]
+        );
+        assert_eq!(
+            dump.messages[1].content,
+            "Use key [REDACTED] to call the API"
+        );
+        assert_eq!(dump.messages[3].content, "OPENAI_API_KEY=[REDACTED]");
+        assert_eq!(dump.messages[3].tool_call_id.as_deref(), Some("call_1"));
+        let written = std::fs::read_to_string(&path).unwrap();
+        assert!(!written.contains("sk-abcdefghijklmnopqrstuvwxyz"));
+        assert!(!written.contains("sk-proj-0123456789abcdefghij"));
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+    }
+}
@@ -4,6 +4,9 @@
 //! stores messages in order (system prompt, user messages, assistant responses,
 //! tool calls, tool results) and automatically trims old messages when approaching
 //! the context limit while preserving the system prompt and recent messages.
+//!
+//! With `record_transcript` on, every message is also kept in an untrimmed
+//! transcript of the conversation (see `rove run --dump-transcript`).
 
 use crate::llm::{Message, MessageRole};
 
@@ -24,6 +27,9 @@ pub struct WorkingMemory {
 
     /// Current estimated token count
     token_count: usize,
+
+    /// Every message since the last `clear`, if recording
+    transcript: Option<Vec<Message>>,
 }
 
 impl WorkingMemory {
@@ -38,9 +44,28 @@ impl WorkingMemory {
             messages: Vec::new(),
             context_limit,
             token_count: 0,
+            transcript: None,
         }
     }
 
+    /// Keep an untrimmed copy of every message added from now on
+    pub fn record_transcript(&mut self) {
+        self.transcript.get_or_insert_with(Vec::new);
+    }
+
+    /// Record `message` in the transcript only, not in the context
+    pub fn add_to_transcript(&mut self, message: Message) {
+        if let Some(transcript) = &mut self.transcript {
+            transcript.push(message);
+        }
+    }
+
+    /// Every message since the last `clear`, including trimmed ones, if
+    /// `record_transcript` is on
+    pub fn transcript(&self) -> Option<&[Message]> {
+        self.transcript.as_deref()
+    }
+
     /// Add a message to the working memory
     ///
     /// If adding the message would exceed the context limit, old messages
@@ -48,6 +73,10 @@ impl WorkingMemory {
     pub fn add_message(&mut self, message: Message) {
         let message_tokens = Self::estimate_tokens(&message);
 
+        if self.transcript.is_some() {
+            self.add_to_transcript(message.clone());
+        }
+
         // Add the message
         self.messages.push(message);
         self.token_count += message_tokens;
@@ -77,6 +106,9 @@ impl WorkingMemory {
     pub fn clear(&mut self) {
         self.messages.clear();
         self.token_count = 0;
+        if let Some(transcript) = &mut self.transcript {
+            transcript.clear();
+        }
     }
 
     /// Trim old messages to stay within context limit
@@ -325,4 +357,22 @@ mod tests {
         assert_eq!(memory.context_limit(), DEFAULT_CONTEXT_LIMIT);
         assert_eq!(memory.messages().len(), 0);
     }
+
+    #[test]
+    fn test_transcript_keeps_trimmed_messages() {
+        let mut memory = WorkingMemory::with_limit(50);
+        assert!(memory.transcript().is_none());
+        memory.record_transcript();
+
+        memory.add_message(Message::system("System prompt"));
+        for i in 0..10 {
+            memory.add_message(Message::user(format!("Message {} {}", i, "x".repeat(40))));
+        }
+
+        assert!(memory.messages().len() < 11);
+        assert_eq!(memory.transcript().unwrap().len(), 11);
+
+        memory.clear();
+        assert_eq!(memory.transcript(), Some(&[][..]));
+    }
 }
@@ -0,0 +1,211 @@
+//! Signed audit-log export
+//!
+//! `rove audit export` writes every recorded task, with its steps and any
+//! failure, to a single JSON document signed with a local Ed25519 key.
+//! `rove audit verify` checks such a file, so an export handed to a reviewer
+//! can be shown not to have been edited since it left the machine.
+//!
+//! The signing key is a 32-byte seed stored hex-encoded in the OS keychain
+//! under `audit.signing_key_secret`. It is generated on first export. The
+//! export embeds the matching public key; verification on the exporting
+//! machine additionally requires that key to be the configured one.
+
+use crate::crypto::CryptoModule;
+use crate::db::{Database, Task, TaskRepository, TaskStep};
+use crate::secrets::SecretManager;
+use anyhow::{anyhow, Context, Result};
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::OsRng;
+use ed25519_dalek::{SigningKey, VerifyingKey, PUBLIC_KEY_LENGTH, SECRET_KEY_LENGTH};
+use serde::{Deserialize, Serialize};
+
+/// Format identifier written to every export
+pub const AUDIT_FORMAT: &str = "rove-audit-v1";
+
+/// A task together with its recorded steps
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditTask {
+    #[serde(flatten)]
+    pub task: Task,
+    pub steps: Vec<TaskStep>,
+}
+
+/// A signed audit-log export
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditExport {
+    pub format: String,
+    pub exported_at: i64,
+    /// Hex-encoded Ed25519 public key of the signer
+    pub public_key: String,
+    pub tasks: Vec<AuditTask>,
+    /// `ed25519:<hex>` signature over the rest of the document
+    pub signature: String,
+}
+
+/// Outcome of a successful `verify`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VerifiedExport {
+    pub exported_at: i64,
+    pub public_key: String,
+    pub tasks: usize,
+    /// True when the signer matched a trusted key, not just the embedded one
+    pub trusted: bool,
+}
+
+/// Collect every task from the database and sign the result
+pub async fn export(database: &Database, key: &SigningKey) -> Result<AuditExport> {
+    let repo = TaskRepository::new(database.pool().clone());
+    let mut tasks = Vec::new();
+    for task in repo.get_recent_tasks(i64::MAX).await? {
+        let steps = repo.get_task_steps(&task.id).await?;
+        tasks.push(AuditTask { task, steps });
+    }
+    // Oldest first reads naturally as a log
+    tasks.reverse();
+
+    let mut export = AuditExport {
+        format: AUDIT_FORMAT.to_string(),
+        exported_at: chrono::Utc::now().timestamp(),
+        public_key: hex::encode(key.verifying_key().to_bytes()),
+        tasks,
+        signature: String::new(),
+    };
+    let unsigned = serde_json::to_vec(&export)?;
+    export.signature = CryptoModule::sign_document(&unsigned, key)?;
+    Ok(export)
+}
+
+/// Verify an exported document
+///
+/// The signature is checked against the public key embedded in the
+/// document. With `trusted` set, that key must also equal `trusted`;
+/// otherwise anyone could re-sign an edited export with their own key.
+pub fn verify(document: &[u8], trusted: Option<&VerifyingKey>) -> Result<VerifiedExport> {
+    let export: AuditExport =
+        serde_json::from_slice(document).context("Not a valid audit export")?;
+    if export.format != AUDIT_FORMAT {
+        return Err(anyhow!("Unsupported audit format '{}'", export.format));
+    }
+
+    let public_key = parse_public_key(&export.public_key)?;
+    if let Some(trusted) = trusted {
+        if trusted != &public_key {
+            return Err(anyhow!(
+                "Export was signed by {}, not by the configured audit key {}",
+                export.public_key,
+                hex::encode(trusted.to_bytes())
+            ));
+        }
+    }
+
+    CryptoModule::verify_document(document, &public_key)
+        .map_err(|_| anyhow!("Audit export signature is invalid; the file has been modified"))?;
+
+    Ok(VerifiedExport {
+        exported_at: export.exported_at,
+        public_key: export.public_key,
+        tasks: export.tasks.len(),
+        trusted: trusted.is_some(),
+    })
+}
+
+/// Load the audit signing key from the keychain
+pub fn load_signing_key(secrets: &SecretManager, secret_name: &str) -> Result<Option<SigningKey>> {
+    if !secrets.has_secret(secret_name) {
+        return Ok(None);
+    }
+    let seed = secrets.get_secret(secret_name)?;
+    let bytes: [u8; SECRET_KEY_LENGTH] = hex::decode(seed.trim())
+        .ok()
+        .and_then(|b| b.try_into().ok())
+        .ok_or_else(|| {
+            anyhow!(
+                "Keychain entry '{}' is not a hex-encoded {}-byte key",
+                secret_name,
+                SECRET_KEY_LENGTH
+            )
+        })?;
+    Ok(Some(SigningKey::from_bytes(&bytes)))
+}
+
+/// Load the audit signing key, generating and storing one if none exists
+pub fn load_or_create_signing_key(
+    secrets: &SecretManager,
+    secret_name: &str,
+) -> Result<SigningKey> {
+    if let Some(key) = load_signing_key(secrets, secret_name)? {
+        return Ok(key);
+    }
+
+    let mut seed = [0u8; SECRET_KEY_LENGTH];
+    OsRng.fill_bytes(&mut seed);
+    secrets.set_secret(secret_name, &hex::encode(seed))?;
+    tracing::info!("Generated new audit signing key '{}'", secret_name);
+    Ok(SigningKey::from_bytes(&seed))
+}
+
+fn parse_public_key(hex_key: &str) -> Result<VerifyingKey> {
+    let bytes: [u8; PUBLIC_KEY_LENGTH] = hex::decode(hex_key)
+        .ok()
+        .and_then(|b| b.try_into().ok())
+        .ok_or_else(|| anyhow!("Invalid public key in audit export"))?;
+    VerifyingKey::from_bytes(&bytes)
+        .map_err(|e| anyhow!("Invalid public key in audit export: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::TaskStatus;
+    use tempfile::TempDir;
+
+    async fn export_with_task(key: &SigningKey) -> (TempDir, Vec<u8>) {
+        let dir = TempDir::new().unwrap();
+        let database = Database::new(&dir.path().join("audit.db")).await.unwrap();
+        let repo = TaskRepository::new(database.pool().clone());
+        let task_id = "3f1c1b7e-0000-4000-8000-000000000001";
+        repo.create_task(task_id, "list files").await.unwrap();
+        repo.update_task_status(task_id, TaskStatus::Completed)
+            .await
+            .unwrap();
+
+        let export = export(&database, key).await.unwrap();
+        (dir, serde_json::to_vec_pretty(&export).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_valid_export_verifies() {
+        let key = SigningKey::from_bytes(&[42u8; 32]);
+        let (_dir, document) = export_with_task(&key).await;
+
+        let verified = verify(&document, Some(&key.verifying_key())).unwrap();
+        assert_eq!(verified.tasks, 1);
+        assert!(verified.trusted);
+
+        let untrusted = verify(&document, None).unwrap();
+        assert!(!untrusted.trusted);
+    }
+
+    #[tokio::test]
+    async fn test_modified_export_fails() {
+        let key = SigningKey::from_bytes(&[42u8; 32]);
+        let (_dir, document) = export_with_task(&key).await;
+
+        let mut value: serde_json::Value = serde_json::from_slice(&document).unwrap();
+        value["tasks"][0]["input"] = serde_json::json!("rm -rf /");
+        let tampered = serde_json::to_vec(&value).unwrap();
+
+        assert!(verify(&tampered, None).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_export_signed_by_other_key_fails() {
+        let key = SigningKey::from_bytes(&[42u8; 32]);
+        let other = SigningKey::from_bytes(&[7u8; 32]);
+        let (_dir, document) = export_with_task(&other).await;
+
+        // Internally consistent, but not signed by the trusted key
+        assert!(verify(&document, None).is_ok());
+        assert!(verify(&document, Some(&key.verifying_key())).is_err());
+    }
+}
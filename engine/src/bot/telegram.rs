@@ -5,6 +5,7 @@
 
 use anyhow::Result;
 use reqwest::Client;
+use sdk::errors::EngineError;
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use std::time::Duration;
@@ -12,13 +13,14 @@ use tokio::sync::Mutex;
 use tracing::{error, info, warn};
 
 use crate::agent::{AgentCore, Task};
-use crate::risk_assessor::OperationSource;
+use crate::rate_limiter::RateLimiter;
+use crate::risk_assessor::{OperationSource, RiskTier};
 use crate::secrets::SecretManager;
 
 /// Callback type for processing incoming task text and returning a reply
 pub type TaskHandler = Arc<Mutex<AgentCore>>;
 
-/// Rate limit tracking for Telegram operations
+/// In-process rate limits, used when no shared `RateLimiter` is attached
 #[derive(Debug, Clone)]
 struct TelegramRateLimits {
     /// Timestamps of recent operations (for 60/hour limit)
@@ -81,6 +83,7 @@ pub struct TelegramBot {
     client: Client,
     agent: Option<TaskHandler>,
     rate_limits: Arc<Mutex<TelegramRateLimits>>,
+    rate_limiter: Option<Arc<RateLimiter>>,
     confirmation_chat_id: Option<i64>,
     secret_manager: Arc<SecretManager>,
 }
@@ -134,6 +137,7 @@ impl TelegramBot {
                 .unwrap_or_default(),
             agent: None,
             rate_limits: Arc::new(Mutex::new(TelegramRateLimits::new())),
+            rate_limiter: None,
             confirmation_chat_id: None,
             secret_manager: Arc::new(SecretManager::new("rove")),
         }
@@ -145,6 +149,17 @@ impl TelegramBot {
         self
     }
 
+    /// Throttle each chat through the shared rate limiter
+    ///
+    /// Every incoming task is checked as a Tier 1 operation keyed by chat id,
+    /// so one chat cannot exhaust the limit for others. Build the limiter with
+    /// `RateLimiter::from_config` so chats listed in
+    /// `security.rate_limit_bypass` are exempt.
+    pub fn with_rate_limiter(mut self, rate_limiter: Arc<RateLimiter>) -> Self {
+        self.rate_limiter = Some(rate_limiter);
+        self
+    }
+
     /// Set the confirmation chat ID for sending results
     pub fn with_confirmation_chat(mut self, chat_id: i64) -> Self {
         self.confirmation_chat_id = Some(chat_id);
@@ -226,24 +241,9 @@ impl TelegramBot {
             }
 
             // Check rate limits (Requirement 16.7)
-            {
-                let mut limits = self.rate_limits.lock().await;
-                if !limits.check_general() {
-                    let _ = self
-                        .send_message(chat_id, "Rate limit exceeded (60/hour). Please wait.")
-                        .await;
-                    return;
-                }
-                // Also check Tier 2 limits for potentially dangerous operations
-                if !limits.check_tier2() {
-                    let _ = self
-                        .send_message(
-                            chat_id,
-                            "Tier 2 rate limit exceeded (10/10min). Please wait.",
-                        )
-                        .await;
-                    return;
-                }
+            if let Some(reply) = self.check_rate_limit(chat_id).await {
+                let _ = self.send_message(chat_id, reply).await;
+                return;
             }
 
             // Dispatch to agent if available
@@ -251,6 +251,10 @@ impl TelegramBot {
                 let _ = self.send_message(chat_id, "Processing your task...").await;
 
                 let task = Task::new(text.as_str(), OperationSource::Remote);
+                info!(
+                    "Task for chat {} has request id {}",
+                    chat_id, task.request_id
+                );
                 let mut agent_guard = agent.lock().await;
 
                 match agent_guard.process_task(task).await {
@@ -287,6 +291,41 @@ impl TelegramBot {
         }
     }
 
+    /// Check and record an incoming task against the rate limits
+    ///
+    /// With a shared `RateLimiter`, each chat is its own Tier 1 source
+    /// (`telegram:<chat_id>`). Without one, the in-process limits apply to all
+    /// chats together. Returns the reply to send if the task is throttled.
+    async fn check_rate_limit(&self, chat_id: i64) -> Option<&'static str> {
+        if let Some(ref limiter) = self.rate_limiter {
+            let source = format!("telegram:{}", chat_id);
+
+            return match limiter.check_and_record(&source, RiskTier::Tier1).await {
+                Ok(()) => None,
+                Err(e) if e.downcast_ref::<EngineError>().is_some() => {
+                    warn!("Throttling Telegram chat {}: {}", chat_id, e);
+                    Some("Slow down: this chat has reached its task limit (60/hour). Please wait.")
+                }
+                // An unrecorded task would not count against the limit, so it
+                // is refused rather than run
+                Err(e) => {
+                    error!("Failed to record Telegram operation for {}: {}", chat_id, e);
+                    Some("Could not check this chat's task limit. Please try again later.")
+                }
+            };
+        }
+
+        let mut limits = self.rate_limits.lock().await;
+        if !limits.check_general() {
+            return Some("Rate limit exceeded (60/hour). Please wait.");
+        }
+        // Also check Tier 2 limits for potentially dangerous operations
+        if !limits.check_tier2() {
+            return Some("Tier 2 rate limit exceeded (10/10min). Please wait.");
+        }
+        None
+    }
+
     /// Send a Tier 1 countdown message (Requirement 16.5)
     ///
     /// Sends countdown messages for Tier 1 operations, giving the user
@@ -421,6 +460,82 @@ mod tests {
         assert!(!limits.check_tier2());
     }
 
+    async fn bot_with_rate_limiter(temp_dir: &tempfile::TempDir) -> TelegramBot {
+        let db = crate::db::Database::new(&temp_dir.path().join("test.db"))
+            .await
+            .unwrap();
+        let limiter = Arc::new(RateLimiter::new(db.pool().clone()));
+        TelegramBot::new("token".to_string(), vec![]).with_rate_limiter(limiter)
+    }
+
+    #[tokio::test]
+    async fn test_chat_exceeding_hourly_limit_is_throttled() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let bot = bot_with_rate_limiter(&temp_dir).await;
+
+        for _ in 0..60 {
+            assert!(bot.check_rate_limit(42).await.is_none());
+        }
+
+        let reply = bot.check_rate_limit(42).await;
+        assert!(reply.unwrap().starts_with("Slow down"));
+    }
+
+    #[tokio::test]
+    async fn test_rate_limit_is_per_chat() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let bot = bot_with_rate_limiter(&temp_dir).await;
+
+        for _ in 0..60 {
+            assert!(bot.check_rate_limit(1).await.is_none());
+        }
+        assert!(bot.check_rate_limit(1).await.is_some());
+
+        // A different chat has its own budget
+        assert!(bot.check_rate_limit(2).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_bypassed_chat_is_never_throttled() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let db = crate::db::Database::new(&temp_dir.path().join("test.db"))
+            .await
+            .unwrap();
+        let mut security = crate::config::Config::default_config().security;
+        security.rate_limit_bypass = vec!["telegram:42".to_string()];
+        let limiter = RateLimiter::from_config(db.pool().clone(), &security);
+        let bot =
+            TelegramBot::new("token".to_string(), vec![]).with_rate_limiter(Arc::new(limiter));
+
+        for _ in 0..70 {
+            assert!(bot.check_rate_limit(42).await.is_none());
+        }
+        for _ in 0..60 {
+            assert!(bot.check_rate_limit(7).await.is_none());
+        }
+        assert!(bot.check_rate_limit(7).await.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_task_is_refused_when_it_cannot_be_recorded() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let db = crate::db::Database::new(&temp_dir.path().join("test.db"))
+            .await
+            .unwrap();
+        sqlx::query(
+            "CREATE TRIGGER reject_rate_limits BEFORE INSERT ON rate_limits \
+             BEGIN SELECT RAISE(ABORT, 'disk full'); END",
+        )
+        .execute(db.pool())
+        .await
+        .unwrap();
+        let bot = TelegramBot::new("token".to_string(), vec![])
+            .with_rate_limiter(Arc::new(RateLimiter::new(db.pool().clone())));
+
+        let reply = bot.check_rate_limit(42).await;
+        assert!(reply.unwrap().starts_with("Could not check"));
+    }
+
     #[test]
     fn test_secret_scrubbing_in_messages() {
         let manager = SecretManager::new("test");
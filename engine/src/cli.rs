@@ -43,12 +43,47 @@ pub enum Command {
     Stop,
 
     /// Show daemon status and provider availability
-    Status,
+    Status {
+        /// Probe every provider, not just the default and those with API keys
+        #[arg(long)]
+        all: bool,
+    },
 
     /// Execute a task immediately
     Run {
         /// The task to execute
-        task: String,
+        #[arg(required_unless_present = "resume")]
+        task: Option<String>,
+
+        /// Resume a failed or interrupted task by ID instead of starting one
+        #[arg(long, value_name = "TASK_ID", conflicts_with = "task")]
+        resume: Option<String>,
+
+        /// Keep no task history and leave nothing behind (for one-shot CI runs)
+        #[arg(long)]
+        no_daemon: bool,
+
+        /// Raise the 1 MB file read limit for this run only (up to 5 MB)
+        #[arg(long, value_name = "BYTES", value_parser = parse_max_file_size)]
+        max_file_size: Option<u64>,
+
+        /// Give up on the task after this long, e.g. 90s, 10m or 1h
+        #[arg(long, value_name = "DURATION", value_parser = parse_timeout)]
+        timeout: Option<std::time::Duration>,
+
+        /// Write the full conversation, secrets scrubbed, to this file as JSON
+        #[arg(long, value_name = "PATH")]
+        dump_transcript: Option<std::path::PathBuf>,
+    },
+
+    /// Print the system prompt the agent would use for a task, without running it
+    ExportPrompt {
+        /// The task to compose the prompt for (skills auto-activate on it)
+        task: Option<String>,
+
+        /// Activate a skill by ID before auto-activation (repeatable)
+        #[arg(long = "skill", value_name = "ID")]
+        skills: Vec<String>,
     },
 
     /// Show task history
@@ -58,6 +93,13 @@ pub enum Command {
         limit: usize,
     },
 
+    /// Show estimated LLM spend
+    Cost {
+        /// Only count tasks since a date (YYYY-MM-DD) or for a recent period (30m, 24h, 7d, 2w)
+        #[arg(long, value_parser = parse_since)]
+        since: Option<i64>,
+    },
+
     /// Replay and show all steps for a task
     Replay {
         /// Task ID to replay
@@ -83,13 +125,21 @@ pub enum Command {
     },
 
     /// Run system diagnostics
-    Doctor,
+    Doctor {
+        /// Repair problems that are safe to fix automatically (e.g. a stale PID file)
+        #[arg(long)]
+        fix: bool,
+    },
 
-    /// Update Rove to the latest version
+    /// Check for a newer Rove release and optionally install it
     Update {
         /// Only check if an update is available, do not download
-        #[arg(long)]
+        #[arg(long, conflicts_with = "apply")]
         check: bool,
+
+        /// Download, verify against the signed release manifest, and install
+        #[arg(long)]
+        apply: bool,
     },
 
     /// Manage Telegram bot
@@ -103,6 +153,61 @@ pub enum Command {
         #[command(subcommand)]
         action: SkillAction,
     },
+
+    /// Manage workspaces
+    Workspace {
+        #[command(subcommand)]
+        action: WorkspaceAction,
+    },
+
+    /// Export and verify the signed audit log
+    Audit {
+        #[command(subcommand)]
+        action: AuditAction,
+    },
+
+    /// Maintain the local database
+    Db {
+        #[command(subcommand)]
+        action: DbAction,
+    },
+}
+
+/// Database maintenance actions
+#[derive(Subcommand, Debug)]
+pub enum DbAction {
+    /// Delete rows older than the `[retention]` limits now
+    Prune,
+}
+
+/// Audit log actions
+#[derive(Subcommand, Debug)]
+pub enum AuditAction {
+    /// Export all tasks and steps as signed JSON
+    Export {
+        /// Write to a file instead of stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+
+    /// Check the signature of an exported audit log
+    Verify {
+        /// Exported JSON file
+        file: PathBuf,
+    },
+}
+
+/// Workspace management actions
+#[derive(Subcommand, Debug)]
+pub enum WorkspaceAction {
+    /// List configured workspaces
+    List,
+
+    /// Set the active workspace for subsequent runs
+    Use {
+        /// Workspace name ("default" selects core.workspace)
+        name: String,
+    },
 }
 
 /// Agent Skill management actions
@@ -144,24 +249,77 @@ pub enum SkillAction {
         /// Name of the skill to edit
         name: String,
     },
+
+    /// Export a skill's TOML or Markdown source
+    Export {
+        /// Name of the skill to export
+        name: String,
+        /// Write to this file instead of stdout
+        #[arg(short, long)]
+        out: Option<PathBuf>,
+    },
+
+    /// Install a skill file into the skills directory
+    Import {
+        /// Path to a .toml or .md skill file
+        file: PathBuf,
+    },
+
+    /// Set a skill's priority in prompt composition (higher goes first)
+    Priority {
+        /// Name of the skill
+        name: String,
+        /// New priority (0-90; up to 100 for built-in skills)
+        value: u16,
+    },
 }
 
 /// Plugin management actions
 #[derive(Subcommand, Debug)]
 pub enum PluginAction {
     /// List all installed plugins
-    List,
+    List {
+        /// Query the registry and show which plugins have a newer version
+        #[arg(long)]
+        check_updates: bool,
+    },
 
-    /// Enable a plugin
-    Enable {
-        /// Plugin name
+    /// Search the official and community plugin registries
+    Search {
+        /// Text to look for in plugin id, name, author or description
+        query: String,
+    },
+
+    /// Update an installed plugin to the registry's latest version
+    Update {
+        /// Plugin id
         name: String,
+
+        /// Grant any new permissions the update requests without asking
+        #[arg(long)]
+        yes: bool,
+    },
+
+    /// Enable plugins, saving the config once
+    Enable {
+        /// Plugin ids, separated by commas (e.g. fs-editor,git)
+        #[arg(value_delimiter = ',', required_unless_present = "all")]
+        names: Vec<String>,
+
+        /// Enable every plugin
+        #[arg(long, conflicts_with = "names")]
+        all: bool,
     },
 
-    /// Disable a plugin
+    /// Disable plugins, saving the config once
     Disable {
-        /// Plugin name
-        name: String,
+        /// Plugin ids, separated by commas (e.g. fs-editor,git)
+        #[arg(value_delimiter = ',', required_unless_present = "all")]
+        names: Vec<String>,
+
+        /// Disable every plugin
+        #[arg(long, conflicts_with = "names")]
+        all: bool,
     },
 
     /// Show plugin details
@@ -221,6 +379,15 @@ pub enum ConfigAction {
 
     /// Validate configuration file
     Validate,
+
+    /// Print the configuration file
+    Dump {
+        /// Print every setting in use, including defaults and the active
+        /// workspace, marking where each value comes from (file, default or
+        /// override)
+        #[arg(long)]
+        effective: bool,
+    },
 }
 
 /// Telegram bot management actions
@@ -254,6 +421,73 @@ pub enum BotAction {
     },
 }
 
+/// Parse `--max-file-size`
+///
+/// The override may only raise the read limit, and not past the hard
+/// ceiling, so values outside that range are rejected rather than adjusted.
+pub fn parse_max_file_size(value: &str) -> Result<u64, String> {
+    use crate::fs_guard::{DEFAULT_MAX_READ_SIZE, HARD_MAX_READ_SIZE};
+
+    let bytes: u64 = value
+        .parse()
+        .map_err(|_| format!("invalid size '{}': use a number of bytes", value))?;
+    if !(DEFAULT_MAX_READ_SIZE..=HARD_MAX_READ_SIZE).contains(&bytes) {
+        return Err(format!(
+            "{} bytes is outside the allowed range: the limit can be raised from {} up to {} bytes",
+            bytes, DEFAULT_MAX_READ_SIZE, HARD_MAX_READ_SIZE
+        ));
+    }
+    Ok(bytes)
+}
+
+/// Parse `--timeout` into a duration
+///
+/// Accepts a number of seconds with an optional unit suffix of `s`, `m` or
+/// `h`. Zero is rejected.
+pub fn parse_timeout(value: &str) -> Result<std::time::Duration, String> {
+    let invalid = || format!("invalid timeout '{}': use e.g. 90s, 10m or 1h", value);
+    let (amount, unit_secs) = match value.chars().last() {
+        Some('s') => (&value[..value.len() - 1], 1),
+        Some('m') => (&value[..value.len() - 1], 60),
+        Some('h') => (&value[..value.len() - 1], 60 * 60),
+        _ => (value, 1),
+    };
+    let amount: u64 = amount.parse().map_err(|_| invalid())?;
+    if amount == 0 {
+        return Err(invalid());
+    }
+
+    Ok(std::time::Duration::from_secs(amount * unit_secs))
+}
+
+/// Parse `--since` into a Unix timestamp in seconds
+///
+/// Accepts a UTC date (`2024-01-31`) or a period counted back from now with a
+/// unit suffix of `m`, `h`, `d` or `w`.
+pub fn parse_since(value: &str) -> Result<i64, String> {
+    if let Ok(date) = chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        return Ok(date.and_time(chrono::NaiveTime::MIN).and_utc().timestamp());
+    }
+
+    let invalid = || format!("invalid period '{}': use e.g. 24h, 7d or 2024-01-31", value);
+    let unit_secs = match value.chars().last() {
+        Some('m') => 60,
+        Some('h') => 60 * 60,
+        Some('d') => 24 * 60 * 60,
+        Some('w') => 7 * 24 * 60 * 60,
+        _ => return Err(invalid()),
+    };
+    let amount: i64 = value[..value.len() - 1].parse().map_err(|_| invalid())?;
+    if amount < 0 {
+        return Err(invalid());
+    }
+
+    amount
+        .checked_mul(unit_secs)
+        .and_then(|period| chrono::Utc::now().timestamp().checked_sub(period))
+        .ok_or_else(|| format!("period '{}' is too long", value))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -262,7 +496,10 @@ mod tests {
     fn test_cli_parsing() {
         // Test basic command parsing
         let cli = Cli::parse_from(["rove", "status"]);
-        assert!(matches!(cli.command, Command::Status));
+        assert!(matches!(cli.command, Command::Status { all: false }));
+
+        let cli = Cli::parse_from(["rove", "status", "--all"]);
+        assert!(matches!(cli.command, Command::Status { all: true }));
         assert!(!cli.json);
         assert!(cli.log.is_none());
         assert!(cli.config.is_none());
@@ -280,11 +517,103 @@ mod tests {
     fn test_run_command() {
         // Test run command with task
         let cli = Cli::parse_from(["rove", "run", "list files in current directory"]);
-        if let Command::Run { task } = cli.command {
-            assert_eq!(task, "list files in current directory");
+        if let Command::Run {
+            task,
+            resume,
+            no_daemon,
+            max_file_size,
+            timeout,
+            dump_transcript,
+        } = cli.command
+        {
+            assert_eq!(task.as_deref(), Some("list files in current directory"));
+            assert_eq!(resume, None);
+            assert!(!no_daemon);
+            assert_eq!(max_file_size, None);
+            assert_eq!(timeout, None);
+            assert_eq!(dump_transcript, None);
+        } else {
+            panic!("Expected Run command");
+        }
+
+        let cli = Cli::parse_from(["rove", "run", "--no-daemon", "echo hi"]);
+        assert!(matches!(
+            cli.command,
+            Command::Run {
+                no_daemon: true,
+                ..
+            }
+        ));
+
+        let cli = Cli::parse_from(["rove", "run", "--max-file-size", "4000000", "read app.log"]);
+        assert!(matches!(
+            cli.command,
+            Command::Run {
+                max_file_size: Some(4_000_000),
+                ..
+            }
+        ));
+        // The override can't lower the limit or pass the hard ceiling
+        for size in ["1000", "6000000", "-1"] {
+            let args = ["rove", "run", "--max-file-size", size, "read app.log"];
+            assert!(Cli::try_parse_from(args).is_err(), "{}", size);
+        }
+
+        let cli = Cli::parse_from(["rove", "run", "--timeout", "10m", "build it"]);
+        if let Command::Run { timeout, .. } = cli.command {
+            assert_eq!(timeout, Some(std::time::Duration::from_secs(600)));
         } else {
             panic!("Expected Run command");
         }
+        assert_eq!(parse_timeout("90"), Ok(std::time::Duration::from_secs(90)));
+        assert_eq!(
+            parse_timeout("1h"),
+            Ok(std::time::Duration::from_secs(3600))
+        );
+        assert!(parse_timeout("0s").is_err());
+        assert!(parse_timeout("soon").is_err());
+    }
+
+    #[test]
+    fn test_run_resume() {
+        let cli = Cli::parse_from(["rove", "run", "--resume", "task-123"]);
+        if let Command::Run { task, resume, .. } = cli.command {
+            assert_eq!(task, None);
+            assert_eq!(resume.as_deref(), Some("task-123"));
+        } else {
+            panic!("Expected Run command");
+        }
+
+        // Either a task or --resume, not both
+        assert!(Cli::try_parse_from(["rove", "run"]).is_err());
+        assert!(Cli::try_parse_from(["rove", "run", "echo hi", "--resume", "task-123"]).is_err());
+    }
+
+    #[test]
+    fn test_export_prompt_command() {
+        let cli = Cli::parse_from(["rove", "export-prompt"]);
+        if let Command::ExportPrompt { task, skills } = cli.command {
+            assert_eq!(task, None);
+            assert!(skills.is_empty());
+        } else {
+            panic!("Expected ExportPrompt command");
+        }
+
+        let cli = Cli::parse_from([
+            "rove",
+            "export-prompt",
+            "refactor the parser",
+            "--skill",
+            "careful",
+            "--skill",
+            "rust",
+        ]);
+        if let Command::ExportPrompt { task, skills } = cli.command {
+            assert_eq!(task.as_deref(), Some("refactor the parser"));
+            assert_eq!(skills, vec!["careful", "rust"]);
+        } else {
+            panic!("Expected ExportPrompt command");
+        }
     }
 
     #[test]
@@ -298,15 +627,137 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_cost_command_since() {
+        let cli = Cli::parse_from(["rove", "cost", "--since", "2024-01-31"]);
+        if let Command::Cost { since } = cli.command {
+            assert_eq!(since, Some(1706659200));
+        } else {
+            panic!("Expected Cost command");
+        }
+
+        let cli = Cli::parse_from(["rove", "cost"]);
+        assert!(matches!(cli.command, Command::Cost { since: None }));
+
+        let week_ago = parse_since("7d").unwrap();
+        let expected = chrono::Utc::now().timestamp() - 7 * 24 * 60 * 60;
+        assert!((week_ago - expected).abs() <= 1);
+
+        assert!(parse_since("7").is_err());
+        assert!(parse_since("soon").is_err());
+        assert!(parse_since("-7d").is_err());
+        assert!(parse_since(&format!("{}w", i64::MAX)).is_err());
+        assert!(Cli::try_parse_from(["rove", "cost", "--since", "yesterday"]).is_err());
+    }
+
+    #[test]
+    fn test_audit_commands() {
+        let cli = Cli::parse_from(["rove", "audit", "export", "-o", "audit.json"]);
+        if let Command::Audit {
+            action: AuditAction::Export { output },
+        } = cli.command
+        {
+            assert_eq!(output, Some(PathBuf::from("audit.json")));
+        } else {
+            panic!("Expected Audit export command");
+        }
+
+        let cli = Cli::parse_from(["rove", "audit", "verify", "audit.json"]);
+        assert!(matches!(
+            cli.command,
+            Command::Audit {
+                action: AuditAction::Verify { .. }
+            }
+        ));
+    }
+
+    #[test]
+    fn test_db_prune_command() {
+        let cli = Cli::parse_from(["rove", "db", "prune"]);
+        assert!(matches!(
+            cli.command,
+            Command::Db {
+                action: DbAction::Prune
+            }
+        ));
+    }
+
     #[test]
     fn test_plugins_list() {
         // Test plugins list subcommand
         let cli = Cli::parse_from(["rove", "plugins", "list"]);
         if let Command::Plugins { action } = cli.command {
-            assert!(matches!(action, PluginAction::List));
+            assert!(matches!(
+                action,
+                PluginAction::List {
+                    check_updates: false
+                }
+            ));
         } else {
             panic!("Expected Plugins command");
         }
+
+        let cli = Cli::parse_from(["rove", "plugins", "list", "--check-updates"]);
+        assert!(matches!(
+            cli.command,
+            Command::Plugins {
+                action: PluginAction::List {
+                    check_updates: true
+                }
+            }
+        ));
+    }
+
+    #[test]
+    fn test_plugins_search() {
+        let cli = Cli::parse_from(["rove", "plugins", "search", "git"]);
+        if let Command::Plugins {
+            action: PluginAction::Search { query },
+        } = cli.command
+        {
+            assert_eq!(query, "git");
+        } else {
+            panic!("Expected Plugins search command");
+        }
+    }
+
+    #[test]
+    fn test_plugins_update() {
+        let cli = Cli::parse_from(["rove", "plugins", "update", "git", "--yes"]);
+        if let Command::Plugins {
+            action: PluginAction::Update { name, yes },
+        } = cli.command
+        {
+            assert_eq!(name, "git");
+            assert!(yes);
+        } else {
+            panic!("Expected Plugins update command");
+        }
+    }
+
+    #[test]
+    fn test_plugins_bulk_toggle() {
+        let cli = Cli::parse_from(["rove", "plugins", "enable", "fs-editor,git"]);
+        if let Command::Plugins {
+            action: PluginAction::Enable { names, all },
+        } = cli.command
+        {
+            assert_eq!(names, vec!["fs-editor", "git"]);
+            assert!(!all);
+        } else {
+            panic!("Expected Plugins enable command");
+        }
+
+        let cli = Cli::parse_from(["rove", "plugins", "disable", "--all"]);
+        assert!(matches!(
+            cli.command,
+            Command::Plugins {
+                action: PluginAction::Disable { ref names, all: true }
+            } if names.is_empty()
+        ));
+
+        assert!(Cli::try_parse_from(["rove", "plugins", "enable"]).is_err());
+        assert!(Cli::try_parse_from(["rove", "plugins", "enable", "git", "--all"]).is_err());
     }
 
     #[test]
@@ -324,6 +775,25 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_config_dump() {
+        let cli = Cli::parse_from(["rove", "config", "dump", "--effective"]);
+        assert!(matches!(
+            cli.command,
+            Command::Config {
+                action: ConfigAction::Dump { effective: true }
+            }
+        ));
+
+        let cli = Cli::parse_from(["rove", "config", "dump"]);
+        assert!(matches!(
+            cli.command,
+            Command::Config {
+                action: ConfigAction::Dump { effective: false }
+            }
+        ));
+    }
+
     #[test]
     fn test_bot_add_user() {
         // Test bot add user subcommand
@@ -370,4 +840,31 @@ mod tests {
             panic!("Expected Skill command");
         }
     }
+
+    #[test]
+    fn test_skill_priority() {
+        let cli = Cli::parse_from(["rove", "skill", "priority", "review", "75"]);
+        if let Command::Skill { action } = cli.command {
+            assert!(matches!(
+                action,
+                SkillAction::Priority { ref name, value: 75 } if name == "review"
+            ));
+        } else {
+            panic!("Expected Skill command");
+        }
+    }
+
+    #[test]
+    fn test_workspace_use() {
+        let cli = Cli::parse_from(["rove", "workspace", "use", "backend"]);
+        if let Command::Workspace { action } = cli.command {
+            if let WorkspaceAction::Use { name } = action {
+                assert_eq!(name, "backend");
+            } else {
+                panic!("Expected WorkspaceAction::Use");
+            }
+        } else {
+            panic!("Expected Workspace command");
+        }
+    }
 }
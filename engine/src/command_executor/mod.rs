@@ -1,6 +1,59 @@
+use crate::fs_guard::FileSystemGuard;
+use crate::message_bus::OutputStream;
 use std::collections::HashSet;
-use std::process::{Command, Output, Stdio};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Output, Stdio};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
 use thiserror::Error;
+use tracing::{debug, info, warn};
+
+/// Commands that can never be added through configuration.
+///
+/// Shells, script interpreters, privilege escalation tools and commands that
+/// run another command would turn any allowlisted invocation into arbitrary
+/// command execution.
+const NEVER_ALLOWED: &[&str] = &[
+    "sh", "bash", "zsh", "fish", "dash", "ksh", "csh", "tcsh", "pwsh", "cmd", "python", "python2",
+    "python3", "perl", "ruby", "node", "nodejs", "deno", "bun", "php", "lua", "tclsh", "sudo",
+    "su", "doas", "env", "xargs", "nohup", "nice", "timeout", "watch", "exec",
+];
+
+/// How long `CommandExecutor::execute` lets a command run before killing it
+pub const DEFAULT_COMMAND_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Variables of the daemon's environment passed on by `execute_in`
+pub const SAFE_ENV_VARS: &[&str] = &["PATH", "HOME"];
+
+/// Whether a command's environment may not set `name`: these change which
+/// program runs or what it loads, bypassing the allowlist
+pub fn is_protected_env_var(name: &str) -> bool {
+    name == "PATH" || name.starts_with("LD_") || name.starts_with("DYLD_")
+}
+
+/// How often a running command is checked for exit
+const WAIT_POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// How long output is still read once a command's process group is killed
+const PIPE_CLOSE_GRACE: Duration = Duration::from_secs(1);
+
+/// Receives each line a command writes, as soon as it is read
+pub type OutputSink = Arc<dyn Fn(OutputStream, &[u8]) + Send + Sync>;
+
+/// Optional inputs of `CommandExecutor::execute_in_with`
+#[derive(Clone, Default)]
+pub struct ExecOptions {
+    /// Variables set on top of `SAFE_ENV_VARS`
+    pub env: Vec<(String, String)>,
+    /// Written to the command's stdin, which is null otherwise
+    pub stdin: Option<String>,
+    /// How long the command may run; `DEFAULT_COMMAND_TIMEOUT` if unset
+    pub timeout: Option<Duration>,
+    /// Called with every line of stdout and stderr while the command runs
+    pub on_output: Option<OutputSink>,
+}
 
 /// CommandExecutor provides secure command execution with allowlist validation
 /// and shell injection prevention.
@@ -17,6 +70,8 @@ pub struct CommandExecutor {
     /// Maps command name -> absolute path (e.g. "git" -> "/usr/bin/git")
     allowlist: HashSet<String>,
     resolved: std::collections::HashMap<String, String>,
+    /// Directory `execute_in` may run commands in (and below)
+    workspace: Option<PathBuf>,
 }
 
 #[derive(Debug, Error)]
@@ -35,6 +90,17 @@ pub enum CommandError {
 
     #[error("Command execution failed: {0}")]
     ExecutionFailed(#[from] std::io::Error),
+
+    #[error("Invalid working directory {0}: {1}")]
+    InvalidWorkingDirectory(PathBuf, String),
+
+    #[error("Environment variable {0} may not be set")]
+    ProtectedEnvVar(String),
+
+    /// The command was killed after running for `timeout`; `output` holds
+    /// what it wrote until then
+    #[error("Command timed out after {} seconds", .timeout.as_secs())]
+    Timeout { timeout: Duration, output: Output },
 }
 
 /// Resolve a command name to its absolute path using `which`
@@ -66,14 +132,10 @@ impl CommandExecutor {
     pub fn new() -> Self {
         let safe_commands = [
             // Version control
-            "git",
-            // File reading (no write capability)
-            "ls", "cat", "grep", "head", "tail", "wc",
-            // Text processing (read-only)
-            "cut", "sort", "uniq", "diff",
-            // System info (read-only)
-            "ps", "df", "du", "uname",
-            // Build tools (scoped)
+            "git", // File reading (no write capability)
+            "ls", "cat", "grep", "head", "tail", "wc", // Text processing (read-only)
+            "cut", "sort", "uniq", "diff", // System info (read-only)
+            "ps", "df", "du", "uname", // Build tools (scoped)
             "cargo", "npm", "yarn", "rustc",
         ];
 
@@ -87,7 +149,61 @@ impl CommandExecutor {
             }
         }
 
-        Self { allowlist, resolved }
+        Self {
+            allowlist,
+            resolved,
+            workspace: None,
+        }
+    }
+
+    /// Creates a CommandExecutor with the default allowlist plus extra commands.
+    ///
+    /// Used for `[security.commands] extra_allowed` in the config. Each extra
+    /// command is pinned to its absolute path; commands that cannot be resolved
+    /// are skipped with a warning. Shells, interpreters and privilege escalation
+    /// tools (see `NEVER_ALLOWED`) and anything that is not a plain command
+    /// name are refused.
+    pub fn with_extra_allowed(extra: &[String]) -> Self {
+        let mut executor = Self::new();
+
+        for cmd in extra {
+            let cmd = cmd.trim();
+            if cmd.is_empty() || cmd.contains('/') || cmd.contains('\\') {
+                warn!(
+                    "Ignoring extra allowed command '{}': must be a plain command name",
+                    cmd
+                );
+                continue;
+            }
+            if NEVER_ALLOWED.contains(&cmd) {
+                warn!(
+                    "Refusing extra allowed command '{}': shells, interpreters and privilege escalation tools cannot be allowlisted",
+                    cmd
+                );
+                continue;
+            }
+
+            match resolve_path(cmd) {
+                Some(abs_path) => {
+                    info!("Allowing extra command '{}' ({})", cmd, abs_path);
+                    executor.resolved.insert(cmd.to_string(), abs_path);
+                    executor.allowlist.insert(cmd.to_string());
+                }
+                None => {
+                    warn!(
+                        "Ignoring extra allowed command '{}': not found on PATH",
+                        cmd
+                    );
+                }
+            }
+        }
+
+        executor
+    }
+
+    /// Returns true if the command is in the allowlist.
+    pub fn is_allowed(&self, command: &str) -> bool {
+        self.allowlist.contains(command)
     }
 
     /// Creates a CommandExecutor with a custom allowlist.
@@ -101,9 +217,16 @@ impl CommandExecutor {
         Self {
             allowlist: commands.into_iter().collect(),
             resolved,
+            workspace: None,
         }
     }
 
+    /// Let `execute_in` run commands in `workspace` and its subdirectories
+    pub fn with_workspace(mut self, workspace: PathBuf) -> Self {
+        self.workspace = Some(workspace);
+        self
+    }
+
     /// Adds a command to the allowlist.
     pub fn allow_command(&mut self, command: String) {
         if let Some(abs_path) = resolve_path(&command) {
@@ -174,40 +297,228 @@ impl CommandExecutor {
     /// - Requirement 8.1: Uses execve-style command execution
     /// - Requirement 8.4: Validates commands against allowlist
     /// - Requirement 8.5: Sets stdin to null, stdout/stderr to piped
+    ///
+    /// Waits at most `DEFAULT_COMMAND_TIMEOUT`; see `execute_with_timeout`.
     pub fn execute(&self, command: &str, args: &[String]) -> Result<Output, CommandError> {
-        // Gate 1: Validate command is in allowlist
-        if !self.allowlist.contains(command) {
-            return Err(CommandError::CommandNotAllowed(command.to_string()));
-        }
+        self.execute_with_timeout(command, args, DEFAULT_COMMAND_TIMEOUT)
+    }
 
-        // Gate 2: Reject shell invocation patterns
-        if command == "sh" || command == "bash" || command == "zsh" || command == "fish" {
-            return Err(CommandError::ShellInjectionAttempt);
-        }
+    /// Executes a command with security validation, killing it after `timeout`.
+    ///
+    /// Runs the same gates as `execute`. On Unix the child gets its own
+    /// process group, so on expiry the whole tree it spawned is killed, not
+    /// just the child. A command that exits while processes it started
+    /// still hold its output open is treated the same way once `timeout`
+    /// passes. Output is read while the command runs; whatever it wrote
+    /// before being killed is returned in `CommandError::Timeout`.
+    pub fn execute_with_timeout(
+        &self,
+        command: &str,
+        args: &[String],
+        timeout: Duration,
+    ) -> Result<Output, CommandError> {
+        self.validate(command, args)?;
+        self.run(command, self.command(command, args), timeout, None, None)
+    }
 
-        // Gate 3: Check for shell metacharacters in arguments
-        for arg in args {
-            if self.has_shell_metacharacters(arg) {
-                return Err(CommandError::ShellMetacharactersDetected(arg.clone()));
-            }
+    /// Executes a command in `cwd` with a controlled environment.
+    ///
+    /// Runs the same gates as `execute`, then checks that `cwd` is a
+    /// directory inside the workspace (see `with_workspace`; without one
+    /// every `cwd` is refused). The child does not inherit the daemon's
+    /// environment, which may hold secrets: it gets only `SAFE_ENV_VARS`
+    /// plus `env`, which may not set a protected variable such as `PATH`
+    /// or `LD_PRELOAD`.
+    ///
+    /// Waits at most `DEFAULT_COMMAND_TIMEOUT`.
+    pub fn execute_in(
+        &self,
+        command: &str,
+        args: &[String],
+        cwd: &Path,
+        env: &[(String, String)],
+    ) -> Result<Output, CommandError> {
+        let options = ExecOptions {
+            env: env.to_vec(),
+            ..ExecOptions::default()
+        };
+        self.execute_in_with(command, args, cwd, options)
+    }
+
+    /// Executes a command like `execute_in`, with stdin, a timeout and
+    /// streamed output as given by `options`
+    pub fn execute_in_with(
+        &self,
+        command: &str,
+        args: &[String],
+        cwd: &Path,
+        options: ExecOptions,
+    ) -> Result<Output, CommandError> {
+        let ExecOptions {
+            env,
+            stdin,
+            timeout,
+            on_output,
+        } = options;
+        self.validate(command, args)?;
+        if let Some((name, _)) = env.iter().find(|(name, _)| is_protected_env_var(name)) {
+            return Err(CommandError::ProtectedEnvVar(name.clone()));
         }
+        let cwd = self.resolve_cwd(cwd)?;
+
+        let mut cmd = self.command(command, args);
+        cmd.current_dir(&cwd)
+            .env_clear()
+            .envs(
+                SAFE_ENV_VARS
+                    .iter()
+                    .filter_map(|name| std::env::var_os(name).map(|value| (*name, value))),
+            )
+            .envs(env.iter().map(|(name, value)| (name, value)));
+        if stdin.is_some() {
+            cmd.stdin(Stdio::piped());
+        }
+        let timeout = timeout.unwrap_or(DEFAULT_COMMAND_TIMEOUT);
+        self.run(command, cmd, timeout, stdin, on_output)
+    }
 
-        // Gate 4: Reject dangerous piping patterns
-        let full_command = format!("{} {}", command, args.join(" "));
-        if self.has_dangerous_pipe(&full_command) {
-            return Err(CommandError::DangerousPipeDetected);
+    /// Canonical `cwd`, if it is a directory inside the workspace
+    fn resolve_cwd(&self, cwd: &Path) -> Result<PathBuf, CommandError> {
+        let invalid = |reason: String| CommandError::InvalidWorkingDirectory(cwd.into(), reason);
+        let workspace = self
+            .workspace
+            .as_ref()
+            .ok_or_else(|| invalid("no workspace configured".to_string()))?
+            .canonicalize()
+            .map_err(|e| invalid(format!("failed to resolve workspace: {}", e)))?;
+        let dir = FileSystemGuard::new(workspace.clone())
+            .validate_path(&workspace.join(cwd))
+            .map_err(|e| invalid(e.to_string()))?;
+        if !dir.is_dir() {
+            return Err(invalid("not a directory".to_string()));
         }
+        Ok(dir)
+    }
 
-        // Execute with execve-style (no shell)
-        // Uses absolute path to prevent PATH hijacking
-        let abs = self.abs_path(command);
-        let output = Command::new(&abs)
-            .args(args)
+    /// The validated command, ready to spawn
+    ///
+    /// Execve-style (no shell), using the absolute path to prevent PATH
+    /// hijacking.
+    fn command(&self, command: &str, args: &[String]) -> Command {
+        let mut cmd = Command::new(self.abs_path(command));
+        cmd.args(args)
             .stdin(Stdio::null())
             .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .output()?;
+            .stderr(Stdio::piped());
+        cmd
+    }
+
+    /// Spawn `cmd` and wait for it, killing it after `timeout`
+    ///
+    /// `stdin` is written to the child's stdin pipe, which is then closed;
+    /// every line of output is passed to `on_output` as it arrives.
+    fn run(
+        &self,
+        command: &str,
+        mut cmd: Command,
+        timeout: Duration,
+        stdin: Option<String>,
+        on_output: Option<OutputSink>,
+    ) -> Result<Output, CommandError> {
+        #[cfg(unix)]
+        {
+            use std::os::unix::process::CommandExt;
+            cmd.process_group(0);
+        }
+        let mut child = cmd.spawn()?;
+
+        // Feed stdin from its own thread so a command that writes before
+        // it has read everything can't deadlock us. Dropping the pipe
+        // closes it.
+        let writer = match (stdin, child.stdin.take()) {
+            (Some(input), Some(mut pipe)) => {
+                Some(std::thread::spawn(move || pipe.write_all(input.as_bytes())))
+            }
+            _ => None,
+        };
+
+        // Drain both pipes so a chatty child can't block on a full pipe
+        let stdout = child
+            .stdout
+            .take()
+            .map(|pipe| read_lines(pipe, OutputStream::Stdout, on_output.clone()));
+        let stderr = child
+            .stderr
+            .take()
+            .map(|pipe| read_lines(pipe, OutputStream::Stderr, on_output.clone()));
+
+        let deadline = Instant::now() + timeout;
+        let mut timed_out = false;
+        let status = loop {
+            let exited = match child.try_wait() {
+                Ok(exited) => exited,
+                Err(e) => {
+                    // Don't leave a command we can no longer wait on running
+                    kill_tree(&mut child);
+                    let _ = child.wait();
+                    return Err(e.into());
+                }
+            };
+            if let Some(status) = exited {
+                break status;
+            }
+            if Instant::now() >= deadline {
+                warn!(
+                    "Command '{}' timed out after {}s, killing it",
+                    command,
+                    timeout.as_secs()
+                );
+                timed_out = true;
+                kill_tree(&mut child);
+                break child.wait()?;
+            }
+            std::thread::sleep(WAIT_POLL_INTERVAL);
+        };
+
+        // A background process the command started (a server, a daemon)
+        // inherits its pipes and keeps them open after it exits. It gets
+        // the rest of the timeout, then is killed with the group.
+        let pipes_closed = || {
+            stdout.iter().chain(&stderr).all(Capture::is_done)
+                && writer.as_ref().map_or(true, JoinHandle::is_finished)
+        };
+        if !timed_out && !wait_until(deadline, pipes_closed) {
+            warn!(
+                "Command '{}' left processes holding its output open after {}s, killing them",
+                command,
+                timeout.as_secs()
+            );
+            timed_out = true;
+            kill_tree(&mut child);
+        }
+        // A process that left the group can hold the pipes forever; keep
+        // what was read so far rather than waiting for it
+        if !wait_until(Instant::now() + PIPE_CLOSE_GRACE, pipes_closed) {
+            warn!(
+                "Output of '{}' is still open, not reading the rest",
+                command
+            );
+        }
 
+        let output = Output {
+            status,
+            stdout: stdout.map(Capture::take).unwrap_or_default(),
+            stderr: stderr.map(Capture::take).unwrap_or_default(),
+        };
+        if let Some(writer) = writer.filter(JoinHandle::is_finished) {
+            // The command may exit without reading all of stdin
+            if let Ok(Err(e)) = writer.join() {
+                debug!("Failed to write stdin of '{}': {}", command, e);
+            }
+        }
+        if timed_out {
+            return Err(CommandError::Timeout { timeout, output });
+        }
         Ok(output)
     }
 
@@ -247,6 +558,84 @@ impl CommandExecutor {
     }
 }
 
+/// Output of a pipe, collected by a `read_lines` thread
+struct Capture {
+    output: Arc<Mutex<Vec<u8>>>,
+    reader: JoinHandle<()>,
+}
+
+impl Capture {
+    /// Whether the pipe reached EOF (or failed)
+    fn is_done(&self) -> bool {
+        self.reader.is_finished()
+    }
+
+    /// What was read so far, without waiting for the pipe to close
+    fn take(self) -> Vec<u8> {
+        let mut output = self.output.lock().unwrap_or_else(|e| e.into_inner());
+        std::mem::take(&mut *output)
+    }
+}
+
+/// Read `pipe` to the end on a separate thread, passing each line to
+/// `on_output` as it arrives
+fn read_lines<R: Read + Send + 'static>(
+    pipe: R,
+    stream: OutputStream,
+    on_output: Option<OutputSink>,
+) -> Capture {
+    let output = Arc::new(Mutex::new(Vec::new()));
+    let captured = output.clone();
+    let reader = std::thread::spawn(move || {
+        let mut reader = BufReader::new(pipe);
+        let mut line = Vec::new();
+        loop {
+            line.clear();
+            match reader.read_until(b'\n', &mut line) {
+                // A read error ends the capture; what was read so far is kept
+                Ok(0) | Err(_) => break,
+                Ok(_) => {
+                    if let Some(ref on_output) = on_output {
+                        on_output(stream, &line);
+                    }
+                    captured
+                        .lock()
+                        .unwrap_or_else(|e| e.into_inner())
+                        .extend_from_slice(&line);
+                }
+            }
+        }
+    });
+    Capture { output, reader }
+}
+
+/// Poll `done` until it holds or `deadline` passes; whether it held
+fn wait_until(deadline: Instant, done: impl Fn() -> bool) -> bool {
+    loop {
+        if done() {
+            return true;
+        }
+        if Instant::now() >= deadline {
+            return false;
+        }
+        std::thread::sleep(WAIT_POLL_INTERVAL);
+    }
+}
+
+/// Kill `child` and, on Unix, every process in its process group
+fn kill_tree(child: &mut Child) {
+    #[cfg(unix)]
+    {
+        use nix::sys::signal::{killpg, Signal};
+        use nix::unistd::Pid;
+        if let Err(e) = killpg(Pid::from_raw(child.id() as i32), Signal::SIGKILL) {
+            warn!("Failed to kill process group {}: {}", child.id(), e);
+        }
+    }
+    // Also covers platforms without process groups
+    let _ = child.kill();
+}
+
 impl Default for CommandExecutor {
     fn default() -> Self {
         Self::new()
@@ -330,11 +719,7 @@ mod tests {
         // Test pipe character in arguments (should be caught by metacharacter check)
         let result = executor.execute(
             "ls",
-            &[
-                "/tmp".to_string(),
-                "|".to_string(),
-                "bash".to_string(),
-            ],
+            &["/tmp".to_string(), "|".to_string(), "bash".to_string()],
         );
         // This will be caught by shell metacharacter detection
         assert!(matches!(
@@ -361,6 +746,50 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_extra_allowed_command_becomes_allowed() {
+        assert!(!CommandExecutor::new().is_allowed("date"));
+
+        let executor = CommandExecutor::with_extra_allowed(&["date".to_string()]);
+        assert!(executor.is_allowed("date"));
+        let result = executor.execute("date", &[]);
+        assert!(result.is_ok());
+
+        // Defaults are kept
+        assert!(executor.is_allowed("git"));
+    }
+
+    #[test]
+    fn test_extra_allowed_refuses_shells() {
+        let executor = CommandExecutor::with_extra_allowed(&[
+            "bash".to_string(),
+            "sh".to_string(),
+            "sudo".to_string(),
+            "/bin/bash".to_string(),
+            "python3".to_string(),
+            "node".to_string(),
+            "xargs".to_string(),
+        ]);
+
+        assert!(!executor.is_allowed("bash"));
+        assert!(!executor.is_allowed("sh"));
+        assert!(!executor.is_allowed("sudo"));
+        assert!(!executor.is_allowed("/bin/bash"));
+        assert!(!executor.is_allowed("python3"));
+        assert!(!executor.is_allowed("node"));
+        assert!(!executor.is_allowed("xargs"));
+
+        let result = executor.execute("bash", &["-c".to_string(), "id".to_string()]);
+        assert!(matches!(result, Err(CommandError::CommandNotAllowed(_))));
+    }
+
+    #[test]
+    fn test_extra_allowed_skips_unresolvable() {
+        let executor =
+            CommandExecutor::with_extra_allowed(&["rove-no-such-command-xyz".to_string()]);
+        assert!(!executor.is_allowed("rove-no-such-command-xyz"));
+    }
+
     #[test]
     fn test_stdin_null_stdout_stderr_piped() {
         let executor = CommandExecutor::new();
@@ -374,4 +803,139 @@ mod tests {
         let stdout = String::from_utf8_lossy(&output.stdout);
         assert!(!stdout.is_empty());
     }
+
+    #[test]
+    fn test_timeout_kills_command_and_keeps_partial_output() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let log = dir.path().join("build.log");
+        std::fs::write(&log, "partial output\n").unwrap();
+        let executor = CommandExecutor::new();
+
+        // `tail -f` prints the file, then waits for more forever
+        let started = Instant::now();
+        let result = executor.execute_with_timeout(
+            "tail",
+            &["-f".to_string(), log.display().to_string()],
+            Duration::from_millis(500),
+        );
+
+        assert!(started.elapsed() < Duration::from_secs(10));
+        match result {
+            Err(CommandError::Timeout { timeout, output }) => {
+                assert_eq!(timeout, Duration::from_millis(500));
+                assert!(!output.status.success());
+                assert_eq!(String::from_utf8_lossy(&output.stdout), "partial output\n");
+            }
+            other => panic!("expected a timeout, got {:?}", other),
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_background_process_holding_output_is_killed_at_timeout() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let script = dir.path().join("serve");
+        let marker = dir.path().join("still-running");
+        std::fs::write(
+            &script,
+            format!(
+                "#!/bin/sh\n(sleep 2; touch {}) &\necho started\n",
+                marker.display()
+            ),
+        )
+        .unwrap();
+        std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+        let command = script.display().to_string();
+        let executor = CommandExecutor::with_allowlist(vec![command.clone()]);
+
+        // The script exits at once, but the subshell keeps its stdout open
+        let started = Instant::now();
+        let result = executor.execute_with_timeout(&command, &[], Duration::from_millis(500));
+
+        assert!(started.elapsed() < Duration::from_secs(2));
+        match result {
+            Err(CommandError::Timeout { output, .. }) => {
+                assert_eq!(String::from_utf8_lossy(&output.stdout), "started\n");
+            }
+            other => panic!("expected a timeout, got {:?}", other),
+        }
+        // Killed with the group, the background process never gets there
+        std::thread::sleep(Duration::from_secs(3));
+        assert!(!marker.exists());
+    }
+
+    #[test]
+    fn test_command_finishing_in_time_is_not_killed() {
+        let executor = CommandExecutor::new();
+        let output = executor
+            .execute_with_timeout("uname", &[], Duration::from_secs(30))
+            .unwrap();
+
+        assert!(output.status.success());
+        assert!(!output.stdout.is_empty());
+    }
+
+    #[test]
+    fn test_execute_in_sets_cwd_and_clears_env() {
+        let dir = tempfile::TempDir::new().unwrap();
+        std::fs::create_dir(dir.path().join("crate")).unwrap();
+        std::fs::write(dir.path().join("crate/Cargo.toml"), "").unwrap();
+        let executor =
+            CommandExecutor::with_allowlist(vec!["ls".to_string(), "printenv".to_string()])
+                .with_workspace(dir.path().to_path_buf());
+
+        let output = executor
+            .execute_in("ls", &[], Path::new("crate"), &[])
+            .unwrap();
+        assert_eq!(String::from_utf8_lossy(&output.stdout), "Cargo.toml\n");
+
+        std::env::set_var("ROVE_TEST_DAEMON_SECRET", "hunter2");
+        let env = [("CARGO_TERM_COLOR".to_string(), "never".to_string())];
+        let output = executor
+            .execute_in("printenv", &[], dir.path(), &env)
+            .unwrap();
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(stdout.contains("CARGO_TERM_COLOR=never"));
+        for line in stdout.lines() {
+            let name = line.split_once('=').map_or(line, |(name, _)| name);
+            assert!(
+                name == "CARGO_TERM_COLOR" || SAFE_ENV_VARS.contains(&name),
+                "inherited {}",
+                name
+            );
+        }
+    }
+
+    #[test]
+    fn test_execute_in_rejects_cwd_outside_workspace_and_protected_env() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let executor = CommandExecutor::with_allowlist(vec!["ls".to_string()])
+            .with_workspace(dir.path().to_path_buf());
+
+        for cwd in ["..", "/tmp", "missing"] {
+            let result = executor.execute_in("ls", &[], Path::new(cwd), &[]);
+            assert!(
+                matches!(result, Err(CommandError::InvalidWorkingDirectory(..))),
+                "{}: {:?}",
+                cwd,
+                result
+            );
+        }
+
+        let env = [("LD_PRELOAD".to_string(), "/tmp/evil.so".to_string())];
+        let result = executor.execute_in("ls", &[], dir.path(), &env);
+        assert!(
+            matches!(result, Err(CommandError::ProtectedEnvVar(ref name)) if name == "LD_PRELOAD")
+        );
+
+        // Without a workspace no cwd is accepted
+        let executor = CommandExecutor::with_allowlist(vec!["ls".to_string()]);
+        let result = executor.execute_in("ls", &[], dir.path(), &[]);
+        assert!(matches!(
+            result,
+            Err(CommandError::InvalidWorkingDirectory(..))
+        ));
+    }
 }
@@ -0,0 +1,116 @@
+//! Call-depth limit
+//!
+//! A task can recurse in several ways: the agent calls tools, tools call
+//! plugins, and plugins call other plugins through `call_plugin`. Each of
+//! these enters one level with `nested`, which tracks the depth in a tokio
+//! task-local and fails with `EngineError::CallDepthExceeded` once
+//! `conductor.max_call_depth` would be exceeded. This is a backstop against
+//! runaway recursion; normal tasks stay far below the limit.
+//!
+//! The depth follows the future, not the thread, so it is also seen by host
+//! functions, which run synchronously while the calling plugin is polled.
+//! Those run their nested calls with `block_on`, which unlike
+//! `futures::executor::block_on` may itself be entered from inside a call it
+//! is running.
+
+use sdk::errors::EngineError;
+use std::future::Future;
+use std::sync::Arc;
+use std::task::{Context, Poll, Wake, Waker};
+use std::thread::Thread;
+
+/// Default for `conductor.max_call_depth`
+pub const DEFAULT_MAX_CALL_DEPTH: usize = 8;
+
+tokio::task_local! {
+    static DEPTH: usize;
+}
+
+/// Nesting depth of the code currently running, 0 outside any `nested` call
+pub fn current() -> usize {
+    DEPTH.try_with(|depth| *depth).unwrap_or(0)
+}
+
+/// Run `call` one level deeper than the current depth
+///
+/// # Errors
+///
+/// Returns `EngineError::CallDepthExceeded` without running `call` if the
+/// new depth would be greater than `limit`.
+pub async fn nested<F: Future>(limit: usize, call: F) -> Result<F::Output, EngineError> {
+    let depth = current() + 1;
+    if depth > limit {
+        tracing::warn!("Call depth {} exceeds the limit of {}", depth, limit);
+        return Err(EngineError::CallDepthExceeded { limit });
+    }
+    Ok(DEPTH.scope(depth, call).await)
+}
+
+/// Wakes the thread blocked in `block_on`
+struct ThreadWaker(Thread);
+
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+/// Run `future` to completion on the current thread
+///
+/// Re-entrant: a plugin called through `call_plugin` may call another plugin
+/// the same way while the outer call is still blocked here.
+pub fn block_on<F: Future>(future: F) -> F::Output {
+    let waker = Waker::from(Arc::new(ThreadWaker(std::thread::current())));
+    let mut cx = Context::from_waker(&waker);
+    let mut future = std::pin::pin!(future);
+    loop {
+        match future.as_mut().poll(&mut cx) {
+            Poll::Ready(output) => return output,
+            Poll::Pending => std::thread::park(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_nested_tracks_depth() {
+        assert_eq!(current(), 0);
+        let depths = nested(3, async {
+            let inner = nested(3, async { current() }).await.unwrap();
+            (current(), inner)
+        })
+        .await
+        .unwrap();
+        assert_eq!(depths, (1, 2));
+        assert_eq!(current(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_exceeding_limit_fails() {
+        let result = nested(2, async {
+            nested(2, async { nested(2, async {}).await }).await
+        })
+        .await;
+
+        assert!(matches!(
+            result,
+            Ok(Ok(Err(EngineError::CallDepthExceeded { limit: 2 })))
+        ));
+    }
+
+    #[test]
+    fn test_depth_is_seen_by_blocking_nested_calls() {
+        // Host functions run nested calls to completion on the calling thread
+        let depth = block_on(nested(4, async {
+            block_on(nested(4, async {
+                block_on(nested(4, async { current() })).unwrap()
+            }))
+            .unwrap()
+        }))
+        .unwrap();
+        assert_eq!(depth, 3);
+    }
+}
@@ -113,6 +113,7 @@ mod tests {
                     step_type: StepType::Research,
                     dependencies: vec![],
                     expected_outcome: "done".to_string(),
+                    tool_calls: vec![],
                 },
                 PlanStep {
                     id: "s2".to_string(),
@@ -120,6 +121,7 @@ mod tests {
                     step_type: StepType::Execute,
                     dependencies: vec!["s1".to_string()],
                     expected_outcome: "done".to_string(),
+                    tool_calls: vec![],
                 },
             ],
             created_at: 0,
@@ -133,6 +135,7 @@ mod tests {
             step_type: StepType::Execute,
             dependencies: vec![],
             expected_outcome: "done".to_string(),
+            tool_calls: vec![],
         }
     }
 
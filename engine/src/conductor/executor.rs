@@ -3,6 +3,7 @@
 //! Executes individual `PlanStep`s by dispatching to the appropriate tools
 //! (filesystem, terminal, vision) based on the step type and LLM guidance.
 
+use crate::conductor::call_depth::{self, DEFAULT_MAX_CALL_DEPTH};
 use crate::conductor::types::{PlanStep, StepResult, StepType};
 use crate::llm::router::LLMRouter;
 use crate::llm::{LLMResponse, Message};
@@ -18,6 +19,7 @@ pub struct Executor {
     router: Arc<LLMRouter>,
     fs_tool: Option<Arc<FilesystemTool>>,
     terminal_tool: Option<Arc<TerminalTool>>,
+    max_call_depth: usize,
 }
 
 impl Executor {
@@ -30,9 +32,16 @@ impl Executor {
             router,
             fs_tool,
             terminal_tool,
+            max_call_depth: DEFAULT_MAX_CALL_DEPTH,
         }
     }
 
+    /// Limit how deeply tool calls made by steps may nest
+    pub fn with_max_call_depth(mut self, depth: usize) -> Self {
+        self.max_call_depth = depth;
+        self
+    }
+
     /// Execute a single plan step
     ///
     /// The executor:
@@ -41,6 +50,9 @@ impl Executor {
     /// 3. Feeds results back to the LLM for next action
     /// 4. Repeats until LLM produces a final answer or max iterations hit
     /// 5. Returns a StepResult summarizing what happened
+    ///
+    /// A tool call that would exceed the call-depth limit aborts the step
+    /// with `EngineError::CallDepthExceeded`.
     pub async fn execute_step(&self, step: &PlanStep, context: &str) -> Result<StepResult> {
         let start = Instant::now();
         let mut tools_used = Vec::new();
@@ -82,9 +94,8 @@ impl Executor {
                     info!("Step {} calling tool: {}", step.id, tool_call.name);
                     tools_used.push(tool_call.name.clone());
 
-                    let tool_result = self
-                        .dispatch_tool(&tool_call.name, &tool_call.arguments)
-                        .await;
+                    let dispatch = self.dispatch_tool(&tool_call.name, &tool_call.arguments);
+                    let tool_result = call_depth::nested(self.max_call_depth, dispatch).await?;
 
                     let result_text = match tool_result {
                         Ok(output) => {
@@ -218,6 +229,7 @@ mod tests {
             step_type,
             dependencies: vec![],
             expected_outcome: "done".to_string(),
+            tool_calls: vec![],
         }
     }
 
@@ -235,6 +247,8 @@ mod tests {
             anthropic: Default::default(),
             gemini: Default::default(),
             nvidia_nim: Default::default(),
+            provider_priority: Vec::new(),
+            circuit_breaker: Default::default(),
         });
 
         let router = Arc::new(LLMRouter::new(vec![], config));
@@ -1,7 +1,9 @@
 //! Conductor System
 //!
-//! Orchestrates planning, memory retrieval, and task execution.
+//! Orchestrates planning, memory retrieval, and task execution. Nested
+//! tool and plugin calls are bounded by `call_depth`.
 
+pub mod call_depth;
 pub mod context;
 pub mod evaluator;
 pub mod executor;
@@ -10,10 +12,11 @@ pub mod planner;
 pub mod project;
 pub mod types;
 
+pub use call_depth::DEFAULT_MAX_CALL_DEPTH;
 pub use context::ContextAssembler;
 pub use evaluator::Evaluator;
 pub use executor::Executor;
 pub use memory::SessionMemory;
 pub use planner::Planner;
 pub use project::ProjectMemory;
-pub use types::{ConductorPlan, MemoryBudget, PlanStep, StepResult, StepType};
+pub use types::{ConductorPlan, MemoryBudget, PlanStep, PlannedToolCall, StepResult, StepType};
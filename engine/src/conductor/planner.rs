@@ -3,8 +3,8 @@
 //! Interacts with the LLM to generate, refine, and orchestrate `ConductorPlan`s
 //! based on user requests and available tools.
 
-use crate::conductor::types::{ConductorPlan, PlanStep, StepType};
-use crate::llm::{LLMProvider, Message};
+use crate::conductor::types::{ConductorPlan, PlanStep, PlannedToolCall, StepType};
+use crate::llm::{LLMProvider, LLMResponse, Message};
 use anyhow::{Context, Result};
 use serde::Deserialize;
 use std::sync::Arc;
@@ -23,6 +23,8 @@ struct RawPlanStep {
     #[serde(default)]
     dependencies: Vec<String>,
     expected_outcome: Option<String>,
+    #[serde(default)]
+    tool_calls: Vec<PlannedToolCall>,
 }
 
 impl Planner {
@@ -32,13 +34,23 @@ impl Planner {
 
     /// Generate an initial plan based on a user goal
     pub async fn generate_plan(&self, goal: &str) -> Result<ConductorPlan> {
+        let response = self.llm.generate(&Self::plan_messages(goal)).await?;
+        Ok(Self::plan_from_response(goal, &response))
+    }
+
+    /// Messages that ask the LLM to break `goal` into plan steps
+    ///
+    /// Exposed so callers that route through `LLMRouter` can run the same
+    /// planning turn as `generate_plan`.
+    pub fn plan_messages(goal: &str) -> Vec<Message> {
         let system_prompt = Message::system(
             "You are the Conductor Planner. Break down the user's goal into discrete steps.\n\
             Output ONLY a JSON array of steps. Each step object must have:\n\
             - \"description\": string describing what to do\n\
             - \"step_type\": one of \"Research\", \"Execute\", or \"Verify\"\n\
             - \"dependencies\": array of step ids this depends on (empty for first step)\n\
-            - \"expected_outcome\": string describing success criteria\n\n\
+            - \"expected_outcome\": string describing success criteria\n\
+            - \"tool_calls\" (optional): tool calls the step will make, each {\"tool\": name, \"arguments\": {...}}\n\n\
             Example output:\n\
             [{\"description\":\"Analyze the codebase\",\"step_type\":\"Research\",\"dependencies\":[],\"expected_outcome\":\"Understanding of code structure\"},\
             {\"description\":\"Implement changes\",\"step_type\":\"Execute\",\"dependencies\":[\"step_1\"],\"expected_outcome\":\"Code modified\"},\
@@ -47,40 +59,46 @@ impl Planner {
         );
         let user_prompt = Message::user(goal);
 
-        let response = self.llm.generate(&[system_prompt, user_prompt]).await?;
+        vec![system_prompt, user_prompt]
+    }
 
-        let content = match &response {
-            crate::llm::LLMResponse::FinalAnswer(a) => &a.content,
-            crate::llm::LLMResponse::ToolCall(_) => {
+    /// Build a plan from the LLM's reply to `plan_messages`
+    ///
+    /// Falls back to `default_plan` when the reply is a tool call or cannot
+    /// be parsed into steps.
+    pub fn plan_from_response(goal: &str, response: &LLMResponse) -> ConductorPlan {
+        let content = match response {
+            LLMResponse::FinalAnswer(a) => &a.content,
+            LLMResponse::ToolCall(_) => {
                 // LLM tried to call a tool instead of planning — fall back to default plan
-                return Ok(self.default_plan(goal));
+                return Self::default_plan(goal);
             }
         };
 
         // Try to parse the LLM response as JSON steps
-        match self.parse_steps(content) {
+        match Self::parse_steps(content) {
             Ok(steps) if !steps.is_empty() => {
                 let now = SystemTime::now()
                     .duration_since(UNIX_EPOCH)
                     .unwrap_or_default()
                     .as_secs() as i64;
 
-                Ok(ConductorPlan {
+                ConductorPlan {
                     id: uuid::Uuid::new_v4().to_string(),
                     original_goal: goal.to_string(),
                     steps,
                     created_at: now,
-                })
+                }
             }
             _ => {
                 tracing::warn!("Failed to parse LLM plan output, using default plan");
-                Ok(self.default_plan(goal))
+                Self::default_plan(goal)
             }
         }
     }
 
     /// Parse LLM output into PlanSteps, handling various JSON formats
-    fn parse_steps(&self, content: &str) -> Result<Vec<PlanStep>> {
+    fn parse_steps(content: &str) -> Result<Vec<PlanStep>> {
         let trimmed = content.trim();
 
         // Try to extract JSON array from the response
@@ -117,6 +135,7 @@ impl Planner {
                     expected_outcome: raw
                         .expected_outcome
                         .unwrap_or_else(|| "Step completed".to_string()),
+                    tool_calls: raw.tool_calls,
                 }
             })
             .collect();
@@ -125,7 +144,7 @@ impl Planner {
     }
 
     /// Generate a default 3-step plan when LLM parsing fails
-    fn default_plan(&self, goal: &str) -> ConductorPlan {
+    fn default_plan(goal: &str) -> ConductorPlan {
         let now = SystemTime::now()
             .duration_since(UNIX_EPOCH)
             .unwrap_or_default()
@@ -141,6 +160,7 @@ impl Planner {
                     step_type: StepType::Research,
                     dependencies: vec![],
                     expected_outcome: "Understanding of required changes".to_string(),
+                    tool_calls: vec![],
                 },
                 PlanStep {
                     id: "step_2".to_string(),
@@ -148,6 +168,7 @@ impl Planner {
                     step_type: StepType::Execute,
                     dependencies: vec!["step_1".to_string()],
                     expected_outcome: "Changes implemented successfully".to_string(),
+                    tool_calls: vec![],
                 },
                 PlanStep {
                     id: "step_3".to_string(),
@@ -155,6 +176,7 @@ impl Planner {
                     step_type: StepType::Verify,
                     dependencies: vec!["step_2".to_string()],
                     expected_outcome: "Tests pass and functionality is confirmed".to_string(),
+                    tool_calls: vec![],
                 },
             ],
             created_at: now,
@@ -168,19 +190,13 @@ mod tests {
 
     #[test]
     fn test_parse_steps_valid_json() {
-        use crate::llm::ollama::OllamaProvider;
-
-        let provider: Box<dyn LLMProvider> =
-            Box::new(OllamaProvider::new("http://localhost:11434", "llama3.1:8b"));
-        let planner = Planner::new(Arc::new(provider));
-
         let json = r#"[
             {"description": "Read the config file", "step_type": "Research", "dependencies": [], "expected_outcome": "Config understood"},
             {"description": "Modify the settings", "step_type": "Execute", "dependencies": ["step_1"], "expected_outcome": "Settings changed"},
             {"description": "Run validation", "step_type": "Verify", "dependencies": ["step_2"], "expected_outcome": "Config valid"}
         ]"#;
 
-        let steps = planner.parse_steps(json).unwrap();
+        let steps = Planner::parse_steps(json).unwrap();
         assert_eq!(steps.len(), 3);
         assert_eq!(steps[0].id, "step_1");
         assert_eq!(steps[0].step_type, StepType::Research);
@@ -191,32 +207,20 @@ mod tests {
 
     #[test]
     fn test_parse_steps_with_markdown_wrapper() {
-        use crate::llm::ollama::OllamaProvider;
-
-        let provider: Box<dyn LLMProvider> =
-            Box::new(OllamaProvider::new("http://localhost:11434", "llama3.1:8b"));
-        let planner = Planner::new(Arc::new(provider));
-
         let json = r#"Here is the plan:
         [{"description": "Do the thing", "step_type": "Execute", "dependencies": [], "expected_outcome": "Done"}]
         Hope this helps!"#;
 
-        let steps = planner.parse_steps(json).unwrap();
+        let steps = Planner::parse_steps(json).unwrap();
         assert_eq!(steps.len(), 1);
         assert_eq!(steps[0].description, "Do the thing");
     }
 
     #[test]
     fn test_parse_steps_missing_optional_fields() {
-        use crate::llm::ollama::OllamaProvider;
-
-        let provider: Box<dyn LLMProvider> =
-            Box::new(OllamaProvider::new("http://localhost:11434", "llama3.1:8b"));
-        let planner = Planner::new(Arc::new(provider));
-
         let json = r#"[{"description": "Minimal step"}]"#;
 
-        let steps = planner.parse_steps(json).unwrap();
+        let steps = Planner::parse_steps(json).unwrap();
         assert_eq!(steps.len(), 1);
         assert_eq!(steps[0].step_type, StepType::Execute); // default
         assert_eq!(steps[0].expected_outcome, "Step completed"); // default
@@ -224,13 +228,7 @@ mod tests {
 
     #[test]
     fn test_default_plan() {
-        use crate::llm::ollama::OllamaProvider;
-
-        let provider: Box<dyn LLMProvider> =
-            Box::new(OllamaProvider::new("http://localhost:11434", "llama3.1:8b"));
-        let planner = Planner::new(Arc::new(provider));
-
-        let plan = planner.default_plan("Fix the bug");
+        let plan = Planner::default_plan("Fix the bug");
         assert_eq!(plan.steps.len(), 3);
         assert!(plan.original_goal.contains("Fix the bug"));
         assert!(plan.created_at > 0);
@@ -48,6 +48,91 @@ pub struct PlanStep {
     pub step_type: StepType,
     pub dependencies: Vec<String>,
     pub expected_outcome: String,
+    /// Tool calls the planner expects the step to make
+    #[serde(default)]
+    pub tool_calls: Vec<PlannedToolCall>,
+}
+
+/// A tool call anticipated by a plan, e.g. for batch approval
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PlannedToolCall {
+    /// Tool name
+    pub tool: String,
+    /// Arguments the tool will be called with
+    #[serde(default)]
+    pub arguments: serde_json::Value,
+}
+
+impl PlannedToolCall {
+    /// Whether a call of `tool` with `arguments` is this planned call
+    ///
+    /// Arguments are compared as JSON values, so key order and formatting
+    /// don't matter. Numbers compare by value (`1` matches `1.0`), and
+    /// arguments given as a JSON-encoded string match the decoded value.
+    pub fn matches(&self, tool: &str, arguments: &serde_json::Value) -> bool {
+        self.tool == tool && same_json(&self.arguments, arguments)
+    }
+}
+
+/// Whether `a` and `b` are the same JSON value, as `PlannedToolCall::matches`
+/// compares them
+fn same_json(a: &serde_json::Value, b: &serde_json::Value) -> bool {
+    use serde_json::Value;
+
+    /// The value a JSON-encoded object or array string stands for
+    fn decoded(value: &Value) -> std::borrow::Cow<'_, Value> {
+        if let Value::String(s) = value {
+            if let Ok(parsed @ (Value::Object(_) | Value::Array(_))) = serde_json::from_str(s) {
+                return std::borrow::Cow::Owned(parsed);
+            }
+        }
+        std::borrow::Cow::Borrowed(value)
+    }
+
+    match (decoded(a).as_ref(), decoded(b).as_ref()) {
+        (Value::Object(a), Value::Object(b)) => {
+            a.len() == b.len()
+                && a.iter()
+                    .all(|(key, value)| b.get(key).is_some_and(|other| same_json(value, other)))
+        }
+        (Value::Array(a), Value::Array(b)) => {
+            a.len() == b.len() && a.iter().zip(b).all(|(x, y)| same_json(x, y))
+        }
+        (Value::Number(a), Value::Number(b)) => a.as_f64() == b.as_f64(),
+        (a, b) => a == b,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_planned_call_matches_equivalent_arguments() {
+        let planned = PlannedToolCall {
+            tool: "run_command".to_string(),
+            arguments: json!({"command": "cargo clean", "timeout": 30}),
+        };
+
+        for arguments in [
+            json!({"timeout": 30, "command": "cargo clean"}),
+            json!({"command": "cargo clean", "timeout": 30.0}),
+            json!(r#"{ "timeout": 30, "command": "cargo clean" }"#),
+        ] {
+            assert!(planned.matches("run_command", &arguments), "{}", arguments);
+        }
+
+        assert!(!planned.matches(
+            "delete_file",
+            &json!({"command": "cargo clean", "timeout": 30})
+        ));
+        assert!(!planned.matches("run_command", &json!({"command": "cargo clean"})));
+        assert!(!planned.matches(
+            "run_command",
+            &json!({"command": "rm -rf /", "timeout": 30})
+        ));
+    }
 }
 
 /// The varying types of work the Conductor orchestrates
@@ -11,6 +11,7 @@
 //! - **plugins**: Plugin enablement flags
 //! - **security**: Risk tier and confirmation settings
 //! - **brains**: Brains configuration (optional)
+//! - **workspaces**: Additional named workspaces (optional)
 //!
 //! # Path Expansion
 //!
@@ -19,6 +20,14 @@
 //! - Canonicalizes paths to resolve symlinks and .. patterns
 //! - Verifies workspace is a directory
 //! - Creates workspace directory if it doesn't exist
+//! - Warns when a workspace is reached through a symlink or is a git submodule
+//!
+//! # Warnings
+//!
+//! Settings that are valid but risky, such as Tier 2 operations without
+//! explicit confirmation, don't fail validation. They are collected as
+//! `ConfigWarning`s, available from `Config::warnings`, and printed when the
+//! CLI starts.
 //!
 //! # Platform-Specific Path Handling
 //!
@@ -47,7 +56,7 @@
 use sdk::errors::EngineError;
 use serde::{Deserialize, Serialize};
 use std::fs;
-use std::path::{Path, PathBuf};
+use std::path::{Component, Path, PathBuf};
 
 /// Main configuration structure
 ///
@@ -85,6 +94,171 @@ pub struct Config {
     /// WebSocket client configuration
     #[serde(default)]
     pub ws_client: WsClientConfig,
+
+    /// Conductor orchestration settings
+    #[serde(default)]
+    pub conductor: ConductorConfig,
+
+    /// Audit-log export settings
+    #[serde(default)]
+    pub audit: AuditConfig,
+
+    /// Data-retention job settings
+    #[serde(default)]
+    pub retention: RetentionConfig,
+
+    /// Log file settings
+    #[serde(default)]
+    pub telemetry: TelemetryConfig,
+
+    /// Additional named workspaces (`[[workspaces]]`)
+    #[serde(default)]
+    pub workspaces: Vec<WorkspaceConfig>,
+
+    /// Name of the active workspace, loaded from the data directory.
+    /// `None` means `core.workspace` is active.
+    #[serde(skip)]
+    active_workspace: Option<String>,
+
+    /// Risky-but-valid settings found by the last validation
+    #[serde(skip)]
+    warnings: Vec<ConfigWarning>,
+}
+
+/// A setting that is allowed but probably not what the user wants
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigWarning {
+    /// Config key the warning is about, e.g. `security.require_explicit_tier2`
+    pub key: String,
+
+    /// What is risky about the setting
+    pub message: String,
+}
+
+impl ConfigWarning {
+    fn new(key: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            key: key.into(),
+            message: message.into(),
+        }
+    }
+}
+
+impl std::fmt::Display for ConfigWarning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.key, self.message)
+    }
+}
+
+/// Where a value of the effective config comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ValueSource {
+    /// Set in the config file (paths as resolved at load time)
+    File,
+    /// Not set in the config file; the built-in default is in use
+    Default,
+    /// Replaced at run time, e.g. `core.workspace` by the workspace picked
+    /// with `rove workspace use`
+    Override,
+}
+
+impl std::fmt::Display for ValueSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ValueSource::File => write!(f, "file"),
+            ValueSource::Default => write!(f, "default"),
+            ValueSource::Override => write!(f, "override"),
+        }
+    }
+}
+
+/// The configuration in use, with the source of every value
+#[derive(Debug, Clone, Serialize)]
+pub struct EffectiveConfig {
+    /// Every setting, after defaults are filled in and paths resolved
+    pub config: toml::Table,
+
+    /// Source of each value, by dotted key (e.g. `llm.default_provider`)
+    pub sources: std::collections::BTreeMap<String, ValueSource>,
+}
+
+impl EffectiveConfig {
+    /// Render as TOML, marking each value that comes from a default or an
+    /// override
+    pub fn to_annotated_toml(&self) -> String {
+        let mut out = String::new();
+        render_table(&mut out, "", &self.config, &self.sources);
+        out
+    }
+}
+
+/// Append the values of `table` (at dotted key `prefix`) and then its
+/// subtables, each under its own header
+fn render_table(
+    out: &mut String,
+    prefix: &str,
+    table: &toml::Table,
+    sources: &std::collections::BTreeMap<String, ValueSource>,
+) {
+    let key_of = |name: &str| {
+        if prefix.is_empty() {
+            name.to_string()
+        } else {
+            format!("{}.{}", prefix, name)
+        }
+    };
+
+    for (name, value) in table.iter().filter(|(_, v)| !v.is_table()) {
+        out.push_str(&format!("{} = {}", name, value));
+        match sources.get(&key_of(name)) {
+            Some(ValueSource::Default) => out.push_str("  # default"),
+            Some(ValueSource::Override) => out.push_str("  # override"),
+            _ => {}
+        }
+        out.push('\n');
+    }
+    for (name, value) in table {
+        if let toml::Value::Table(subtable) = value {
+            let key = key_of(name);
+            if !out.is_empty() {
+                out.push('\n');
+            }
+            out.push_str(&format!("[{}]\n", key));
+            render_table(out, &key, subtable, sources);
+        }
+    }
+}
+
+/// Record the source of every value under `effective`: `File` if `file`
+/// sets it, `Default` otherwise
+fn collect_sources(
+    prefix: &str,
+    effective: &toml::Table,
+    file: Option<&toml::Table>,
+    sources: &mut std::collections::BTreeMap<String, ValueSource>,
+) {
+    for (name, value) in effective {
+        let key = if prefix.is_empty() {
+            name.clone()
+        } else {
+            format!("{}.{}", prefix, name)
+        };
+        let in_file = file.and_then(|file| file.get(name));
+        match value {
+            toml::Value::Table(subtable) => {
+                collect_sources(&key, subtable, in_file.and_then(|v| v.as_table()), sources)
+            }
+            _ => {
+                let source = if in_file.is_some() {
+                    ValueSource::File
+                } else {
+                    ValueSource::Default
+                };
+                sources.insert(key, source);
+            }
+        }
+    }
 }
 
 /// Core engine configuration
@@ -104,6 +278,10 @@ pub struct CoreConfig {
     /// Data directory path (supports ~ expansion)
     #[serde(default = "default_data_dir")]
     pub data_dir: PathBuf,
+
+    /// Keep a watched in-memory index of workspace files for `find_files`
+    #[serde(default)]
+    pub index_workspace: bool,
 }
 
 /// LLM provider configuration
@@ -112,6 +290,15 @@ pub struct LLMConfig {
     /// Default LLM provider (ollama, openai, anthropic, gemini, nvidia_nim)
     pub default_provider: String,
 
+    /// Providers to try in order, e.g. `["anthropic", "openai", "ollama"]`
+    ///
+    /// When set, the router walks this list instead of preferring
+    /// `default_provider`, falling back to the next entry when a provider
+    /// fails or its circuit breaker is open. Unlisted providers are tried
+    /// last.
+    #[serde(default)]
+    pub provider_priority: Vec<String>,
+
     /// Sensitivity threshold for local provider preference (0.0-1.0)
     #[serde(default = "default_sensitivity_threshold")]
     pub sensitivity_threshold: f64,
@@ -139,6 +326,31 @@ pub struct LLMConfig {
     /// NVIDIA NIM provider settings
     #[serde(default)]
     pub nvidia_nim: NvidiaNimConfig,
+
+    /// Circuit breaker for providers that keep failing
+    #[serde(default)]
+    pub circuit_breaker: CircuitBreakerConfig,
+}
+
+/// Per-provider circuit breaker configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CircuitBreakerConfig {
+    /// Consecutive failures after which a provider is skipped
+    #[serde(default = "default_breaker_failure_threshold")]
+    pub failure_threshold: u32,
+
+    /// Seconds a failing provider is skipped before it is tried again
+    #[serde(default = "default_breaker_cooldown_secs")]
+    pub cooldown_secs: u64,
+}
+
+impl Default for CircuitBreakerConfig {
+    fn default() -> Self {
+        Self {
+            failure_threshold: default_breaker_failure_threshold(),
+            cooldown_secs: default_breaker_cooldown_secs(),
+        }
+    }
 }
 
 /// Ollama provider configuration
@@ -239,6 +451,60 @@ pub struct PluginsConfig {
     /// Enable git plugin
     #[serde(default = "default_true")]
     pub git: bool,
+
+    /// Load unsigned plugins from this directory, without a manifest
+    ///
+    /// Each `*.wasm` file is checked against the hash in its `.sha256` file.
+    /// INSECURE, for plugin development only. Ignored by production builds.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dev_dir: Option<PathBuf>,
+
+    /// Maximum number of instances kept per WASM plugin, i.e. how many calls
+    /// to one plugin can run at once
+    #[serde(default = "default_plugin_pool_size")]
+    pub pool_size: usize,
+
+    /// WASM fuel a single plugin call may consume before it is stopped
+    #[serde(default = "default_plugin_fuel_limit")]
+    pub fuel_limit: u64,
+}
+
+/// Ids of the plugins that have an enable flag in `[plugins]`
+pub const TOGGLEABLE_PLUGINS: &[&str] = &["fs-editor", "terminal", "screenshot", "git"];
+
+impl PluginsConfig {
+    /// Enable flags of all toggleable plugins, keyed by plugin id
+    pub fn flags(&self) -> [(&'static str, bool); 4] {
+        [
+            ("fs-editor", self.fs_editor),
+            ("terminal", self.terminal),
+            ("screenshot", self.screenshot),
+            ("git", self.git),
+        ]
+    }
+
+    /// Enable or disable the plugin `id`
+    ///
+    /// # Errors
+    ///
+    /// Returns `EngineError::Config` if `id` is not in `TOGGLEABLE_PLUGINS`.
+    pub fn set_enabled(&mut self, id: &str, enabled: bool) -> Result<(), EngineError> {
+        let flag = match id {
+            "fs-editor" => &mut self.fs_editor,
+            "terminal" => &mut self.terminal,
+            "screenshot" => &mut self.screenshot,
+            "git" => &mut self.git,
+            _ => {
+                return Err(EngineError::Config(format!(
+                    "Unknown plugin '{}'. Known plugins: {}",
+                    id,
+                    TOGGLEABLE_PLUGINS.join(", ")
+                )))
+            }
+        };
+        *flag = enabled;
+        Ok(())
+    }
 }
 
 /// Security configuration
@@ -256,9 +522,78 @@ pub struct SecurityConfig {
     #[serde(default = "default_tier1_delay")]
     pub confirm_tier1_delay: u64,
 
+    /// How the Tier 1 countdown is shown on stderr
+    #[serde(default)]
+    pub countdown_style: CountdownStyle,
+
     /// Require explicit confirmation for Tier 2 operations
     #[serde(default = "default_true")]
     pub require_explicit_tier2: bool,
+
+    /// Generate Tier 2 confirmation summaries with a local provider only,
+    /// even when the task runs on a cloud provider
+    #[serde(default)]
+    pub summarize_tier2_locally: bool,
+
+    /// Command allowlist settings (`[security.commands]`)
+    #[serde(default)]
+    pub commands: CommandsConfig,
+
+    /// Host functions no plugin may call, whatever its permissions
+    /// (e.g. `["exec_git"]`)
+    #[serde(default)]
+    pub disabled_host_functions: Vec<String>,
+
+    /// Reject a signed manifest whose `signed_at` is more than this many
+    /// days old at startup (`None` skips the check, since clocks vary)
+    #[serde(default)]
+    pub manifest_max_age_days: Option<u64>,
+
+    /// Rate limit sources that are never throttled: Telegram chats
+    /// (`telegram:<chat_id>`) and API tokens (`api:<hash>`). Tasks run with
+    /// `rove run` are limited per task, so they never match.
+    #[serde(default)]
+    pub rate_limit_bypass: Vec<String>,
+
+    /// Threads that verify manifest file hashes at startup (`None` uses
+    /// the number of CPUs; at most 16)
+    #[serde(default)]
+    pub verify_parallelism: Option<usize>,
+
+    /// Network egress allowlist (`[security.network]`)
+    #[serde(default)]
+    pub network: NetworkConfig,
+}
+
+/// Rendering of the Tier 1 confirmation countdown
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CountdownStyle {
+    /// Redraw the remaining seconds in place every second
+    #[default]
+    Live,
+    /// Print a single line when the countdown starts
+    Plain,
+}
+
+/// Command allowlist settings
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct CommandsConfig {
+    /// Project-specific commands added to the built-in safe command set.
+    /// Shells, interpreters (sh, bash, python, node, ...) and privilege
+    /// escalation tools are always refused.
+    #[serde(default)]
+    pub extra_allowed: Vec<String>,
+}
+
+/// Network egress settings
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct NetworkConfig {
+    /// Hosts the engine may connect to, e.g. `["api.openai.com"]`. Unset
+    /// allows every host; once set, every other host is refused, so it must
+    /// include the base URL host of each provider in use.
+    #[serde(default)]
+    pub allowed_hosts: Option<Vec<String>>,
 }
 
 /// Memory system configuration
@@ -316,6 +651,16 @@ pub struct SteeringConfig {
     /// Directory for steering skill files (supports ~ expansion)
     #[serde(default = "default_steering_dir")]
     pub skill_dir: PathBuf,
+
+    /// Custom system prompt template (supports ~ expansion).
+    /// The embedded default is used when unset or unreadable.
+    #[serde(default)]
+    pub prompt_template: Option<PathBuf>,
+
+    /// Let `always_verify` skills send the transcript to a cloud provider
+    /// for the second pass (local providers only by default)
+    #[serde(default)]
+    pub verify_with_cloud: bool,
 }
 
 impl Default for SteeringConfig {
@@ -324,10 +669,28 @@ impl Default for SteeringConfig {
             default_skills: Vec::new(),
             auto_detect: true,
             skill_dir: default_steering_dir(),
+            prompt_template: None,
+            verify_with_cloud: false,
         }
     }
 }
 
+/// A named workspace that can be switched to with `rove workspace use`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkspaceConfig {
+    /// Unique workspace name
+    pub name: String,
+
+    /// Workspace directory path (supports ~ expansion)
+    pub path: PathBuf,
+}
+
+/// Reserved workspace name that refers to `core.workspace`
+pub const DEFAULT_WORKSPACE_NAME: &str = "default";
+
+/// File in the data directory that records the active workspace name
+const ACTIVE_WORKSPACE_FILE: &str = "active_workspace";
+
 /// WebSocket client configuration for connecting to external UI
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WsClientConfig {
@@ -359,6 +722,147 @@ impl Default for WsClientConfig {
     }
 }
 
+/// Conductor orchestration configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConductorConfig {
+    /// Have the agent produce an explicit plan before executing any tools
+    #[serde(default)]
+    pub plan_first: bool,
+
+    /// Ask for confirmation of the plan before execution (with `plan_first`)
+    #[serde(default = "default_true")]
+    pub confirm_plan: bool,
+
+    /// Approve the Tier 2 calls the plan anticipates as one batch instead of
+    /// one by one (with `plan_first`)
+    #[serde(default)]
+    pub batch_tier2_approval: bool,
+
+    /// Maximum nesting of the task, its tool calls and plugin-to-plugin calls
+    #[serde(default = "default_max_call_depth")]
+    pub max_call_depth: usize,
+
+    /// Have the LLM summarize each completed task for `rove history`
+    #[serde(default)]
+    pub summarize: bool,
+
+    /// Tier 0 tools whose results are never reused within a task
+    #[serde(default = "default_uncached_tools")]
+    pub uncached_tools: Vec<String>,
+
+    /// Deadline for a whole task in seconds, unless `rove run --timeout`
+    /// sets one (`None` lets tasks run to their iteration limit)
+    #[serde(default)]
+    pub task_timeout_secs: Option<u64>,
+}
+
+impl Default for ConductorConfig {
+    fn default() -> Self {
+        Self {
+            plan_first: false,
+            confirm_plan: true,
+            batch_tier2_approval: false,
+            max_call_depth: default_max_call_depth(),
+            summarize: false,
+            uncached_tools: default_uncached_tools(),
+            task_timeout_secs: None,
+        }
+    }
+}
+
+/// Audit-log export configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditConfig {
+    /// Keychain entry holding the hex-encoded Ed25519 key that signs exports
+    #[serde(default = "default_audit_signing_key_secret")]
+    pub signing_key_secret: String,
+}
+
+impl Default for AuditConfig {
+    fn default() -> Self {
+        Self {
+            signing_key_secret: default_audit_signing_key_secret(),
+        }
+    }
+}
+
+/// Data-retention job configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RetentionConfig {
+    /// Seconds between pruning runs while the daemon is running
+    #[serde(default = "default_retention_interval_secs")]
+    pub interval_secs: u64,
+
+    /// Delete tasks, with their steps and failures, older than this many days.
+    /// 0 keeps task history forever.
+    #[serde(default = "default_task_max_age_days")]
+    pub task_max_age_days: u64,
+
+    /// Delete task steps, the audit log of tool calls and their approvals,
+    /// older than this many days, even while their task is kept. 0 removes
+    /// steps only together with their task.
+    #[serde(default)]
+    pub audit_max_age_days: u64,
+
+    /// Delete rate-limit entries older than this many seconds
+    #[serde(default = "default_rate_limit_max_age_secs")]
+    pub rate_limit_max_age_secs: u64,
+}
+
+impl Default for RetentionConfig {
+    fn default() -> Self {
+        Self {
+            interval_secs: default_retention_interval_secs(),
+            task_max_age_days: default_task_max_age_days(),
+            audit_max_age_days: 0,
+            rate_limit_max_age_secs: default_rate_limit_max_age_secs(),
+        }
+    }
+}
+
+/// Log file configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TelemetryConfig {
+    /// Also write logs to this file (`None` logs to the terminal only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub log_file: Option<PathBuf>,
+
+    /// When the log file is rolled over
+    #[serde(default)]
+    pub rotation: LogRotation,
+
+    /// Size in megabytes at which the log file rolls over with
+    /// `rotation = "size"`
+    #[serde(default = "default_log_max_size_mb")]
+    pub max_size_mb: u64,
+
+    /// Rolled-over log files to keep; older ones are deleted
+    #[serde(default = "default_log_max_files")]
+    pub max_files: usize,
+}
+
+impl Default for TelemetryConfig {
+    fn default() -> Self {
+        Self {
+            log_file: None,
+            rotation: LogRotation::default(),
+            max_size_mb: default_log_max_size_mb(),
+            max_files: default_log_max_files(),
+        }
+    }
+}
+
+/// When the log file is rolled over
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogRotation {
+    /// When a write would take the file past `max_size_mb`
+    #[default]
+    Size,
+    /// On the first write of a new day
+    Daily,
+}
+
 // Default value functions
 fn default_log_level() -> String {
     "info".to_string()
@@ -448,6 +952,57 @@ fn default_episodic_retention_days() -> u32 {
     30
 }
 
+fn default_max_call_depth() -> usize {
+    crate::conductor::DEFAULT_MAX_CALL_DEPTH
+}
+
+fn default_plugin_pool_size() -> usize {
+    crate::runtime::DEFAULT_POOL_SIZE
+}
+
+fn default_plugin_fuel_limit() -> u64 {
+    crate::runtime::DEFAULT_FUEL_LIMIT
+}
+
+fn default_uncached_tools() -> Vec<String> {
+    crate::agent::DEFAULT_UNCACHED_TOOLS
+        .iter()
+        .map(|tool| tool.to_string())
+        .collect()
+}
+
+fn default_breaker_failure_threshold() -> u32 {
+    crate::llm::breaker::DEFAULT_FAILURE_THRESHOLD
+}
+
+fn default_breaker_cooldown_secs() -> u64 {
+    crate::llm::breaker::DEFAULT_COOLDOWN.as_secs()
+}
+
+fn default_log_max_size_mb() -> u64 {
+    10
+}
+
+fn default_log_max_files() -> usize {
+    5
+}
+
+fn default_retention_interval_secs() -> u64 {
+    3600
+}
+
+fn default_task_max_age_days() -> u64 {
+    90
+}
+
+fn default_rate_limit_max_age_secs() -> u64 {
+    crate::rate_limiter::LONGEST_WINDOW.as_secs()
+}
+
+fn default_audit_signing_key_secret() -> String {
+    "audit_signing_key".to_string()
+}
+
 fn default_ws_url() -> String {
     "ws://localhost:9090/rove".to_string()
 }
@@ -547,8 +1102,9 @@ impl Config {
         let contents = fs::read_to_string(path)
             .map_err(|e| EngineError::Config(format!("Failed to read config file: {}", e)))?;
 
-        let mut config: Config = toml::from_str(&contents)
-            .map_err(|e| EngineError::Config(format!("Failed to parse config: {}", e)))?;
+        let mut config: Config =
+            serde_path_to_error::deserialize(toml::Deserializer::new(&contents))
+                .map_err(|e| parse_error(&contents, &e))?;
 
         // Validate and process configuration
         config.validate_and_process()?;
@@ -602,8 +1158,54 @@ impl Config {
         Ok(config)
     }
 
+    /// Write the plugin enable flags to the config file at `path`
+    ///
+    /// Only the flags in `[plugins]` are updated; every other setting is kept
+    /// as it is in the file. All flags go out in a single write: the new file
+    /// is written next to the old one and renamed over it, so a failed save
+    /// leaves the previous config intact.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file can't be read, parsed or replaced.
+    pub fn save_plugins(&self, path: &Path) -> Result<(), EngineError> {
+        let contents = fs::read_to_string(path)
+            .map_err(|e| EngineError::Config(format!("Failed to read config file: {}", e)))?;
+        let mut table: toml::Table = contents
+            .parse()
+            .map_err(|e| EngineError::Config(format!("Failed to parse config file: {}", e)))?;
+
+        let plugins = table
+            .entry("plugins")
+            .or_insert_with(|| toml::Value::Table(toml::Table::new()))
+            .as_table_mut()
+            .ok_or_else(|| EngineError::Config("[plugins] is not a table".to_string()))?;
+        for (id, enabled) in self.plugins.flags() {
+            plugins.insert(id.to_string(), toml::Value::Boolean(enabled));
+        }
+
+        let toml_string = toml::to_string_pretty(&table)
+            .map_err(|e| EngineError::Config(format!("Failed to serialize config: {}", e)))?;
+
+        let tmp_path = path.with_extension("toml.tmp");
+        fs::write(&tmp_path, &toml_string)
+            .map_err(|e| EngineError::Config(format!("Failed to write config file: {}", e)))?;
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let perms = std::fs::Permissions::from_mode(0o600);
+            std::fs::set_permissions(&tmp_path, perms).map_err(|e| {
+                EngineError::Config(format!("Failed to set config file permissions: {}", e))
+            })?;
+        }
+
+        fs::rename(&tmp_path, path)
+            .map_err(|e| EngineError::Config(format!("Failed to replace config file: {}", e)))
+    }
+
     /// Get the default configuration file path (~/.rove/config.toml)
-    fn default_config_path() -> Result<PathBuf, EngineError> {
+    pub fn default_config_path() -> Result<PathBuf, EngineError> {
         let home = dirs::home_dir()
             .ok_or_else(|| EngineError::Config("Could not determine home directory".to_string()))?;
 
@@ -611,16 +1213,18 @@ impl Config {
     }
 
     /// Create a default configuration
-    fn default_config() -> Self {
+    pub(crate) fn default_config() -> Self {
         Self {
             core: CoreConfig {
                 workspace: PathBuf::from("~/projects"),
                 log_level: default_log_level(),
                 auto_sync: true,
                 data_dir: default_data_dir(),
+                index_workspace: false,
             },
             llm: LLMConfig {
                 default_provider: "ollama".to_string(),
+                provider_priority: Vec::new(),
                 sensitivity_threshold: default_sensitivity_threshold(),
                 complexity_threshold: default_complexity_threshold(),
                 ollama: OllamaConfig::default(),
@@ -628,6 +1232,7 @@ impl Config {
                 anthropic: AnthropicConfig::default(),
                 gemini: GeminiConfig::default(),
                 nvidia_nim: NvidiaNimConfig::default(),
+                circuit_breaker: CircuitBreakerConfig::default(),
             },
             tools: ToolsConfig {
                 tg_controller: false,
@@ -639,56 +1244,223 @@ impl Config {
                 terminal: true,
                 screenshot: false,
                 git: true,
+                dev_dir: None,
+                pool_size: default_plugin_pool_size(),
+                fuel_limit: default_plugin_fuel_limit(),
             },
             security: SecurityConfig {
                 max_risk_tier: default_max_risk_tier(),
                 confirm_tier1: true,
                 confirm_tier1_delay: default_tier1_delay(),
+                countdown_style: CountdownStyle::default(),
                 require_explicit_tier2: true,
+                summarize_tier2_locally: false,
+                commands: CommandsConfig::default(),
+                disabled_host_functions: Vec::new(),
+                manifest_max_age_days: None,
+                rate_limit_bypass: Vec::new(),
+                verify_parallelism: None,
+                network: NetworkConfig::default(),
             },
             memory: MemoryConfig::default(),
             brains: BrainsConfig::default(),
             steering: SteeringConfig::default(),
             ws_client: WsClientConfig::default(),
+            conductor: ConductorConfig::default(),
+            audit: AuditConfig::default(),
+            retention: RetentionConfig::default(),
+            telemetry: TelemetryConfig::default(),
+            workspaces: Vec::new(),
+            active_workspace: None,
+            warnings: Vec::new(),
         }
     }
 
-    /// Validate and process configuration
+    /// Risky-but-valid settings found when the config was loaded
+    pub fn warnings(&self) -> &[ConfigWarning] {
+        &self.warnings
+    }
+
+    /// The configuration in use, given the config file it was loaded from
     ///
-    /// This method:
-    /// - Validates required fields
-    /// - Expands ~ in paths
-    /// - Canonicalizes paths
-    /// - Verifies workspace is a directory
-    /// - Creates workspace if it doesn't exist
+    /// Every value is listed, including defaults the file leaves out, and
+    /// annotated with its source. With a workspace selected by `rove
+    /// workspace use`, `core.workspace` is that workspace's path, marked as
+    /// an override.
     ///
     /// # Errors
     ///
-    /// Returns an error if:
-    /// - Required fields are missing or invalid
-    /// - Path expansion fails
-    /// - Path canonicalization fails
-    /// - Workspace creation fails
-    fn validate_and_process(&mut self) -> Result<(), EngineError> {
-        // Validate log level
-        let valid_log_levels = ["error", "warn", "info", "debug", "trace"];
-        if !valid_log_levels.contains(&self.core.log_level.as_str()) {
-            return Err(EngineError::Config(format!(
-                "Invalid log level '{}'. Must be one of: {}",
-                self.core.log_level,
-                valid_log_levels.join(", ")
-            )));
+    /// Returns an error if the file doesn't parse or the config can't be
+    /// serialized.
+    pub fn effective(&self, file_contents: &str) -> Result<EffectiveConfig, EngineError> {
+        let file: toml::Table = toml::from_str(file_contents)
+            .map_err(|e| EngineError::Config(format!("Failed to parse config: {}", e)))?;
+        let mut config = match toml::Value::try_from(self) {
+            Ok(toml::Value::Table(config)) => config,
+            Ok(_) => unreachable!("Config serializes to a table"),
+            Err(e) => {
+                return Err(EngineError::Config(format!(
+                    "Failed to serialize config: {}",
+                    e
+                )))
+            }
+        };
+
+        let mut sources = std::collections::BTreeMap::new();
+        collect_sources("", &config, Some(&file), &mut sources);
+
+        if self.active_workspace.is_some() {
+            if let Some(toml::Value::Table(core)) = config.get_mut("core") {
+                core.insert(
+                    "workspace".to_string(),
+                    toml::Value::String(self.workspace().to_string_lossy().into_owned()),
+                );
+                sources.insert("core.workspace".to_string(), ValueSource::Override);
+            }
         }
+        Ok(EffectiveConfig { config, sources })
+    }
 
-        // Validate default provider
-        let valid_providers = ["ollama", "openai", "anthropic", "gemini", "nvidia_nim"];
-        if !valid_providers.contains(&self.llm.default_provider.as_str()) {
-            return Err(EngineError::Config(format!(
-                "Invalid default provider '{}'. Must be one of: {}",
+    /// Get the active workspace directory
+    ///
+    /// Returns the path of the workspace selected with `rove workspace use`,
+    /// or `core.workspace` if none is selected. The file system guard and
+    /// command working directory should always be derived from this path.
+    pub fn workspace(&self) -> &Path {
+        self.active_workspace
+            .as_deref()
+            .and_then(|name| self.workspaces.iter().find(|w| w.name == name))
+            .map(|w| w.path.as_path())
+            .unwrap_or(&self.core.workspace)
+    }
+
+    /// Get the name of the active workspace
+    ///
+    /// Returns `"default"` when `core.workspace` is active.
+    pub fn active_workspace_name(&self) -> &str {
+        self.active_workspace
+            .as_deref()
+            .unwrap_or(DEFAULT_WORKSPACE_NAME)
+    }
+
+    /// Switch the active workspace for subsequent runs
+    ///
+    /// The selection is stored in the data directory so the config file is
+    /// never rewritten. Pass `"default"` to switch back to `core.workspace`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `EngineError::Config` if no workspace has the given name or the
+    /// selection cannot be saved.
+    pub fn use_workspace(&mut self, name: &str) -> Result<&Path, EngineError> {
+        let marker = self.core.data_dir.join(ACTIVE_WORKSPACE_FILE);
+
+        if name == DEFAULT_WORKSPACE_NAME {
+            if marker.exists() {
+                fs::remove_file(&marker).map_err(|e| {
+                    EngineError::Config(format!("Failed to reset active workspace: {}", e))
+                })?;
+            }
+            self.active_workspace = None;
+            return Ok(self.workspace());
+        }
+
+        if !self.workspaces.iter().any(|w| w.name == name) {
+            let known: Vec<&str> = self.workspaces.iter().map(|w| w.name.as_str()).collect();
+            return Err(EngineError::Config(format!(
+                "Unknown workspace '{}'. Available: {}",
+                name,
+                if known.is_empty() {
+                    "none (add [[workspaces]] entries to config.toml)".to_string()
+                } else {
+                    format!("{}, {}", DEFAULT_WORKSPACE_NAME, known.join(", "))
+                }
+            )));
+        }
+
+        fs::write(&marker, name)
+            .map_err(|e| EngineError::Config(format!("Failed to save active workspace: {}", e)))?;
+        self.active_workspace = Some(name.to_string());
+
+        Ok(self.workspace())
+    }
+
+    /// Load the active workspace selection from the data directory
+    ///
+    /// A selection that no longer matches a configured workspace is ignored
+    /// so a stale marker never blocks startup.
+    fn load_active_workspace(&mut self) {
+        let marker = self.core.data_dir.join(ACTIVE_WORKSPACE_FILE);
+        let Ok(contents) = fs::read_to_string(&marker) else {
+            return;
+        };
+
+        let name = contents.trim();
+        if self.workspaces.iter().any(|w| w.name == name) {
+            self.active_workspace = Some(name.to_string());
+        } else if !name.is_empty() {
+            tracing::warn!(
+                "Active workspace '{}' is not configured, falling back to core.workspace",
+                name
+            );
+        }
+    }
+
+    /// Validate and process configuration
+    ///
+    /// This method:
+    /// - Validates required fields
+    /// - Expands ~ in paths
+    /// - Canonicalizes paths
+    /// - Verifies workspace is a directory
+    /// - Creates workspace if it doesn't exist
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - Required fields are missing or invalid
+    /// - Path expansion fails
+    /// - Path canonicalization fails
+    /// - Workspace creation fails
+    ///
+    /// Risky settings that are still valid are recorded in `warnings`.
+    fn validate_and_process(&mut self) -> Result<(), EngineError> {
+        let mut warnings = Vec::new();
+
+        // Validate log level
+        let valid_log_levels = ["error", "warn", "info", "debug", "trace"];
+        if !valid_log_levels.contains(&self.core.log_level.as_str()) {
+            return Err(EngineError::Config(format!(
+                "Invalid log level '{}'. Must be one of: {}",
+                self.core.log_level,
+                valid_log_levels.join(", ")
+            )));
+        }
+
+        // Validate default provider
+        let valid_providers = ["ollama", "openai", "anthropic", "gemini", "nvidia_nim"];
+        if !valid_providers.contains(&self.llm.default_provider.as_str()) {
+            return Err(EngineError::Config(format!(
+                "Invalid default provider '{}'. Must be one of: {}",
                 self.llm.default_provider,
                 valid_providers.join(", ")
             )));
         }
+        for (i, name) in self.llm.provider_priority.iter().enumerate() {
+            if !valid_providers.contains(&name.as_str()) {
+                return Err(EngineError::Config(format!(
+                    "Invalid provider '{}' in llm.provider_priority. Must be one of: {}",
+                    name,
+                    valid_providers.join(", ")
+                )));
+            }
+            if self.llm.provider_priority[..i].contains(name) {
+                return Err(EngineError::Config(format!(
+                    "Provider '{}' is listed twice in llm.provider_priority",
+                    name
+                )));
+            }
+        }
 
         // Validate thresholds
         if self.llm.sensitivity_threshold < 0.0 || self.llm.sensitivity_threshold > 1.0 {
@@ -709,13 +1481,85 @@ impl Config {
             ));
         }
 
+        // A misspelled name would silently leave the function enabled
+        let host_functions = crate::runtime::host_policy::HOST_FUNCTIONS;
+        if let Some(unknown) = self
+            .security
+            .disabled_host_functions
+            .iter()
+            .find(|name| !host_functions.contains(&name.as_str()))
+        {
+            return Err(EngineError::Config(format!(
+                "Unknown host function '{}' in security.disabled_host_functions. Must be one of: {}",
+                unknown,
+                host_functions.join(", ")
+            )));
+        }
+
+        if self.security.manifest_max_age_days == Some(0) {
+            return Err(EngineError::Config(
+                "security.manifest_max_age_days must be at least 1".to_string(),
+            ));
+        }
+
+        if self.plugins.pool_size == 0 {
+            return Err(EngineError::Config(
+                "plugins.pool_size must be at least 1".to_string(),
+            ));
+        }
+
+        // Every call uses some fuel, so 0 would stop every call
+        if self.plugins.fuel_limit == 0 {
+            return Err(EngineError::Config(
+                "plugins.fuel_limit must be at least 1".to_string(),
+            ));
+        }
+
+        // A task itself is one level deep, so 0 would refuse every task
+        if self.conductor.max_call_depth == 0 {
+            return Err(EngineError::Config(
+                "conductor.max_call_depth must be at least 1".to_string(),
+            ));
+        }
+
+        if self.conductor.task_timeout_secs == Some(0) {
+            return Err(EngineError::Config(
+                "conductor.task_timeout_secs must be at least 1".to_string(),
+            ));
+        }
+
+        if self.llm.circuit_breaker.failure_threshold == 0 {
+            return Err(EngineError::Config(
+                "llm.circuit_breaker.failure_threshold must be at least 1".to_string(),
+            ));
+        }
+
+        if self.retention.interval_secs == 0 {
+            return Err(EngineError::Config(
+                "retention.interval_secs must be at least 1".to_string(),
+            ));
+        }
+
+        // Pruning inside a rate-limit window would reset the limits
+        let longest_window = crate::rate_limiter::LONGEST_WINDOW.as_secs();
+        if self.retention.rate_limit_max_age_secs < longest_window {
+            return Err(EngineError::Config(format!(
+                "retention.rate_limit_max_age_secs must be at least {}",
+                longest_window
+            )));
+        }
+
         // Expand and validate workspace path
         self.core.workspace = expand_path(&self.core.workspace)?;
 
         // Reject dangerous workspace paths (system roots)
         reject_dangerous_workspace(&self.core.workspace)?;
 
+        let configured = self.core.workspace.clone();
         self.core.workspace = canonicalize_or_create(&self.core.workspace)?;
+        for warning in workspace_warnings(&configured, &self.core.workspace) {
+            warnings.push(ConfigWarning::new("core.workspace", warning));
+        }
 
         // Verify workspace is a directory
         if !self.core.workspace.is_dir() {
@@ -725,6 +1569,46 @@ impl Config {
             )));
         }
 
+        // Validate additional workspaces the same way as core.workspace
+        let mut seen_names = std::collections::HashSet::new();
+        for workspace in &mut self.workspaces {
+            if workspace.name.trim().is_empty() {
+                return Err(EngineError::Config(
+                    "Workspace name must not be empty".to_string(),
+                ));
+            }
+            if workspace.name == DEFAULT_WORKSPACE_NAME {
+                return Err(EngineError::Config(format!(
+                    "Workspace name '{}' is reserved for core.workspace",
+                    DEFAULT_WORKSPACE_NAME
+                )));
+            }
+            if !seen_names.insert(workspace.name.clone()) {
+                return Err(EngineError::Config(format!(
+                    "Duplicate workspace name '{}'",
+                    workspace.name
+                )));
+            }
+
+            workspace.path = expand_path(&workspace.path)?;
+            reject_dangerous_workspace(&workspace.path)?;
+            let configured = workspace.path.clone();
+            workspace.path = canonicalize_or_create(&workspace.path)?;
+            for warning in workspace_warnings(&configured, &workspace.path) {
+                warnings.push(ConfigWarning::new(
+                    format!("workspaces.{}", workspace.name),
+                    warning,
+                ));
+            }
+
+            if !workspace.path.is_dir() {
+                return Err(EngineError::Config(format!(
+                    "Workspace '{}' path is not a directory: {:?}",
+                    workspace.name, workspace.path
+                )));
+            }
+        }
+
         // Expand and validate data directory
         self.core.data_dir = expand_path(&self.core.data_dir)?;
 
@@ -735,8 +1619,95 @@ impl Config {
             })?;
         }
 
+        // Plugin dev mode skips signature checks, so production builds ignore it
+        if let Some(ref dev_dir) = self.plugins.dev_dir {
+            if crate::crypto::CryptoModule::is_production() {
+                tracing::warn!(
+                    "Ignoring plugins.dev_dir ({}): plugin dev mode is disabled in production builds",
+                    dev_dir.display()
+                );
+                self.plugins.dev_dir = None;
+            } else {
+                self.plugins.dev_dir = Some(expand_path(dev_dir)?);
+            }
+        }
+
+        if self.telemetry.max_size_mb == 0 {
+            return Err(EngineError::Config(
+                "telemetry.max_size_mb must be at least 1".to_string(),
+            ));
+        }
+        if let Some(ref log_file) = self.telemetry.log_file {
+            self.telemetry.log_file = Some(expand_path(log_file)?);
+        }
+
+        // Expand the prompt template path; a missing file falls back to the default
+        if let Some(ref template) = self.steering.prompt_template {
+            self.steering.prompt_template = Some(expand_path(template)?);
+        }
+
+        // Restore the workspace selected with `rove workspace use`
+        self.load_active_workspace();
+
+        warnings.extend(self.risky_settings());
+        self.warnings = warnings;
+
         Ok(())
     }
+
+    /// Settings that are valid but weaken security
+    fn risky_settings(&self) -> Vec<ConfigWarning> {
+        let mut warnings = Vec::new();
+
+        if self.security.max_risk_tier >= 2 && !self.security.require_explicit_tier2 {
+            warnings.push(ConfigWarning::new(
+                "security.require_explicit_tier2",
+                "Tier 2 operations (deleting files, running commands) are allowed without explicit confirmation",
+            ));
+        }
+
+        if self.ws_client.enabled && !is_local_or_encrypted(&self.ws_client.url, "wss") {
+            warnings.push(ConfigWarning::new(
+                "ws_client.url",
+                format!(
+                    "{} is not a loopback address and the connection is not encrypted",
+                    self.ws_client.url
+                ),
+            ));
+        }
+
+        if !is_local_or_encrypted(&self.llm.ollama.base_url, "https") {
+            warnings.push(ConfigWarning::new(
+                "llm.ollama.base_url",
+                format!(
+                    "{} is not a loopback address; prompts are sent to it unencrypted",
+                    self.llm.ollama.base_url
+                ),
+            ));
+        }
+
+        warnings
+    }
+}
+
+/// Whether `url` points at this machine or uses the `secure_scheme`
+///
+/// URLs that don't parse are left to the code that uses them.
+fn is_local_or_encrypted(url: &str, secure_scheme: &str) -> bool {
+    let Ok(url) = reqwest::Url::parse(url) else {
+        return true;
+    };
+    if url.scheme() == secure_scheme {
+        return true;
+    }
+    let Some(host) = url.host_str() else {
+        return true;
+    };
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+    match host.parse::<std::net::IpAddr>() {
+        Ok(ip) => ip.is_loopback(),
+        Err(_) => host.eq_ignore_ascii_case("localhost"),
+    }
 }
 
 /// Expand ~ in path to user's home directory
@@ -817,6 +1788,28 @@ fn reject_dangerous_workspace(path: &Path) -> Result<(), EngineError> {
     Ok(())
 }
 
+/// Describe a config parse error with the offending key and its position
+///
+/// E.g. "Failed to parse config: invalid `security.max_risk_tier` at line 12,
+/// column 17: invalid type: string "high", expected u8". Syntax errors have
+/// no key.
+fn parse_error(contents: &str, error: &serde_path_to_error::Error<toml::de::Error>) -> EngineError {
+    let mut message = String::from("Failed to parse config");
+    let key = error.path().to_string();
+    if key != "." {
+        message.push_str(&format!(": invalid `{}`", key));
+    }
+    if let Some(span) = error.inner().span() {
+        let before = contents.get(..span.start).unwrap_or(contents);
+        let line = before.matches('\n').count() + 1;
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        let column = before[line_start..].chars().count() + 1;
+        message.push_str(&format!(" at line {}, column {}", line, column));
+    }
+    message.push_str(&format!(": {}", error.inner().message()));
+    EngineError::Config(message)
+}
+
 /// Canonicalize path, creating it if it doesn't exist
 ///
 /// This function attempts to canonicalize the path. If the path doesn't exist,
@@ -837,6 +1830,70 @@ fn canonicalize_or_create(path: &Path) -> Result<PathBuf, EngineError> {
     }
 }
 
+/// Describe why a workspace may behave unexpectedly
+///
+/// `configured` is the expanded path from the config and `canonical` the
+/// path it resolved to. A workspace reached through a symlink is confined to
+/// the link's target, and one that is a git submodule shares its git
+/// directory with the parent repository, so git operations there can affect
+/// the parent. Linked worktrees, whose `.git` is a file too, are not reported.
+fn workspace_warnings(configured: &Path, canonical: &Path) -> Vec<String> {
+    let mut warnings = Vec::new();
+
+    let absolute = if configured.is_absolute() {
+        configured.to_path_buf()
+    } else {
+        std::env::current_dir()
+            .map(|cwd| cwd.join(configured))
+            .unwrap_or_else(|_| configured.to_path_buf())
+    };
+    if through_symlink(&absolute) {
+        warnings.push(format!(
+            "{} resolves to {} (symlink); file access is confined to the resolved path",
+            configured.display(),
+            canonical.display()
+        ));
+    }
+
+    // A submodule's `.git` is a file pointing into the parent's
+    // `.git/modules`; a linked worktree's points into `.git/worktrees`, and
+    // its git directory has a `commondir` file
+    let gitdir = fs::read_to_string(canonical.join(".git"))
+        .ok()
+        .and_then(|contents| {
+            let target = contents.strip_prefix("gitdir:")?.trim().to_string();
+            Some(canonical.join(target))
+        });
+    if let Some(gitdir) = gitdir {
+        let is_worktree = gitdir.join("commondir").is_file();
+        if !is_worktree && gitdir.components().any(|c| c.as_os_str() == "modules") {
+            warnings.push(format!(
+                "{} is a git submodule; git operations may affect the parent repository",
+                canonical.display()
+            ));
+        }
+    }
+
+    warnings
+}
+
+/// Whether `path`, or a directory on the way to it, is a symlink
+///
+/// Only the components of `path` are checked, so `.` and `..` in it or a
+/// trailing separator don't count as a symlink.
+fn through_symlink(path: &Path) -> bool {
+    let mut walked = PathBuf::new();
+    for component in path.components() {
+        walked.push(component);
+        if matches!(component, Component::Normal(_))
+            && fs::symlink_metadata(&walked).is_ok_and(|meta| meta.file_type().is_symlink())
+        {
+            return true;
+        }
+    }
+    false
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -892,4 +1949,704 @@ mod tests {
             deserialized.llm.default_provider
         );
     }
+
+    fn config_with_workspaces(root: &Path) -> Config {
+        let mut config = Config::default_config();
+        config.core.workspace = root.join("main");
+        config.core.data_dir = root.join("data");
+        config.workspaces = vec![
+            WorkspaceConfig {
+                name: "frontend".to_string(),
+                path: root.join("frontend"),
+            },
+            WorkspaceConfig {
+                name: "backend".to_string(),
+                path: root.join("backend"),
+            },
+        ];
+        config.validate_and_process().unwrap();
+        config
+    }
+
+    #[test]
+    fn test_workspace_defaults_to_core_workspace() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let config = config_with_workspaces(temp_dir.path());
+
+        assert_eq!(config.active_workspace_name(), DEFAULT_WORKSPACE_NAME);
+        assert_eq!(config.workspace(), config.core.workspace.as_path());
+    }
+
+    #[test]
+    fn test_use_workspace_switches_and_persists() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let mut config = config_with_workspaces(temp_dir.path());
+
+        let backend = config.use_workspace("backend").unwrap().to_path_buf();
+        assert!(backend.ends_with("backend"));
+        assert_eq!(config.active_workspace_name(), "backend");
+
+        // A freshly loaded config picks up the selection
+        let reloaded = config_with_workspaces(temp_dir.path());
+        assert_eq!(reloaded.active_workspace_name(), "backend");
+        assert_eq!(reloaded.workspace(), backend.as_path());
+
+        // Switching back to default restores core.workspace
+        config.use_workspace(DEFAULT_WORKSPACE_NAME).unwrap();
+        let reloaded = config_with_workspaces(temp_dir.path());
+        assert_eq!(reloaded.workspace(), reloaded.core.workspace.as_path());
+    }
+
+    #[test]
+    fn test_use_unknown_workspace_fails() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let mut config = config_with_workspaces(temp_dir.path());
+
+        let result = config.use_workspace("missing");
+        assert!(matches!(result, Err(EngineError::Config(_))));
+        assert_eq!(config.active_workspace_name(), DEFAULT_WORKSPACE_NAME);
+    }
+
+    #[test]
+    fn test_duplicate_workspace_names_rejected() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let mut config = Config::default_config();
+        config.core.workspace = temp_dir.path().join("main");
+        config.core.data_dir = temp_dir.path().join("data");
+        config.workspaces = vec![
+            WorkspaceConfig {
+                name: "app".to_string(),
+                path: temp_dir.path().join("a"),
+            },
+            WorkspaceConfig {
+                name: "app".to_string(),
+                path: temp_dir.path().join("b"),
+            },
+        ];
+
+        assert!(config.validate_and_process().is_err());
+    }
+
+    #[test]
+    fn test_reserved_workspace_name_rejected() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let mut config = Config::default_config();
+        config.core.workspace = temp_dir.path().join("main");
+        config.core.data_dir = temp_dir.path().join("data");
+        config.workspaces = vec![WorkspaceConfig {
+            name: DEFAULT_WORKSPACE_NAME.to_string(),
+            path: temp_dir.path().join("other"),
+        }];
+
+        assert!(config.validate_and_process().is_err());
+    }
+
+    #[test]
+    fn test_workspaces_toml_parsing() {
+        let config: Config = toml::from_str(
+            r#"
+[core]
+workspace = "~/projects"
+
+[llm]
+default_provider = "ollama"
+
+[tools]
+
+[plugins]
+
+[security]
+
+[[workspaces]]
+name = "api"
+path = "~/code/api"
+
+[[workspaces]]
+name = "web"
+path = "~/code/web"
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(config.workspaces.len(), 2);
+        assert_eq!(config.workspaces[0].name, "api");
+        assert_eq!(config.workspaces[1].path, PathBuf::from("~/code/web"));
+    }
+
+    #[test]
+    fn test_security_commands_toml_parsing() {
+        let config: Config = toml::from_str(
+            r#"
+[core]
+workspace = "~/projects"
+
+[llm]
+default_provider = "ollama"
+
+[tools]
+
+[plugins]
+
+[security]
+
+[security.commands]
+extra_allowed = ["pnpm", "make"]
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(config.security.commands.extra_allowed, vec!["pnpm", "make"]);
+
+        // Absent section defaults to no extra commands
+        let config = Config::default_config();
+        assert!(config.security.commands.extra_allowed.is_empty());
+    }
+
+    #[test]
+    fn test_countdown_style_parsing() {
+        let config: Config = toml::from_str(
+            r#"
+[core]
+workspace = "~/projects"
+
+[llm]
+default_provider = "ollama"
+
+[tools]
+
+[plugins]
+
+[security]
+countdown_style = "plain"
+"#,
+        )
+        .unwrap();
+        assert_eq!(config.security.countdown_style, CountdownStyle::Plain);
+
+        assert_eq!(
+            Config::default_config().security.countdown_style,
+            CountdownStyle::Live
+        );
+    }
+
+    #[test]
+    fn test_disabled_host_functions() {
+        let config: Config = toml::from_str(
+            r#"
+[core]
+workspace = "~/projects"
+
+[llm]
+default_provider = "ollama"
+
+[tools]
+
+[plugins]
+
+[security]
+disabled_host_functions = ["exec_git"]
+"#,
+        )
+        .unwrap();
+        assert_eq!(config.security.disabled_host_functions, vec!["exec_git"]);
+        assert!(Config::default_config()
+            .security
+            .disabled_host_functions
+            .is_empty());
+
+        let mut config = Config::default_config();
+        config.security.disabled_host_functions = vec!["exec_shell".to_string()];
+        assert!(config.validate_and_process().is_err());
+    }
+
+    #[test]
+    fn test_summarize_tier2_locally() {
+        let config: Config = toml::from_str(
+            r#"
+[core]
+workspace = "~/projects"
+
+[llm]
+default_provider = "openai"
+
+[tools]
+
+[plugins]
+
+[security]
+summarize_tier2_locally = true
+"#,
+        )
+        .unwrap();
+        assert!(config.security.summarize_tier2_locally);
+        assert!(config.security.require_explicit_tier2);
+        assert!(!Config::default_config().security.summarize_tier2_locally);
+    }
+
+    #[test]
+    fn test_verify_with_cloud_is_opt_in() {
+        let config: Config = toml::from_str(
+            r#"
+[core]
+workspace = "~/projects"
+
+[llm]
+default_provider = "openai"
+
+[tools]
+
+[plugins]
+
+[security]
+
+[steering]
+verify_with_cloud = true
+"#,
+        )
+        .unwrap();
+        assert!(config.steering.verify_with_cloud);
+        assert!(!Config::default_config().steering.verify_with_cloud);
+    }
+
+    #[test]
+    fn test_max_call_depth_validation() {
+        let mut config = Config::default_config();
+        assert_eq!(
+            config.conductor.max_call_depth,
+            crate::conductor::DEFAULT_MAX_CALL_DEPTH
+        );
+
+        config.conductor.max_call_depth = 0;
+        assert!(config.validate_and_process().is_err());
+    }
+
+    #[test]
+    fn test_plugin_pool_size_validation() {
+        let mut config = Config::default_config();
+        assert_eq!(config.plugins.pool_size, crate::runtime::DEFAULT_POOL_SIZE);
+
+        config.plugins.pool_size = 0;
+        assert!(config.validate_and_process().is_err());
+    }
+
+    #[test]
+    fn test_plugin_fuel_limit_validation() {
+        let mut config = Config::default_config();
+        assert_eq!(
+            config.plugins.fuel_limit,
+            crate::runtime::DEFAULT_FUEL_LIMIT
+        );
+
+        config.plugins.fuel_limit = 0;
+        assert!(config.validate_and_process().is_err());
+    }
+
+    #[test]
+    fn test_retention_validation() {
+        let mut config = Config::default_config();
+        assert_eq!(config.retention.interval_secs, 3600);
+        assert_eq!(config.retention.task_max_age_days, 90);
+        assert_eq!(config.retention.rate_limit_max_age_secs, 3600);
+
+        config.retention.interval_secs = 0;
+        assert!(config.validate_and_process().is_err());
+
+        let mut config = Config::default_config();
+        config.retention.rate_limit_max_age_secs = 600;
+        assert!(config.validate_and_process().is_err());
+    }
+
+    #[test]
+    fn test_risky_settings_are_warned_about() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let root = temp_dir.path().canonicalize().unwrap();
+        let mut config = Config::default_config();
+        config.core.workspace = root.join("projects");
+        config.core.data_dir = root.join("data");
+        config.validate_and_process().unwrap();
+        assert!(config.warnings().is_empty(), "{:?}", config.warnings());
+
+        config.security.max_risk_tier = 2;
+        config.security.require_explicit_tier2 = false;
+        config.ws_client.enabled = true;
+        config.ws_client.url = "ws://192.168.1.20:9090/rove".to_string();
+        config.llm.ollama.base_url = "http://[::1]:11434".to_string();
+        config.validate_and_process().unwrap();
+
+        let keys: Vec<&str> = config.warnings().iter().map(|w| w.key.as_str()).collect();
+        assert_eq!(
+            keys,
+            vec!["security.require_explicit_tier2", "ws_client.url"]
+        );
+        assert!(config.warnings()[1].to_string().contains("192.168.1.20"));
+
+        // Encrypted connections are fine wherever they go
+        config.ws_client.url = "wss://ui.example.com/rove".to_string();
+        config.llm.ollama.base_url = "http://gpu-box:11434".to_string();
+        config.validate_and_process().unwrap();
+        let keys: Vec<&str> = config.warnings().iter().map(|w| w.key.as_str()).collect();
+        assert_eq!(
+            keys,
+            vec!["security.require_explicit_tier2", "llm.ollama.base_url"]
+        );
+    }
+
+    fn load_error(contents: &str) -> String {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        fs::write(&path, contents).unwrap();
+        Config::load_from_path(&path).unwrap_err().to_string()
+    }
+
+    #[test]
+    fn test_parse_error_names_field_and_location() {
+        let error = load_error(
+            r#"[core]
+workspace = "~/projects"
+
+[llm]
+default_provider = "ollama"
+
+[tools]
+
+[plugins]
+
+[security]
+max_risk_tier = "high"
+"#,
+        );
+
+        assert!(error.contains("`security.max_risk_tier`"), "{}", error);
+        assert!(error.contains("line 12, column 17"), "{}", error);
+        assert!(error.contains("invalid type"), "{}", error);
+    }
+
+    #[test]
+    fn test_parse_error_locates_syntax_errors() {
+        let error = load_error(
+            r#"[core]
+workspace = "~/projects"
+log_level = info
+"#,
+        );
+
+        assert!(error.contains("at line 3, column"), "{}", error);
+        assert!(!error.contains("invalid `"), "{}", error);
+
+        let error = load_error(
+            r#"[core]
+workspace = "~/projects"
+
+[llm]
+default_provider = "ollama"
+
+[tools]
+
+[plugins]
+
+[security]
+disabled_host_functions = ["exec_git", 7]
+"#,
+        );
+        assert!(
+            error.contains("`security.disabled_host_functions[1]`"),
+            "{}",
+            error
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_symlinked_workspace_is_canonicalized_and_reported() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let root = temp_dir.path().canonicalize().unwrap();
+        let target = root.join("projects");
+        fs::create_dir(&target).unwrap();
+        let link = root.join("link");
+        std::os::unix::fs::symlink(&target, &link).unwrap();
+
+        let mut config = Config::default_config();
+        config.core.workspace = link.clone();
+        config.core.data_dir = root.join("data");
+        config.validate_and_process().unwrap();
+
+        assert_eq!(config.core.workspace, target);
+        let warnings = workspace_warnings(&link, &config.core.workspace);
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains("symlink"));
+        assert!(warnings[0].contains(&target.display().to_string()));
+
+        // The real path itself is not reported, however it is spelled
+        assert!(workspace_warnings(&target, &target).is_empty());
+        let spelled = root.join("projects/../projects/./");
+        assert!(workspace_warnings(&spelled, &target).is_empty());
+    }
+
+    #[test]
+    fn test_submodule_workspace_is_reported() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let root = temp_dir.path().canonicalize().unwrap();
+        let submodule = root.join("vendor");
+        fs::create_dir(&submodule).unwrap();
+        fs::write(submodule.join(".git"), "gitdir: ../.git/modules/vendor\n").unwrap();
+        fs::create_dir_all(root.join(".git/modules/vendor")).unwrap();
+
+        let warnings = workspace_warnings(&submodule, &submodule);
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains("git submodule"));
+
+        // A regular repository has a .git directory
+        assert!(workspace_warnings(&root, &root).is_empty());
+    }
+
+    #[test]
+    fn test_linked_worktree_is_not_a_submodule() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let root = temp_dir.path().canonicalize().unwrap();
+        let gitdir = root.join("repo/.git/worktrees/feature");
+        fs::create_dir_all(&gitdir).unwrap();
+        fs::write(gitdir.join("commondir"), "../..\n").unwrap();
+        let worktree = root.join("feature");
+        fs::create_dir(&worktree).unwrap();
+        fs::write(
+            worktree.join(".git"),
+            format!("gitdir: {}\n", gitdir.display()),
+        )
+        .unwrap();
+
+        assert!(workspace_warnings(&worktree, &worktree).is_empty());
+    }
+
+    #[test]
+    fn test_plugin_dev_dir_ignored_in_production() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let mut config = Config::default_config();
+        config.core.workspace = temp_dir.path().join("main");
+        config.core.data_dir = temp_dir.path().join("data");
+        config.plugins.dev_dir = Some(temp_dir.path().join("plugins"));
+
+        config.validate_and_process().unwrap();
+
+        if crate::crypto::CryptoModule::is_production() {
+            assert_eq!(config.plugins.dev_dir, None);
+        } else {
+            assert_eq!(
+                config.plugins.dev_dir,
+                Some(temp_dir.path().join("plugins"))
+            );
+        }
+    }
+
+    #[test]
+    fn test_telemetry_log_file() {
+        let config = Config::default_config();
+        assert!(config.telemetry.log_file.is_none());
+        assert_eq!(config.telemetry.rotation, LogRotation::Size);
+
+        let mut config: Config = toml::from_str(
+            r#"
+[core]
+workspace = "~/projects"
+
+[llm]
+default_provider = "ollama"
+
+[tools]
+
+[plugins]
+
+[security]
+
+[telemetry]
+log_file = "~/.rove/logs/rove.log"
+rotation = "daily"
+max_files = 7
+"#,
+        )
+        .unwrap();
+        assert_eq!(config.telemetry.rotation, LogRotation::Daily);
+        assert_eq!(config.telemetry.max_files, 7);
+        assert_eq!(config.telemetry.max_size_mb, 10);
+
+        let dir = tempfile::tempdir().unwrap();
+        config.core.workspace = dir.path().join("workspace");
+        config.core.data_dir = dir.path().join("data");
+        config.validate_and_process().unwrap();
+        let log_file = config.telemetry.log_file.clone().unwrap();
+        assert!(log_file.is_absolute());
+        assert!(log_file.ends_with(".rove/logs/rove.log"));
+
+        config.telemetry.max_size_mb = 0;
+        assert!(config.validate_and_process().is_err());
+    }
+
+    #[test]
+    fn test_provider_priority() {
+        let mut config: Config = toml::from_str(
+            r#"
+[core]
+workspace = "~/projects"
+
+[llm]
+default_provider = "ollama"
+provider_priority = ["anthropic", "openai", "ollama"]
+
+[tools]
+
+[plugins]
+
+[security]
+"#,
+        )
+        .unwrap();
+        assert_eq!(
+            config.llm.provider_priority,
+            vec!["anthropic", "openai", "ollama"]
+        );
+        assert!(Config::default_config().llm.provider_priority.is_empty());
+
+        let dir = tempfile::tempdir().unwrap();
+        config.core.workspace = dir.path().join("workspace");
+        config.core.data_dir = dir.path().join("data");
+        config.validate_and_process().unwrap();
+
+        config.llm.provider_priority = vec!["anthropic".to_string(), "claude".to_string()];
+        let err = config.validate_and_process().unwrap_err();
+        assert!(err.to_string().contains("'claude'"));
+
+        config.llm.provider_priority = vec!["openai".to_string(), "openai".to_string()];
+        assert!(config.validate_and_process().is_err());
+    }
+
+    #[test]
+    fn test_bulk_plugin_enable_persists_in_one_write() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        std::fs::write(
+            &path,
+            format!(
+                r#"
+[core]
+workspace = "{}"
+data_dir = "{}"
+
+[llm]
+default_provider = "ollama"
+
+[tools]
+
+[plugins]
+terminal = false
+git = false
+
+[security]
+max_risk_tier = 1
+"#,
+                dir.path().join("workspace").display(),
+                dir.path().join("data").display()
+            ),
+        )
+        .unwrap();
+
+        let mut config = Config::load_from_path(&path).unwrap();
+        assert!(!config.plugins.screenshot);
+        for id in TOGGLEABLE_PLUGINS {
+            config.plugins.set_enabled(id, true).unwrap();
+        }
+        config.save_plugins(&path).unwrap();
+
+        let reloaded = Config::load_from_path(&path).unwrap();
+        assert!(reloaded.plugins.flags().iter().all(|(_, enabled)| *enabled));
+        // Untouched settings survive, and no temp file is left behind
+        assert_eq!(reloaded.security.max_risk_tier, 1);
+        assert!(!dir.path().join("config.toml.tmp").exists());
+
+        let err = config.plugins.set_enabled("telegram", false).unwrap_err();
+        assert!(err.to_string().contains("Unknown plugin 'telegram'"));
+    }
+
+    #[test]
+    fn test_effective_config_marks_value_sources() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        let contents = format!(
+            r#"
+[core]
+workspace = "{}"
+data_dir = "{}"
+
+[llm]
+default_provider = "openai"
+
+[llm.openai]
+model = "gpt-4o-mini"
+
+[tools]
+
+[plugins]
+
+[security]
+"#,
+            dir.path().join("workspace").display(),
+            dir.path().join("data").display()
+        );
+        std::fs::write(&path, &contents).unwrap();
+        let config = Config::load_from_path(&path).unwrap();
+
+        let effective = config.effective(&contents).unwrap();
+
+        let source = |key: &str| effective.sources.get(key).copied();
+        assert_eq!(source("llm.default_provider"), Some(ValueSource::File));
+        assert_eq!(source("llm.openai.model"), Some(ValueSource::File));
+        assert_eq!(source("llm.openai.base_url"), Some(ValueSource::Default));
+        assert_eq!(source("security.max_risk_tier"), Some(ValueSource::Default));
+
+        let dump = effective.to_annotated_toml();
+        assert!(dump.contains("default_provider = \"openai\"\n"), "{}", dump);
+        assert!(dump.contains("\nmodel = \"gpt-4o-mini\"\n"), "{}", dump);
+        assert!(
+            dump.contains("confirm_tier1_delay = 10  # default\n"),
+            "{}",
+            dump
+        );
+
+        // The dump is itself a valid config
+        let reparsed: Config = toml::from_str(&dump).unwrap();
+        assert_eq!(reparsed.llm.default_provider, "openai");
+        assert_eq!(reparsed.security.confirm_tier1_delay, 10);
+    }
+
+    #[test]
+    fn test_effective_config_marks_active_workspace_override() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let mut config = config_with_workspaces(temp_dir.path());
+        let contents = format!(
+            "[core]\nworkspace = \"{}\"\n",
+            config.core.workspace.display()
+        );
+
+        let effective = config.effective(&contents).unwrap();
+        assert_eq!(
+            effective.sources.get("core.workspace"),
+            Some(&ValueSource::File)
+        );
+
+        let backend = config.use_workspace("backend").unwrap().to_path_buf();
+        let effective = config.effective(&contents).unwrap();
+        assert_eq!(
+            effective.sources.get("core.workspace"),
+            Some(&ValueSource::Override)
+        );
+        assert_eq!(
+            effective.config["core"]["workspace"].as_str(),
+            Some(backend.to_str().unwrap())
+        );
+        let dump = effective.to_annotated_toml();
+        let line = format!("workspace = \"{}\"  # override\n", backend.display());
+        assert!(dump.contains(&line), "{}", dump);
+        assert_eq!(
+            serde_json::to_value(&effective).unwrap()["sources"]["core.workspace"],
+            "override"
+        );
+    }
 }
@@ -0,0 +1,491 @@
+//! Tier 1 confirmation countdown
+//!
+//! Before a Tier 1 (write/reversible) operation runs, `rove run` shows a
+//! countdown of `security.confirm_tier1_delay` seconds on stderr. Pressing
+//! Esc or `n` during the countdown aborts, and the operation is denied. When
+//! the countdown runs out the operation proceeds.
+//!
+//! When stdin is not a terminal nobody can press a key, so the countdown is
+//! announced once and the operation proceeds after the delay. Key presses are
+//! read through `KeyInput`, which tests replace with a scripted source.
+//!
+//! Tier 2 operations need an explicit "y" typed at the terminal. When stdin
+//! is not a terminal it is input piped into `rove run`, not an answer, so
+//! Tier 2 operations are denied without reading it.
+//!
+//! The agent asks a `Confirmer` before Tier 1 and Tier 2 tool calls, as
+//! selected by a `ConfirmationPolicy`. A confirmer decides over any
+//! transport: `TerminalConfirmer` (the countdown and a y/N prompt),
+//! `ws_client::WebSocketConfirmer` (the connected UI) or `AutoApprove`.
+
+use crate::config::{CountdownStyle, SecurityConfig};
+use crate::risk_assessor::RiskTier;
+use async_trait::async_trait;
+use std::io::{self, BufRead, IsTerminal, Write};
+use std::time::{Duration, Instant};
+
+/// Escape key
+const ESC: u8 = 0x1b;
+
+/// Outcome of a confirmation countdown
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Decision {
+    Proceed,
+    Abort,
+}
+
+/// Source of key presses for the countdown
+pub trait KeyInput {
+    /// Whether a user can press keys, i.e. stdin is a terminal
+    fn is_interactive(&self) -> bool;
+
+    /// Wait up to `timeout` for a key press
+    fn read_key(&mut self, timeout: Duration) -> io::Result<Option<u8>>;
+}
+
+/// Whether `key` aborts the countdown
+pub fn is_abort_key(key: u8) -> bool {
+    matches!(key, ESC | b'n' | b'N')
+}
+
+/// Countdown shown before a Tier 1 operation
+#[derive(Debug, Clone, Copy)]
+pub struct Countdown {
+    delay: Duration,
+    style: CountdownStyle,
+}
+
+impl Countdown {
+    pub fn new(delay: Duration, style: CountdownStyle) -> Self {
+        Self { delay, style }
+    }
+
+    /// Run the countdown for `operation`, rendering to `out`
+    pub fn run(&self, operation: &str, input: &mut dyn KeyInput, out: &mut dyn Write) -> Decision {
+        if !input.is_interactive() {
+            let _ = writeln!(
+                out,
+                "{} runs in {}s (stdin is not a terminal, proceeding automatically)",
+                operation,
+                whole_seconds(self.delay)
+            );
+            std::thread::sleep(self.delay);
+            return Decision::Proceed;
+        }
+
+        if self.style == CountdownStyle::Plain {
+            let _ = writeln!(
+                out,
+                "{} runs in {}s. Press Esc or n to abort.",
+                operation,
+                whole_seconds(self.delay)
+            );
+        }
+
+        let deadline = Instant::now() + self.delay;
+        loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                break;
+            }
+
+            if self.style == CountdownStyle::Live {
+                let _ = write!(
+                    out,
+                    "\r{} runs in {}s. Press Esc or n to abort. ",
+                    operation,
+                    whole_seconds(remaining)
+                );
+                let _ = out.flush();
+            }
+
+            let slice = remaining.min(Duration::from_secs(1));
+            match input.read_key(slice) {
+                Ok(Some(key)) if is_abort_key(key) => {
+                    if self.style == CountdownStyle::Live {
+                        let _ = writeln!(out);
+                    }
+                    let _ = writeln!(out, "Aborted {}", operation);
+                    return Decision::Abort;
+                }
+                Ok(_) => {}
+                Err(e) => {
+                    // Without working input the countdown just runs out
+                    tracing::warn!("Failed to read confirmation key: {}", e);
+                    std::thread::sleep(slice);
+                }
+            }
+        }
+
+        if self.style == CountdownStyle::Live {
+            let _ = writeln!(out);
+        }
+        Decision::Proceed
+    }
+}
+
+/// Seconds left in `duration`, rounded up so the countdown never shows 0s
+fn whole_seconds(duration: Duration) -> u128 {
+    duration.as_millis().div_ceil(1000)
+}
+
+/// Which tool calls the agent asks its `Confirmer` about
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConfirmationPolicy {
+    /// Ask before Tier 1 calls; otherwise they run without asking
+    pub tier1: bool,
+
+    /// Ask before every Tier 2 call; otherwise only before those planned by
+    /// a cloud provider
+    pub explicit_tier2: bool,
+
+    /// Have a local provider write the summary shown for a Tier 2 call,
+    /// even when the task runs on a cloud provider
+    pub summarize_tier2_locally: bool,
+}
+
+impl Default for ConfirmationPolicy {
+    fn default() -> Self {
+        Self {
+            tier1: true,
+            explicit_tier2: true,
+            summarize_tier2_locally: false,
+        }
+    }
+}
+
+impl From<&SecurityConfig> for ConfirmationPolicy {
+    fn from(security: &SecurityConfig) -> Self {
+        Self {
+            tier1: security.confirm_tier1,
+            explicit_tier2: security.require_explicit_tier2,
+            summarize_tier2_locally: security.summarize_tier2_locally,
+        }
+    }
+}
+
+/// Decides whether an operation that needs confirmation may run
+///
+/// A Tier 1 operation should proceed unless someone objects in time, like
+/// the countdown; a Tier 2 operation only on an explicit approval.
+#[async_trait]
+pub trait Confirmer: Send + Sync {
+    /// Decide on `operation` (the tool and its arguments) of risk `tier`;
+    /// `reason` says what it does or why it needs confirmation
+    async fn confirm(&self, operation: &str, tier: RiskTier, reason: &str) -> Decision;
+}
+
+/// Confirms on the local terminal: the countdown for Tier 1, a y/N prompt
+/// on stdin for Tier 2
+#[derive(Debug, Clone, Copy)]
+pub struct TerminalConfirmer {
+    countdown: Countdown,
+}
+
+impl TerminalConfirmer {
+    pub fn new(countdown: Countdown) -> Self {
+        Self { countdown }
+    }
+}
+
+#[async_trait]
+impl Confirmer for TerminalConfirmer {
+    async fn confirm(&self, operation: &str, tier: RiskTier, reason: &str) -> Decision {
+        let countdown = self.countdown;
+        let operation = operation.to_string();
+        let reason = reason.to_string();
+        let decision = tokio::task::spawn_blocking(move || match tier {
+            RiskTier::Tier0 => Decision::Proceed,
+            RiskTier::Tier1 => {
+                let mut input = TerminalInput::new();
+                countdown.run(&operation, &mut input, &mut io::stderr())
+            }
+            RiskTier::Tier2 => {
+                let stdin = io::stdin();
+                let interactive = stdin.is_terminal();
+                prompt_tier2(&operation, &reason, interactive, &mut stdin.lock())
+            }
+        })
+        .await;
+        decision.unwrap_or_else(|e| {
+            tracing::error!("Terminal confirmation failed: {}", e);
+            Decision::Abort
+        })
+    }
+}
+
+/// Show an operation on stderr and ask for an explicit "y" on `input`
+///
+/// Anything other than "y"/"yes" (including a closed stdin) aborts, and so
+/// does a stdin that is not a terminal: see `read_yes_no`.
+fn prompt_tier2(
+    operation: &str,
+    reason: &str,
+    interactive: bool,
+    input: &mut dyn BufRead,
+) -> Decision {
+    eprintln!("Tier 2 operation: {}", operation);
+    eprintln!("  {}", reason);
+    if read_yes_no("Run this operation?", interactive, input) {
+        Decision::Proceed
+    } else {
+        Decision::Abort
+    }
+}
+
+/// Ask `question` on stderr and read the answer from stdin
+///
+/// Only "y"/"yes" typed at a terminal is a yes; anything else, including a
+/// closed stdin, is a no. Without a terminal the answer is no.
+pub fn ask_yes_no(question: &str) -> bool {
+    let stdin = io::stdin();
+    let interactive = stdin.is_terminal();
+    read_yes_no(question, interactive, &mut stdin.lock())
+}
+
+/// Ask `question` on stderr and read the answer from `input`
+///
+/// When `interactive` is false, stdin is piped input rather than a person:
+/// a `y` line in it must not approve anything, and its lines may be content
+/// meant for `write_file`. The answer is then no, and `input` is not read.
+fn read_yes_no(question: &str, interactive: bool, input: &mut dyn BufRead) -> bool {
+    eprint!("{} [y/N]: ", question);
+    if !interactive {
+        eprintln!("n (stdin is not a terminal)");
+        return false;
+    }
+    let _ = io::stderr().flush();
+
+    let mut answer = String::new();
+    if input.read_line(&mut answer).is_err() {
+        return false;
+    }
+    matches!(answer.trim().to_lowercase().as_str(), "y" | "yes")
+}
+
+/// Lets every operation run without asking anyone
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AutoApprove;
+
+#[async_trait]
+impl Confirmer for AutoApprove {
+    async fn confirm(&self, _operation: &str, _tier: RiskTier, _reason: &str) -> Decision {
+        Decision::Proceed
+    }
+}
+
+/// Key presses from the controlling terminal
+///
+/// On Unix the terminal is switched to non-canonical mode while this value
+/// lives, so single keys arrive without Enter; the previous mode is restored
+/// on drop. Elsewhere stdin is treated as non-interactive.
+pub struct TerminalInput {
+    #[cfg(unix)]
+    saved: Option<nix::sys::termios::Termios>,
+}
+
+impl TerminalInput {
+    pub fn new() -> Self {
+        Self {
+            #[cfg(unix)]
+            saved: enable_raw_mode(),
+        }
+    }
+}
+
+impl Default for TerminalInput {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl KeyInput for TerminalInput {
+    #[cfg(unix)]
+    fn is_interactive(&self) -> bool {
+        self.saved.is_some()
+    }
+
+    #[cfg(not(unix))]
+    fn is_interactive(&self) -> bool {
+        false
+    }
+
+    #[cfg(unix)]
+    fn read_key(&mut self, timeout: Duration) -> io::Result<Option<u8>> {
+        use io::Read;
+        use nix::poll::{poll, PollFd, PollFlags, PollTimeout};
+        use std::os::fd::AsFd;
+
+        let stdin = io::stdin();
+        let mut fds = [PollFd::new(stdin.as_fd(), PollFlags::POLLIN)];
+        let millis = timeout.as_millis().min(u16::MAX as u128) as u16;
+        if poll(&mut fds, PollTimeout::from(millis))? == 0 {
+            return Ok(None);
+        }
+
+        let mut key = [0u8; 1];
+        let read = stdin.lock().read(&mut key)?;
+        Ok((read == 1).then_some(key[0]))
+    }
+
+    #[cfg(not(unix))]
+    fn read_key(&mut self, timeout: Duration) -> io::Result<Option<u8>> {
+        std::thread::sleep(timeout);
+        Ok(None)
+    }
+}
+
+#[cfg(unix)]
+impl Drop for TerminalInput {
+    fn drop(&mut self) {
+        use nix::sys::termios::{tcsetattr, SetArg};
+        use std::os::fd::AsFd;
+
+        if let Some(ref saved) = self.saved {
+            let _ = tcsetattr(io::stdin().as_fd(), SetArg::TCSANOW, saved);
+        }
+    }
+}
+
+/// Turn off line buffering and echo on stdin, returning the previous mode
+///
+/// Returns `None` when stdin is not a terminal or its mode can't be changed.
+#[cfg(unix)]
+fn enable_raw_mode() -> Option<nix::sys::termios::Termios> {
+    use nix::sys::termios::{tcgetattr, tcsetattr, LocalFlags, SetArg};
+    use std::io::IsTerminal;
+    use std::os::fd::AsFd;
+
+    let stdin = io::stdin();
+    if !stdin.is_terminal() {
+        return None;
+    }
+
+    let switch = || -> nix::Result<nix::sys::termios::Termios> {
+        let original = tcgetattr(stdin.as_fd())?;
+        let mut raw = original.clone();
+        raw.local_flags
+            .remove(LocalFlags::ICANON | LocalFlags::ECHO);
+        tcsetattr(stdin.as_fd(), SetArg::TCSANOW, &raw)?;
+        Ok(original)
+    };
+    switch()
+        .map_err(|e| tracing::warn!("Failed to switch terminal mode: {}", e))
+        .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::VecDeque;
+
+    /// Scripted key presses; `None` entries are reads that time out
+    struct MockInput {
+        interactive: bool,
+        keys: VecDeque<Option<u8>>,
+        reads: usize,
+    }
+
+    impl MockInput {
+        fn new(interactive: bool, keys: &[Option<u8>]) -> Self {
+            Self {
+                interactive,
+                keys: keys.iter().copied().collect(),
+                reads: 0,
+            }
+        }
+    }
+
+    impl KeyInput for MockInput {
+        fn is_interactive(&self) -> bool {
+            self.interactive
+        }
+
+        fn read_key(&mut self, timeout: Duration) -> io::Result<Option<u8>> {
+            self.reads += 1;
+            match self.keys.pop_front() {
+                Some(key) => Ok(key),
+                None => {
+                    std::thread::sleep(timeout);
+                    Ok(None)
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_abort_before_timeout() {
+        for key in [ESC, b'n', b'N'] {
+            let countdown = Countdown::new(Duration::from_secs(10), CountdownStyle::Live);
+            let mut input = MockInput::new(true, &[Some(b'x'), Some(key)]);
+            let mut out = Vec::new();
+
+            let start = Instant::now();
+            let decision = countdown.run("write_file", &mut input, &mut out);
+
+            assert_eq!(decision, Decision::Abort);
+            assert!(start.elapsed() < Duration::from_secs(1));
+            assert_eq!(input.reads, 2);
+            let rendered = String::from_utf8(out).unwrap();
+            assert!(rendered.contains("\rwrite_file runs in 10s"));
+            assert!(rendered.ends_with("Aborted write_file\n"));
+        }
+    }
+
+    #[test]
+    fn test_proceeds_when_countdown_runs_out() {
+        let countdown = Countdown::new(Duration::from_millis(200), CountdownStyle::Plain);
+        let mut input = MockInput::new(true, &[Some(b'y')]);
+        let mut out = Vec::new();
+
+        let decision = countdown.run("write_file", &mut input, &mut out);
+
+        assert_eq!(decision, Decision::Proceed);
+        let rendered = String::from_utf8(out).unwrap();
+        assert_eq!(
+            rendered,
+            "write_file runs in 1s. Press Esc or n to abort.\n"
+        );
+    }
+
+    #[test]
+    fn test_auto_proceed_on_non_tty() {
+        let delay = Duration::from_millis(100);
+        let countdown = Countdown::new(delay, CountdownStyle::Live);
+        // An abort key on a non-terminal stdin is never read
+        let mut input = MockInput::new(false, &[Some(ESC)]);
+        let mut out = Vec::new();
+
+        let start = Instant::now();
+        let decision = countdown.run("write_file", &mut input, &mut out);
+
+        assert_eq!(decision, Decision::Proceed);
+        assert!(start.elapsed() >= delay);
+        assert_eq!(input.reads, 0);
+        assert!(String::from_utf8(out)
+            .unwrap()
+            .contains("stdin is not a terminal, proceeding automatically"));
+    }
+
+    #[test]
+    fn test_piped_yes_does_not_approve_tier2() {
+        let mut piped: &[u8] = b"y\nfile content\n";
+
+        let decision = prompt_tier2("delete_file build", "Deletes files", false, &mut piped);
+
+        assert_eq!(decision, Decision::Abort);
+        // The input is left for whoever it was meant for
+        assert_eq!(piped, b"y\nfile content\n");
+    }
+
+    #[test]
+    fn test_typed_yes_approves_tier2() {
+        let mut typed: &[u8] = b"y\n";
+        let decision = prompt_tier2("delete_file build", "Deletes files", true, &mut typed);
+        assert_eq!(decision, Decision::Proceed);
+
+        let mut typed: &[u8] = b"\n";
+        let decision = prompt_tier2("delete_file build", "Deletes files", true, &mut typed);
+        assert_eq!(decision, Decision::Abort);
+    }
+}
@@ -4,6 +4,7 @@
 //! - Ed25519 signature verification for manifests and core tools
 //! - SHA-256 file hashing for integrity verification
 //! - Automatic deletion of compromised files
+//! - Caching of successful file signature checks by file hash
 //!
 //! # Security
 //!
@@ -11,13 +12,16 @@
 //! tampering. All verification failures result in immediate file deletion
 //! to prevent execution of compromised code.
 
-use ed25519_dalek::{Signature, Verifier, VerifyingKey, PUBLIC_KEY_LENGTH, SIGNATURE_LENGTH};
+use ed25519_dalek::{
+    Signature, Signer, SigningKey, Verifier, VerifyingKey, PUBLIC_KEY_LENGTH, SIGNATURE_LENGTH,
+};
 use sdk::errors::EngineError;
 use sha2::{Digest, Sha256};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fs::File;
 use std::io::Read;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::{Arc, Mutex};
 use std::time::{SystemTime, UNIX_EPOCH};
 
@@ -38,6 +42,19 @@ const TEAM_PUBLIC_KEY_BYTES: &[u8] =
 /// for reasonable clock skew between systems.
 const NONCE_WINDOW_SECS: u64 = 30;
 
+/// Most threads `verify_files` hashes with, so a large manifest doesn't
+/// thrash the disk
+pub const MAX_VERIFY_PARALLELISM: usize = 16;
+
+/// Default `verify_files` parallelism: the number of CPUs, capped at
+/// `MAX_VERIFY_PARALLELISM`
+pub fn default_verify_parallelism() -> usize {
+    std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(1)
+        .min(MAX_VERIFY_PARALLELISM)
+}
+
 /// Envelope for secure message transmission
 ///
 /// An envelope contains a message payload along with cryptographic metadata
@@ -98,6 +115,20 @@ impl NonceCache {
     }
 }
 
+/// A file signature that verified successfully against a public key
+///
+/// All fields are lowercase hex. The file hash is still recomputed on every
+/// check, so a cached entry only applies while the file is unchanged.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct VerifiedSignature {
+    /// SHA-256 hash of the file contents
+    pub file_hash: String,
+    /// Ed25519 public key the signature was checked against
+    pub public_key: String,
+    /// Ed25519 signature over the file hash
+    pub signature: String,
+}
+
 /// Cryptographic operations module
 ///
 /// Provides methods for:
@@ -108,6 +139,7 @@ impl NonceCache {
 pub struct CryptoModule {
     team_public_key: VerifyingKey,
     nonce_cache: Arc<Mutex<NonceCache>>,
+    verified_signatures: Arc<Mutex<HashSet<VerifiedSignature>>>,
 }
 
 impl CryptoModule {
@@ -140,6 +172,7 @@ impl CryptoModule {
         Ok(Self {
             team_public_key,
             nonce_cache: Arc::new(Mutex::new(NonceCache::new())),
+            verified_signatures: Arc::new(Mutex::new(HashSet::new())),
         })
     }
 
@@ -149,9 +182,40 @@ impl CryptoModule {
         Self {
             team_public_key: key,
             nonce_cache: Arc::new(Mutex::new(NonceCache::new())),
+            verified_signatures: Arc::new(Mutex::new(HashSet::new())),
         }
     }
 
+    /// Hex-encoded team public key, as stored in `VerifiedSignature`
+    pub fn public_key_hex(&self) -> String {
+        hex::encode(self.team_public_key.to_bytes())
+    }
+
+    /// Seed the signature cache, e.g. with entries loaded from the database
+    ///
+    /// Entries for other public keys are ignored.
+    pub fn preload_verified_signatures(
+        &self,
+        entries: impl IntoIterator<Item = VerifiedSignature>,
+    ) {
+        let key = self.public_key_hex();
+        let mut cache = self
+            .verified_signatures
+            .lock()
+            .expect("verified_signatures lock poisoned");
+        cache.extend(entries.into_iter().filter(|e| e.public_key == key));
+    }
+
+    /// Successful file signature checks so far, for persisting to the database
+    pub fn verified_signatures(&self) -> Vec<VerifiedSignature> {
+        self.verified_signatures
+            .lock()
+            .expect("verified_signatures lock poisoned")
+            .iter()
+            .cloned()
+            .collect()
+    }
+
     /// Whether we're running a production build
     pub fn is_production() -> bool {
         cfg!(feature = "production")
@@ -239,10 +303,58 @@ impl CryptoModule {
         Ok(())
     }
 
+    /// Verify the hashes of `files` on up to `parallelism` threads
+    ///
+    /// Each `(path, expected_hash)` pair is checked as by `verify_file`, so
+    /// every file that fails is deleted. Unlike a serial loop that stops at
+    /// the first failure, all files are checked. Results are in the order of
+    /// `files`. `parallelism` is clamped to `1..=MAX_VERIFY_PARALLELISM`.
+    pub fn verify_files(
+        &self,
+        files: &[(PathBuf, String)],
+        parallelism: usize,
+    ) -> Vec<Result<(), EngineError>> {
+        let workers = parallelism
+            .clamp(1, MAX_VERIFY_PARALLELISM)
+            .min(files.len());
+        if workers <= 1 {
+            return files
+                .iter()
+                .map(|(path, hash)| self.verify_file(path, hash))
+                .collect();
+        }
+
+        // Workers take the next unverified file until none are left
+        let next = AtomicUsize::new(0);
+        let mut results: Vec<(usize, Result<(), EngineError>)> = std::thread::scope(|scope| {
+            let handles: Vec<_> = (0..workers)
+                .map(|_| {
+                    scope.spawn(|| {
+                        let mut done = Vec::new();
+                        loop {
+                            let i = next.fetch_add(1, Ordering::Relaxed);
+                            let Some((path, hash)) = files.get(i) else {
+                                break done;
+                            };
+                            done.push((i, self.verify_file(path, hash)));
+                        }
+                    })
+                })
+                .collect();
+            handles
+                .into_iter()
+                .flat_map(|handle| handle.join().expect("verification thread panicked"))
+                .collect()
+        });
+        results.sort_by_key(|(i, _)| *i);
+        results.into_iter().map(|(_, result)| result).collect()
+    }
+
     /// Verify an individual tool's Ed25519 signature
     ///
     /// Computes the SHA-256 hash of the file and verifies the signature
-    /// against that hash string.
+    /// against that hash string. If the same hash and signature already
+    /// verified against the team key, the Ed25519 check is skipped.
     pub fn verify_file_signature(
         &self,
         path: &Path,
@@ -256,6 +368,23 @@ impl CryptoModule {
         // Parse signature
         let signature = self.parse_signature(signature_hex)?;
 
+        let entry = VerifiedSignature {
+            file_hash: file_hash.clone(),
+            public_key: self.public_key_hex(),
+            signature: hex::encode(signature.to_bytes()),
+        };
+        let mut cache = self
+            .verified_signatures
+            .lock()
+            .expect("verified_signatures lock poisoned");
+        if cache.contains(&entry) {
+            tracing::debug!(
+                "File signature already verified for unchanged file: {}",
+                path.display()
+            );
+            return Ok(());
+        }
+
         // Verify signature against file hash
         self.team_public_key
             .verify(file_hash.as_bytes(), &signature)
@@ -268,6 +397,7 @@ impl CryptoModule {
                 EngineError::InvalidSignature
             })?;
 
+        cache.insert(entry);
         tracing::info!("File signature verified: {}", path.display());
         Ok(())
     }
@@ -388,6 +518,10 @@ impl CryptoModule {
     /// - "ed25519:hex_string"
     /// - Raw hex string
     fn parse_signature(&self, sig_str: &str) -> Result<Signature, EngineError> {
+        Self::decode_signature(sig_str)
+    }
+
+    fn decode_signature(sig_str: &str) -> Result<Signature, EngineError> {
         // Remove "ed25519:" prefix if present
         let hex = sig_str.strip_prefix("ed25519:").unwrap_or(sig_str);
 
@@ -469,6 +603,78 @@ impl CryptoModule {
         let canonical = Self::canonicalize_manifest(manifest_json)?;
         self.verify_manifest(&canonical, signature)
     }
+
+    /// Check that a manifest was signed no longer than `max_age` before `now`
+    ///
+    /// Guards against an old signed manifest, e.g. one that still lists since
+    /// revoked plugins, being put back in place. `signed_at` is the RFC 3339
+    /// timestamp written by the signer.
+    ///
+    /// # Errors
+    ///
+    /// Returns `EngineError::Config` if `signed_at` is missing or not a
+    /// timestamp, or if it is older than `max_age`.
+    pub fn check_manifest_freshness(
+        manifest: &serde_json::Value,
+        max_age: std::time::Duration,
+        now: chrono::DateTime<chrono::Utc>,
+    ) -> Result<(), EngineError> {
+        let signed_at = manifest
+            .get("signed_at")
+            .and_then(|s| s.as_str())
+            .ok_or_else(|| EngineError::Config("No signed_at in manifest".to_string()))?;
+        let signed_at = chrono::DateTime::parse_from_rfc3339(signed_at).map_err(|e| {
+            EngineError::Config(format!("Invalid signed_at '{}': {}", signed_at, e))
+        })?;
+
+        let age = now.signed_duration_since(signed_at);
+        if age.to_std().is_ok_and(|age| age > max_age) {
+            tracing::error!(
+                "Manifest signed at {} is {} days old (max {})",
+                signed_at,
+                age.num_days(),
+                max_age.as_secs() / 86400
+            );
+            return Err(EngineError::Config(format!(
+                "Manifest signed at {} is older than the allowed {} days",
+                signed_at,
+                max_age.as_secs() / 86400
+            )));
+        }
+        Ok(())
+    }
+
+    /// Sign a JSON document with a local key
+    ///
+    /// The document is canonicalized the same way as manifests, so any
+    /// existing `signature` field is ignored. Returns `ed25519:<hex>`, ready to
+    /// be stored in the document's `signature` field.
+    pub fn sign_document(document_json: &[u8], key: &SigningKey) -> Result<String, EngineError> {
+        let canonical = Self::canonicalize_manifest(document_json)?;
+        let signature = key.sign(&canonical);
+        Ok(format!("ed25519:{}", hex::encode(signature.to_bytes())))
+    }
+
+    /// Verify the `signature` field of a JSON document signed with `sign_document`
+    ///
+    /// Unlike `verify_manifest_file` this checks against the given key rather
+    /// than the team key, and never accepts placeholder signatures.
+    pub fn verify_document(document_json: &[u8], key: &VerifyingKey) -> Result<(), EngineError> {
+        let value: serde_json::Value = serde_json::from_slice(document_json)
+            .map_err(|e| EngineError::Config(format!("Invalid document JSON: {}", e)))?;
+
+        let signature = value
+            .get("signature")
+            .and_then(|s| s.as_str())
+            .ok_or_else(|| EngineError::Config("No signature in document".to_string()))?;
+        let signature = Self::decode_signature(signature)?;
+
+        let canonical = Self::canonicalize_manifest(document_json)?;
+        key.verify(&canonical, &signature).map_err(|e| {
+            tracing::warn!("Document signature verification failed: {}", e);
+            EngineError::InvalidSignature
+        })
+    }
 }
 
 #[cfg(test)]
@@ -740,6 +946,59 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_verify_file_signature_cached_for_unchanged_file() {
+        use ed25519_dalek::Signer;
+
+        let (signing_key, crypto) = test_crypto();
+
+        let mut temp_file = NamedTempFile::new().unwrap();
+        temp_file.write_all(b"plugin binary data").unwrap();
+        temp_file.flush().unwrap();
+
+        let hash = crypto.compute_file_hash(temp_file.path()).unwrap();
+        let sig_hex = hex::encode(signing_key.sign(hash.as_bytes()).to_bytes());
+
+        crypto
+            .verify_file_signature(temp_file.path(), &sig_hex)
+            .unwrap();
+        let cached = crypto.verified_signatures();
+        assert_eq!(cached.len(), 1);
+        assert_eq!(cached[0].file_hash, hash);
+
+        // A fresh module seeded with a bogus cached signature accepts it
+        // without running Ed25519, proving the second check short-circuits
+        let (_, fresh) = test_crypto();
+        let bogus = hex::encode([7u8; SIGNATURE_LENGTH]);
+        fresh.preload_verified_signatures([VerifiedSignature {
+            file_hash: hash,
+            public_key: fresh.public_key_hex(),
+            signature: bogus.clone(),
+        }]);
+        assert!(fresh
+            .verify_file_signature(temp_file.path(), &bogus)
+            .is_ok());
+
+        // Once the file changes its hash no longer matches the cache
+        temp_file.write_all(b" tampered").unwrap();
+        temp_file.flush().unwrap();
+        assert!(matches!(
+            fresh.verify_file_signature(temp_file.path(), &bogus),
+            Err(EngineError::InvalidSignature)
+        ));
+    }
+
+    #[test]
+    fn test_preload_ignores_other_keys() {
+        let (_, crypto) = test_crypto();
+        crypto.preload_verified_signatures([VerifiedSignature {
+            file_hash: "00".repeat(32),
+            public_key: "ff".repeat(32),
+            signature: "11".repeat(64),
+        }]);
+        assert!(crypto.verified_signatures().is_empty());
+    }
+
     #[test]
     fn test_verify_manifest_file_placeholder_dev() {
         let (_, crypto) = test_crypto();
@@ -793,6 +1052,28 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_manifest_freshness() {
+        let now = chrono::DateTime::parse_from_rfc3339("2025-03-01T00:00:00Z")
+            .unwrap()
+            .with_timezone(&chrono::Utc);
+        let max_age = std::time::Duration::from_secs(30 * 86400);
+        let signed = |at: &str| serde_json::json!({ "plugins": [], "signed_at": at });
+
+        // Python's isoformat() output, ten days old
+        let fresh = signed("2025-02-19T08:15:30.123456Z");
+        assert!(CryptoModule::check_manifest_freshness(&fresh, max_age, now).is_ok());
+
+        let stale = signed("2024-12-01T00:00:00Z");
+        let err = CryptoModule::check_manifest_freshness(&stale, max_age, now).unwrap_err();
+        assert!(err.to_string().contains("older than the allowed 30 days"));
+
+        let placeholder = signed("local-development");
+        assert!(CryptoModule::check_manifest_freshness(&placeholder, max_age, now).is_err());
+        let unsigned = serde_json::json!({ "plugins": [] });
+        assert!(CryptoModule::check_manifest_freshness(&unsigned, max_age, now).is_err());
+    }
+
     #[test]
     fn test_envelope_valid() {
         use ed25519_dalek::Signer;
@@ -873,4 +1154,42 @@ mod tests {
             Err(EngineError::NonceReused)
         ));
     }
+
+    #[test]
+    fn test_parallel_verification_matches_serial() {
+        let (_, crypto) = test_crypto();
+        let dir = tempfile::TempDir::new().unwrap();
+
+        // Every third file is tampered with
+        let write_files = || -> Vec<(PathBuf, String)> {
+            (0..12)
+                .map(|i| {
+                    let path = dir.path().join(format!("tool-{}.bin", i));
+                    let content = format!("tool {}", i);
+                    std::fs::write(&path, &content).unwrap();
+                    let hash = if i % 3 == 0 {
+                        CryptoModule::compute_hash(b"something else")
+                    } else {
+                        CryptoModule::compute_hash(content.as_bytes())
+                    };
+                    (path, hash)
+                })
+                .collect()
+        };
+        let outcomes = |results: Vec<Result<(), EngineError>>| -> Vec<bool> {
+            results.iter().map(Result::is_ok).collect()
+        };
+
+        let files = write_files();
+        let serial = outcomes(crypto.verify_files(&files, 1));
+        let files = write_files();
+        let parallel = outcomes(crypto.verify_files(&files, 4));
+
+        assert_eq!(serial, parallel);
+        for (i, (path, _)) in files.iter().enumerate() {
+            assert_eq!(serial[i], i % 3 != 0);
+            // Files that failed were deleted
+            assert_eq!(path.exists(), i % 3 != 0);
+        }
+    }
 }
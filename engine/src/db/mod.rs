@@ -14,12 +14,101 @@ use tracing::{debug, info};
 
 pub mod memory;
 pub mod plugins;
+pub mod retention;
+pub mod signatures;
 pub mod tasks;
 
 // Re-export commonly used types
 pub use memory::{EpisodicMemory, MemoryEntry};
 pub use plugins::{Plugin, PluginRepository};
-pub use tasks::{StepType, Task, TaskRepository, TaskStatus, TaskStep};
+pub use retention::{prune, spawn_retention_job, PruneReport};
+pub use signatures::SignatureCacheRepository;
+pub use tasks::{
+    CostSummary, FailureReason, StepType, Task, TaskFailure, TaskRepository, TaskStatus, TaskStep,
+};
+
+/// A schema migration
+struct Migration {
+    name: &'static str,
+    sql: &'static str,
+    /// `(table, column)` the migration adds. SQLite has no ADD COLUMN IF NOT
+    /// EXISTS, so the migration is skipped once the column exists.
+    adds_column: Option<(&'static str, &'static str)>,
+}
+
+/// All migrations, in the order they run
+const MIGRATIONS: &[Migration] = &[
+    Migration {
+        name: "001_initial.sql",
+        sql: include_str!("../../migrations/001_initial.sql"),
+        adds_column: None,
+    },
+    Migration {
+        name: "002_fts_memory.sql",
+        sql: include_str!("../../migrations/002_fts_memory.sql"),
+        adds_column: None,
+    },
+    Migration {
+        name: "003_task_failures.sql",
+        sql: include_str!("../../migrations/003_task_failures.sql"),
+        adds_column: None,
+    },
+    Migration {
+        name: "004_verified_signatures.sql",
+        sql: include_str!("../../migrations/004_verified_signatures.sql"),
+        adds_column: None,
+    },
+    Migration {
+        name: "005_task_usage.sql",
+        sql: include_str!("../../migrations/005_task_usage.sql"),
+        adds_column: Some(("tasks", "total_tokens")),
+    },
+    Migration {
+        name: "006_task_summary.sql",
+        sql: include_str!("../../migrations/006_task_summary.sql"),
+        adds_column: Some(("tasks", "summary")),
+    },
+    Migration {
+        name: "007_step_approval.sql",
+        sql: include_str!("../../migrations/007_step_approval.sql"),
+        adds_column: Some(("task_steps", "approval")),
+    },
+    Migration {
+        name: "008_task_request_id.sql",
+        sql: include_str!("../../migrations/008_task_request_id.sql"),
+        adds_column: Some(("tasks", "request_id")),
+    },
+    Migration {
+        name: "009_api_tokens.sql",
+        sql: include_str!("../../migrations/009_api_tokens.sql"),
+        adds_column: None,
+    },
+    Migration {
+        name: "010_rate_limits_id.sql",
+        sql: include_str!("../../migrations/010_rate_limits_id.sql"),
+        adds_column: Some(("rate_limits", "id")),
+    },
+    Migration {
+        name: "011_verified_signatures_mac.sql",
+        sql: include_str!("../../migrations/011_verified_signatures_mac.sql"),
+        adds_column: Some(("verified_signatures", "mac")),
+    },
+    Migration {
+        name: "012_task_source.sql",
+        sql: include_str!("../../migrations/012_task_source.sql"),
+        adds_column: Some(("tasks", "remote")),
+    },
+    Migration {
+        name: "013_api_token_rate_limit_source.sql",
+        sql: include_str!("../../migrations/013_api_token_rate_limit_source.sql"),
+        adds_column: Some(("api_tokens", "rate_limit_source")),
+    },
+    Migration {
+        name: "014_task_owner.sql",
+        sql: include_str!("../../migrations/014_task_owner.sql"),
+        adds_column: Some(("tasks", "owner_pid")),
+    },
+];
 
 /// Database connection pool
 pub struct Database {
@@ -85,22 +174,39 @@ impl Database {
     /// Run database migrations
     ///
     /// This reads and executes all SQL files from the migrations directory.
-    /// Migrations are idempotent and can be run multiple times safely.
+    /// Migrations are idempotent and can be run multiple times safely. They
+    /// all run on one connection, so each one sees the schema left by the
+    /// previous ones.
     ///
     /// Requirements: 12.7
     async fn run_migrations(&self) -> Result<()> {
         info!("Running database migrations");
 
-        // Execute queries
-        sqlx::raw_sql(include_str!("../../migrations/001_initial.sql"))
-            .execute(&self.pool)
-            .await
-            .context("Failed to execute migration 001_initial.sql")?;
-
-        sqlx::raw_sql(include_str!("../../migrations/002_fts_memory.sql"))
-            .execute(&self.pool)
+        let mut conn = self
+            .pool
+            .acquire()
             .await
-            .context("Failed to execute migration 002_fts_memory.sql")?;
+            .context("Failed to acquire a connection for migrations")?;
+
+        for migration in MIGRATIONS {
+            if let Some((table, column)) = migration.adds_column {
+                let has_column: i64 =
+                    sqlx::query_scalar("SELECT COUNT(*) FROM pragma_table_info(?) WHERE name = ?")
+                        .bind(table)
+                        .bind(column)
+                        .fetch_one(&mut *conn)
+                        .await
+                        .with_context(|| format!("Failed to inspect {} table", table))?;
+                if has_column > 0 {
+                    continue;
+                }
+            }
+
+            sqlx::raw_sql(migration.sql)
+                .execute(&mut *conn)
+                .await
+                .with_context(|| format!("Failed to execute migration {}", migration.name))?;
+        }
 
         info!("Database migrations completed successfully");
         Ok(())
@@ -163,6 +269,12 @@ impl Database {
     pub fn plugins(&self) -> PluginRepository {
         PluginRepository::new(self.pool.clone())
     }
+
+    /// Create a signature verification cache repository whose rows are
+    /// authenticated with `key` (see `signatures::load_or_create_cache_key`)
+    pub fn signatures(&self, key: [u8; 32]) -> SignatureCacheRepository {
+        SignatureCacheRepository::new(self.pool.clone(), key)
+    }
 }
 
 #[cfg(test)]
@@ -207,6 +319,28 @@ mod tests {
         assert!(tables.contains(&"plugins".to_string()));
         assert!(tables.contains(&"secrets_cache".to_string()));
         assert!(tables.contains(&"rate_limits".to_string()));
+        assert!(tables.contains(&"verified_signatures".to_string()));
+        assert!(tables.contains(&"api_tokens".to_string()));
+
+        db.close().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_migrations_rerun_on_existing_database() {
+        let temp_dir = TempDir::new().unwrap();
+        let db_path = temp_dir.path().join("test.db");
+
+        let first = Database::new(&db_path).await.unwrap();
+        first.close().await.unwrap();
+        let db = Database::new(&db_path).await.unwrap();
+
+        let columns: Vec<String> =
+            sqlx::query_scalar("SELECT name FROM pragma_table_info('tasks')")
+                .fetch_all(db.pool())
+                .await
+                .unwrap();
+        assert!(columns.contains(&"total_tokens".to_string()));
+        assert!(columns.contains(&"cost".to_string()));
 
         db.close().await.unwrap();
     }
@@ -0,0 +1,259 @@
+/// Data retention
+///
+/// Task history, its audit log and rate-limit entries grow with every run.
+/// The retention job prunes rows older than the ages configured under
+/// `[retention]`:
+///
+/// - `tasks`, together with their steps and failures (deleted by cascade),
+///   after `task_max_age_days` (0 keeps them)
+/// - `task_steps`, the audit log, after `audit_max_age_days` (0 keeps them
+///   as long as their task)
+/// - `rate_limits` after `rate_limit_max_age_secs`
+///
+/// The daemon runs the job every `retention.interval_secs`; `rove db prune`
+/// runs it once.
+use crate::config::RetentionConfig;
+use crate::db::{Database, TaskRepository};
+use crate::rate_limiter::RateLimiter;
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tracing::{debug, info, warn};
+
+/// Rows removed by one retention run
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct PruneReport {
+    pub tasks: u64,
+    pub task_steps: u64,
+    pub rate_limits: u64,
+}
+
+/// Delete rows older than the configured ages
+pub async fn prune(database: &Database, config: &RetentionConfig) -> Result<PruneReport> {
+    let mut report = PruneReport::default();
+
+    let tasks = TaskRepository::new(database.pool().clone());
+    if config.task_max_age_days > 0 {
+        report.tasks = tasks
+            .delete_old_tasks(config.task_max_age_days as i64)
+            .await
+            .context("Failed to prune task history")?;
+    }
+    if config.audit_max_age_days > 0 {
+        report.task_steps = tasks
+            .delete_old_steps(config.audit_max_age_days as i64)
+            .await
+            .context("Failed to prune the audit log")?;
+    }
+
+    report.rate_limits = RateLimiter::new(database.pool().clone())
+        .cleanup_old_entries(Duration::from_secs(config.rate_limit_max_age_secs))
+        .await
+        .context("Failed to prune rate-limit entries")?;
+
+    info!(
+        "Retention pruned {} task(s), {} task step(s) and {} rate-limit entries",
+        report.tasks, report.task_steps, report.rate_limits
+    );
+    Ok(report)
+}
+
+/// Run `prune` every `config.interval_secs` until `shutdown_flag` is set
+///
+/// The first run happens immediately. A failed run is logged and retried at
+/// the next interval.
+pub fn spawn_retention_job(
+    database: Arc<Database>,
+    config: RetentionConfig,
+    shutdown_flag: Arc<AtomicBool>,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(config.interval_secs));
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+        loop {
+            interval.tick().await;
+            if shutdown_flag.load(Ordering::Relaxed) {
+                debug!("Retention job stopping for shutdown");
+                break;
+            }
+            if let Err(e) = prune(&database, &config).await {
+                warn!("Retention run failed: {:#}", e);
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{SystemTime, UNIX_EPOCH};
+    use tempfile::TempDir;
+
+    async fn setup_test_db() -> (TempDir, Database) {
+        let temp_dir = TempDir::new().unwrap();
+        let db = Database::new(&temp_dir.path().join("test.db"))
+            .await
+            .unwrap();
+        (temp_dir, db)
+    }
+
+    /// Insert a task and a rate-limit entry created `age` ago
+    async fn insert_rows(db: &Database, id: &str, age: Duration) {
+        let created = SystemTime::now().duration_since(UNIX_EPOCH).unwrap() - age;
+
+        sqlx::query("INSERT INTO tasks (id, input, status, created_at) VALUES (?, ?, ?, ?)")
+            .bind(id)
+            .bind("task")
+            .bind("completed")
+            .bind(created.as_secs() as i64)
+            .execute(db.pool())
+            .await
+            .unwrap();
+        sqlx::query(
+            "INSERT INTO task_steps (task_id, step_order, step_type, content, created_at) VALUES (?, ?, ?, ?, ?)",
+        )
+        .bind(id)
+        .bind(0)
+        .bind("user_message")
+        .bind("task")
+        .bind(created.as_secs() as i64)
+        .execute(db.pool())
+        .await
+        .unwrap();
+        sqlx::query("INSERT INTO rate_limits (source, tier, timestamp) VALUES (?, ?, ?)")
+            .bind(id)
+            .bind(1)
+            .bind(created.as_millis() as i64)
+            .execute(db.pool())
+            .await
+            .unwrap();
+    }
+
+    async fn count(db: &Database, table: &str) -> i64 {
+        sqlx::query_scalar(&format!("SELECT COUNT(*) FROM {}", table))
+            .fetch_one(db.pool())
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_prune_removes_only_old_rows() {
+        let (_temp_dir, db) = setup_test_db().await;
+        let day = Duration::from_secs(24 * 60 * 60);
+        insert_rows(&db, "old", 40 * day).await;
+        insert_rows(&db, "recent", Duration::from_secs(60)).await;
+
+        let config = RetentionConfig {
+            task_max_age_days: 30,
+            ..RetentionConfig::default()
+        };
+        let report = prune(&db, &config).await.unwrap();
+
+        assert_eq!(
+            report,
+            PruneReport {
+                tasks: 1,
+                task_steps: 0,
+                rate_limits: 1
+            }
+        );
+        let remaining: Vec<String> = sqlx::query_scalar("SELECT id FROM tasks")
+            .fetch_all(db.pool())
+            .await
+            .unwrap();
+        assert_eq!(remaining, vec!["recent".to_string()]);
+        assert_eq!(count(&db, "task_steps").await, 1);
+        assert_eq!(count(&db, "rate_limits").await, 1);
+    }
+
+    #[tokio::test]
+    async fn test_zero_task_age_keeps_history() {
+        let (_temp_dir, db) = setup_test_db().await;
+        insert_rows(&db, "ancient", Duration::from_secs(1000 * 24 * 60 * 60)).await;
+
+        let config = RetentionConfig {
+            task_max_age_days: 0,
+            ..RetentionConfig::default()
+        };
+        let report = prune(&db, &config).await.unwrap();
+
+        assert_eq!(report.tasks, 0);
+        assert_eq!(count(&db, "tasks").await, 1);
+        assert_eq!(count(&db, "rate_limits").await, 0);
+    }
+
+    #[tokio::test]
+    async fn test_audit_age_prunes_steps_of_kept_tasks() {
+        let (_temp_dir, db) = setup_test_db().await;
+        let day = Duration::from_secs(24 * 60 * 60);
+        insert_rows(&db, "old", 40 * day).await;
+        insert_rows(&db, "recent", Duration::from_secs(60)).await;
+
+        let config = RetentionConfig {
+            task_max_age_days: 0,
+            audit_max_age_days: 30,
+            ..RetentionConfig::default()
+        };
+        let report = prune(&db, &config).await.unwrap();
+
+        assert_eq!(report.tasks, 0);
+        assert_eq!(report.task_steps, 1);
+        assert_eq!(count(&db, "tasks").await, 2);
+        let steps: Vec<String> = sqlx::query_scalar("SELECT task_id FROM task_steps")
+            .fetch_all(db.pool())
+            .await
+            .unwrap();
+        assert_eq!(steps, vec!["recent".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn test_prune_on_a_freshly_created_database() {
+        // `rove db prune` opens (and migrates) the database, then prunes
+        // right away
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("fresh.db");
+        let db = Database::new(&path).await.unwrap();
+        insert_rows(&db, "old", Duration::from_secs(2 * 3600)).await;
+
+        let report = prune(&db, &RetentionConfig::default()).await.unwrap();
+
+        assert_eq!(report.rate_limits, 1);
+        assert_eq!(count(&db, "rate_limits").await, 0);
+    }
+
+    #[tokio::test]
+    async fn test_retention_job_runs_until_shutdown() {
+        let (_temp_dir, db) = setup_test_db().await;
+        let db = Arc::new(db);
+        insert_rows(&db, "old", Duration::from_secs(2 * 3600)).await;
+
+        let shutdown = Arc::new(AtomicBool::new(false));
+        let handle = spawn_retention_job(
+            Arc::clone(&db),
+            RetentionConfig {
+                interval_secs: 1,
+                ..RetentionConfig::default()
+            },
+            Arc::clone(&shutdown),
+        );
+
+        // The first run happens right away
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while count(&db, "rate_limits").await > 0 {
+                tokio::time::sleep(Duration::from_millis(20)).await;
+            }
+        })
+        .await
+        .expect("retention job did not prune");
+
+        shutdown.store(true, Ordering::Relaxed);
+        tokio::time::timeout(Duration::from_secs(5), handle)
+            .await
+            .expect("retention job did not stop")
+            .unwrap();
+    }
+}
@@ -0,0 +1,147 @@
+/// Signature verification cache
+///
+/// Persists "file hash X with signature S verified against key Y" results so
+/// `CryptoModule` can skip the Ed25519 check for files that have not changed
+/// since they were last verified. Only successful checks are stored.
+///
+/// Every row carries a keyed BLAKE3 hash made with a key kept in the
+/// keychain, and rows whose hash doesn't match are ignored, so an entry
+/// written straight into the database doesn't skip a signature check.
+use anyhow::{anyhow, Context, Result};
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::OsRng;
+use sqlx::{Row, SqlitePool};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::crypto::{CryptoModule, VerifiedSignature};
+use crate::secrets::SecretManager;
+
+/// Keychain entry holding the key that authenticates cache rows
+pub const CACHE_KEY_SECRET: &str = "signature_cache_key";
+
+/// Load the cache key from the keychain, generating and storing one if none
+/// exists
+pub fn load_or_create_cache_key(secrets: &SecretManager) -> Result<[u8; 32]> {
+    if secrets.has_secret(CACHE_KEY_SECRET) {
+        let key = secrets.get_secret(CACHE_KEY_SECRET)?;
+        return hex::decode(key.trim())
+            .ok()
+            .and_then(|bytes| bytes.try_into().ok())
+            .ok_or_else(|| {
+                anyhow!(
+                    "Keychain entry '{}' is not a hex-encoded 32-byte key",
+                    CACHE_KEY_SECRET
+                )
+            });
+    }
+
+    let mut key = [0u8; 32];
+    OsRng.fill_bytes(&mut key);
+    secrets.set_secret(CACHE_KEY_SECRET, &hex::encode(key))?;
+    tracing::info!("Generated new signature cache key");
+    Ok(key)
+}
+
+/// Signature cache repository for database operations
+pub struct SignatureCacheRepository {
+    pool: SqlitePool,
+    key: [u8; 32],
+}
+
+impl SignatureCacheRepository {
+    /// Create a new signature cache repository authenticating rows with `key`
+    pub fn new(pool: SqlitePool, key: [u8; 32]) -> Self {
+        Self { pool, key }
+    }
+
+    /// Keyed hash of `entry`, stored with its row
+    fn mac(&self, entry: &VerifiedSignature) -> blake3::Hash {
+        let row = format!(
+            "{}\n{}\n{}",
+            entry.file_hash, entry.public_key, entry.signature
+        );
+        blake3::keyed_hash(&self.key, row.as_bytes())
+    }
+
+    /// Record a successful verification
+    ///
+    /// Recording the same entry twice only refreshes its timestamp.
+    pub async fn record(&self, entry: &VerifiedSignature) -> Result<()> {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() as i64;
+
+        sqlx::query(
+            "INSERT INTO verified_signatures (file_hash, public_key, signature, verified_at, mac) VALUES (?, ?, ?, ?, ?)
+             ON CONFLICT(file_hash, public_key, signature) DO UPDATE SET verified_at = excluded.verified_at, mac = excluded.mac",
+        )
+        .bind(&entry.file_hash)
+        .bind(&entry.public_key)
+        .bind(&entry.signature)
+        .bind(now)
+        .bind(self.mac(entry).to_hex().as_str())
+        .execute(&self.pool)
+        .await
+        .context("Failed to record verified signature")?;
+
+        Ok(())
+    }
+
+    /// Load all cached verifications made with `public_key`
+    ///
+    /// Rows that fail authentication are skipped.
+    pub async fn load_for_key(&self, public_key: &str) -> Result<Vec<VerifiedSignature>> {
+        let rows = sqlx::query(
+            "SELECT file_hash, public_key, signature, mac FROM verified_signatures WHERE public_key = ?",
+        )
+        .bind(public_key)
+        .fetch_all(&self.pool)
+        .await
+        .context("Failed to load verified signatures")?;
+
+        let mut entries = Vec::with_capacity(rows.len());
+        for row in rows {
+            let entry = VerifiedSignature {
+                file_hash: row.get("file_hash"),
+                public_key: row.get("public_key"),
+                signature: row.get("signature"),
+            };
+            let mac: String = row.get("mac");
+            // `Hash` compares in constant time
+            match blake3::Hash::from_hex(&mac) {
+                Ok(mac) if mac == self.mac(&entry) => entries.push(entry),
+                _ => tracing::warn!(
+                    "Ignoring unauthenticated signature cache entry for {}",
+                    entry.file_hash
+                ),
+            }
+        }
+        Ok(entries)
+    }
+
+    /// Seed `crypto` with the cached verifications for its key
+    ///
+    /// Returns the number of entries loaded.
+    pub async fn load_into(&self, crypto: &CryptoModule) -> Result<usize> {
+        let entries = self.load_for_key(&crypto.public_key_hex()).await?;
+        let count = entries.len();
+        crypto.preload_verified_signatures(entries);
+        Ok(count)
+    }
+
+    /// Persist every verification `crypto` has made or been seeded with
+    pub async fn save_from(&self, crypto: &CryptoModule) -> Result<()> {
+        for entry in crypto.verified_signatures() {
+            self.record(&entry).await?;
+        }
+        Ok(())
+    }
+
+    /// Drop every cached verification
+    pub async fn clear(&self) -> Result<()> {
+        sqlx::query("DELETE FROM verified_signatures")
+            .execute(&self.pool)
+            .await
+            .context("Failed to clear verified signatures")?;
+
+        Ok(())
+    }
+}
@@ -5,7 +5,9 @@
 ///
 /// Requirements: 12.2, 12.4, 12.5, 12.7, 12.10
 use anyhow::{Context, Result};
+use sdk::errors::EngineError;
 use serde::{Deserialize, Serialize};
+use sqlx::sqlite::SqliteRow;
 use sqlx::{Row, SqlitePool};
 use std::time::{SystemTime, UNIX_EPOCH};
 
@@ -51,6 +53,86 @@ impl StepType {
     }
 }
 
+/// Category of a task failure
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum FailureReason {
+    /// LLM provider error, unreachable or unconfigured provider
+    ProviderError,
+    /// Task hit the maximum number of agent iterations or the call-depth limit
+    MaxSteps,
+    /// LLM call or the whole task timed out
+    Timeout,
+    /// Rate limit or circuit breaker rejected the task
+    RateLimited,
+    /// Tool result or final answer exceeded the size limit
+    ResultTooLarge,
+    /// Anything else
+    Other,
+}
+
+impl FailureReason {
+    pub fn as_str(&self) -> &str {
+        match self {
+            FailureReason::ProviderError => "provider_error",
+            FailureReason::MaxSteps => "max_steps",
+            FailureReason::Timeout => "timeout",
+            FailureReason::RateLimited => "rate_limited",
+            FailureReason::ResultTooLarge => "result_too_large",
+            FailureReason::Other => "other",
+        }
+    }
+
+    fn parse(value: &str) -> Self {
+        match value {
+            "provider_error" => FailureReason::ProviderError,
+            "max_steps" => FailureReason::MaxSteps,
+            "timeout" => FailureReason::Timeout,
+            "rate_limited" => FailureReason::RateLimited,
+            "result_too_large" => FailureReason::ResultTooLarge,
+            _ => FailureReason::Other,
+        }
+    }
+
+    /// Classify an agent error by the first `EngineError` in its chain
+    pub fn from_error(error: &anyhow::Error) -> Self {
+        let engine_error = error
+            .chain()
+            .find_map(|cause| cause.downcast_ref::<EngineError>());
+
+        match engine_error {
+            Some(EngineError::MaxIterationsExceeded | EngineError::CallDepthExceeded { .. }) => {
+                FailureReason::MaxSteps
+            }
+            Some(EngineError::LLMTimeout | EngineError::TaskTimedOut { .. }) => {
+                FailureReason::Timeout
+            }
+            Some(
+                EngineError::LLMProvider(_)
+                | EngineError::AllProvidersExhausted
+                | EngineError::ProviderNotConfigured { .. }
+                | EngineError::Network(_),
+            ) => FailureReason::ProviderError,
+            Some(
+                EngineError::RateLimitExceeded { .. } | EngineError::CircuitBreakerTripped { .. },
+            ) => FailureReason::RateLimited,
+            Some(EngineError::ResultSizeExceeded { .. }) => FailureReason::ResultTooLarge,
+            _ => FailureReason::Other,
+        }
+    }
+}
+
+/// Dead-letter record describing why a task failed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskFailure {
+    pub reason: FailureReason,
+    pub message: String,
+    /// Number of steps persisted before the failure
+    pub steps: i64,
+    /// Accumulated LLM cost at the time of failure
+    pub cost: f64,
+}
+
 /// Task record
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Task {
@@ -61,6 +143,94 @@ pub struct Task {
     pub duration_ms: Option<i64>,
     pub created_at: i64,
     pub completed_at: Option<i64>,
+    /// Estimated tokens used across all LLM calls of the task
+    #[serde(default)]
+    pub total_tokens: i64,
+    /// Estimated LLM cost of the task in USD
+    #[serde(default)]
+    pub cost: f64,
+    /// Short summary of what the task did, if summaries are enabled
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+    /// Correlation id of the request that started the task
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
+    /// True if the task was requested remotely (Telegram, API)
+    #[serde(default)]
+    pub remote: bool,
+    /// Process that last marked the task running
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub owner_pid: Option<i64>,
+    /// Failure record, present only for failed tasks recorded with a reason
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub failure: Option<TaskFailure>,
+}
+
+/// Columns selected for a task joined with its failure record
+const TASK_COLUMNS: &str = "t.id, t.input, t.status, t.provider_used, t.duration_ms, t.created_at, t.completed_at, \
+     t.total_tokens, t.cost, t.summary, t.request_id, t.remote, t.owner_pid, f.reason AS failure_reason, f.message AS failure_message, f.steps AS failure_steps, f.cost AS failure_cost";
+
+fn task_from_row(r: &SqliteRow) -> Task {
+    let failure = r
+        .get::<Option<String>, _>("failure_reason")
+        .map(|reason| TaskFailure {
+            reason: FailureReason::parse(&reason),
+            message: r.get("failure_message"),
+            steps: r.get("failure_steps"),
+            cost: r.get("failure_cost"),
+        });
+
+    Task {
+        id: r.get("id"),
+        input: r.get("input"),
+        status: match r.get::<String, _>("status").as_str() {
+            "pending" => TaskStatus::Pending,
+            "running" => TaskStatus::Running,
+            "completed" => TaskStatus::Completed,
+            "failed" => TaskStatus::Failed,
+            _ => TaskStatus::Failed,
+        },
+        provider_used: r.get("provider_used"),
+        duration_ms: r.get("duration_ms"),
+        created_at: r.get("created_at"),
+        completed_at: r.get("completed_at"),
+        total_tokens: r.get("total_tokens"),
+        cost: r.get("cost"),
+        summary: r.get("summary"),
+        request_id: r.get("request_id"),
+        remote: r.get("remote"),
+        owner_pid: r.get("owner_pid"),
+        failure,
+    }
+}
+
+impl Task {
+    /// Whether the task is marked running but the process running it is
+    /// gone, e.g. after a crash, a kill or Ctrl-C
+    ///
+    /// A running task without an owner was started before owners were
+    /// recorded, and counts as orphaned.
+    pub fn is_orphaned(&self) -> bool {
+        if self.status != TaskStatus::Running {
+            return false;
+        }
+        match self.owner_pid.and_then(|pid| u32::try_from(pid).ok()) {
+            Some(pid) if pid == std::process::id() => false,
+            Some(pid) => !crate::daemon::DaemonManager::is_process_running(pid),
+            None => true,
+        }
+    }
+}
+
+/// Spend totals over a set of tasks
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct CostSummary {
+    /// Number of tasks included
+    pub tasks: i64,
+    /// Estimated tokens across those tasks
+    pub total_tokens: i64,
+    /// Estimated cost in USD across those tasks
+    pub cost: f64,
 }
 
 /// Task step record
@@ -72,6 +242,9 @@ pub struct TaskStep {
     pub step_type: StepType,
     pub content: String,
     pub created_at: i64,
+    /// How a tool call step was approved, e.g. `auto-approved (tier 0)`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub approval: Option<String>,
 }
 
 /// Task repository for database operations
@@ -89,19 +262,35 @@ impl TaskRepository {
     ///
     /// Requirements: 12.4, 12.10
     pub async fn create_task(&self, id: &str, input: &str) -> Result<Task> {
+        self.create_task_for_request(id, input, None, false).await
+    }
+
+    /// Create a new task started by the request `request_id`, made remotely
+    /// if `remote` is set
+    pub async fn create_task_for_request(
+        &self,
+        id: &str,
+        input: &str,
+        request_id: Option<&str>,
+        remote: bool,
+    ) -> Result<Task> {
         let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() as i64;
 
         let status = TaskStatus::Pending.as_str();
 
         // Use parameterized query to prevent SQL injection
-        sqlx::query("INSERT INTO tasks (id, input, status, created_at) VALUES (?, ?, ?, ?)")
-            .bind(id)
-            .bind(input)
-            .bind(status)
-            .bind(now)
-            .execute(&self.pool)
-            .await
-            .context("Failed to create task")?;
+        sqlx::query(
+            "INSERT INTO tasks (id, input, status, created_at, request_id, remote) VALUES (?, ?, ?, ?, ?, ?)",
+        )
+        .bind(id)
+        .bind(input)
+        .bind(status)
+        .bind(now)
+        .bind(request_id)
+        .bind(remote)
+        .execute(&self.pool)
+        .await
+        .context("Failed to create task")?;
 
         Ok(Task {
             id: id.to_string(),
@@ -111,13 +300,26 @@ impl TaskRepository {
             duration_ms: None,
             created_at: now,
             completed_at: None,
+            total_tokens: 0,
+            cost: 0.0,
+            summary: None,
+            request_id: request_id.map(str::to_string),
+            remote,
+            owner_pid: None,
+            failure: None,
         })
     }
 
     /// Update task status
     ///
+    /// A task marked running is owned by the current process (see
+    /// `mark_running`).
+    ///
     /// Requirements: 12.4, 12.10
     pub async fn update_task_status(&self, task_id: &str, status: TaskStatus) -> Result<()> {
+        if status == TaskStatus::Running {
+            return self.mark_running(task_id, std::process::id()).await;
+        }
         let status_str = status.as_str();
 
         sqlx::query("UPDATE tasks SET status = ? WHERE id = ?")
@@ -130,6 +332,22 @@ impl TaskRepository {
         Ok(())
     }
 
+    /// Mark a task running in the process `owner_pid`
+    ///
+    /// Once that process has exited without finishing the task, the task is
+    /// orphaned (see `Task::is_orphaned`) and can be resumed.
+    pub async fn mark_running(&self, task_id: &str, owner_pid: u32) -> Result<()> {
+        sqlx::query("UPDATE tasks SET status = ?, owner_pid = ? WHERE id = ?")
+            .bind(TaskStatus::Running.as_str())
+            .bind(owner_pid as i64)
+            .bind(task_id)
+            .execute(&self.pool)
+            .await
+            .context("Failed to update task status")?;
+
+        Ok(())
+    }
+
     /// Complete a task with results
     ///
     /// Requirements: 12.4, 12.10
@@ -155,9 +373,60 @@ impl TaskRepository {
         .await
         .context("Failed to complete task")?;
 
+        // A resumed task that completes is no longer a failure
+        sqlx::query("DELETE FROM task_failures WHERE task_id = ?")
+            .bind(task_id)
+            .execute(&self.pool)
+            .await
+            .context("Failed to clear task failure")?;
+
         Ok(())
     }
 
+    /// Store the summary of a completed task
+    pub async fn set_summary(&self, task_id: &str, summary: &str) -> Result<()> {
+        sqlx::query("UPDATE tasks SET summary = ? WHERE id = ?")
+            .bind(summary)
+            .bind(task_id)
+            .execute(&self.pool)
+            .await
+            .context("Failed to store task summary")?;
+
+        Ok(())
+    }
+
+    /// Store the token and cost totals accumulated by a task
+    pub async fn record_usage(&self, task_id: &str, total_tokens: i64, cost: f64) -> Result<()> {
+        sqlx::query("UPDATE tasks SET total_tokens = ?, cost = ? WHERE id = ?")
+            .bind(total_tokens)
+            .bind(cost)
+            .bind(task_id)
+            .execute(&self.pool)
+            .await
+            .context("Failed to record task usage")?;
+
+        Ok(())
+    }
+
+    /// Total tokens and cost of all tasks created at or after `since`
+    /// (a Unix timestamp in seconds), or of every task when `since` is `None`
+    pub async fn cost_summary(&self, since: Option<i64>) -> Result<CostSummary> {
+        let row = sqlx::query(
+            "SELECT COUNT(*) AS tasks, COALESCE(SUM(total_tokens), 0) AS total_tokens, \
+             COALESCE(SUM(cost), 0.0) AS cost FROM tasks WHERE created_at >= ?",
+        )
+        .bind(since.unwrap_or(i64::MIN))
+        .fetch_one(&self.pool)
+        .await
+        .context("Failed to sum task costs")?;
+
+        Ok(CostSummary {
+            tasks: row.get("tasks"),
+            total_tokens: row.get("total_tokens"),
+            cost: row.get("cost"),
+        })
+    }
+
     /// Mark a task as failed
     ///
     /// Requirements: 12.4, 12.10
@@ -177,65 +446,74 @@ impl TaskRepository {
         Ok(())
     }
 
+    /// Mark a task as failed and store a dead-letter record with the reason
+    ///
+    /// Replaces any earlier failure record for the same task.
+    pub async fn record_failure(&self, task_id: &str, failure: &TaskFailure) -> Result<()> {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() as i64;
+
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .context("Failed to begin transaction")?;
+
+        sqlx::query("UPDATE tasks SET status = ?, completed_at = ? WHERE id = ?")
+            .bind(TaskStatus::Failed.as_str())
+            .bind(now)
+            .bind(task_id)
+            .execute(&mut *tx)
+            .await
+            .context("Failed to mark task as failed")?;
+
+        sqlx::query(
+            "INSERT OR REPLACE INTO task_failures (task_id, reason, message, steps, cost, created_at) VALUES (?, ?, ?, ?, ?, ?)"
+        )
+        .bind(task_id)
+        .bind(failure.reason.as_str())
+        .bind(&failure.message)
+        .bind(failure.steps)
+        .bind(failure.cost)
+        .bind(now)
+        .execute(&mut *tx)
+        .await
+        .context("Failed to record task failure")?;
+
+        tx.commit().await.context("Failed to commit task failure")?;
+
+        Ok(())
+    }
+
     /// Get a task by ID
     ///
     /// Requirements: 12.4, 12.10
     pub async fn get_task(&self, task_id: &str) -> Result<Option<Task>> {
-        let row = sqlx::query(
-            "SELECT id, input, status, provider_used, duration_ms, created_at, completed_at FROM tasks WHERE id = ?"
-        )
+        let row = sqlx::query(&format!(
+            "SELECT {} FROM tasks t LEFT JOIN task_failures f ON f.task_id = t.id WHERE t.id = ?",
+            TASK_COLUMNS
+        ))
         .bind(task_id)
         .fetch_optional(&self.pool)
         .await
         .context("Failed to fetch task")?;
 
-        Ok(row.map(|r| Task {
-            id: r.get("id"),
-            input: r.get("input"),
-            status: match r.get::<String, _>("status").as_str() {
-                "pending" => TaskStatus::Pending,
-                "running" => TaskStatus::Running,
-                "completed" => TaskStatus::Completed,
-                "failed" => TaskStatus::Failed,
-                _ => TaskStatus::Failed,
-            },
-            provider_used: r.get("provider_used"),
-            duration_ms: r.get("duration_ms"),
-            created_at: r.get("created_at"),
-            completed_at: r.get("completed_at"),
-        }))
+        Ok(row.as_ref().map(task_from_row))
     }
 
     /// Get recent tasks (last N tasks)
     ///
     /// Requirements: 12.4, 12.10
     pub async fn get_recent_tasks(&self, limit: i64) -> Result<Vec<Task>> {
-        let rows = sqlx::query(
-            "SELECT id, input, status, provider_used, duration_ms, created_at, completed_at FROM tasks ORDER BY created_at DESC LIMIT ?"
-        )
+        let rows = sqlx::query(&format!(
+            "SELECT {} FROM tasks t LEFT JOIN task_failures f ON f.task_id = t.id ORDER BY t.created_at DESC LIMIT ?",
+            TASK_COLUMNS
+        ))
         .bind(limit)
         .fetch_all(&self.pool)
         .await
         .context("Failed to fetch recent tasks")?;
 
-        Ok(rows
-            .into_iter()
-            .map(|r| Task {
-                id: r.get("id"),
-                input: r.get("input"),
-                status: match r.get::<String, _>("status").as_str() {
-                    "pending" => TaskStatus::Pending,
-                    "running" => TaskStatus::Running,
-                    "completed" => TaskStatus::Completed,
-                    "failed" => TaskStatus::Failed,
-                    _ => TaskStatus::Failed,
-                },
-                provider_used: r.get("provider_used"),
-                duration_ms: r.get("duration_ms"),
-                created_at: r.get("created_at"),
-                completed_at: r.get("completed_at"),
-            })
-            .collect())
+        Ok(rows.iter().map(task_from_row).collect())
     }
 
     /// Add a step to a task
@@ -271,15 +549,33 @@ impl TaskRepository {
             step_type,
             content: content.to_string(),
             created_at: now,
+            approval: None,
         })
     }
 
+    /// Record how the tool call at `step_order` was approved
+    pub async fn set_step_approval(
+        &self,
+        task_id: &str,
+        step_order: i64,
+        approval: &str,
+    ) -> Result<()> {
+        sqlx::query("UPDATE task_steps SET approval = ? WHERE task_id = ? AND step_order = ?")
+            .bind(approval)
+            .bind(task_id)
+            .bind(step_order)
+            .execute(&self.pool)
+            .await
+            .context("Failed to record step approval")?;
+        Ok(())
+    }
+
     /// Get all steps for a task
     ///
     /// Requirements: 12.5, 12.10
     pub async fn get_task_steps(&self, task_id: &str) -> Result<Vec<TaskStep>> {
         let rows = sqlx::query(
-            "SELECT id, task_id, step_order, step_type, content, created_at FROM task_steps WHERE task_id = ? ORDER BY step_order ASC"
+            "SELECT id, task_id, step_order, step_type, content, created_at, approval FROM task_steps WHERE task_id = ? ORDER BY step_order ASC"
         )
         .bind(task_id)
         .fetch_all(&self.pool)
@@ -301,6 +597,7 @@ impl TaskRepository {
                 },
                 content: r.get("content"),
                 created_at: r.get("created_at"),
+                approval: r.get("approval"),
             })
             .collect())
     }
@@ -320,4 +617,18 @@ impl TaskRepository {
 
         Ok(result.rows_affected())
     }
+
+    /// Delete task steps older than `older_than_days`, keeping their tasks
+    pub async fn delete_old_steps(&self, older_than_days: i64) -> Result<u64> {
+        let cutoff = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() as i64
+            - (older_than_days * 24 * 60 * 60);
+
+        let result = sqlx::query("DELETE FROM task_steps WHERE created_at < ?")
+            .bind(cutoff)
+            .execute(&self.pool)
+            .await
+            .context("Failed to delete old task steps")?;
+
+        Ok(result.rows_affected())
+    }
 }
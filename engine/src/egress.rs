@@ -0,0 +1,222 @@
+//! Network egress allowlist
+//!
+//! `[security.network] allowed_hosts` restricts which hosts the engine may
+//! connect to. It is off by default; once set it is strict: a request to any
+//! other host fails with `EngineError::EgressDenied` before it is sent, and
+//! so does a redirect to one. Hosts are matched exactly (case-insensitive),
+//! without port or scheme.
+//!
+//! Every LLM provider sends its requests through an `EgressClient`, and so
+//! must any network host function. The configured provider base URLs must be
+//! on the list; `check_url` is used at startup to reject a configuration
+//! that would lock out an active provider.
+
+use sdk::errors::EngineError;
+use std::collections::BTreeSet;
+use std::sync::Arc;
+
+/// Hosts the engine may connect to
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EgressPolicy {
+    /// Allowed hosts, lowercased (`None` allows every host)
+    allowed_hosts: Option<BTreeSet<String>>,
+}
+
+impl EgressPolicy {
+    /// Policy that allows only `hosts`
+    pub fn allow_only<I, S>(hosts: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        Self {
+            allowed_hosts: Some(
+                hosts
+                    .into_iter()
+                    .map(|host| host.as_ref().to_ascii_lowercase())
+                    .collect(),
+            ),
+        }
+    }
+
+    /// Policy for `security.network.allowed_hosts` (`None` allows every host)
+    pub fn from_allowed_hosts(hosts: Option<&[String]>) -> Self {
+        match hosts {
+            Some(hosts) => Self::allow_only(hosts),
+            None => Self::default(),
+        }
+    }
+
+    /// Whether the allowlist is in effect
+    pub fn is_restricted(&self) -> bool {
+        self.allowed_hosts.is_some()
+    }
+
+    /// The allowed hosts, lowercased (`None` while unrestricted)
+    pub fn allowed_hosts(&self) -> Option<impl Iterator<Item = &str>> {
+        self.allowed_hosts
+            .as_ref()
+            .map(|hosts| hosts.iter().map(String::as_str))
+    }
+
+    /// Whether requests to `host` are allowed
+    pub fn allows_host(&self, host: &str) -> bool {
+        match &self.allowed_hosts {
+            Some(allowed) => allowed.contains(&host.to_ascii_lowercase()),
+            None => true,
+        }
+    }
+
+    /// Check that requests to `url` are allowed
+    ///
+    /// # Errors
+    ///
+    /// Returns `EngineError::EgressDenied` if the URL's host is not on the
+    /// allowlist, or the URL has no host. Never fails while unrestricted.
+    pub fn check_url(&self, url: &str) -> Result<(), EngineError> {
+        if !self.is_restricted() {
+            return Ok(());
+        }
+        let host = reqwest::Url::parse(url)
+            .ok()
+            .and_then(|url| url.host_str().map(str::to_string));
+        match host {
+            Some(host) if self.allows_host(&host) => Ok(()),
+            Some(host) => {
+                tracing::warn!("Blocked network egress to {}", host);
+                Err(EngineError::EgressDenied(host))
+            }
+            None => Err(EngineError::EgressDenied(url.to_string())),
+        }
+    }
+}
+
+/// HTTP client that only connects to hosts its `EgressPolicy` allows
+#[derive(Debug, Clone)]
+pub struct EgressClient {
+    client: reqwest::Client,
+    policy: Arc<EgressPolicy>,
+}
+
+impl EgressClient {
+    /// Build a client from `builder` that enforces `policy`, including on
+    /// redirects
+    ///
+    /// # Panics
+    ///
+    /// Panics if the client cannot be built, like `reqwest::Client::new`.
+    pub fn new(builder: reqwest::ClientBuilder, policy: Arc<EgressPolicy>) -> Self {
+        let redirect_policy = Arc::clone(&policy);
+        let client = builder
+            .redirect(reqwest::redirect::Policy::custom(move |attempt| {
+                if attempt.previous().len() >= 10 {
+                    attempt.error("too many redirects")
+                } else {
+                    match redirect_policy.check_url(attempt.url().as_str()) {
+                        Ok(()) => attempt.follow(),
+                        Err(e) => attempt.error(e),
+                    }
+                }
+            }))
+            .build()
+            .expect("Failed to create HTTP client");
+        Self { client, policy }
+    }
+
+    /// Client from `builder` that may connect to any host
+    pub fn unrestricted(builder: reqwest::ClientBuilder) -> Self {
+        Self::new(builder, Arc::new(EgressPolicy::default()))
+    }
+
+    /// The policy this client enforces
+    pub fn policy(&self) -> &EgressPolicy {
+        &self.policy
+    }
+
+    /// Start a GET request to `url`
+    ///
+    /// # Errors
+    ///
+    /// Returns `EngineError::EgressDenied` if the policy rejects the host.
+    pub fn get(&self, url: &str) -> Result<reqwest::RequestBuilder, EngineError> {
+        self.policy.check_url(url)?;
+        Ok(self.client.get(url))
+    }
+
+    /// Start a POST request to `url`
+    ///
+    /// # Errors
+    ///
+    /// Returns `EngineError::EgressDenied` if the policy rejects the host.
+    pub fn post(&self, url: &str) -> Result<reqwest::RequestBuilder, EngineError> {
+        self.policy.check_url(url)?;
+        Ok(self.client.post(url))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[tokio::test]
+    async fn test_allowlisted_host_succeeds_and_others_are_blocked() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/chat/completions"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("ok"))
+            .expect(1)
+            .mount(&server)
+            .await;
+        // The mock server listens on 127.0.0.1
+        let client = EgressClient::new(
+            reqwest::Client::builder(),
+            Arc::new(EgressPolicy::allow_only(["127.0.0.1"])),
+        );
+
+        let response = client
+            .post(&format!("{}/v1/chat/completions", server.uri()))
+            .unwrap()
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.text().await.unwrap(), "ok");
+
+        let blocked = client.post("https://api.openai.com/v1/chat/completions");
+        assert!(matches!(
+            blocked,
+            Err(EngineError::EgressDenied(ref host)) if host == "api.openai.com"
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_redirect_to_unlisted_host_is_blocked() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(
+                ResponseTemplate::new(302).insert_header("Location", "http://example.com/"),
+            )
+            .mount(&server)
+            .await;
+        let client = EgressClient::new(
+            reqwest::Client::builder(),
+            Arc::new(EgressPolicy::allow_only(["127.0.0.1"])),
+        );
+
+        let result = client.get(&server.uri()).unwrap().send().await;
+
+        assert!(result.unwrap_err().is_redirect());
+    }
+
+    #[test]
+    fn test_unrestricted_policy_allows_everything() {
+        let policy = EgressPolicy::from_allowed_hosts(None);
+        assert!(!policy.is_restricted());
+        assert!(policy.check_url("https://anywhere.example").is_ok());
+
+        let policy = EgressPolicy::from_allowed_hosts(Some(&["API.Anthropic.com".to_string()]));
+        assert!(policy.check_url("https://api.anthropic.com/v1/messages").is_ok());
+        assert!(policy.check_url("not a url").is_err());
+    }
+}
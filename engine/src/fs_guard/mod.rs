@@ -1,6 +1,16 @@
 use sdk::errors::EngineError;
 use std::path::{Path, PathBuf};
 
+/// Largest file the guard lets a tool read by default (1 MB)
+///
+/// Before the limit existed, reads were only bounded by the agent's 5 MB
+/// tool result limit; `rove run --max-file-size` restores that for a run.
+pub const DEFAULT_MAX_READ_SIZE: u64 = 1024 * 1024;
+
+/// Largest read limit a per-run override may set (5 MB, the agent's tool
+/// result limit)
+pub const HARD_MAX_READ_SIZE: u64 = 5 * 1024 * 1024;
+
 /// FileSystemGuard provides multi-layer defense against path traversal and unauthorized access.
 ///
 /// It maintains a deny list of sensitive paths and performs double canonicalization checks
@@ -25,10 +35,14 @@ use std::path::{Path, PathBuf};
 /// 2. Canonicalize path to resolve symlinks and .. patterns
 /// 3. Check deny list after canonicalization
 /// 4. Verify path is within workspace
-#[derive(Debug)]
+///
+/// Reads are additionally capped at `max_read_size` bytes (see
+/// `check_read_size`).
+#[derive(Debug, Clone)]
 pub struct FileSystemGuard {
     workspace: PathBuf,
     deny_list: Vec<PathBuf>,
+    max_read_size: u64,
 }
 
 impl FileSystemGuard {
@@ -92,7 +106,59 @@ impl FileSystemGuard {
         Self {
             workspace,
             deny_list,
+            max_read_size: DEFAULT_MAX_READ_SIZE,
+        }
+    }
+
+    /// Raise the read limit, e.g. for a single `rove run --max-file-size`
+    ///
+    /// The override is logged. It never lowers the limit: a smaller value is
+    /// ignored with a warning, and values above `HARD_MAX_READ_SIZE` are
+    /// clamped to it with a warning.
+    pub fn with_max_read_size(mut self, bytes: u64) -> Self {
+        if bytes < self.max_read_size {
+            tracing::warn!(
+                "Read limit of {} bytes is below the current {} bytes, keeping the current limit",
+                bytes,
+                self.max_read_size
+            );
+            return self;
+        }
+        if bytes > HARD_MAX_READ_SIZE {
+            tracing::warn!(
+                "Read limit of {} bytes exceeds the hard ceiling, using {} bytes",
+                bytes,
+                HARD_MAX_READ_SIZE
+            );
         }
+        self.max_read_size = bytes.min(HARD_MAX_READ_SIZE);
+        tracing::info!(
+            "Read limit overridden: {} bytes (default {})",
+            self.max_read_size,
+            DEFAULT_MAX_READ_SIZE
+        );
+        self
+    }
+
+    /// Largest file, in bytes, that may be read
+    pub fn max_read_size(&self) -> u64 {
+        self.max_read_size
+    }
+
+    /// Check that a file of `size` bytes at `path` may be read
+    ///
+    /// # Errors
+    ///
+    /// Returns `EngineError::FileTooLarge` if `size` exceeds the read limit.
+    pub fn check_read_size(&self, path: &Path, size: u64) -> Result<(), EngineError> {
+        if size > self.max_read_size {
+            return Err(EngineError::FileTooLarge {
+                path: path.to_path_buf(),
+                size,
+                limit: self.max_read_size,
+            });
+        }
+        Ok(())
     }
 
     /// Validates a path through four security gates.
@@ -310,4 +376,28 @@ mod tests {
         assert!(result.is_err());
         assert!(matches!(result.unwrap_err(), EngineError::PathDenied(_)));
     }
+
+    #[test]
+    fn test_read_size_override_is_capped_at_hard_ceiling() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("big.log");
+
+        let guard = FileSystemGuard::new(temp.path().to_path_buf());
+        assert!(guard.check_read_size(&path, DEFAULT_MAX_READ_SIZE).is_ok());
+        assert!(matches!(
+            guard.check_read_size(&path, DEFAULT_MAX_READ_SIZE + 1),
+            Err(EngineError::FileTooLarge { limit, .. }) if limit == DEFAULT_MAX_READ_SIZE
+        ));
+
+        // An override never lowers the limit
+        let guard = FileSystemGuard::new(temp.path().to_path_buf()).with_max_read_size(1000);
+        assert_eq!(guard.max_read_size(), DEFAULT_MAX_READ_SIZE);
+
+        let guard = FileSystemGuard::new(temp.path().to_path_buf()).with_max_read_size(u64::MAX);
+        assert_eq!(guard.max_read_size(), HARD_MAX_READ_SIZE);
+        assert!(guard.check_read_size(&path, HARD_MAX_READ_SIZE).is_ok());
+        assert!(guard
+            .check_read_size(&path, HARD_MAX_READ_SIZE + 1)
+            .is_err());
+    }
 }
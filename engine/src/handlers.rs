@@ -2,9 +2,15 @@
 //!
 //! This module implements the handlers for all CLI commands:
 //! - run: Execute a task immediately
+//! - export-prompt: Show the system prompt for a task
 //! - history: Show last N tasks
 //! - replay: Show all steps for a task
 //! - plugins list: List all installed plugins
+//! - plugins search: Search the plugin registries
+//! - plugins update: Update a plugin, asking again for new permissions
+//! - workspace list/use: Show and switch the active workspace
+//! - db prune: Apply the data-retention limits
+//! - config dump: Print the config file or the effective configuration
 //! - doctor: Validate configuration and check dependencies
 //!
 //! Requirements: 15.3, 15.4, 15.5, 15.6, 15.7
@@ -14,7 +20,8 @@ use serde_json::json;
 use std::path::{Path, PathBuf};
 
 use crate::config::Config;
-use crate::daemon::DaemonManager;
+use crate::confirmation::{ConfirmationPolicy, Countdown, TerminalConfirmer};
+use crate::daemon::{DaemonManager, PidFileState};
 use crate::db::{tasks::TaskRepository, Database};
 
 /// Output format for command results
@@ -26,25 +33,90 @@ pub enum OutputFormat {
     Json,
 }
 
+/// Options for `rove run`
+#[derive(Debug, Clone, Default)]
+pub struct RunOptions {
+    /// Task to run; ignored when `resume` is set
+    pub task: Option<String>,
+
+    /// ID of a failed or interrupted task to continue
+    pub resume: Option<String>,
+
+    /// Run without any shared state: a scratch database that is removed
+    /// afterwards and no background watcher
+    pub no_daemon: bool,
+
+    /// Largest file, in bytes, the file tools will read or write
+    pub max_file_size: Option<u64>,
+
+    /// Deadline for the task, overriding the configured one
+    pub timeout: Option<std::time::Duration>,
+
+    /// File to write the task's transcript to
+    pub dump_transcript: Option<PathBuf>,
+}
+
 /// Run a task immediately
 ///
-/// This handler executes a task synchronously and returns the result.
-/// If the daemon is running, it delegates to the daemon. Otherwise, it
-/// executes the task directly.
+/// This handler executes the task in this process and returns the result.
+/// Task history goes to the database in the data directory, which a running
+/// daemon shares.
+///
+/// With `options.no_daemon` nothing is shared or left behind: the task's
+/// history goes to a scratch database in a temporary directory, which is
+/// closed and removed before returning, and no background file watcher is
+/// started. The PID file is never read or written. Such a run can't resume a
+/// task, since the history of earlier runs isn't there.
+///
+/// With `OutputFormat::Json` the output is NDJSON: one `TaskEvent` per line
+/// as the task progresses, ending with `task_completed` or `task_failed`.
+///
+/// With `options.resume` set, the failed or interrupted task of that ID continues
+/// from its last completed step instead of running `options.task`.
+///
+/// With `options.dump_transcript` set, the task's full conversation is written there
+/// as JSON with secrets scrubbed, whether it completed or failed.
 ///
 /// Requirements: 15.3
-pub async fn handle_run(task: String, config: &Config, format: OutputFormat) -> Result<()> {
-    use crate::agent::{AgentCore, SteeringEngine, Task};
+pub async fn handle_run(options: RunOptions, config: &Config, format: OutputFormat) -> Result<()> {
+    let RunOptions {
+        task,
+        resume,
+        no_daemon,
+        max_file_size,
+        timeout,
+        dump_transcript,
+    } = options;
+    use crate::agent::{
+        ndjson_sink, AgentCore, PromptTemplate, Task, TaskEvent, TaskTimeout, TranscriptDump,
+    };
     use crate::db::tasks::TaskRepository;
+    use crate::egress::EgressPolicy;
     use crate::llm::ollama::OllamaProvider;
     use crate::llm::router::LLMRouter;
     use crate::rate_limiter::RateLimiter;
     use crate::risk_assessor::{OperationSource, RiskAssessor};
-    use crate::tools::{FilesystemTool, TerminalTool, ToolRegistry, VisionTool};
     use std::sync::Arc;
 
+    // A --no-daemon run keeps its history in a scratch directory, removed
+    // when it is dropped at the end of the run
+    let scratch = if no_daemon {
+        if resume.is_some() {
+            return Err(anyhow::anyhow!(
+                "--resume can't be used with --no-daemon, which doesn't keep task history"
+            ));
+        }
+        tracing::info!("Running task in-process with a scratch database (--no-daemon)");
+        Some(tempfile::tempdir().context("Failed to create a scratch data directory")?)
+    } else {
+        None
+    };
+
     // Initialize database
-    let db_path = get_db_path(config)?;
+    let db_path = match scratch {
+        Some(ref dir) => dir.path().join("rove.db"),
+        None => get_db_path(config)?,
+    };
     let database = Database::new(&db_path)
         .await
         .context("Failed to open database")?;
@@ -52,12 +124,35 @@ pub async fn handle_run(task: String, config: &Config, format: OutputFormat) ->
     // Create LLM providers
     let mut providers: Vec<Box<dyn crate::llm::LLMProvider>> = Vec::new();
 
-    // Add Ollama provider (always configured with defaults)
-    let ollama = OllamaProvider::new(
-        config.llm.ollama.base_url.clone(),
-        config.llm.ollama.model.clone(),
-    );
-    providers.push(Box::new(ollama));
+    // Providers only reach the hosts on the egress allowlist, which must
+    // include the base URL of every provider in use
+    let egress = Arc::new(EgressPolicy::from_allowed_hosts(
+        config.security.network.allowed_hosts.as_deref(),
+    ));
+    let check_egress = |provider: &str, base_url: &str| -> Result<()> {
+        egress.check_url(base_url).with_context(|| {
+            format!(
+                "security.network.allowed_hosts must include the host of the {} base URL {}",
+                provider, base_url
+            )
+        })
+    };
+
+    // Add Ollama provider (always configured with defaults), unless the
+    // allowlist leaves it out and it isn't the default provider
+    if egress.check_url(&config.llm.ollama.base_url).is_ok()
+        || config.llm.default_provider == "ollama"
+    {
+        check_egress("ollama", &config.llm.ollama.base_url)?;
+        let ollama = OllamaProvider::new(
+            config.llm.ollama.base_url.clone(),
+            config.llm.ollama.model.clone(),
+        )
+        .with_egress_policy(egress.clone());
+        providers.push(Box::new(ollama));
+    } else {
+        tracing::info!("Ollama is not on security.network.allowed_hosts, skipping it");
+    }
 
     // Initialize SecretCache
     use crate::secrets::{SecretCache, SecretManager};
@@ -68,34 +163,38 @@ pub async fn handle_run(task: String, config: &Config, format: OutputFormat) ->
     // (don't prompt interactively — Ollama works without any keys)
     if secret_manager.has_secret("openai_api_key") {
         use crate::llm::openai::OpenAIProvider;
-        providers.push(Box::new(OpenAIProvider::new(
-            config.llm.openai.clone(),
-            secret_cache.clone(),
-        )));
+        check_egress("openai", &config.llm.openai.base_url)?;
+        providers.push(Box::new(
+            OpenAIProvider::new(config.llm.openai.clone(), secret_cache.clone())
+                .with_egress_policy(egress.clone()),
+        ));
     }
 
     if secret_manager.has_secret("anthropic_api_key") {
         use crate::llm::anthropic::AnthropicProvider;
-        providers.push(Box::new(AnthropicProvider::new(
-            config.llm.anthropic.clone(),
-            secret_cache.clone(),
-        )));
+        check_egress("anthropic", &config.llm.anthropic.base_url)?;
+        providers.push(Box::new(
+            AnthropicProvider::new(config.llm.anthropic.clone(), secret_cache.clone())
+                .with_egress_policy(egress.clone()),
+        ));
     }
 
     if secret_manager.has_secret("gemini_api_key") {
         use crate::llm::gemini::GeminiProvider;
-        providers.push(Box::new(GeminiProvider::new(
-            config.llm.gemini.clone(),
-            secret_cache.clone(),
-        )));
+        check_egress("gemini", &config.llm.gemini.base_url)?;
+        providers.push(Box::new(
+            GeminiProvider::new(config.llm.gemini.clone(), secret_cache.clone())
+                .with_egress_policy(egress.clone()),
+        ));
     }
 
     if secret_manager.has_secret("nvidia_nim_api_key") {
         use crate::llm::nvidia_nim::NvidiaNimProvider;
-        providers.push(Box::new(NvidiaNimProvider::new(
-            config.llm.nvidia_nim.clone(),
-            secret_cache.clone(),
-        )));
+        check_egress("nvidia_nim", &config.llm.nvidia_nim.base_url)?;
+        providers.push(Box::new(
+            NvidiaNimProvider::new(config.llm.nvidia_nim.clone(), secret_cache.clone())
+                .with_egress_policy(egress.clone()),
+        ));
     }
 
     if providers.is_empty() {
@@ -107,8 +206,18 @@ pub async fn handle_run(task: String, config: &Config, format: OutputFormat) ->
     // Create LLM router
     let router = Arc::new(LLMRouter::new(providers, Arc::new(config.llm.clone())));
 
+    // A first provider that can't be used doesn't stop the task while others
+    // can take it, but the user should still see how to fix it. A fatal
+    // problem fails the task itself with the same guidance.
+    if let Ok(Some(problem)) = router.ensure_default_provider().await {
+        eprintln!("Warning: {}", problem);
+    }
+
     // Create rate limiter
-    let rate_limiter = Arc::new(RateLimiter::new(database.pool().clone()));
+    let rate_limiter = Arc::new(RateLimiter::from_config(
+        database.pool().clone(),
+        &config.security,
+    ));
 
     // Create risk assessor
     let risk_assessor = RiskAssessor::new();
@@ -116,120 +225,347 @@ pub async fn handle_run(task: String, config: &Config, format: OutputFormat) ->
     // Create task repository
     let task_repo = Arc::new(TaskRepository::new(database.pool().clone()));
 
-    // Create tool registry based on config flags
-    let workspace = config.core.workspace.clone();
-    let workspace_str = workspace.to_string_lossy().to_string();
-
-    let tools = Arc::new(ToolRegistry {
-        fs: if config.plugins.fs_editor {
-            Some(FilesystemTool::new(workspace.clone()))
-        } else {
-            None
-        },
-        terminal: if config.plugins.terminal {
-            Some(TerminalTool::new(workspace_str))
-        } else {
-            None
-        },
-        vision: if config.plugins.screenshot {
-            Some(VisionTool::new(workspace.clone()))
-        } else {
-            None
-        },
-    });
+    // Create tool registry based on config flags, scoped to the active workspace
+    let mut tools = build_tool_registry(config, no_daemon);
+    if let Some(bytes) = max_file_size {
+        tools.fs = tools.fs.map(|fs| fs.with_max_read_size(bytes));
+    }
+    // Input piped to `rove run` can be written with write_file's from_stdin
+    if !std::io::IsTerminal::is_terminal(&std::io::stdin()) {
+        tools.fs = tools.fs.map(|fs| fs.with_stdin(Box::new(std::io::stdin())));
+    }
+    let tools = Arc::new(tools);
 
     // Load steering engine from config
-    let steering = {
-        let skill_dir = if config.steering.skill_dir.to_string_lossy().starts_with("~/") {
-            let home = dirs::home_dir()
-                .ok_or_else(|| anyhow::anyhow!("Cannot determine home directory"))?;
-            let rest = config.steering.skill_dir.to_string_lossy();
-            let rest = rest.strip_prefix("~/").unwrap_or(&rest);
-            home.join(rest)
-        } else {
-            config.steering.skill_dir.clone()
-        };
+    let steering = load_steering(config).await?;
 
-        if config.steering.auto_detect {
-            match SteeringEngine::new(&skill_dir).await {
-                Ok(engine) => Some(engine),
-                Err(e) => {
-                    tracing::warn!("Failed to load steering engine: {}", e);
-                    None
-                }
-            }
+    // Create agent
+    let prompt_template = PromptTemplate::load(config.steering.prompt_template.as_deref());
+    let mut agent = AgentCore::new(
+        router,
+        risk_assessor,
+        rate_limiter,
+        task_repo,
+        tools,
+        steering,
+    )
+    .with_prompt_template(prompt_template)
+    .with_workspace(config.workspace())
+    .with_max_call_depth(config.conductor.max_call_depth)
+    .with_uncached_tools(config.conductor.uncached_tools.iter().cloned());
+
+    if config.conductor.summarize {
+        agent = agent.with_task_summary();
+    }
+
+    if config.steering.verify_with_cloud {
+        agent = agent.with_cloud_verification();
+    }
+
+    if dump_transcript.is_some() {
+        agent = agent.with_transcript();
+    }
+
+    // --timeout overrides the configured deadline
+    let timeout = timeout.or(config
+        .conductor
+        .task_timeout_secs
+        .map(std::time::Duration::from_secs));
+    if let Some(timeout) = timeout {
+        agent = agent.with_task_timeout(timeout);
+    }
+
+    if config.conductor.plan_first {
+        let approval: Option<crate::agent::PlanApproval> = if config.conductor.confirm_plan {
+            Some(Arc::new(confirm_plan_on_stdin))
         } else {
             None
+        };
+        agent = agent.with_plan_first(approval);
+        if config.conductor.batch_tier2_approval {
+            agent = agent.with_tier2_batch_approval(Arc::new(confirm_tier2_batch_on_stdin));
         }
-    };
-
-    // Create agent
-    let mut agent = AgentCore::new(router, risk_assessor, rate_limiter, task_repo, tools, steering);
+    }
 
-    // Create task
-    let agent_task = Task::new(task.clone(), OperationSource::Local);
+    let countdown = Countdown::new(
+        std::time::Duration::from_secs(config.security.confirm_tier1_delay),
+        config.security.countdown_style,
+    );
+    agent = agent.with_confirmer(
+        Arc::new(TerminalConfirmer::new(countdown)),
+        ConfirmationPolicy::from(&config.security),
+    );
 
-    match format {
+    // JSON output is a stream of NDJSON progress events
+    let events = match format {
         OutputFormat::Text => {
-            println!("Executing task: {}", task);
+            match (&resume, &task) {
+                (Some(task_id), _) => println!("Resuming task: {}", task_id),
+                (None, Some(task)) => println!("Executing task: {}", task),
+                (None, None) => {}
+            }
             println!();
+            None
         }
         OutputFormat::Json => {
-            let output = json!({
-                "status": "running",
-                "task": task.clone()
-            });
-            println!("{}", serde_json::to_string_pretty(&output)?);
+            let events = ndjson_sink(std::io::stdout());
+            agent = agent.with_event_sink(events.clone());
+            Some(events)
         }
-    }
+    };
 
     // Execute task
-    let result = agent.process_task(agent_task).await;
+    let result = match (resume, task) {
+        (Some(task_id), _) => agent.resume_task(&task_id).await,
+        (None, Some(task)) => {
+            agent
+                .process_task(Task::new(task, OperationSource::Local))
+                .await
+        }
+        (None, None) => Err(anyhow::anyhow!("No task given to run or resume")),
+    };
+
+    if let Some(path) = dump_transcript.as_deref() {
+        let dump = match &result {
+            Ok(task_result) => {
+                TranscriptDump::new(Some(task_result.task_id.clone()), agent.transcript(), None)
+            }
+            Err(e) => TranscriptDump::new(
+                e.downcast_ref::<TaskTimeout>()
+                    .map(|timeout| timeout.task_id.clone()),
+                agent.transcript(),
+                Some(e.to_string()),
+            ),
+        };
+        match dump.write(path) {
+            Ok(()) => tracing::info!("Transcript written to {}", path.display()),
+            Err(e) => tracing::warn!("{:#}", e),
+        }
+    }
+
+    if let Some(scratch) = scratch {
+        // Tear down in-process components before reporting
+        drop(agent);
+        if let Err(e) = database.close().await {
+            tracing::warn!("Failed to close database: {}", e);
+        }
+        if let Err(e) = scratch.close() {
+            tracing::warn!("Failed to remove the scratch data directory: {}", e);
+        }
+    }
 
     match result {
         Ok(task_result) => {
-            match format {
-                OutputFormat::Text => {
+            match events {
+                None => {
                     println!("Result:");
                     println!("{}", task_result.answer);
                     println!();
                     println!("✓ Task completed successfully");
                     println!("  Provider: {}", task_result.provider_used);
+                    println!("  Routing: {}", task_result.routing);
                     println!("  Duration: {}ms", task_result.duration_ms);
                     println!("  Iterations: {}", task_result.iterations);
                 }
-                OutputFormat::Json => {
-                    let output = json!({
-                        "status": "completed",
-                        "task_id": task_result.task_id,
-                        "answer": task_result.answer,
-                        "provider": task_result.provider_used,
-                        "duration_ms": task_result.duration_ms,
-                        "iterations": task_result.iterations
-                    });
-                    println!("{}", serde_json::to_string_pretty(&output)?);
-                }
+                Some(events) => events(&TaskEvent::TaskCompleted {
+                    task_id: task_result.task_id,
+                    provider: task_result.provider_used,
+                    routing: task_result.routing,
+                    duration_ms: task_result.duration_ms,
+                    iterations: task_result.iterations,
+                }),
             }
             Ok(())
         }
         Err(e) => {
-            match format {
-                OutputFormat::Text => {
+            match events {
+                None => {
                     println!("✗ Task failed: {}", e);
+                    if let Some(timeout) = e.downcast_ref::<TaskTimeout>() {
+                        println!();
+                        println!("Partial transcript ({} steps):", timeout.transcript.len());
+                        for step in &timeout.transcript {
+                            println!("Step {}: {:?}", step.step_order, step.step_type);
+                            println!("  {}", step.content);
+                        }
+                    }
                 }
-                OutputFormat::Json => {
-                    let output = json!({
-                        "status": "failed",
-                        "error": e.to_string()
-                    });
-                    println!("{}", serde_json::to_string_pretty(&output)?);
-                }
+                Some(events) => events(&TaskEvent::TaskFailed {
+                    error: e.to_string(),
+                }),
             }
             Err(e)
         }
     }
 }
 
+/// Show a plan on stderr and ask for approval on stdin
+///
+/// Anything other than "y"/"yes" (including a closed stdin) rejects the plan.
+/// Without a terminal there is nobody to ask, so the plan goes ahead like a
+/// Tier 1 countdown does; each of its operations is still confirmed by tier.
+fn confirm_plan_on_stdin(plan: &crate::conductor::ConductorPlan) -> bool {
+    eprintln!("{}", crate::agent::format_plan(plan));
+    if !std::io::IsTerminal::is_terminal(&std::io::stdin()) {
+        eprintln!("(stdin is not a terminal, proceeding with the plan)");
+        return true;
+    }
+    crate::confirmation::ask_yes_no("Execute this plan?")
+}
+
+/// Show the Tier 2 calls of a plan on stderr and ask to approve them all
+///
+/// Anything other than "y"/"yes" typed at a terminal (including a closed or
+/// piped stdin) leaves each call to be confirmed on its own.
+fn confirm_tier2_batch_on_stdin(batch: &[crate::conductor::PlannedToolCall]) -> bool {
+    eprintln!("The plan will run {} Tier 2 operation(s):", batch.len());
+    for call in batch {
+        eprintln!("  {} {}", call.tool, call.arguments);
+    }
+    crate::confirmation::ask_yes_no("Approve all of them now?")
+}
+
+/// Build the agent's tool registry from the plugin flags in `config`
+///
+/// Tools are scoped to the active workspace. With `no_daemon` no workspace
+/// index watcher is started.
+fn build_tool_registry(config: &Config, no_daemon: bool) -> crate::tools::ToolRegistry {
+    use crate::tools::{
+        FilesystemTool, GitTool, TerminalTool, ToolRegistry, VisionTool, WorkspaceIndex,
+    };
+    use std::sync::Arc;
+
+    let workspace = config.workspace().to_path_buf();
+    let workspace_str = workspace.to_string_lossy().to_string();
+
+    ToolRegistry {
+        fs: if config.plugins.fs_editor {
+            let fs = FilesystemTool::new(workspace.clone());
+            // A one-shot run gains nothing from a watcher thread
+            if config.core.index_workspace && !no_daemon {
+                match WorkspaceIndex::build(workspace.clone()).watch() {
+                    Ok(index) => Some(fs.with_index(Arc::new(index))),
+                    Err(e) => {
+                        tracing::warn!("Workspace indexing disabled: {}", e);
+                        Some(fs)
+                    }
+                }
+            } else {
+                Some(fs)
+            }
+        } else {
+            None
+        },
+        terminal: if config.plugins.terminal {
+            let executor = crate::daemon::command_executor(config);
+            Some(TerminalTool::new(workspace_str).with_executor(executor))
+        } else {
+            None
+        },
+        vision: if config.plugins.screenshot {
+            Some(VisionTool::new(workspace.clone()))
+        } else {
+            None
+        },
+        git: if config.plugins.git {
+            Some(GitTool::new(workspace.clone()))
+        } else {
+            None
+        },
+    }
+}
+
+/// Load the steering engine from `steering.skill_dir`
+///
+/// Returns `None` if steering is disabled or the skills can't be loaded.
+async fn load_steering(config: &Config) -> Result<Option<crate::agent::SteeringEngine>> {
+    use crate::agent::SteeringEngine;
+
+    if !config.steering.auto_detect {
+        return Ok(None);
+    }
+
+    let configured = config.steering.skill_dir.to_string_lossy();
+    let skill_dir = match configured.strip_prefix("~/") {
+        Some(rest) => dirs::home_dir()
+            .ok_or_else(|| anyhow::anyhow!("Cannot determine home directory"))?
+            .join(rest),
+        None => config.steering.skill_dir.clone(),
+    };
+
+    match SteeringEngine::new(&skill_dir).await {
+        Ok(engine) => Ok(Some(engine)),
+        Err(e) => {
+            tracing::warn!("Failed to load steering engine: {}", e);
+            Ok(None)
+        }
+    }
+}
+
+/// Print the system prompt the agent would use for a task
+///
+/// The prompt is composed exactly as `rove run` would compose it (template,
+/// tool descriptions and the directives of the skills active for `task`),
+/// without contacting a provider or running anything. `skills` are activated
+/// manually before auto-activation, like skills enabled for a session.
+pub async fn handle_export_prompt(
+    task: Option<String>,
+    skills: &[String],
+    config: &Config,
+    format: OutputFormat,
+) -> Result<()> {
+    use crate::agent::{compose_system_prompt, PromptTemplate};
+    use crate::risk_assessor::{Operation, OperationSource, RiskAssessor};
+
+    let task = task.unwrap_or_default();
+    let tools = build_tool_registry(config, true);
+    let mut steering = load_steering(config).await?;
+
+    if !skills.is_empty() {
+        let engine = steering
+            .as_mut()
+            .ok_or_else(|| anyhow::anyhow!("Skills are disabled (steering.auto_detect = false)"))?;
+        for skill in skills {
+            engine.activate(skill)?;
+        }
+    }
+
+    let risk_tier = RiskAssessor::new()
+        .assess(&Operation::new(
+            "execute_task",
+            vec![],
+            OperationSource::Local,
+        ))
+        .context("Failed to assess risk tier")?;
+    let prompt_template = PromptTemplate::load(config.steering.prompt_template.as_deref());
+    let prompt = compose_system_prompt(
+        &prompt_template,
+        Some(config.workspace()),
+        &tools.tool_descriptions(),
+        steering.as_mut(),
+        &task,
+        risk_tier as u8,
+    );
+    let active_skills = steering
+        .as_ref()
+        .map(|engine| engine.active_skills().to_vec())
+        .unwrap_or_default();
+
+    match format {
+        OutputFormat::Text => println!("{}", prompt),
+        OutputFormat::Json => {
+            let output = json!({
+                "task": task,
+                "active_skills": active_skills,
+                "system_prompt": prompt,
+            });
+            println!("{}", serde_json::to_string_pretty(&output)?);
+        }
+    }
+
+    Ok(())
+}
+
 /// Show task history
 ///
 /// This handler retrieves and displays the last N tasks from the database.
@@ -265,6 +601,19 @@ pub async fn handle_history(limit: usize, config: &Config, format: OutputFormat)
                 println!("  Input: {}", task.input);
                 println!("  Status: {:?}", task.status);
 
+                if let Some(ref summary) = task.summary {
+                    println!("  Summary: {}", summary.replace('\n', "\n           "));
+                }
+
+                if let Some(ref failure) = task.failure {
+                    println!(
+                        "  Failure: {} after {} steps: {}",
+                        failure.reason.as_str(),
+                        failure.steps,
+                        failure.message
+                    );
+                }
+
                 if let Some(provider) = task.provider_used {
                     println!("  Provider: {}", provider);
                 }
@@ -273,6 +622,10 @@ pub async fn handle_history(limit: usize, config: &Config, format: OutputFormat)
                     println!("  Duration: {}ms", duration);
                 }
 
+                if task.total_tokens > 0 {
+                    println!("  Usage: ~{} tokens, ${:.4}", task.total_tokens, task.cost);
+                }
+
                 // Format timestamp
                 let created = chrono::DateTime::from_timestamp(task.created_at, 0)
                     .map(|dt| dt.format("%Y-%m-%d %H:%M:%S").to_string())
@@ -295,6 +648,215 @@ pub async fn handle_history(limit: usize, config: &Config, format: OutputFormat)
     Ok(())
 }
 
+/// Show the estimated LLM spend of all tasks, or of those since `since`
+pub async fn handle_cost(since: Option<i64>, config: &Config, format: OutputFormat) -> Result<()> {
+    let db_path = get_db_path(config)?;
+    let database = Database::new(&db_path)
+        .await
+        .context("Failed to open database")?;
+
+    let summary = TaskRepository::new(database.pool().clone())
+        .cost_summary(since)
+        .await
+        .context("Failed to total task costs")?;
+
+    let since_label = since.and_then(|ts| {
+        chrono::DateTime::from_timestamp(ts, 0).map(|dt| dt.format("%Y-%m-%d %H:%M:%S").to_string())
+    });
+
+    match format {
+        OutputFormat::Text => {
+            match since_label {
+                Some(ref label) => println!("LLM spend since {} UTC:", label),
+                None => println!("LLM spend (all tasks):"),
+            }
+            println!("  Tasks: {}", summary.tasks);
+            println!("  Tokens: ~{}", summary.total_tokens);
+            println!("  Cost: ${:.4}", summary.cost);
+        }
+        OutputFormat::Json => {
+            let output = json!({
+                "since": since,
+                "tasks": summary.tasks,
+                "total_tokens": summary.total_tokens,
+                "cost": summary.cost
+            });
+            println!("{}", serde_json::to_string_pretty(&output)?);
+        }
+    }
+
+    Ok(())
+}
+
+/// Export the audit log as signed JSON
+///
+/// Without `output` the export is written to stdout. The signing key is
+/// generated and stored in the keychain on first use.
+pub async fn handle_audit_export(
+    output: Option<&Path>,
+    config: &Config,
+    format: OutputFormat,
+) -> Result<()> {
+    let db_path = get_db_path(config)?;
+    let database = Database::new(&db_path)
+        .await
+        .context("Failed to open database")?;
+
+    let secrets = crate::secrets::SecretManager::new("rove");
+    let key = crate::audit::load_or_create_signing_key(&secrets, &config.audit.signing_key_secret)
+        .context("Failed to load audit signing key")?;
+
+    let export = crate::audit::export(&database, &key)
+        .await
+        .context("Failed to export audit log")?;
+    let document = serde_json::to_string_pretty(&export)?;
+
+    let Some(path) = output else {
+        println!("{}", document);
+        return Ok(());
+    };
+
+    std::fs::write(path, document)
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+
+    match format {
+        OutputFormat::Text => {
+            println!(
+                "Exported {} tasks to {}",
+                export.tasks.len(),
+                path.display()
+            );
+            println!("  Signing key: {}", export.public_key);
+        }
+        OutputFormat::Json => {
+            let output = json!({
+                "path": path,
+                "tasks": export.tasks.len(),
+                "public_key": export.public_key
+            });
+            println!("{}", serde_json::to_string_pretty(&output)?);
+        }
+    }
+
+    Ok(())
+}
+
+/// Verify the signature of an exported audit log
+///
+/// If this machine has an audit signing key, the export must have been
+/// signed with it. Otherwise only the embedded key is checked.
+pub fn handle_audit_verify(file: &Path, config: &Config, format: OutputFormat) -> Result<()> {
+    let document =
+        std::fs::read(file).with_context(|| format!("Failed to read {}", file.display()))?;
+
+    let secrets = crate::secrets::SecretManager::new("rove");
+    let trusted = crate::audit::load_signing_key(&secrets, &config.audit.signing_key_secret)
+        .context("Failed to load audit signing key")?
+        .map(|key| key.verifying_key());
+
+    let verified = crate::audit::verify(&document, trusted.as_ref())?;
+
+    match format {
+        OutputFormat::Text => {
+            let exported = chrono::DateTime::from_timestamp(verified.exported_at, 0)
+                .map(|dt| dt.format("%Y-%m-%d %H:%M:%S").to_string())
+                .unwrap_or_else(|| "Unknown".to_string());
+            println!("✓ Signature valid");
+            println!("  Tasks: {}", verified.tasks);
+            println!("  Exported: {} UTC", exported);
+            println!("  Signing key: {}", verified.public_key);
+            if !verified.trusted {
+                println!("  Warning: no local audit key; the signer was not checked");
+            }
+        }
+        OutputFormat::Json => {
+            let output = json!({
+                "valid": true,
+                "tasks": verified.tasks,
+                "exported_at": verified.exported_at,
+                "public_key": verified.public_key,
+                "trusted": verified.trusted
+            });
+            println!("{}", serde_json::to_string_pretty(&output)?);
+        }
+    }
+
+    Ok(())
+}
+
+/// Run the data-retention job once
+pub async fn handle_db_prune(config: &Config, format: OutputFormat) -> Result<()> {
+    let database = open_database(config).await?;
+    let report = crate::db::prune(&database, &config.retention).await?;
+
+    match format {
+        OutputFormat::Text => {
+            println!("Pruned {} task(s)", report.tasks);
+            println!("Pruned {} audit-log step(s)", report.task_steps);
+            println!("Pruned {} rate-limit entries", report.rate_limits);
+        }
+        OutputFormat::Json => {
+            println!("{}", serde_json::to_string_pretty(&report)?);
+        }
+    }
+
+    Ok(())
+}
+
+/// Print the config file at `path`, or with `effective` every setting in use
+///
+/// The effective config includes the defaults the file leaves out and the
+/// active workspace picked with `rove workspace use`, and marks them; as
+/// JSON it is `{"config", "sources"}`, with the source (`file`, `default` or
+/// `override`) of each dotted key.
+pub fn handle_config_dump(
+    config: &Config,
+    path: &Path,
+    effective: bool,
+    format: OutputFormat,
+) -> Result<()> {
+    let contents = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+
+    if !effective {
+        match format {
+            OutputFormat::Text => print!("{}", contents),
+            OutputFormat::Json => {
+                let file: toml::Table = toml::from_str(&contents)
+                    .with_context(|| format!("Failed to parse {}", path.display()))?;
+                println!("{}", serde_json::to_string_pretty(&file)?);
+            }
+        }
+        return Ok(());
+    }
+
+    let effective = config.effective(&contents)?;
+    match format {
+        OutputFormat::Text => {
+            println!(
+                "# Effective configuration from {}; values marked default are not set there, \
+                 values marked override replace it",
+                path.display()
+            );
+            println!();
+            print!("{}", effective.to_annotated_toml());
+        }
+        OutputFormat::Json => {
+            println!("{}", serde_json::to_string_pretty(&effective)?);
+        }
+    }
+
+    Ok(())
+}
+
+/// Open the engine database in the configured data directory
+pub async fn open_database(config: &Config) -> Result<Database> {
+    let db_path = get_db_path(config)?;
+    Database::new(&db_path)
+        .await
+        .context("Failed to open database")
+}
+
 /// Replay a task and show all steps
 ///
 /// This handler retrieves a task and all its steps from the database
@@ -330,6 +892,15 @@ pub async fn handle_replay(task_id: String, config: &Config, format: OutputForma
             println!("Input: {}", task.input);
             println!("Status: {:?}", task.status);
 
+            if let Some(ref failure) = task.failure {
+                println!(
+                    "Failure: {} after {} steps: {}",
+                    failure.reason.as_str(),
+                    failure.steps,
+                    failure.message
+                );
+            }
+
             if let Some(provider) = task.provider_used {
                 println!("Provider: {}", provider);
             }
@@ -364,79 +935,449 @@ pub async fn handle_replay(task_id: String, config: &Config, format: OutputForma
 /// List all installed plugins
 ///
 /// This handler retrieves and displays all plugins from the database.
+/// With `check_updates` the official registry is queried and plugins with a
+/// newer version are annotated.
 ///
 /// Requirements: 15.6
-pub async fn handle_plugins_list(config: &Config, format: OutputFormat) -> Result<()> {
+pub async fn handle_plugins_list(
+    config: &Config,
+    format: OutputFormat,
+    check_updates: bool,
+) -> Result<()> {
     // Initialize database
     let db_path = get_db_path(config)?;
-    let _database = Database::new(&db_path)
+    let database = Database::new(&db_path)
         .await
         .context("Failed to open database")?;
+    let installed = crate::db::PluginRepository::new(database.pool().clone())
+        .get_all_plugins()
+        .await
+        .context("Failed to list plugins")?;
+
+    // Built-in plugins are enabled in config; the database knows their version
+    let mut plugins: Vec<PluginListing> = [
+        ("fs-editor", config.plugins.fs_editor),
+        ("terminal", config.plugins.terminal),
+        ("screenshot", config.plugins.screenshot),
+        ("git", config.plugins.git),
+    ]
+    .into_iter()
+    .map(|(name, enabled)| PluginListing {
+        name: name.to_string(),
+        enabled,
+        version: installed
+            .iter()
+            .find(|p| p.id == name || p.name == name)
+            .map(|p| p.version.clone()),
+        update_available: None,
+    })
+    .collect();
+    for plugin in &installed {
+        if !plugins
+            .iter()
+            .any(|p| p.name == plugin.id || p.name == plugin.name)
+        {
+            plugins.push(PluginListing {
+                name: plugin.id.clone(),
+                enabled: plugin.enabled,
+                version: Some(plugin.version.clone()),
+                update_available: None,
+            });
+        }
+    }
 
-    // TODO: Implement plugin listing from database
-    // For now, show configured plugins from config
+    let mut update_check_error = None;
+    if check_updates {
+        match rove_plugins::registry::fetch_manifest().await {
+            Ok(manifest) => {
+                for plugin in &mut plugins {
+                    if let Some(version) = &plugin.version {
+                        plugin.update_available = rove_plugins::registry::available_update(
+                            &manifest,
+                            &plugin.name,
+                            version,
+                        )
+                        .map(str::to_string);
+                    }
+                }
+            }
+            Err(e) => {
+                tracing::warn!("Failed to check for plugin updates: {:#}", e);
+                update_check_error = Some(e.to_string());
+            }
+        }
+    }
 
     match format {
         OutputFormat::Text => {
             println!("Installed Plugins:");
             println!();
 
-            println!(
-                "  fs-editor: {}",
-                if config.plugins.fs_editor {
-                    "enabled"
-                } else {
-                    "disabled"
-                }
-            );
-            println!(
-                "  terminal: {}",
-                if config.plugins.terminal {
+            for plugin in &plugins {
+                let state = if plugin.enabled {
                     "enabled"
                 } else {
                     "disabled"
+                };
+                match (&plugin.version, &plugin.update_available) {
+                    (Some(version), Some(latest)) => println!(
+                        "  {}: {} (v{}, update available: {}→{})",
+                        plugin.name, state, version, version, latest
+                    ),
+                    (Some(version), None) => {
+                        println!("  {}: {} (v{})", plugin.name, state, version)
+                    }
+                    (None, _) => println!("  {}: {}", plugin.name, state),
                 }
-            );
-            println!(
-                "  screenshot: {}",
-                if config.plugins.screenshot {
-                    "enabled"
+            }
+
+            if let Some(e) = update_check_error {
+                println!();
+                println!("Could not check for updates: {}", e);
+            }
+        }
+        OutputFormat::Json => {
+            let output = json!({
+                "plugins": plugins,
+                "update_check_error": update_check_error
+            });
+            println!("{}", serde_json::to_string_pretty(&output)?);
+        }
+    }
+
+    Ok(())
+}
+
+/// A row of `rove plugins list`
+#[derive(Debug, serde::Serialize)]
+struct PluginListing {
+    name: String,
+    enabled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    version: Option<String>,
+    /// Newer registry version, with `--check-updates`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    update_available: Option<String>,
+}
+
+/// Search the official and community plugin registries
+///
+/// Community plugins are marked as needing consent before install.
+pub async fn handle_plugins_search(query: &str, format: OutputFormat) -> Result<()> {
+    use rove_community_plugins::search::{search, COMMUNITY_REGISTRY_URL};
+    use rove_plugins::registry::OFFICIAL_REGISTRY_URL;
+
+    let results = search(OFFICIAL_REGISTRY_URL, COMMUNITY_REGISTRY_URL, query)
+        .await
+        .context("Failed to search plugin registries")?;
+
+    match format {
+        OutputFormat::Text => {
+            if results.is_empty() {
+                println!("No plugins match '{}'", query);
+                return Ok(());
+            }
+            for result in &results {
+                let author = if result.author.is_empty() {
+                    "unknown"
                 } else {
-                    "disabled"
+                    &result.author
+                };
+                print!(
+                    "  {} v{} by {} [{}]",
+                    result.id,
+                    result.version,
+                    author,
+                    result.trust.as_str()
+                );
+                if result.requires_consent {
+                    print!(" (requires consent)");
                 }
-            );
-            println!(
-                "  git: {}",
-                if config.plugins.git {
-                    "enabled"
-                } else {
-                    "disabled"
+                println!();
+                if !result.description.is_empty() {
+                    println!("    {}", result.description);
                 }
-            );
+            }
+            println!();
+            println!("{} plugin(s) found.", results.len());
+        }
+        OutputFormat::Json => {
+            let output = json!({ "query": query, "plugins": results });
+            println!("{}", serde_json::to_string_pretty(&output)?);
+        }
+    }
+
+    Ok(())
+}
+
+/// Update the installed plugin `id` to the official registry's latest version
+///
+/// If the new version requests more permissions than the installed one, they
+/// are listed and the user must consent (or pass `yes`); otherwise the
+/// installed version is kept.
+pub async fn handle_plugins_update(
+    config: &Config,
+    id: &str,
+    yes: bool,
+    format: OutputFormat,
+) -> Result<()> {
+    use crate::runtime::{Installer, UpdateOutcome};
+    use rove_plugins::registry::{available_update, fetch_manifest, find_plugin};
+
+    let db_path = get_db_path(config)?;
+    let database = Database::new(&db_path)
+        .await
+        .context("Failed to open database")?;
+    let plugins = crate::db::PluginRepository::new(database.pool().clone());
+    let plugin = plugins
+        .get_plugin(id)
+        .await
+        .context("Failed to load plugin")?
+        .ok_or_else(|| anyhow::anyhow!("Plugin '{}' is not installed", id))?;
+
+    let registry = fetch_manifest()
+        .await
+        .context("Failed to fetch plugin registry")?;
+    let (Some(_), Some(entry)) = (
+        available_update(&registry, &plugin.id, &plugin.version),
+        find_plugin(&registry, &plugin.id),
+    ) else {
+        match format {
+            OutputFormat::Text => println!("{} v{} is up to date", plugin.id, plugin.version),
+            OutputFormat::Json => println!(
+                "{}",
+                json!({ "plugin": plugin.id, "version": plugin.version, "updated": false })
+            ),
+        }
+        return Ok(());
+    };
+
+    let installed = installed_entry(&plugin)?;
+    let updated = sdk::manifest::PluginEntry {
+        version: entry.version.clone(),
+        hash: entry.hash.clone(),
+        permissions: entry.permissions.clone(),
+        ..installed.clone()
+    };
+    let added = installed.permissions.expansions(&updated.permissions);
+    let consented = yes || added.is_empty() || {
+        eprintln!(
+            "{} v{} requests new permissions: {}",
+            plugin.id,
+            entry.version,
+            added.join(", ")
+        );
+        tokio::task::spawn_blocking(|| crate::confirmation::ask_yes_no("Grant them and update?"))
+            .await?
+    };
+
+    let installer = Installer::new();
+    let outcome = update_installed_plugin(
+        &plugins,
+        &installer,
+        &plugin,
+        &entry.download_url,
+        &updated,
+        |_| consented,
+    )
+    .await?;
+
+    match format {
+        OutputFormat::Text => match &outcome {
+            UpdateOutcome::Updated => {
+                println!(
+                    "Updated {} v{} → v{}",
+                    plugin.id, plugin.version, entry.version
+                )
+            }
+            UpdateOutcome::ConsentRequired { .. } => println!(
+                "Kept {} v{}: the new permissions were not granted",
+                plugin.id, plugin.version
+            ),
+        },
+        OutputFormat::Json => {
+            let (updated, added) = match outcome {
+                UpdateOutcome::Updated => (true, Vec::new()),
+                UpdateOutcome::ConsentRequired { added } => (false, added),
+            };
+            let output = json!({
+                "plugin": plugin.id,
+                "version": if updated { &entry.version } else { &plugin.version },
+                "updated": updated,
+                "permissions_not_granted": added,
+            });
+            println!("{}", serde_json::to_string_pretty(&output)?);
+        }
+    }
+
+    Ok(())
+}
+
+/// Download `updated` from `url` over the installed `plugin` and record it
+///
+/// The installed permissions are read from the plugin's stored manifest
+/// entry, and `consent` is asked as by `Installer::update`. The database is
+/// only changed once the new version is in place.
+pub async fn update_installed_plugin<F>(
+    plugins: &crate::db::PluginRepository,
+    installer: &crate::runtime::Installer,
+    plugin: &crate::db::Plugin,
+    url: &str,
+    updated: &sdk::manifest::PluginEntry,
+    consent: F,
+) -> Result<crate::runtime::UpdateOutcome>
+where
+    F: FnOnce(&[String]) -> bool,
+{
+    let installed = installed_entry(plugin)?;
+    let outcome = installer
+        .update(
+            url,
+            Path::new(&plugin.wasm_path),
+            &installed.permissions,
+            updated,
+            consent,
+        )
+        .await
+        .with_context(|| format!("Failed to update plugin '{}'", plugin.id))?;
+
+    if outcome == crate::runtime::UpdateOutcome::Updated {
+        plugins
+            .update_plugin(
+                &plugin.id,
+                &updated.version,
+                &plugin.wasm_path,
+                &updated.hash,
+                &serde_json::to_string(updated)?,
+            )
+            .await?;
+    }
+    Ok(outcome)
+}
+
+/// Manifest entry the installed version of `plugin` was recorded with
+fn installed_entry(plugin: &crate::db::Plugin) -> Result<sdk::manifest::PluginEntry> {
+    serde_json::from_str(&plugin.manifest_json).with_context(|| {
+        format!(
+            "Stored manifest of plugin '{}' is unreadable; reinstall it",
+            plugin.id
+        )
+    })
+}
+
+/// Enable or disable plugins in the config file
+///
+/// `ids` is a list of plugin ids; `all` selects every toggleable plugin
+/// instead. All flags are changed in memory first and then saved with a
+/// single config write, so an unknown id leaves the file untouched.
+pub fn handle_plugins_toggle(
+    ids: &[String],
+    all: bool,
+    enable: bool,
+    config: &mut Config,
+    config_path: &Path,
+    format: OutputFormat,
+) -> Result<()> {
+    use crate::config::TOGGLEABLE_PLUGINS;
+
+    let ids: Vec<&str> = if all {
+        TOGGLEABLE_PLUGINS.to_vec()
+    } else {
+        ids.iter()
+            .map(|id| id.trim())
+            .filter(|id| !id.is_empty())
+            .collect()
+    };
+    if ids.is_empty() {
+        anyhow::bail!("No plugins given. Pass plugin ids or --all");
+    }
+
+    for id in &ids {
+        config.plugins.set_enabled(id, enable)?;
+    }
+    config
+        .save_plugins(config_path)
+        .with_context(|| format!("Failed to save {}", config_path.display()))?;
+
+    let state = if enable { "enabled" } else { "disabled" };
+    match format {
+        OutputFormat::Text => {
+            for id in &ids {
+                println!("  {} {}", id, state);
+            }
+            println!("Saved {}", config_path.display());
+        }
+        OutputFormat::Json => {
+            let output = json!({ "plugins": ids, "enabled": enable });
+            println!("{}", serde_json::to_string_pretty(&output)?);
+        }
+    }
 
+    Ok(())
+}
+
+/// List configured workspaces
+///
+/// Shows `core.workspace` as "default" followed by every `[[workspaces]]`
+/// entry, marking the active one.
+pub fn handle_workspace_list(config: &Config, format: OutputFormat) -> Result<()> {
+    use crate::config::DEFAULT_WORKSPACE_NAME;
+
+    let active = config.active_workspace_name();
+    let mut entries = vec![(DEFAULT_WORKSPACE_NAME, config.core.workspace.as_path())];
+    entries.extend(
+        config
+            .workspaces
+            .iter()
+            .map(|w| (w.name.as_str(), w.path.as_path())),
+    );
+
+    match format {
+        OutputFormat::Text => {
+            println!("Workspaces:");
             println!();
-            println!("Note: Plugin database integration will be completed in a future task");
+            for (name, path) in &entries {
+                let marker = if *name == active { "*" } else { " " };
+                println!("{} {} ({})", marker, name, path.display());
+            }
+        }
+        OutputFormat::Json => {
+            let workspaces: Vec<_> = entries
+                .iter()
+                .map(|(name, path)| {
+                    json!({
+                        "name": name,
+                        "path": path.display().to_string(),
+                        "active": *name == active,
+                    })
+                })
+                .collect();
+            let output = json!({ "active": active, "workspaces": workspaces });
+            println!("{}", serde_json::to_string_pretty(&output)?);
+        }
+    }
+
+    Ok(())
+}
+
+/// Switch the active workspace
+///
+/// The selection persists across runs; file access and command working
+/// directories follow the new workspace from the next task on.
+pub fn handle_workspace_use(name: &str, config: &mut Config, format: OutputFormat) -> Result<()> {
+    let path = config
+        .use_workspace(name)
+        .context("Failed to switch workspace")?
+        .to_path_buf();
+
+    match format {
+        OutputFormat::Text => {
+            println!("Active workspace: {} ({})", name, path.display());
         }
         OutputFormat::Json => {
             let output = json!({
-                "plugins": [
-                    {
-                        "name": "fs-editor",
-                        "enabled": config.plugins.fs_editor
-                    },
-                    {
-                        "name": "terminal",
-                        "enabled": config.plugins.terminal
-                    },
-                    {
-                        "name": "screenshot",
-                        "enabled": config.plugins.screenshot
-                    },
-                    {
-                        "name": "git",
-                        "enabled": config.plugins.git
-                    }
-                ]
+                "active": name,
+                "path": path.display().to_string(),
             });
             println!("{}", serde_json::to_string_pretty(&output)?);
         }
@@ -448,10 +1389,11 @@ pub async fn handle_plugins_list(config: &Config, format: OutputFormat) -> Resul
 /// Run system diagnostics
 ///
 /// This handler validates the configuration, checks dependencies,
-/// verifies the manifest, and reports any issues.
+/// verifies the manifest, and reports any issues. With `fix`, problems that
+/// are safe to repair automatically (a stale PID file) are repaired.
 ///
 /// Requirements: 15.7
-pub async fn handle_doctor(config: &Config, format: OutputFormat) -> Result<()> {
+pub async fn handle_doctor(config: &Config, format: OutputFormat, fix: bool) -> Result<()> {
     let mut issues = Vec::new();
     let mut checks = Vec::new();
 
@@ -459,14 +1401,14 @@ pub async fn handle_doctor(config: &Config, format: OutputFormat) -> Result<()>
     checks.push(("Configuration", "Valid"));
     // Config is already validated when loaded
 
-    // Check 2: Workspace directory
-    if config.core.workspace.exists() {
+    // Check 2: Workspace directory (the active one)
+    if config.workspace().exists() {
         checks.push(("Workspace directory", "Exists"));
     } else {
         checks.push(("Workspace directory", "Missing"));
         issues.push(format!(
             "Workspace directory does not exist: {:?}",
-            config.core.workspace
+            config.workspace()
         ));
     }
 
@@ -559,6 +1501,7 @@ pub async fn handle_doctor(config: &Config, format: OutputFormat) -> Result<()>
     }
 
     // Check 7: Manifest verification
+    let mut verified_manifest = None;
     {
         let manifest_paths = [
             std::path::PathBuf::from("manifest/manifest.json"),
@@ -586,6 +1529,7 @@ pub async fn handle_doctor(config: &Config, format: OutputFormat) -> Result<()>
                                 } else {
                                     checks.push(("Manifest signature", "Valid"));
                                 }
+                                verified_manifest = Some(bytes.clone());
                             }
                             Err(_) => {
                                 checks.push(("Manifest signature", "INVALID"));
@@ -611,6 +1555,67 @@ pub async fn handle_doctor(config: &Config, format: OutputFormat) -> Result<()>
         }
     }
 
+    // Check 8: WASM plugins load and pass their self-test
+    let mut plugin_checks = Vec::new();
+    if let Some(bytes) = verified_manifest {
+        match check_wasm_plugins(config, &bytes).await {
+            Ok(results) => {
+                for (name, result) in results {
+                    match result {
+                        Ok(()) => plugin_checks.push((name, "OK".to_string())),
+                        Err(e) => {
+                            issues.push(format!("Plugin '{}' failed to load: {}", name, e));
+                            plugin_checks.push((name, "FAILED".to_string()));
+                        }
+                    }
+                }
+                checks.push(("WASM runtime", "OK"));
+            }
+            Err(e) => {
+                checks.push(("WASM runtime", "Error"));
+                issues.push(format!("Cannot check WASM plugins: {}", e));
+            }
+        }
+    }
+
+    // Check 9: Stale or foreign PID file
+    let mut stale_pid_file = None;
+    match DaemonManager::inspect_pid_file(config) {
+        Ok(PidFileState::Missing) => checks.push(("PID file", "None")),
+        Ok(PidFileState::Running { .. }) => checks.push(("PID file", "OK")),
+        Ok(PidFileState::Stale { pid }) => {
+            stale_pid_file = Some(format!("process {} is not running", pid));
+        }
+        Ok(PidFileState::Invalid { reason }) => stale_pid_file = Some(reason),
+        Ok(PidFileState::Foreign { pid, process }) => {
+            checks.push(("PID file", "Foreign process"));
+            issues.push(format!(
+                "PID file points at process {} ('{}'), which is not Rove. \
+                 The daemon likely crashed and the PID was reused; \
+                 remove the PID file manually once you have checked that process.",
+                pid, process
+            ));
+        }
+        Err(e) => {
+            checks.push(("PID file", "Error"));
+            issues.push(format!("Cannot inspect PID file: {}", e));
+        }
+    }
+    if let Some(problem) = stale_pid_file {
+        if !fix {
+            checks.push(("PID file", "Stale"));
+            issues.push(format!(
+                "Stale PID file ({}). Run 'rove doctor --fix' to remove it.",
+                problem
+            ));
+        } else if let Err(e) = DaemonManager::remove_stale_pid_file(config) {
+            checks.push(("PID file", "Stale"));
+            issues.push(format!("Cannot remove stale PID file: {}", e));
+        } else {
+            checks.push(("PID file", "Stale (removed)"));
+        }
+    }
+
     // Output results
     match format {
         OutputFormat::Text => {
@@ -623,6 +1628,14 @@ pub async fn handle_doctor(config: &Config, format: OutputFormat) -> Result<()>
                 println!("  {:<25} {}", format!("{}:", check), status);
             }
 
+            if !plugin_checks.is_empty() {
+                println!();
+                println!("Plugin Checks:");
+                for (plugin, status) in &plugin_checks {
+                    println!("  {:<25} {}", format!("{}:", plugin), status);
+                }
+            }
+
             println!();
 
             if issues.is_empty() {
@@ -643,6 +1656,12 @@ pub async fn handle_doctor(config: &Config, format: OutputFormat) -> Result<()>
                         "status": status
                     })
                 }).collect::<Vec<_>>(),
+                "plugins": plugin_checks.iter().map(|(name, status)| {
+                    json!({
+                        "name": name,
+                        "status": status
+                    })
+                }).collect::<Vec<_>>(),
                 "issues": issues,
                 "healthy": issues.is_empty()
             });
@@ -653,6 +1672,48 @@ pub async fn handle_doctor(config: &Config, format: OutputFormat) -> Result<()>
     Ok(())
 }
 
+/// Try to load every enabled plugin declared in the signed `manifest`
+///
+/// Each plugin goes through the same gates as a real load (manifest, host
+/// ABI, hash) and is instantiated and self-tested, then unloaded again.
+/// Plugins switched off in `[plugins]` are skipped.
+async fn check_wasm_plugins(
+    config: &Config,
+    manifest: &[u8],
+) -> Result<Vec<(String, std::result::Result<(), sdk::errors::EngineError>)>> {
+    use crate::crypto::CryptoModule;
+    use crate::fs_guard::FileSystemGuard;
+    use crate::runtime::WasmRuntime;
+    use sdk::manifest::Manifest;
+    use std::sync::Arc;
+
+    let manifest = Manifest::from_json(std::str::from_utf8(manifest)?)?;
+    let names: Vec<String> = manifest
+        .plugins
+        .iter()
+        .map(|plugin| plugin.name.clone())
+        .filter(|name| {
+            !config
+                .plugins
+                .flags()
+                .iter()
+                .any(|(id, enabled)| id == name && !enabled)
+        })
+        .collect();
+
+    let crypto = Arc::new(CryptoModule::new()?);
+    let fs_guard = Arc::new(FileSystemGuard::new(config.workspace().to_path_buf()));
+    let mut runtime = WasmRuntime::new(manifest, crypto, fs_guard);
+    crate::daemon::configure_wasm_runtime(&mut runtime, config);
+
+    let mut results = Vec::new();
+    for name in names {
+        let result = runtime.check_plugin(&name).await;
+        results.push((name, result));
+    }
+    Ok(results)
+}
+
 /// Run the interactive setup wizard
 ///
 /// Prompts the user for:
@@ -879,85 +1940,20 @@ fn expand_data_dir(path: &Path) -> Result<PathBuf> {
     Ok(path.to_path_buf())
 }
 
-// --- Self-update types and handler ---
-
-#[derive(serde::Deserialize)]
-struct GitHubRelease {
-    tag_name: String,
-    html_url: String,
-    assets: Vec<GitHubAsset>,
-}
-
-#[derive(serde::Deserialize)]
-struct GitHubAsset {
-    name: String,
-    browser_download_url: String,
-    size: u64,
-}
-
-fn current_target() -> &'static str {
-    #[cfg(all(target_arch = "x86_64", target_os = "linux"))]
-    {
-        "x86_64-unknown-linux-gnu"
-    }
-
-    #[cfg(all(target_arch = "aarch64", target_os = "linux"))]
-    {
-        "aarch64-unknown-linux-gnu"
-    }
-
-    #[cfg(all(target_arch = "x86_64", target_os = "macos"))]
-    {
-        "x86_64-apple-darwin"
-    }
-
-    #[cfg(all(target_arch = "aarch64", target_os = "macos"))]
-    {
-        "aarch64-apple-darwin"
-    }
-
-    #[cfg(all(target_arch = "x86_64", target_os = "windows"))]
-    {
-        "x86_64-pc-windows-msvc"
-    }
-
-    #[cfg(all(target_arch = "aarch64", target_os = "windows"))]
-    {
-        "aarch64-pc-windows-msvc"
-    }
-}
-
-/// Check for updates and optionally self-update the binary
+/// Check for updates and, with `apply`, install the latest release
 ///
-/// Fetches the latest release from GitHub, compares semver versions,
-/// and downloads + replaces the binary if a newer version is available.
-pub async fn handle_update(check_only: bool, format: OutputFormat) -> Result<()> {
-    use futures::StreamExt;
+/// Without `apply` this only reports whether a newer release exists. Applying
+/// goes through `Updater`, which refuses downgrades and anything not verified
+/// against the signed release manifest.
+pub async fn handle_update(check_only: bool, apply: bool, format: OutputFormat) -> Result<()> {
+    use crate::updater::{self, Updater};
 
     let current = semver::Version::parse(env!("CARGO_PKG_VERSION"))
         .context("Failed to parse current version")?;
 
-    // Fetch latest release
-    let client = reqwest::Client::builder()
-        .user_agent(format!("rove/{}", env!("CARGO_PKG_VERSION")))
-        .build()?;
-
-    let release: GitHubRelease = client
-        .get("https://api.github.com/repos/OvrisHQ/rove/releases/latest")
-        .header("Accept", "application/vnd.github+json")
-        .send()
-        .await?
-        .error_for_status()
-        .context("Failed to fetch latest release from GitHub")?
-        .json()
-        .await?;
-
-    let latest_tag = release
-        .tag_name
-        .strip_prefix('v')
-        .unwrap_or(&release.tag_name);
-    let latest =
-        semver::Version::parse(latest_tag).context("Failed to parse latest release version")?;
+    let client = updater::http_client()?;
+    let release = updater::fetch_latest(&client, updater::RELEASES_URL).await?;
+    let latest = release.version()?;
 
     if latest <= current {
         match format {
@@ -978,6 +1974,9 @@ pub async fn handle_update(check_only: bool, format: OutputFormat) -> Result<()>
         OutputFormat::Text => {
             println!("Update available: v{} -> v{}", current, latest);
             println!("Release: {}", release.html_url);
+            if !apply && !check_only {
+                println!("Run 'rove update --apply' to install it.");
+            }
         }
         OutputFormat::Json => {
             println!(
@@ -992,168 +1991,23 @@ pub async fn handle_update(check_only: bool, format: OutputFormat) -> Result<()>
         }
     }
 
-    if check_only {
+    if check_only || !apply {
         return Ok(());
     }
 
-    // Find matching asset
-    let target = current_target();
-    let asset_name = format!("rove-{}", target);
-    let asset = release
-        .assets
-        .iter()
-        .find(|a| a.name == asset_name || a.name == format!("{}.exe", asset_name))
-        .ok_or_else(|| {
-            anyhow::anyhow!(
-                "No release asset for target '{}'. Available: {}",
-                target,
-                release
-                    .assets
-                    .iter()
-                    .map(|a| a.name.as_str())
-                    .collect::<Vec<_>>()
-                    .join(", ")
-            )
-        })?;
-
-    println!(
-        "Downloading {} ({:.1} MB)...",
-        asset.name,
-        asset.size as f64 / 1_048_576.0
-    );
-
-    // Stream download into memory (verify before writing to disk)
-    let response = client
-        .get(&asset.browser_download_url)
-        .send()
-        .await?
-        .error_for_status()
-        .context("Failed to download release asset")?;
-
-    let total = response.content_length().unwrap_or(asset.size);
-    let mut bytes = Vec::with_capacity(total as usize);
-    let mut stream = response.bytes_stream();
-    let mut downloaded: u64 = 0;
-    let mut last_pct: u32 = 0;
-
-    while let Some(chunk) = stream.next().await {
-        let chunk = chunk.context("Error reading download stream")?;
-        downloaded += chunk.len() as u64;
-        bytes.extend_from_slice(&chunk);
-
-        if total > 0 {
-            let pct = (downloaded as f64 / total as f64 * 100.0) as u32;
-            if pct / 10 > last_pct / 10 {
-                eprint!("\r  Progress: {}%", pct);
-                last_pct = pct;
-            }
-        }
-    }
-    eprintln!("\r  Progress: 100%");
-
-    // Verify integrity: check SHA-256 hash against release manifest if available
-    // Look for a manifest.json asset in the release
-    let manifest_asset = release
-        .assets
-        .iter()
-        .find(|a| a.name == "manifest.json");
-
-    if let Some(manifest_asset) = manifest_asset {
-        eprintln!("Verifying download integrity...");
-
-        let manifest_response = client
-            .get(&manifest_asset.browser_download_url)
-            .send()
-            .await?
-            .error_for_status()
-            .context("Failed to download release manifest")?;
-
-        let manifest_bytes = manifest_response
-            .bytes()
-            .await
-            .context("Failed to read release manifest")?;
-
-        // Verify manifest signature
-        match crate::crypto::CryptoModule::new() {
-            Ok(crypto) => {
-                match crypto.verify_manifest_file(&manifest_bytes) {
-                    Ok(()) => {
-                        eprintln!("  Manifest signature: verified");
-
-                        // Check binary hash against manifest
-                        let computed_hash = crate::crypto::CryptoModule::compute_hash(&bytes);
-
-                        if let Ok(manifest_value) =
-                            serde_json::from_slice::<serde_json::Value>(&manifest_bytes)
-                        {
-                            // Look for our binary's hash in the manifest
-                            let expected_hash = manifest_value
-                                .get("binaries")
-                                .and_then(|b| b.get(&asset.name))
-                                .and_then(|b| b.get("hash"))
-                                .and_then(|h| h.as_str())
-                                .or_else(|| {
-                                    // Fallback: check core_tools array
-                                    manifest_value
-                                        .get("core_tools")
-                                        .and_then(|t| t.as_array())
-                                        .and_then(|arr| {
-                                            arr.iter().find_map(|entry| {
-                                                let name =
-                                                    entry.get("id").and_then(|i| i.as_str())?;
-                                                if name == "rove" || asset.name.contains(name) {
-                                                    entry.get("hash").and_then(|h| h.as_str())
-                                                } else {
-                                                    None
-                                                }
-                                            })
-                                        })
-                                });
-
-                            if let Some(expected) = expected_hash {
-                                if computed_hash != expected {
-                                    return Err(anyhow::anyhow!(
-                                        "Binary hash mismatch! Expected: {}, Got: {}. Download may be corrupted or tampered.",
-                                        expected, computed_hash
-                                    ));
-                                }
-                                eprintln!("  Binary hash: verified (SHA-256: {}...)", &computed_hash[..16]);
-                            } else {
-                                eprintln!("  Binary hash: not in manifest (skipping hash check)");
-                            }
-                        }
-                    }
-                    Err(e) => {
-                        eprintln!("  Warning: Manifest signature verification failed: {}", e);
-                        eprintln!("  Proceeding with update (signature verification will be enforced in future releases)");
-                    }
-                }
-            }
-            Err(e) => {
-                eprintln!("  Warning: Cannot initialize crypto module: {}", e);
-            }
-        }
-    } else {
-        eprintln!("  Note: No release manifest found (hash verification skipped)");
-    }
-
-    // Write to temp file and self-replace
-    let temp_path = std::env::temp_dir().join(&asset.name);
-    std::fs::write(&temp_path, &bytes).context("Failed to write temporary update file")?;
-
-    #[cfg(unix)]
-    {
-        use std::os::unix::fs::PermissionsExt;
-        std::fs::set_permissions(&temp_path, std::fs::Permissions::from_mode(0o755))?;
-    }
+    let binary = std::env::current_exe()
+        .and_then(|path| path.canonicalize())
+        .context("Failed to locate the current binary")?;
+    let crypto = crate::crypto::CryptoModule::new()?;
 
-    self_replace::self_replace(&temp_path).context("Failed to replace the current binary")?;
-
-    let _ = std::fs::remove_file(&temp_path);
+    eprintln!("Verifying release v{}...", latest);
+    let installed = Updater::new(client, crypto, current.clone())
+        .apply(&release, &binary)
+        .await?;
 
     match format {
         OutputFormat::Text => {
-            println!("Successfully updated Rove: v{} -> v{}", current, latest);
+            println!("Successfully updated Rove: v{} -> v{}", current, installed);
         }
         OutputFormat::Json => {
             println!(
@@ -1161,7 +2015,7 @@ pub async fn handle_update(check_only: bool, format: OutputFormat) -> Result<()>
                 serde_json::to_string_pretty(&json!({
                     "status": "updated",
                     "previous_version": current.to_string(),
-                    "new_version": latest.to_string(),
+                    "new_version": installed.to_string(),
                 }))?
             );
         }
@@ -63,8 +63,20 @@ pub mod cli;
 /// Command handlers module
 pub mod handlers;
 
+/// Verified self-update
+pub mod updater;
+
+/// Signed audit-log export
+pub mod audit;
+
+/// Tier 1 confirmation countdown
+pub mod confirmation;
+
 /// WebSocket client for external UI connection
 pub mod ws_client;
 
 /// Platform-specific utilities module
 pub mod platform;
+
+/// Network egress allowlist
+pub mod egress;
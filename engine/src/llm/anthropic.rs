@@ -1,5 +1,6 @@
 use super::{LLMError, LLMProvider, LLMResponse, Message};
 use crate::config::AnthropicConfig;
+use crate::egress::{EgressClient, EgressPolicy};
 use crate::secrets::SecretCache;
 use async_trait::async_trait;
 use serde_json::json;
@@ -8,7 +9,7 @@ use std::sync::Arc;
 pub struct AnthropicProvider {
     config: AnthropicConfig,
     secret_cache: Arc<SecretCache>,
-    client: reqwest::Client,
+    client: EgressClient,
 }
 
 impl AnthropicProvider {
@@ -16,9 +17,15 @@ impl AnthropicProvider {
         Self {
             config,
             secret_cache,
-            client: reqwest::Client::new(),
+            client: EgressClient::unrestricted(reqwest::Client::builder()),
         }
     }
+
+    /// Only connect to hosts `policy` allows
+    pub fn with_egress_policy(mut self, policy: Arc<EgressPolicy>) -> Self {
+        self.client = EgressClient::new(reqwest::Client::builder(), policy);
+        self
+    }
 }
 
 #[async_trait]
@@ -69,9 +76,12 @@ impl LLMProvider for AnthropicProvider {
             "messages": api_messages,
         });
 
+        super::trace_request(self.name(), &payload);
+
         let response = self
             .client
             .post(&url)
+            .map_err(|e| LLMError::NetworkError(e.to_string()))?
             .header("x-api-key", api_key.unsecure())
             .header("anthropic-version", "2023-06-01")
             .header("Content-Type", "application/json")
@@ -93,10 +103,14 @@ impl LLMProvider for AnthropicProvider {
             }
         }
 
-        let data: serde_json::Value = response
-            .json()
+        let body = response
+            .text()
             .await
-            .map_err(|e| LLMError::ParseError(e.to_string()))?;
+            .map_err(|e| LLMError::NetworkError(e.to_string()))?;
+        super::trace_response(self.name(), &body);
+
+        let data: serde_json::Value =
+            serde_json::from_str(&body).map_err(|e| LLMError::ParseError(e.to_string()))?;
 
         let content_arr = data
             .get("content")
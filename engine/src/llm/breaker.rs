@@ -0,0 +1,126 @@
+//! Per-provider circuit breaker
+//!
+//! A provider that keeps failing would otherwise be tried first on every
+//! call, adding its timeout to each task. After `failure_threshold`
+//! consecutive failures (errors or timeouts) the provider's breaker opens and
+//! the router skips it for `cooldown`, falling back to the next provider.
+//! Once the cooldown has passed the provider is tried again: a success closes
+//! the breaker, another failure opens it for a further cooldown. When every
+//! provider's breaker is open, the router probes the first one in its order
+//! rather than failing without trying any.
+//!
+//! This is unrelated to the rate limiter's circuit breaker, which stops
+//! Tier 2 operations after a burst.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Consecutive failures that open a provider's breaker
+pub const DEFAULT_FAILURE_THRESHOLD: u32 = 3;
+
+/// How long an open breaker skips its provider
+pub const DEFAULT_COOLDOWN: Duration = Duration::from_secs(60);
+
+/// Circuit breakers of all providers, keyed by provider name
+#[derive(Debug)]
+pub struct ProviderBreakers {
+    failure_threshold: u32,
+    cooldown: Duration,
+    states: Mutex<HashMap<String, BreakerState>>,
+}
+
+#[derive(Debug, Default)]
+struct BreakerState {
+    consecutive_failures: u32,
+    open_until: Option<Instant>,
+}
+
+impl Default for ProviderBreakers {
+    fn default() -> Self {
+        Self::new(DEFAULT_FAILURE_THRESHOLD, DEFAULT_COOLDOWN)
+    }
+}
+
+impl ProviderBreakers {
+    /// Breakers that open after `failure_threshold` consecutive failures and
+    /// stay open for `cooldown`
+    pub fn new(failure_threshold: u32, cooldown: Duration) -> Self {
+        Self {
+            failure_threshold: failure_threshold.max(1),
+            cooldown,
+            states: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Whether `provider` is skipped because its breaker is open
+    pub fn is_open(&self, provider: &str) -> bool {
+        let states = self.states.lock().expect("breaker lock poisoned");
+        states
+            .get(provider)
+            .and_then(|state| state.open_until)
+            .is_some_and(|open_until| Instant::now() < open_until)
+    }
+
+    /// Record a successful call, closing the breaker of `provider`
+    pub fn record_success(&self, provider: &str) {
+        let mut states = self.states.lock().expect("breaker lock poisoned");
+        if let Some(state) = states.remove(provider) {
+            if state.open_until.is_some() {
+                tracing::info!("Provider {} recovered, circuit breaker closed", provider);
+            }
+        }
+    }
+
+    /// Record a failed call, opening the breaker of `provider` once it has
+    /// failed `failure_threshold` times in a row
+    pub fn record_failure(&self, provider: &str) {
+        let mut states = self.states.lock().expect("breaker lock poisoned");
+        let state = states.entry(provider.to_string()).or_default();
+        state.consecutive_failures += 1;
+        if state.consecutive_failures >= self.failure_threshold {
+            tracing::warn!(
+                "Provider {} failed {} times in a row, skipping it for {}s",
+                provider,
+                state.consecutive_failures,
+                self.cooldown.as_secs()
+            );
+            state.open_until = Some(Instant::now() + self.cooldown);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_breaker_opens_after_threshold() {
+        let breakers = ProviderBreakers::new(2, Duration::from_secs(60));
+
+        breakers.record_failure("openai");
+        assert!(!breakers.is_open("openai"));
+        breakers.record_failure("openai");
+        assert!(breakers.is_open("openai"));
+        assert!(!breakers.is_open("anthropic"));
+
+        breakers.record_success("openai");
+        assert!(!breakers.is_open("openai"));
+    }
+
+    #[test]
+    fn test_failed_probe_reopens_breaker() {
+        let breakers = ProviderBreakers::new(2, Duration::ZERO);
+        breakers.record_failure("openai");
+        breakers.record_failure("openai");
+        // Cooldown over: the next call is a probe
+        assert!(!breakers.is_open("openai"));
+
+        let breakers = ProviderBreakers {
+            cooldown: Duration::from_secs(60),
+            ..breakers
+        };
+        breakers.record_failure("openai");
+        assert!(breakers.is_open("openai"));
+    }
+}
@@ -1,5 +1,6 @@
 use super::{LLMError, LLMProvider, LLMResponse, Message};
 use crate::config::GeminiConfig;
+use crate::egress::{EgressClient, EgressPolicy};
 use crate::secrets::SecretCache;
 use async_trait::async_trait;
 use serde_json::json;
@@ -8,7 +9,7 @@ use std::sync::Arc;
 pub struct GeminiProvider {
     config: GeminiConfig,
     secret_cache: Arc<SecretCache>,
-    client: reqwest::Client,
+    client: EgressClient,
 }
 
 impl GeminiProvider {
@@ -16,9 +17,15 @@ impl GeminiProvider {
         Self {
             config,
             secret_cache,
-            client: reqwest::Client::new(),
+            client: EgressClient::unrestricted(reqwest::Client::builder()),
         }
     }
+
+    /// Only connect to hosts `policy` allows
+    pub fn with_egress_policy(mut self, policy: Arc<EgressPolicy>) -> Self {
+        self.client = EgressClient::new(reqwest::Client::builder(), policy);
+        self
+    }
 }
 
 #[async_trait]
@@ -77,9 +84,12 @@ impl LLMProvider for GeminiProvider {
             payload.insert("systemInstruction".to_string(), sys);
         }
 
+        super::trace_request(self.name(), &payload);
+
         let response = self
             .client
             .post(&url)
+            .map_err(|e| LLMError::NetworkError(e.to_string()))?
             .header("Content-Type", "application/json")
             .json(&payload)
             .send()
@@ -104,10 +114,14 @@ impl LLMProvider for GeminiProvider {
             }
         }
 
-        let data: serde_json::Value = response
-            .json()
+        let body = response
+            .text()
             .await
-            .map_err(|e| LLMError::ParseError(e.to_string()))?;
+            .map_err(|e| LLMError::NetworkError(e.to_string()))?;
+        super::trace_response(self.name(), &body);
+
+        let data: serde_json::Value =
+            serde_json::from_str(&body).map_err(|e| LLMError::ParseError(e.to_string()))?;
 
         let candidate = data
             .get("candidates")
@@ -4,17 +4,24 @@
 //! (Ollama, OpenAI, Anthropic, Gemini, NVIDIA NIM). The LLMProvider trait defines
 //! the contract that all providers must implement, enabling the LLM router to work
 //! with multiple providers transparently.
+//!
+//! With `RUST_LOG=trace`, every provider logs its outgoing request body and
+//! the raw response body. Both are passed through `SecretManager::scrub` first.
 
+use crate::secrets::SecretManager;
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use std::fmt;
 
 pub mod anthropic;
+pub mod breaker;
 pub mod gemini;
 pub mod nvidia_nim;
 pub mod ollama;
 pub mod openai;
 pub mod router;
+#[cfg(test)]
+pub(crate) mod test_provider;
 
 /// Result type for LLM operations
 pub type Result<T> = std::result::Result<T, LLMError>;
@@ -212,6 +219,27 @@ pub trait LLMProvider: Send + Sync {
     }
 }
 
+/// Log an outgoing provider request body at TRACE level, with secrets scrubbed
+///
+/// The body is only serialized when TRACE is enabled.
+pub(crate) fn trace_request(provider: &str, body: &impl Serialize) {
+    if tracing::enabled!(tracing::Level::TRACE) {
+        let body = serde_json::to_string(body).unwrap_or_default();
+        tracing::trace!(provider, body = %scrub_body(&body), "LLM request");
+    }
+}
+
+/// Log a raw provider response body at TRACE level, with secrets scrubbed
+pub(crate) fn trace_response(provider: &str, body: &str) {
+    if tracing::enabled!(tracing::Level::TRACE) {
+        tracing::trace!(provider, body = %scrub_body(body), "LLM response");
+    }
+}
+
+fn scrub_body(body: &str) -> String {
+    SecretManager::new("rove").scrub(body)
+}
+
 /// Helper function to parse tool calls from string content.
 ///
 /// Handles multiple LLM output formats:
@@ -390,4 +418,56 @@ mod tests {
         let json = serde_json::to_string(&final_answer).unwrap();
         assert!(json.contains(r#""type":"final_answer"#));
     }
+
+    /// Collects everything a `fmt` subscriber writes
+    #[derive(Clone, Default)]
+    struct CaptureWriter(std::sync::Arc<std::sync::Mutex<Vec<u8>>>);
+
+    impl std::io::Write for CaptureWriter {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn capture_logs(level: tracing::Level, f: impl FnOnce()) -> String {
+        let writer = CaptureWriter::default();
+        let make_writer = writer.clone();
+        let subscriber = tracing_subscriber::fmt()
+            .with_max_level(level)
+            .with_ansi(false)
+            .with_writer(move || make_writer.clone())
+            .finish();
+        tracing::subscriber::with_default(subscriber, f);
+        let bytes = writer.0.lock().unwrap().clone();
+        String::from_utf8(bytes).unwrap()
+    }
+
+    #[test]
+    fn test_trace_request_redacts_embedded_key() {
+        let key = "sk-proj-abcdefghijklmnopqrstuvwxyz123456";
+        let payload = serde_json::json!({
+            "model": "gpt-4o-mini",
+            "messages": [{"role": "user", "content": format!("my key is {}", key)}],
+        });
+
+        let logs = capture_logs(tracing::Level::TRACE, || {
+            trace_request("openai", &payload);
+            trace_response("openai", &format!(r#"{{"echo":"{}"}}"#, key));
+        });
+        assert!(logs.contains("LLM request"));
+        assert!(logs.contains("LLM response"));
+        assert!(logs.contains("[REDACTED]"));
+        assert!(!logs.contains(key));
+
+        // Nothing is logged at the default level
+        let logs = capture_logs(tracing::Level::INFO, || {
+            trace_request("openai", &payload);
+        });
+        assert!(logs.is_empty());
+    }
 }
@@ -1,5 +1,6 @@
 use super::{LLMError, LLMProvider, LLMResponse, Message};
 use crate::config::NvidiaNimConfig;
+use crate::egress::{EgressClient, EgressPolicy};
 use crate::secrets::SecretCache;
 use async_trait::async_trait;
 use serde_json::json;
@@ -8,7 +9,7 @@ use std::sync::Arc;
 pub struct NvidiaNimProvider {
     config: NvidiaNimConfig,
     secret_cache: Arc<SecretCache>,
-    client: reqwest::Client,
+    client: EgressClient,
 }
 
 impl NvidiaNimProvider {
@@ -16,9 +17,15 @@ impl NvidiaNimProvider {
         Self {
             config,
             secret_cache,
-            client: reqwest::Client::new(),
+            client: EgressClient::unrestricted(reqwest::Client::builder()),
         }
     }
+
+    /// Only connect to hosts `policy` allows
+    pub fn with_egress_policy(mut self, policy: Arc<EgressPolicy>) -> Self {
+        self.client = EgressClient::new(reqwest::Client::builder(), policy);
+        self
+    }
 }
 
 #[async_trait]
@@ -61,9 +68,12 @@ impl LLMProvider for NvidiaNimProvider {
             "messages": api_messages,
         });
 
+        super::trace_request(self.name(), &payload);
+
         let response = self
             .client
             .post(&url)
+            .map_err(|e| LLMError::NetworkError(e.to_string()))?
             .header("Authorization", format!("Bearer {}", api_key.unsecure()))
             .header("Content-Type", "application/json")
             .json(&payload)
@@ -84,10 +94,14 @@ impl LLMProvider for NvidiaNimProvider {
             }
         }
 
-        let data: serde_json::Value = response
-            .json()
+        let body = response
+            .text()
             .await
-            .map_err(|e| LLMError::ParseError(e.to_string()))?;
+            .map_err(|e| LLMError::NetworkError(e.to_string()))?;
+        super::trace_response(self.name(), &body);
+
+        let data: serde_json::Value =
+            serde_json::from_str(&body).map_err(|e| LLMError::ParseError(e.to_string()))?;
 
         let choice = data
             .get("choices")
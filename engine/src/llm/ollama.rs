@@ -13,8 +13,11 @@
 use async_trait::async_trait;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
+use std::sync::Arc;
 use std::time::Duration;
 
+use crate::egress::{EgressClient, EgressPolicy};
+
 use super::{FinalAnswer, LLMError, LLMProvider, LLMResponse, Message, MessageRole, Result};
 
 /// Ollama provider configuration
@@ -27,7 +30,7 @@ pub struct OllamaProvider {
     model: String,
 
     /// HTTP client for API requests
-    client: Client,
+    client: EgressClient,
 }
 
 impl OllamaProvider {
@@ -40,13 +43,20 @@ impl OllamaProvider {
         Self {
             base_url: base_url.into(),
             model: model.into(),
-            client: Client::builder()
-                .timeout(Duration::from_secs(300))
-                .build()
-                .expect("Failed to create HTTP client"),
+            client: EgressClient::unrestricted(Self::client_builder()),
         }
     }
 
+    /// Only connect to hosts `policy` allows
+    pub fn with_egress_policy(mut self, policy: Arc<EgressPolicy>) -> Self {
+        self.client = EgressClient::new(Self::client_builder(), policy);
+        self
+    }
+
+    fn client_builder() -> reqwest::ClientBuilder {
+        Client::builder().timeout(Duration::from_secs(300))
+    }
+
     /// Convert our Message format to Ollama's format
     fn convert_messages(&self, messages: &[Message]) -> Vec<OllamaMessage> {
         messages
@@ -78,6 +88,18 @@ impl LLMProvider for OllamaProvider {
         0.0 // Local provider, no cost
     }
 
+    /// Ollama is healthy if its HTTP server answers at all.
+    ///
+    /// Any response (even an error status) means the server is reachable;
+    /// only connection failures and timeouts count as unavailable.
+    async fn check_health(&self) -> bool {
+        let url = format!("{}/api/tags", self.base_url);
+        let Ok(request) = self.client.get(&url) else {
+            return false;
+        };
+        request.timeout(Duration::from_secs(3)).send().await.is_ok()
+    }
+
     async fn generate(&self, messages: &[Message]) -> Result<LLMResponse> {
         // Convert messages to Ollama format
         let ollama_messages = self.convert_messages(messages);
@@ -99,12 +121,15 @@ impl LLMProvider for OllamaProvider {
             stream: false, // For now, use non-streaming mode
         };
 
+        super::trace_request(self.name(), &request);
+
         // Make API call
         let url = format!("{}/api/chat", self.base_url);
         let start = std::time::Instant::now();
         let response = self
             .client
             .post(&url)
+            .map_err(|e| LLMError::NetworkError(e.to_string()))?
             .json(&request)
             .send()
             .await
@@ -137,9 +162,13 @@ impl LLMProvider for OllamaProvider {
         }
 
         // Parse response
-        let ollama_response: OllamaResponse = response
-            .json()
+        let body = response
+            .text()
             .await
+            .map_err(|e| LLMError::NetworkError(e.to_string()))?;
+        super::trace_response(self.name(), &body);
+
+        let ollama_response: OllamaResponse = serde_json::from_str(&body)
             .map_err(|e| LLMError::ParseError(format!("Failed to parse Ollama response: {}", e)))?;
 
         // Extract content from response
@@ -6,10 +6,39 @@
 //!
 //! **Requirements**: 4.2, 4.3, 4.6
 
+use super::breaker::ProviderBreakers;
 use super::{LLMProvider, Message};
 use crate::config::LLMConfig;
-use std::sync::Arc;
-use std::time::Duration;
+use sdk::errors::EngineError;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Estimated token count above which cloud providers are preferred
+const LARGE_TASK_TOKENS: usize = 4000;
+
+/// How long the default provider's health check result is reused
+const HEALTH_CHECK_TTL: Duration = Duration::from_secs(60);
+
+/// Constraints on provider selection for a call
+///
+/// Mostly merged from the active skills (see `SteeringEngine`); the agent
+/// sets `local_only` for calls whose content must stay on the machine.
+#[derive(Debug, Clone, Default)]
+pub struct RoutingPreferences {
+    /// Preferred providers (from highest priority skill)
+    pub preferred_providers: Vec<String>,
+    /// Providers to avoid (union of all active)
+    pub avoid_providers: Vec<String>,
+    /// Preferred execution mode (from highest priority skill)
+    pub prefer_mode: Option<String>,
+    /// Whether to always verify (true if any active skill requires it)
+    pub always_verify: bool,
+    /// Minimum score threshold (strictest across all active)
+    pub min_score_threshold: f32,
+    /// Route to local providers only, for content that must stay on the
+    /// machine (never set by skills)
+    pub local_only: bool,
+}
 
 /// Task profile used for provider ranking
 #[derive(Debug, Clone)]
@@ -37,6 +66,16 @@ impl TaskProfile {
     }
 }
 
+/// Estimated token usage and cost of a single LLM call
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct CallUsage {
+    /// Estimated prompt plus completion tokens
+    pub tokens: usize,
+
+    /// Estimated cost in USD (always 0.0 for local providers)
+    pub cost: f64,
+}
+
 /// LLM Router that selects appropriate providers based on task characteristics
 pub struct LLMRouter {
     /// Available LLM providers
@@ -44,6 +83,12 @@ pub struct LLMRouter {
 
     /// LLM configuration
     config: Arc<LLMConfig>,
+
+    /// Circuit breakers of providers that keep failing
+    breakers: ProviderBreakers,
+
+    /// Last health check of the default provider and when it ran
+    default_health: Mutex<Option<(Instant, bool)>>,
 }
 
 impl LLMRouter {
@@ -53,7 +98,16 @@ impl LLMRouter {
     /// * `providers` - List of available LLM providers
     /// * `config` - LLM configuration
     pub fn new(providers: Vec<Box<dyn LLMProvider>>, config: Arc<LLMConfig>) -> Self {
-        Self { providers, config }
+        let breakers = ProviderBreakers::new(
+            config.circuit_breaker.failure_threshold,
+            Duration::from_secs(config.circuit_breaker.cooldown_secs),
+        );
+        Self {
+            providers,
+            config,
+            breakers,
+            default_health: Mutex::new(None),
+        }
     }
 
     /// Analyze task characteristics from message history
@@ -154,6 +208,32 @@ impl LLMRouter {
         total_chars / 4
     }
 
+    /// Estimate the usage of a call answered by `provider_name`
+    ///
+    /// Providers do not report token counts, so prompt and completion are both
+    /// estimated with the same ~4 characters per token heuristic and priced via
+    /// the provider's `estimated_cost`. Unknown providers cost nothing.
+    pub fn estimate_usage(
+        &self,
+        provider_name: &str,
+        messages: &[Message],
+        response: &super::LLMResponse,
+    ) -> CallUsage {
+        let completion_chars = match response {
+            super::LLMResponse::ToolCall(call) => call.name.len() + call.arguments.len(),
+            super::LLMResponse::FinalAnswer(answer) => answer.content.len(),
+        };
+        let tokens = self.estimate_tokens(messages) + completion_chars / 4;
+        let cost = self
+            .providers
+            .iter()
+            .find(|p| p.name() == provider_name)
+            .map(|p| p.estimated_cost(tokens))
+            .unwrap_or(0.0);
+
+        CallUsage { tokens, cost }
+    }
+
     /// Rank providers based on task profile
     ///
     /// Ranking algorithm:
@@ -162,6 +242,10 @@ impl LLMRouter {
     /// 3. Prefer cloud providers for large tasks (tokens > session limit)
     /// 4. Consider cost (prefer cheaper when quality is similar)
     ///
+    /// The configured default provider is strongly preferred. If
+    /// `llm.provider_priority` is set it replaces that preference: listed
+    /// providers come first, in the listed order, followed by the rest.
+    ///
     /// Returns a sorted list of providers (best first)
     ///
     /// **Validates: Requirements 4.2, 4.3, 4.6**
@@ -169,6 +253,7 @@ impl LLMRouter {
         let mut providers: Vec<&dyn LLMProvider> =
             self.providers.iter().map(|b| b.as_ref()).collect();
 
+        let priority = &self.config.provider_priority;
         let default_provider = &self.config.default_provider;
 
         providers.sort_by(|a, b| {
@@ -176,11 +261,13 @@ impl LLMRouter {
             let mut score_b = 0.0_f64;
 
             // Strongly prefer the user's configured default provider
-            if a.name() == default_provider {
-                score_a += 200.0;
-            }
-            if b.name() == default_provider {
-                score_b += 200.0;
+            if priority.is_empty() {
+                if a.name() == default_provider {
+                    score_a += 200.0;
+                }
+                if b.name() == default_provider {
+                    score_b += 200.0;
+                }
             }
 
             // Prefer local for sensitive tasks
@@ -203,8 +290,8 @@ impl LLMRouter {
                 }
             }
 
-            // Prefer cloud heavily if token estimate is high (e.g., > 4000)
-            if profile.estimated_tokens > 4000 {
+            // Prefer cloud heavily if token estimate is high
+            if profile.estimated_tokens > LARGE_TASK_TOKENS {
                 if !a.is_local() {
                     score_a += 50.0;
                 }
@@ -227,9 +314,130 @@ impl LLMRouter {
                 .unwrap_or(std::cmp::Ordering::Equal)
         });
 
+        // An explicit priority list wins over the scores
+        if !priority.is_empty() {
+            providers.sort_by_key(|p| {
+                priority
+                    .iter()
+                    .position(|name| name == p.name())
+                    .unwrap_or(usize::MAX)
+            });
+        }
+
         providers
     }
 
+    /// Rank providers for `profile`, then apply the routing preferences of
+    /// the active skills
+    ///
+    /// Avoided providers are dropped. Preferred providers move to the front,
+    /// in the order the skill lists them; the rest keep their ranking.
+    pub fn rank_with_preferences(
+        &self,
+        profile: &TaskProfile,
+        prefs: &RoutingPreferences,
+    ) -> Vec<&dyn LLMProvider> {
+        let mut ranked: Vec<&dyn LLMProvider> = self
+            .rank_providers(profile)
+            .into_iter()
+            .filter(|p| !prefs.local_only || p.is_local())
+            .filter(|p| !prefs.avoid_providers.iter().any(|name| name == p.name()))
+            .collect();
+        ranked.sort_by_key(|p| {
+            prefs
+                .preferred_providers
+                .iter()
+                .position(|name| name == p.name())
+                .unwrap_or(usize::MAX)
+        });
+        ranked
+    }
+
+    /// Explain why `provider_name` answered a call with `messages`
+    ///
+    /// Names the ranking factors that favored the provider, e.g.
+    /// "ollama (local): sensitivity 0.90 > threshold 0.70", or that it was
+    /// a fallback after the providers ranked above it failed.
+    pub fn explain_routing(
+        &self,
+        messages: &[Message],
+        provider_name: &str,
+        prefs: &RoutingPreferences,
+    ) -> String {
+        let profile = self.analyze_task(messages);
+        let ranked = self.rank_with_preferences(&profile, prefs);
+        let Some(position) = ranked.iter().position(|p| p.name() == provider_name) else {
+            return format!("{}: not a selectable provider", provider_name);
+        };
+        let provider = ranked[position];
+        let kind = if provider.is_local() {
+            "local"
+        } else {
+            "cloud"
+        };
+
+        if position > 0 {
+            let failed: Vec<&str> = ranked[..position].iter().map(|p| p.name()).collect();
+            return format!(
+                "{} ({}): fallback after {} failed",
+                provider_name,
+                kind,
+                failed.join(", ")
+            );
+        }
+
+        let mut reasons = Vec::new();
+        if prefs
+            .preferred_providers
+            .iter()
+            .any(|name| name == provider_name)
+        {
+            reasons.push("preferred by active skill".to_string());
+        }
+        if let Some(rank) = self
+            .config
+            .provider_priority
+            .iter()
+            .position(|name| name == provider_name)
+        {
+            reasons.push(format!("provider priority #{}", rank + 1));
+        } else if provider_name == self.config.default_provider
+            && self.config.provider_priority.is_empty()
+        {
+            reasons.push("default provider".to_string());
+        }
+        if provider.is_local() {
+            if profile.sensitivity > self.config.sensitivity_threshold {
+                reasons.push(format!(
+                    "sensitivity {:.2} > threshold {:.2}",
+                    profile.sensitivity, self.config.sensitivity_threshold
+                ));
+            }
+        } else {
+            if profile.complexity > self.config.complexity_threshold {
+                reasons.push(format!(
+                    "complexity {:.2} > threshold {:.2}",
+                    profile.complexity, self.config.complexity_threshold
+                ));
+            }
+            if profile.estimated_tokens > LARGE_TASK_TOKENS {
+                reasons.push(format!(
+                    "~{} tokens > {}",
+                    profile.estimated_tokens, LARGE_TASK_TOKENS
+                ));
+            }
+        }
+        if reasons.is_empty() {
+            reasons.push(if ranked.len() == 1 {
+                "only provider".to_string()
+            } else {
+                "lowest estimated cost".to_string()
+            });
+        }
+
+        format!("{} ({}): {}", provider_name, kind, reasons.join("; "))
+    }
+
     /// Call LLM providers with automatic failover
     ///
     /// This method:
@@ -240,6 +448,22 @@ impl LLMRouter {
     ///
     /// Requirements: 4.4, 4.5
     pub async fn call(&self, messages: &[Message]) -> super::Result<(super::LLMResponse, String)> {
+        self.call_with_preferences(messages, &RoutingPreferences::default())
+            .await
+    }
+
+    /// Call LLM providers with automatic failover, honoring the routing
+    /// preferences of the active skills (see `rank_with_preferences`)
+    ///
+    /// # Errors
+    ///
+    /// Returns `LLMError::ProviderUnavailable` if no provider is left to try,
+    /// or the error of the last provider if all of them fail.
+    pub async fn call_with_preferences(
+        &self,
+        messages: &[Message],
+        prefs: &RoutingPreferences,
+    ) -> super::Result<(super::LLMResponse, String)> {
         use super::LLMError;
 
         // If no providers available, return error immediately
@@ -251,12 +475,61 @@ impl LLMRouter {
 
         // Analyze task and rank providers
         let profile = self.analyze_task(messages);
-        let ranked_providers = self.rank_providers(&profile);
+        let ranked_providers = self.rank_with_preferences(&profile, prefs);
+        if ranked_providers.is_empty() {
+            let reason = if prefs.local_only {
+                "No local LLM provider is available"
+            } else {
+                "All LLM providers are avoided by the active skills"
+            };
+            return Err(LLMError::ProviderUnavailable(reason.to_string()));
+        }
+
+        self.call_in_order(ranked_providers, messages).await
+    }
+
+    /// Whether `provider_name` is a registered local provider
+    pub fn is_local(&self, provider_name: &str) -> bool {
+        self.providers
+            .iter()
+            .any(|p| p.name() == provider_name && p.is_local())
+    }
+
+    /// Try `providers` in order until one answers
+    ///
+    /// Providers whose circuit breaker is open are skipped. When every
+    /// breaker is open, the first provider is tried anyway as a half-open
+    /// probe, so a recovered provider is found before its cooldown ends.
+    async fn call_in_order(
+        &self,
+        providers: Vec<&dyn LLMProvider>,
+        messages: &[Message],
+    ) -> super::Result<(super::LLMResponse, String)> {
+        use super::LLMError;
+
+        let (mut providers, open): (Vec<&dyn LLMProvider>, Vec<&dyn LLMProvider>) = providers
+            .into_iter()
+            .partition(|p| !self.breakers.is_open(p.name()));
+        if providers.is_empty() {
+            let probe = open[0];
+            tracing::info!(
+                "All circuit breakers open, probing provider {}",
+                probe.name()
+            );
+            providers.push(probe);
+        } else {
+            for provider in &open {
+                tracing::debug!(
+                    "Skipping provider {}: circuit breaker open",
+                    provider.name()
+                );
+            }
+        }
 
         // Try each provider in order with timeout (Requirement 4.5)
         // Local providers (Ollama) get 120s for model loading + generation
         // Cloud providers get 30s (fast API responses)
-        for provider in ranked_providers {
+        for provider in providers {
             let timeout_secs = if provider.is_local() { 120 } else { 30 };
             tracing::debug!(
                 "Attempting provider: {} (timeout: {}s)",
@@ -273,13 +546,16 @@ impl LLMRouter {
             match result {
                 Ok(Ok(response)) => {
                     tracing::info!("Provider {} succeeded", provider.name());
+                    self.breakers.record_success(provider.name());
                     return Ok((response, provider.name().to_string()));
                 }
                 Ok(Err(e)) => {
                     tracing::warn!("Provider {} failed: {}", provider.name(), e);
+                    self.breakers.record_failure(provider.name());
                 }
                 Err(_) => {
                     tracing::warn!("Provider {} timed out after 30s", provider.name());
+                    self.breakers.record_failure(provider.name());
                 }
             }
         }
@@ -291,6 +567,81 @@ impl LLMRouter {
         ))
     }
 
+    /// Verify that the configured default provider can be used
+    ///
+    /// Called at task start so a missing API key or an unreachable local server
+    /// is reported up front with setup guidance, instead of surfacing later as
+    /// an HTTP failure inside the agent loop. When `llm.provider_priority` is
+    /// set, its first entry is checked instead, since that is the provider
+    /// tasks go to first. The check is advisory when other providers are
+    /// registered: the problem is logged and returned as `Ok(Some(_))`, so the
+    /// caller can show its guidance while the task goes on with the
+    /// fallbacks. The health check result is reused for `HEALTH_CHECK_TTL`,
+    /// so back-to-back tasks don't each probe the provider.
+    ///
+    /// # Errors
+    ///
+    /// Returns `EngineError::ProviderNotConfigured` if no other provider is
+    /// registered and the default provider is not registered either (cloud
+    /// providers are only registered when their API key exists) or fails its
+    /// health check.
+    pub async fn ensure_default_provider(&self) -> Result<Option<EngineError>, EngineError> {
+        let name = self
+            .config
+            .provider_priority
+            .first()
+            .unwrap_or(&self.config.default_provider)
+            .as_str();
+        let Err(error) = self.check_default_provider(name).await else {
+            return Ok(None);
+        };
+
+        if self.providers.iter().any(|p| p.name() != name) {
+            tracing::warn!("{}; falling back to the other providers", error);
+            return Ok(Some(error));
+        }
+        tracing::error!("{}", error);
+        Err(error)
+    }
+
+    /// Config key that picks the provider `ensure_default_provider` checks
+    fn provider_setting(&self) -> &'static str {
+        if self.config.provider_priority.is_empty() {
+            "llm.default_provider"
+        } else {
+            "llm.provider_priority"
+        }
+    }
+
+    /// Whether the default provider `name` is registered and healthy
+    async fn check_default_provider(&self, name: &str) -> Result<(), EngineError> {
+        let Some(provider) = self.providers.iter().find(|p| p.name() == name) else {
+            return Err(EngineError::ProviderNotConfigured {
+                provider: name.to_string(),
+                guidance: missing_provider_guidance(name, self.provider_setting()),
+            });
+        };
+
+        let cached = *self.default_health.lock().expect("health lock poisoned");
+        let healthy = match cached {
+            Some((checked_at, healthy)) if checked_at.elapsed() < HEALTH_CHECK_TTL => healthy,
+            _ => {
+                let healthy = provider.check_health().await;
+                *self.default_health.lock().expect("health lock poisoned") =
+                    Some((Instant::now(), healthy));
+                healthy
+            }
+        };
+        if !healthy {
+            return Err(EngineError::ProviderNotConfigured {
+                provider: name.to_string(),
+                guidance: unhealthy_provider_guidance(name, provider.is_local()),
+            });
+        }
+
+        Ok(())
+    }
+
     /// Check the health of all registered providers
     /// Returns a list of (provider_name, is_healthy)
     pub async fn check_health(&self) -> Vec<(&str, bool)> {
@@ -303,47 +654,53 @@ impl LLMRouter {
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::llm::{LLMError, LLMResponse};
-    use async_trait::async_trait;
-
-    // Mock provider for testing
-    struct MockProvider {
-        name: String,
-        is_local: bool,
-        cost_per_1k: f64,
+/// Setup guidance for a default provider that was never registered
+///
+/// Cloud providers are skipped at startup when no API key is stored, so this
+/// almost always means the key is missing. `setting` is the config key the
+/// provider was chosen by.
+fn missing_provider_guidance(name: &str, setting: &str) -> String {
+    let switch = if setting == "llm.provider_priority" {
+        format!("put \"ollama\" first in {}", setting)
+    } else {
+        format!("set {} = \"ollama\"", setting)
+    };
+    if name == "ollama" {
+        format!(
+            "Ollama is not enabled. Check the [llm.ollama] section of config.toml, \
+             or choose another provider in {}",
+            setting
+        )
+    } else {
+        format!(
+            "no API key found for '{}'. Run 'rove setup' to store '{}_api_key' in the system keychain, \
+             or {} in config.toml",
+            name, name, switch
+        )
     }
+}
 
-    impl MockProvider {
-        fn new(name: &str, is_local: bool, cost_per_1k: f64) -> Self {
-            Self {
-                name: name.to_string(),
-                is_local,
-                cost_per_1k,
-            }
-        }
+/// Setup guidance for a default provider that is registered but unhealthy
+fn unhealthy_provider_guidance(name: &str, is_local: bool) -> String {
+    if is_local {
+        format!(
+            "'{}' is not reachable. Make sure it is running (e.g. 'ollama serve') \
+             and that llm.{}.base_url in config.toml is correct",
+            name, name
+        )
+    } else {
+        format!(
+            "the API key for '{}' could not be read. Run 'rove setup' to store it again",
+            name
+        )
     }
+}
 
-    #[async_trait]
-    impl LLMProvider for MockProvider {
-        fn name(&self) -> &str {
-            &self.name
-        }
-
-        fn is_local(&self) -> bool {
-            self.is_local
-        }
-
-        fn estimated_cost(&self, tokens: usize) -> f64 {
-            (tokens as f64 / 1000.0) * self.cost_per_1k
-        }
-
-        async fn generate(&self, _messages: &[Message]) -> Result<LLMResponse, LLMError> {
-            unimplemented!("Mock provider doesn't implement generate")
-        }
-    }
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm::test_provider::TestProvider;
+    use crate::llm::{LLMError, LLMResponse};
 
     fn create_test_config() -> Arc<LLMConfig> {
         Arc::new(LLMConfig {
@@ -355,9 +712,152 @@ mod tests {
             anthropic: Default::default(),
             gemini: Default::default(),
             nvidia_nim: Default::default(),
+            provider_priority: Vec::new(),
+            circuit_breaker: Default::default(),
         })
     }
 
+    #[test]
+    fn test_estimate_usage_prices_by_provider() {
+        let providers: Vec<Box<dyn LLMProvider>> = vec![
+            Box::new(TestProvider::new("ollama", true)),
+            Box::new(TestProvider::new("openai", false).with_cost_per_1k(0.002)),
+        ];
+        let router = LLMRouter::new(providers, create_test_config());
+
+        let messages = vec![Message::user("a".repeat(4000))];
+        let response = LLMResponse::FinalAnswer(crate::llm::FinalAnswer::new("b".repeat(4000)));
+
+        let usage = router.estimate_usage("openai", &messages, &response);
+        assert_eq!(usage.tokens, 2000);
+        assert!((usage.cost - 0.004).abs() < 1e-9);
+
+        let local = router.estimate_usage("ollama", &messages, &response);
+        assert_eq!(local.tokens, 2000);
+        assert_eq!(local.cost, 0.0);
+
+        assert_eq!(
+            router.estimate_usage("missing", &messages, &response).cost,
+            0.0
+        );
+    }
+
+    #[test]
+    fn test_explain_routing_names_sensitivity() {
+        let providers: Vec<Box<dyn LLMProvider>> = vec![
+            Box::new(TestProvider::new("openai", false).with_cost_per_1k(0.002)),
+            Box::new(TestProvider::new("ollama", true)),
+        ];
+        let router = LLMRouter::new(providers, create_test_config());
+        let messages = vec![Message::user(
+            "Rotate the password, secret, token and api_key in ~/.ssh",
+        )];
+
+        assert_eq!(
+            router.explain_routing(&messages, "ollama", &RoutingPreferences::default()),
+            "ollama (local): default provider; sensitivity 1.00 > threshold 0.70"
+        );
+        assert_eq!(
+            router.explain_routing(&messages, "openai", &RoutingPreferences::default()),
+            "openai (cloud): fallback after ollama failed"
+        );
+    }
+
+    #[test]
+    fn test_explain_routing_names_complexity() {
+        let providers: Vec<Box<dyn LLMProvider>> = vec![
+            Box::new(TestProvider::new("ollama", true)),
+            Box::new(TestProvider::new("openai", false).with_cost_per_1k(0.002)),
+        ];
+        let config = Arc::new(LLMConfig {
+            default_provider: "anthropic".to_string(),
+            ..(*create_test_config()).clone()
+        });
+        let router = LLMRouter::new(providers, config);
+        let messages: Vec<Message> = (0..10)
+            .map(|_| Message::user(format!("```{}```", "a".repeat(1000))))
+            .collect();
+
+        assert_eq!(
+            router.explain_routing(&messages, "openai", &RoutingPreferences::default()),
+            "openai (cloud): complexity 1.00 > threshold 0.80"
+        );
+
+        let simple = vec![Message::user("What is 2+2?")];
+        assert_eq!(
+            router.explain_routing(&simple, "ollama", &RoutingPreferences::default()),
+            "ollama (local): lowest estimated cost"
+        );
+    }
+
+    #[test]
+    fn test_skill_preferences_reorder_and_avoid() {
+        let providers: Vec<Box<dyn LLMProvider>> = vec![
+            Box::new(TestProvider::new("ollama", true)),
+            Box::new(TestProvider::new("openai", false).with_cost_per_1k(0.002)),
+            Box::new(TestProvider::new("anthropic", false).with_cost_per_1k(0.003)),
+        ];
+        let router = LLMRouter::new(providers, create_test_config());
+        let messages = vec![Message::user("Refactor this module")];
+        let profile = router.analyze_task(&messages);
+        let prefs = RoutingPreferences {
+            preferred_providers: vec!["anthropic".to_string()],
+            avoid_providers: vec!["ollama".to_string()],
+            ..Default::default()
+        };
+
+        let names: Vec<&str> = router
+            .rank_with_preferences(&profile, &prefs)
+            .iter()
+            .map(|p| p.name())
+            .collect();
+        assert_eq!(names, vec!["anthropic", "openai"]);
+        assert_eq!(
+            router.explain_routing(&messages, "anthropic", &prefs),
+            "anthropic (cloud): preferred by active skill"
+        );
+        assert_eq!(
+            router.explain_routing(&messages, "ollama", &prefs),
+            "ollama: not a selectable provider"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_call_with_everything_avoided_fails() {
+        let providers: Vec<Box<dyn LLMProvider>> =
+            vec![Box::new(TestProvider::new("ollama", true))];
+        let router = LLMRouter::new(providers, create_test_config());
+        let prefs = RoutingPreferences {
+            avoid_providers: vec!["ollama".to_string()],
+            ..Default::default()
+        };
+
+        let result = router
+            .call_with_preferences(&[Message::user("hi")], &prefs)
+            .await;
+        assert!(matches!(result, Err(LLMError::ProviderUnavailable(_))));
+    }
+
+    #[tokio::test]
+    async fn test_local_only_call_skips_cloud_providers() {
+        let providers: Vec<Box<dyn LLMProvider>> = vec![Box::new(
+            TestProvider::new("openai", false).with_cost_per_1k(0.002),
+        )];
+        let router = LLMRouter::new(providers, create_test_config());
+        let prefs = RoutingPreferences {
+            local_only: true,
+            ..Default::default()
+        };
+
+        assert!(!router.is_local("openai"));
+        assert!(!router.is_local("ollama"));
+        // The cloud provider's unimplemented `generate` is never reached
+        let result = router
+            .call_with_preferences(&[Message::user("rm -rf build")], &prefs)
+            .await;
+        assert!(matches!(result, Err(LLMError::ProviderUnavailable(_))));
+    }
+
     #[test]
     fn test_task_profile_creation() {
         let profile = TaskProfile::new(0.5, 0.8, 1000);
@@ -477,9 +977,9 @@ mod tests {
         let config = create_test_config();
 
         let providers: Vec<Box<dyn LLMProvider>> = vec![
-            Box::new(MockProvider::new("openai", false, 0.002)),
-            Box::new(MockProvider::new("ollama", true, 0.0)),
-            Box::new(MockProvider::new("anthropic", false, 0.003)),
+            Box::new(TestProvider::new("openai", false).with_cost_per_1k(0.002)),
+            Box::new(TestProvider::new("ollama", true)),
+            Box::new(TestProvider::new("anthropic", false).with_cost_per_1k(0.003)),
         ];
 
         let router = LLMRouter::new(providers, config);
@@ -497,9 +997,9 @@ mod tests {
         let config = create_test_config();
 
         let providers: Vec<Box<dyn LLMProvider>> = vec![
-            Box::new(MockProvider::new("ollama", true, 0.0)),
-            Box::new(MockProvider::new("openai", false, 0.002)),
-            Box::new(MockProvider::new("anthropic", false, 0.003)),
+            Box::new(TestProvider::new("ollama", true)),
+            Box::new(TestProvider::new("openai", false).with_cost_per_1k(0.002)),
+            Box::new(TestProvider::new("anthropic", false).with_cost_per_1k(0.003)),
         ];
 
         let router = LLMRouter::new(providers, config);
@@ -517,9 +1017,9 @@ mod tests {
         let config = create_test_config();
 
         let providers: Vec<Box<dyn LLMProvider>> = vec![
-            Box::new(MockProvider::new("expensive", false, 0.010)),
-            Box::new(MockProvider::new("cheap", false, 0.001)),
-            Box::new(MockProvider::new("medium", false, 0.005)),
+            Box::new(TestProvider::new("expensive", false).with_cost_per_1k(0.010)),
+            Box::new(TestProvider::new("cheap", false).with_cost_per_1k(0.001)),
+            Box::new(TestProvider::new("medium", false).with_cost_per_1k(0.005)),
         ];
 
         let router = LLMRouter::new(providers, config);
@@ -539,8 +1039,8 @@ mod tests {
         let config = create_test_config();
 
         let providers: Vec<Box<dyn LLMProvider>> = vec![
-            Box::new(MockProvider::new("ollama", true, 0.0)),
-            Box::new(MockProvider::new("openai", false, 0.002)),
+            Box::new(TestProvider::new("ollama", true)),
+            Box::new(TestProvider::new("openai", false).with_cost_per_1k(0.002)),
         ];
 
         let router = LLMRouter::new(providers, config);
@@ -552,4 +1052,256 @@ mod tests {
         // Should prefer cheaper option (ollama)
         assert_eq!(ranked[0].name(), "ollama");
     }
+
+    fn config_with_default(provider: &str) -> Arc<LLMConfig> {
+        let mut config = (*create_test_config()).clone();
+        config.default_provider = provider.to_string();
+        Arc::new(config)
+    }
+
+    #[tokio::test]
+    async fn test_ensure_default_provider_available() {
+        let providers: Vec<Box<dyn LLMProvider>> =
+            vec![Box::new(TestProvider::new("ollama", true))];
+        let router = LLMRouter::new(providers, create_test_config());
+
+        assert!(router.ensure_default_provider().await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_ensure_default_provider_missing_key() {
+        // Cloud provider selected but never registered because no key exists
+        let router = LLMRouter::new(Vec::new(), config_with_default("openai"));
+
+        let err = router.ensure_default_provider().await.unwrap_err();
+        match &err {
+            EngineError::ProviderNotConfigured { provider, guidance } => {
+                assert_eq!(provider, "openai");
+                assert!(guidance.contains("rove setup"));
+                assert!(guidance.contains("openai_api_key"));
+                assert!(guidance.contains("llm.default_provider = \"ollama\""));
+            }
+            other => panic!("Expected ProviderNotConfigured, got {:?}", other),
+        }
+        assert!(err.to_string().contains("not configured"));
+    }
+
+    #[tokio::test]
+    async fn test_ensure_default_provider_unreachable() {
+        let providers: Vec<Box<dyn LLMProvider>> =
+            vec![Box::new(TestProvider::new("ollama", true).unreachable())];
+        let router = LLMRouter::new(providers, create_test_config());
+
+        let err = router.ensure_default_provider().await.unwrap_err();
+        match err {
+            EngineError::ProviderNotConfigured { provider, guidance } => {
+                assert_eq!(provider, "ollama");
+                assert!(guidance.contains("not reachable"));
+                assert!(guidance.contains("llm.ollama.base_url"));
+            }
+            other => panic!("Expected ProviderNotConfigured, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_ensure_default_provider_is_advisory_with_fallbacks() {
+        // Unregistered default, but another provider can take the task
+        let providers: Vec<Box<dyn LLMProvider>> =
+            vec![Box::new(TestProvider::new("ollama", true))];
+        let router = LLMRouter::new(providers, config_with_default("openai"));
+        let problem = router.ensure_default_provider().await.unwrap();
+        assert!(matches!(
+            problem,
+            Some(EngineError::ProviderNotConfigured { ref guidance, .. })
+                if guidance.contains("openai_api_key")
+        ));
+
+        // Unhealthy default, same
+        let providers: Vec<Box<dyn LLMProvider>> = vec![
+            Box::new(TestProvider::new("ollama", true).unreachable()),
+            Box::new(TestProvider::new("openai", false).with_cost_per_1k(0.002)),
+        ];
+        let router = LLMRouter::new(providers, create_test_config());
+        let problem = router.ensure_default_provider().await.unwrap();
+        assert!(matches!(
+            problem,
+            Some(EngineError::ProviderNotConfigured { ref guidance, .. })
+                if guidance.contains("not reachable")
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_default_provider_health_is_cached() {
+        let unreachable = TestProvider::new("ollama", true).unreachable();
+        let health_checks = unreachable.health_checks();
+        let providers: Vec<Box<dyn LLMProvider>> = vec![Box::new(unreachable)];
+        let router = LLMRouter::new(providers, create_test_config());
+
+        assert!(router.ensure_default_provider().await.is_err());
+        assert!(router.ensure_default_provider().await.is_err());
+
+        assert_eq!(health_checks.load(std::sync::atomic::Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_failing_provider_is_skipped_until_cooldown() {
+        use std::sync::atomic::Ordering;
+
+        let openai = TestProvider::new("openai", false)
+            .answering("from openai")
+            .failing();
+        let calls = openai.calls();
+        let failing = openai.failing_switch();
+        let providers: Vec<Box<dyn LLMProvider>> = vec![
+            Box::new(openai),
+            Box::new(TestProvider::new("ollama", true).answering("from ollama")),
+        ];
+        let mut router = LLMRouter::new(providers, config_with_default("openai"));
+        router.breakers = ProviderBreakers::new(2, Duration::from_millis(100));
+        let messages = [Message::user("hi")];
+
+        // Two failures in a row fall back to ollama and open the breaker
+        for _ in 0..2 {
+            let (_, provider) = router.call(&messages).await.unwrap();
+            assert_eq!(provider, "ollama");
+        }
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+        // While open, openai is not even tried
+        let (_, provider) = router.call(&messages).await.unwrap();
+        assert_eq!(provider, "ollama");
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+        // After the cooldown a successful probe closes the breaker
+        tokio::time::sleep(Duration::from_millis(150)).await;
+        failing.store(false, Ordering::SeqCst);
+        let (_, provider) = router.call(&messages).await.unwrap();
+        assert_eq!(provider, "openai");
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+        assert!(!router.breakers.is_open("openai"));
+    }
+
+    #[tokio::test]
+    async fn test_all_breakers_open_probes_first_provider() {
+        use std::sync::atomic::Ordering;
+
+        let openai = TestProvider::new("openai", false)
+            .answering("from openai")
+            .failing();
+        let ollama = TestProvider::new("ollama", true)
+            .answering("from ollama")
+            .failing();
+        let (openai_calls, ollama_calls) = (openai.calls(), ollama.calls());
+        let openai_failing = openai.failing_switch();
+        let providers: Vec<Box<dyn LLMProvider>> = vec![Box::new(openai), Box::new(ollama)];
+        let mut router = LLMRouter::new(providers, config_with_default("openai"));
+        router.breakers = ProviderBreakers::new(1, Duration::from_secs(60));
+        let messages = [Message::user("hi")];
+
+        // Both fail once, which opens both breakers
+        assert!(router.call(&messages).await.is_err());
+        assert!(router.breakers.is_open("openai") && router.breakers.is_open("ollama"));
+
+        // Only the first provider is probed, and reopens on failure
+        assert!(router.call(&messages).await.is_err());
+        assert_eq!(openai_calls.load(Ordering::SeqCst), 2);
+        assert_eq!(ollama_calls.load(Ordering::SeqCst), 1);
+        assert!(router.breakers.is_open("openai"));
+
+        // A successful probe closes its breaker within the cooldown
+        openai_failing.store(false, Ordering::SeqCst);
+        let (_, provider) = router.call(&messages).await.unwrap();
+        assert_eq!(provider, "openai");
+        assert!(!router.breakers.is_open("openai"));
+        assert!(router.breakers.is_open("ollama"));
+        assert_eq!(ollama_calls.load(Ordering::SeqCst), 1);
+    }
+
+    /// Provider that answers with its own name, or always fails when down
+    fn static_provider(name: &'static str, local: bool, up: bool) -> Box<dyn LLMProvider> {
+        let provider = TestProvider::new(name, local).answering(name);
+        Box::new(if up { provider } else { provider.failing() })
+    }
+
+    fn priority_router(anthropic_up: bool, openai_up: bool) -> LLMRouter {
+        let providers: Vec<Box<dyn LLMProvider>> = vec![
+            static_provider("ollama", true, true),
+            static_provider("openai", false, openai_up),
+            static_provider("anthropic", false, anthropic_up),
+        ];
+        let config = Arc::new(LLMConfig {
+            provider_priority: vec![
+                "anthropic".to_string(),
+                "openai".to_string(),
+                "ollama".to_string(),
+            ],
+            ..(*create_test_config()).clone()
+        });
+        LLMRouter::new(providers, config)
+    }
+
+    #[tokio::test]
+    async fn test_ensure_default_provider_checks_the_priority_head() {
+        use std::sync::atomic::Ordering;
+
+        let ollama = TestProvider::new("ollama", true);
+        let anthropic = TestProvider::new("anthropic", false);
+        let ollama_checks = ollama.health_checks();
+        let anthropic_checks = anthropic.health_checks();
+        let config = Arc::new(LLMConfig {
+            provider_priority: vec!["anthropic".to_string(), "ollama".to_string()],
+            ..(*create_test_config()).clone()
+        });
+        let router = LLMRouter::new(vec![Box::new(ollama), Box::new(anthropic)], config);
+
+        assert!(router.ensure_default_provider().await.unwrap().is_none());
+        assert_eq!(anthropic_checks.load(Ordering::SeqCst), 1);
+        assert_eq!(ollama_checks.load(Ordering::SeqCst), 0);
+
+        // The default provider being absent doesn't matter
+        let config = Arc::new(LLMConfig {
+            provider_priority: vec!["ollama".to_string()],
+            ..(*config_with_default("openai")).clone()
+        });
+        let router = LLMRouter::new(vec![Box::new(TestProvider::new("ollama", true))], config);
+        assert!(router.ensure_default_provider().await.unwrap().is_none());
+
+        // Guidance names the setting the missing provider came from
+        let config = Arc::new(LLMConfig {
+            provider_priority: vec!["openai".to_string()],
+            ..(*create_test_config()).clone()
+        });
+        let router = LLMRouter::new(Vec::new(), config);
+        match router.ensure_default_provider().await.unwrap_err() {
+            EngineError::ProviderNotConfigured { guidance, .. } => {
+                assert!(guidance.contains("first in llm.provider_priority"));
+                assert!(!guidance.contains("llm.default_provider"));
+            }
+            other => panic!("Expected ProviderNotConfigured, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_provider_priority_overrides_default() {
+        let router = priority_router(true, true);
+        let messages = [Message::user("hi")];
+
+        let (_, provider) = router.call(&messages).await.unwrap();
+        assert_eq!(provider, "anthropic");
+        assert_eq!(
+            router.explain_routing(&messages, "anthropic", &RoutingPreferences::default()),
+            "anthropic (cloud): provider priority #1"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_provider_priority_falls_back_in_order() {
+        let messages = [Message::user("hi")];
+
+        let (_, provider) = priority_router(false, true).call(&messages).await.unwrap();
+        assert_eq!(provider, "openai");
+
+        let (_, provider) = priority_router(false, false).call(&messages).await.unwrap();
+        assert_eq!(provider, "ollama");
+    }
 }
@@ -0,0 +1,149 @@
+//! Configurable provider stand-in for unit tests
+//!
+//! One `TestProvider` covers what the tests need from a provider: replaying
+//! a script of responses, answering or stalling once the script runs out,
+//! failing on demand, reporting itself unhealthy, pricing tokens and
+//! counting its calls.
+
+use super::{LLMError, LLMProvider, LLMResponse, Message, Result};
+use async_trait::async_trait;
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// What `generate` does once the script is used up
+enum Exhausted {
+    Panic,
+    Answer(String),
+    Stall,
+}
+
+pub(crate) struct TestProvider {
+    name: &'static str,
+    local: bool,
+    cost_per_1k: f64,
+    script: Mutex<VecDeque<LLMResponse>>,
+    exhausted: Exhausted,
+    healthy: bool,
+    failing: Arc<AtomicBool>,
+    calls: Arc<AtomicUsize>,
+    health_checks: Arc<AtomicUsize>,
+    before: Option<Box<dyn Fn(usize) + Send + Sync>>,
+}
+
+impl TestProvider {
+    /// Free, healthy provider with an empty script
+    pub(crate) fn new(name: &'static str, local: bool) -> Self {
+        Self {
+            name,
+            local,
+            cost_per_1k: 0.0,
+            script: Mutex::new(VecDeque::new()),
+            exhausted: Exhausted::Panic,
+            healthy: true,
+            failing: Arc::new(AtomicBool::new(false)),
+            calls: Arc::new(AtomicUsize::new(0)),
+            health_checks: Arc::new(AtomicUsize::new(0)),
+            before: None,
+        }
+    }
+
+    /// Provider that replays `responses` in order and panics once they run out
+    pub(crate) fn scripted(name: &'static str, local: bool, responses: Vec<LLMResponse>) -> Self {
+        Self {
+            script: Mutex::new(responses.into()),
+            ..Self::new(name, local)
+        }
+    }
+
+    /// Answer with `text` once the script is used up
+    pub(crate) fn answering(mut self, text: &str) -> Self {
+        self.exhausted = Exhausted::Answer(text.to_string());
+        self
+    }
+
+    /// Never answer once the script is used up
+    pub(crate) fn stalling(mut self) -> Self {
+        self.exhausted = Exhausted::Stall;
+        self
+    }
+
+    /// Fail every call until the switch from `failing_switch` is cleared
+    pub(crate) fn failing(self) -> Self {
+        self.failing.store(true, Ordering::SeqCst);
+        self
+    }
+
+    /// Fail every call and every health check
+    pub(crate) fn unreachable(mut self) -> Self {
+        self.healthy = false;
+        self.failing()
+    }
+
+    pub(crate) fn with_cost_per_1k(mut self, cost_per_1k: f64) -> Self {
+        self.cost_per_1k = cost_per_1k;
+        self
+    }
+
+    /// Run `before` with the number of the call before each response, e.g.
+    /// to change the workspace between tool calls
+    pub(crate) fn before_each(mut self, before: impl Fn(usize) + Send + Sync + 'static) -> Self {
+        self.before = Some(Box::new(before));
+        self
+    }
+
+    pub(crate) fn failing_switch(&self) -> Arc<AtomicBool> {
+        Arc::clone(&self.failing)
+    }
+
+    pub(crate) fn calls(&self) -> Arc<AtomicUsize> {
+        Arc::clone(&self.calls)
+    }
+
+    pub(crate) fn health_checks(&self) -> Arc<AtomicUsize> {
+        Arc::clone(&self.health_checks)
+    }
+}
+
+#[async_trait]
+impl LLMProvider for TestProvider {
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn is_local(&self) -> bool {
+        self.local
+    }
+
+    fn estimated_cost(&self, tokens: usize) -> f64 {
+        (tokens as f64 / 1000.0) * self.cost_per_1k
+    }
+
+    async fn generate(&self, _messages: &[Message]) -> Result<LLMResponse> {
+        let call = self.calls.fetch_add(1, Ordering::SeqCst) + 1;
+        if let Some(before) = &self.before {
+            before(call);
+        }
+        if self.failing.load(Ordering::SeqCst) {
+            return Err(LLMError::ProviderUnavailable(format!(
+                "{} is down",
+                self.name
+            )));
+        }
+
+        let next = self.script.lock().unwrap().pop_front();
+        match (next, &self.exhausted) {
+            (Some(response), _) => Ok(response),
+            (None, Exhausted::Answer(text)) => {
+                Ok(LLMResponse::FinalAnswer(super::FinalAnswer::new(text)))
+            }
+            (None, Exhausted::Stall) => std::future::pending().await,
+            (None, Exhausted::Panic) => panic!("script exhausted"),
+        }
+    }
+
+    async fn check_health(&self) -> bool {
+        self.health_checks.fetch_add(1, Ordering::SeqCst);
+        self.healthy
+    }
+}
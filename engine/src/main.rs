@@ -3,14 +3,19 @@
 
 use clap::Parser;
 use rove_engine::agent::SteeringEngine;
-use rove_engine::cli::{Cli, Command, PluginAction, SkillAction};
+use rove_engine::cli::{
+    AuditAction, Cli, Command, ConfigAction, DbAction, PluginAction, SkillAction, WorkspaceAction,
+};
 use rove_engine::config::Config;
 use rove_engine::daemon::DaemonManager;
 use rove_engine::handlers::{
-    handle_doctor, handle_history, handle_plugins_list, handle_replay, handle_run, handle_update,
-    OutputFormat,
+    handle_audit_export, handle_audit_verify, handle_config_dump, handle_cost, handle_db_prune,
+    handle_doctor, handle_export_prompt, handle_history, handle_plugins_list,
+    handle_plugins_search, handle_plugins_toggle, handle_plugins_update, handle_replay, handle_run,
+    handle_update, handle_workspace_list, handle_workspace_use, open_database, OutputFormat,
+    RunOptions,
 };
-use rove_engine::telemetry::{init_telemetry, init_telemetry_with_level};
+use rove_engine::telemetry::{init_log_file, init_telemetry, init_telemetry_with_level};
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
@@ -34,7 +39,7 @@ async fn main() -> anyhow::Result<()> {
     };
 
     // Load configuration (or use custom path if provided)
-    let config = if let Some(config_path) = &cli.config {
+    let mut config = if let Some(config_path) = &cli.config {
         Config::load_from_path(config_path)?
     } else {
         Config::load_or_create()?
@@ -43,6 +48,12 @@ async fn main() -> anyhow::Result<()> {
     // Re-initialize telemetry with config-driven log level
     // (only takes effect if RUST_LOG env var is not set)
     init_telemetry_with_level(&config.core.log_level);
+    if let Err(e) = init_log_file(&config.telemetry) {
+        tracing::warn!("Failed to open log file: {}", e);
+    }
+    for warning in config.warnings() {
+        eprintln!("Warning: {}", warning);
+    }
 
     // Handle commands
     match cli.command {
@@ -53,8 +64,13 @@ async fn main() -> anyhow::Result<()> {
 
         Command::Start => {
             tracing::info!("Starting daemon...");
-            let manager = DaemonManager::new(&config)?;
+            let mut manager = DaemonManager::new(&config)?;
+            // The database is set first so startup verification can use the
+            // signature cache
+            manager.set_database(std::sync::Arc::new(open_database(&config).await?));
             manager.start().await?;
+            manager.start_retention(&config.retention)?;
+            manager.load_dev_plugins(&config).await?;
             println!("Rove daemon started (PID {})", std::process::id());
 
             // Keep the process alive — wait for shutdown signal
@@ -62,6 +78,7 @@ async fn main() -> anyhow::Result<()> {
                 .wait_for_shutdown(std::time::Duration::from_secs(u64::MAX))
                 .await
                 .ok();
+            manager.graceful_shutdown(&config).await?;
             Ok(())
         }
 
@@ -72,61 +89,74 @@ async fn main() -> anyhow::Result<()> {
             Ok(())
         }
 
-        Command::Status => {
+        Command::Status { all } => {
             tracing::info!("Checking daemon status...");
-            let status = DaemonManager::status(&config)?;
+            let status = DaemonManager::status_probing(&config, all)?;
             if status.is_running {
                 println!("Rove daemon is running (PID {})", status.pid.unwrap_or(0));
             } else {
                 println!("Rove daemon is not running.");
             }
-            println!("Providers:");
-            println!(
-                "  Ollama:     {}",
-                if status.providers.ollama {
-                    "available"
-                } else {
-                    "unavailable"
-                }
-            );
-            println!(
-                "  OpenAI:     {}",
-                if status.providers.openai {
+            if let Some(last) = &status.last_shutdown {
+                let stopped_at = chrono::DateTime::from_timestamp(last.stopped_at, 0)
+                    .map(|dt| dt.format("%Y-%m-%d %H:%M:%S").to_string())
+                    .unwrap_or_else(|| "Unknown".to_string());
+                println!("Last stopped: {} at {}", last.reason, stopped_at);
+            }
+            let providers = &status.providers;
+            let availability = |name: &str, available: bool| {
+                if providers.skipped.contains(&name) {
+                    "not probed"
+                } else if available {
                     "available"
                 } else {
                     "unavailable"
                 }
-            );
+            };
+            println!("Providers:");
+            println!("  Ollama:     {}", availability("ollama", providers.ollama));
+            println!("  OpenAI:     {}", availability("openai", providers.openai));
             println!(
                 "  Anthropic:  {}",
-                if status.providers.anthropic {
-                    "available"
-                } else {
-                    "unavailable"
-                }
-            );
-            println!(
-                "  Gemini:     {}",
-                if status.providers.gemini {
-                    "available"
-                } else {
-                    "unavailable"
-                }
+                availability("anthropic", providers.anthropic)
             );
+            println!("  Gemini:     {}", availability("gemini", providers.gemini));
             println!(
                 "  NVIDIA NIM: {}",
-                if status.providers.nvidia_nim {
-                    "available"
-                } else {
-                    "unavailable"
-                }
+                availability("nvidia_nim", providers.nvidia_nim)
             );
+            if !providers.skipped.is_empty() {
+                println!("Use --all to probe every provider.");
+            }
             Ok(())
         }
 
-        Command::Run { task } => {
-            tracing::info!("Executing task: {}", task);
-            handle_run(task, &config, format).await
+        Command::Run {
+            task,
+            resume,
+            no_daemon,
+            max_file_size,
+            timeout,
+            dump_transcript,
+        } => {
+            match (&task, &resume) {
+                (_, Some(task_id)) => tracing::info!("Resuming task: {}", task_id),
+                (Some(task), None) => tracing::info!("Executing task: {}", task),
+                (None, None) => {}
+            }
+            let options = RunOptions {
+                task,
+                resume,
+                no_daemon,
+                max_file_size,
+                timeout,
+                dump_transcript,
+            };
+            handle_run(options, &config, format).await
+        }
+
+        Command::ExportPrompt { task, skills } => {
+            handle_export_prompt(task, &skills, &config, format).await
         }
 
         Command::History { limit } => {
@@ -134,6 +164,8 @@ async fn main() -> anyhow::Result<()> {
             handle_history(limit, &config, format).await
         }
 
+        Command::Cost { since } => handle_cost(since, &config, format).await,
+
         Command::Replay { task_id } => {
             tracing::info!("Replaying task: {}", task_id);
             handle_replay(task_id, &config, format).await
@@ -142,9 +174,24 @@ async fn main() -> anyhow::Result<()> {
         Command::Plugins { action } => {
             tracing::info!("Plugin management: {:?}", action);
             match action {
-                PluginAction::List => handle_plugins_list(&config, format).await,
-                _ => {
-                    println!("Plugin management actions (enable/disable/info) - to be implemented");
+                PluginAction::List { check_updates } => {
+                    handle_plugins_list(&config, format, check_updates).await
+                }
+                PluginAction::Search { query } => handle_plugins_search(&query, format).await,
+                PluginAction::Update { ref name, yes } => {
+                    handle_plugins_update(&config, name, yes, format).await
+                }
+                PluginAction::Enable { ref names, all }
+                | PluginAction::Disable { ref names, all } => {
+                    let enable = matches!(action, PluginAction::Enable { .. });
+                    let config_path = match &cli.config {
+                        Some(path) => path.clone(),
+                        None => Config::default_config_path()?,
+                    };
+                    handle_plugins_toggle(names, all, enable, &mut config, &config_path, format)
+                }
+                PluginAction::Info { .. } => {
+                    println!("Plugin management actions (info) - to be implemented");
                     Ok(())
                 }
             }
@@ -156,20 +203,29 @@ async fn main() -> anyhow::Result<()> {
             Ok(())
         }
 
-        Command::Config { action } => {
-            tracing::info!("Config management: {:?}", action);
-            println!("Config management - to be implemented");
-            Ok(())
-        }
+        Command::Config { action } => match action {
+            ConfigAction::Dump { effective } => {
+                let path = match cli.config {
+                    Some(path) => path,
+                    None => Config::default_config_path()?,
+                };
+                handle_config_dump(&config, &path, effective, format)
+            }
+            action => {
+                tracing::info!("Config management: {:?}", action);
+                println!("Config management - to be implemented");
+                Ok(())
+            }
+        },
 
-        Command::Doctor => {
+        Command::Doctor { fix } => {
             tracing::info!("Running diagnostics...");
-            handle_doctor(&config, format).await
+            handle_doctor(&config, format, fix).await
         }
 
-        Command::Update { check } => {
+        Command::Update { check, apply } => {
             tracing::info!("Checking for updates...");
-            handle_update(check, format).await
+            handle_update(check, apply, format).await
         }
 
         Command::Bot { action } => {
@@ -178,6 +234,25 @@ async fn main() -> anyhow::Result<()> {
             Ok(())
         }
 
+        Command::Workspace { action } => {
+            tracing::info!("Workspace management: {:?}", action);
+            match action {
+                WorkspaceAction::List => handle_workspace_list(&config, format),
+                WorkspaceAction::Use { name } => handle_workspace_use(&name, &mut config, format),
+            }
+        }
+
+        Command::Audit { action } => match action {
+            AuditAction::Export { output } => {
+                handle_audit_export(output.as_deref(), &config, format).await
+            }
+            AuditAction::Verify { file } => handle_audit_verify(&file, &config, format),
+        },
+
+        Command::Db { action } => match action {
+            DbAction::Prune => handle_db_prune(&config, format).await,
+        },
+
         Command::Skill { action } => {
             tracing::info!("Skill management: {:?}", action);
 
@@ -329,6 +404,37 @@ always_verify = false
                     }
                     Ok(())
                 }
+
+                SkillAction::Export { name, out } => {
+                    let engine = SteeringEngine::new(&skills_dir).await?;
+                    let content = engine.export_skill(&name).await?;
+                    match out {
+                        Some(path) => {
+                            tokio::fs::write(&path, content).await?;
+                            println!("Exported skill '{}' to {}", name, path.display());
+                        }
+                        None => print!("{}", content),
+                    }
+                    Ok(())
+                }
+
+                SkillAction::Import { file } => {
+                    let mut engine = SteeringEngine::new(&skills_dir).await?;
+                    let skill = engine.import_skill(&file).await?;
+                    println!(
+                        "Imported skill '{}' to {}",
+                        skill.name,
+                        skill.file_path.display()
+                    );
+                    Ok(())
+                }
+
+                SkillAction::Priority { name, value } => {
+                    let mut engine = SteeringEngine::new(&skills_dir).await?;
+                    engine.set_priority(&name, value).await?;
+                    println!("Skill '{}' priority set to {}.", name, value);
+                    Ok(())
+                }
             }
         }
     }
@@ -5,6 +5,10 @@
 //! memory growth and supports both specific event subscriptions and global
 //! "All" subscriptions.
 //!
+//! High-volume events such as command output go through `publish_nowait`,
+//! which drops the event for a subscriber whose channel is full instead of
+//! stalling the publisher.
+//!
 //! # Requirements
 //! - 1.2: Engine SHALL provide a Message_Bus for all inter-component communication
 //! - 1.3: Engine SHALL prevent direct communication between Core_Tools and Plugins
@@ -36,6 +40,8 @@ pub enum EventType {
     ConfigChanged,
     /// A plugin has crashed
     PluginCrashed,
+    /// A running command wrote output, or exited
+    CommandOutput,
     /// Subscribe to all event types
     All,
 }
@@ -66,6 +72,25 @@ pub enum Event {
     },
     /// Plugin crashed
     PluginCrashed { plugin_id: String, error: String },
+    /// A line (or final partial line) a command of `task_id` wrote
+    CommandOutput {
+        task_id: String,
+        stream: OutputStream,
+        chunk: String,
+    },
+    /// A command of `task_id` exited; `exit_code` is `None` if it was
+    /// killed by a signal
+    CommandExited {
+        task_id: String,
+        exit_code: Option<i32>,
+    },
+}
+
+/// Output stream of a command
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputStream {
+    Stdout,
+    Stderr,
 }
 
 impl Event {
@@ -80,6 +105,7 @@ impl Event {
             Event::DaemonStopping => EventType::DaemonStopping,
             Event::ConfigChanged { .. } => EventType::ConfigChanged,
             Event::PluginCrashed { .. } => EventType::PluginCrashed,
+            Event::CommandOutput { .. } | Event::CommandExited { .. } => EventType::CommandOutput,
         }
     }
 }
@@ -148,6 +174,22 @@ impl MessageBus {
             }
         }
     }
+
+    /// Publish an event without waiting for slow subscribers
+    ///
+    /// Like `publish`, but a subscriber whose channel is full misses the
+    /// event. Blocks on the subscriber table lock, so call it from a
+    /// blocking thread (e.g. one reading a child's output), not async code.
+    pub fn publish_nowait(&self, event: Event) {
+        let channels = self.channels.blocking_lock();
+        let event_type = event.event_type();
+
+        for key in [event_type, EventType::All] {
+            for tx in channels.get(&key).into_iter().flatten() {
+                let _ = tx.try_send(event.clone());
+            }
+        }
+    }
 }
 
 impl Default for MessageBus {
@@ -298,4 +340,29 @@ mod tests {
         // rx_completed should not have received the TaskStarted event
         assert!(rx_completed.try_recv().is_err());
     }
+
+    #[tokio::test]
+    async fn test_publish_nowait_drops_events_for_full_subscribers() {
+        let bus = Arc::new(MessageBus::new());
+        let mut rx = bus.subscribe(EventType::CommandOutput).await;
+
+        let publisher = Arc::clone(&bus);
+        tokio::task::spawn_blocking(move || {
+            for i in 0..CHANNEL_BUFFER_SIZE + 10 {
+                publisher.publish_nowait(Event::CommandOutput {
+                    task_id: "task-6".to_string(),
+                    stream: OutputStream::Stdout,
+                    chunk: format!("line {}\n", i),
+                });
+            }
+        })
+        .await
+        .unwrap();
+
+        let mut count = 0;
+        while rx.try_recv().is_ok() {
+            count += 1;
+        }
+        assert_eq!(count, CHANNEL_BUFFER_SIZE);
+    }
 }
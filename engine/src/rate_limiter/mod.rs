@@ -13,30 +13,73 @@
 //! For Tier 2 operations, a circuit breaker trips when 5 operations occur
 //! within 60 seconds. When tripped, all Tier 2 operations require local unlock.
 //!
+//! # Trusted Sources
+//!
+//! Sources listed with `with_bypass_sources` are never limited; each bypass
+//! is logged. Their operations are still recorded. Limiters that see client
+//! sources, i.e. the Telegram bot's (`telegram:<chat_id>`) and
+//! `CoreToolRateLimit` behind the API server (`api:<hash>`), are built with
+//! `from_config` so the list comes from `security.rate_limit_bypass`.
+//!
 //! # Database Tracking
 //!
-//! All operations are tracked in the `rate_limits` table with automatic cleanup
-//! of old entries (older than 1 hour).
+//! All operations are tracked in the `rate_limits` table. Old entries are
+//! pruned by the data-retention job (see `db::retention`), which must keep at
+//! least `LONGEST_WINDOW` of history.
 //!
 //! Requirements: 11.1, 11.2, 11.3, 11.4, 11.5, 11.6, 11.7
 
-use anyhow::{Context, Result};
+use crate::config::SecurityConfig;
+use anyhow::{anyhow, Context, Result};
+use sdk::core_tool::RateLimitHandleImpl;
 use sdk::errors::EngineError;
 use sqlx::SqlitePool;
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::collections::HashSet;
+use std::future::Future;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::runtime::{Handle, RuntimeFlavor};
 use tracing::{debug, error, info, warn};
 
-use crate::risk_assessor::RiskTier;
+use crate::risk_assessor::{Operation, OperationSource, RiskAssessor, RiskTier};
+
+/// Longest window any limit looks back over (Tier 1: 60 per hour)
+pub const LONGEST_WINDOW: Duration = Duration::from_secs(3600);
 
 /// Rate limiter for tracking and enforcing operation limits
 pub struct RateLimiter {
     pool: SqlitePool,
+    /// Sources that are never limited
+    bypass: HashSet<String>,
 }
 
 impl RateLimiter {
     /// Create a new rate limiter
     pub fn new(pool: SqlitePool) -> Self {
-        Self { pool }
+        Self {
+            pool,
+            bypass: HashSet::new(),
+        }
+    }
+
+    /// Create a rate limiter exempting the sources listed in
+    /// `security.rate_limit_bypass`
+    ///
+    /// `rove run` limits each task by its id, which is never listed, so the
+    /// list only matters to the Telegram bot's limiter and
+    /// `CoreToolRateLimit`.
+    pub fn from_config(pool: SqlitePool, security: &SecurityConfig) -> Self {
+        Self::new(pool).with_bypass_sources(security.rate_limit_bypass.iter().cloned())
+    }
+
+    /// Exempt `sources` from all limits, e.g. `telegram:<chat_id>` or an
+    /// `api:<hash>` source of a trusted token
+    pub fn with_bypass_sources<I, S>(mut self, sources: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.bypass = sources.into_iter().map(Into::into).collect();
+        self
     }
 
     /// Check if an operation is allowed under rate limits
@@ -46,8 +89,18 @@ impl RateLimiter {
     /// - Tier 1: 60 operations per hour
     /// - Tier 2: 10 operations per 10 minutes AND 5 operations per 60 seconds
     ///
+    /// Sources on the bypass list are always allowed.
+    ///
     /// Requirements: 11.1, 11.2, 11.3
     pub async fn check_limit(&self, source: &str, tier: RiskTier) -> Result<()> {
+        if self.bypass.contains(source) && !matches!(tier, RiskTier::Tier0) {
+            info!(
+                "Rate limits bypassed for trusted source {} ({:?})",
+                source, tier
+            );
+            return Ok(());
+        }
+
         let now = SystemTime::now()
             .duration_since(UNIX_EPOCH)
             .context("Failed to get current time")?
@@ -132,7 +185,6 @@ impl RateLimiter {
     /// Record an operation for rate limiting
     ///
     /// This should be called after an operation is successfully executed.
-    /// It records the operation in the database and cleans up old entries.
     ///
     /// Requirements: 11.1, 11.2, 11.3
     pub async fn record_operation(&self, source: &str, tier: RiskTier) -> Result<()> {
@@ -162,12 +214,72 @@ impl RateLimiter {
             source, tier_value, now
         );
 
-        // Clean up old entries (older than 1 hour)
-        self.cleanup_old_entries(now).await?;
-
         Ok(())
     }
 
+    /// Check an operation against the limits and record it if it is allowed
+    ///
+    /// The check and the insert are one SQL statement, so concurrent callers
+    /// can't both take the last slot in a window. A refused operation is not
+    /// recorded and gets the same error as from `check_limit`.
+    ///
+    /// Requirements: 11.1, 11.2, 11.3
+    pub async fn check_and_record(&self, source: &str, tier: RiskTier) -> Result<()> {
+        // `(window_ms, limit)` pairs that must all have room for the operation
+        let windows: [(u64, i64); 2] = match tier {
+            RiskTier::Tier0 => return Ok(()),
+            _ if self.bypass.contains(source) => {
+                info!(
+                    "Rate limits bypassed for trusted source {} ({:?})",
+                    source, tier
+                );
+                return self.record_operation(source, tier).await;
+            }
+            // Tier 1 has a single window, checked twice
+            RiskTier::Tier1 => [(3_600_000, 60), (3_600_000, 60)],
+            RiskTier::Tier2 => [(600_000, 10), (60_000, 5)],
+        };
+
+        loop {
+            let now = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .context("Failed to get current time")?
+                .as_millis() as u64;
+
+            let result = sqlx::query(
+                "INSERT INTO rate_limits (source, tier, timestamp) \
+                 SELECT ?1, ?2, ?3 \
+                 WHERE (SELECT COUNT(*) FROM rate_limits \
+                        WHERE source = ?1 AND tier = ?2 AND timestamp >= ?4) < ?5 \
+                 AND (SELECT COUNT(*) FROM rate_limits \
+                      WHERE source = ?1 AND tier = ?2 AND timestamp >= ?6) < ?7",
+            )
+            .bind(source)
+            .bind(tier as i32)
+            .bind(now as i64)
+            .bind(now.saturating_sub(windows[0].0) as i64)
+            .bind(windows[0].1)
+            .bind(now.saturating_sub(windows[1].0) as i64)
+            .bind(windows[1].1)
+            .execute(&self.pool)
+            .await
+            .context("Failed to record operation")?;
+
+            if result.rows_affected() > 0 {
+                debug!(
+                    "Recorded operation: source={}, tier={}, timestamp={}",
+                    source, tier as i32, now
+                );
+                return Ok(());
+            }
+
+            // Refused: `check_limit` reports which limit was hit (and trips
+            // the circuit breaker). If an old entry left the window in the
+            // meantime it passes, and the insert is retried.
+            self.check_limit(source, tier).await?;
+        }
+    }
+
     /// Count operations for a source and tier since a given timestamp
     ///
     /// Requirements: 11.1, 11.2, 11.3
@@ -216,11 +328,15 @@ impl RateLimiter {
 
     /// Clean up old rate limit entries
     ///
-    /// Removes entries older than 1 hour to prevent unbounded growth.
+    /// Removes entries older than `max_age` to prevent unbounded growth and
+    /// returns how many were removed. Called by the data-retention job.
     ///
     /// Requirements: 11.7
-    async fn cleanup_old_entries(&self, now: u64) -> Result<()> {
-        let cutoff = (now - 3_600_000) as i64; // 1 hour ago
+    pub async fn cleanup_old_entries(&self, max_age: Duration) -> Result<u64> {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .context("Failed to get current time")?;
+        let cutoff = now.saturating_sub(max_age).as_millis() as i64;
 
         let result = sqlx::query("DELETE FROM rate_limits WHERE timestamp < ?")
             .bind(cutoff)
@@ -235,7 +351,7 @@ impl RateLimiter {
             );
         }
 
-        Ok(())
+        Ok(result.rows_affected())
     }
 
     /// Check if circuit breaker is tripped for a source
@@ -280,6 +396,92 @@ impl RateLimiter {
     }
 }
 
+/// Rate limiting for core tools, behind `CoreContext::rate_limit`
+///
+/// Operations are assessed by the `RiskAssessor` as remote operations, so
+/// e.g. `execute_task` is Tier 1. The handle methods block on the database.
+pub struct CoreToolRateLimit {
+    limiter: RateLimiter,
+    assessor: RiskAssessor,
+}
+
+impl CoreToolRateLimit {
+    /// Rate limit core tools with `limiter`
+    pub fn new(limiter: RateLimiter) -> Self {
+        Self {
+            limiter,
+            assessor: RiskAssessor::new(),
+        }
+    }
+
+    /// Rate limit core tools, exempting the API tokens and other sources
+    /// listed in `security.rate_limit_bypass`
+    pub fn from_config(pool: SqlitePool, security: &SecurityConfig) -> Self {
+        Self::new(RateLimiter::from_config(pool, security))
+    }
+
+    fn tier(&self, operation: &str) -> std::result::Result<RiskTier, EngineError> {
+        self.assessor
+            .assess(&Operation::new(operation, vec![], OperationSource::Remote))
+    }
+}
+
+impl RateLimitHandleImpl for CoreToolRateLimit {
+    fn check_limit(&self, source: &str, operation: &str) -> std::result::Result<(), EngineError> {
+        let tier = self.tier(operation)?;
+        block_on(self.limiter.check_limit(source, tier))
+    }
+
+    fn record_operation(
+        &self,
+        source: &str,
+        operation: &str,
+    ) -> std::result::Result<(), EngineError> {
+        let tier = self.tier(operation)?;
+        block_on(self.limiter.record_operation(source, tier))
+    }
+
+    fn check_and_record(
+        &self,
+        source: &str,
+        operation: &str,
+    ) -> std::result::Result<(), EngineError> {
+        let tier = self.tier(operation)?;
+        block_on(self.limiter.check_and_record(source, tier))
+    }
+}
+
+/// Run `future` to completion from synchronous code
+///
+/// On a multi-threaded runtime the current worker blocks in place.
+/// `block_in_place` panics on a current-thread runtime, so there (and
+/// outside any runtime) the future runs on a helper thread instead.
+fn block_on(
+    future: impl Future<Output = Result<()>> + Send,
+) -> std::result::Result<(), EngineError> {
+    let result = match Handle::try_current() {
+        Ok(handle) if handle.runtime_flavor() == RuntimeFlavor::MultiThread => {
+            tokio::task::block_in_place(|| handle.block_on(future))
+        }
+        _ => std::thread::scope(|scope| {
+            scope
+                .spawn(|| {
+                    tokio::runtime::Builder::new_current_thread()
+                        .enable_all()
+                        .build()
+                        .context("Failed to start a runtime for rate limiting")?
+                        .block_on(future)
+                })
+                .join()
+                .unwrap_or_else(|_| Err(anyhow!("Rate limit check panicked")))
+        }),
+    };
+    result.map_err(|e| match e.downcast::<EngineError>() {
+        Ok(engine_error) => engine_error,
+        Err(e) => EngineError::Database(format!("{:#}", e)),
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -471,6 +673,23 @@ mod tests {
             .is_ok());
     }
 
+    #[tokio::test]
+    async fn test_operations_in_the_same_millisecond_are_all_counted() {
+        let (_temp_dir, _db, limiter) = setup_test_db().await;
+
+        for _ in 0..20 {
+            limiter
+                .record_operation("test_source", RiskTier::Tier1)
+                .await
+                .unwrap();
+        }
+
+        assert_eq!(
+            limiter.count_operations("test_source", 1, 0).await.unwrap(),
+            20
+        );
+    }
+
     #[tokio::test]
     async fn test_cleanup_old_entries() {
         let (_temp_dir, _db, limiter) = setup_test_db().await;
@@ -500,13 +719,10 @@ mod tests {
             .await
             .unwrap();
 
-        // Trigger cleanup by recording a new operation
-        limiter
-            .record_operation("test_source", RiskTier::Tier1)
-            .await
-            .unwrap();
+        let removed = limiter.cleanup_old_entries(LONGEST_WINDOW).await.unwrap();
+        assert_eq!(removed, 1);
 
-        // Verify old entry was cleaned up
+        // Verify old entry was cleaned up and recent ones kept
         let cutoff = (now - 3_600_000) as i64;
         let count: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM rate_limits WHERE timestamp < ?")
             .bind(cutoff)
@@ -515,5 +731,129 @@ mod tests {
             .unwrap();
 
         assert_eq!(count.0, 0, "Old entries should be cleaned up");
+
+        let remaining: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM rate_limits")
+            .fetch_one(&limiter.pool)
+            .await
+            .unwrap();
+        assert_eq!(remaining.0, 10);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_core_tool_rate_limit_assesses_remote_tasks_as_tier1() {
+        let (_temp_dir, _db, limiter) = setup_test_db().await;
+        let rate_limit = CoreToolRateLimit::new(limiter);
+
+        for _ in 0..60 {
+            rate_limit.check_limit("api", "execute_task").unwrap();
+            rate_limit.record_operation("api", "execute_task").unwrap();
+        }
+
+        assert!(matches!(
+            rate_limit.check_limit("api", "execute_task"),
+            Err(EngineError::RateLimitExceeded {
+                tier: 1,
+                count: 60,
+                ..
+            })
+        ));
+        assert!(rate_limit.check_limit("other", "execute_task").is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_core_tool_rate_limit_works_on_a_current_thread_runtime() {
+        let (_temp_dir, _db, limiter) = setup_test_db().await;
+        let rate_limit = CoreToolRateLimit::new(limiter);
+
+        for _ in 0..60 {
+            rate_limit.check_and_record("api", "execute_task").unwrap();
+        }
+        assert!(matches!(
+            rate_limit.check_and_record("api", "execute_task"),
+            Err(EngineError::RateLimitExceeded { count: 60, .. })
+        ));
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_concurrent_check_and_record_never_exceeds_the_limit() {
+        let (_temp_dir, _db, limiter) = setup_test_db().await;
+        let limiter = std::sync::Arc::new(limiter);
+
+        let attempts: Vec<_> = (0..80)
+            .map(|_| {
+                let limiter = limiter.clone();
+                tokio::spawn(async move {
+                    limiter
+                        .check_and_record("test_source", RiskTier::Tier1)
+                        .await
+                        .is_ok()
+                })
+            })
+            .collect();
+        let mut allowed = 0;
+        for attempt in attempts {
+            if attempt.await.unwrap() {
+                allowed += 1;
+            }
+        }
+
+        assert_eq!(allowed, 60);
+        assert_eq!(
+            limiter.count_operations("test_source", 1, 0).await.unwrap(),
+            60
+        );
+    }
+
+    #[tokio::test]
+    async fn test_bypass_source_is_never_limited() {
+        let (_temp_dir, db, _) = setup_test_db().await;
+        let limiter = RateLimiter::new(db.pool().clone()).with_bypass_sources(["cli:admin"]);
+
+        // Both sources go well past the Tier 1 and Tier 2 limits
+        for source in ["cli:admin", "remote"] {
+            for (tier, count) in [(RiskTier::Tier1, 70), (RiskTier::Tier2, 12)] {
+                for _ in 0..count {
+                    limiter.record_operation(source, tier).await.unwrap();
+                }
+            }
+        }
+
+        for tier in [RiskTier::Tier1, RiskTier::Tier2] {
+            assert!(limiter.check_limit("cli:admin", tier).await.is_ok());
+            assert!(limiter.check_limit("remote", tier).await.is_err());
+        }
+        assert!(!limiter
+            .is_circuit_breaker_tripped("cli:admin")
+            .await
+            .unwrap());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_core_tool_rate_limit_honors_configured_bypass() {
+        use sdk::core_tool::RateLimitHandle;
+        use std::sync::Arc;
+
+        let (_temp_dir, db, _) = setup_test_db().await;
+        let mut security = crate::config::Config::default_config().security;
+        security.rate_limit_bypass = vec!["api:9c1d5e7a2b4f6083".to_string()];
+        let handle = RateLimitHandle::new(Arc::new(CoreToolRateLimit::from_config(
+            db.pool().clone(),
+            &security,
+        )));
+
+        for _ in 0..70 {
+            handle
+                .check_and_record("api:9c1d5e7a2b4f6083", "execute_task")
+                .unwrap();
+        }
+        for _ in 0..60 {
+            handle
+                .check_and_record("api:0000000000000000", "execute_task")
+                .unwrap();
+        }
+        assert!(matches!(
+            handle.check_and_record("api:0000000000000000", "execute_task"),
+            Err(EngineError::RateLimitExceeded { count: 60, .. })
+        ));
     }
 }
@@ -4,10 +4,10 @@
 //! All operations are classified into three risk tiers:
 //!
 //! - **Tier 0 (Read-only)**: Auto-execute without confirmation
-//!   - read_file, list_dir, git_status, git_log
+//!   - read_file, list_dir, git_status, git_log, git_diff
 //!
 //! - **Tier 1 (Write/Reversible)**: Display operation with 10-second countdown
-//!   - write_file, git_commit, create_dir
+//!   - write_file, git_commit, git_branch, git_checkout, create_dir
 //!
 //! - **Tier 2 (Destructive/Irreversible)**: Require explicit confirmation
 //!   - delete_file, git_push, execute_command, git_reset
@@ -82,6 +82,45 @@ impl Operation {
             source,
         }
     }
+
+    /// Operation performed by a call to the tool `tool_name`
+    ///
+    /// Maps tool names onto the operations they perform; unknown tools map to
+    /// `execute_task` (Tier 0). String argument values are kept so dangerous
+    /// flags are still detected.
+    pub fn for_tool_call(
+        tool_name: &str,
+        args: &serde_json::Value,
+        source: OperationSource,
+    ) -> Self {
+        let name = match tool_name {
+            "read_file" | "read_files" | "tail_file" | "list_dir" | "file_exists" => "read_file",
+            "write_file" => "write_file",
+            "create_dir" => "create_dir",
+            "delete_file" => "delete_file",
+            "run_command" => "execute_command",
+            "git_log" => "git_log",
+            "git_commit" => "git_commit",
+            "git_diff" => "git_diff",
+            "git_branch" => match args.get("action").and_then(|v| v.as_str()) {
+                Some("create") => "git_branch",
+                Some("checkout") => "git_checkout",
+                _ => "git_status", // Tier 0, listing branches
+            },
+            "capture_screen" => "read_file", // Tier 0
+            _ => "execute_task",
+        };
+
+        let args = match args {
+            serde_json::Value::Object(map) => map
+                .values()
+                .filter_map(|v| v.as_str().map(|s| s.to_string()))
+                .collect(),
+            _ => vec![],
+        };
+
+        Self::new(name, args, source)
+    }
 }
 
 /// Risk assessor for operation classification
@@ -173,12 +212,13 @@ impl RiskAssessor {
     fn classify_operation(&self, operation_name: &str) -> Result<RiskTier, EngineError> {
         match operation_name {
             // Tier 0: Read-only operations and core agent tasks
-            "read_file" | "list_dir" | "git_status" | "git_log" | "execute_task" => {
+            "read_file" | "list_dir" | "git_status" | "git_log" | "git_diff" | "execute_task" => {
                 Ok(RiskTier::Tier0)
             }
 
             // Tier 1: Write/reversible operations
-            "write_file" | "git_add" | "git_commit" | "create_dir" => Ok(RiskTier::Tier1),
+            "write_file" | "git_add" | "git_commit" | "git_branch" | "git_checkout"
+            | "create_dir" => Ok(RiskTier::Tier1),
 
             // Tier 2: Destructive/irreversible operations
             "delete_file" | "git_push" | "execute_command" | "git_reset" => Ok(RiskTier::Tier2),
@@ -460,6 +500,39 @@ mod tests {
         assert_eq!(op.source, OperationSource::Local);
     }
 
+    #[test]
+    fn test_operation_for_tool_call() {
+        let args = serde_json::json!({"command": "rm -rf build", "timeout": 5});
+        let op = Operation::for_tool_call("run_command", &args, OperationSource::Local);
+        assert_eq!(op.name, "execute_command");
+        assert_eq!(op.args, vec!["rm -rf build".to_string()]);
+
+        let op = Operation::for_tool_call(
+            "summarize",
+            &serde_json::json!(null),
+            OperationSource::Local,
+        );
+        assert_eq!(op.name, "execute_task");
+        assert!(op.args.is_empty());
+
+        let args = serde_json::json!({"path": "build", "recursive": true});
+        let op = Operation::for_tool_call("delete_file", &args, OperationSource::Local);
+        assert_eq!(op.name, "delete_file");
+        let op = Operation::for_tool_call("create_dir", &args, OperationSource::Local);
+        assert_eq!(op.name, "create_dir");
+
+        let args = serde_json::json!({"message": "Fix"});
+        let op = Operation::for_tool_call("git_commit", &args, OperationSource::Local);
+        assert_eq!(op.name, "git_commit");
+
+        let args = serde_json::json!({"action": "checkout", "name": "main"});
+        let op = Operation::for_tool_call("git_branch", &args, OperationSource::Local);
+        assert_eq!(op.name, "git_checkout");
+        let op =
+            Operation::for_tool_call("git_branch", &serde_json::json!({}), OperationSource::Local);
+        assert_eq!(op.name, "git_status");
+    }
+
     #[test]
     fn test_risk_assessor_default() {
         let assessor = RiskAssessor::default();
@@ -0,0 +1,65 @@
+//! Host ABI compatibility between the engine and WASM plugins
+//!
+//! The host functions a plugin can import, and the shape of their input and
+//! output, form the host ABI. It is versioned with `HOST_ABI_VERSION`, which
+//! is bumped on every incompatible change. A plugin declares the version it
+//! was built against in its manifest entry (`host_abi`); the runtime refuses
+//! to load a plugin that targets a newer ABI than the host provides, or one
+//! older than `MIN_SUPPORTED_ABI_VERSION`. Entries without `host_abi` predate
+//! the field and are treated as targeting ABI 1.
+
+use sdk::errors::EngineError;
+
+/// Version of the host ABI this engine provides
+pub const HOST_ABI_VERSION: u32 = 1;
+
+/// Oldest host ABI version this engine can still load plugins for
+pub const MIN_SUPPORTED_ABI_VERSION: u32 = 1;
+
+/// ABI version assumed for manifest entries that don't declare one
+const UNDECLARED_ABI_VERSION: u32 = 1;
+
+/// Check that `plugin`, targeting host ABI `declared`, can be loaded
+///
+/// # Errors
+///
+/// Returns `EngineError::Plugin` naming both versions if the plugin targets
+/// an ABI newer than `HOST_ABI_VERSION` or older than
+/// `MIN_SUPPORTED_ABI_VERSION`.
+pub fn check_compatible(plugin: &str, declared: Option<u32>) -> Result<(), EngineError> {
+    let target = declared.unwrap_or(UNDECLARED_ABI_VERSION);
+    if target > HOST_ABI_VERSION {
+        return Err(EngineError::Plugin(format!(
+            "Plugin '{}' targets host ABI {}, but this engine provides ABI {}. Upgrade rove to load it",
+            plugin, target, HOST_ABI_VERSION
+        )));
+    }
+    if target < MIN_SUPPORTED_ABI_VERSION {
+        return Err(EngineError::Plugin(format!(
+            "Plugin '{}' targets host ABI {}, which is no longer supported (oldest supported: {}). Rebuild it against ABI {}",
+            plugin, target, MIN_SUPPORTED_ABI_VERSION, HOST_ABI_VERSION
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compatible_abi_is_accepted() {
+        assert!(check_compatible("fs-editor", Some(HOST_ABI_VERSION)).is_ok());
+        assert!(check_compatible("fs-editor", None).is_ok());
+    }
+
+    #[test]
+    fn test_newer_abi_is_rejected() {
+        let err = check_compatible("fs-editor", Some(HOST_ABI_VERSION + 1)).unwrap_err();
+
+        assert!(matches!(err, EngineError::Plugin(_)));
+        let message = err.to_string();
+        assert!(message.contains(&format!("targets host ABI {}", HOST_ABI_VERSION + 1)));
+        assert!(message.contains("Upgrade rove"));
+    }
+}
@@ -0,0 +1,97 @@
+//! Clock and randomness sources for plugin host functions
+//!
+//! Plugins read the time through the `now_ms` host function and random
+//! numbers through `random_u64`. Normally these are backed by the system
+//! clock and the OS random number generator. For reproducible plugin tests a
+//! runtime can instead inject a `DeterministicEnv`: every instance then sees
+//! the same fixed time, and each plugin draws from one RNG seeded with the
+//! given value, so two runs of a plugin produce identical output.
+
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::OsRng;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Fixed clock and RNG seed injected into plugin instances
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DeterministicEnv {
+    /// Time reported by `now_ms`, in milliseconds since the Unix epoch
+    pub now_ms: i64,
+    /// Seed for the sequence returned by `random_u64`
+    pub seed: u64,
+}
+
+impl DeterministicEnv {
+    pub fn new(now_ms: i64, seed: u64) -> Self {
+        Self { now_ms, seed }
+    }
+}
+
+/// Current time for a plugin, in milliseconds since the Unix epoch
+pub(crate) fn now_ms(env: Option<&DeterministicEnv>) -> i64 {
+    match env {
+        Some(env) => env.now_ms,
+        None => SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as i64)
+            .unwrap_or_default(),
+    }
+}
+
+/// Random number source of one plugin, shared by its pooled instances
+#[derive(Debug)]
+pub(crate) enum HostRng {
+    /// OS random number generator
+    Os,
+    /// SplitMix64 sequence from a fixed seed
+    Seeded(u64),
+}
+
+impl HostRng {
+    pub(crate) fn new(env: Option<&DeterministicEnv>) -> Self {
+        match env {
+            Some(env) => HostRng::Seeded(env.seed),
+            None => HostRng::Os,
+        }
+    }
+
+    pub(crate) fn next_u64(&mut self) -> u64 {
+        match self {
+            HostRng::Os => OsRng.next_u64(),
+            HostRng::Seeded(state) => {
+                *state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+                let mut z = *state;
+                z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+                z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+                z ^ (z >> 31)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fixed_clock() {
+        let env = DeterministicEnv::new(1_700_000_000_000, 7);
+        assert_eq!(now_ms(Some(&env)), 1_700_000_000_000);
+
+        // The real clock is well past the injected time used in tests
+        assert!(now_ms(None) > 1_700_000_000_000);
+    }
+
+    #[test]
+    fn test_seeded_rng_is_reproducible() {
+        let env = DeterministicEnv::new(0, 42);
+        let mut a = HostRng::new(Some(&env));
+        let mut b = HostRng::new(Some(&env));
+        let first: Vec<u64> = (0..8).map(|_| a.next_u64()).collect();
+        let second: Vec<u64> = (0..8).map(|_| b.next_u64()).collect();
+        assert_eq!(first, second);
+
+        let mut other = HostRng::new(Some(&DeterministicEnv::new(0, 43)));
+        let third: Vec<u64> = (0..8).map(|_| other.next_u64()).collect();
+        assert_ne!(first, third);
+    }
+}
@@ -0,0 +1,250 @@
+//! Host side of the `exec_command` host function
+//!
+//! Plugins such as the terminal plugin run commands through the host rather
+//! than spawning processes themselves. Every call goes through two checks:
+//! the plugin's manifest permissions (`can_execute`, `allowed_commands`,
+//! `denied_flags`) and then the engine's `CommandExecutor`, so the command
+//! allowlist and the shell metacharacter gates apply exactly as they do for
+//! the agent's own `run_command`.
+//!
+//! Commands run through `CommandExecutor::execute_in`: in the root of the
+//! executor's workspace, with none of the daemon's environment beyond
+//! `SAFE_ENV_VARS`. An executor without a workspace refuses every command.
+//!
+//! The input is a command name and a JSON array of arguments. The output is
+//! always `{"stdout", "stderr", "exit_code"}`; a refused command never runs
+//! and comes back with exit code `DENIED_EXIT_CODE` and the reason in
+//! `stderr`. A command that was allowed but timed out or could not be
+//! started reports `TIMEOUT_EXIT_CODE` or `FAILED_EXIT_CODE` instead.
+
+use crate::command_executor::{CommandError, CommandExecutor};
+use sdk::manifest::PluginPermissions;
+use serde::Serialize;
+use std::path::Path;
+
+/// Exit code reported for a command that was refused before running
+///
+/// Matches the shell's "command cannot execute" status.
+pub const DENIED_EXIT_CODE: i32 = 126;
+
+/// Exit code reported for a command killed after running too long
+///
+/// Matches the status of `timeout(1)`; the output written until then is kept.
+pub const TIMEOUT_EXIT_CODE: i32 = 124;
+
+/// Exit code reported for an allowed command that could not be run
+pub const FAILED_EXIT_CODE: i32 = 125;
+
+/// Result of an `exec_command` call, as returned to the plugin
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ExecOutput {
+    pub stdout: String,
+    pub stderr: String,
+    pub exit_code: i32,
+}
+
+impl ExecOutput {
+    fn denied(reason: String) -> Self {
+        Self {
+            stdout: String::new(),
+            stderr: reason,
+            exit_code: DENIED_EXIT_CODE,
+        }
+    }
+}
+
+/// Serve an `exec_command` call of `command` with `args_json` from a plugin
+/// holding `permissions`
+///
+/// Blocking; the `exec_command` host function calls this directly.
+pub fn exec_command(
+    executor: &CommandExecutor,
+    permissions: &PluginPermissions,
+    command: &str,
+    args_json: &str,
+) -> ExecOutput {
+    let args: Vec<String> = if args_json.trim().is_empty() {
+        Vec::new()
+    } else {
+        match serde_json::from_str(args_json) {
+            Ok(args) => args,
+            Err(e) => {
+                return ExecOutput::denied(format!(
+                    "Invalid arguments, expected a JSON array of strings: {}",
+                    e
+                ))
+            }
+        }
+    };
+
+    let command_line = if args.is_empty() {
+        command.to_string()
+    } else {
+        format!("{} {}", command, args.join(" "))
+    };
+    if !permissions.is_command_allowed(&command_line) {
+        tracing::warn!(
+            "Plugin command '{}' refused by its manifest permissions",
+            command_line
+        );
+        return ExecOutput::denied(format!(
+            "Command '{}' is not permitted by the plugin's manifest",
+            command
+        ));
+    }
+
+    match executor.execute_in(command, &args, Path::new("."), &[]) {
+        Ok(output) => ExecOutput {
+            stdout: String::from_utf8_lossy(&output.stdout).into_owned(),
+            stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
+            exit_code: output.status.code().unwrap_or(-1),
+        },
+        Err(CommandError::Timeout { timeout, output }) => {
+            tracing::warn!("Plugin command '{}' timed out", command_line);
+            let mut stderr = String::from_utf8_lossy(&output.stderr).into_owned();
+            if !stderr.is_empty() && !stderr.ends_with('\n') {
+                stderr.push('\n');
+            }
+            stderr.push_str(&format!(
+                "Command timed out after {} seconds",
+                timeout.as_secs()
+            ));
+            ExecOutput {
+                stdout: String::from_utf8_lossy(&output.stdout).into_owned(),
+                stderr,
+                exit_code: TIMEOUT_EXIT_CODE,
+            }
+        }
+        Err(e @ CommandError::ExecutionFailed(_)) => {
+            tracing::warn!("Plugin command '{}' failed: {}", command_line, e);
+            ExecOutput {
+                stdout: String::new(),
+                stderr: e.to_string(),
+                exit_code: FAILED_EXIT_CODE,
+            }
+        }
+        Err(e) => {
+            tracing::warn!("Plugin command '{}' refused: {}", command_line, e);
+            ExecOutput::denied(e.to_string())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command_executor::SAFE_ENV_VARS;
+
+    fn executing() -> PluginPermissions {
+        PluginPermissions {
+            can_execute: true,
+            ..PluginPermissions::default()
+        }
+    }
+
+    /// An executor allowing `echo`, with the system temp dir as workspace
+    fn echo_executor() -> CommandExecutor {
+        CommandExecutor::with_allowlist(vec!["echo".to_string()])
+            .with_workspace(std::env::temp_dir())
+    }
+
+    #[test]
+    fn test_allowed_command_runs() {
+        let executor = echo_executor();
+
+        let output = exec_command(&executor, &executing(), "echo", r#"["hello"]"#);
+
+        assert_eq!(output.exit_code, 0);
+        assert_eq!(output.stdout.trim(), "hello");
+    }
+
+    #[test]
+    fn test_command_outside_allowlist_is_denied() {
+        let executor = echo_executor();
+
+        // Passes the manifest (no denied flag), so the allowlist decides
+        let output = exec_command(&executor, &executing(), "rm", r#"["target"]"#);
+
+        assert_eq!(output.exit_code, DENIED_EXIT_CODE);
+        assert_eq!(output.stderr, "Command not allowed: rm");
+        assert!(output.stdout.is_empty());
+    }
+
+    #[test]
+    fn test_metacharacters_are_denied() {
+        let executor = echo_executor();
+
+        let output = exec_command(&executor, &executing(), "echo", r#"["hi; rm target"]"#);
+
+        assert_eq!(output.exit_code, DENIED_EXIT_CODE);
+        assert!(output.stderr.contains("metacharacters"));
+    }
+
+    #[test]
+    fn test_denied_flags_are_refused_before_the_executor() {
+        let executor = CommandExecutor::with_allowlist(vec!["rm".to_string()])
+            .with_workspace(std::env::temp_dir());
+
+        let output = exec_command(&executor, &executing(), "rm", r#"["-rf", "target"]"#);
+
+        assert_eq!(output.exit_code, DENIED_EXIT_CODE);
+        assert_eq!(
+            output.stderr,
+            "Command 'rm' is not permitted by the plugin's manifest"
+        );
+    }
+
+    #[test]
+    fn test_manifest_permissions_are_enforced() {
+        let executor = echo_executor();
+
+        let output = exec_command(&executor, &PluginPermissions::default(), "echo", "[]");
+        assert_eq!(output.exit_code, DENIED_EXIT_CODE);
+        assert!(output
+            .stderr
+            .contains("not permitted by the plugin's manifest"));
+
+        let output = exec_command(&executor, &executing(), "echo", r#"{"not": "a list"}"#);
+        assert_eq!(output.exit_code, DENIED_EXIT_CODE);
+        assert!(output.stderr.starts_with("Invalid arguments"));
+    }
+
+    #[test]
+    fn test_daemon_environment_is_not_visible() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let executor = CommandExecutor::with_allowlist(vec!["printenv".to_string()])
+            .with_workspace(dir.path().to_path_buf());
+        std::env::set_var("ROVE_TEST_HOST_EXEC_SECRET", "hunter2");
+
+        let output = exec_command(&executor, &executing(), "printenv", "[]");
+
+        assert_eq!(output.exit_code, 0);
+        assert!(!output.stdout.contains("ROVE_TEST_HOST_EXEC_SECRET"));
+        for line in output.stdout.lines() {
+            let name = line.split_once('=').map_or(line, |(name, _)| name);
+            assert!(SAFE_ENV_VARS.contains(&name), "inherited {}", name);
+        }
+    }
+
+    #[test]
+    fn test_executor_without_workspace_refuses() {
+        let executor = CommandExecutor::with_allowlist(vec!["echo".to_string()]);
+
+        let output = exec_command(&executor, &executing(), "echo", r#"["hello"]"#);
+
+        assert_eq!(output.exit_code, DENIED_EXIT_CODE);
+        assert!(output.stdout.is_empty());
+    }
+
+    #[test]
+    fn test_failure_to_run_is_not_reported_as_denied() {
+        // Allowlisted, but not installed
+        let executor = CommandExecutor::with_allowlist(vec!["rove-missing-tool".to_string()])
+            .with_workspace(std::env::temp_dir());
+
+        let output = exec_command(&executor, &executing(), "rove-missing-tool", "[]");
+
+        assert_eq!(output.exit_code, FAILED_EXIT_CODE);
+        assert!(output.stderr.starts_with("Command execution failed"));
+    }
+}
@@ -0,0 +1,275 @@
+//! Host side of the `find_files` host function
+//!
+//! Lets a plugin search a directory tree with a glob instead of guessing
+//! paths, e.g. `{"root": "logs", "pattern": "**/*.log"}`. The pattern is
+//! matched against each file's path relative to `root`, one path segment at
+//! a time: `*` and `?` stay within a segment and `**` matches any number of
+//! segments, including none.
+//!
+//! Every directory and file the walk meets is validated by the
+//! FileSystemGuard; denied ones are skipped silently, and a denied directory
+//! is not descended into. The root and every file returned must also be
+//! allowed by the plugin's manifest (`allowed_paths`, `denied_paths`). Ignore files are not applied, since logs are
+//! usually ignored. At most `max_results` (default `DEFAULT_MAX_RESULTS`)
+//! files are returned, in path order.
+//!
+//! The output is `{"files": [{"path", "size", "modified"}], "truncated"}`
+//! with paths relative to the workspace and `modified` in seconds since the
+//! Unix epoch, or `{"error", "code"}`.
+
+use super::host_read;
+use crate::fs_guard::FileSystemGuard;
+use crate::tools::filesystem::wildcard_match;
+use sdk::manifest::PluginPermissions;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::path::Path;
+use std::time::UNIX_EPOCH;
+
+/// Files returned when the plugin doesn't set `max_results`
+pub const DEFAULT_MAX_RESULTS: usize = 500;
+
+/// A `find_files` call from a plugin
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct FindRequest {
+    /// Directory to search, relative to the workspace or absolute
+    #[serde(default = "default_root")]
+    pub root: String,
+
+    /// Glob matched against paths relative to `root`
+    pub pattern: String,
+
+    /// Most files to return (default `DEFAULT_MAX_RESULTS`)
+    #[serde(default)]
+    pub max_results: Option<usize>,
+}
+
+fn default_root() -> String {
+    ".".to_string()
+}
+
+/// A file matched by `find_files`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FoundFile {
+    /// Path relative to the workspace
+    pub path: String,
+
+    /// Size in bytes
+    pub size: u64,
+
+    /// Last modification, in seconds since the Unix epoch
+    pub modified: u64,
+}
+
+/// Whether `path` (relative to the search root) matches the glob `pattern`
+pub fn glob_match(pattern: &str, path: &Path) -> bool {
+    let pattern: Vec<&str> = pattern.split('/').filter(|s| !s.is_empty()).collect();
+    let segments: Vec<String> = path
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy().into_owned())
+        .collect();
+    // memo[p][s]: whether pattern[p..] matches segments[s..], so patterns
+    // with many `**` stay polynomial
+    let mut memo = vec![vec![None; segments.len() + 1]; pattern.len() + 1];
+    match_segments(&pattern, &segments, 0, 0, &mut memo)
+}
+
+fn match_segments(
+    pattern: &[&str],
+    segments: &[String],
+    p: usize,
+    s: usize,
+    memo: &mut [Vec<Option<bool>>],
+) -> bool {
+    if let Some(matched) = memo[p][s] {
+        return matched;
+    }
+    let matched = match pattern.get(p) {
+        None => s == segments.len(),
+        Some(&"**") => {
+            (s..=segments.len()).any(|skip| match_segments(pattern, segments, p + 1, skip, memo))
+        }
+        Some(first) => {
+            s < segments.len()
+                && wildcard_match(first.as_bytes(), segments[s].as_bytes())
+                && match_segments(pattern, segments, p + 1, s + 1, memo)
+        }
+    };
+    memo[p][s] = Some(matched);
+    matched
+}
+
+/// Serve a `find_files` call with `input` from a plugin holding `permissions`
+///
+/// Blocking; the `find_files` host function calls this directly.
+pub fn find_files(guard: &FileSystemGuard, permissions: &PluginPermissions, input: &str) -> Value {
+    let request: FindRequest = match serde_json::from_str(input) {
+        Ok(request) => request,
+        Err(e) => {
+            return json!({ "error": format!("Invalid request: {}", e), "code": "invalid_request" })
+        }
+    };
+    let root = match host_read::resolve_plugin_path(guard, permissions, &request.root) {
+        Ok(root) if root.is_dir() => root,
+        Ok(root) => {
+            return json!({
+                "error": format!("{} is not a directory", root.display()),
+                "code": "invalid_root",
+            })
+        }
+        Err(e) => return json!({ "error": e, "code": "invalid_root" }),
+    };
+    let limit = request.max_results.unwrap_or(DEFAULT_MAX_RESULTS);
+
+    let entry_guard = guard.clone();
+    let walker = ignore::WalkBuilder::new(&root)
+        .standard_filters(false)
+        .sort_by_file_name(|a, b| a.cmp(b))
+        .filter_entry(move |entry| {
+            entry.depth() == 0 || entry_guard.validate_path(entry.path()).is_ok()
+        })
+        .build();
+
+    let mut files = Vec::new();
+    let mut truncated = false;
+    for entry in walker.filter_map(|entry| entry.ok()) {
+        if !entry.file_type().is_some_and(|ft| ft.is_file()) {
+            continue;
+        }
+        let Ok(relative) = entry.path().strip_prefix(&root) else {
+            continue;
+        };
+        if !glob_match(&request.pattern, relative)
+            || !host_read::is_permitted(guard, permissions, entry.path())
+        {
+            continue;
+        }
+        if files.len() == limit {
+            truncated = true;
+            break;
+        }
+        let Ok(metadata) = entry.metadata() else {
+            continue;
+        };
+        let modified = metadata
+            .modified()
+            .ok()
+            .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+            .map_or(0, |since| since.as_secs());
+        let path = entry
+            .path()
+            .strip_prefix(guard.workspace())
+            .unwrap_or(entry.path());
+        files.push(FoundFile {
+            path: path.to_string_lossy().into_owned(),
+            size: metadata.len(),
+            modified,
+        });
+    }
+
+    json!({ "files": files, "truncated": truncated })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runtime::host_read::tests::workspace_with;
+
+    /// Find with the default manifest permissions (the whole workspace)
+    fn find(guard: &FileSystemGuard, input: &str) -> Value {
+        find_files(guard, &PluginPermissions::default(), input)
+    }
+
+    fn paths(result: &Value) -> Vec<&str> {
+        result["files"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|file| file["path"].as_str().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_glob_segments() {
+        assert!(glob_match("**/*.log", Path::new("app.log")));
+        assert!(glob_match("**/*.log", Path::new("var/log/app.log")));
+        assert!(glob_match("var/**/app.log", Path::new("var/app.log")));
+        assert!(glob_match("*/app.log", Path::new("var/app.log")));
+        assert!(!glob_match("*.log", Path::new("var/app.log")));
+        assert!(!glob_match("*/app.log", Path::new("var/log/app.log")));
+        assert!(!glob_match("**/*.log", Path::new("app.log.1")));
+    }
+
+    #[test]
+    fn test_many_double_stars_do_not_backtrack_exponentially() {
+        let pattern = "**/".repeat(30) + "*.log";
+        let path: std::path::PathBuf = std::iter::repeat("dir").take(40).collect();
+
+        assert!(!glob_match(&pattern, &path.join("app.txt")));
+        assert!(glob_match(&pattern, &path.join("app.log")));
+    }
+
+    #[test]
+    fn test_finds_matches_under_root_and_skips_denied() {
+        let (_dir, guard) = workspace_with(&[
+            ("logs/app.log", "x"),
+            ("logs/old/app.1.log", "x"),
+            ("logs/notes.txt", "x"),
+            ("logs/.ssh/keys.log", "x"),
+            ("other.log", "x"),
+        ]);
+
+        let result = find(&guard, r#"{"root": "logs", "pattern": "**/*.log"}"#);
+
+        assert_eq!(paths(&result), vec!["logs/app.log", "logs/old/app.1.log"]);
+        assert_eq!(result["truncated"], false);
+        assert_eq!(result["files"][0]["size"], 1);
+        assert!(result["files"][0]["modified"].as_u64().unwrap() > 0);
+    }
+
+    #[test]
+    fn test_results_are_capped() {
+        let (_dir, guard) = workspace_with(&[("a.log", "x"), ("b.log", "x"), ("c.log", "x")]);
+
+        let result = find(&guard, r#"{"pattern": "*.log", "max_results": 2}"#);
+        assert_eq!(paths(&result), vec!["a.log", "b.log"]);
+        assert_eq!(result["truncated"], true);
+
+        let result = find(&guard, r#"{"pattern": "*.log", "max_results": 3}"#);
+        assert_eq!(paths(&result).len(), 3);
+        assert_eq!(result["truncated"], false);
+    }
+
+    #[test]
+    fn test_manifest_paths_are_enforced() {
+        let (_dir, guard) = workspace_with(&[
+            ("logs/app.log", "x"),
+            ("logs/secrets/keys.log", "x"),
+            ("other.log", "x"),
+        ]);
+        let permissions = PluginPermissions {
+            allowed_paths: vec!["logs".to_string()],
+            denied_paths: vec!["secrets".to_string()],
+            ..PluginPermissions::default()
+        };
+
+        let result = find_files(
+            &guard,
+            &permissions,
+            r#"{"root": "logs", "pattern": "**/*.log"}"#,
+        );
+        assert_eq!(paths(&result), vec!["logs/app.log"]);
+
+        let result = find_files(&guard, &permissions, r#"{"pattern": "**/*.log"}"#);
+        assert_eq!(result["code"], "invalid_root");
+    }
+
+    #[test]
+    fn test_root_outside_workspace_is_refused() {
+        let (_dir, guard) = workspace_with(&[("a.log", "x")]);
+
+        let result = find(&guard, r#"{"root": "..", "pattern": "*"}"#);
+
+        assert_eq!(result["code"], "invalid_root");
+    }
+}
@@ -0,0 +1,103 @@
+//! Deployment-wide host function kill-switch
+//!
+//! `security.disabled_host_functions` turns individual host functions off
+//! for every plugin, whatever its manifest permissions allow, e.g. to keep
+//! file writes but rule out `exec_git` entirely. A disabled function is
+//! still registered (a plugin importing it must still instantiate), but
+//! every call fails with `EngineError::FeatureDisabled` before the host
+//! does any work.
+
+use sdk::errors::EngineError;
+use std::collections::BTreeSet;
+
+/// Names of the host functions the WASM runtime provides
+pub const HOST_FUNCTIONS: &[&str] = &[
+    "read_file",
+    "write_file",
+    "list_directory",
+    "find_files",
+    "exec_git",
+    "exec_command",
+    "now_ms",
+    "random_u64",
+    "capture_window",
+    "ocr",
+    "call_plugin",
+    "cache_get",
+    "cache_set",
+];
+
+/// Set of host functions disabled for this deployment
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HostFunctionPolicy {
+    disabled: BTreeSet<String>,
+}
+
+impl HostFunctionPolicy {
+    /// Disable the host functions named in `disabled`
+    pub fn new<I, S>(disabled: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self {
+            disabled: disabled.into_iter().map(Into::into).collect(),
+        }
+    }
+
+    /// Whether `function` is disabled
+    pub fn is_disabled(&self, function: &str) -> bool {
+        self.disabled.contains(function)
+    }
+
+    /// Check that `plugin` may invoke `function`
+    ///
+    /// # Errors
+    ///
+    /// Returns `EngineError::FeatureDisabled` if the function is
+    /// disabled. Plugin permissions are not consulted.
+    pub fn check(&self, plugin: &str, function: &str) -> Result<(), EngineError> {
+        if self.is_disabled(function) {
+            tracing::warn!(
+                "Plugin '{}' called host function '{}', which is disabled by policy",
+                plugin,
+                function
+            );
+            return Err(EngineError::FeatureDisabled(format!(
+                "Host function '{}'",
+                function
+            )));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_disabled_function_is_refused() {
+        let policy = HostFunctionPolicy::new(["exec_git"]);
+
+        let result = policy.check("git", "exec_git");
+
+        assert!(matches!(
+            result,
+            Err(EngineError::FeatureDisabled(ref name)) if name == "Host function 'exec_git'"
+        ));
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "Host function 'exec_git' is disabled by configuration"
+        );
+        assert!(policy.check("git", "read_file").is_ok());
+    }
+
+    #[test]
+    fn test_default_policy_allows_everything() {
+        let policy = HostFunctionPolicy::default();
+        for function in HOST_FUNCTIONS {
+            assert!(policy.check("fs-editor", function).is_ok());
+        }
+    }
+}
@@ -0,0 +1,355 @@
+//! Host side of the `read_file` host function
+//!
+//! Plugins receive file contents as a string, so the host has to turn the
+//! file's bytes into UTF-8. It never does so silently: a file that is not
+//! valid UTF-8 is detected as binary and returned base64-encoded with
+//! `"encoding": "base64"`. A plugin that wants text anyway can opt in to a
+//! lossy read, which replaces invalid sequences with U+FFFD and flags the
+//! result with `"lossy": true`, so the plugin knows the content is not the
+//! file's. With `"binary": false` such a file is refused instead, with a
+//! structured error naming the offset of the first invalid byte.
+//!
+//! At most `max_bytes` (default `DEFAULT_MAX_BYTES`, never more than the
+//! guard's read limit) are read. A longer file is cut short, at a character
+//! boundary for text, and flagged with `"truncated": true`.
+//!
+//! The input is either a bare path or `{"path": "...", "lossy": true,
+//! "binary": true, "max_bytes": 1048576}`. The output is `{"content",
+//! "encoding", "lossy", "truncated"}` or `{"error", "code"}`; paths are
+//! validated by the FileSystemGuard and then against the `allowed_paths` and
+//! `denied_paths` of the plugin's manifest, relative to the workspace.
+
+use crate::fs_guard::FileSystemGuard;
+use base64::Engine as _;
+use sdk::manifest::PluginPermissions;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+/// Bytes read when the plugin doesn't set `max_bytes` (1 MiB)
+pub const DEFAULT_MAX_BYTES: u64 = 1024 * 1024;
+
+/// A `read_file` call from a plugin
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct ReadRequest {
+    /// File to read, relative to the workspace or absolute
+    pub path: String,
+
+    /// Replace invalid UTF-8 instead of failing
+    #[serde(default)]
+    pub lossy: bool,
+
+    /// Return a file that is not valid UTF-8 base64-encoded instead of
+    /// failing (the default)
+    #[serde(default = "default_binary")]
+    pub binary: bool,
+
+    /// Most bytes to read (default `DEFAULT_MAX_BYTES`)
+    #[serde(default)]
+    pub max_bytes: Option<u64>,
+}
+
+impl ReadRequest {
+    /// Parse the plugin's input: a JSON request, or a bare path
+    pub fn parse(input: &str) -> Self {
+        serde_json::from_str(input).unwrap_or_else(|_| Self {
+            path: input.to_string(),
+            lossy: false,
+            binary: default_binary(),
+            max_bytes: None,
+        })
+    }
+}
+
+fn default_binary() -> bool {
+    true
+}
+
+/// File contents decoded for a plugin
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Decoded {
+    /// Text handed to the plugin
+    pub content: String,
+
+    /// Whether invalid sequences were replaced
+    pub lossy: bool,
+}
+
+/// Decode `bytes` as UTF-8, replacing invalid sequences only if `lossy`
+///
+/// # Errors
+///
+/// Returns the `Utf8Error` of a strict decode of invalid bytes.
+pub fn decode(bytes: Vec<u8>, lossy: bool) -> Result<Decoded, std::str::Utf8Error> {
+    match String::from_utf8(bytes) {
+        Ok(content) => Ok(Decoded {
+            content,
+            lossy: false,
+        }),
+        Err(e) if lossy => Ok(Decoded {
+            content: String::from_utf8_lossy(e.as_bytes()).into_owned(),
+            lossy: true,
+        }),
+        Err(e) => Err(e.utf8_error()),
+    }
+}
+
+/// Read at most `limit` bytes of `path`, and whether there was more
+fn read_prefix(path: &Path, limit: u64) -> std::io::Result<(Vec<u8>, bool)> {
+    let mut bytes = Vec::new();
+    std::fs::File::open(path)?
+        .take(limit.saturating_add(1))
+        .read_to_end(&mut bytes)?;
+    let truncated = bytes.len() as u64 > limit;
+    bytes.truncate(limit as usize);
+    Ok((bytes, truncated))
+}
+
+/// Length of `bytes` without a multi-byte character a truncation cut in half
+fn without_partial_char(bytes: &[u8]) -> usize {
+    match std::str::from_utf8(bytes) {
+        // `error_len` is `None` only for an incomplete sequence at the end
+        Err(e) if e.error_len().is_none() => e.valid_up_to(),
+        _ => bytes.len(),
+    }
+}
+
+/// Validate `path`, as given by a plugin holding `permissions`
+///
+/// Relative paths are taken from the workspace. The path must pass the
+/// FileSystemGuard and the plugin's manifest permissions.
+pub fn resolve_plugin_path(
+    guard: &FileSystemGuard,
+    permissions: &PluginPermissions,
+    path: &str,
+) -> Result<PathBuf, String> {
+    let target = Path::new(path);
+    let abs = if target.is_absolute() {
+        target.to_path_buf()
+    } else {
+        guard.workspace().join(target)
+    };
+    let resolved = guard.validate_path(&abs).map_err(|e| e.to_string())?;
+    if !is_permitted(guard, permissions, &resolved) {
+        return Err(format!(
+            "{} is not permitted by the plugin's manifest",
+            path
+        ));
+    }
+    Ok(resolved)
+}
+
+/// Whether the guard-validated `path` is allowed by `permissions`
+pub(crate) fn is_permitted(
+    guard: &FileSystemGuard,
+    permissions: &PluginPermissions,
+    path: &Path,
+) -> bool {
+    let relative = path.strip_prefix(guard.workspace()).unwrap_or(path);
+    permissions.is_path_allowed(&relative.to_string_lossy())
+}
+
+/// Serve a `read_file` call with `input` from a plugin holding `permissions`
+///
+/// Blocking; the `read_file` host function calls this directly.
+pub fn read_file(guard: &FileSystemGuard, permissions: &PluginPermissions, input: &str) -> Value {
+    let request = ReadRequest::parse(input);
+    let path = match resolve_plugin_path(guard, permissions, &request.path) {
+        Ok(path) => path,
+        Err(e) => return json!({ "error": e, "code": "read_failed" }),
+    };
+    let limit = request
+        .max_bytes
+        .unwrap_or(DEFAULT_MAX_BYTES)
+        .min(guard.max_read_size());
+
+    let (mut bytes, truncated) = match read_prefix(&path, limit) {
+        Ok(read) => read,
+        Err(e) => return json!({ "error": e.to_string(), "code": "read_failed" }),
+    };
+    let text_len = if truncated {
+        without_partial_char(&bytes)
+    } else {
+        bytes.len()
+    };
+
+    // Binary content is returned whole; only text is cut at a character
+    if request.binary && !request.lossy && std::str::from_utf8(&bytes[..text_len]).is_err() {
+        return json!({
+            "content": base64::engine::general_purpose::STANDARD.encode(bytes),
+            "encoding": "base64",
+            "lossy": false,
+            "truncated": truncated,
+        });
+    }
+
+    bytes.truncate(text_len);
+    match decode(bytes, request.lossy) {
+        Ok(decoded) => {
+            if decoded.lossy {
+                tracing::warn!(
+                    "Invalid UTF-8 in {} replaced for a lossy plugin read",
+                    request.path
+                );
+            }
+            json!({
+                "content": decoded.content,
+                "encoding": "utf-8",
+                "lossy": decoded.lossy,
+                "truncated": truncated,
+            })
+        }
+        Err(e) => json!({
+            "error": format!(
+                "{} is not valid UTF-8 (invalid byte at offset {}). Read it with \"lossy\": true to replace invalid bytes, or drop \"binary\": false to get base64",
+                request.path,
+                e.valid_up_to()
+            ),
+            "code": "invalid_utf8",
+            "valid_up_to": e.valid_up_to(),
+        }),
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    /// Workspace holding `files` as (path, contents), and a guard for it
+    pub(crate) fn workspace_with<C: AsRef<[u8]>>(
+        files: &[(&str, C)],
+    ) -> (TempDir, FileSystemGuard) {
+        let dir = TempDir::new().unwrap();
+        for (name, contents) in files {
+            let path = dir.path().join(name);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, contents).unwrap();
+        }
+        let guard = FileSystemGuard::new(dir.path().canonicalize().unwrap());
+        (dir, guard)
+    }
+
+    /// Read with the default manifest permissions (the whole workspace)
+    fn read(guard: &FileSystemGuard, input: &str) -> Value {
+        read_file(guard, &PluginPermissions::default(), input)
+    }
+
+    #[test]
+    fn test_valid_utf8_is_returned_unchanged() {
+        let (_dir, guard) = workspace_with(&[("notes.txt", "héllo ✓".as_bytes())]);
+
+        for input in ["notes.txt", r#"{"path": "notes.txt", "lossy": true}"#] {
+            assert_eq!(
+                read(&guard, input),
+                json!({ "content": "héllo ✓", "encoding": "utf-8", "lossy": false, "truncated": false })
+            );
+        }
+    }
+
+    #[test]
+    fn test_invalid_utf8_is_refused_without_binary() {
+        let (_dir, guard) = workspace_with(&[("data.bin", b"abc\xff\xfedef")]);
+
+        let result = read(&guard, r#"{"path": "data.bin", "binary": false}"#);
+
+        assert_eq!(result["code"], "invalid_utf8");
+        assert_eq!(result["valid_up_to"], 3);
+        assert!(result.get("content").is_none());
+    }
+
+    #[test]
+    fn test_lossy_read_replaces_and_flags_invalid_utf8() {
+        let (_dir, guard) = workspace_with(&[("data.bin", b"abc\xffdef")]);
+
+        let result = read(&guard, r#"{"path": "data.bin", "lossy": true}"#);
+
+        assert_eq!(
+            result,
+            json!({ "content": "abc\u{FFFD}def", "encoding": "utf-8", "lossy": true, "truncated": false })
+        );
+    }
+
+    #[test]
+    fn test_paths_are_validated() {
+        let (_dir, guard) = workspace_with(&[("notes.txt", b"hi")]);
+
+        let result = read(&guard, "../outside.txt");
+
+        assert_eq!(result["code"], "read_failed");
+    }
+
+    #[test]
+    fn test_manifest_paths_are_enforced() {
+        let (_dir, guard) = workspace_with(&[
+            ("notes.txt", "hi"),
+            ("logs/app.log", "started"),
+            ("logs/secrets.log", "hunter2"),
+        ]);
+        let permissions = PluginPermissions {
+            allowed_paths: vec!["logs".to_string()],
+            denied_paths: vec!["secrets".to_string()],
+            ..PluginPermissions::default()
+        };
+
+        let result = read_file(&guard, &permissions, "logs/app.log");
+        assert_eq!(result["content"], "started");
+
+        for path in ["notes.txt", "logs/secrets.log"] {
+            let result = read_file(&guard, &permissions, path);
+            assert_eq!(result["code"], "read_failed");
+            assert!(result["error"]
+                .as_str()
+                .unwrap()
+                .contains("not permitted by the plugin's manifest"));
+        }
+    }
+
+    #[test]
+    fn test_large_file_is_truncated_at_max_bytes() {
+        let (_dir, guard) = workspace_with(&[("log.txt", "ab✓cd".as_bytes())]);
+
+        // 4 bytes would split the three-byte ✓
+        let result = read(&guard, r#"{"path": "log.txt", "max_bytes": 4}"#);
+        assert_eq!(result["content"], "ab");
+        assert_eq!(result["truncated"], true);
+
+        let result = read(&guard, r#"{"path": "log.txt", "max_bytes": 7}"#);
+        assert_eq!(result["content"], "ab✓cd");
+        assert_eq!(result["truncated"], false);
+
+        let big = "x".repeat(DEFAULT_MAX_BYTES as usize + 10);
+        let (_dir, guard) = workspace_with(&[("big.txt", big.as_bytes())]);
+        let result = read(&guard, "big.txt");
+        assert_eq!(
+            result["content"].as_str().unwrap().len() as u64,
+            DEFAULT_MAX_BYTES
+        );
+        assert_eq!(result["truncated"], true);
+    }
+
+    #[test]
+    fn test_binary_file_is_detected_and_returned_as_base64() {
+        let (_dir, guard) = workspace_with(&[("image.png", b"\x89PNG\r\n\x1a\n")]);
+
+        for input in ["image.png", r#"{"path": "image.png", "binary": true}"#] {
+            let result = read(&guard, input);
+            assert_eq!(result["encoding"], "base64");
+            assert_eq!(result["content"], "iVBORw0KGgo=");
+            assert_eq!(result["truncated"], false);
+        }
+
+        // A truncated binary file keeps all `max_bytes` bytes
+        let result = read(&guard, r#"{"path": "image.png", "max_bytes": 3}"#);
+        assert_eq!(result["encoding"], "base64");
+        assert_eq!(result["content"], "iVBO");
+        assert_eq!(result["truncated"], true);
+
+        // Text is still returned as text
+        let (_dir, guard) = workspace_with(&[("notes.txt", b"hi")]);
+        let result = read(&guard, "notes.txt");
+        assert_eq!(result["encoding"], "utf-8");
+        assert_eq!(result["content"], "hi");
+    }
+}
@@ -0,0 +1,281 @@
+//! Plugin download installer
+//!
+//! Downloads plugin WASM blobs with bounded retry and HTTP range-resume. Data
+//! is streamed to `<dest>.part`; when a transfer drops, the next attempt asks
+//! the server only for the missing bytes with a `Range` request. The `.part`
+//! file is promoted to `dest` only after its SHA-256 hash matches the expected
+//! value, so a partial or corrupted blob is never loaded as a plugin.
+//!
+//! `update` additionally compares the permissions of the installed version
+//! with those of the new one. If the new version asks for more (e.g. network
+//! access), nothing is downloaded until the user consents again, so the old
+//! version stays in place.
+
+use crate::crypto::CryptoModule;
+use futures::StreamExt;
+use reqwest::header::{CONTENT_RANGE, RANGE};
+use reqwest::StatusCode;
+use sdk::errors::EngineError;
+use sdk::manifest::{PluginEntry, PluginPermissions};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tokio::io::AsyncWriteExt;
+use tracing::{info, warn};
+
+/// Default number of retries after the first download attempt
+pub const DEFAULT_MAX_RETRIES: u32 = 3;
+
+/// Default delay before the first retry, doubled on every further retry
+pub const DEFAULT_INITIAL_BACKOFF: Duration = Duration::from_millis(500);
+
+/// Longest delay between two retries, however many retries came before
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+
+/// Result of `Installer::update`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UpdateOutcome {
+    /// The new version was downloaded and replaced the old one
+    Updated,
+
+    /// The new version asks for more permissions and the user did not
+    /// consent; the installed version was left as it was
+    ConsentRequired {
+        /// Description of each permission the new version adds
+        added: Vec<String>,
+    },
+}
+
+/// Downloads plugin blobs into place with retry, resume, and hash verification
+pub struct Installer {
+    client: reqwest::Client,
+    max_retries: u32,
+    initial_backoff: Duration,
+}
+
+impl Installer {
+    /// Create an installer with the default retry policy
+    pub fn new() -> Self {
+        let client = reqwest::Client::builder()
+            .user_agent(format!("rove/{}", env!("CARGO_PKG_VERSION")))
+            .build()
+            .unwrap_or_default();
+
+        Self {
+            client,
+            max_retries: DEFAULT_MAX_RETRIES,
+            initial_backoff: DEFAULT_INITIAL_BACKOFF,
+        }
+    }
+
+    /// Set how many times a failed download is retried
+    pub fn with_max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// Set the delay before the first retry
+    pub fn with_initial_backoff(mut self, backoff: Duration) -> Self {
+        self.initial_backoff = backoff;
+        self
+    }
+
+    /// Download `url` to `dest` and verify it against `expected_hash`
+    ///
+    /// `expected_hash` is SHA-256 hex, optionally prefixed with `sha256:`. An
+    /// existing `.part` file from an earlier interrupted run is resumed.
+    ///
+    /// # Errors
+    ///
+    /// Returns `EngineError::Network` once all retries are exhausted and
+    /// `EngineError::HashMismatch` if the completed download does not match
+    /// the expected hash. On a mismatch the `.part` file is deleted.
+    pub async fn download(
+        &self,
+        url: &str,
+        dest: &Path,
+        expected_hash: &str,
+    ) -> Result<(), EngineError> {
+        let part = part_path(dest);
+        let mut attempt = 0;
+
+        loop {
+            match self.fetch(url, &part).await {
+                Ok(()) => break,
+                Err(e) if attempt < self.max_retries => {
+                    let delay = self
+                        .initial_backoff
+                        .saturating_mul(2u32.saturating_pow(attempt))
+                        .min(MAX_BACKOFF);
+                    attempt += 1;
+                    warn!(
+                        "Download of {} failed (retry {}/{} in {:?}): {}",
+                        url, attempt, self.max_retries, delay, e
+                    );
+                    tokio::time::sleep(delay).await;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+
+        let bytes = tokio::fs::read(&part).await?;
+        let computed = CryptoModule::compute_hash(&bytes);
+        let expected = expected_hash
+            .strip_prefix("sha256:")
+            .unwrap_or(expected_hash);
+
+        if !computed.eq_ignore_ascii_case(expected) {
+            tracing::error!(
+                "Hash mismatch for {}: expected {}, got {}",
+                url,
+                expected,
+                computed
+            );
+            // A corrupt partial file cannot be resumed; start fresh next time
+            tokio::fs::remove_file(&part).await?;
+            return Err(EngineError::HashMismatch(dest.display().to_string()));
+        }
+
+        tokio::fs::rename(&part, dest).await?;
+        info!("Downloaded and verified {} to {}", url, dest.display());
+        Ok(())
+    }
+
+    /// Update an installed plugin to the version described by `entry`
+    ///
+    /// `installed` are the permissions the current version was granted. When
+    /// `entry` requests more, `consent` is called with a description of each
+    /// added permission; unless it returns `true` nothing is downloaded and
+    /// `UpdateOutcome::ConsentRequired` is returned. Updates that keep or
+    /// narrow the permissions never ask.
+    ///
+    /// # Errors
+    ///
+    /// Same as `download`. A failed update leaves the installed version in
+    /// place.
+    pub async fn update<F>(
+        &self,
+        url: &str,
+        dest: &Path,
+        installed: &PluginPermissions,
+        entry: &PluginEntry,
+        consent: F,
+    ) -> Result<UpdateOutcome, EngineError>
+    where
+        F: FnOnce(&[String]) -> bool,
+    {
+        let added = installed.expansions(&entry.permissions);
+        if !added.is_empty() {
+            warn!(
+                "Plugin '{}' {} requests new permissions: {}",
+                entry.name,
+                entry.version,
+                added.join(", ")
+            );
+            if !consent(&added) {
+                info!(
+                    "Update of plugin '{}' to {} awaits consent; keeping the installed version",
+                    entry.name, entry.version
+                );
+                return Ok(UpdateOutcome::ConsentRequired { added });
+            }
+        }
+
+        self.download(url, dest, &entry.hash).await?;
+        info!("Updated plugin '{}' to {}", entry.name, entry.version);
+        Ok(UpdateOutcome::Updated)
+    }
+
+    /// Run one download attempt, appending to `part` when the server resumes
+    async fn fetch(&self, url: &str, part: &Path) -> Result<(), EngineError> {
+        let offset = match tokio::fs::metadata(part).await {
+            Ok(metadata) => metadata.len(),
+            Err(_) => 0,
+        };
+
+        let mut request = self.client.get(url);
+        if offset > 0 {
+            request = request.header(RANGE, format!("bytes={}-", offset));
+        }
+
+        let response = request
+            .send()
+            .await
+            .map_err(|e| EngineError::Network(format!("Failed to download {}: {}", url, e)))?;
+
+        let append = match response.status() {
+            StatusCode::PARTIAL_CONTENT => {
+                let start = response
+                    .headers()
+                    .get(CONTENT_RANGE)
+                    .and_then(|value| value.to_str().ok())
+                    .and_then(content_range_start);
+                if start != Some(offset) {
+                    // Appending a different range would corrupt the blob;
+                    // start over from scratch on the next attempt
+                    tokio::fs::remove_file(part).await.ok();
+                    return Err(EngineError::Network(format!(
+                        "Failed to download {}: server resumed at {:?} instead of byte {}",
+                        url, start, offset
+                    )));
+                }
+                true
+            }
+            // The `.part` file already holds the whole blob
+            StatusCode::RANGE_NOT_SATISFIABLE if offset > 0 => return Ok(()),
+            // The server ignored the range and sent everything again
+            status if status.is_success() => false,
+            status => {
+                return Err(EngineError::Network(format!(
+                    "Failed to download {}: HTTP {}",
+                    url, status
+                )))
+            }
+        };
+
+        let mut file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .write(true)
+            .append(append)
+            .truncate(!append)
+            .open(part)
+            .await?;
+
+        let mut stream = response.bytes_stream();
+        let result = loop {
+            match stream.next().await {
+                Some(Ok(chunk)) => file.write_all(&chunk).await?,
+                Some(Err(e)) => {
+                    break Err(EngineError::Network(format!(
+                        "Download of {} interrupted: {}",
+                        url, e
+                    )))
+                }
+                None => break Ok(()),
+            }
+        };
+
+        // Keep whatever arrived so the next attempt can resume from it
+        file.flush().await?;
+        result
+    }
+}
+
+impl Default for Installer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// First byte of a `Content-Range: bytes <start>-<end>/<total>` value
+fn content_range_start(value: &str) -> Option<u64> {
+    let range = value.trim().strip_prefix("bytes ")?;
+    let (start, _) = range.split_once('-')?;
+    start.trim().parse().ok()
+}
+
+/// Path of the in-progress download for `dest` (`<dest>.part`)
+pub fn part_path(dest: &Path) -> PathBuf {
+    let mut name = dest.as_os_str().to_os_string();
+    name.push(".part");
+    PathBuf::from(name)
+}
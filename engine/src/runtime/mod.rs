@@ -3,9 +3,37 @@
 //! This module provides two runtime implementations:
 //! - NativeRuntime: Loads core tools as native shared libraries with four-gate verification
 //! - WasmRuntime: Loads plugins as WASM modules with two-gate verification
+//!
+//! WASM plugin instances are pooled per plugin (see `pool`) so concurrent calls
+//! never share an instance. Plugin output is checked against the schemas
+//! declared in the manifest (see `schema`). Plugin blobs are downloaded with
+//! retry, resume, and hash verification by the `installer`. Plugin time and
+//! randomness can be pinned for reproducible tests (see `determinism`).
+//! Plugins can call each other through the host (see `plugin_call`).
+//! Host functions can be switched off per deployment (see `host_policy`).
+//! Plugins can memoize results for the rest of a task (see `task_cache`).
+//! File reads for plugins return files that are not UTF-8 as base64 (see `host_read`).
+//! Plugins run commands through the engine's CommandExecutor (see `host_exec`).
+//! Plugins targeting an incompatible host ABI are refused (see `abi`).
 
+pub mod abi;
+pub mod determinism;
+pub mod host_exec;
+pub mod host_find;
+pub mod host_policy;
+pub mod host_read;
+pub mod installer;
 pub mod native;
+pub mod plugin_call;
+pub mod pool;
+pub mod schema;
+pub mod task_cache;
 pub mod wasm;
 
+pub use abi::HOST_ABI_VERSION;
+pub use determinism::DeterministicEnv;
+pub use host_policy::HostFunctionPolicy;
+pub use installer::{Installer, UpdateOutcome};
 pub use native::NativeRuntime;
-pub use wasm::WasmRuntime;
+pub use pool::{InstancePool, DEFAULT_POOL_SIZE};
+pub use wasm::{MeteredOutput, PluginHandle, WasmRuntime, DEFAULT_FUEL_LIMIT};
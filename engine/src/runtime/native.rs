@@ -39,7 +39,7 @@
 //! // Create CoreContext with handles
 //! let ctx = CoreContext::new(
 //!     // ... handles ...
-//! #   todo!(), todo!(), todo!(), todo!(), todo!(), todo!()
+//! #   todo!(), todo!(), todo!(), todo!(), todo!(), todo!(), todo!()
 //! );
 //!
 //! // Load a core tool with four-gate verification
@@ -0,0 +1,200 @@
+//! Plugin-to-plugin calls
+//!
+//! A plugin can reuse another plugin's capability through the `call_plugin`
+//! host function instead of duplicating it, e.g. the git plugin reading a
+//! file through fs-read. The call is routed back through the runtime and
+//! checked as if the agent had made it:
+//!
+//! - The target plugin must be loaded
+//! - A plugin without `can_execute` may not reach one that has it
+//! - The call is risk-assessed like an agent tool call, with the source of
+//!   the task that made it, so calls made for a remote task are escalated
+//!   the same way. Tier 2 calls are refused, since nobody can confirm them
+//!   from inside a plugin.
+//!
+//! Each nested call enters one level of the conductor's call depth (see
+//! `conductor::call_depth`), so plugins calling each other in a loop fail
+//! once `conductor.max_call_depth` is reached instead of recursing forever.
+
+use crate::risk_assessor::{Operation, OperationSource, RiskAssessor, RiskTier};
+use sdk::errors::EngineError;
+use sdk::manifest::PluginPermissions;
+use std::future::Future;
+
+tokio::task_local! {
+    static SOURCE: OperationSource;
+}
+
+/// Run `task` with `source` as the origin of the plugin calls it makes
+pub async fn with_source<F: Future>(source: OperationSource, task: F) -> F::Output {
+    SOURCE.scope(source, task).await
+}
+
+/// Origin of the task currently running
+///
+/// Outside a task the origin is unknown, so calls are assessed as remote.
+pub fn current_source() -> OperationSource {
+    SOURCE
+        .try_with(|source| source.clone())
+        .unwrap_or(OperationSource::Remote)
+}
+
+/// Check whether `caller` may call `function` on `target` for a task from
+/// `source`
+///
+/// Returns the risk tier of the call if it is allowed.
+///
+/// # Errors
+///
+/// Returns `EngineError::Plugin` if the call would give the caller command
+/// execution it doesn't have, or if the call is Tier 2.
+pub fn authorize(
+    caller: &str,
+    caller_permissions: &PluginPermissions,
+    target: &str,
+    target_permissions: &PluginPermissions,
+    function: &str,
+    input: &serde_json::Value,
+    source: OperationSource,
+) -> Result<RiskTier, EngineError> {
+    if target_permissions.can_execute && !caller_permissions.can_execute {
+        tracing::warn!(
+            "Plugin '{}' denied call to '{}': target can execute commands",
+            caller,
+            target
+        );
+        return Err(EngineError::Plugin(format!(
+            "Plugin '{}' may not call '{}', which can execute commands",
+            caller, target
+        )));
+    }
+
+    let mut operation = Operation::for_tool_call(function, input, source);
+    if target_permissions.can_execute {
+        operation.name = "execute_command".to_string();
+    }
+    let tier = RiskAssessor::new().assess(&operation)?;
+
+    if tier == RiskTier::Tier2 {
+        tracing::warn!(
+            "Plugin '{}' denied Tier 2 call to {}.{}",
+            caller,
+            target,
+            function
+        );
+        return Err(EngineError::Plugin(format!(
+            "Call to {}.{} is Tier 2 and needs explicit confirmation",
+            target, function
+        )));
+    }
+
+    tracing::info!(
+        "Plugin '{}' calling {}.{} ({:?})",
+        caller,
+        target,
+        function,
+        tier
+    );
+    Ok(tier)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_authorize_checks_permissions_and_risk() {
+        let reader = PluginPermissions::default();
+        let executor = PluginPermissions {
+            can_execute: true,
+            ..PluginPermissions::default()
+        };
+        let local = || OperationSource::Local;
+
+        let tier = authorize(
+            "git",
+            &reader,
+            "fs-read",
+            &reader,
+            "read_file",
+            &json!({"path": "src/main.rs"}),
+            local(),
+        )
+        .unwrap();
+        assert_eq!(tier, RiskTier::Tier0);
+
+        let tier = authorize(
+            "git",
+            &reader,
+            "fs-editor",
+            &reader,
+            "write_file",
+            &json!({"path": "notes.md"}),
+            local(),
+        )
+        .unwrap();
+        assert_eq!(tier, RiskTier::Tier1);
+
+        // No command execution through a plugin that has it
+        assert!(authorize(
+            "git",
+            &reader,
+            "terminal",
+            &executor,
+            "run",
+            &json!({}),
+            local()
+        )
+        .is_err());
+
+        // Dangerous flags make the call Tier 2
+        assert!(authorize(
+            "git",
+            &reader,
+            "fs-editor",
+            &reader,
+            "write_file",
+            &json!({"path": "--force"}),
+            local(),
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_remote_calls_are_escalated() {
+        let reader = PluginPermissions::default();
+        let write = json!({"path": "notes.md"});
+
+        let tier = authorize(
+            "git",
+            &reader,
+            "fs-read",
+            &reader,
+            "read_file",
+            &json!({"path": "src/main.rs"}),
+            OperationSource::Remote,
+        )
+        .unwrap();
+        assert_eq!(tier, RiskTier::Tier1);
+
+        // A write is Tier 1 locally but Tier 2 for a remote task
+        assert!(authorize(
+            "git",
+            &reader,
+            "fs-editor",
+            &reader,
+            "write_file",
+            &write,
+            OperationSource::Remote,
+        )
+        .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_source_follows_the_task() {
+        assert_eq!(current_source(), OperationSource::Remote);
+        let source = with_source(OperationSource::Local, async { current_source() }).await;
+        assert_eq!(source, OperationSource::Local);
+    }
+}
@@ -0,0 +1,348 @@
+//! Per-plugin instance pooling
+//!
+//! Extism plugin instances own mutable linear memory, so a single instance must
+//! never serve two calls at the same time. The `InstancePool` keeps a small set
+//! of instances for one plugin and hands out exactly one instance per call:
+//!
+//! - Idle instances are reused first
+//! - New instances are created on demand, up to the configured cap
+//! - Callers beyond the cap wait until an instance is returned
+//!
+//! Instances are returned to the pool automatically when the `PooledInstance`
+//! guard is dropped. An instance that crashed can be discarded instead, so the
+//! next checkout gets a freshly created one.
+
+use sdk::errors::EngineError;
+use std::sync::Mutex;
+use tokio::sync::{Semaphore, SemaphorePermit, TryAcquireError};
+
+/// Default number of instances kept per plugin
+pub const DEFAULT_POOL_SIZE: usize = 4;
+
+/// Factory used to create new instances on demand
+type InstanceFactory<T> = Box<dyn Fn() -> Result<T, EngineError> + Send + Sync>;
+
+/// Bounded pool of instances for a single plugin
+///
+/// The pool is generic over the instance type so the checkout logic can be
+/// exercised without a real WASM module. The WasmRuntime uses it with
+/// `extism::Plugin`.
+pub struct InstancePool<T> {
+    /// Plugin name, used in error messages and logs
+    name: String,
+    /// Creates a new instance when no idle one is available
+    factory: InstanceFactory<T>,
+    /// Instances not currently checked out
+    idle: Mutex<Vec<T>>,
+    /// One permit per instance that may exist at the same time
+    permits: Semaphore,
+    /// Maximum number of instances
+    max_size: usize,
+}
+
+impl<T> InstancePool<T> {
+    /// Create a new pool that builds instances with `factory`
+    ///
+    /// No instances are created up front; the first checkout creates the first
+    /// instance. A `max_size` of 0 is treated as 1.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Name of the plugin this pool serves
+    /// * `max_size` - Maximum number of instances that may exist at once
+    /// * `factory` - Closure that creates a new instance
+    pub fn new<F>(name: impl Into<String>, max_size: usize, factory: F) -> Self
+    where
+        F: Fn() -> Result<T, EngineError> + Send + Sync + 'static,
+    {
+        let max_size = max_size.max(1);
+        Self {
+            name: name.into(),
+            factory: Box::new(factory),
+            idle: Mutex::new(Vec::with_capacity(max_size)),
+            permits: Semaphore::new(max_size),
+            max_size,
+        }
+    }
+
+    /// Check out an instance for exclusive use
+    ///
+    /// Reuses an idle instance if one is available, otherwise creates a new one.
+    /// If `max_size` instances are already checked out, waits until one is
+    /// returned.
+    ///
+    /// # Errors
+    ///
+    /// Returns `EngineError::Plugin` if the pool has been closed, or any error
+    /// returned by the instance factory.
+    pub async fn checkout(&self) -> Result<PooledInstance<'_, T>, EngineError> {
+        let permit = self
+            .permits
+            .acquire()
+            .await
+            .map_err(|_| self.closed_error())?;
+        self.instance_for(permit)
+    }
+
+    /// Check out an instance only if one is available right away
+    ///
+    /// Returns `Ok(None)` instead of waiting when `max_size` instances are
+    /// already checked out. Used for nested plugin calls, where the caller
+    /// blocks its thread and waiting could deadlock.
+    ///
+    /// # Errors
+    ///
+    /// Same as `checkout`.
+    pub fn try_checkout(&self) -> Result<Option<PooledInstance<'_, T>>, EngineError> {
+        match self.permits.try_acquire() {
+            Ok(permit) => self.instance_for(permit).map(Some),
+            Err(TryAcquireError::NoPermits) => Ok(None),
+            Err(TryAcquireError::Closed) => Err(self.closed_error()),
+        }
+    }
+
+    /// Take an idle instance, or create one, for an acquired permit
+    fn instance_for<'a>(
+        &'a self,
+        permit: SemaphorePermit<'a>,
+    ) -> Result<PooledInstance<'a, T>, EngineError> {
+        let idle = self.idle.lock().expect("idle pool lock poisoned").pop();
+        let instance = match idle {
+            Some(instance) => instance,
+            None => {
+                tracing::debug!("Creating new instance for plugin '{}'", self.name);
+                (self.factory)()?
+            }
+        };
+
+        Ok(PooledInstance {
+            pool: self,
+            instance: Some(instance),
+            _permit: permit,
+        })
+    }
+
+    fn closed_error(&self) -> EngineError {
+        EngineError::Plugin(format!("Instance pool for '{}' is closed", self.name))
+    }
+
+    /// Close the pool and drop all idle instances
+    ///
+    /// Pending and future checkouts fail. Instances that are currently checked
+    /// out are dropped when their guard is released.
+    pub fn close(&self) {
+        self.permits.close();
+        self.idle.lock().expect("idle pool lock poisoned").clear();
+    }
+
+    /// Maximum number of instances this pool will create
+    pub fn max_size(&self) -> usize {
+        self.max_size
+    }
+
+    /// Number of instances currently idle in the pool
+    pub fn idle_count(&self) -> usize {
+        self.idle.lock().expect("idle pool lock poisoned").len()
+    }
+
+    /// Number of instances that can be checked out without waiting
+    pub fn available(&self) -> usize {
+        self.permits.available_permits()
+    }
+}
+
+/// An instance checked out from an `InstancePool`
+///
+/// Dereferences to the underlying instance. The instance goes back to the pool
+/// when the guard is dropped, unless it was discarded.
+pub struct PooledInstance<'a, T> {
+    pool: &'a InstancePool<T>,
+    instance: Option<T>,
+    // Held for the lifetime of the checkout; released after the instance is returned
+    _permit: SemaphorePermit<'a>,
+}
+
+impl<T> PooledInstance<'_, T> {
+    /// Drop this instance instead of returning it to the pool
+    ///
+    /// Used after a crash so a possibly corrupted instance is never reused.
+    pub fn discard(mut self) {
+        self.instance = None;
+    }
+}
+
+impl<T> std::ops::Deref for PooledInstance<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.instance
+            .as_ref()
+            .expect("pooled instance accessed after discard")
+    }
+}
+
+impl<T> std::ops::DerefMut for PooledInstance<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        self.instance
+            .as_mut()
+            .expect("pooled instance accessed after discard")
+    }
+}
+
+impl<T> Drop for PooledInstance<'_, T> {
+    fn drop(&mut self) {
+        if let Some(instance) = self.instance.take() {
+            if !self.pool.permits.is_closed() {
+                self.pool
+                    .idle
+                    .lock()
+                    .expect("idle pool lock poisoned")
+                    .push(instance);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    fn counting_pool(max_size: usize) -> (Arc<InstancePool<usize>>, Arc<AtomicUsize>) {
+        let created = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&created);
+        let pool = InstancePool::new("test-plugin", max_size, move || {
+            Ok(counter.fetch_add(1, Ordering::SeqCst))
+        });
+        (Arc::new(pool), created)
+    }
+
+    #[tokio::test]
+    async fn test_checkout_reuses_idle_instance() {
+        let (pool, created) = counting_pool(2);
+
+        {
+            let instance = pool.checkout().await.unwrap();
+            assert_eq!(*instance, 0);
+        }
+        assert_eq!(pool.idle_count(), 1);
+
+        let instance = pool.checkout().await.unwrap();
+        assert_eq!(*instance, 0);
+        assert_eq!(created.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_checkouts_get_distinct_instances() {
+        let (pool, created) = counting_pool(2);
+
+        let a = pool.checkout().await.unwrap();
+        let b = pool.checkout().await.unwrap();
+        assert_ne!(*a, *b);
+        assert_eq!(created.load(Ordering::SeqCst), 2);
+        assert_eq!(pool.available(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_checkout_waits_beyond_cap() {
+        let (pool, created) = counting_pool(1);
+
+        let held = pool.checkout().await.unwrap();
+
+        let waiter = {
+            let pool = Arc::clone(&pool);
+            tokio::spawn(async move {
+                let instance = pool.checkout().await.unwrap();
+                *instance
+            })
+        };
+
+        // The second checkout must not complete while the only instance is held
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(!waiter.is_finished());
+
+        drop(held);
+        let value = waiter.await.unwrap();
+        assert_eq!(value, 0);
+        assert_eq!(created.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_discard_creates_fresh_instance() {
+        let (pool, created) = counting_pool(1);
+
+        let instance = pool.checkout().await.unwrap();
+        instance.discard();
+        assert_eq!(pool.idle_count(), 0);
+
+        let instance = pool.checkout().await.unwrap();
+        assert_eq!(*instance, 1);
+        assert_eq!(created.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_factory_error_releases_slot() {
+        let pool = InstancePool::<usize>::new("broken", 1, || {
+            Err(EngineError::Plugin("boom".to_string()))
+        });
+
+        assert!(pool.checkout().await.is_err());
+        assert_eq!(pool.available(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_closed_pool_rejects_checkout() {
+        let (pool, _) = counting_pool(1);
+        pool.close();
+
+        let result = pool.checkout().await;
+        assert!(matches!(result, Err(EngineError::Plugin(_))));
+    }
+
+    #[test]
+    fn test_try_checkout_does_not_wait() {
+        let (pool, _) = counting_pool(1);
+
+        let held = pool.try_checkout().unwrap().expect("instance available");
+        assert!(pool.try_checkout().unwrap().is_none());
+
+        drop(held);
+        assert!(pool.try_checkout().unwrap().is_some());
+
+        pool.close();
+        assert!(pool.try_checkout().is_err());
+    }
+
+    #[test]
+    fn test_zero_size_treated_as_one() {
+        let (pool, _) = counting_pool(0);
+        assert_eq!(pool.max_size(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_many_concurrent_calls_do_not_share_instances() {
+        let (pool, _) = counting_pool(3);
+        let in_use = Arc::new(Mutex::new(std::collections::HashSet::new()));
+
+        let mut handles = Vec::new();
+        for _ in 0..16 {
+            let pool = Arc::clone(&pool);
+            let in_use = Arc::clone(&in_use);
+            handles.push(tokio::spawn(async move {
+                let instance = pool.checkout().await.unwrap();
+                let id = *instance;
+                // No other task may hold this instance while we do
+                assert!(in_use.lock().unwrap().insert(id));
+                tokio::time::sleep(Duration::from_millis(5)).await;
+                assert!(in_use.lock().unwrap().remove(&id));
+            }));
+        }
+
+        for handle in handles {
+            handle.await.unwrap();
+        }
+        assert!(pool.idle_count() <= 3);
+    }
+}
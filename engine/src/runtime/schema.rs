@@ -0,0 +1,261 @@
+//! Output schema validation for plugin responses
+//!
+//! Plugins may declare an output schema per function in the manifest
+//! (`output_schemas`). After a successful call, the runtime checks the plugin's
+//! JSON output against that schema so a malformed response surfaces as a
+//! descriptive `EngineError::ToolError` naming the offending field, instead of a
+//! raw deserialize error further down the line.
+//!
+//! Only a small subset of JSON Schema is supported:
+//!
+//! - `type`: `object`, `array`, `string`, `number`, `integer`, `boolean`, `null`,
+//!   or a list of those
+//! - `required`: field names that must be present in an object
+//! - `properties`: schemas for object fields (unlisted fields are allowed)
+//! - `items`: schema applied to every array element
+//!
+//! Unknown keywords are ignored.
+
+use sdk::errors::EngineError;
+use serde_json::Value;
+
+/// Validate raw plugin output against a declared output schema
+///
+/// # Errors
+///
+/// Returns `EngineError::ToolError` if the output is not valid JSON or does not
+/// match the schema. The message names the plugin, the function, and the path
+/// of the offending field.
+pub fn validate_output(
+    plugin: &str,
+    function: &str,
+    schema: &Value,
+    output: &[u8],
+) -> Result<(), EngineError> {
+    let value: Value = serde_json::from_slice(output).map_err(|e| {
+        EngineError::ToolError(format!(
+            "Plugin '{}' returned invalid JSON from '{}': {}",
+            plugin, function, e
+        ))
+    })?;
+
+    check(schema, &value, "").map_err(|reason| {
+        EngineError::ToolError(format!(
+            "Plugin '{}' returned output from '{}' that does not match its schema: {}",
+            plugin, function, reason
+        ))
+    })
+}
+
+/// Recursively check `value` against `schema`; `path` is the field path so far
+fn check(schema: &Value, value: &Value, path: &str) -> Result<(), String> {
+    if let Some(expected) = schema.get("type") {
+        let allowed: Vec<&str> = match expected {
+            Value::String(name) => vec![name.as_str()],
+            Value::Array(names) => names.iter().filter_map(Value::as_str).collect(),
+            _ => Vec::new(),
+        };
+
+        if !allowed.is_empty() && !allowed.iter().any(|name| matches_type(name, value)) {
+            return Err(format!(
+                "{} expected {}, got {}",
+                describe(path),
+                allowed.join(" or "),
+                type_name(value)
+            ));
+        }
+    }
+
+    if let Value::Object(fields) = value {
+        if let Some(required) = schema.get("required").and_then(Value::as_array) {
+            for name in required.iter().filter_map(Value::as_str) {
+                if !fields.contains_key(name) {
+                    return Err(format!("missing required field '{}'", join(path, name)));
+                }
+            }
+        }
+
+        if let Some(properties) = schema.get("properties").and_then(Value::as_object) {
+            for (name, field_schema) in properties {
+                if let Some(field) = fields.get(name) {
+                    check(field_schema, field, &join(path, name))?;
+                }
+            }
+        }
+    }
+
+    if let (Value::Array(elements), Some(item_schema)) = (value, schema.get("items")) {
+        for (index, element) in elements.iter().enumerate() {
+            check(item_schema, element, &format!("{}[{}]", path, index))?;
+        }
+    }
+
+    Ok(())
+}
+
+fn matches_type(name: &str, value: &Value) -> bool {
+    match name {
+        "object" => value.is_object(),
+        "array" => value.is_array(),
+        "string" => value.is_string(),
+        "number" => value.is_number(),
+        "integer" => value.is_i64() || value.is_u64(),
+        "boolean" => value.is_boolean(),
+        "null" => value.is_null(),
+        // Unknown type names never reject output
+        _ => true,
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+fn join(path: &str, field: &str) -> String {
+    if path.is_empty() {
+        field.to_string()
+    } else {
+        format!("{}.{}", path, field)
+    }
+}
+
+fn describe(path: &str) -> String {
+    if path.is_empty() {
+        "output".to_string()
+    } else {
+        format!("field '{}'", path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn read_file_schema() -> Value {
+        json!({
+            "type": "object",
+            "required": ["content", "size"],
+            "properties": {
+                "content": { "type": "string" },
+                "size": { "type": "integer" },
+                "lines": {
+                    "type": "array",
+                    "items": {
+                        "type": "object",
+                        "required": ["number"],
+                        "properties": { "number": { "type": "integer" } }
+                    }
+                }
+            }
+        })
+    }
+
+    fn error_message(result: Result<(), EngineError>) -> String {
+        match result {
+            Err(EngineError::ToolError(msg)) => msg,
+            other => panic!("Expected ToolError, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_valid_output_passes() {
+        let output = json!({
+            "content": "hello",
+            "size": 5,
+            "lines": [{ "number": 1 }],
+            "extra": true
+        });
+
+        let result = validate_output(
+            "fs-editor",
+            "read_file",
+            &read_file_schema(),
+            output.to_string().as_bytes(),
+        );
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_missing_required_field_is_named() {
+        let output = json!({ "content": "hello" });
+
+        let msg = error_message(validate_output(
+            "fs-editor",
+            "read_file",
+            &read_file_schema(),
+            output.to_string().as_bytes(),
+        ));
+        assert!(msg.contains("fs-editor"));
+        assert!(msg.contains("read_file"));
+        assert!(msg.contains("missing required field 'size'"));
+    }
+
+    #[test]
+    fn test_wrong_field_type_is_named() {
+        let output = json!({ "content": 42, "size": 5 });
+
+        let msg = error_message(validate_output(
+            "fs-editor",
+            "read_file",
+            &read_file_schema(),
+            output.to_string().as_bytes(),
+        ));
+        assert!(msg.contains("field 'content' expected string, got number"));
+    }
+
+    #[test]
+    fn test_nested_array_field_path() {
+        let output = json!({
+            "content": "a\nb",
+            "size": 3,
+            "lines": [{ "number": 1 }, { "number": "two" }]
+        });
+
+        let msg = error_message(validate_output(
+            "fs-editor",
+            "read_file",
+            &read_file_schema(),
+            output.to_string().as_bytes(),
+        ));
+        assert!(msg.contains("field 'lines[1].number' expected integer, got string"));
+    }
+
+    #[test]
+    fn test_wrong_root_type() {
+        let msg = error_message(validate_output(
+            "fs-editor",
+            "read_file",
+            &read_file_schema(),
+            b"[1, 2, 3]",
+        ));
+        assert!(msg.contains("output expected object, got array"));
+    }
+
+    #[test]
+    fn test_non_json_output() {
+        let msg = error_message(validate_output(
+            "fs-editor",
+            "read_file",
+            &read_file_schema(),
+            b"not json",
+        ));
+        assert!(msg.contains("invalid JSON"));
+    }
+
+    #[test]
+    fn test_type_list_and_unknown_keywords() {
+        let schema = json!({ "type": ["string", "null"], "format": "path" });
+
+        assert!(validate_output("p", "f", &schema, b"null").is_ok());
+        assert!(validate_output("p", "f", &schema, br#""a.txt""#).is_ok());
+        assert!(validate_output("p", "f", &schema, b"1").is_err());
+    }
+}
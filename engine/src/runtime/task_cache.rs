@@ -0,0 +1,166 @@
+//! Per-task cache shared by plugins
+//!
+//! The `cache_get` and `cache_set` host functions let plugins memoize
+//! expensive results (e.g. a directory scan) across calls within one task.
+//! The cache lives in a tokio task-local installed by `scope` around the
+//! agent's task, so it is shared by every plugin the task calls and dropped
+//! when the task ends. Like the call depth (see `conductor::call_depth`), it
+//! follows the future and is seen by host functions running while a plugin
+//! is polled. Outside a task there is no cache: gets miss and sets are
+//! refused.
+
+use sdk::errors::EngineError;
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Maximum number of entries one task may cache
+pub const MAX_ENTRIES: usize = 1024;
+
+tokio::task_local! {
+    static CACHE: Arc<TaskCache>;
+}
+
+/// Cached values of one task
+#[derive(Debug, Default)]
+pub struct TaskCache {
+    entries: Mutex<HashMap<String, Entry>>,
+}
+
+#[derive(Debug)]
+struct Entry {
+    value: String,
+    expires_at: Option<Instant>,
+}
+
+impl Entry {
+    fn is_expired(&self, now: Instant) -> bool {
+        self.expires_at.is_some_and(|expires_at| now >= expires_at)
+    }
+}
+
+impl TaskCache {
+    /// Value stored under `key`, unless it has expired
+    pub fn get(&self, key: &str) -> Option<String> {
+        let mut entries = self.entries.lock().expect("task cache lock poisoned");
+        match entries.get(key) {
+            Some(entry) if entry.is_expired(Instant::now()) => {
+                entries.remove(key);
+                None
+            }
+            Some(entry) => Some(entry.value.clone()),
+            None => None,
+        }
+    }
+
+    /// Store `value` under `key`, for `ttl` or until the task ends
+    ///
+    /// # Errors
+    ///
+    /// Returns `EngineError::Plugin` if the cache already holds
+    /// `MAX_ENTRIES` live entries and `key` is not one of them.
+    pub fn set(&self, key: &str, value: String, ttl: Option<Duration>) -> Result<(), EngineError> {
+        let now = Instant::now();
+        let mut entries = self.entries.lock().expect("task cache lock poisoned");
+        if entries.len() >= MAX_ENTRIES && !entries.contains_key(key) {
+            entries.retain(|_, entry| !entry.is_expired(now));
+            if entries.len() >= MAX_ENTRIES {
+                return Err(EngineError::Plugin(format!(
+                    "Task cache is full ({} entries)",
+                    MAX_ENTRIES
+                )));
+            }
+        }
+        entries.insert(
+            key.to_string(),
+            Entry {
+                value,
+                expires_at: ttl.map(|ttl| now + ttl),
+            },
+        );
+        Ok(())
+    }
+}
+
+/// Run `task` with a fresh cache, dropped when it completes
+pub async fn scope<F: Future>(task: F) -> F::Output {
+    CACHE.scope(Arc::new(TaskCache::default()), task).await
+}
+
+/// Look up `key` in the cache of the current task
+///
+/// Returns `None` on a miss, or when not running inside a task.
+pub fn get(key: &str) -> Option<String> {
+    CACHE.try_with(|cache| cache.get(key)).ok().flatten()
+}
+
+/// Store `value` under `key` in the cache of the current task
+///
+/// A `ttl` of `None` keeps the value until the task ends.
+///
+/// # Errors
+///
+/// Returns `EngineError::Plugin` when not running inside a task or if the
+/// cache is full.
+pub fn set(key: &str, value: String, ttl: Option<Duration>) -> Result<(), EngineError> {
+    CACHE
+        .try_with(|cache| cache.set(key, value, ttl))
+        .map_err(|_| EngineError::Plugin("No task cache outside a task".to_string()))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_value_set_in_one_call_is_seen_by_the_next() {
+        // Host functions run synchronously inside the plugin call's poll
+        let first_call = || set("scan:src", r#"["main.rs"]"#.to_string(), None);
+        let second_call = || get("scan:src");
+
+        let cached = scope(async {
+            first_call().unwrap();
+            tokio::task::yield_now().await;
+            second_call()
+        })
+        .await;
+
+        assert_eq!(cached.as_deref(), Some(r#"["main.rs"]"#));
+    }
+
+    #[tokio::test]
+    async fn test_cache_is_scoped_to_the_task() {
+        scope(async { set("key", "value".to_string(), None).unwrap() }).await;
+
+        assert_eq!(scope(async { get("key") }).await, None);
+        assert_eq!(get("key"), None);
+        assert!(set("key", "value".to_string(), None).is_err());
+    }
+
+    #[test]
+    fn test_entries_expire_after_ttl() {
+        let cache = TaskCache::default();
+        cache
+            .set("short", "1".to_string(), Some(Duration::ZERO))
+            .unwrap();
+        cache
+            .set("long", "2".to_string(), Some(Duration::from_secs(60)))
+            .unwrap();
+
+        assert_eq!(cache.get("short"), None);
+        assert_eq!(cache.get("long").as_deref(), Some("2"));
+    }
+
+    #[test]
+    fn test_full_cache_refuses_new_keys() {
+        let cache = TaskCache::default();
+        for i in 0..MAX_ENTRIES {
+            cache.set(&i.to_string(), String::new(), None).unwrap();
+        }
+
+        assert!(cache.set("one-more", String::new(), None).is_err());
+        // Existing keys can still be updated
+        assert!(cache.set("0", "updated".to_string(), None).is_ok());
+    }
+}
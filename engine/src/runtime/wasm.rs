@@ -52,24 +52,202 @@
 //! # }
 //! ```
 
+use super::abi;
+use super::determinism::{self, DeterministicEnv, HostRng};
+use super::host_exec;
+use super::host_find;
+use super::host_policy::HostFunctionPolicy;
+use super::host_read;
+use super::plugin_call;
+use super::pool::{InstancePool, PooledInstance, DEFAULT_POOL_SIZE};
+use super::schema::validate_output;
+use super::task_cache;
+use crate::command_executor::CommandExecutor;
+use crate::conductor::call_depth::{self, DEFAULT_MAX_CALL_DEPTH};
 use crate::crypto::CryptoModule;
+use crate::egress::EgressPolicy;
 use crate::fs_guard::FileSystemGuard;
 use crate::message_bus::{Event, MessageBus};
-use extism::{Function, Manifest as ExtismManifest, Plugin, UserData, Wasm};
-use sdk::{errors::EngineError, manifest::Manifest};
+use crate::tools::{ocr, vision};
+use extism::{
+    CurrentPlugin, Function, Manifest as ExtismManifest, Plugin, PluginBuilder, UserData, Val,
+    ValType, Wasm,
+};
+use sdk::errors::EngineError;
+use sdk::manifest::{Manifest, PluginEntry, PluginPermissions};
 use std::collections::HashMap;
-use std::path::PathBuf;
-use std::sync::Arc;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, RwLock, Weak};
+use std::time::Duration;
 
 /// Maximum number of crash restarts allowed per plugin before giving up
 const MAX_CRASH_RESTARTS: u32 = 3;
 
-/// Metadata about a loaded plugin
-struct PluginMetadata {
-    /// The Extism plugin instance
-    plugin: Plugin,
-    /// Number of times this plugin has crashed and been restarted
-    crash_count: u32,
+/// Fuel (roughly, WASM instructions) one plugin call may consume by default
+///
+/// High enough that only a runaway plugin hits it; metering is always on so
+/// every call's consumption can be reported.
+pub const DEFAULT_FUEL_LIMIT: u64 = 1_000_000_000_000;
+
+/// Optional export a plugin can provide to verify itself; called by
+/// `WasmRuntime::check_plugin` with empty input, any error fails the check
+pub const SELF_TEST_FUNCTION: &str = "self_test";
+
+/// Output of a plugin call with the fuel it consumed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MeteredOutput {
+    /// Bytes returned by the plugin function
+    pub output: Vec<u8>,
+    /// Fuel consumed by the call (`None` if the runtime did not report it)
+    pub fuel_consumed: Option<u64>,
+}
+
+/// Loaded plugins indexed by name, shared with the `call_plugin` host function
+type PluginTable = Arc<RwLock<HashMap<String, PluginHandle>>>;
+
+/// Shareable handle to a loaded plugin
+///
+/// A handle holds the plugin's instance pool and crash counter, so it can be
+/// cloned out of the runtime and used without keeping the `WasmRuntime` locked.
+/// Every call checks out its own Extism instance, which makes concurrent calls
+/// to the same plugin safe.
+#[derive(Clone)]
+pub struct PluginHandle {
+    /// Name of the plugin
+    name: String,
+    /// Pool of Extism instances for this plugin
+    pool: Arc<InstancePool<Plugin>>,
+    /// Number of times this plugin has crashed since it was loaded
+    crash_count: Arc<AtomicU32>,
+    /// Message bus for publishing crash events (optional)
+    message_bus: Option<Arc<MessageBus>>,
+    /// Declared output schemas keyed by function name
+    output_schemas: Arc<HashMap<String, serde_json::Value>>,
+    /// Permissions declared in the manifest
+    permissions: Arc<PluginPermissions>,
+}
+
+/// The plugin on whose behalf a `call_plugin` host function runs
+#[derive(Clone)]
+struct PluginCaller {
+    name: String,
+    permissions: PluginPermissions,
+    /// Weak so the plugin table is not kept alive by its own instances
+    plugins: Weak<RwLock<HashMap<String, PluginHandle>>>,
+    max_call_depth: usize,
+}
+
+impl PluginCaller {
+    /// Call `function` on the loaded plugin `target` from a host function
+    ///
+    /// Runs the nested call to completion on the current thread, which is
+    /// blocked in the caller's host function anyway.
+    fn call(&self, target: &str, function: &str, input: &str) -> Result<String, EngineError> {
+        let handle = self
+            .plugins
+            .upgrade()
+            .and_then(|plugins| {
+                plugins
+                    .read()
+                    .expect("plugin table lock poisoned")
+                    .get(target)
+                    .cloned()
+            })
+            .ok_or_else(|| EngineError::PluginNotLoaded(target.to_string()))?;
+
+        let args = serde_json::from_str(input).unwrap_or(serde_json::Value::Null);
+        plugin_call::authorize(
+            &self.name,
+            &self.permissions,
+            target,
+            &handle.permissions,
+            function,
+            &args,
+            plugin_call::current_source(),
+        )?;
+
+        let call = handle.call_checked(function, input.as_bytes(), false);
+        let result = call_depth::block_on(call_depth::nested(self.max_call_depth, call))??;
+        Ok(String::from_utf8_lossy(&result.output).into_owned())
+    }
+}
+
+/// Hosts a plugin's HTTP requests may go to
+///
+/// Empty unless the plugin has the `network` permission, which Extism treats
+/// as no network access at all. With the permission, the egress allowlist
+/// applies, or every host while it is unrestricted.
+fn allowed_hosts(permissions: &PluginPermissions, egress: &EgressPolicy) -> Vec<String> {
+    if !permissions.network {
+        return Vec::new();
+    }
+    match egress.allowed_hosts() {
+        Some(hosts) => hosts.map(str::to_string).collect(),
+        None => vec!["*".to_string()],
+    }
+}
+
+/// Build the host function `name` with `build`, unless `policy` disables it
+///
+/// A disabled function is replaced by `disabled_host_function` with the same
+/// signature, so plugins importing it still instantiate.
+fn host_function(
+    policy: &Arc<HostFunctionPolicy>,
+    plugin: &str,
+    name: &str,
+    params: &[ValType],
+    results: &[ValType],
+    build: impl FnOnce(&str, Vec<ValType>, Vec<ValType>) -> Function,
+) -> Function {
+    if policy.is_disabled(name) {
+        tracing::info!(
+            "Host function '{}' disabled by policy for plugin '{}'",
+            name,
+            plugin
+        );
+        return disabled_host_function(policy, plugin, name, params.to_vec(), results.to_vec());
+    }
+    build(name, params.to_vec(), results.to_vec())
+}
+
+/// Stand-in for a host function disabled by policy
+///
+/// Every call is refused by `HostFunctionPolicy::check`: the error is
+/// returned as `{"error"}` to functions that produce output, and raised as a
+/// call error otherwise.
+fn disabled_host_function(
+    policy: &Arc<HostFunctionPolicy>,
+    plugin: &str,
+    name: &str,
+    params: Vec<ValType>,
+    results: Vec<ValType>,
+) -> Function {
+    let target = (Arc::clone(policy), plugin.to_string(), name.to_string());
+    Function::new(
+        name,
+        params,
+        results,
+        UserData::new(target),
+        |current: &mut CurrentPlugin, _inputs: &[Val], outputs: &mut [Val], user_data| {
+            let (policy, plugin, function) = user_data
+                .get()?
+                .lock()
+                .expect("policy lock poisoned")
+                .clone();
+            let Err(error) = policy.check(&plugin, &function) else {
+                return Ok(());
+            };
+            match outputs.first_mut() {
+                Some(output) => {
+                    let result = serde_json::json!({ "error": error.to_string() });
+                    current.memory_set_val(output, result.to_string())?;
+                    Ok(())
+                }
+                None => Err(error.into()),
+            }
+        },
+    )
 }
 
 /// WASM runtime for loading and managing plugins
@@ -88,13 +266,22 @@ struct PluginMetadata {
 /// and will not be restarted automatically. This prevents infinite restart loops
 /// while allowing recovery from transient failures.
 ///
+/// # Instance Pooling
+///
+/// Each loaded plugin gets a small pool of Extism instances (see `set_pool_size`).
+/// A call checks out one instance for its whole duration, so two tasks calling
+/// the same plugin never share linear memory. New instances are created on
+/// demand up to the pool size; calls beyond that wait for an instance to be
+/// returned.
+///
 /// # Thread Safety
 ///
-/// This struct is not thread-safe by default. Wrap in Arc<Mutex<_>> if sharing
-/// across threads is needed.
+/// Loading and unloading require `&mut self`. Wrap in Arc<Mutex<_>> if sharing
+/// across threads is needed, and use `plugin_handle` to make calls without
+/// holding the lock.
 pub struct WasmRuntime {
-    /// Loaded plugins indexed by name with metadata
-    plugins: HashMap<String, PluginMetadata>,
+    /// Loaded plugins indexed by name
+    plugins: PluginTable,
     /// Manifest containing plugin metadata
     manifest: Manifest,
     /// Cryptographic module for verification
@@ -104,6 +291,20 @@ pub struct WasmRuntime {
     fs_guard: Arc<FileSystemGuard>,
     /// Message bus for publishing crash events (optional)
     message_bus: Option<Arc<MessageBus>>,
+    /// Maximum number of instances per plugin
+    pool_size: usize,
+    /// Fixed clock and RNG seed for plugins (`None` uses the real ones)
+    deterministic: Option<DeterministicEnv>,
+    /// Call-depth limit for plugin-to-plugin calls
+    max_call_depth: usize,
+    /// Host functions disabled for this deployment
+    host_policy: Arc<HostFunctionPolicy>,
+    /// Fuel each plugin call may consume
+    fuel_limit: u64,
+    /// Allowlist and gates for commands run through `exec_command`
+    command_executor: Arc<CommandExecutor>,
+    /// Hosts plugins with the `network` permission may reach
+    egress: EgressPolicy,
 }
 
 impl WasmRuntime {
@@ -135,15 +336,137 @@ impl WasmRuntime {
         fs_guard: Arc<FileSystemGuard>,
     ) -> Self {
         tracing::info!("Initializing WasmRuntime");
+        let command_executor =
+            CommandExecutor::new().with_workspace(fs_guard.workspace().to_path_buf());
         Self {
-            plugins: HashMap::new(),
+            plugins: Arc::new(RwLock::new(HashMap::new())),
             manifest,
             crypto,
             fs_guard,
             message_bus: None,
+            pool_size: DEFAULT_POOL_SIZE,
+            deterministic: None,
+            max_call_depth: DEFAULT_MAX_CALL_DEPTH,
+            host_policy: Arc::new(HostFunctionPolicy::default()),
+            fuel_limit: DEFAULT_FUEL_LIMIT,
+            command_executor: Arc::new(command_executor),
+            egress: EgressPolicy::default(),
         }
     }
 
+    /// Give plugins a fixed clock and a seeded RNG instead of the real ones
+    ///
+    /// Intended for reproducible plugin tests: every instance loaded afterwards
+    /// reads `env.now_ms` from `now_ms` and a `random_u64` sequence that
+    /// restarts from `env.seed`. Not available in production builds.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use rove_engine::runtime::{DeterministicEnv, WasmRuntime};
+    /// # fn example(runtime: WasmRuntime) {
+    /// let runtime = runtime.with_deterministic_env(DeterministicEnv::new(1_700_000_000_000, 42));
+    /// # }
+    /// ```
+    #[cfg(not(feature = "production"))]
+    pub fn with_deterministic_env(mut self, env: DeterministicEnv) -> Self {
+        tracing::warn!("WasmRuntime using a fixed clock and RNG seed for plugins");
+        self.deterministic = Some(env);
+        self
+    }
+
+    /// Set the maximum number of instances kept per plugin
+    ///
+    /// Applies to plugins loaded after this call. Normally set from
+    /// `plugins.pool_size`. A size of 0 is treated as 1, which serializes all
+    /// calls to a plugin.
+    ///
+    /// # Arguments
+    ///
+    /// * `size` - Maximum number of concurrent instances per plugin
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use rove_engine::runtime::WasmRuntime;
+    /// # fn example(runtime: &mut WasmRuntime) {
+    /// runtime.set_pool_size(8);
+    /// # }
+    /// ```
+    pub fn set_pool_size(&mut self, size: usize) {
+        self.pool_size = size.max(1);
+    }
+
+    /// Get the maximum number of instances kept per plugin
+    pub fn pool_size(&self) -> usize {
+        self.pool_size
+    }
+
+    /// Set the call-depth limit for plugins calling other plugins
+    ///
+    /// Applies to plugins loaded after this call. Normally set from
+    /// `conductor.max_call_depth`.
+    pub fn set_max_call_depth(&mut self, depth: usize) {
+        self.max_call_depth = depth;
+    }
+
+    /// Call-depth limit of plugins loaded from now on
+    pub fn max_call_depth(&self) -> usize {
+        self.max_call_depth
+    }
+
+    /// Disable host functions for every plugin, whatever its permissions
+    ///
+    /// Applies to plugins loaded after this call. Normally set from
+    /// `security.disabled_host_functions`.
+    pub fn set_host_function_policy(&mut self, policy: HostFunctionPolicy) {
+        self.host_policy = Arc::new(policy);
+    }
+
+    /// Host functions disabled for plugins loaded from now on
+    pub fn host_function_policy(&self) -> &HostFunctionPolicy {
+        &self.host_policy
+    }
+
+    /// Set the executor that runs commands for the `exec_command` host function
+    ///
+    /// Applies to plugins loaded after this call. Defaults to
+    /// `CommandExecutor::new()` with the `fs_guard`'s workspace; pass one
+    /// built from `security.commands.extra_allowed` to match the agent's
+    /// allowlist. Commands run in the executor's workspace, so it needs one.
+    pub fn set_command_executor(&mut self, executor: CommandExecutor) {
+        self.command_executor = Arc::new(executor);
+    }
+
+    /// Executor used by plugins loaded from now on
+    pub fn command_executor(&self) -> &CommandExecutor {
+        &self.command_executor
+    }
+
+    /// Set the hosts plugins with the `network` permission may reach
+    ///
+    /// Applies to plugins loaded after this call. Normally set from
+    /// `security.network.allowed_hosts`; plugins without the permission
+    /// never reach the network.
+    pub fn set_egress_policy(&mut self, policy: EgressPolicy) {
+        self.egress = policy;
+    }
+
+    /// Set the fuel a single plugin call may consume
+    ///
+    /// Applies to plugins loaded after this call. A call that runs out of fuel
+    /// traps and is handled like a crash. Normally set from
+    /// `plugins.fuel_limit`; use the fuel figures reported by
+    /// `PluginHandle::call_metered` to tune it.
+    pub fn set_fuel_limit(&mut self, limit: u64) {
+        self.fuel_limit = limit;
+    }
+
+    /// Get the fuel a single plugin call may consume
+    pub fn fuel_limit(&self) -> u64 {
+        self.fuel_limit
+    }
+
     /// Set the message bus for publishing crash events
     ///
     /// This is optional but recommended for production use. When set, the runtime
@@ -165,6 +488,10 @@ impl WasmRuntime {
     /// # }
     /// ```
     pub fn set_message_bus(&mut self, bus: Arc<MessageBus>) {
+        let mut plugins = self.plugins.write().expect("plugin table lock poisoned");
+        for handle in plugins.values_mut() {
+            handle.message_bus = Some(Arc::clone(&bus));
+        }
         self.message_bus = Some(bus);
     }
 
@@ -215,6 +542,13 @@ impl WasmRuntime {
 
         tracing::info!("Gate 1 PASSED: Plugin '{}' found in manifest", name);
 
+        // Refuse plugins built against an incompatible host ABI before
+        // touching the file
+        abi::check_compatible(name, plugin_entry.host_abi).map_err(|e| {
+            tracing::error!("{}", e);
+            e
+        })?;
+
         // Validate no absolute paths in manifest (Requirement 5.4)
         let plugin_path = PathBuf::from(&plugin_entry.path);
         if plugin_path.is_absolute() {
@@ -253,32 +587,237 @@ impl WasmRuntime {
             EngineError::Plugin(format!("Failed to read WASM file: {}", e))
         })?;
 
-        // Create Extism manifest for the plugin
-        let wasm = Wasm::data(wasm_bytes);
-        let extism_manifest = ExtismManifest::new([wasm]);
+        let plugin_entry = plugin_entry.clone();
+        self.instantiate(name, &plugin_entry, wasm_bytes).await
+    }
 
-        // Create host functions for the plugin
-        let host_functions = self.create_host_functions();
+    /// Load every `*.wasm` file in `dir` without a manifest (plugin dev mode)
+    ///
+    /// **INSECURE**: the plugins are not signed or declared anywhere. Each
+    /// module is only checked against the SHA-256 hash in the `.sha256` file
+    /// next to it (`echo.wasm.sha256` for `echo.wasm`, in `sha256sum`
+    /// format), so a build that was replaced without updating its hash is
+    /// not loaded. Plugins are named after their file stem and given the
+    /// default permissions. Meant for iterating on a plugin without
+    /// re-signing the manifest after every build; production builds refuse
+    /// it. Returns the names of the loaded plugins.
+    ///
+    /// # Errors
+    ///
+    /// Returns `EngineError::Config` in production builds or if `dir` can't
+    /// be read, `EngineError::HashMismatch` if a module doesn't match its
+    /// hash file, and `EngineError::Plugin` if a hash file is missing or a
+    /// module fails to load.
+    pub async fn load_dev_plugins(&mut self, dir: &Path) -> Result<Vec<String>, EngineError> {
+        if CryptoModule::is_production() {
+            tracing::error!(
+                "Refusing to load unsigned plugins from {}: dev mode is disabled in production builds",
+                dir.display()
+            );
+            return Err(EngineError::Config(
+                "Plugin dev mode is not available in production builds".to_string(),
+            ));
+        }
 
-        // Create the Extism plugin with host functions
-        let plugin = Plugin::new(&extism_manifest, host_functions, true).map_err(|e| {
-            tracing::error!("Failed to create Extism plugin for '{}': {}", name, e);
-            EngineError::Plugin(format!("Failed to create plugin: {}", e))
+        tracing::warn!(
+            "INSECURE: plugin dev mode is loading unsigned plugins from {}",
+            dir.display()
+        );
+
+        let entries = std::fs::read_dir(dir).map_err(|e| {
+            EngineError::Config(format!(
+                "Failed to read plugin dev directory {}: {}",
+                dir.display(),
+                e
+            ))
         })?;
+        let mut paths: Vec<PathBuf> = entries
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.extension().is_some_and(|ext| ext == "wasm"))
+            .collect();
+        paths.sort();
+
+        let mut loaded = Vec::new();
+        for path in paths {
+            let Some(name) = path.file_stem().and_then(|stem| stem.to_str()) else {
+                continue;
+            };
+            let wasm_bytes = std::fs::read(&path)
+                .map_err(|e| EngineError::Plugin(format!("Failed to read WASM file: {}", e)))?;
+            let hash = CryptoModule::compute_hash(&wasm_bytes);
+            let expected = Self::dev_plugin_hash(&path)?;
+            if !expected.eq_ignore_ascii_case(&hash) {
+                tracing::error!(
+                    "Dev plugin '{}' does not match its hash file: expected {}, got {}",
+                    name,
+                    expected,
+                    hash
+                );
+                return Err(EngineError::HashMismatch(path.display().to_string()));
+            }
+            tracing::warn!(
+                "Loading unsigned dev plugin '{}' from {} (unverified, sha256 {})",
+                name,
+                path.display(),
+                hash
+            );
 
-        // Store the plugin with metadata
-        self.plugins.insert(
-            name.to_string(),
-            PluginMetadata {
-                plugin,
-                crash_count: 0,
-            },
-        );
+            let plugin_entry = PluginEntry {
+                name: name.to_string(),
+                version: "dev".to_string(),
+                path: path.display().to_string(),
+                hash,
+                permissions: PluginPermissions::default(),
+                output_schemas: HashMap::new(),
+                host_abi: None,
+            };
+            self.instantiate(name, &plugin_entry, wasm_bytes).await?;
+            loaded.push(name.to_string());
+        }
+
+        Ok(loaded)
+    }
 
-        tracing::info!("Plugin '{}' loaded successfully", name);
+    /// Expected hash of the dev plugin at `path`, read from its `.sha256` file
+    fn dev_plugin_hash(path: &Path) -> Result<String, EngineError> {
+        let mut hash_path = path.as_os_str().to_owned();
+        hash_path.push(".sha256");
+        let hash_path = PathBuf::from(hash_path);
+        let contents = std::fs::read_to_string(&hash_path).map_err(|e| {
+            EngineError::Plugin(format!(
+                "Dev plugin {} needs a hash file {}: {}",
+                path.display(),
+                hash_path.display(),
+                e
+            ))
+        })?;
+        contents
+            .split_whitespace()
+            .next()
+            .map(|hash| hash.trim_start_matches("sha256:").to_string())
+            .ok_or_else(|| {
+                EngineError::Plugin(format!("Hash file {} is empty", hash_path.display()))
+            })
+    }
+
+    /// Build the instance pool for verified `wasm_bytes` and register it
+    async fn instantiate(
+        &mut self,
+        name: &str,
+        plugin_entry: &PluginEntry,
+        wasm_bytes: Vec<u8>,
+    ) -> Result<(), EngineError> {
+        // Instances are built from the verified bytes kept in memory, so pooled
+        // instances created later never re-read the file from disk
+        let plugin_name = name.to_string();
+        let deterministic = self.deterministic;
+        let workspace = self.fs_guard.workspace().to_path_buf();
+        let fs_guard = self.fs_guard.clone();
+        let permissions = plugin_entry.permissions.clone();
+        let host_policy = Arc::clone(&self.host_policy);
+        let command_executor = Arc::clone(&self.command_executor);
+        let fuel_limit = self.fuel_limit;
+        let allowed_hosts = allowed_hosts(&permissions, &self.egress);
+        // One random sequence per plugin: pooled instances share it, so a
+        // seeded sequence doesn't restart with every new instance
+        let rng = UserData::new(HostRng::new(deterministic.as_ref()));
+        let caller = PluginCaller {
+            name: name.to_string(),
+            permissions: permissions.clone(),
+            plugins: Arc::downgrade(&self.plugins),
+            max_call_depth: self.max_call_depth,
+        };
+        let pool = InstancePool::new(name, self.pool_size, move || {
+            // Create Extism manifest for the plugin
+            let wasm = Wasm::data(wasm_bytes.clone());
+            let extism_manifest =
+                ExtismManifest::new([wasm]).with_allowed_hosts(allowed_hosts.clone().into_iter());
+
+            // Create the Extism plugin with host functions
+            let functions = Self::create_host_functions(
+                deterministic,
+                rng.clone(),
+                workspace.clone(),
+                fs_guard.clone(),
+                caller.clone(),
+                &host_policy,
+                &command_executor,
+            );
+            PluginBuilder::new(extism_manifest)
+                .with_wasi(true)
+                .with_functions(functions)
+                .with_fuel_limit(fuel_limit)
+                .build()
+                .map_err(|e| {
+                    tracing::error!(
+                        "Failed to create Extism plugin for '{}': {}",
+                        plugin_name,
+                        e
+                    );
+                    EngineError::Plugin(format!("Failed to create plugin: {}", e))
+                })
+        });
+
+        // Create the first instance eagerly so invalid modules fail at load time
+        pool.checkout().await?;
+
+        let output_schemas = plugin_entry.output_schemas.clone();
+
+        self.plugins
+            .write()
+            .expect("plugin table lock poisoned")
+            .insert(
+                name.to_string(),
+                PluginHandle {
+                    name: name.to_string(),
+                    pool: Arc::new(pool),
+                    crash_count: Arc::new(AtomicU32::new(0)),
+                    message_bus: self.message_bus.clone(),
+                    output_schemas: Arc::new(output_schemas),
+                    permissions: Arc::new(permissions),
+                },
+            );
+
+        tracing::info!(
+            "Plugin '{}' loaded successfully (pool size {})",
+            name,
+            self.pool_size
+        );
         Ok(())
     }
 
+    /// Get a shareable handle to a loaded plugin
+    ///
+    /// The handle can be cloned and used from several tasks at once without
+    /// holding a lock on the runtime. Each call through the handle checks out
+    /// its own instance from the plugin's pool.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Name of the plugin
+    ///
+    /// # Errors
+    ///
+    /// Returns `EngineError::PluginNotLoaded` if the plugin is not currently loaded.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use rove_engine::runtime::WasmRuntime;
+    /// # async fn example(runtime: &WasmRuntime) -> Result<(), Box<dyn std::error::Error>> {
+    /// let handle = runtime.plugin_handle("fs-editor")?;
+    /// let output = handle.call("read_file", br#"{"path":"test.txt"}"#).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn plugin_handle(&self, name: &str) -> Result<PluginHandle, EngineError> {
+        let plugins = self.plugins.read().expect("plugin table lock poisoned");
+        plugins.get(name).cloned().ok_or_else(|| {
+            tracing::error!("Plugin '{}' not loaded", name);
+            EngineError::PluginNotLoaded(name.to_string())
+        })
+    }
+
     /// Create host functions that plugins can call
     ///
     /// These host functions provide controlled access to file system operations.
@@ -286,9 +825,41 @@ impl WasmRuntime {
     ///
     /// # Host Functions Provided
     ///
-    /// - `read_file(path: string) -> string` - Read a file's contents
+    /// - `read_file(path: string) -> string` - Read a file, at most
+    ///   `max_bytes` of it (JSON with `content`, `encoding`, `lossy` and
+    ///   `truncated`, or `error`). See `host_read`.
     /// - `write_file(path: string, content: string)` - Write content to a file
     /// - `list_directory(path: string) -> string` - List directory contents (JSON array)
+    /// - `find_files(request: string) -> string` - Find files under `root`
+    ///   matching a glob `pattern` (JSON with `files` and `truncated`, or
+    ///   `error`). See `host_find`.
+    /// - `exec_command(command: string, args: string) -> string` - Run an
+    ///   allowlisted command with a JSON array of arguments (JSON with
+    ///   `stdout`, `stderr` and `exit_code`). See `host_exec`.
+    /// - `now_ms() -> i64` - Milliseconds since the Unix epoch
+    /// - `random_u64() -> i64` - Random 64-bit value
+    /// - `capture_window(title: string) -> string` - Capture the window whose
+    ///   title matches into the workspace (JSON with `path` and `window`
+    ///   bounds, or `error`)
+    /// - `ocr(path: string) -> string` - Recognize the text in an image (JSON
+    ///   with `text` and word `boxes`, or `error`)
+    /// - `call_plugin(plugin_id: string, function: string, input: string) -> string` -
+    ///   Call a function of another loaded plugin and return its output (or
+    ///   JSON with `error`). See `plugin_call` for the checks applied.
+    /// - `cache_get(key: string) -> string` - Look up a value in the task's
+    ///   cache (JSON with `value`, null on a miss)
+    /// - `cache_set(key: string, value: string, ttl_ms: i64) -> string` -
+    ///   Cache a value for the rest of the task, or `ttl_ms` if positive
+    ///   (JSON with `ok`, or `error`). See `task_cache`.
+    ///
+    /// `read_file`, `find_files`, `now_ms`, `random_u64`, `capture_window`, `ocr`,
+    /// `call_plugin`, `cache_get` and `cache_set` are fully implemented. With a
+    /// `DeterministicEnv`, `now_ms` returns the fixed time and `random_u64` a
+    /// seeded sequence shared by the plugin's pooled instances (`rng`), so
+    /// plugin behavior can be reproduced in tests.
+    ///
+    /// Functions disabled by `policy` are replaced with stand-ins of the same
+    /// signature that refuse every call (see `disabled_host_function`).
     ///
     /// # Security
     ///
@@ -297,67 +868,391 @@ impl WasmRuntime {
     /// - Canonicalizes paths to prevent traversal attacks
     /// - Ensures operations stay within the workspace
     ///
-    /// Additionally, plugin permissions from the manifest are enforced:
+    /// `read_file` additionally enforces the plugin's manifest permissions
+    /// (see `host_read`):
     /// - allowed_paths: Only paths matching these patterns are allowed
     /// - denied_paths: Paths matching these patterns are explicitly denied
-    /// - max_file_size: Maximum file size for read/write operations
     ///
     /// # Implementation Note
     ///
-    /// These are placeholder implementations. The actual Extism host function API
-    /// requires using the PDK's host function interface, which works differently
-    /// than shown here. In production, plugins would call these functions via
-    /// the Extism PDK's `host_fn!` macro, and the host would implement them
-    /// using Extism's `Function::new` with proper memory handling.
-    ///
-    /// For now, we return empty function lists since the actual implementation
-    /// requires deeper integration with Extism's memory model.
-    fn create_host_functions(&self) -> Vec<Function> {
-        // TODO: Implement actual host functions using Extism's PDK interface
-        // The challenge is that Extism's host functions need to:
-        // 1. Read strings from plugin linear memory
-        // 2. Perform operations (with our security checks)
-        // 3. Write results back to plugin memory
-
-        tracing::warn!(
-            "Host functions not yet fully implemented - plugins will receive empty/dummy responses"
-        );
-
-        use extism::ValType;
-
-        let read_file = Function::new(
+    /// `write_file` and `list_directory` are still stubs that accept every
+    /// call and do nothing.
+    fn create_host_functions(
+        deterministic: Option<DeterministicEnv>,
+        rng: UserData<HostRng>,
+        workspace: PathBuf,
+        fs_guard: Arc<FileSystemGuard>,
+        caller: PluginCaller,
+        policy: &Arc<HostFunctionPolicy>,
+        command_executor: &Arc<CommandExecutor>,
+    ) -> Vec<Function> {
+        tracing::debug!("write_file and list_directory host functions are stubs");
+
+        let plugin_name = caller.name.clone();
+
+        // Takes a path or `{"path", "lossy", "binary", "max_bytes"}` and
+        // returns `{"content", "encoding", "lossy", "truncated"}`, or
+        // `{"error", "code"}`. Files that are not UTF-8 come back as base64
+        // unless the plugin asks for a lossy read, and only paths the
+        // plugin's manifest allows are read (see `host_read`).
+        let read_file = host_function(
+            policy,
+            &plugin_name,
             "read_file",
-            [ValType::I64],
-            [ValType::I64],
-            UserData::new(()),
-            |_plugin, _inputs, _outputs, _user_data| Ok(()),
+            &[ValType::I64],
+            &[ValType::I64],
+            |name, params, results| {
+                Function::new(
+                    name,
+                    params,
+                    results,
+                    UserData::new((fs_guard.clone(), caller.permissions.clone())),
+                    |plugin, inputs, outputs, user_data| {
+                        let input: String = plugin.memory_get_val(&inputs[0])?;
+                        let (guard, permissions) = user_data
+                            .get()?
+                            .lock()
+                            .expect("guard lock poisoned")
+                            .clone();
+                        let result = host_read::read_file(&guard, &permissions, &input);
+                        plugin.memory_set_val(&mut outputs[0], result.to_string())?;
+                        Ok(())
+                    },
+                )
+            },
         );
 
-        let write_file = Function::new(
+        let write_file = host_function(
+            policy,
+            &plugin_name,
             "write_file",
-            [ValType::I64, ValType::I64],
-            [],
-            UserData::new(()),
-            |_plugin, _inputs, _outputs, _user_data| Ok(()),
+            &[ValType::I64, ValType::I64],
+            &[],
+            |name, params, results| {
+                Function::new(
+                    name,
+                    params,
+                    results,
+                    UserData::new(()),
+                    |_plugin, _inputs, _outputs, _user_data| Ok(()),
+                )
+            },
         );
 
-        let list_directory = Function::new(
+        let list_directory = host_function(
+            policy,
+            &plugin_name,
             "list_directory",
-            [ValType::I64],
-            [ValType::I64],
-            UserData::new(()),
-            |_plugin, _inputs, _outputs, _user_data| Ok(()),
+            &[ValType::I64],
+            &[ValType::I64],
+            |name, params, results| {
+                Function::new(
+                    name,
+                    params,
+                    results,
+                    UserData::new(()),
+                    |_plugin, _inputs, _outputs, _user_data| Ok(()),
+                )
+            },
         );
 
-        let exec_git = Function::new(
+        // Takes `{"root", "pattern", "max_results"}` and returns `{"files",
+        // "truncated"}`, or `{"error", "code"}`. Denied paths and paths the
+        // plugin's manifest doesn't allow are skipped (see `host_find`).
+        let find_files = host_function(
+            policy,
+            &plugin_name,
+            "find_files",
+            &[ValType::I64],
+            &[ValType::I64],
+            |name, params, results| {
+                Function::new(
+                    name,
+                    params,
+                    results,
+                    UserData::new((fs_guard.clone(), caller.permissions.clone())),
+                    |plugin, inputs, outputs, user_data| {
+                        let input: String = plugin.memory_get_val(&inputs[0])?;
+                        let (guard, permissions) = user_data
+                            .get()?
+                            .lock()
+                            .expect("guard lock poisoned")
+                            .clone();
+                        let result = host_find::find_files(&guard, &permissions, &input);
+                        plugin.memory_set_val(&mut outputs[0], result.to_string())?;
+                        Ok(())
+                    },
+                )
+            },
+        );
+
+        let exec_git = host_function(
+            policy,
+            &plugin_name,
             "exec_git",
-            [ValType::I64],
-            [ValType::I64],
-            UserData::new(()),
-            |_plugin, _inputs, _outputs, _user_data| Ok(()),
+            &[ValType::I64],
+            &[ValType::I64],
+            |name, params, results| {
+                Function::new(
+                    name,
+                    params,
+                    results,
+                    UserData::new(()),
+                    |_plugin, _inputs, _outputs, _user_data| Ok(()),
+                )
+            },
+        );
+
+        // Takes a command name and a JSON array of arguments and returns
+        // `{"stdout", "stderr", "exit_code"}`. Checked against the plugin's
+        // permissions and the CommandExecutor gates (see `host_exec`).
+        let exec_command = host_function(
+            policy,
+            &plugin_name,
+            "exec_command",
+            &[ValType::I64, ValType::I64],
+            &[ValType::I64],
+            |name, params, results| {
+                Function::new(
+                    name,
+                    params,
+                    results,
+                    UserData::new((Arc::clone(command_executor), caller.permissions.clone())),
+                    |plugin, inputs, outputs, user_data| {
+                        let command: String = plugin.memory_get_val(&inputs[0])?;
+                        let args: String = plugin.memory_get_val(&inputs[1])?;
+                        let (executor, permissions) = user_data
+                            .get()?
+                            .lock()
+                            .expect("executor lock poisoned")
+                            .clone();
+                        let result =
+                            host_exec::exec_command(&executor, &permissions, &command, &args);
+                        plugin.memory_set_val(&mut outputs[0], serde_json::to_string(&result)?)?;
+                        Ok(())
+                    },
+                )
+            },
+        );
+
+        let now_ms = host_function(
+            policy,
+            &plugin_name,
+            "now_ms",
+            &[],
+            &[ValType::I64],
+            |name, params, results| {
+                Function::new(
+                    name,
+                    params,
+                    results,
+                    UserData::new(deterministic),
+                    |_plugin, _inputs, outputs, user_data| {
+                        let env = user_data.get()?;
+                        let env = env.lock().expect("clock lock poisoned");
+                        outputs[0] = Val::I64(determinism::now_ms(env.as_ref()));
+                        Ok(())
+                    },
+                )
+            },
+        );
+
+        let random_u64 = host_function(
+            policy,
+            &plugin_name,
+            "random_u64",
+            &[],
+            &[ValType::I64],
+            |name, params, results| {
+                Function::new(
+                    name,
+                    params,
+                    results,
+                    rng,
+                    |_plugin, _inputs, outputs, user_data| {
+                        let rng = user_data.get()?;
+                        let mut rng = rng.lock().expect("rng lock poisoned");
+                        outputs[0] = Val::I64(rng.next_u64() as i64);
+                        Ok(())
+                    },
+                )
+            },
+        );
+
+        // Takes a window title and returns `{"path", "window"}` for the
+        // captured window, or `{"error"}` if no single window matches
+        let capture_window = host_function(
+            policy,
+            &plugin_name,
+            "capture_window",
+            &[ValType::I64],
+            &[ValType::I64],
+            |name, params, results| {
+                Function::new(
+                    name,
+                    params,
+                    results,
+                    UserData::new(workspace),
+                    |plugin, inputs, outputs, user_data| {
+                        let title: String = plugin.memory_get_val(&inputs[0])?;
+                        let workspace = user_data
+                            .get()?
+                            .lock()
+                            .expect("workspace lock poisoned")
+                            .clone();
+                        let output_file = format!("window-{}.png", determinism::now_ms(None));
+                        let result = match vision::capture_window(&workspace, &title, &output_file)
+                        {
+                            Ok(capture) => serde_json::to_value(&capture)?,
+                            Err(e) => serde_json::json!({ "error": e.to_string() }),
+                        };
+                        plugin.memory_set_val(&mut outputs[0], result.to_string())?;
+                        Ok(())
+                    },
+                )
+            },
+        );
+
+        // Takes an image path and returns `{"image_path", "text", "boxes"}`,
+        // or `{"error"}`. The path is validated by the FileSystemGuard.
+        let ocr = host_function(
+            policy,
+            &plugin_name,
+            "ocr",
+            &[ValType::I64],
+            &[ValType::I64],
+            |name, params, results| {
+                Function::new(
+                    name,
+                    params,
+                    results,
+                    UserData::new(fs_guard),
+                    |plugin, inputs, outputs, user_data| {
+                        let image_path: String = plugin.memory_get_val(&inputs[0])?;
+                        let guard = user_data
+                            .get()?
+                            .lock()
+                            .expect("guard lock poisoned")
+                            .clone();
+                        let options = ocr::ExtractTextOptions {
+                            image_path,
+                            boxes: true,
+                        };
+                        let result = match ocr::extract_text(&guard, &ocr::Tesseract, &options) {
+                            Ok(text) => serde_json::to_value(&text)?,
+                            Err(e) => serde_json::json!({ "error": e.to_string() }),
+                        };
+                        plugin.memory_set_val(&mut outputs[0], result.to_string())?;
+                        Ok(())
+                    },
+                )
+            },
+        );
+
+        // Takes a plugin name, function name and input, and returns the
+        // target's output, or `{"error"}` if the call is refused or fails
+        let call_plugin = host_function(
+            policy,
+            &plugin_name,
+            "call_plugin",
+            &[ValType::I64, ValType::I64, ValType::I64],
+            &[ValType::I64],
+            |name, params, results| {
+                Function::new(
+                    name,
+                    params,
+                    results,
+                    UserData::new(caller),
+                    |plugin, inputs, outputs, user_data| {
+                        let target: String = plugin.memory_get_val(&inputs[0])?;
+                        let function: String = plugin.memory_get_val(&inputs[1])?;
+                        let input: String = plugin.memory_get_val(&inputs[2])?;
+                        let caller = user_data
+                            .get()?
+                            .lock()
+                            .expect("caller lock poisoned")
+                            .clone();
+                        let result = match caller.call(&target, &function, &input) {
+                            Ok(output) => output,
+                            Err(e) => serde_json::json!({ "error": e.to_string() }).to_string(),
+                        };
+                        plugin.memory_set_val(&mut outputs[0], result)?;
+                        Ok(())
+                    },
+                )
+            },
+        );
+
+        // Takes a key and returns `{"value"}`, with a null value on a miss
+        let cache_get = host_function(
+            policy,
+            &plugin_name,
+            "cache_get",
+            &[ValType::I64],
+            &[ValType::I64],
+            |name, params, results| {
+                Function::new(
+                    name,
+                    params,
+                    results,
+                    UserData::new(()),
+                    |plugin, inputs, outputs, _user_data| {
+                        let key: String = plugin.memory_get_val(&inputs[0])?;
+                        let result = serde_json::json!({ "value": task_cache::get(&key) });
+                        plugin.memory_set_val(&mut outputs[0], result.to_string())?;
+                        Ok(())
+                    },
+                )
+            },
+        );
+
+        // Takes a key, a value and a TTL in milliseconds (0 keeps the value
+        // until the task ends), and returns `{"ok": true}` or `{"error"}`
+        let cache_set = host_function(
+            policy,
+            &plugin_name,
+            "cache_set",
+            &[ValType::I64, ValType::I64, ValType::I64],
+            &[ValType::I64],
+            |name, params, results| {
+                Function::new(
+                    name,
+                    params,
+                    results,
+                    UserData::new(()),
+                    |plugin, inputs, outputs, _user_data| {
+                        let key: String = plugin.memory_get_val(&inputs[0])?;
+                        let value: String = plugin.memory_get_val(&inputs[1])?;
+                        let ttl = match inputs[2].i64().unwrap_or(0) {
+                            ms if ms > 0 => Some(Duration::from_millis(ms as u64)),
+                            _ => None,
+                        };
+                        let result = match task_cache::set(&key, value, ttl) {
+                            Ok(()) => serde_json::json!({ "ok": true }),
+                            Err(e) => serde_json::json!({ "error": e.to_string() }),
+                        };
+                        plugin.memory_set_val(&mut outputs[0], result.to_string())?;
+                        Ok(())
+                    },
+                )
+            },
         );
 
-        vec![read_file, write_file, list_directory, exec_git]
+        vec![
+            read_file,
+            write_file,
+            list_directory,
+            find_files,
+            exec_git,
+            exec_command,
+            now_ms,
+            random_u64,
+            capture_window,
+            ocr,
+            call_plugin,
+            cache_get,
+            cache_set,
+        ]
     }
 
     /// Call a plugin function with the given input
@@ -366,6 +1261,9 @@ impl WasmRuntime {
     /// If a plugin crashes, it will be automatically restarted up to MAX_CRASH_RESTARTS
     /// times. After that, the plugin is marked as failed and subsequent calls will fail.
     ///
+    /// The call checks out its own instance from the plugin's pool, waiting if all
+    /// instances are busy. See `PluginHandle::call`.
+    ///
     /// # Arguments
     ///
     /// * `name` - Name of the plugin to call
@@ -386,15 +1284,15 @@ impl WasmRuntime {
     /// When a plugin crashes:
     /// 1. The crash is logged with details
     /// 2. A PluginCrashed event is published to the message bus (if configured)
-    /// 3. The plugin is automatically restarted (if under MAX_CRASH_RESTARTS)
-    /// 4. The call is retried once after restart
+    /// 3. The crashed instance is discarded and replaced with a fresh one (if under MAX_CRASH_RESTARTS)
+    /// 4. The call is retried once on the fresh instance
     /// 5. If the retry fails or max restarts exceeded, an error is returned
     ///
     /// # Examples
     ///
     /// ```no_run
     /// # use rove_engine::runtime::WasmRuntime;
-    /// # async fn example(runtime: &mut WasmRuntime) -> Result<(), Box<dyn std::error::Error>> {
+    /// # async fn example(runtime: &WasmRuntime) -> Result<(), Box<dyn std::error::Error>> {
     /// let input = serde_json::json!({
     ///     "path": "test.txt"
     /// });
@@ -407,165 +1305,12 @@ impl WasmRuntime {
     /// # }
     /// ```
     pub async fn call_plugin(
-        &mut self,
+        &self,
         name: &str,
         function: &str,
         input: &[u8],
     ) -> Result<Vec<u8>, EngineError> {
-        tracing::debug!("Calling plugin '{}' function '{}'", name, function);
-
-        // Check if plugin is loaded
-        let metadata = self.plugins.get_mut(name).ok_or_else(|| {
-            tracing::error!("Plugin '{}' not loaded", name);
-            EngineError::PluginNotLoaded(name.to_string())
-        })?;
-
-        // Check if plugin has crashed too many times
-        if metadata.crash_count >= MAX_CRASH_RESTARTS {
-            tracing::error!(
-                "Plugin '{}' has crashed {} times, refusing to call",
-                name,
-                metadata.crash_count
-            );
-            return Err(EngineError::Plugin(format!(
-                "Plugin '{}' has crashed too many times ({} crashes)",
-                name, metadata.crash_count
-            )));
-        }
-
-        // Attempt to call the plugin function
-        let result = metadata
-            .plugin
-            .call::<&[u8], Vec<u8>>(function, input)
-            .map_err(|e| {
-                tracing::error!("Plugin '{}' function '{}' failed: {}", name, function, e);
-                EngineError::Plugin(format!("Plugin call failed: {}", e))
-            });
-
-        match result {
-            Ok(output) => {
-                // Success - reset crash count on successful call
-                if metadata.crash_count > 0 {
-                    tracing::info!(
-                        "Plugin '{}' recovered after {} crashes",
-                        name,
-                        metadata.crash_count
-                    );
-                    metadata.crash_count = 0;
-                }
-                Ok(output)
-            }
-            Err(e) => {
-                // Plugin call failed - treat as potential crash
-                self.handle_plugin_crash(name, &e).await?;
-
-                // Retry once after restart
-                tracing::info!(
-                    "Retrying plugin '{}' function '{}' after restart",
-                    name,
-                    function
-                );
-                let metadata = self.plugins.get_mut(name).ok_or_else(|| {
-                    EngineError::Plugin(format!("Plugin '{}' disappeared after restart", name))
-                })?;
-
-                metadata
-                    .plugin
-                    .call::<&[u8], Vec<u8>>(function, input)
-                    .map_err(|e| {
-                        tracing::error!(
-                            "Plugin '{}' function '{}' failed again after restart: {}",
-                            name,
-                            function,
-                            e
-                        );
-                        EngineError::Plugin(format!("Plugin call failed after restart: {}", e))
-                    })
-            }
-        }
-    }
-
-    /// Handle a plugin crash by logging, publishing events, and attempting restart
-    ///
-    /// This internal method is called when a plugin call fails. It:
-    /// 1. Increments the crash counter
-    /// 2. Logs the crash with details
-    /// 3. Publishes a PluginCrashed event to the message bus
-    /// 4. Attempts to restart the plugin (if under MAX_CRASH_RESTARTS)
-    ///
-    /// # Arguments
-    ///
-    /// * `name` - Name of the crashed plugin
-    /// * `error` - The error that caused the crash
-    ///
-    /// # Errors
-    ///
-    /// Returns an error if the plugin cannot be restarted or has crashed too many times.
-    async fn handle_plugin_crash(
-        &mut self,
-        name: &str,
-        error: &EngineError,
-    ) -> Result<(), EngineError> {
-        // Increment crash counter
-        if let Some(metadata) = self.plugins.get_mut(name) {
-            metadata.crash_count += 1;
-            let crash_count = metadata.crash_count;
-
-            tracing::error!(
-                "Plugin '{}' crashed (crash #{}/{}): {}",
-                name,
-                crash_count,
-                MAX_CRASH_RESTARTS,
-                error
-            );
-
-            // Publish crash event to message bus
-            if let Some(bus) = &self.message_bus {
-                let event = Event::PluginCrashed {
-                    plugin_id: name.to_string(),
-                    error: format!("Crash #{}: {}", crash_count, error),
-                };
-                bus.publish(event).await;
-            }
-
-            // Check if we should attempt restart
-            if crash_count >= MAX_CRASH_RESTARTS {
-                tracing::error!(
-                    "Plugin '{}' has reached maximum crash limit ({}), will not restart",
-                    name,
-                    MAX_CRASH_RESTARTS
-                );
-                return Err(EngineError::Plugin(format!(
-                    "Plugin '{}' has crashed {} times and will not be restarted",
-                    name, MAX_CRASH_RESTARTS
-                )));
-            }
-
-            // Attempt to restart the plugin
-            tracing::warn!(
-                "Attempting to restart plugin '{}' (crash #{}/{})",
-                name,
-                crash_count,
-                MAX_CRASH_RESTARTS
-            );
-
-            // Remove the crashed plugin (but keep the crash count)
-            let crash_count_backup = crash_count;
-            self.plugins.remove(name);
-
-            // Reload the plugin
-            self.load_plugin(name).await?;
-
-            // Restore the crash count
-            if let Some(metadata) = self.plugins.get_mut(name) {
-                metadata.crash_count = crash_count_backup;
-            }
-
-            tracing::info!("Plugin '{}' restarted successfully after crash", name);
-            Ok(())
-        } else {
-            Err(EngineError::PluginNotLoaded(name.to_string()))
-        }
+        self.plugin_handle(name)?.call(function, input).await
     }
 
     /// Unload a plugin
@@ -586,7 +1331,14 @@ impl WasmRuntime {
     /// # }
     /// ```
     pub fn unload_plugin(&mut self, name: &str) {
-        if self.plugins.remove(name).is_some() {
+        let removed = self
+            .plugins
+            .write()
+            .expect("plugin table lock poisoned")
+            .remove(name);
+        if let Some(handle) = removed {
+            // Handles cloned out of the runtime must not keep using this plugin
+            handle.pool.close();
             tracing::info!("Plugin '{}' unloaded", name);
         } else {
             tracing::debug!("Plugin '{}' not loaded, nothing to unload", name);
@@ -623,20 +1375,61 @@ impl WasmRuntime {
         tracing::warn!("Manually restarting plugin: {}", name);
 
         // Remove crashed plugin
-        self.plugins.remove(name);
+        let removed = self
+            .plugins
+            .write()
+            .expect("plugin table lock poisoned")
+            .remove(name);
+        if let Some(handle) = removed {
+            handle.pool.close();
+        }
 
         // Reload
         self.load_plugin(name).await?;
 
         // Reset crash count since this is a manual restart
-        if let Some(metadata) = self.plugins.get_mut(name) {
-            metadata.crash_count = 0;
+        let plugins = self.plugins.read().expect("plugin table lock poisoned");
+        if let Some(handle) = plugins.get(name) {
+            handle.crash_count.store(0, Ordering::SeqCst);
         }
 
         tracing::info!("Plugin '{}' restarted successfully", name);
         Ok(())
     }
 
+    /// Check that the manifest plugin `name` loads and passes its self-test
+    ///
+    /// Runs every load gate (manifest, host ABI, hash), instantiates the
+    /// module, and calls its `self_test` export with empty input if it has
+    /// one. A plugin that was not loaded before is unloaded again afterwards.
+    /// Used by `rove doctor` to catch a corrupt or incompatible plugin before
+    /// a task needs it.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of the first gate or call that fails.
+    pub async fn check_plugin(&mut self, name: &str) -> Result<(), EngineError> {
+        let was_loaded = self.is_plugin_loaded(name);
+        if !was_loaded {
+            self.load_plugin(name).await?;
+        }
+
+        let result = self.self_test(name).await;
+        if !was_loaded {
+            self.unload_plugin(name);
+        }
+        result
+    }
+
+    /// Call the `self_test` export of the loaded plugin `name`, if any
+    async fn self_test(&self, name: &str) -> Result<(), EngineError> {
+        let handle = self.plugin_handle(name)?;
+        if handle.exports(SELF_TEST_FUNCTION).await? {
+            handle.call(SELF_TEST_FUNCTION, b"").await?;
+        }
+        Ok(())
+    }
+
     /// Get the crash count for a plugin
     ///
     /// Returns the number of times the plugin has crashed since it was loaded
@@ -661,7 +1454,11 @@ impl WasmRuntime {
     /// # }
     /// ```
     pub fn get_crash_count(&self, name: &str) -> Option<u32> {
-        self.plugins.get(name).map(|m| m.crash_count)
+        self.plugins
+            .read()
+            .expect("plugin table lock poisoned")
+            .get(name)
+            .map(|h| h.crash_count.load(Ordering::SeqCst))
     }
 
     /// Check if a plugin is currently loaded
@@ -685,7 +1482,10 @@ impl WasmRuntime {
     /// # }
     /// ```
     pub fn is_plugin_loaded(&self, name: &str) -> bool {
-        self.plugins.contains_key(name)
+        self.plugins
+            .read()
+            .expect("plugin table lock poisoned")
+            .contains_key(name)
     }
 
     /// Get a list of all loaded plugin names
@@ -704,7 +1504,12 @@ impl WasmRuntime {
     /// # }
     /// ```
     pub fn loaded_plugins(&self) -> Vec<String> {
-        self.plugins.keys().cloned().collect()
+        self.plugins
+            .read()
+            .expect("plugin table lock poisoned")
+            .keys()
+            .cloned()
+            .collect()
     }
 
     /// Unload all plugins
@@ -722,7 +1527,7 @@ impl WasmRuntime {
     pub fn unload_all(&mut self) {
         tracing::info!("Unloading all plugins");
 
-        let plugin_names: Vec<String> = self.plugins.keys().cloned().collect();
+        let plugin_names = self.loaded_plugins();
 
         for name in plugin_names {
             self.unload_plugin(&name);
@@ -732,6 +1537,242 @@ impl WasmRuntime {
     }
 }
 
+impl PluginHandle {
+    /// Name of the plugin this handle refers to
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Call a plugin function on an instance checked out from the pool
+    ///
+    /// Waits for a free instance if the pool is at capacity. If the call fails,
+    /// the instance is treated as crashed: it is discarded, the crash counter is
+    /// incremented, a PluginCrashed event is published, and the call is retried
+    /// once on a fresh instance (if under MAX_CRASH_RESTARTS).
+    ///
+    /// If the manifest declares an output schema for `function`, the output is
+    /// validated against it before being returned.
+    ///
+    /// # Arguments
+    ///
+    /// * `function` - Name of the function to call within the plugin
+    /// * `input` - Input data as bytes (typically JSON)
+    ///
+    /// # Errors
+    ///
+    /// Returns `EngineError::Plugin` if the function call fails, the plugin has
+    /// crashed too many times, or the plugin was unloaded.
+    /// Returns `EngineError::ToolError` if the output does not match the
+    /// declared output schema.
+    pub async fn call(&self, function: &str, input: &[u8]) -> Result<Vec<u8>, EngineError> {
+        Ok(self.call_checked(function, input, true).await?.output)
+    }
+
+    /// Like `call`, but also report the fuel the call consumed
+    ///
+    /// Useful for profiling plugins and tuning `WasmRuntime::set_fuel_limit`.
+    /// The figure is for the successful attempt only if the call was retried.
+    pub async fn call_metered(
+        &self,
+        function: &str,
+        input: &[u8],
+    ) -> Result<MeteredOutput, EngineError> {
+        self.call_checked(function, input, true).await
+    }
+
+    /// Whether the plugin exports `function`
+    pub async fn exports(&self, function: &str) -> Result<bool, EngineError> {
+        Ok(self.checkout(true).await?.function_exists(function))
+    }
+
+    /// Call a function and validate its output
+    ///
+    /// With `wait` unset, fails instead of waiting for a free instance.
+    async fn call_checked(
+        &self,
+        function: &str,
+        input: &[u8],
+        wait: bool,
+    ) -> Result<MeteredOutput, EngineError> {
+        let result = self.call_instance(function, input, wait).await?;
+
+        // A schema mismatch is a plugin bug, not a crash: the instance is fine
+        if let Some(schema) = self.output_schemas.get(function) {
+            validate_output(&self.name, function, schema, &result.output)?;
+        }
+
+        Ok(result)
+    }
+
+    /// Fuel consumed by the last call on `instance`, logged for profiling
+    fn report_fuel(&self, function: &str, instance: &Plugin) -> Option<u64> {
+        let fuel = instance.fuel_consumed();
+        if let Some(fuel) = fuel {
+            tracing::debug!(
+                "Plugin '{}' function '{}' consumed {} fuel",
+                self.name,
+                function,
+                fuel
+            );
+        }
+        fuel
+    }
+
+    /// Run a call on a pooled instance with crash handling and one retry
+    async fn call_instance(
+        &self,
+        function: &str,
+        input: &[u8],
+        wait: bool,
+    ) -> Result<MeteredOutput, EngineError> {
+        tracing::debug!("Calling plugin '{}' function '{}'", self.name, function);
+
+        // Check if plugin has crashed too many times
+        let crash_count = self.crash_count.load(Ordering::SeqCst);
+        if crash_count >= MAX_CRASH_RESTARTS {
+            tracing::error!(
+                "Plugin '{}' has crashed {} times, refusing to call",
+                self.name,
+                crash_count
+            );
+            return Err(EngineError::Plugin(format!(
+                "Plugin '{}' has crashed too many times ({} crashes)",
+                self.name, crash_count
+            )));
+        }
+
+        // Attempt to call the plugin function on an exclusive instance
+        let mut instance = self.checkout(wait).await?;
+        let result = instance
+            .call::<&[u8], Vec<u8>>(function, input)
+            .map_err(|e| {
+                tracing::error!(
+                    "Plugin '{}' function '{}' failed: {}",
+                    self.name,
+                    function,
+                    e
+                );
+                EngineError::Plugin(format!("Plugin call failed: {}", e))
+            });
+
+        match result {
+            Ok(output) => {
+                // Success - reset crash count on successful call
+                let previous = self.crash_count.swap(0, Ordering::SeqCst);
+                if previous > 0 {
+                    tracing::info!(
+                        "Plugin '{}' recovered after {} crashes",
+                        self.name,
+                        previous
+                    );
+                }
+                Ok(MeteredOutput {
+                    fuel_consumed: self.report_fuel(function, &instance),
+                    output,
+                })
+            }
+            Err(e) => {
+                // Plugin call failed - never hand this instance out again
+                instance.discard();
+                self.handle_crash(&e).await?;
+
+                // Retry once on a fresh instance
+                tracing::info!(
+                    "Retrying plugin '{}' function '{}' after restart",
+                    self.name,
+                    function
+                );
+                let mut instance = self.checkout(wait).await?;
+                let result = instance
+                    .call::<&[u8], Vec<u8>>(function, input)
+                    .map_err(|e| {
+                        tracing::error!(
+                            "Plugin '{}' function '{}' failed again after restart: {}",
+                            self.name,
+                            function,
+                            e
+                        );
+                        EngineError::Plugin(format!("Plugin call failed after restart: {}", e))
+                    });
+                match result {
+                    Ok(output) => Ok(MeteredOutput {
+                        fuel_consumed: self.report_fuel(function, &instance),
+                        output,
+                    }),
+                    Err(e) => {
+                        instance.discard();
+                        Err(e)
+                    }
+                }
+            }
+        }
+    }
+
+    /// Check out an instance from the pool
+    ///
+    /// Nested calls from a `call_plugin` host function pass `wait: false`:
+    /// their caller blocks this thread and may itself hold the instance
+    /// being waited for.
+    async fn checkout(&self, wait: bool) -> Result<PooledInstance<'_, Plugin>, EngineError> {
+        if wait {
+            return self.pool.checkout().await;
+        }
+        self.pool.try_checkout()?.ok_or_else(|| {
+            EngineError::Plugin(format!(
+                "Plugin '{}' has no free instance for a nested call",
+                self.name
+            ))
+        })
+    }
+
+    /// Record a crash, publish a PluginCrashed event, and check the restart limit
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the plugin has crashed MAX_CRASH_RESTARTS times.
+    async fn handle_crash(&self, error: &EngineError) -> Result<(), EngineError> {
+        let crash_count = self.crash_count.fetch_add(1, Ordering::SeqCst) + 1;
+
+        tracing::error!(
+            "Plugin '{}' crashed (crash #{}/{}): {}",
+            self.name,
+            crash_count,
+            MAX_CRASH_RESTARTS,
+            error
+        );
+
+        // Publish crash event to message bus
+        if let Some(bus) = &self.message_bus {
+            let event = Event::PluginCrashed {
+                plugin_id: self.name.clone(),
+                error: format!("Crash #{}: {}", crash_count, error),
+            };
+            bus.publish(event).await;
+        }
+
+        // Check if we should attempt restart
+        if crash_count >= MAX_CRASH_RESTARTS {
+            tracing::error!(
+                "Plugin '{}' has reached maximum crash limit ({}), will not restart",
+                self.name,
+                MAX_CRASH_RESTARTS
+            );
+            return Err(EngineError::Plugin(format!(
+                "Plugin '{}' has crashed {} times and will not be restarted",
+                self.name, MAX_CRASH_RESTARTS
+            )));
+        }
+
+        tracing::warn!(
+            "Replacing crashed instance of plugin '{}' (crash #{}/{})",
+            self.name,
+            crash_count,
+            MAX_CRASH_RESTARTS
+        );
+        Ok(())
+    }
+}
+
 impl Drop for WasmRuntime {
     /// Ensure all plugins are properly unloaded when the runtime is dropped
     fn drop(&mut self) {
@@ -746,6 +1787,27 @@ mod tests {
     // Note: These tests require a valid manifest and crypto setup
     // They are primarily for documentation and will be expanded with integration tests
 
+    #[test]
+    fn test_network_permission_gates_allowed_hosts() {
+        let sandboxed = PluginPermissions::default();
+        let networked = PluginPermissions {
+            network: true,
+            ..PluginPermissions::default()
+        };
+        let restricted = EgressPolicy::allow_only(["api.github.com"]);
+
+        assert!(allowed_hosts(&sandboxed, &EgressPolicy::default()).is_empty());
+        assert!(allowed_hosts(&sandboxed, &restricted).is_empty());
+        assert_eq!(
+            allowed_hosts(&networked, &EgressPolicy::default()),
+            vec!["*"]
+        );
+        assert_eq!(
+            allowed_hosts(&networked, &restricted),
+            vec!["api.github.com"]
+        );
+    }
+
     #[test]
     fn test_wasm_runtime_creation() {
         // Create a minimal manifest for testing
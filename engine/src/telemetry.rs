@@ -3,9 +3,23 @@
 //! Handles setting up `tracing-subscriber` for structured logging.
 //! Supports config-driven log levels, environment variable overrides,
 //! and format switching between pretty (debug) and JSON (release).
+//!
+//! With `[telemetry] log_file` set, logs are also written to that file.
+//! The file is rolled over by size or daily: `rove.log` is renamed to
+//! `rove.log.1`, earlier files move up by one, and files beyond
+//! `max_files` are deleted, so a long-running daemon uses bounded disk space.
 
+use crate::config::{LogRotation, TelemetryConfig};
+use chrono::{DateTime, Local, NaiveDate};
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
 use tracing_subscriber::{fmt, layer::SubscriberExt, util::SubscriberInitExt, EnvFilter};
 
+/// Log file shared by the subscriber's file layer, opened by `init_log_file`
+static LOG_FILE: Mutex<Option<RotatingFileWriter>> = Mutex::new(None);
+
 /// Initialize the tracing subscriber with the given log level from config.
 ///
 /// Priority: `RUST_LOG` env var > `log_level` parameter > default "info"
@@ -23,6 +37,7 @@ pub fn init_telemetry_with_level(log_level: &str) {
         tracing_subscriber::registry()
             .with(env_filter)
             .with(fmt::layer().pretty().with_target(false))
+            .with(fmt::layer().with_ansi(false).with_writer(|| LogFile))
             .try_init()
             .ok();
     }
@@ -32,6 +47,12 @@ pub fn init_telemetry_with_level(log_level: &str) {
         tracing_subscriber::registry()
             .with(env_filter)
             .with(fmt::layer().json().with_current_span(true))
+            .with(
+                fmt::layer()
+                    .json()
+                    .with_current_span(true)
+                    .with_writer(|| LogFile),
+            )
             .try_init()
             .ok();
     }
@@ -44,3 +65,225 @@ pub fn init_telemetry_with_level(log_level: &str) {
 pub fn init_telemetry() {
     init_telemetry_with_level("info");
 }
+
+/// Start (or stop) writing logs to the file configured in `config`
+///
+/// # Errors
+///
+/// Returns an error if the log file can't be opened.
+pub fn init_log_file(config: &TelemetryConfig) -> io::Result<()> {
+    let writer = match config.log_file {
+        Some(ref path) => Some(RotatingFileWriter::open(
+            path,
+            config.rotation,
+            max_size_bytes(config),
+            config.max_files,
+        )?),
+        None => None,
+    };
+    *LOG_FILE.lock().unwrap_or_else(|e| e.into_inner()) = writer;
+    Ok(())
+}
+
+/// Rollover threshold of `config` in bytes; a size too large to count in
+/// bytes means the file never rolls over by size
+fn max_size_bytes(config: &TelemetryConfig) -> u64 {
+    config.max_size_mb.saturating_mul(1024 * 1024)
+}
+
+/// Writer handed to the file layer; discards output until a log file is open
+struct LogFile;
+
+impl Write for LogFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match LOG_FILE.lock().unwrap_or_else(|e| e.into_inner()).as_mut() {
+            Some(writer) => writer.write(buf),
+            None => Ok(buf.len()),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match LOG_FILE.lock().unwrap_or_else(|e| e.into_inner()).as_mut() {
+            Some(writer) => writer.flush(),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Log file that rolls over by size or by day
+#[derive(Debug)]
+pub struct RotatingFileWriter {
+    path: PathBuf,
+    rotation: LogRotation,
+    max_bytes: u64,
+    max_files: usize,
+    file: File,
+    size: u64,
+    opened_on: NaiveDate,
+}
+
+impl RotatingFileWriter {
+    /// Open `path` for appending, creating it and its directory if needed
+    ///
+    /// With `LogRotation::Size` the file rolls over before a write would take
+    /// it past `max_bytes`. `max_files` rolled-over files are kept.
+    pub fn open(
+        path: &Path,
+        rotation: LogRotation,
+        max_bytes: u64,
+        max_files: usize,
+    ) -> io::Result<Self> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        let metadata = file.metadata()?;
+        let opened_on = metadata
+            .modified()
+            .map(|modified| DateTime::<Local>::from(modified).date_naive())
+            .unwrap_or_else(|_| Local::now().date_naive());
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            rotation,
+            max_bytes,
+            max_files,
+            file,
+            size: metadata.len(),
+            opened_on,
+        })
+    }
+
+    /// Path of the `n`th most recent rolled-over file, e.g. `rove.log.1`
+    pub fn rotated_path(&self, n: usize) -> PathBuf {
+        let mut name = self.path.file_name().unwrap_or_default().to_os_string();
+        name.push(format!(".{}", n));
+        self.path.with_file_name(name)
+    }
+
+    fn needs_rollover(&self, incoming: usize, today: NaiveDate) -> bool {
+        match self.rotation {
+            LogRotation::Size => self.size > 0 && self.size + incoming as u64 > self.max_bytes,
+            LogRotation::Daily => today != self.opened_on,
+        }
+    }
+
+    /// Shift the rolled-over files up by one, delete those beyond
+    /// `max_files`, and start a new file
+    fn roll_over(&mut self, today: NaiveDate) -> io::Result<()> {
+        self.file.flush()?;
+
+        self.prune()?;
+        for n in (1..self.max_files).rev() {
+            let from = self.rotated_path(n);
+            if from.exists() {
+                fs::rename(from, self.rotated_path(n + 1))?;
+            }
+        }
+        if self.max_files > 0 {
+            fs::rename(&self.path, self.rotated_path(1))?;
+        }
+
+        self.file = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .truncate(true)
+            .open(&self.path)?;
+        self.size = 0;
+        self.opened_on = today;
+        Ok(())
+    }
+
+    /// Delete the rolled-over files that would be shifted past `max_files`
+    fn prune(&self) -> io::Result<()> {
+        let dir = match self.path.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent,
+            _ => Path::new("."),
+        };
+        let prefix = format!(
+            "{}.",
+            self.path.file_name().unwrap_or_default().to_string_lossy()
+        );
+        for entry in fs::read_dir(dir)? {
+            let entry = entry?;
+            let n = entry
+                .file_name()
+                .to_str()
+                .and_then(|name| name.strip_prefix(&prefix))
+                .and_then(|n| n.parse::<usize>().ok());
+            if n.is_some_and(|n| n >= self.max_files.max(1)) {
+                fs::remove_file(entry.path())?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Write for RotatingFileWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let today = Local::now().date_naive();
+        if self.needs_rollover(buf.len(), today) {
+            self.roll_over(today)?;
+        }
+        let written = self.file.write(buf)?;
+        self.size += written as u64;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rollover_at_size_threshold() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("logs").join("rove.log");
+        let mut writer = RotatingFileWriter::open(&path, LogRotation::Size, 10, 3).unwrap();
+
+        writer.write_all(b"12345\n").unwrap();
+        writer.write_all(b"678\n").unwrap();
+        assert!(!writer.rotated_path(1).exists());
+
+        // 10 bytes written, the next line would cross the threshold
+        writer.write_all(b"abc\n").unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "abc\n");
+        assert_eq!(
+            fs::read_to_string(writer.rotated_path(1)).unwrap(),
+            "12345\n678\n"
+        );
+    }
+
+    #[test]
+    fn test_max_size_does_not_overflow() {
+        let config = |max_size_mb| TelemetryConfig {
+            max_size_mb,
+            ..TelemetryConfig::default()
+        };
+        assert_eq!(max_size_bytes(&config(10)), 10 * 1024 * 1024);
+        assert_eq!(max_size_bytes(&config(u64::MAX)), u64::MAX);
+    }
+
+    #[test]
+    fn test_rollover_prunes_beyond_retention_count() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("rove.log");
+        // Left over from a run that kept more files
+        fs::write(dir.path().join("rove.log.4"), "old").unwrap();
+        let mut writer = RotatingFileWriter::open(&path, LogRotation::Size, 4, 2).unwrap();
+
+        for line in ["one\n", "two\n", "six\n", "ten\n"] {
+            writer.write_all(line.as_bytes()).unwrap();
+        }
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "ten\n");
+        assert_eq!(fs::read_to_string(writer.rotated_path(1)).unwrap(), "six\n");
+        assert_eq!(fs::read_to_string(writer.rotated_path(2)).unwrap(), "two\n");
+        assert!(!writer.rotated_path(3).exists());
+        assert!(!writer.rotated_path(4).exists());
+    }
+}
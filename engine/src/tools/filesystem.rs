@@ -2,31 +2,137 @@
 //!
 //! Native filesystem operations for reading and writing files within the workspace.
 //! All paths are validated through `FileSystemGuard` (4-gate security) before any I/O.
+//! `find_files` answers from a `WorkspaceIndex` when one is attached and walks
+//! the workspace otherwise.
+//!
+//! `list_dir`, `find_files`, and `search_content` skip entries matched by
+//! `.gitignore` and `DEFAULT_IGNORED_DIRS` unless `include_ignored` is set.
+//!
+//! `read_files` reads a batch of files in one call. Each path is validated on
+//! its own, so a denied path fails alone, and the contents share one
+//! `max_total_bytes` budget (at most the guard's read limit).
+//!
+//! `tail_file` returns the last lines of a file by reading backwards from its
+//! end, so a large log costs only the bytes of the lines returned.
+//!
+//! `write_file_with` can append instead of overwriting, or write atomically:
+//! the content goes to a temporary file next to the target, which is then
+//! renamed over it, so a crash mid-write never leaves a partial file. The
+//! replaced file's permissions are kept.
+//!
+//! A tool built `with_stdin` can write a file from that stream instead of a
+//! JSON string (`write_file_from_stdin`), e.g. content piped into `rove run`.
+//! The registry only allows this for local tasks.
 
 use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::ffi::OsString;
 use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
 use tokio::fs;
+use tokio::io::AsyncWriteExt;
 use tracing::{debug, info, warn};
 
 use crate::fs_guard::FileSystemGuard;
+use crate::tools::index::{scan_files, workspace_walker, WorkspaceIndex};
+
+/// Maximum number of paths returned by `find_files`
+const MAX_FIND_RESULTS: usize = 200;
+
+/// Maximum number of matching lines returned by `search_content`
+const MAX_SEARCH_RESULTS: usize = 100;
+
+/// Files larger than this are skipped by `search_content`
+const MAX_SEARCH_FILE_SIZE: u64 = 1024 * 1024;
+
+/// Bytes `tail_file` reads per step backwards from the end of a file
+const TAIL_CHUNK_SIZE: u64 = 8 * 1024;
+
+/// How `write_file_with` treats an existing file
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WriteMode {
+    /// Replace the file's contents
+    #[default]
+    Overwrite,
+    /// Add to the end of the file
+    Append,
+}
+
+/// Options of `write_file_with`; the default overwrites in place
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct WriteOptions {
+    /// Overwrite or append
+    pub mode: WriteMode,
+    /// Write to a temporary file and rename it over the target
+    pub atomic: bool,
+}
+
+/// Stream `write_file_from_stdin` copies into files
+pub type StdinSource = Arc<Mutex<Box<dyn std::io::Read + Send>>>;
 
-#[derive(Debug)]
 pub struct FilesystemTool {
-    guard: FileSystemGuard,
+    guard: Arc<FileSystemGuard>,
+    index: Option<Arc<WorkspaceIndex>>,
+    stdin: Option<StdinSource>,
+}
+
+impl std::fmt::Debug for FilesystemTool {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FilesystemTool")
+            .field("guard", &self.guard)
+            .field("index", &self.index)
+            .field("stdin", &self.stdin.is_some())
+            .finish()
+    }
 }
 
 impl FilesystemTool {
     pub fn new(workspace: PathBuf) -> Self {
         Self {
-            guard: FileSystemGuard::new(workspace),
+            guard: Arc::new(FileSystemGuard::new(workspace)),
+            index: None,
+            stdin: None,
         }
     }
 
+    /// Let `write_file_from_stdin` write the contents of `stdin`
+    pub fn with_stdin(mut self, stdin: Box<dyn std::io::Read + Send>) -> Self {
+        self.stdin = Some(Arc::new(Mutex::new(stdin)));
+        self
+    }
+
+    /// Whether a stream for `write_file_from_stdin` is attached
+    pub fn has_stdin(&self) -> bool {
+        self.stdin.is_some()
+    }
+
+    /// Answer `find_files` from a workspace index instead of walking the tree
+    pub fn with_index(mut self, index: Arc<WorkspaceIndex>) -> Self {
+        self.index = Some(index);
+        self
+    }
+
+    /// Raise the guard's read limit (see `FileSystemGuard::with_max_read_size`)
+    pub fn with_max_read_size(mut self, bytes: u64) -> Self {
+        self.guard = Arc::new((*self.guard).clone().with_max_read_size(bytes));
+        self
+    }
+
     /// Read the contents of a file within the workspace.
+    ///
+    /// Files larger than the guard's read limit are refused.
     pub async fn read_file(&self, path: &str) -> Result<String> {
         let path = self.resolve_path(path)?;
         info!("Reading file: {}", path.display());
 
+        let size = fs::metadata(&path)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to read {}: {}", path.display(), e))?
+            .len();
+        self.guard.check_read_size(&path, size)?;
+
         let content = fs::read_to_string(&path)
             .await
             .map_err(|e| anyhow::anyhow!("Failed to read {}: {}", path.display(), e))?;
@@ -35,9 +141,129 @@ impl FilesystemTool {
         Ok(content)
     }
 
+    /// Read several files within the workspace in one call.
+    ///
+    /// Every path gets an entry in the result, holding its content or the
+    /// error that path failed with. The contents together are capped at
+    /// `max_total_bytes` (default and upper bound: the guard's read limit);
+    /// the file that crosses the cap is cut short, later files are not read,
+    /// and `truncated` is set.
+    pub async fn read_files(&self, paths: &[String], max_total_bytes: Option<u64>) -> ReadBatch {
+        let limit = max_total_bytes
+            .unwrap_or(u64::MAX)
+            .min(self.guard.max_read_size());
+        let mut remaining = limit as usize;
+        let mut batch = ReadBatch::default();
+
+        for path in paths {
+            let entry = if batch.truncated {
+                BatchEntry::Error(format!("Not read: max_total_bytes ({}) reached", limit))
+            } else {
+                match self.read_file(path).await {
+                    Ok(mut content) => {
+                        if content.len() > remaining {
+                            content.truncate(floor_char_boundary(&content, remaining));
+                            remaining = 0;
+                            batch.truncated = true;
+                        } else {
+                            remaining -= content.len();
+                        }
+                        BatchEntry::Content(content)
+                    }
+                    Err(e) => BatchEntry::Error(e.to_string()),
+                }
+            };
+            batch.files.insert(path.clone(), entry);
+        }
+
+        debug!(
+            "Read {} file(s), {} of {} bytes used",
+            paths.len(),
+            limit as usize - remaining,
+            limit
+        );
+        batch
+    }
+
     /// Write content to a file within the workspace.
     /// Creates parent directories if they don't exist.
     pub async fn write_file(&self, path: &str, content: &str) -> Result<String> {
+        self.write_file_with(path, content, WriteOptions::default())
+            .await
+    }
+
+    /// Write content to a file within the workspace, appending or writing
+    /// atomically as `options` say.
+    ///
+    /// An atomic write can't append: the temporary file replaces the target.
+    pub async fn write_file_with(
+        &self,
+        path: &str,
+        content: &str,
+        options: WriteOptions,
+    ) -> Result<String> {
+        if options.atomic && options.mode == WriteMode::Append {
+            return Err(anyhow::anyhow!("Atomic writes cannot append"));
+        }
+        let validated = self.resolve_write_path(path).await?;
+
+        info!(
+            "Writing {} bytes to: {} ({:?}{})",
+            content.len(),
+            validated.display(),
+            options.mode,
+            if options.atomic { ", atomic" } else { "" }
+        );
+
+        let result = match options.mode {
+            WriteMode::Overwrite if options.atomic => write_atomic(&validated, content).await,
+            WriteMode::Overwrite => fs::write(&validated, content).await,
+            WriteMode::Append => append(&validated, content).await,
+        };
+        result.map_err(|e| anyhow::anyhow!("Failed to write {}: {}", validated.display(), e))?;
+
+        let verb = match options.mode {
+            WriteMode::Overwrite => "Wrote",
+            WriteMode::Append => "Appended",
+        };
+        Ok(format!(
+            "{} {} bytes to {}",
+            verb,
+            content.len(),
+            validated.display()
+        ))
+    }
+
+    /// Write the rest of the attached stdin stream to a file within the
+    /// workspace, as `write_file` does with a string.
+    ///
+    /// The stream is copied straight to the file, so its size doesn't matter.
+    /// It can be consumed only once; a later call writes an empty file.
+    pub async fn write_file_from_stdin(&self, path: &str) -> Result<String> {
+        let Some(stdin) = self.stdin.clone() else {
+            return Err(anyhow::anyhow!("No stdin is attached to write from"));
+        };
+        let validated = self.resolve_write_path(path).await?;
+        info!("Writing stdin to: {}", validated.display());
+
+        let target = validated.clone();
+        let written = tokio::task::spawn_blocking(move || -> std::io::Result<u64> {
+            let mut stdin = stdin.lock().unwrap_or_else(|e| e.into_inner());
+            let mut file = std::fs::File::create(&target)?;
+            std::io::copy(&mut *stdin, &mut file)
+        })
+        .await?
+        .map_err(|e| anyhow::anyhow!("Failed to write {}: {}", validated.display(), e))?;
+
+        Ok(format!(
+            "Wrote {} bytes to {}",
+            written,
+            validated.display()
+        ))
+    }
+
+    /// Validate the target of a write, creating its parent directories.
+    async fn resolve_write_path(&self, path: &str) -> Result<PathBuf> {
         let target = PathBuf::from(path);
 
         // For new files that don't exist yet, validate the full target path
@@ -75,29 +301,111 @@ impl FilesystemTool {
             }
             abs
         };
+        Ok(validated)
+    }
 
-        info!(
-            "Writing {} bytes to: {}",
-            content.len(),
-            validated.display()
-        );
+    /// Delete a file within the workspace.
+    ///
+    /// A directory is only deleted, with everything in it, if `recursive`
+    /// is set. The workspace itself is never deleted. A symlink is removed
+    /// itself; the file it points to is left alone.
+    pub async fn delete_file(&self, path: &str, recursive: bool) -> Result<String> {
+        let validated = self.resolve_entry(path)?;
+        if validated == self.guard.workspace() {
+            return Err(anyhow::anyhow!("Refusing to delete the workspace"));
+        }
 
-        fs::write(&validated, content)
+        let metadata = fs::symlink_metadata(&validated)
             .await
-            .map_err(|e| anyhow::anyhow!("Failed to write {}: {}", validated.display(), e))?;
+            .map_err(|e| anyhow::anyhow!("Failed to read {}: {}", validated.display(), e))?;
+        let result = if metadata.is_dir() {
+            if !recursive {
+                return Err(anyhow::anyhow!(
+                    "{} is a directory; set recursive to delete it and its contents",
+                    validated.display()
+                ));
+            }
+            fs::remove_dir_all(&validated).await
+        } else {
+            fs::remove_file(&validated).await
+        };
+        result.map_err(|e| anyhow::anyhow!("Failed to delete {}: {}", validated.display(), e))?;
 
-        Ok(format!(
-            "Wrote {} bytes to {}",
-            content.len(),
-            validated.display()
-        ))
+        info!("Deleted: {}", validated.display());
+        Ok(format!("Deleted {}", validated.display()))
+    }
+
+    /// Create a directory within the workspace.
+    ///
+    /// Missing parent directories are created only if `recursive` is set.
+    /// An existing directory is not an error.
+    pub async fn create_dir(&self, path: &str, recursive: bool) -> Result<String> {
+        let target = Path::new(path);
+        // `..` would let the created directories climb out of the ancestor
+        // validated below
+        if target
+            .components()
+            .any(|c| matches!(c, std::path::Component::ParentDir))
+        {
+            return Err(anyhow::anyhow!(
+                "Refusing to create {}: '..' is not allowed",
+                target.display()
+            ));
+        }
+        let abs = if target.is_absolute() {
+            target.to_path_buf()
+        } else {
+            self.guard.workspace().join(target)
+        };
+        self.guard.check_denied(&abs).map_err(|e| {
+            warn!("Path denied for new directory {}: {}", abs.display(), e);
+            anyhow::anyhow!("{}", e)
+        })?;
+
+        // The closest existing ancestor decides where the directory ends up
+        let existing = abs
+            .ancestors()
+            .find(|ancestor| ancestor.exists())
+            .ok_or_else(|| anyhow::anyhow!("Invalid path {}", abs.display()))?;
+        self.guard.validate_path(existing).map_err(|e| {
+            warn!("Path validation failed for {}: {}", existing.display(), e);
+            anyhow::anyhow!("{}", e)
+        })?;
+        if existing == abs {
+            if abs.is_dir() {
+                return Ok(format!("Directory {} already exists", abs.display()));
+            }
+            return Err(anyhow::anyhow!(
+                "{} exists and is not a directory",
+                abs.display()
+            ));
+        }
+
+        let result = if recursive {
+            fs::create_dir_all(&abs).await
+        } else {
+            fs::create_dir(&abs).await
+        };
+        result.map_err(|e| anyhow::anyhow!("Failed to create {}: {}", abs.display(), e))?;
+
+        info!("Created directory: {}", abs.display());
+        Ok(format!("Created directory {}", abs.display()))
     }
 
     /// List files and directories at the given path within the workspace.
-    pub async fn list_dir(&self, path: &str) -> Result<String> {
+    ///
+    /// Ignored entries are left out unless `include_ignored` is set.
+    pub async fn list_dir(&self, path: &str, include_ignored: bool) -> Result<String> {
         let path = self.resolve_path(path)?;
         info!("Listing directory: {}", path.display());
 
+        let visible = if include_ignored {
+            None
+        } else {
+            let dir = path.clone();
+            Some(tokio::task::spawn_blocking(move || visible_entries(&dir)).await?)
+        };
+
         let mut entries = fs::read_dir(&path)
             .await
             .map_err(|e| anyhow::anyhow!("Failed to read directory {}: {}", path.display(), e))?;
@@ -107,6 +415,11 @@ impl FilesystemTool {
         let mut links = Vec::new();
 
         while let Some(entry) = entries.next_entry().await? {
+            if let Some(ref visible) = visible {
+                if !visible.contains(&entry.file_name()) {
+                    continue;
+                }
+            }
             let name = entry.file_name().to_string_lossy().to_string();
             let ft = entry.file_type().await?;
             if ft.is_dir() {
@@ -135,6 +448,46 @@ impl FilesystemTool {
         Ok(out.join("\n"))
     }
 
+    /// Return the last `lines` lines of a file within the workspace.
+    ///
+    /// The file is read backwards from its end, one `TAIL_CHUNK_SIZE` chunk
+    /// at a time, until enough lines are found. A missing trailing newline
+    /// still counts as the end of the last line; a file with fewer lines is
+    /// returned whole. The lines returned must fit the guard's read limit.
+    pub async fn tail_file(&self, path: &str, lines: usize) -> Result<TailResult> {
+        let path = self.resolve_path(path)?;
+        info!("Tailing {} lines of {}", lines, path.display());
+
+        let read_path = path.clone();
+        let guard = Arc::clone(&self.guard);
+        let (offset, bytes) = tokio::task::spawn_blocking(move || -> Result<(u64, Vec<u8>)> {
+            let failed = |e: std::io::Error| {
+                anyhow::anyhow!("Failed to read {}: {}", read_path.display(), e)
+            };
+            let mut file = std::fs::File::open(&read_path).map_err(failed)?;
+            let (start, len) = tail_offset(&mut file, lines).map_err(failed)?;
+            // Checked before reading, so a large `lines` can't pull a whole
+            // log into memory
+            guard.check_read_size(&read_path, len - start)?;
+            let bytes = read_range(&mut file, start, len).map_err(failed)?;
+            Ok((start, bytes))
+        })
+        .await??;
+
+        let content = String::from_utf8_lossy(&bytes).into_owned();
+        debug!(
+            "Read {} bytes from offset {} of {}",
+            bytes.len(),
+            offset,
+            path.display()
+        );
+        Ok(TailResult {
+            lines: content.lines().count(),
+            content,
+            offset,
+        })
+    }
+
     /// Check if a file or directory exists within the workspace.
     pub async fn file_exists(&self, path: &str) -> Result<bool> {
         match self.resolve_path(path) {
@@ -143,6 +496,140 @@ impl FilesystemTool {
         }
     }
 
+    /// Find files whose name matches `pattern`.
+    ///
+    /// `pattern` supports `*` and `?` wildcards. It is matched against the file
+    /// name, or against the relative path when it contains `/`. A pattern
+    /// without wildcards matches any name containing it. The index only holds
+    /// non-ignored files, so `include_ignored` always walks the workspace.
+    pub async fn find_files(&self, pattern: &str, include_ignored: bool) -> Result<String> {
+        info!("Finding files matching: {}", pattern);
+
+        let files = match &self.index {
+            Some(index) if !include_ignored => {
+                let index = Arc::clone(index);
+                tokio::task::spawn_blocking(move || index.files()).await?
+            }
+            _ => {
+                let guard = Arc::clone(&self.guard);
+                tokio::task::spawn_blocking(move || {
+                    scan_files(&guard, guard.workspace(), include_ignored)
+                        .into_iter()
+                        .map(|(path, _)| path)
+                        .collect::<Vec<_>>()
+                })
+                .await?
+            }
+        };
+
+        let mut matches: Vec<String> = files
+            .iter()
+            .filter(|path| matches_pattern(path, pattern))
+            .map(|path| path.to_string_lossy().to_string())
+            .collect();
+        matches.sort();
+
+        if matches.is_empty() {
+            return Ok(format!("No files matching '{}'", pattern));
+        }
+
+        let total = matches.len();
+        matches.truncate(MAX_FIND_RESULTS);
+        let mut out = vec![format!("{} file(s) matching '{}'", total, pattern)];
+        out.extend(matches);
+        if total > MAX_FIND_RESULTS {
+            out.push(format!("... {} more not shown", total - MAX_FIND_RESULTS));
+        }
+        Ok(out.join("\n"))
+    }
+
+    /// Search workspace files for lines containing `query`.
+    ///
+    /// Returns `path:line: text` for each match. Binary files and files over
+    /// `MAX_SEARCH_FILE_SIZE` are skipped.
+    pub async fn search_content(&self, query: &str, include_ignored: bool) -> Result<String> {
+        info!("Searching files for: {}", query);
+        if query.is_empty() {
+            return Err(anyhow::anyhow!("Search query must not be empty"));
+        }
+
+        let guard = Arc::clone(&self.guard);
+        let needle = query.to_string();
+        let (matches, total) = tokio::task::spawn_blocking(move || {
+            let mut files = scan_files(&guard, guard.workspace(), include_ignored);
+            files.sort();
+
+            let mut matches = Vec::new();
+            let mut total = 0;
+            for (relative, _) in files {
+                let path = guard.workspace().join(&relative);
+                let too_big = std::fs::metadata(&path)
+                    .map(|m| m.len() > MAX_SEARCH_FILE_SIZE)
+                    .unwrap_or(true);
+                if too_big {
+                    debug!("Skipping {} in search", relative.display());
+                    continue;
+                }
+                let Ok(content) = std::fs::read_to_string(&path) else {
+                    continue;
+                };
+                for (number, line) in content.lines().enumerate() {
+                    if line.contains(&needle) {
+                        total += 1;
+                        if matches.len() < MAX_SEARCH_RESULTS {
+                            matches.push(format!(
+                                "{}:{}: {}",
+                                relative.display(),
+                                number + 1,
+                                line.trim()
+                            ));
+                        }
+                    }
+                }
+            }
+            (matches, total)
+        })
+        .await?;
+
+        if matches.is_empty() {
+            return Ok(format!("No matches for '{}'", query));
+        }
+
+        let mut out = vec![format!("{} match(es) for '{}'", total, query)];
+        out.extend(matches);
+        if total > MAX_SEARCH_RESULTS {
+            out.push(format!("... {} more not shown", total - MAX_SEARCH_RESULTS));
+        }
+        Ok(out.join("\n"))
+    }
+
+    /// Resolve and validate a path without following a symlink at its end.
+    ///
+    /// The parent directory goes through the FileSystemGuard and the last
+    /// component is joined back unresolved, so a symlink names the link.
+    fn resolve_entry(&self, path: &str) -> Result<PathBuf> {
+        let target = Path::new(path);
+        let abs = if target.is_absolute() {
+            target.to_path_buf()
+        } else {
+            self.guard.workspace().join(target)
+        };
+        let (Some(parent), Some(name)) = (abs.parent(), abs.file_name()) else {
+            return self.resolve_path(path);
+        };
+
+        let parent = self.guard.validate_path(parent).map_err(|e| {
+            warn!("Path validation failed for {}: {}", abs.display(), e);
+            anyhow::anyhow!("{}", e)
+        })?;
+        let entry = parent.join(name);
+        self.guard.check_denied(&entry).map_err(|e| {
+            warn!("Path denied for {}: {}", entry.display(), e);
+            anyhow::anyhow!("{}", e)
+        })?;
+        Ok(entry)
+    }
+
     /// Resolve and validate a path through the FileSystemGuard.
     fn resolve_path(&self, path: &str) -> Result<PathBuf> {
         let target = Path::new(path);
@@ -159,6 +646,191 @@ impl FilesystemTool {
     }
 }
 
+/// Result of a `read_files` call, returned to the LLM as JSON
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ReadBatch {
+    /// Content or error of each requested path
+    pub files: BTreeMap<String, BatchEntry>,
+    /// True when the contents hit `max_total_bytes` and were cut short
+    pub truncated: bool,
+}
+
+/// One path of a `read_files` call: `{"content": ...}` or `{"error": ...}`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BatchEntry {
+    Content(String),
+    Error(String),
+}
+
+/// Result of a `tail_file` call, returned to the LLM as JSON
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TailResult {
+    /// The last lines of the file
+    pub content: String,
+    /// Byte offset in the file where `content` starts
+    pub offset: u64,
+    /// Number of lines in `content`
+    pub lines: usize,
+}
+
+/// Find where the last `lines` lines of `file` start, returning that
+/// offset and the file's length
+fn tail_offset<F: std::io::Read + std::io::Seek>(
+    file: &mut F,
+    lines: usize,
+) -> std::io::Result<(u64, u64)> {
+    use std::io::SeekFrom;
+
+    let len = file.seek(SeekFrom::End(0))?;
+    if lines == 0 {
+        return Ok((len, len));
+    }
+
+    // Walk back chunk by chunk, counting the newlines that end lines. A
+    // newline as the file's last byte ends the last line, so it is skipped.
+    let mut pos = len;
+    let mut newlines = 0;
+    let mut buf = vec![0; TAIL_CHUNK_SIZE as usize];
+    while pos > 0 {
+        let size = TAIL_CHUNK_SIZE.min(pos);
+        pos -= size;
+        let chunk = &mut buf[..size as usize];
+        file.seek(SeekFrom::Start(pos))?;
+        file.read_exact(chunk)?;
+        for (i, &byte) in chunk.iter().enumerate().rev() {
+            let at = pos + i as u64;
+            if byte == b'\n' && at + 1 != len {
+                newlines += 1;
+                if newlines == lines {
+                    return Ok((at + 1, len));
+                }
+            }
+        }
+    }
+    Ok((0, len))
+}
+
+/// Read the bytes of `file` from `start` up to `end`
+fn read_range<F: std::io::Read + std::io::Seek>(
+    file: &mut F,
+    start: u64,
+    end: u64,
+) -> std::io::Result<Vec<u8>> {
+    use std::io::{Read, SeekFrom};
+
+    let mut bytes = Vec::with_capacity((end - start) as usize);
+    file.seek(SeekFrom::Start(start))?;
+    file.take(end - start).read_to_end(&mut bytes)?;
+    Ok(bytes)
+}
+
+/// Largest index `<= index` that lies on a char boundary of `s`
+fn floor_char_boundary(s: &str, index: usize) -> usize {
+    (0..=index.min(s.len()))
+        .rev()
+        .find(|&i| s.is_char_boundary(i))
+        .unwrap_or(0)
+}
+
+/// Names of the entries directly inside `dir` that survive the ignore rules.
+///
+/// Hidden entries stay visible; only `.gitignore` and `DEFAULT_IGNORED_DIRS`
+/// apply to listings.
+fn visible_entries(dir: &Path) -> HashSet<OsString> {
+    let mut walker = workspace_walker(dir, false);
+    walker.max_depth(Some(1)).hidden(false);
+
+    walker
+        .build()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.depth() == 1)
+        .map(|entry| entry.file_name().to_os_string())
+        .collect()
+}
+
+/// Write `content` to a temporary file next to `path`, then rename it over
+/// `path`. The temporary file is removed if anything fails.
+///
+/// An existing target's permissions carry over to the new file; they are set
+/// before any content is written, so it is never readable more widely.
+async fn write_atomic(path: &Path, content: &str) -> std::io::Result<()> {
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+    let temp = path.with_file_name(format!(".{}.{}.tmp", name, uuid::Uuid::new_v4()));
+    let permissions = match fs::metadata(path).await {
+        Ok(metadata) => Some(metadata.permissions()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+        Err(e) => return Err(e),
+    };
+
+    let result = async {
+        let mut file = fs::File::create(&temp).await?;
+        if let Some(permissions) = permissions {
+            file.set_permissions(permissions).await?;
+        }
+        file.write_all(content.as_bytes()).await?;
+        file.sync_all().await?;
+        fs::rename(&temp, path).await
+    }
+    .await;
+    if result.is_err() {
+        let _ = fs::remove_file(&temp).await;
+    }
+    result
+}
+
+/// Add `content` to the end of `path`, creating it if needed
+async fn append(path: &Path, content: &str) -> std::io::Result<()> {
+    let mut file = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .await?;
+    file.write_all(content.as_bytes()).await?;
+    file.flush().await
+}
+
+/// Match a workspace-relative path against a `find_files` pattern.
+fn matches_pattern(path: &Path, pattern: &str) -> bool {
+    let subject = if pattern.contains('/') {
+        path.to_string_lossy().to_string()
+    } else {
+        path.file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default()
+    };
+
+    if pattern.contains(['*', '?']) {
+        wildcard_match(pattern.as_bytes(), subject.as_bytes())
+    } else {
+        subject.contains(pattern)
+    }
+}
+
+/// Glob-style match supporting `*` (any run) and `?` (any single byte).
+pub(crate) fn wildcard_match(pattern: &[u8], text: &[u8]) -> bool {
+    let (mut p, mut t) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == b'?' || pattern[p] == text[t]) {
+            p += 1;
+            t += 1;
+        } else if p < pattern.len() && pattern[p] == b'*' {
+            backtrack = Some((p, t));
+            p += 1;
+        } else if let Some((star, matched)) = backtrack {
+            p = star + 1;
+            t = matched + 1;
+            backtrack = Some((star, matched + 1));
+        } else {
+            return false;
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == b'*')
+}
+
 /// Format a byte count into a human-readable size string.
 fn format_size(bytes: u64) -> String {
     if bytes < 1024 {
@@ -207,6 +879,24 @@ mod tests {
         assert_eq!(content, "deep content");
     }
 
+    #[tokio::test]
+    async fn test_max_read_size_override() {
+        use crate::fs_guard::{DEFAULT_MAX_READ_SIZE, HARD_MAX_READ_SIZE};
+
+        let (temp, tool) = setup();
+        let log = temp.path().join("big.log");
+        std::fs::write(&log, "x".repeat(DEFAULT_MAX_READ_SIZE as usize + 1)).unwrap();
+        let too_big = temp.path().join("huge.log");
+        std::fs::write(&too_big, "x".repeat(HARD_MAX_READ_SIZE as usize + 1)).unwrap();
+
+        assert!(tool.read_file(log.to_str().unwrap()).await.is_err());
+
+        let tool = tool.with_max_read_size(u64::MAX);
+        let content = tool.read_file(log.to_str().unwrap()).await.unwrap();
+        assert_eq!(content.len() as u64, DEFAULT_MAX_READ_SIZE + 1);
+        assert!(tool.read_file(too_big.to_str().unwrap()).await.is_err());
+    }
+
     #[tokio::test]
     async fn test_read_nonexistent_file() {
         let (temp, tool) = setup();
@@ -222,7 +912,10 @@ mod tests {
         std::fs::write(temp.path().join("b.txt"), "b").unwrap();
         std::fs::create_dir(temp.path().join("subdir")).unwrap();
 
-        let listing = tool.list_dir(temp.path().to_str().unwrap()).await.unwrap();
+        let listing = tool
+            .list_dir(temp.path().to_str().unwrap(), false)
+            .await
+            .unwrap();
         assert!(listing.contains("a.txt"));
         assert!(listing.contains("b.txt"));
         assert!(listing.contains("d  subdir/"));
@@ -270,4 +963,361 @@ mod tests {
             .await;
         assert!(result.is_err());
     }
+
+    #[tokio::test]
+    async fn test_delete_and_create_dir() {
+        let (temp, tool) = setup();
+        std::fs::create_dir_all(temp.path().join("build/out")).unwrap();
+        std::fs::write(temp.path().join("build/out/app"), "binary").unwrap();
+        std::fs::write(temp.path().join("notes.txt"), "notes").unwrap();
+
+        tool.delete_file("notes.txt", false).await.unwrap();
+        assert!(!temp.path().join("notes.txt").exists());
+        assert!(tool.delete_file("build", false).await.is_err());
+        assert!(temp.path().join("build/out/app").exists());
+        tool.delete_file("build", true).await.unwrap();
+        assert!(!temp.path().join("build").exists());
+        assert!(tool.delete_file(".", true).await.is_err());
+
+        assert!(tool.create_dir("a/b/c", false).await.is_err());
+        tool.create_dir("a/b/c", true).await.unwrap();
+        assert!(temp.path().join("a/b/c").is_dir());
+        tool.create_dir("a/b/d", false).await.unwrap();
+        assert!(tool.create_dir("a/b/d", false).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_delete_and_create_dir_denied_paths() {
+        let (temp, tool) = setup();
+        let ssh_dir = temp.path().join(".ssh");
+        std::fs::create_dir(&ssh_dir).unwrap();
+        std::fs::write(ssh_dir.join("id_rsa"), "private key").unwrap();
+
+        assert!(tool.delete_file(".ssh/id_rsa", false).await.is_err());
+        assert!(tool.delete_file(".ssh", true).await.is_err());
+        assert!(ssh_dir.join("id_rsa").exists());
+
+        assert!(tool.create_dir(".ssh/keys", false).await.is_err());
+        assert!(tool.create_dir("config/.ssh", true).await.is_err());
+        assert!(!temp.path().join("config").exists());
+        assert!(tool.create_dir("../outside", false).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_create_dir_rejects_parent_components() {
+        let outer = TempDir::new().unwrap();
+        let workspace = outer.path().join("workspace");
+        std::fs::create_dir(&workspace).unwrap();
+        let tool = FilesystemTool::new(workspace.clone());
+
+        assert!(tool.create_dir("nope/../../escaped", true).await.is_err());
+        assert!(!outer.path().join("escaped").exists());
+        assert!(!workspace.join("nope").exists());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_delete_symlink_keeps_target() {
+        let (temp, tool) = setup();
+        std::fs::write(temp.path().join("target.txt"), "keep me").unwrap();
+        std::fs::create_dir(temp.path().join("dir")).unwrap();
+        std::os::unix::fs::symlink(temp.path().join("target.txt"), temp.path().join("link"))
+            .unwrap();
+        std::os::unix::fs::symlink(temp.path().join("dir"), temp.path().join("dir_link")).unwrap();
+
+        tool.delete_file("link", false).await.unwrap();
+        assert!(std::fs::symlink_metadata(temp.path().join("link")).is_err());
+        assert!(temp.path().join("target.txt").exists());
+
+        // A link to a directory is not a directory
+        tool.delete_file("dir_link", false).await.unwrap();
+        assert!(temp.path().join("dir").is_dir());
+    }
+
+    #[tokio::test]
+    async fn test_read_files_mixed_batch() {
+        let (temp, tool) = setup();
+        std::fs::write(temp.path().join("a.txt"), "alpha").unwrap();
+        std::fs::write(temp.path().join("b.txt"), "beta").unwrap();
+        std::fs::create_dir(temp.path().join(".ssh")).unwrap();
+        std::fs::write(temp.path().join(".ssh/id_rsa"), "private key").unwrap();
+
+        let paths = ["a.txt", ".ssh/id_rsa", "b.txt", "missing.txt"].map(String::from);
+        let batch = tool.read_files(&paths, None).await;
+
+        assert!(!batch.truncated);
+        assert_eq!(batch.files["a.txt"], BatchEntry::Content("alpha".into()));
+        assert_eq!(batch.files["b.txt"], BatchEntry::Content("beta".into()));
+        assert!(matches!(batch.files[".ssh/id_rsa"], BatchEntry::Error(_)));
+        assert!(matches!(batch.files["missing.txt"], BatchEntry::Error(_)));
+        assert_eq!(
+            serde_json::to_value(&batch).unwrap()["files"]["a.txt"],
+            serde_json::json!({"content": "alpha"})
+        );
+    }
+
+    #[tokio::test]
+    async fn test_read_files_total_size_cap() {
+        let (temp, tool) = setup();
+        std::fs::write(temp.path().join("a.txt"), "alpha").unwrap();
+        std::fs::write(temp.path().join("b.txt"), "héllo").unwrap();
+        std::fs::write(temp.path().join("c.txt"), "gamma").unwrap();
+
+        let paths = ["a.txt", "b.txt", "c.txt"].map(String::from);
+        let batch = tool.read_files(&paths, Some(7)).await;
+
+        assert!(batch.truncated);
+        assert_eq!(batch.files["a.txt"], BatchEntry::Content("alpha".into()));
+        // Cut before the two-byte é rather than inside it
+        assert_eq!(batch.files["b.txt"], BatchEntry::Content("h".into()));
+        assert!(matches!(batch.files["c.txt"], BatchEntry::Error(_)));
+    }
+
+    #[test]
+    fn test_tail_offset_edge_cases() {
+        let tail = |text: &str, lines: usize| {
+            let mut file = std::io::Cursor::new(text);
+            let (offset, len) = tail_offset(&mut file, lines).unwrap();
+            let bytes = read_range(&mut file, offset, len).unwrap();
+            (offset, String::from_utf8(bytes).unwrap())
+        };
+
+        assert_eq!(tail("a\nb\nc\n", 2), (2, "b\nc\n".to_string()));
+        // No trailing newline
+        assert_eq!(tail("a\nb\nc", 2), (2, "b\nc".to_string()));
+        // Fewer lines than asked for
+        assert_eq!(tail("a\nb\n", 10), (0, "a\nb\n".to_string()));
+        assert_eq!(tail("", 3), (0, String::new()));
+        assert_eq!(tail("a\nb\n", 0), (4, String::new()));
+        // Blank lines count
+        assert_eq!(tail("a\n\n\n", 2), (2, "\n\n".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_tail_file_spans_chunks() {
+        let (temp, tool) = setup();
+        let log: String = (0..5000).map(|i| format!("request {}\n", i)).collect();
+        std::fs::write(temp.path().join("access.log"), &log).unwrap();
+
+        let tail = tool.tail_file("access.log", 100).await.unwrap();
+
+        assert_eq!(tail.lines, 100);
+        assert!(tail.content.starts_with("request 4900\n"));
+        assert!(tail.content.ends_with("request 4999\n"));
+        assert_eq!(&log[tail.offset as usize..], tail.content);
+        assert!(tool.tail_file(".ssh/id_rsa", 1).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_tail_file_over_read_limit_is_refused() {
+        use crate::fs_guard::DEFAULT_MAX_READ_SIZE;
+
+        let (temp, tool) = setup();
+        let line = "x".repeat(1023) + "\n";
+        let lines = (DEFAULT_MAX_READ_SIZE / 1024 + 10) as usize;
+        std::fs::write(temp.path().join("big.log"), line.repeat(lines)).unwrap();
+
+        let err = tool.tail_file("big.log", lines).await.unwrap_err();
+        assert!(err.to_string().contains("too large"), "{}", err);
+        // A tail within the limit still works
+        let tail = tool.tail_file("big.log", 10).await.unwrap();
+        assert_eq!(tail.lines, 10);
+    }
+
+    #[tokio::test]
+    async fn test_find_files_by_name_and_wildcard() {
+        let (temp, tool) = setup();
+        std::fs::create_dir_all(temp.path().join("src/tools")).unwrap();
+        std::fs::write(temp.path().join("src/main.rs"), "").unwrap();
+        std::fs::write(temp.path().join("src/tools/index.rs"), "").unwrap();
+        std::fs::write(temp.path().join("README.md"), "").unwrap();
+
+        let found = tool.find_files("*.rs", false).await.unwrap();
+        assert!(found.starts_with("2 file(s)"));
+        assert!(found.contains("src/main.rs"));
+        assert!(found.contains("src/tools/index.rs"));
+
+        let found = tool.find_files("src/tools/*", false).await.unwrap();
+        assert!(found.contains("src/tools/index.rs"));
+        assert!(!found.contains("src/main.rs"));
+
+        let found = tool.find_files("READ", false).await.unwrap();
+        assert!(found.contains("README.md"));
+
+        let found = tool.find_files("*.toml", false).await.unwrap();
+        assert!(found.starts_with("No files matching"));
+    }
+
+    #[tokio::test]
+    async fn test_find_files_skips_ignored_and_denied() {
+        let (temp, tool) = setup();
+        std::fs::write(temp.path().join(".gitignore"), "build/\n").unwrap();
+        std::fs::create_dir_all(temp.path().join("build")).unwrap();
+        std::fs::write(temp.path().join("build/config.json"), "").unwrap();
+        std::fs::write(temp.path().join("config.json"), "").unwrap();
+        std::fs::write(temp.path().join("credentials"), "").unwrap();
+
+        let found = tool.find_files("*", false).await.unwrap();
+        assert!(found.contains("config.json"));
+        assert!(!found.contains("build/config.json"));
+        assert!(!found.contains("credentials"));
+    }
+
+    #[tokio::test]
+    async fn test_find_files_uses_index() {
+        let (temp, tool) = setup();
+        std::fs::write(temp.path().join("indexed.txt"), "").unwrap();
+
+        let index = Arc::new(WorkspaceIndex::build(temp.path().to_path_buf()));
+        let tool = tool.with_index(index);
+
+        // Not watched, so a file created after the build is not seen
+        std::fs::write(temp.path().join("unindexed.txt"), "").unwrap();
+        let found = tool.find_files("*.txt", false).await.unwrap();
+        assert!(found.contains("indexed.txt"));
+        assert!(!found.contains("unindexed.txt"));
+    }
+
+    /// Workspace with a gitignored `logs/` and a default-ignored `node_modules/`
+    fn ignored_tree(temp: &TempDir) {
+        let root = temp.path();
+        std::fs::write(root.join(".gitignore"), "logs/\n").unwrap();
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::create_dir_all(root.join("logs")).unwrap();
+        std::fs::create_dir_all(root.join("node_modules/pkg")).unwrap();
+        std::fs::write(root.join("src/lib.rs"), "// needle in source\n").unwrap();
+        std::fs::write(root.join("logs/run.log"), "needle in log\n").unwrap();
+        std::fs::write(root.join("node_modules/pkg/index.js"), "// needle\n").unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_list_dir_respects_ignores() {
+        let (temp, tool) = setup();
+        ignored_tree(&temp);
+        let dir = temp.path().to_str().unwrap();
+
+        let listing = tool.list_dir(dir, false).await.unwrap();
+        assert!(listing.contains("d  src/"));
+        assert!(listing.contains(".gitignore"));
+        assert!(!listing.contains("logs/"));
+        assert!(!listing.contains("node_modules/"));
+
+        let listing = tool.list_dir(dir, true).await.unwrap();
+        assert!(listing.contains("d  logs/"));
+        assert!(listing.contains("d  node_modules/"));
+
+        // Listing an ignored directory by name still shows its contents
+        let nested = temp.path().join("node_modules");
+        let listing = tool
+            .list_dir(nested.to_str().unwrap(), false)
+            .await
+            .unwrap();
+        assert!(listing.contains("d  pkg/"));
+    }
+
+    #[tokio::test]
+    async fn test_find_files_include_ignored() {
+        let (temp, tool) = setup();
+        ignored_tree(&temp);
+
+        let found = tool.find_files("*.*", false).await.unwrap();
+        assert!(found.contains("src/lib.rs"));
+        assert!(!found.contains("run.log"));
+        assert!(!found.contains("index.js"));
+
+        let found = tool.find_files("*.*", true).await.unwrap();
+        assert!(found.contains("logs/run.log"));
+        assert!(found.contains("node_modules/pkg/index.js"));
+    }
+
+    #[tokio::test]
+    async fn test_search_content_respects_ignores() {
+        let (temp, tool) = setup();
+        ignored_tree(&temp);
+
+        let found = tool.search_content("needle", false).await.unwrap();
+        assert!(found.starts_with("1 match(es)"));
+        assert!(found.contains("src/lib.rs:1: // needle in source"));
+
+        let found = tool.search_content("needle", true).await.unwrap();
+        assert!(found.starts_with("3 match(es)"));
+        assert!(found.contains("logs/run.log:1: needle in log"));
+        assert!(found.contains("node_modules/pkg/index.js:1:"));
+
+        let found = tool.search_content("haystack", true).await.unwrap();
+        assert!(found.starts_with("No matches"));
+    }
+
+    #[test]
+    fn test_wildcard_match() {
+        assert!(wildcard_match(b"*.rs", b"main.rs"));
+        assert!(wildcard_match(b"m??n.*", b"main.rs"));
+        assert!(wildcard_match(b"*", b""));
+        assert!(!wildcard_match(b"*.rs", b"main.rsx"));
+        assert!(!wildcard_match(b"a*b", b"acd"));
+    }
+
+    #[tokio::test]
+    async fn test_append_and_atomic_writes() {
+        let (temp, tool) = setup();
+        let log = temp.path().join("logs/run.log");
+        let path = log.to_str().unwrap();
+        let append = WriteOptions {
+            mode: WriteMode::Append,
+            atomic: false,
+        };
+
+        tool.write_file_with(path, "first\n", append).await.unwrap();
+        let msg = tool
+            .write_file_with(path, "second\n", append)
+            .await
+            .unwrap();
+        assert!(msg.starts_with("Appended 7 bytes"));
+        assert_eq!(std::fs::read_to_string(&log).unwrap(), "first\nsecond\n");
+
+        let atomic = WriteOptions {
+            mode: WriteMode::Overwrite,
+            atomic: true,
+        };
+        tool.write_file_with(path, "replaced", atomic)
+            .await
+            .unwrap();
+        assert_eq!(std::fs::read_to_string(&log).unwrap(), "replaced");
+        // No temporary file is left behind
+        let entries = std::fs::read_dir(temp.path().join("logs")).unwrap().count();
+        assert_eq!(entries, 1);
+
+        let both = WriteOptions {
+            mode: WriteMode::Append,
+            atomic: true,
+        };
+        assert!(tool.write_file_with(path, "x", both).await.is_err());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_atomic_write_keeps_permissions() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let (temp, tool) = setup();
+        let script = temp.path().join("deploy.sh");
+        let secret = temp.path().join("token.txt");
+        std::fs::write(&script, "#!/bin/sh\n").unwrap();
+        std::fs::write(&secret, "old").unwrap();
+        std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+        std::fs::set_permissions(&secret, std::fs::Permissions::from_mode(0o600)).unwrap();
+
+        let atomic = WriteOptions {
+            mode: WriteMode::Overwrite,
+            atomic: true,
+        };
+        for (path, mode) in [(&script, 0o755), (&secret, 0o600)] {
+            tool.write_file_with(path.to_str().unwrap(), "new", atomic)
+                .await
+                .unwrap();
+            assert_eq!(std::fs::read_to_string(path).unwrap(), "new");
+            let written = std::fs::metadata(path).unwrap().permissions().mode();
+            assert_eq!(written & 0o777, mode);
+        }
+    }
 }
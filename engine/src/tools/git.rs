@@ -0,0 +1,724 @@
+//! Git Core Tool
+//!
+//! Git operations on the workspace repository. `git_log` pages through
+//! history with `count`/`skip` and caps its output so a large log never
+//! floods the LLM context; anything past the cap is cut at a line boundary
+//! and reported with a `truncated` flag. `git_diff` is capped the same way.
+//!
+//! `git_branch` lists, creates and checks out branches, enough for a
+//! feature-branch workflow together with `git_commit`. Branch names are
+//! validated before they reach git's command line.
+//!
+//! `git_commit` commits the staged changes (or every tracked change with
+//! `all`). A daemon shared by several users can commit on behalf of each of
+//! them by passing `author_name`/`author_email`, which override the
+//! repository's identity for that commit only.
+
+use anyhow::{anyhow, Result};
+use serde::Serialize;
+use std::path::PathBuf;
+use std::process::Stdio;
+use std::time::Duration;
+use tokio::io::AsyncReadExt;
+use tracing::{info, warn};
+
+/// Commits returned when `count` is not given
+pub const DEFAULT_LOG_COUNT: u64 = 5;
+
+/// Largest accepted `count`
+pub const MAX_LOG_COUNT: u64 = 200;
+
+/// Bytes of `git log`, `git diff` or branch list output returned before
+/// truncating
+pub const MAX_LOG_OUTPUT: usize = 64 * 1024;
+
+/// Output format of `git_log`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogFormat {
+    /// One line per commit: abbreviated hash and subject
+    Oneline,
+    /// Full hash, author, committer and message
+    Full,
+}
+
+/// Validated arguments of a `git_log` call
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogOptions {
+    pub count: u64,
+    pub skip: u64,
+    pub format: LogFormat,
+}
+
+impl Default for LogOptions {
+    fn default() -> Self {
+        Self {
+            count: DEFAULT_LOG_COUNT,
+            skip: 0,
+            format: LogFormat::Oneline,
+        }
+    }
+}
+
+impl LogOptions {
+    /// Parse `{"count": n, "skip": n, "format": "oneline"|"full"}`
+    ///
+    /// Every field is optional. `count` must be between 1 and `MAX_LOG_COUNT`
+    /// and `skip` a non-negative integer.
+    pub fn from_args(args: &serde_json::Value) -> Result<Self> {
+        let mut options = Self::default();
+
+        if let Some(count) = args.get("count") {
+            options.count = count
+                .as_u64()
+                .filter(|n| (1..=MAX_LOG_COUNT).contains(n))
+                .ok_or_else(|| {
+                    anyhow!(
+                        "count must be an integer between 1 and {}, got {}",
+                        MAX_LOG_COUNT,
+                        count
+                    )
+                })?;
+        }
+
+        if let Some(skip) = args.get("skip") {
+            options.skip = skip
+                .as_u64()
+                .ok_or_else(|| anyhow!("skip must be a non-negative integer, got {}", skip))?;
+        }
+
+        if let Some(format) = args.get("format") {
+            options.format = match format.as_str() {
+                Some("oneline") => LogFormat::Oneline,
+                Some("full") => LogFormat::Full,
+                _ => {
+                    return Err(anyhow!(
+                        "format must be \"oneline\" or \"full\", got {}",
+                        format
+                    ))
+                }
+            };
+        }
+
+        Ok(options)
+    }
+
+    /// Arguments passed to `git` for these options
+    pub fn git_args(&self) -> Vec<String> {
+        let mut args = vec![
+            "log".to_string(),
+            "--no-color".to_string(),
+            "-n".to_string(),
+            self.count.to_string(),
+            format!("--skip={}", self.skip),
+        ];
+        args.push(
+            match self.format {
+                LogFormat::Oneline => "--oneline",
+                LogFormat::Full => "--format=full",
+            }
+            .to_string(),
+        );
+        args
+    }
+}
+
+/// Validated arguments of a `git_diff` call
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DiffOptions {
+    /// Only diff this path, relative to the workspace
+    pub path: Option<String>,
+    /// Diff the staged changes instead of the unstaged ones
+    pub staged: bool,
+}
+
+impl DiffOptions {
+    /// Parse `{"path": "src/main.rs", "staged": false}`
+    ///
+    /// Both fields are optional.
+    pub fn from_args(args: &serde_json::Value) -> Result<Self> {
+        let mut options = Self::default();
+
+        if let Some(path) = args.get("path") {
+            let path = path
+                .as_str()
+                .filter(|path| !path.is_empty())
+                .ok_or_else(|| anyhow!("path must be a non-empty string, got {}", path))?;
+            options.path = Some(path.to_string());
+        }
+
+        if let Some(staged) = args.get("staged") {
+            options.staged = staged
+                .as_bool()
+                .ok_or_else(|| anyhow!("staged must be true or false, got {}", staged))?;
+        }
+
+        Ok(options)
+    }
+
+    /// Arguments passed to `git` for these options
+    pub fn git_args(&self) -> Vec<String> {
+        let mut args = vec!["diff".to_string(), "--no-color".to_string()];
+        if self.staged {
+            args.push("--staged".to_string());
+        }
+        if let Some(ref path) = self.path {
+            // After `--` the path can't be taken for an option
+            args.extend(["--".to_string(), path.clone()]);
+        }
+        args
+    }
+}
+
+/// Validated arguments of a `git_branch` call
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BranchAction {
+    /// List the local branches, marking the current one
+    List,
+    /// Create a branch at the current commit, without switching to it
+    Create(String),
+    /// Switch to an existing branch
+    Checkout(String),
+}
+
+impl BranchAction {
+    /// Parse `{"action": "list"|"create"|"checkout", "name": "feature/x"}`
+    ///
+    /// `action` defaults to `list`; `create` and `checkout` need a `name`,
+    /// which may not contain whitespace, control characters or shell
+    /// metacharacters, or start with `-`.
+    pub fn from_args(args: &serde_json::Value) -> Result<Self> {
+        let action = match args.get("action") {
+            Some(action) => action
+                .as_str()
+                .ok_or_else(|| anyhow!("action must be a string, got {}", action))?,
+            None => "list",
+        };
+        match action {
+            "list" => Ok(Self::List),
+            "create" => Ok(Self::Create(branch_name(args)?)),
+            "checkout" => Ok(Self::Checkout(branch_name(args)?)),
+            other => Err(anyhow!(
+                "action must be \"list\", \"create\" or \"checkout\", got \"{}\"",
+                other
+            )),
+        }
+    }
+
+    /// Whether the action changes the repository
+    pub fn is_write(&self) -> bool {
+        !matches!(self, Self::List)
+    }
+
+    /// Arguments passed to `git` for this action
+    pub fn git_args(&self) -> Vec<String> {
+        match self {
+            Self::List => vec!["branch".to_string(), "--no-color".to_string()],
+            Self::Create(name) => vec!["branch".to_string(), name.clone()],
+            // `--` keeps git from reading a name that matches no branch as
+            // a pathspec and discarding changes to that path
+            Self::Checkout(name) => vec!["checkout".to_string(), name.clone(), "--".to_string()],
+        }
+    }
+}
+
+/// Read and validate the branch `name` of a `git_branch` call
+fn branch_name(args: &serde_json::Value) -> Result<String> {
+    let name = args
+        .get("name")
+        .and_then(|v| v.as_str())
+        .filter(|name| !name.is_empty())
+        .ok_or_else(|| anyhow!("name must be a non-empty string"))?;
+    if name.starts_with('-') {
+        return Err(anyhow!("name must not start with '-'"));
+    }
+    if let Some(c) = name
+        .chars()
+        .find(|c| c.is_whitespace() || c.is_control() || SHELL_METACHARACTERS.contains(c))
+    {
+        return Err(anyhow!("name must not contain {:?}", c));
+    }
+    Ok(name.to_string())
+}
+
+/// Characters refused in commit author fields and branch names
+const SHELL_METACHARACTERS: &[char] = &[
+    ';', '&', '|', '$', '`', '<', '>', '(', ')', '{', '}', '[', ']', '*', '?', '!', '~', '#', '\'',
+    '"', '\\',
+];
+
+/// Validated arguments of a `git_commit` call
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GitCommitInput {
+    pub message: String,
+    /// Commit every modified tracked file, not just the staged ones
+    pub all: bool,
+    /// Author name for this commit (the repository's `user.name` otherwise)
+    pub author_name: Option<String>,
+    /// Author email for this commit (the repository's `user.email` otherwise)
+    pub author_email: Option<String>,
+}
+
+impl GitCommitInput {
+    /// Parse `{"message", "all", "author_name", "author_email"}`
+    ///
+    /// `message` is required and must not be blank. The author fields are
+    /// optional; they may not be blank, contain control characters or shell
+    /// metacharacters, since they end up in git's command line.
+    pub fn from_args(args: &serde_json::Value) -> Result<Self> {
+        let message = args
+            .get("message")
+            .and_then(|v| v.as_str())
+            .filter(|message| !message.trim().is_empty())
+            .ok_or_else(|| anyhow!("message must be a non-empty string"))?;
+        let all = match args.get("all") {
+            Some(all) => all
+                .as_bool()
+                .ok_or_else(|| anyhow!("all must be true or false, got {}", all))?,
+            None => false,
+        };
+
+        Ok(Self {
+            message: message.to_string(),
+            all,
+            author_name: author_field(args, "author_name")?,
+            author_email: author_field(args, "author_email")?,
+        })
+    }
+
+    /// Arguments passed to `git` for this commit
+    pub fn git_args(&self) -> Vec<String> {
+        let mut args = Vec::new();
+        if let Some(ref name) = self.author_name {
+            args.extend(["-c".to_string(), format!("user.name={}", name)]);
+        }
+        if let Some(ref email) = self.author_email {
+            args.extend(["-c".to_string(), format!("user.email={}", email)]);
+        }
+        args.push("commit".to_string());
+        if self.all {
+            args.push("--all".to_string());
+        }
+        args.extend(["-m".to_string(), self.message.clone()]);
+        args
+    }
+}
+
+/// Read and validate an optional author field of a `git_commit` call
+fn author_field(args: &serde_json::Value, key: &str) -> Result<Option<String>> {
+    let Some(value) = args.get(key) else {
+        return Ok(None);
+    };
+    let value = value
+        .as_str()
+        .ok_or_else(|| anyhow!("{} must be a string, got {}", key, value))?;
+    if value.trim().is_empty() {
+        return Err(anyhow!("{} must not be empty", key));
+    }
+    if let Some(c) = value
+        .chars()
+        .find(|c| c.is_control() || SHELL_METACHARACTERS.contains(c))
+    {
+        return Err(anyhow!("{} must not contain {:?}", key, c));
+    }
+    Ok(Some(value.to_string()))
+}
+
+/// Output of a `git_diff` or `git_branch` call, returned to the LLM as JSON
+#[derive(Debug, Clone, Serialize)]
+pub struct GitOutput {
+    pub stdout: String,
+    /// Messages git printed, e.g. "Switched to branch ..." after a checkout
+    pub stderr: String,
+    /// True when stdout hit `MAX_LOG_OUTPUT` and was cut short
+    pub truncated: bool,
+}
+
+/// Result of a `git_log` call, returned to the LLM as JSON
+#[derive(Debug, Clone, Serialize)]
+pub struct LogPage {
+    pub log: String,
+    pub count: u64,
+    pub skip: u64,
+    /// True when the output hit `MAX_LOG_OUTPUT` and was cut short
+    pub truncated: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct GitTool {
+    work_dir: PathBuf,
+    timeout: Duration,
+}
+
+impl GitTool {
+    pub fn new(work_dir: PathBuf) -> Self {
+        Self {
+            work_dir,
+            timeout: Duration::from_secs(30),
+        }
+    }
+
+    /// Run `git log` for one page of history
+    ///
+    /// Output is read incrementally and the process is killed once
+    /// `MAX_LOG_OUTPUT` bytes have arrived.
+    pub async fn log(&self, options: &LogOptions) -> Result<LogPage> {
+        let output = self.run_capped(&options.git_args()).await?;
+
+        Ok(LogPage {
+            log: output.stdout,
+            count: options.count,
+            skip: options.skip,
+            truncated: output.truncated,
+        })
+    }
+
+    /// Run `git diff`, capped like `git log`
+    pub async fn diff(&self, options: &DiffOptions) -> Result<GitOutput> {
+        self.run_capped(&options.git_args()).await
+    }
+
+    /// List, create or check out a branch
+    pub async fn branch(&self, action: &BranchAction) -> Result<GitOutput> {
+        self.run_capped(&action.git_args()).await
+    }
+
+    /// Run git with `args` and collect its output
+    ///
+    /// Output is read incrementally and the process is killed once
+    /// `MAX_LOG_OUTPUT` bytes have arrived. A non-zero exit is an error
+    /// carrying git's stderr.
+    async fn run_capped(&self, args: &[String]) -> Result<GitOutput> {
+        let command = args.first().map(String::as_str).unwrap_or_default();
+        info!("Running git {}", args.join(" "));
+
+        let mut child = tokio::process::Command::new("git")
+            .args(args)
+            .current_dir(&self.work_dir)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(|e| anyhow!("Failed to start git: {}", e))?;
+
+        let mut stdout = child.stdout.take().expect("stdout is piped");
+        let mut stderr = child.stderr.take().expect("stderr is piped");
+        // Both pipes are drained together, so a child filling stderr never
+        // blocks while stdout is being read
+        let read_stdout = async {
+            let mut buf = Vec::new();
+            (&mut stdout)
+                .take(MAX_LOG_OUTPUT as u64 + 1)
+                .read_to_end(&mut buf)
+                .await?;
+            if buf.len() > MAX_LOG_OUTPUT {
+                // The rest of the output is not needed
+                child.start_kill().ok();
+            }
+            Ok::<_, std::io::Error>(buf)
+        };
+        let read_stderr = async {
+            let mut buf = Vec::new();
+            (&mut stderr)
+                .take(MAX_LOG_OUTPUT as u64)
+                .read_to_end(&mut buf)
+                .await?;
+            tokio::io::copy(&mut stderr, &mut tokio::io::sink()).await?;
+            Ok::<_, std::io::Error>(buf)
+        };
+        let read = async { tokio::try_join!(read_stdout, read_stderr) };
+        let (out, err) = match tokio::time::timeout(self.timeout, read).await {
+            Ok(result) => result?,
+            Err(_) => {
+                return Err(anyhow!(
+                    "git {} timed out after {:?}",
+                    command,
+                    self.timeout
+                ))
+            }
+        };
+        let status = child.wait().await?;
+
+        let (stdout, truncated) = cap_output(&out, MAX_LOG_OUTPUT);
+        let stderr = String::from_utf8_lossy(&err).trim().to_string();
+        if !truncated && !status.success() {
+            warn!("git {} failed: {}", command, stderr);
+            return Err(anyhow!("git {} failed: {}", command, stderr));
+        }
+
+        Ok(GitOutput {
+            stdout,
+            stderr,
+            truncated,
+        })
+    }
+
+    /// Run `git commit` and return its summary of the new commit
+    pub async fn commit(&self, input: &GitCommitInput) -> Result<String> {
+        let args = input.git_args();
+        info!("Running git commit in {}", self.work_dir.display());
+
+        let output = tokio::process::Command::new("git")
+            .args(&args)
+            .current_dir(&self.work_dir)
+            .stdin(Stdio::null())
+            .kill_on_drop(true)
+            .output();
+        let output = match tokio::time::timeout(self.timeout, output).await {
+            Ok(result) => result.map_err(|e| anyhow!("Failed to start git: {}", e))?,
+            Err(_) => return Err(anyhow!("git commit timed out after {:?}", self.timeout)),
+        };
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        if !output.status.success() {
+            // "nothing to commit" is reported on stdout
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            let reason = if stderr.trim().is_empty() {
+                stdout.trim()
+            } else {
+                stderr.trim()
+            };
+            warn!("git commit failed: {}", reason);
+            return Err(anyhow!("git commit failed: {}", reason));
+        }
+        Ok(stdout.trim().to_string())
+    }
+}
+
+/// Cut `output` to at most `limit` bytes, ending on a complete line
+fn cap_output(output: &[u8], limit: usize) -> (String, bool) {
+    if output.len() <= limit {
+        return (String::from_utf8_lossy(output).to_string(), false);
+    }
+
+    let head = &output[..limit];
+    let end = head
+        .iter()
+        .rposition(|&b| b == b'\n')
+        .map(|i| i + 1)
+        .unwrap_or(limit);
+    (String::from_utf8_lossy(&head[..end]).to_string(), true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_count_and_skip_map_to_git_args() {
+        let options = LogOptions::from_args(&json!({"count": 20, "skip": 40})).unwrap();
+        assert_eq!(
+            options.git_args(),
+            vec!["log", "--no-color", "-n", "20", "--skip=40", "--oneline"]
+        );
+
+        let defaults = LogOptions::from_args(&json!({})).unwrap();
+        assert_eq!(defaults, LogOptions::default());
+        assert_eq!(
+            defaults.git_args(),
+            vec!["log", "--no-color", "-n", "5", "--skip=0", "--oneline"]
+        );
+    }
+
+    #[test]
+    fn test_format_selection() {
+        let oneline = LogOptions::from_args(&json!({"format": "oneline"})).unwrap();
+        assert_eq!(oneline.format, LogFormat::Oneline);
+        assert_eq!(oneline.git_args().last().unwrap(), "--oneline");
+
+        let full = LogOptions::from_args(&json!({"format": "full"})).unwrap();
+        assert_eq!(full.format, LogFormat::Full);
+        assert_eq!(full.git_args().last().unwrap(), "--format=full");
+        assert!(!full.git_args().contains(&"--oneline".to_string()));
+    }
+
+    #[test]
+    fn test_rejects_invalid_arguments() {
+        for args in [
+            json!({"count": 0}),
+            json!({"count": MAX_LOG_COUNT + 1}),
+            json!({"count": -1}),
+            json!({"count": 2.5}),
+            json!({"count": "10"}),
+            json!({"skip": -5}),
+            json!({"skip": "1; rm -rf /"}),
+            json!({"format": "raw"}),
+            json!({"format": 1}),
+        ] {
+            assert!(LogOptions::from_args(&args).is_err(), "accepted {}", args);
+        }
+    }
+
+    #[test]
+    fn test_commit_author_maps_to_config_overrides() {
+        let input = GitCommitInput::from_args(&json!({
+            "message": "Update notes",
+            "author_name": "Ada Lovelace",
+            "author_email": "ada@example.com",
+        }))
+        .unwrap();
+        assert_eq!(
+            input.git_args(),
+            vec![
+                "-c",
+                "user.name=Ada Lovelace",
+                "-c",
+                "user.email=ada@example.com",
+                "commit",
+                "-m",
+                "Update notes"
+            ]
+        );
+
+        let input = GitCommitInput::from_args(&json!({"message": "Fix", "all": true})).unwrap();
+        assert_eq!(input.git_args(), vec!["commit", "--all", "-m", "Fix"]);
+    }
+
+    #[test]
+    fn test_rejects_unsafe_commit_authors() {
+        for args in [
+            json!({"message": ""}),
+            json!({"author_name": "Ada"}),
+            json!({"message": "Fix", "author_name": "Ada; rm -rf /"}),
+            json!({"message": "Fix", "author_name": "$(whoami)"}),
+            json!({"message": "Fix", "author_name": "Ada\nEvil"}),
+            json!({"message": "Fix", "author_name": " "}),
+            json!({"message": "Fix", "author_email": "ada@example.com`id`"}),
+            json!({"message": "Fix", "author_email": "<ada@example.com>"}),
+            json!({"message": "Fix", "author_email": 7}),
+            json!({"message": "Fix", "all": "yes"}),
+        ] {
+            assert!(
+                GitCommitInput::from_args(&args).is_err(),
+                "accepted {}",
+                args
+            );
+        }
+    }
+
+    #[test]
+    fn test_diff_args() {
+        let options = DiffOptions::from_args(&json!({})).unwrap();
+        assert_eq!(options.git_args(), vec!["diff", "--no-color"]);
+
+        let options = DiffOptions::from_args(&json!({"path": "-p", "staged": true})).unwrap();
+        assert_eq!(
+            options.git_args(),
+            vec!["diff", "--no-color", "--staged", "--", "-p"]
+        );
+
+        assert!(DiffOptions::from_args(&json!({"path": ""})).is_err());
+        assert!(DiffOptions::from_args(&json!({"staged": "yes"})).is_err());
+    }
+
+    #[test]
+    fn test_branch_actions() {
+        assert_eq!(
+            BranchAction::from_args(&json!({})).unwrap(),
+            BranchAction::List
+        );
+        let create =
+            BranchAction::from_args(&json!({"action": "create", "name": "feature/login-v2"}))
+                .unwrap();
+        assert_eq!(create.git_args(), vec!["branch", "feature/login-v2"]);
+        assert!(create.is_write());
+        let checkout =
+            BranchAction::from_args(&json!({"action": "checkout", "name": "main"})).unwrap();
+        assert_eq!(checkout.git_args(), vec!["checkout", "main", "--"]);
+        assert!(!BranchAction::List.is_write());
+    }
+
+    #[test]
+    fn test_rejects_unsafe_branch_names() {
+        for args in [
+            json!({"action": "create"}),
+            json!({"action": "create", "name": ""}),
+            json!({"action": "create", "name": "my feature"}),
+            json!({"action": "create", "name": "fix;rm -rf /"}),
+            json!({"action": "create", "name": "$(whoami)"}),
+            json!({"action": "create", "name": "a|b"}),
+            json!({"action": "create", "name": "--force"}),
+            json!({"action": "checkout", "name": "main\tx"}),
+            json!({"action": "delete", "name": "main"}),
+        ] {
+            assert!(BranchAction::from_args(&args).is_err(), "accepted {}", args);
+        }
+    }
+
+    #[test]
+    fn test_cap_output_cuts_at_line_boundary() {
+        let output = b"aaaa\nbbbb\ncccc\n";
+
+        assert_eq!(
+            cap_output(output, 64),
+            ("aaaa\nbbbb\ncccc\n".to_string(), false)
+        );
+        assert_eq!(cap_output(output, 12), ("aaaa\nbbbb\n".to_string(), true));
+        assert_eq!(cap_output(b"abcdef", 3), ("abc".to_string(), true));
+    }
+
+    /// Run git in `dir`, panicking if it fails
+    fn git(dir: &std::path::Path, args: &[&str]) {
+        let status = std::process::Command::new("git")
+            .args(args)
+            .current_dir(dir)
+            .status()
+            .unwrap();
+        assert!(status.success(), "git {:?} failed", args);
+    }
+
+    /// A repository with one commit holding `src/lib.rs`
+    fn init_repo() -> tempfile::TempDir {
+        let temp = tempfile::TempDir::new().unwrap();
+        std::fs::create_dir(temp.path().join("src")).unwrap();
+        std::fs::write(temp.path().join("src/lib.rs"), "committed").unwrap();
+        git(temp.path(), &["init", "-q"]);
+        git(temp.path(), &["add", "."]);
+        git(
+            temp.path(),
+            &[
+                "-c",
+                "user.name=Test",
+                "-c",
+                "user.email=test@example.com",
+                "commit",
+                "-q",
+                "-m",
+                "Initial",
+            ],
+        );
+        temp
+    }
+
+    #[tokio::test]
+    async fn test_checkout_never_treats_name_as_path() {
+        let repo = init_repo();
+        std::fs::write(repo.path().join("src/lib.rs"), "uncommitted").unwrap();
+        let tool = GitTool::new(repo.path().to_path_buf());
+
+        let checkout =
+            BranchAction::from_args(&json!({"action": "checkout", "name": "src"})).unwrap();
+        assert!(tool.branch(&checkout).await.is_err());
+        assert_eq!(
+            std::fs::read_to_string(repo.path().join("src/lib.rs")).unwrap(),
+            "uncommitted"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_run_capped_drains_stderr() {
+        let repo = init_repo();
+        let mut tool = GitTool::new(repo.path().to_path_buf());
+        tool.timeout = Duration::from_secs(10);
+
+        // Far more stderr than a pipe buffers, then a line on stdout
+        let alias = "alias.noisy=!head -c 1000000 /dev/zero >&2; echo done";
+        let output = tool
+            .run_capped(&["-c".to_string(), alias.to_string(), "noisy".to_string()])
+            .await
+            .unwrap();
+        assert_eq!(output.stdout.trim(), "done");
+        assert!(!output.truncated);
+    }
+}
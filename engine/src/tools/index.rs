@@ -0,0 +1,404 @@
+//! Workspace File Index
+//!
+//! Optional in-memory index of the workspace's files (relative path and
+//! modification time), kept current by a file watcher so `find_files` does not
+//! have to walk the whole tree on every call. Enabled with
+//! `core.index_workspace`.
+//!
+//! Files are indexed under the same rules as a default full scan: paths
+//! rejected by the `FileSystemGuard` deny list, hidden entries, directories in
+//! `DEFAULT_IGNORED_DIRS`, and anything matched by `.gitignore` are left out.
+//! Watch events are checked against the workspace-root `.gitignore` only;
+//! when the watcher reports an error, drops events, or a `.gitignore`
+//! changes, the index is marked stale, and the next query reloads the root
+//! `.gitignore` and rebuilds the index with a full scan.
+
+use anyhow::{Context, Result};
+use ignore::gitignore::Gitignore;
+use ignore::WalkBuilder;
+use notify::{Event, RecommendedWatcher, RecursiveMode, Watcher};
+use std::collections::HashMap;
+use std::path::{Component, Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::SystemTime;
+use tracing::{debug, info, warn};
+
+use crate::fs_guard::FileSystemGuard;
+
+/// Directories skipped by listing and search even without a `.gitignore`
+pub const DEFAULT_IGNORED_DIRS: &[&str] = &[".git", "target", "node_modules"];
+
+/// Whether a directory name is in `DEFAULT_IGNORED_DIRS`
+pub fn is_default_ignored_dir(name: &std::ffi::OsStr) -> bool {
+    DEFAULT_IGNORED_DIRS.iter().any(|dir| name == *dir)
+}
+
+/// Walker over `dir` applying the workspace ignore rules
+///
+/// By default this respects `.gitignore` (and `.ignore`) files, skips hidden
+/// entries, and skips `DEFAULT_IGNORED_DIRS`. With `include_ignored` every
+/// entry is visited.
+pub fn workspace_walker(dir: &Path, include_ignored: bool) -> WalkBuilder {
+    let mut builder = WalkBuilder::new(dir);
+    if include_ignored {
+        builder.standard_filters(false);
+    } else {
+        // `require_git(false)` applies .gitignore even outside a git checkout
+        // The root itself is never filtered, so listing `target/` still works
+        builder.require_git(false).filter_entry(|entry| {
+            !(entry.depth() > 0
+                && entry.file_type().is_some_and(|ft| ft.is_dir())
+                && is_default_ignored_dir(entry.file_name()))
+        });
+    }
+    builder
+}
+
+/// Walk `dir` (inside the workspace) and return the files a search should see
+///
+/// Paths are relative to the workspace. Files on the guard's deny list are
+/// never returned. Shared by the index and the full-scan fallback in
+/// `FilesystemTool`.
+pub fn scan_files(
+    guard: &FileSystemGuard,
+    dir: &Path,
+    include_ignored: bool,
+) -> Vec<(PathBuf, SystemTime)> {
+    let root = guard.workspace();
+    let mut files = Vec::new();
+
+    for entry in workspace_walker(dir, include_ignored).build() {
+        let entry = match entry {
+            Ok(entry) => entry,
+            Err(e) => {
+                debug!("Skipping unreadable entry during scan: {}", e);
+                continue;
+            }
+        };
+
+        if !entry.file_type().is_some_and(|ft| ft.is_file()) {
+            continue;
+        }
+
+        let Ok(relative) = entry.path().strip_prefix(root) else {
+            continue;
+        };
+        if guard.check_denied(relative).is_err() {
+            continue;
+        }
+
+        let modified = entry
+            .metadata()
+            .ok()
+            .and_then(|m| m.modified().ok())
+            .unwrap_or(SystemTime::UNIX_EPOCH);
+        files.push((relative.to_path_buf(), modified));
+    }
+
+    files
+}
+
+/// Shared index state, updated from the watcher thread
+struct IndexState {
+    guard: FileSystemGuard,
+    gitignore: RwLock<Gitignore>,
+    files: RwLock<HashMap<PathBuf, SystemTime>>,
+    stale: AtomicBool,
+}
+
+impl IndexState {
+    fn root(&self) -> &Path {
+        self.guard.workspace()
+    }
+
+    /// Replace the index contents with a full scan
+    fn rescan(&self) {
+        *self.gitignore.write().expect("gitignore lock poisoned") = load_gitignore(self.root());
+        let files: HashMap<_, _> = scan_files(&self.guard, self.root(), false)
+            .into_iter()
+            .collect();
+        info!("Indexed {} files in {}", files.len(), self.root().display());
+
+        *self.files.write().expect("index lock poisoned") = files;
+        self.stale.store(false, Ordering::Relaxed);
+    }
+
+    /// Whether a path under the workspace belongs in the index
+    fn is_indexable(&self, relative: &Path, is_dir: bool) -> bool {
+        let hidden = relative.components().any(|c| match c {
+            Component::Normal(name) => name.to_string_lossy().starts_with('.'),
+            _ => false,
+        });
+
+        // The last component is only a directory when `is_dir` is set
+        let parents = if is_dir {
+            Some(relative)
+        } else {
+            relative.parent()
+        };
+        let default_ignored = parents.is_some_and(|dirs| {
+            dirs.components()
+                .any(|c| is_default_ignored_dir(c.as_os_str()))
+        });
+
+        !hidden
+            && !default_ignored
+            && self.guard.check_denied(relative).is_ok()
+            && !self
+                .gitignore
+                .read()
+                .expect("gitignore lock poisoned")
+                .matched_path_or_any_parents(relative, is_dir)
+                .is_ignore()
+    }
+
+    fn handle_event(&self, event: notify::Result<Event>) {
+        let event = match event {
+            Ok(event) => event,
+            Err(e) => {
+                warn!("Workspace watcher error, index marked stale: {}", e);
+                self.stale.store(true, Ordering::Relaxed);
+                return;
+            }
+        };
+
+        if event.need_rescan() {
+            self.stale.store(true, Ordering::Relaxed);
+            return;
+        }
+
+        for path in &event.paths {
+            if path.file_name().is_some_and(|name| name == ".gitignore") {
+                self.stale.store(true, Ordering::Relaxed);
+            }
+            self.refresh(path);
+        }
+    }
+
+    /// Bring the entry (or subtree) at `path` in line with the disk
+    fn refresh(&self, path: &Path) {
+        let Ok(relative) = path.strip_prefix(self.root()) else {
+            return;
+        };
+
+        match std::fs::symlink_metadata(path) {
+            Ok(meta) if meta.is_file() => {
+                let mut files = self.files.write().expect("index lock poisoned");
+                if self.is_indexable(relative, false) {
+                    let modified = meta.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+                    files.insert(relative.to_path_buf(), modified);
+                } else {
+                    files.remove(relative);
+                }
+            }
+            Ok(meta) if meta.is_dir() => {
+                // A directory moved into the workspace arrives as one event
+                if self.is_indexable(relative, true) {
+                    let scanned = scan_files(&self.guard, path, false);
+                    let mut files = self.files.write().expect("index lock poisoned");
+                    files.extend(scanned);
+                }
+            }
+            Ok(_) => {}
+            Err(_) => {
+                // Removed: drop the path and anything that was under it
+                let mut files = self.files.write().expect("index lock poisoned");
+                files.retain(|file, _| !file.starts_with(relative));
+            }
+        }
+    }
+}
+
+/// Matcher for the `.gitignore` at the root of `workspace`
+fn load_gitignore(workspace: &Path) -> Gitignore {
+    let (gitignore, error) = Gitignore::new(workspace.join(".gitignore"));
+    if let Some(e) = error {
+        debug!("Workspace .gitignore not fully loaded: {}", e);
+    }
+    gitignore
+}
+
+/// In-memory index of workspace files, optionally kept current by a watcher
+pub struct WorkspaceIndex {
+    state: Arc<IndexState>,
+    watcher: Mutex<Option<RecommendedWatcher>>,
+}
+
+impl WorkspaceIndex {
+    /// Build the index with a full scan of the workspace
+    ///
+    /// The index does not follow changes until `watch` is called.
+    pub fn build(workspace: PathBuf) -> Self {
+        let guard = FileSystemGuard::new(workspace);
+        let state = Arc::new(IndexState {
+            guard,
+            gitignore: RwLock::new(Gitignore::empty()),
+            files: RwLock::new(HashMap::new()),
+            stale: AtomicBool::new(false),
+        });
+        state.rescan();
+
+        Self {
+            state,
+            watcher: Mutex::new(None),
+        }
+    }
+
+    /// Start watching the workspace and updating the index in the background
+    pub fn watch(self) -> Result<Self> {
+        let state = Arc::clone(&self.state);
+        let mut watcher = notify::recommended_watcher(move |event| state.handle_event(event))
+            .context("Failed to create workspace watcher")?;
+
+        watcher
+            .watch(self.state.root(), RecursiveMode::Recursive)
+            .with_context(|| format!("Failed to watch {}", self.state.root().display()))?;
+        info!("Watching {} for changes", self.state.root().display());
+
+        *self.watcher.lock().expect("watcher lock poisoned") = Some(watcher);
+        Ok(self)
+    }
+
+    /// Indexed files (relative to the workspace), rebuilt first if stale
+    pub fn files(&self) -> Vec<PathBuf> {
+        if self.is_stale() {
+            self.state.rescan();
+        }
+
+        let files = self.state.files.read().expect("index lock poisoned");
+        files.keys().cloned().collect()
+    }
+
+    /// Last known modification time of an indexed file
+    pub fn modified(&self, relative: &Path) -> Option<SystemTime> {
+        let files = self.state.files.read().expect("index lock poisoned");
+        files.get(relative).copied()
+    }
+
+    /// Whether the index needs a full rescan before it can be trusted
+    pub fn is_stale(&self) -> bool {
+        self.state.stale.load(Ordering::Relaxed)
+    }
+
+    /// Whether a watcher is keeping the index current
+    pub fn is_watching(&self) -> bool {
+        self.watcher
+            .lock()
+            .expect("watcher lock poisoned")
+            .is_some()
+    }
+
+    /// Workspace root the index covers
+    pub fn root(&self) -> &Path {
+        self.state.root()
+    }
+}
+
+impl std::fmt::Debug for WorkspaceIndex {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WorkspaceIndex")
+            .field("root", &self.root())
+            .field("stale", &self.is_stale())
+            .field("watching", &self.is_watching())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+    use tempfile::TempDir;
+
+    /// Poll until `check` passes; watcher events arrive asynchronously
+    fn wait_for(index: &WorkspaceIndex, check: impl Fn(&[PathBuf]) -> bool) -> bool {
+        for _ in 0..100 {
+            if check(&index.files()) {
+                return true;
+            }
+            std::thread::sleep(Duration::from_millis(50));
+        }
+        false
+    }
+
+    #[test]
+    fn test_build_skips_ignored_and_denied_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        std::fs::write(root.join(".gitignore"), "target/\n*.log\n").unwrap();
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::create_dir_all(root.join("target")).unwrap();
+        std::fs::write(root.join("src/main.rs"), "fn main() {}").unwrap();
+        std::fs::write(root.join("target/out.bin"), "").unwrap();
+        std::fs::write(root.join("debug.log"), "").unwrap();
+        std::fs::write(root.join("credentials"), "secret").unwrap();
+        std::fs::create_dir_all(root.join("node_modules/pkg")).unwrap();
+        std::fs::write(root.join("node_modules/pkg/index.js"), "").unwrap();
+
+        let index = WorkspaceIndex::build(root.to_path_buf());
+        let files = index.files();
+
+        assert_eq!(files, vec![PathBuf::from("src/main.rs")]);
+        assert!(index.modified(Path::new("src/main.rs")).is_some());
+        assert!(!index.is_watching());
+    }
+
+    #[test]
+    fn test_index_tracks_created_and_removed_files() {
+        let temp_dir = TempDir::new().unwrap();
+        std::fs::write(temp_dir.path().join(".gitignore"), "*.tmp\n").unwrap();
+
+        let index = WorkspaceIndex::build(temp_dir.path().to_path_buf())
+            .watch()
+            .unwrap();
+        assert!(index.is_watching());
+        assert!(index.files().is_empty());
+
+        let root = index.root().to_path_buf();
+        std::fs::create_dir_all(root.join("docs")).unwrap();
+        std::fs::write(root.join("docs/guide.md"), "# Guide").unwrap();
+        std::fs::write(root.join("scratch.tmp"), "").unwrap();
+
+        assert!(wait_for(&index, |files| files
+            .contains(&PathBuf::from("docs/guide.md"))));
+        assert!(!index.files().contains(&PathBuf::from("scratch.tmp")));
+
+        std::fs::remove_file(root.join("docs/guide.md")).unwrap();
+        assert!(wait_for(&index, |files| !files
+            .contains(&PathBuf::from("docs/guide.md"))));
+    }
+
+    #[test]
+    fn test_changed_gitignore_is_reloaded() {
+        let temp_dir = TempDir::new().unwrap();
+        let index = WorkspaceIndex::build(temp_dir.path().to_path_buf());
+        let root = index.root().to_path_buf();
+
+        // A watched .gitignore change marks the index stale
+        std::fs::write(root.join(".gitignore"), "*.tmp\n").unwrap();
+        index.state.stale.store(true, Ordering::Relaxed);
+        assert!(index.files().is_empty());
+
+        // Later events are checked against the new rules
+        std::fs::write(root.join("scratch.tmp"), "").unwrap();
+        std::fs::write(root.join("notes.md"), "").unwrap();
+        index.state.refresh(&root.join("scratch.tmp"));
+        index.state.refresh(&root.join("notes.md"));
+        assert_eq!(index.files(), vec![PathBuf::from("notes.md")]);
+    }
+
+    #[test]
+    fn test_stale_index_is_rebuilt_on_query() {
+        let temp_dir = TempDir::new().unwrap();
+        let index = WorkspaceIndex::build(temp_dir.path().to_path_buf());
+
+        // Without a watcher the index only learns about this file on rescan
+        std::fs::write(index.root().join("late.txt"), "").unwrap();
+        assert!(index.files().is_empty());
+
+        index.state.stale.store(true, Ordering::Relaxed);
+        assert_eq!(index.files(), vec![PathBuf::from("late.txt")]);
+        assert!(!index.is_stale());
+    }
+}
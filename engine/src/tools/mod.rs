@@ -1,11 +1,18 @@
 pub mod filesystem;
+pub mod git;
+pub mod index;
+pub mod ocr;
 pub mod terminal;
 pub mod vision;
 
-pub use filesystem::FilesystemTool;
+pub use filesystem::{FilesystemTool, WriteMode, WriteOptions};
+pub use git::GitTool;
+pub use index::WorkspaceIndex;
 pub use terminal::TerminalTool;
 pub use vision::VisionTool;
 
+use crate::risk_assessor::OperationSource;
+use sdk::errors::EngineError;
 use tracing::{debug, warn};
 
 /// Registry of available tools that can be dispatched by the agent.
@@ -16,6 +23,7 @@ pub struct ToolRegistry {
     pub fs: Option<FilesystemTool>,
     pub terminal: Option<TerminalTool>,
     pub vision: Option<VisionTool>,
+    pub git: Option<GitTool>,
 }
 
 impl ToolRegistry {
@@ -25,6 +33,40 @@ impl ToolRegistry {
             fs: None,
             terminal: None,
             vision: None,
+            git: None,
+        }
+    }
+
+    /// Plugin (as named in `[plugins]`) that provides `tool`, and whether it
+    /// is enabled
+    fn plugin_for(&self, tool: &str) -> Option<(&'static str, bool)> {
+        match tool {
+            "read_file" | "read_files" | "tail_file" | "write_file" | "delete_file"
+            | "create_dir" | "list_dir" | "file_exists" | "find_files" | "search_content" => {
+                Some(("fs-editor", self.fs.is_some()))
+            }
+            "run_command" => Some(("terminal", self.terminal.is_some())),
+            "capture_screen" | "extract_text" => Some(("screenshot", self.vision.is_some())),
+            "git_log" | "git_commit" | "git_diff" | "git_branch" => {
+                Some(("git", self.git.is_some()))
+            }
+            _ => None,
+        }
+    }
+
+    /// Check that the plugin providing `tool` is enabled
+    ///
+    /// # Errors
+    ///
+    /// Returns `EngineError::FeatureDisabled` naming the plugin if it is
+    /// disabled. Unknown tools pass; `dispatch` reports them separately.
+    pub fn check_enabled(&self, tool: &str) -> Result<(), EngineError> {
+        match self.plugin_for(tool) {
+            Some((plugin, false)) => Err(EngineError::FeatureDisabled(format!(
+                "Plugin '{}' (providing {})",
+                plugin, tool
+            ))),
+            _ => Ok(()),
         }
     }
 
@@ -33,6 +75,32 @@ impl ToolRegistry {
     /// Returns the tool output as a string. Errors are returned as `Ok(error_string)`
     /// so the LLM can see the error and self-correct.
     pub async fn dispatch(&self, name: &str, arguments_json: &str) -> String {
+        self.dispatch_inner(None, None, name, arguments_json).await
+    }
+
+    /// Dispatch a tool call made by the task `task_id` from `source`
+    ///
+    /// Like `dispatch`; `run_command` streams its output under the task's id
+    /// when the terminal has a message bus, and `write_file` may read its
+    /// content from stdin if the task is local.
+    pub async fn dispatch_for_task(
+        &self,
+        task_id: &str,
+        source: &OperationSource,
+        name: &str,
+        arguments_json: &str,
+    ) -> String {
+        self.dispatch_inner(Some(task_id), Some(source), name, arguments_json)
+            .await
+    }
+
+    async fn dispatch_inner(
+        &self,
+        task_id: Option<&str>,
+        source: Option<&OperationSource>,
+        name: &str,
+        arguments_json: &str,
+    ) -> String {
         debug!("Dispatching tool '{}' with args: {}", name, arguments_json);
 
         let args: serde_json::Value = match serde_json::from_str(arguments_json) {
@@ -42,6 +110,10 @@ impl ToolRegistry {
             }
         };
 
+        if let Err(e) = self.check_enabled(name) {
+            return format!("ERROR: {}", e);
+        }
+
         match name {
             "read_file" => {
                 let Some(ref fs) = self.fs else {
@@ -56,6 +128,37 @@ impl ToolRegistry {
                     Err(e) => format!("ERROR: {}", e),
                 }
             }
+            "read_files" => {
+                let Some(ref fs) = self.fs else {
+                    return "ERROR: read_files tool is not enabled".to_string();
+                };
+                let paths: Vec<String> = match args.get("paths") {
+                    Some(paths) => match serde_json::from_value(paths.clone()) {
+                        Ok(paths) => paths,
+                        Err(e) => return format!("ERROR: paths must be a list of strings: {}", e),
+                    },
+                    None => return "ERROR: read_files requires paths".to_string(),
+                };
+                let max_total_bytes = args.get("max_total_bytes").and_then(|v| v.as_u64());
+                let batch = fs.read_files(&paths, max_total_bytes).await;
+                serde_json::to_string(&batch).unwrap_or_else(|e| format!("ERROR: {}", e))
+            }
+            "tail_file" => {
+                let Some(ref fs) = self.fs else {
+                    return "ERROR: tail_file tool is not enabled".to_string();
+                };
+                let path = args
+                    .get("path")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or_default();
+                let lines = args.get("lines").and_then(|v| v.as_u64()).unwrap_or(10);
+                match fs.tail_file(path, lines as usize).await {
+                    Ok(tail) => {
+                        serde_json::to_string(&tail).unwrap_or_else(|e| format!("ERROR: {}", e))
+                    }
+                    Err(e) => format!("ERROR: {}", e),
+                }
+            }
             "write_file" => {
                 let Some(ref fs) = self.fs else {
                     return "ERROR: write_file tool is not enabled".to_string();
@@ -64,11 +167,63 @@ impl ToolRegistry {
                     .get("path")
                     .and_then(|v| v.as_str())
                     .unwrap_or_default();
+                if args.get("from_stdin").and_then(|v| v.as_bool()) == Some(true) {
+                    // Stdin belongs to whoever started the daemon, not to a
+                    // remote caller
+                    if source != Some(&OperationSource::Local) {
+                        return "ERROR: from_stdin is only available to local tasks".to_string();
+                    }
+                    return match fs.write_file_from_stdin(path).await {
+                        Ok(msg) => msg,
+                        Err(e) => format!("ERROR: {}", e),
+                    };
+                }
                 let content = args
                     .get("content")
                     .and_then(|v| v.as_str())
                     .unwrap_or_default();
-                match fs.write_file(path, content).await {
+                let mode = match args.get("mode") {
+                    Some(mode) => match serde_json::from_value(mode.clone()) {
+                        Ok(mode) => mode,
+                        Err(_) => {
+                            return "ERROR: mode must be \"overwrite\" or \"append\"".to_string()
+                        }
+                    },
+                    None => WriteMode::default(),
+                };
+                let options = WriteOptions {
+                    mode,
+                    atomic: args.get("atomic").and_then(|v| v.as_bool()) == Some(true),
+                };
+                match fs.write_file_with(path, content, options).await {
+                    Ok(msg) => msg,
+                    Err(e) => format!("ERROR: {}", e),
+                }
+            }
+            "delete_file" => {
+                let Some(ref fs) = self.fs else {
+                    return "ERROR: delete_file tool is not enabled".to_string();
+                };
+                let path = args
+                    .get("path")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or_default();
+                let recursive = args.get("recursive").and_then(|v| v.as_bool()) == Some(true);
+                match fs.delete_file(path, recursive).await {
+                    Ok(msg) => msg,
+                    Err(e) => format!("ERROR: {}", e),
+                }
+            }
+            "create_dir" => {
+                let Some(ref fs) = self.fs else {
+                    return "ERROR: create_dir tool is not enabled".to_string();
+                };
+                let path = args
+                    .get("path")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or_default();
+                let recursive = args.get("recursive").and_then(|v| v.as_bool()) == Some(true);
+                match fs.create_dir(path, recursive).await {
                     Ok(msg) => msg,
                     Err(e) => format!("ERROR: {}", e),
                 }
@@ -78,7 +233,7 @@ impl ToolRegistry {
                     return "ERROR: list_dir tool is not enabled".to_string();
                 };
                 let path = args.get("path").and_then(|v| v.as_str()).unwrap_or(".");
-                match fs.list_dir(path).await {
+                match fs.list_dir(path, include_ignored(&args)).await {
                     Ok(listing) => listing,
                     Err(e) => format!("ERROR: {}", e),
                 }
@@ -102,6 +257,32 @@ impl ToolRegistry {
                     Err(e) => format!("ERROR: {}", e),
                 }
             }
+            "find_files" => {
+                let Some(ref fs) = self.fs else {
+                    return "ERROR: find_files tool is not enabled".to_string();
+                };
+                let pattern = args
+                    .get("pattern")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or_default();
+                match fs.find_files(pattern, include_ignored(&args)).await {
+                    Ok(found) => found,
+                    Err(e) => format!("ERROR: {}", e),
+                }
+            }
+            "search_content" => {
+                let Some(ref fs) = self.fs else {
+                    return "ERROR: search_content tool is not enabled".to_string();
+                };
+                let query = args
+                    .get("query")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or_default();
+                match fs.search_content(query, include_ignored(&args)).await {
+                    Ok(found) => found,
+                    Err(e) => format!("ERROR: {}", e),
+                }
+            }
             "run_command" => {
                 let Some(ref terminal) = self.terminal else {
                     return "ERROR: run_command tool is not enabled".to_string();
@@ -110,7 +291,18 @@ impl ToolRegistry {
                     .get("command")
                     .and_then(|v| v.as_str())
                     .unwrap_or_default();
-                match terminal.execute(command).await {
+                let format = match terminal::OutputFormat::from_args(&args) {
+                    Ok(format) => format,
+                    Err(e) => return format!("ERROR: {}", e),
+                };
+                let options = match terminal::CommandOptions::from_args(&args) {
+                    Ok(options) => terminal::CommandOptions {
+                        task_id: task_id.map(str::to_string),
+                        ..options
+                    },
+                    Err(e) => return format!("ERROR: {}", e),
+                };
+                match terminal.execute_structured(command, options, format).await {
                     Ok(output) => output,
                     Err(e) => format!("ERROR: {}", e),
                 }
@@ -123,8 +315,93 @@ impl ToolRegistry {
                     .get("output_file")
                     .and_then(|v| v.as_str())
                     .unwrap_or("screenshot.png");
-                match vision.capture_screen(output_file).await {
-                    Ok(path) => format!("Screenshot saved to {}", path.display()),
+                match vision::CaptureTarget::from_args(&args) {
+                    Ok(vision::CaptureTarget::Screen) => {
+                        match vision.capture_screen(output_file).await {
+                            Ok(path) => format!("Screenshot saved to {}", path.display()),
+                            Err(e) => format!("ERROR: {}", e),
+                        }
+                    }
+                    Ok(vision::CaptureTarget::Window(title)) => {
+                        match vision.capture_window(&title, output_file).await {
+                            Ok(capture) => serde_json::to_string(&capture)
+                                .unwrap_or_else(|e| format!("ERROR: {}", e)),
+                            Err(e) => format!("ERROR: {}", e),
+                        }
+                    }
+                    Err(e) => format!("ERROR: {}", e),
+                }
+            }
+            "extract_text" => {
+                let Some(ref vision) = self.vision else {
+                    return "ERROR: extract_text tool is not enabled".to_string();
+                };
+                let options = match ocr::ExtractTextOptions::from_args(&args) {
+                    Ok(options) => options,
+                    Err(e) => return format!("ERROR: {}", e),
+                };
+                match vision.extract_text(options).await {
+                    Ok(text) => {
+                        serde_json::to_string(&text).unwrap_or_else(|e| format!("ERROR: {}", e))
+                    }
+                    Err(e) => format!("ERROR: {}", e),
+                }
+            }
+            "git_log" => {
+                let Some(ref git) = self.git else {
+                    return "ERROR: git_log tool is not enabled".to_string();
+                };
+                let options = match git::LogOptions::from_args(&args) {
+                    Ok(options) => options,
+                    Err(e) => return format!("ERROR: {}", e),
+                };
+                match git.log(&options).await {
+                    Ok(page) => {
+                        serde_json::to_string(&page).unwrap_or_else(|e| format!("ERROR: {}", e))
+                    }
+                    Err(e) => format!("ERROR: {}", e),
+                }
+            }
+            "git_diff" => {
+                let Some(ref git) = self.git else {
+                    return "ERROR: git_diff tool is not enabled".to_string();
+                };
+                let options = match git::DiffOptions::from_args(&args) {
+                    Ok(options) => options,
+                    Err(e) => return format!("ERROR: {}", e),
+                };
+                match git.diff(&options).await {
+                    Ok(output) => {
+                        serde_json::to_string(&output).unwrap_or_else(|e| format!("ERROR: {}", e))
+                    }
+                    Err(e) => format!("ERROR: {}", e),
+                }
+            }
+            "git_branch" => {
+                let Some(ref git) = self.git else {
+                    return "ERROR: git_branch tool is not enabled".to_string();
+                };
+                let action = match git::BranchAction::from_args(&args) {
+                    Ok(action) => action,
+                    Err(e) => return format!("ERROR: {}", e),
+                };
+                match git.branch(&action).await {
+                    Ok(output) => {
+                        serde_json::to_string(&output).unwrap_or_else(|e| format!("ERROR: {}", e))
+                    }
+                    Err(e) => format!("ERROR: {}", e),
+                }
+            }
+            "git_commit" => {
+                let Some(ref git) = self.git else {
+                    return "ERROR: git_commit tool is not enabled".to_string();
+                };
+                let input = match git::GitCommitInput::from_args(&args) {
+                    Ok(input) => input,
+                    Err(e) => return format!("ERROR: {}", e),
+                };
+                match git.commit(&input).await {
+                    Ok(summary) => summary,
                     Err(e) => format!("ERROR: {}", e),
                 }
             }
@@ -139,84 +416,263 @@ impl ToolRegistry {
         }
     }
 
-    /// Generate a system prompt describing the available tools.
+    /// Describe the available tools for the system prompt.
     ///
-    /// Only tools that are `Some` are included.
-    pub fn system_prompt(&self) -> String {
-        let mut parts = vec![
-            "You are Rove, an AI agent that can use tools to accomplish tasks.".to_string(),
-            String::new(),
-            "IMPORTANT RULES:".to_string(),
-            "1. To call a tool, your ENTIRE response must be ONLY the JSON object — nothing else. No explanation, no markdown fences, no text before or after.".to_string(),
-            "2. When you have the final answer (after receiving tool results), respond with plain text only — no JSON.".to_string(),
-            "3. Never guess or hallucinate tool output. Always call the tool and wait for the real result.".to_string(),
-            String::new(),
-            "Tool call format (your entire response must be exactly this):".to_string(),
-            r#"{"function": "tool_name", "arguments": {"arg1": "value1"}}"#.to_string(),
-            String::new(),
-            "Available tools:".to_string(),
-        ];
+    /// Only tools that are `Some` are included. The result is substituted for
+    /// `{{tools}}` in the agent's prompt template.
+    pub fn tool_descriptions(&self) -> String {
+        let mut sections: Vec<String> = Vec::new();
 
-        if self.fs.is_some() {
-            parts.push(String::new());
-            parts.push("## read_file".to_string());
-            parts.push("Read the contents of a file.".to_string());
-            parts.push(r#"Arguments: {"path": "relative/or/absolute/path"}"#.to_string());
-
-            parts.push(String::new());
-            parts.push("## write_file".to_string());
-            parts.push(
-                "Write content to a file (creates parent directories if needed).".to_string(),
+        if let Some(ref fs) = self.fs {
+            sections.push(
+                [
+                    "## read_file",
+                    "Read the contents of a file.",
+                    r#"Arguments: {"path": "relative/or/absolute/path"}"#,
+                ]
+                .join("\n"),
             );
-            parts.push(
-                r#"Arguments: {"path": "file/path", "content": "file contents"}"#.to_string(),
+            sections.push(
+                [
+                    "## read_files",
+                    "Read several files at once. Returns JSON with files mapping each path to {\"content\": ...} or {\"error\": ...}, and a truncated flag set when the combined contents hit max_total_bytes.",
+                    r#"Arguments: {"paths": ["a.txt", "b.txt"], "max_total_bytes": 100000}"#,
+                ]
+                .join("\n"),
             );
-
-            parts.push(String::new());
-            parts.push("## list_dir".to_string());
-            parts.push(
-                "List files and directories at a path. Returns entries with type, size, and name."
-                    .to_string(),
+            sections.push(
+                [
+                    "## tail_file",
+                    "Read the last lines of a file without reading the whole file, e.g. a large log. Returns JSON with content, the byte offset it starts at, and the number of lines.",
+                    r#"Arguments: {"path": "logs/access.log", "lines": 100}"#,
+                ]
+                .join("\n"),
             );
-            parts.push(r#"Arguments: {"path": "directory/path"}"#.to_string());
-
-            parts.push(String::new());
-            parts.push("## file_exists".to_string());
-            parts.push(
-                r#"Check if a file or directory exists. Returns "true" or "false"."#.to_string(),
+            let mut write_file = vec![
+                "## write_file",
+                "Write content to a file (creates parent directories if needed).",
+                r#"Arguments: {"path": "file/path", "content": "file contents"}"#,
+                r#"Optional: "mode": "append" to add to the end of the file instead of replacing it, "atomic": true to replace the file in one step so it is never left half-written."#,
+            ];
+            if fs.has_stdin() {
+                write_file.push(
+                    r#"To write the input piped to this run instead: {"path": "file/path", "from_stdin": true}"#,
+                );
+            }
+            sections.push(write_file.join("\n"));
+            sections.push(
+                [
+                    "## delete_file",
+                    "Delete a file. A directory is only deleted, with its contents, if recursive is true.",
+                    r#"Arguments: {"path": "file/path", "recursive": false}"#,
+                ]
+                .join("\n"),
+            );
+            sections.push(
+                [
+                    "## create_dir",
+                    "Create a directory. With recursive, missing parent directories are created too.",
+                    r#"Arguments: {"path": "directory/path", "recursive": false}"#,
+                ]
+                .join("\n"),
+            );
+            sections.push(
+                [
+                    "## list_dir",
+                    "List files and directories at a path. Returns entries with type, size, and name. Ignored entries (.gitignore, .git, target, node_modules) are hidden unless include_ignored is true.",
+                    r#"Arguments: {"path": "directory/path", "include_ignored": false}"#,
+                ]
+                .join("\n"),
+            );
+            sections.push(
+                [
+                    "## file_exists",
+                    r#"Check if a file or directory exists. Returns "true" or "false"."#,
+                    r#"Arguments: {"path": "file/path"}"#,
+                ]
+                .join("\n"),
+            );
+            sections.push(
+                [
+                    "## find_files",
+                    "Find files in the workspace by name. Supports * and ? wildcards; patterns containing / match the relative path. Ignored files are skipped unless include_ignored is true.",
+                    r#"Arguments: {"pattern": "*.rs", "include_ignored": false}"#,
+                ]
+                .join("\n"),
+            );
+            sections.push(
+                [
+                    "## search_content",
+                    "Search workspace files for lines containing text. Returns path:line: text for each match. Ignored files are skipped unless include_ignored is true.",
+                    r#"Arguments: {"query": "fn main", "include_ignored": false}"#,
+                ]
+                .join("\n"),
             );
-            parts.push(r#"Arguments: {"path": "file/path"}"#.to_string());
         }
 
         if self.terminal.is_some() {
-            parts.push(String::new());
-            parts.push("## run_command".to_string());
-            parts.push("Execute a shell command and return its output.".to_string());
-            parts.push(r#"Arguments: {"command": "shell command to run"}"#.to_string());
+            sections.push(
+                [
+                    "## run_command",
+                    "Execute a shell command and return its output. With output_format json or lines, stdout is parsed into {\"parsed\": ...}; output that doesn't parse is returned raw with parse_failed set. stdin, if given, is piped to the command. cwd is a workspace directory to run in; env adds environment variables.",
+                    r#"Arguments: {"command": "shell command to run", "stdin": "optional input", "cwd": "optional/dir", "env": {}, "output_format": "raw"}"#,
+                ]
+                .join("\n"),
+            );
         }
 
         if self.vision.is_some() {
-            parts.push(String::new());
-            parts.push("## capture_screen".to_string());
-            parts.push("Capture a screenshot and save it to a file.".to_string());
-            parts.push(r#"Arguments: {"output_file": "screenshot.png"}"#.to_string());
+            sections.push(
+                [
+                    "## capture_screen",
+                    "Capture a screenshot and save it to a file. With window_title, capture only the window whose title matches; the result includes its title and bounds.",
+                    r#"Arguments: {"output_file": "screenshot.png", "window_title": "optional"}"#,
+                ]
+                .join("\n"),
+            );
+            sections.push(
+                [
+                    "## extract_text",
+                    "Read the text in an image, such as a screenshot. With boxes, also return each word's position in pixels.",
+                    r#"Arguments: {"image_path": "screenshot.png", "boxes": false}"#,
+                ]
+                .join("\n"),
+            );
         }
 
-        parts.join("\n")
+        if self.git.is_some() {
+            sections.push(
+                [
+                    "## git_log",
+                    "Show commit history of the workspace repository, newest first. Page with count and skip; format is \"oneline\" or \"full\". Returns JSON with log and a truncated flag set when the output was cut short.",
+                    r#"Arguments: {"count": 5, "skip": 0, "format": "oneline"}"#,
+                ]
+                .join("\n"),
+            );
+            sections.push(
+                [
+                    "## git_commit",
+                    "Commit the staged changes, or every modified tracked file with all. author_name and author_email set the author of this commit instead of the repository's identity.",
+                    r#"Arguments: {"message": "commit message", "all": false}"#,
+                    r#"Optional: "author_name": "Name", "author_email": "name@example.com""#,
+                ]
+                .join("\n"),
+            );
+            sections.push(
+                [
+                    "## git_diff",
+                    "Show uncommitted changes in the workspace repository, or the staged ones with staged. path limits the diff to one file or directory. Returns JSON with stdout, stderr and a truncated flag.",
+                    r#"Arguments: {"path": "optional/path", "staged": false}"#,
+                ]
+                .join("\n"),
+            );
+            sections.push(
+                [
+                    "## git_branch",
+                    "List branches (action \"list\", the default), create one at the current commit (\"create\") or switch to an existing one (\"checkout\"). Returns JSON with stdout, stderr and a truncated flag.",
+                    r#"Arguments: {"action": "create", "name": "feature/name"}"#,
+                ]
+                .join("\n"),
+            );
+        }
+
+        if sections.is_empty() {
+            return "(no tools enabled)".to_string();
+        }
+
+        sections.join("\n\n")
     }
 
     /// Return the names of all currently enabled tools.
     fn available_tool_names(&self) -> Vec<&'static str> {
         let mut names = Vec::new();
         if self.fs.is_some() {
-            names.extend_from_slice(&["read_file", "write_file", "list_dir", "file_exists"]);
+            names.extend_from_slice(&[
+                "read_file",
+                "read_files",
+                "tail_file",
+                "write_file",
+                "delete_file",
+                "create_dir",
+                "list_dir",
+                "file_exists",
+                "find_files",
+                "search_content",
+            ]);
         }
         if self.terminal.is_some() {
             names.push("run_command");
         }
         if self.vision.is_some() {
-            names.push("capture_screen");
+            names.extend_from_slice(&["capture_screen", "extract_text"]);
+        }
+        if self.git.is_some() {
+            names.extend_from_slice(&["git_log", "git_commit", "git_diff", "git_branch"]);
         }
         names
     }
 }
+
+/// Read the optional `include_ignored` flag from tool arguments (default off).
+fn include_ignored(args: &serde_json::Value) -> bool {
+    args.get("include_ignored")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_disabled_plugin_is_reported_as_feature_disabled() {
+        let registry = ToolRegistry::empty();
+
+        let err = registry.check_enabled("run_command").unwrap_err();
+        assert!(matches!(err, EngineError::FeatureDisabled(_)));
+        assert_eq!(
+            err.to_string(),
+            "Plugin 'terminal' (providing run_command) is disabled by configuration"
+        );
+
+        let output = registry
+            .dispatch("run_command", r#"{"command": "ls"}"#)
+            .await;
+        assert_eq!(output, format!("ERROR: {}", err));
+        assert!(registry.check_enabled("no_such_tool").is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_write_file_from_stdin_is_local_only() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let workspace = dir.path().canonicalize().unwrap();
+        let stdin = std::io::Cursor::new(b"piped content\n".to_vec());
+        let registry = ToolRegistry {
+            fs: Some(FilesystemTool::new(workspace.clone()).with_stdin(Box::new(stdin))),
+            ..ToolRegistry::empty()
+        };
+        let args = r#"{"path": "out.txt", "from_stdin": true}"#;
+
+        let refused = registry
+            .dispatch_for_task("task-1", &OperationSource::Remote, "write_file", args)
+            .await;
+        assert_eq!(
+            refused,
+            "ERROR: from_stdin is only available to local tasks"
+        );
+        assert!(registry
+            .dispatch("write_file", args)
+            .await
+            .starts_with("ERROR:"));
+        assert!(!workspace.join("out.txt").exists());
+
+        let output = registry
+            .dispatch_for_task("task-1", &OperationSource::Local, "write_file", args)
+            .await;
+        assert!(!output.starts_with("ERROR:"), "{}", output);
+        assert_eq!(
+            std::fs::read_to_string(workspace.join("out.txt")).unwrap(),
+            "piped content\n"
+        );
+    }
+}
@@ -0,0 +1,337 @@
+//! OCR Text Extraction
+//!
+//! `extract_text` reads the text in an image, typically a screenshot taken
+//! with `capture_screen`, so the agent can "read the screen". Recognition is
+//! done by an `OcrEngine`; the default engine runs the `tesseract` CLI. The
+//! image path is validated through `FileSystemGuard` like any other file read.
+
+use crate::fs_guard::FileSystemGuard;
+use anyhow::{anyhow, Result};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use tracing::{info, warn};
+
+/// Validated arguments of an `extract_text` call
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExtractTextOptions {
+    pub image_path: String,
+    /// Include word bounding boxes in the result
+    pub boxes: bool,
+}
+
+impl ExtractTextOptions {
+    /// Parse `{"image_path": "...", "boxes": bool}`
+    ///
+    /// `image_path` is required; `boxes` defaults to false.
+    pub fn from_args(args: &serde_json::Value) -> Result<Self> {
+        let image_path = match args.get("image_path") {
+            Some(serde_json::Value::String(path)) if !path.trim().is_empty() => path.clone(),
+            Some(other) => {
+                return Err(anyhow!(
+                    "image_path must be a non-empty string, got {}",
+                    other
+                ))
+            }
+            None => return Err(anyhow!("image_path is required")),
+        };
+
+        let boxes = match args.get("boxes") {
+            None | Some(serde_json::Value::Null) => false,
+            Some(serde_json::Value::Bool(boxes)) => *boxes,
+            Some(other) => return Err(anyhow!("boxes must be a boolean, got {}", other)),
+        };
+
+        Ok(Self { image_path, boxes })
+    }
+}
+
+/// A recognized word and where it is in the image, in pixels
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TextBox {
+    pub text: String,
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+    /// Engine confidence from 0 to 100
+    pub confidence: f32,
+}
+
+/// Output of an OCR engine
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct Recognized {
+    /// Recognized text, one line of the image per line
+    pub text: String,
+    pub boxes: Vec<TextBox>,
+}
+
+/// Result of `extract_text`, returned to the LLM as JSON
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ExtractedText {
+    pub image_path: PathBuf,
+    pub text: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub boxes: Option<Vec<TextBox>>,
+}
+
+/// Text recognition backend
+pub trait OcrEngine: std::fmt::Debug + Send + Sync {
+    fn recognize(&self, image: &Path) -> Result<Recognized>;
+}
+
+/// Recognition with the `tesseract` command-line tool
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Tesseract;
+
+impl OcrEngine for Tesseract {
+    fn recognize(&self, image: &Path) -> Result<Recognized> {
+        let output = std::process::Command::new("tesseract")
+            .arg(image)
+            .args(["stdout", "tsv"])
+            .output()
+            .map_err(|e| {
+                warn!("Failed to execute tesseract: {}", e);
+                anyhow!("Failed to execute tesseract (is it installed?): {}", e)
+            })?;
+        if !output.status.success() {
+            let err = String::from_utf8_lossy(&output.stderr);
+            warn!("tesseract failed: {}", err.trim());
+            return Err(anyhow!("OCR failed: {}", err.trim()));
+        }
+        Ok(parse_tsv(&String::from_utf8_lossy(&output.stdout)))
+    }
+}
+
+/// Validate the image path and run OCR on it
+///
+/// Blocking; the `ocr` host function calls this directly.
+pub fn extract_text(
+    guard: &FileSystemGuard,
+    engine: &dyn OcrEngine,
+    options: &ExtractTextOptions,
+) -> Result<ExtractedText> {
+    let target = Path::new(&options.image_path);
+    let abs = if target.is_absolute() {
+        target.to_path_buf()
+    } else {
+        guard.workspace().join(target)
+    };
+    let image_path = guard.validate_path(&abs).map_err(|e| {
+        warn!("Path validation failed for {}: {}", abs.display(), e);
+        anyhow!("{}", e)
+    })?;
+
+    info!("Extracting text from {}", image_path.display());
+    let recognized = engine.recognize(&image_path)?;
+
+    Ok(ExtractedText {
+        image_path,
+        text: recognized.text,
+        boxes: options.boxes.then_some(recognized.boxes),
+    })
+}
+
+/// Parse `tesseract ... tsv` output into text and word boxes
+///
+/// Columns: level, page, block, paragraph, line, word, left, top, width,
+/// height, confidence, text. Only word rows (level 5) carry text.
+fn parse_tsv(tsv: &str) -> Recognized {
+    let mut lines: Vec<String> = Vec::new();
+    let mut current_line = None;
+    let mut boxes = Vec::new();
+
+    for row in tsv.lines().skip(1) {
+        let cols: Vec<&str> = row.splitn(12, '\t').collect();
+        if cols.len() < 12 || cols[0] != "5" {
+            continue;
+        }
+        let text = cols[11].trim();
+        let (Ok(x), Ok(y), Ok(width), Ok(height), Ok(confidence)) = (
+            cols[6].parse(),
+            cols[7].parse(),
+            cols[8].parse(),
+            cols[9].parse(),
+            cols[10].parse::<f32>(),
+        ) else {
+            continue;
+        };
+        if text.is_empty() || confidence < 0.0 {
+            continue;
+        }
+
+        // Block, paragraph and line numbers identify the line of a word
+        let line_id = (cols[1], cols[2], cols[3], cols[4]);
+        if current_line == Some(line_id) {
+            if let Some(last) = lines.last_mut() {
+                last.push(' ');
+                last.push_str(text);
+            }
+        } else {
+            lines.push(text.to_string());
+            current_line = Some(line_id);
+        }
+
+        boxes.push(TextBox {
+            text: text.to_string(),
+            x,
+            y,
+            width,
+            height,
+            confidence,
+        });
+    }
+
+    Recognized {
+        text: lines.join("\n"),
+        boxes,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    /// Returns a fixed result instead of running an OCR engine
+    #[derive(Debug)]
+    struct MockOcr;
+
+    impl OcrEngine for MockOcr {
+        fn recognize(&self, _image: &Path) -> Result<Recognized> {
+            Ok(Recognized {
+                text: "Build failed\nerror[E0308]".to_string(),
+                boxes: vec![TextBox {
+                    text: "Build".to_string(),
+                    x: 12,
+                    y: 8,
+                    width: 40,
+                    height: 14,
+                    confidence: 96.5,
+                }],
+            })
+        }
+    }
+
+    fn workspace_with_image() -> (TempDir, FileSystemGuard) {
+        let dir = TempDir::new().unwrap();
+        std::fs::write(dir.path().join("screen.png"), b"\x89PNG").unwrap();
+        let guard = FileSystemGuard::new(dir.path().to_path_buf());
+        (dir, guard)
+    }
+
+    #[test]
+    fn test_options_validation() {
+        let options = ExtractTextOptions::from_args(&json!({"image_path": "screen.png"})).unwrap();
+        assert_eq!(
+            options,
+            ExtractTextOptions {
+                image_path: "screen.png".to_string(),
+                boxes: false,
+            }
+        );
+
+        let options =
+            ExtractTextOptions::from_args(&json!({"image_path": "screen.png", "boxes": true}))
+                .unwrap();
+        assert!(options.boxes);
+
+        for args in [
+            json!({}),
+            json!({"image_path": ""}),
+            json!({"image_path": 7}),
+            json!({"image_path": "screen.png", "boxes": "yes"}),
+        ] {
+            assert!(
+                ExtractTextOptions::from_args(&args).is_err(),
+                "accepted {}",
+                args
+            );
+        }
+    }
+
+    #[test]
+    fn test_output_structure() {
+        let (_dir, guard) = workspace_with_image();
+
+        let options = ExtractTextOptions::from_args(&json!({"image_path": "screen.png"})).unwrap();
+        let result = extract_text(&guard, &MockOcr, &options).unwrap();
+        let value = serde_json::to_value(&result).unwrap();
+        assert_eq!(value["text"], "Build failed\nerror[E0308]");
+        assert!(value["image_path"]
+            .as_str()
+            .unwrap()
+            .ends_with("screen.png"));
+        assert!(value.get("boxes").is_none());
+
+        let options =
+            ExtractTextOptions::from_args(&json!({"image_path": "screen.png", "boxes": true}))
+                .unwrap();
+        let value =
+            serde_json::to_value(extract_text(&guard, &MockOcr, &options).unwrap()).unwrap();
+        assert_eq!(
+            value["boxes"],
+            json!([{
+                "text": "Build",
+                "x": 12,
+                "y": 8,
+                "width": 40,
+                "height": 14,
+                "confidence": 96.5
+            }])
+        );
+    }
+
+    #[test]
+    fn test_image_path_goes_through_guard() {
+        let (dir, guard) = workspace_with_image();
+        std::fs::write(dir.path().join(".env"), b"SECRET=1").unwrap();
+        let outside = TempDir::new().unwrap();
+        std::fs::write(outside.path().join("other.png"), b"\x89PNG").unwrap();
+
+        for path in [
+            ".env".to_string(),
+            "missing.png".to_string(),
+            "../other.png".to_string(),
+            outside.path().join("other.png").display().to_string(),
+        ] {
+            let options = ExtractTextOptions {
+                image_path: path.clone(),
+                boxes: false,
+            };
+            assert!(
+                extract_text(&guard, &MockOcr, &options).is_err(),
+                "read {}",
+                path
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_tesseract_tsv() {
+        let tsv = "level\tpage_num\tblock_num\tpar_num\tline_num\tword_num\tleft\ttop\twidth\theight\tconf\ttext\n\
+                   1\t1\t0\t0\t0\t0\t0\t0\t640\t480\t-1\t\n\
+                   4\t1\t1\t1\t1\t0\t12\t8\t120\t14\t-1\t\n\
+                   5\t1\t1\t1\t1\t1\t12\t8\t40\t14\t96.5\tBuild\n\
+                   5\t1\t1\t1\t1\t2\t56\t8\t50\t14\t91\tfailed\n\
+                   5\t1\t1\t1\t2\t1\t12\t30\t90\t14\t88.25\terror[E0308]\n\
+                   5\t1\t1\t1\t2\t2\t110\t30\t4\t14\t-1\t \n";
+
+        let recognized = parse_tsv(tsv);
+        assert_eq!(recognized.text, "Build failed\nerror[E0308]");
+        assert_eq!(recognized.boxes.len(), 3);
+        assert_eq!(
+            recognized.boxes[1],
+            TextBox {
+                text: "failed".to_string(),
+                x: 56,
+                y: 8,
+                width: 50,
+                height: 14,
+                confidence: 91.0,
+            }
+        );
+
+        assert_eq!(parse_tsv(""), Recognized::default());
+    }
+}
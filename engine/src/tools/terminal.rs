@@ -4,116 +4,586 @@
 //! on the host OS with the same privileges as the Rove daemon. Execution
 //! is routed through `CommandExecutor` for allowlist validation and shell
 //! injection prevention.
+//!
+//! A command may declare the format of its stdout (`output_format`), in which
+//! case the output is parsed into JSON the agent can use directly. Output that
+//! doesn't parse is returned raw and flagged with `"parse_failed": true`.
+//!
+//! A command may also be given (see `CommandOptions`):
+//! - `stdin` (at most `MAX_STDIN_BYTES`), which is written to the child's
+//!   stdin before it is closed. Without it stdin is null.
+//! - `cwd`, a directory inside the workspace to run in instead of its root
+//! - `env`, variables merged onto the base environment
+//!
+//! Commands run through `CommandExecutor::execute_in_with`, so they don't
+//! inherit the daemon's environment (which may hold API keys), only the
+//! variables in `SAFE_ENV_VARS`, and a command that runs too long is killed
+//! together with everything it spawned.
+//!
+//! With a message bus attached (`with_message_bus`), a command run for a task
+//! streams its output while it runs: every line of stdout and stderr is
+//! published as an `Event::CommandOutput`, and an `Event::CommandExited`
+//! with the exit code follows once the command has exited. Events are
+//! published with `publish_nowait`, so a slow or absent subscriber never
+//! holds up the command.
 
-use anyhow::Result;
+use anyhow::{anyhow, Result};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use std::time::Duration;
 use tracing::{debug, info, warn};
 
-use crate::command_executor::CommandExecutor;
+use crate::command_executor::{CommandError, CommandExecutor, ExecOptions, OutputSink};
+use crate::message_bus::{Event, MessageBus, OutputStream};
+
+/// Largest `stdin` payload a command can be given (1 MiB)
+pub const MAX_STDIN_BYTES: usize = 1024 * 1024;
+
+/// Optional inputs of a command
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CommandOptions {
+    /// Written to the command's stdin
+    pub stdin: Option<String>,
+    /// Directory to run in, relative to the workspace
+    pub cwd: Option<String>,
+    /// Variables merged onto `SAFE_ENV_VARS`
+    pub env: HashMap<String, String>,
+    /// Task the command runs for; its output is streamed under this id
+    /// when a message bus is attached. Not read from tool arguments.
+    pub task_id: Option<String>,
+}
+
+impl CommandOptions {
+    /// Parse the optional `stdin`, `cwd` and `env` arguments of `run_command`
+    pub fn from_args(args: &Value) -> Result<Self> {
+        let string = |key: &str| match args.get(key) {
+            None | Some(Value::Null) => Ok(None),
+            Some(Value::String(s)) => Ok(Some(s.clone())),
+            Some(other) => Err(anyhow!("{} must be a string, got {}", key, other)),
+        };
+        let env = match args.get("env") {
+            None | Some(Value::Null) => HashMap::new(),
+            Some(env) => serde_json::from_value(env.clone())
+                .map_err(|_| anyhow!("env must be an object of strings, got {}", env))?,
+        };
+        Ok(Self {
+            stdin: string("stdin")?,
+            cwd: string("cwd")?,
+            env,
+            task_id: None,
+        })
+    }
+}
+
+/// Declared format of a command's stdout
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// Plain text, returned as is
+    Raw,
+    /// A single JSON document
+    Json,
+    /// One item per line
+    Lines,
+}
+
+impl OutputFormat {
+    /// Parse the optional `output_format` argument of `run_command`
+    pub fn from_args(args: &Value) -> Result<Self> {
+        match args.get("output_format") {
+            None | Some(Value::Null) => Ok(OutputFormat::Raw),
+            Some(Value::String(format)) => match format.as_str() {
+                "raw" => Ok(OutputFormat::Raw),
+                "json" => Ok(OutputFormat::Json),
+                "lines" => Ok(OutputFormat::Lines),
+                other => Err(anyhow!(
+                    "Unknown output_format '{}', expected raw, json or lines",
+                    other
+                )),
+            },
+            Some(other) => Err(anyhow!("output_format must be a string, got {}", other)),
+        }
+    }
+}
+
+/// Structure `output` according to `format`
+///
+/// Returns `{"parsed": ...}`, or `{"output": ..., "parsed": null,
+/// "parse_failed": true, "parse_error": ...}` when the output doesn't parse.
+/// `Raw` output is not structured at all.
+pub fn structure_output(output: String, format: OutputFormat) -> String {
+    let parsed = match format {
+        OutputFormat::Raw => return output,
+        OutputFormat::Json => serde_json::from_str::<Value>(&output).map_err(|e| e.to_string()),
+        OutputFormat::Lines => Ok(json!(output.lines().collect::<Vec<_>>())),
+    };
+    match parsed {
+        Ok(parsed) => json!({ "parsed": parsed }).to_string(),
+        Err(e) => {
+            debug!("Command output is not valid {:?}: {}", format, e);
+            json!({
+                "output": output,
+                "parsed": null,
+                "parse_failed": true,
+                "parse_error": e,
+            })
+            .to_string()
+        }
+    }
+}
+
+/// Publishes the output of one command on the message bus
+#[derive(Clone)]
+struct OutputPublisher {
+    bus: Arc<MessageBus>,
+    task_id: String,
+}
+
+impl OutputPublisher {
+    fn chunk(&self, stream: OutputStream, chunk: &[u8]) {
+        self.bus.publish_nowait(Event::CommandOutput {
+            task_id: self.task_id.clone(),
+            stream,
+            chunk: String::from_utf8_lossy(chunk).into_owned(),
+        });
+    }
+
+    fn exited(&self, exit_code: Option<i32>) {
+        self.bus.publish_nowait(Event::CommandExited {
+            task_id: self.task_id.clone(),
+            exit_code,
+        });
+    }
+}
 
-#[derive(Debug)]
 pub struct TerminalTool {
     work_dir: String,
     timeout: Duration,
     executor: CommandExecutor,
+    message_bus: Option<Arc<MessageBus>>,
+}
+
+impl std::fmt::Debug for TerminalTool {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TerminalTool")
+            .field("work_dir", &self.work_dir)
+            .field("timeout", &self.timeout)
+            .field("executor", &self.executor)
+            .field("streaming", &self.message_bus.is_some())
+            .finish()
+    }
 }
 
 impl TerminalTool {
     pub fn new(work_dir: String) -> Self {
+        let executor = CommandExecutor::new().with_workspace(PathBuf::from(&work_dir));
         Self {
             work_dir,
             timeout: Duration::from_secs(60), // Default 60s timeout
-            executor: CommandExecutor::new(),
+            executor,
+            message_bus: None,
         }
     }
 
+    /// Use a custom executor, e.g. one with extra allowed commands from config
+    ///
+    /// Commands still run in this tool's `work_dir`.
+    pub fn with_executor(mut self, executor: CommandExecutor) -> Self {
+        self.executor = executor.with_workspace(PathBuf::from(&self.work_dir));
+        self
+    }
+
+    /// Stream the output of commands run for a task on `bus`
+    pub fn with_message_bus(mut self, bus: Arc<MessageBus>) -> Self {
+        self.message_bus = Some(bus);
+        self
+    }
+
+    /// Execute a command and structure its output according to `format`
+    ///
+    /// See `structure_output`; failed commands are errors as with `execute`.
+    pub async fn execute_structured(
+        &self,
+        command: &str,
+        options: CommandOptions,
+        format: OutputFormat,
+    ) -> Result<String> {
+        let output = self.execute_with(command, options).await?;
+        Ok(structure_output(output, format))
+    }
+
     /// Execute a command through the secure CommandExecutor
     ///
     /// The command string is parsed into program + arguments and routed through
-    /// `CommandExecutor::execute()` which enforces:
+    /// `CommandExecutor::execute_in_with()` which enforces:
     /// - Allowlist validation
     /// - Shell invocation rejection
     /// - Shell metacharacter detection
     /// - Dangerous pipe pattern detection
     /// - execve-style execution (no shell)
     pub async fn execute(&self, command: &str) -> Result<String> {
+        self.execute_with(command, CommandOptions::default()).await
+    }
+
+    /// Execute a command like `execute`, with the given stdin, working
+    /// directory and environment
+    ///
+    /// Fails without running the command if `stdin` is larger than
+    /// `MAX_STDIN_BYTES`, `cwd` is not a directory inside the workspace, or
+    /// `env` sets a protected variable such as `PATH` or `LD_PRELOAD`.
+    pub async fn execute_with(&self, command: &str, options: CommandOptions) -> Result<String> {
         info!("Executing terminal command: {}", command);
 
+        let CommandOptions {
+            stdin,
+            cwd,
+            env,
+            task_id,
+        } = options;
+        if let Some(ref input) = stdin {
+            if input.len() > MAX_STDIN_BYTES {
+                return Err(anyhow!(
+                    "stdin is {} bytes, more than the {} byte limit",
+                    input.len(),
+                    MAX_STDIN_BYTES
+                ));
+            }
+        }
+
         // Parse command into program and arguments
         let parts: Vec<&str> = command.split_whitespace().collect();
         if parts.is_empty() {
             return Err(anyhow::anyhow!("Empty command"));
         }
 
-        let program = parts[0];
+        let program = parts[0].to_string();
         let args: Vec<String> = parts[1..].iter().map(|s| s.to_string()).collect();
 
-        // Route through CommandExecutor for security validation
+        let publisher = match (&self.message_bus, task_id) {
+            (Some(bus), Some(task_id)) => Some(OutputPublisher {
+                bus: Arc::clone(bus),
+                task_id,
+            }),
+            _ => None,
+        };
+        let on_output = publisher.clone().map(|publisher| -> OutputSink {
+            Arc::new(move |stream, chunk| publisher.chunk(stream, chunk))
+        });
+        let exec_options = ExecOptions {
+            env: env.into_iter().collect(),
+            stdin,
+            timeout: Some(self.timeout),
+            on_output,
+        };
+        let cwd = cwd.unwrap_or_else(|| ".".to_string());
+
+        // The executor runs every security gate, then the pinned binary in
+        // `cwd` with a controlled environment, killing it on timeout
         let executor = self.executor.clone();
-        let program_owned = program.to_string();
-        let work_dir = self.work_dir.clone();
-        let timeout = self.timeout;
-
-        let result = tokio::time::timeout(timeout, tokio::task::spawn_blocking(move || {
-            // Execute with security gates via CommandExecutor
-            // We need to set working directory, so we use a modified approach
-            use std::process::{Command, Stdio};
-
-            // First validate through CommandExecutor's security gates
-            // (allowlist, shell rejection, metachar, pipe detection)
-            match executor.validate(&program_owned, &args) {
-                Ok(()) => {}
-                Err(e) => {
-                    return Err(anyhow::anyhow!("Command rejected: {}", e));
+        let run_cwd = cwd.clone();
+        let result = tokio::task::spawn_blocking(move || {
+            let result =
+                executor.execute_in_with(&program, &args, Path::new(&run_cwd), exec_options);
+            if let Some(ref publisher) = publisher {
+                match result {
+                    Ok(ref output) | Err(CommandError::Timeout { ref output, .. }) => {
+                        publisher.exited(output.status.code())
+                    }
+                    Err(_) => {}
                 }
             }
+            result
+        })
+        .await
+        .map_err(|e| {
+            warn!("Command task panicked: {}", e);
+            anyhow!("Command execution panicked: {}", e)
+        })?;
 
-            // Execute with working directory set (CommandExecutor doesn't support cwd)
-            let output = Command::new(&program_owned)
-                .args(&args)
-                .current_dir(&work_dir)
-                .stdin(Stdio::null())
-                .stdout(Stdio::piped())
-                .stderr(Stdio::piped())
-                .output()
-                .map_err(|e| anyhow::anyhow!("Failed to start command: {}", e))?;
-
-            let stdout = String::from_utf8_lossy(&output.stdout).to_string();
-            let stderr = String::from_utf8_lossy(&output.stderr).to_string();
-
-            if output.status.success() {
-                if stdout.is_empty() && !stderr.is_empty() {
-                    Ok(stderr)
-                } else {
-                    Ok(stdout)
+        let output = result.map_err(|e| {
+            let e = match e {
+                CommandError::InvalidWorkingDirectory(_, reason) => {
+                    anyhow!("Invalid cwd '{}': {}", cwd, reason)
                 }
+                CommandError::Timeout { .. } => anyhow!("{}", e),
+                CommandError::ExecutionFailed(e) => anyhow!("Failed to run command: {}", e),
+                e => anyhow!("Command rejected: {}", e),
+            };
+            warn!("Command failed: {}", e);
+            e
+        })?;
+
+        let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+        let stderr = String::from_utf8_lossy(&output.stderr).to_string();
+
+        if output.status.success() {
+            debug!("Command succeeded");
+            if stdout.is_empty() && !stderr.is_empty() {
+                Ok(stderr)
             } else {
-                Err(anyhow::anyhow!(
-                    "Command failed with status: {}\nStdout: {}\nStderr: {}",
-                    output.status, stdout, stderr
-                ))
+                Ok(stdout)
             }
-        }))
-        .await;
+        } else {
+            let e = anyhow!(
+                "Command failed with status: {}\nStdout: {}\nStderr: {}",
+                output.status,
+                stdout,
+                stderr
+            );
+            warn!("Command failed: {}", e);
+            Err(e)
+        }
+    }
+}
 
-        match result {
-            Ok(Ok(Ok(output))) => {
-                debug!("Command succeeded");
-                Ok(output)
-            }
-            Ok(Ok(Err(e))) => {
-                warn!("Command failed: {}", e);
-                Err(e)
-            }
-            Ok(Err(e)) => {
-                warn!("Command task panicked: {}", e);
-                Err(anyhow::anyhow!("Command execution panicked: {}", e))
-            }
-            Err(_) => {
-                let err_msg = format!("Command timed out after {} seconds", self.timeout.as_secs());
-                warn!("{}", err_msg);
-                Err(anyhow::anyhow!(err_msg))
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command_executor::SAFE_ENV_VARS;
+    use crate::message_bus::EventType;
+    use tempfile::TempDir;
+
+    fn terminal_with(name: &str, contents: &str) -> (TempDir, TerminalTool) {
+        let dir = TempDir::new().unwrap();
+        std::fs::write(dir.path().join(name), contents).unwrap();
+        let terminal = TerminalTool::new(dir.path().to_string_lossy().to_string());
+        (dir, terminal)
+    }
+
+    #[tokio::test]
+    async fn test_json_stdout_is_parsed() {
+        let (_dir, terminal) = terminal_with("status.json", r#"{"clean": true, "ahead": 2}"#);
+
+        let result = terminal
+            .execute_structured(
+                "cat status.json",
+                CommandOptions::default(),
+                OutputFormat::Json,
+            )
+            .await
+            .unwrap();
+
+        let result: Value = serde_json::from_str(&result).unwrap();
+        assert_eq!(result, json!({"parsed": {"clean": true, "ahead": 2}}));
+    }
+
+    #[tokio::test]
+    async fn test_unparseable_stdout_falls_back_to_raw() {
+        let (_dir, terminal) = terminal_with("notes.txt", "not json\nat all\n");
+
+        let result = terminal
+            .execute_structured(
+                "cat notes.txt",
+                CommandOptions::default(),
+                OutputFormat::Json,
+            )
+            .await
+            .unwrap();
+
+        let result: Value = serde_json::from_str(&result).unwrap();
+        assert_eq!(result["output"], "not json\nat all\n");
+        assert_eq!(result["parsed"], Value::Null);
+        assert_eq!(result["parse_failed"], true);
+
+        let lines = structure_output("a\nb\n".to_string(), OutputFormat::Lines);
+        assert_eq!(lines, r#"{"parsed":["a","b"]}"#);
+        let raw = terminal
+            .execute_structured(
+                "cat notes.txt",
+                CommandOptions::default(),
+                OutputFormat::Raw,
+            )
+            .await
+            .unwrap();
+        assert_eq!(raw, "not json\nat all\n");
+    }
+
+    #[test]
+    fn test_output_format_from_args() {
+        assert_eq!(
+            OutputFormat::from_args(&json!({})).unwrap(),
+            OutputFormat::Raw
+        );
+        assert_eq!(
+            OutputFormat::from_args(&json!({"output_format": "json"})).unwrap(),
+            OutputFormat::Json
+        );
+        assert!(OutputFormat::from_args(&json!({"output_format": "yaml"})).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_stdin_is_fed_to_the_command() {
+        let (_dir, terminal) = terminal_with("unused.txt", "");
+
+        let options = CommandOptions {
+            stdin: Some("pear\napple\nfig\n".to_string()),
+            ..CommandOptions::default()
+        };
+        let sorted = terminal.execute_with("sort", options).await.unwrap();
+        assert_eq!(sorted, "apple\nfig\npear\n");
+
+        // Without stdin the command sees an empty, closed input
+        assert_eq!(terminal.execute("cat").await.unwrap(), "");
+    }
+
+    #[tokio::test]
+    async fn test_oversized_stdin_is_rejected() {
+        let (_dir, terminal) = terminal_with("unused.txt", "");
+
+        let options = CommandOptions {
+            stdin: Some("x".repeat(MAX_STDIN_BYTES + 1)),
+            ..CommandOptions::default()
+        };
+        let err = terminal.execute_with("cat", options).await.unwrap_err();
+
+        assert!(err.to_string().contains("byte limit"));
+    }
+
+    #[tokio::test]
+    async fn test_cwd_is_resolved_inside_the_workspace() {
+        let (dir, terminal) = terminal_with("top.txt", "top");
+        std::fs::create_dir(dir.path().join("sub")).unwrap();
+        std::fs::write(dir.path().join("sub").join("nested.txt"), "nested").unwrap();
+
+        let options = CommandOptions {
+            cwd: Some("sub".to_string()),
+            ..CommandOptions::default()
+        };
+        assert_eq!(
+            terminal.execute_with("ls", options).await.unwrap(),
+            "nested.txt\n"
+        );
+
+        for cwd in ["..", "/tmp", "missing"] {
+            let options = CommandOptions {
+                cwd: Some(cwd.to_string()),
+                ..CommandOptions::default()
+            };
+            let err = terminal.execute_with("ls", options).await.unwrap_err();
+            assert!(err.to_string().contains("Invalid cwd"), "{}: {}", cwd, err);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_env_is_merged_onto_the_base_env() {
+        let (_dir, terminal) = terminal_with("unused.txt", "");
+        let terminal = terminal.with_executor(CommandExecutor::with_extra_allowed(&[
+            "printenv".to_string()
+        ]));
+        let args = json!({"env": {"ROVE_GREETING": "hello"}});
+
+        let options = CommandOptions::from_args(&args).unwrap();
+        let output = terminal.execute_with("printenv", options).await.unwrap();
+
+        let names: Vec<&str> = output
+            .lines()
+            .filter_map(|line| line.split_once('=').map(|(name, _)| name))
+            .collect();
+        assert!(output.contains("ROVE_GREETING=hello"));
+        for name in names {
+            assert!(
+                name == "ROVE_GREETING" || SAFE_ENV_VARS.contains(&name),
+                "inherited {}",
+                name
+            );
+        }
+
+        let options = CommandOptions {
+            env: HashMap::from([("LD_PRELOAD".to_string(), "/tmp/evil.so".to_string())]),
+            ..CommandOptions::default()
+        };
+        let err = terminal.execute_with("ls", options).await.unwrap_err();
+        assert!(err.to_string().contains("LD_PRELOAD"));
+    }
+
+    #[tokio::test]
+    async fn test_output_is_streamed_on_the_message_bus() {
+        let (_dir, terminal) = terminal_with("build.log", "Compiling rove\nFinished\n");
+        let bus = Arc::new(MessageBus::new());
+        let mut rx = bus.subscribe(EventType::CommandOutput).await;
+        let terminal = terminal.with_message_bus(Arc::clone(&bus));
+
+        let options = CommandOptions {
+            task_id: Some("task-1".to_string()),
+            ..CommandOptions::default()
+        };
+        let output = terminal
+            .execute_with("cat build.log", options)
+            .await
+            .unwrap();
+        assert_eq!(output, "Compiling rove\nFinished\n");
+
+        let mut events = Vec::new();
+        while let Ok(event) = rx.try_recv() {
+            events.push(event);
+        }
+        let chunks: Vec<&str> = events
+            .iter()
+            .filter_map(|event| match event {
+                Event::CommandOutput {
+                    task_id,
+                    stream: OutputStream::Stdout,
+                    chunk,
+                } if task_id == "task-1" => Some(chunk.as_str()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(chunks, ["Compiling rove\n", "Finished\n"]);
+        assert!(matches!(
+            events.last(),
+            Some(Event::CommandExited { task_id, exit_code: Some(0) }) if task_id == "task-1"
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_unread_subscriber_does_not_block_the_command() {
+        let lines: String = (0..1000).map(|i| format!("line {}\n", i)).collect();
+        let (_dir, terminal) = terminal_with("long.log", &lines);
+        let bus = Arc::new(MessageBus::new());
+        // Subscribed, but never read: its channel fills up
+        let _rx = bus.subscribe(EventType::All).await;
+        let terminal = terminal.with_message_bus(bus);
+
+        let options = CommandOptions {
+            task_id: Some("task-2".to_string()),
+            ..CommandOptions::default()
+        };
+        let output = terminal
+            .execute_with("cat long.log", options)
+            .await
+            .unwrap();
+
+        assert_eq!(output, lines);
+    }
+
+    #[tokio::test]
+    async fn test_timed_out_command_is_killed() {
+        let (_dir, mut terminal) = terminal_with("build.log", "partial\n");
+        terminal.timeout = Duration::from_millis(300);
+        let bus = Arc::new(MessageBus::new());
+        let mut rx = bus.subscribe(EventType::All).await;
+        let terminal = terminal.with_message_bus(Arc::clone(&bus));
+
+        // `tail -f` never exits on its own
+        let options = CommandOptions {
+            task_id: Some("task-3".to_string()),
+            ..CommandOptions::default()
+        };
+        let started = std::time::Instant::now();
+        let err = terminal
+            .execute_with("tail -f build.log", options)
+            .await
+            .unwrap_err();
+
+        assert!(started.elapsed() < Duration::from_secs(10));
+        assert!(err.to_string().contains("timed out"), "{}", err);
+        let mut exited = None;
+        while let Ok(event) = rx.try_recv() {
+            if let Event::CommandExited { exit_code, .. } = event {
+                exited = Some(exit_code);
             }
         }
+        assert_eq!(exited, Some(None));
     }
 }
@@ -1,15 +1,73 @@
-use anyhow::Result;
-use std::path::PathBuf;
+use super::ocr::{self, ExtractTextOptions, ExtractedText, OcrEngine, Tesseract};
+use crate::fs_guard::FileSystemGuard;
+use anyhow::{anyhow, Result};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use tracing::{info, warn};
 
+/// What `capture_screen` should capture
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CaptureTarget {
+    /// The whole screen
+    Screen,
+    /// The single window whose title matches
+    Window(String),
+}
+
+impl CaptureTarget {
+    /// Parse the optional `window_title` argument of `capture_screen`
+    pub fn from_args(args: &serde_json::Value) -> Result<Self> {
+        match args.get("window_title") {
+            None | Some(serde_json::Value::Null) => Ok(CaptureTarget::Screen),
+            Some(serde_json::Value::String(title)) if !title.trim().is_empty() => {
+                Ok(CaptureTarget::Window(title.trim().to_string()))
+            }
+            Some(other) => Err(anyhow!(
+                "window_title must be a non-empty string, got {}",
+                other
+            )),
+        }
+    }
+}
+
+/// A desktop window and its position on screen
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct WindowInfo {
+    pub title: String,
+    pub x: i64,
+    pub y: i64,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Result of capturing a single window
+#[derive(Debug, Clone, Serialize)]
+pub struct WindowCapture {
+    pub path: PathBuf,
+    pub window: WindowInfo,
+}
+
 #[derive(Debug, Clone)]
 pub struct VisionTool {
     work_dir: PathBuf,
+    guard: Arc<FileSystemGuard>,
+    ocr: Arc<dyn OcrEngine>,
 }
 
 impl VisionTool {
     pub fn new(work_dir: PathBuf) -> Self {
-        Self { work_dir }
+        Self {
+            guard: Arc::new(FileSystemGuard::new(work_dir.clone())),
+            work_dir,
+            ocr: Arc::new(Tesseract),
+        }
+    }
+
+    /// Use a different OCR backend for `extract_text`
+    pub fn with_ocr_engine(mut self, engine: Arc<dyn OcrEngine>) -> Self {
+        self.ocr = engine;
+        self
     }
 
     /// Capture a screenshot and save it to the specified relative or absolute path
@@ -63,4 +121,331 @@ impl VisionTool {
             }
         }
     }
+
+    /// Capture only the window whose title matches `title`
+    ///
+    /// See `find_window` for how titles are matched.
+    pub async fn capture_window(&self, title: &str, output_file: &str) -> Result<WindowCapture> {
+        let work_dir = self.work_dir.clone();
+        let title = title.to_string();
+        let output_file = output_file.to_string();
+        tokio::task::spawn_blocking(move || capture_window(&work_dir, &title, &output_file)).await?
+    }
+
+    /// Recognize the text in an image inside the workspace
+    pub async fn extract_text(&self, options: ExtractTextOptions) -> Result<ExtractedText> {
+        let guard = self.guard.clone();
+        let engine = self.ocr.clone();
+        tokio::task::spawn_blocking(move || ocr::extract_text(&guard, engine.as_ref(), &options))
+            .await?
+    }
+}
+
+/// Find the one window matching `title`
+///
+/// Titles are compared case-insensitively. An exact title match wins;
+/// otherwise the title may match part of a window title. No match, or more
+/// than one, is an error that lists the candidates.
+pub fn find_window<'a>(windows: &'a [WindowInfo], title: &str) -> Result<&'a WindowInfo> {
+    let wanted = title.to_lowercase();
+    let exact: Vec<&WindowInfo> = windows
+        .iter()
+        .filter(|w| w.title.to_lowercase() == wanted)
+        .collect();
+    let matches = if exact.is_empty() {
+        windows
+            .iter()
+            .filter(|w| w.title.to_lowercase().contains(&wanted))
+            .collect()
+    } else {
+        exact
+    };
+
+    match matches.as_slice() {
+        [] => Err(anyhow!("No window matches '{}'", title)),
+        [window] => Ok(window),
+        many => Err(anyhow!(
+            "{} windows match '{}': {}. Use a more specific title",
+            many.len(),
+            title,
+            many.iter()
+                .map(|w| format!("\"{}\"", w.title))
+                .collect::<Vec<_>>()
+                .join(", ")
+        )),
+    }
+}
+
+/// Find the window matching `title` and capture just its area
+///
+/// Blocking; used directly by the `capture_window` host function.
+pub fn capture_window(work_dir: &Path, title: &str, output_file: &str) -> Result<WindowCapture> {
+    let mut save_path = PathBuf::from(output_file);
+    if !save_path.is_absolute() {
+        save_path = work_dir.join(save_path);
+    }
+
+    let windows = list_windows()?;
+    let window = find_window(&windows, title)?.clone();
+    info!(
+        "Capturing window '{}' ({}x{} at {},{}) to: {}",
+        window.title,
+        window.width,
+        window.height,
+        window.x,
+        window.y,
+        save_path.display()
+    );
+
+    capture_region(&window, &save_path)?;
+    Ok(WindowCapture {
+        path: save_path,
+        window,
+    })
+}
+
+/// List visible windows via `wmctrl -lG`
+#[cfg(target_os = "linux")]
+fn list_windows() -> Result<Vec<WindowInfo>> {
+    let output = run_utility("wmctrl", &["-lG"])?;
+    Ok(parse_wmctrl(&output))
+}
+
+/// List visible windows via System Events
+#[cfg(target_os = "macos")]
+fn list_windows() -> Result<Vec<WindowInfo>> {
+    const SCRIPT: &str = r#"
+set out to ""
+tell application "System Events"
+    repeat with p in (every process whose visible is true)
+        repeat with w in (every window of p)
+            set {x, y} to position of w
+            set {wd, ht} to size of w
+            set out to out & (name of w) & tab & x & tab & y & tab & wd & tab & ht & linefeed
+        end repeat
+    end repeat
+end tell
+return out"#;
+    let output = run_utility("osascript", &["-e", SCRIPT])?;
+    Ok(parse_tab_separated(&output))
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+fn list_windows() -> Result<Vec<WindowInfo>> {
+    Err(anyhow!(
+        "Window capture is not implemented for this platform yet"
+    ))
+}
+
+#[cfg(target_os = "linux")]
+fn capture_region(window: &WindowInfo, path: &Path) -> Result<()> {
+    let region = format!(
+        "{},{},{},{}",
+        window.x, window.y, window.width, window.height
+    );
+    run_utility("scrot", &["-a", &region, &path.to_string_lossy()]).map(|_| ())
+}
+
+#[cfg(target_os = "macos")]
+fn capture_region(window: &WindowInfo, path: &Path) -> Result<()> {
+    let region = format!(
+        "{},{},{},{}",
+        window.x, window.y, window.width, window.height
+    );
+    run_utility(
+        "screencapture",
+        &["-x", "-R", &region, &path.to_string_lossy()],
+    )
+    .map(|_| ())
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+fn capture_region(_window: &WindowInfo, _path: &Path) -> Result<()> {
+    Err(anyhow!(
+        "Window capture is not implemented for this platform yet"
+    ))
+}
+
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+fn run_utility(program: &str, args: &[&str]) -> Result<String> {
+    let output = std::process::Command::new(program)
+        .args(args)
+        .output()
+        .map_err(|e| {
+            warn!("Failed to execute {}: {}", program, e);
+            anyhow!("Failed to execute {}: {}", program, e)
+        })?;
+    if !output.status.success() {
+        let err = String::from_utf8_lossy(&output.stderr);
+        warn!("{} failed: {}", program, err.trim());
+        return Err(anyhow!("{} failed: {}", program, err.trim()));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// Parse `wmctrl -lG` output: `id desktop x y width height host title`
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_wmctrl(output: &str) -> Vec<WindowInfo> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut rest = line.trim_start();
+            let mut fields = Vec::with_capacity(7);
+            for _ in 0..7 {
+                let end = rest.find(char::is_whitespace)?;
+                fields.push(&rest[..end]);
+                rest = rest[end..].trim_start();
+            }
+            Some(WindowInfo {
+                title: rest.trim_end().to_string(),
+                x: fields[2].parse().ok()?,
+                y: fields[3].parse().ok()?,
+                width: fields[4].parse().ok()?,
+                height: fields[5].parse().ok()?,
+            })
+        })
+        .filter(|w| !w.title.is_empty())
+        .collect()
+}
+
+/// Parse `title<TAB>x<TAB>y<TAB>width<TAB>height` lines
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+fn parse_tab_separated(output: &str) -> Vec<WindowInfo> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.rsplitn(5, '\t');
+            let height = fields.next()?.trim().parse().ok()?;
+            let width = fields.next()?.trim().parse().ok()?;
+            let y = fields.next()?.trim().parse().ok()?;
+            let x = fields.next()?.trim().parse().ok()?;
+            let title = fields.next()?.to_string();
+            Some(WindowInfo {
+                title,
+                x,
+                y,
+                width,
+                height,
+            })
+        })
+        .filter(|w| !w.title.is_empty())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn window(title: &str) -> WindowInfo {
+        WindowInfo {
+            title: title.to_string(),
+            x: 10,
+            y: 20,
+            width: 800,
+            height: 600,
+        }
+    }
+
+    #[test]
+    fn test_capture_target_parsing() {
+        assert_eq!(
+            CaptureTarget::from_args(&json!({})).unwrap(),
+            CaptureTarget::Screen
+        );
+        assert_eq!(
+            CaptureTarget::from_args(&json!({"output_file": "a.png", "window_title": null}))
+                .unwrap(),
+            CaptureTarget::Screen
+        );
+        assert_eq!(
+            CaptureTarget::from_args(&json!({"window_title": " Firefox "})).unwrap(),
+            CaptureTarget::Window("Firefox".to_string())
+        );
+
+        for args in [
+            json!({"window_title": ""}),
+            json!({"window_title": "   "}),
+            json!({"window_title": 42}),
+            json!({"window_title": ["Firefox"]}),
+        ] {
+            assert!(
+                CaptureTarget::from_args(&args).is_err(),
+                "accepted {}",
+                args
+            );
+        }
+    }
+
+    #[test]
+    fn test_find_window_no_match() {
+        let windows = vec![window("Terminal"), window("Mozilla Firefox")];
+        let err = find_window(&windows, "Slack").unwrap_err();
+        assert_eq!(err.to_string(), "No window matches 'Slack'");
+
+        assert!(find_window(&[], "Terminal").is_err());
+    }
+
+    #[test]
+    fn test_find_window_matching() {
+        let windows = vec![
+            window("Terminal"),
+            window("Terminal — build"),
+            window("Mozilla Firefox"),
+        ];
+
+        // Partial, case-insensitive match
+        assert_eq!(
+            find_window(&windows, "firefox").unwrap().title,
+            "Mozilla Firefox"
+        );
+
+        // An exact match wins over partial ones
+        assert_eq!(find_window(&windows, "terminal").unwrap().title, "Terminal");
+
+        let err = find_window(&windows, "term").unwrap_err().to_string();
+        assert!(err.starts_with("2 windows match 'term'"), "{}", err);
+        assert!(err.contains("\"Terminal — build\""));
+    }
+
+    #[test]
+    fn test_parse_window_lists() {
+        let wmctrl = "0x03a00003  0 0    28   1920 1052 host Mozilla Firefox\n\
+                      0x04200007 -1 -10  -20  640  480  host   \n\
+                      0x04400001  1 100  200  800  600  host Terminal — build\n";
+        assert_eq!(
+            parse_wmctrl(wmctrl),
+            vec![
+                WindowInfo {
+                    title: "Mozilla Firefox".to_string(),
+                    x: 0,
+                    y: 28,
+                    width: 1920,
+                    height: 1052,
+                },
+                WindowInfo {
+                    title: "Terminal — build".to_string(),
+                    x: 100,
+                    y: 200,
+                    width: 800,
+                    height: 600,
+                },
+            ]
+        );
+
+        let tabbed = "Notes\t10\t20\t800\t600\nA\ttitle\t-5\t0\t300\t200\nbroken line\n";
+        assert_eq!(
+            parse_tab_separated(tabbed),
+            vec![
+                window("Notes"),
+                WindowInfo {
+                    title: "A\ttitle".to_string(),
+                    x: -5,
+                    y: 0,
+                    width: 300,
+                    height: 200,
+                },
+            ]
+        );
+    }
 }
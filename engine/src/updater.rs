@@ -0,0 +1,540 @@
+//! Self-update from GitHub releases
+//!
+//! `rove update --apply` installs a release only when every check passes:
+//!
+//! 1. The release version is newer than the running one (no downgrades)
+//! 2. The release ships a `manifest.json` whose Ed25519 signature verifies
+//!    against the team key via `CryptoModule`
+//! 3. The version signed into that manifest is the release's version, so a
+//!    retagged old release is still refused
+//! 4. The downloaded binary's SHA-256 matches the hash in that manifest
+//!
+//! The new binary is staged next to the current one and swapped in with a
+//! rename, so a failed or interrupted update leaves the installed binary as it
+//! was.
+
+use crate::crypto::CryptoModule;
+use anyhow::{anyhow, Context, Result};
+use futures::StreamExt;
+use semver::Version;
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+
+/// GitHub API endpoint for the latest release
+pub const RELEASES_URL: &str = "https://api.github.com/repos/OvrisHQ/rove/releases/latest";
+
+/// Most memory reserved up front for a download, whatever size the server
+/// announces
+const MAX_PREALLOCATION: u64 = 64 * 1024 * 1024;
+
+/// A GitHub release
+#[derive(Debug, Clone, Deserialize)]
+pub struct Release {
+    pub tag_name: String,
+    pub html_url: String,
+    pub assets: Vec<ReleaseAsset>,
+}
+
+/// A downloadable file attached to a release
+#[derive(Debug, Clone, Deserialize)]
+pub struct ReleaseAsset {
+    pub name: String,
+    pub browser_download_url: String,
+    pub size: u64,
+}
+
+impl Release {
+    /// Semver version from the tag (a leading `v` is ignored)
+    pub fn version(&self) -> Result<Version> {
+        let tag = self.tag_name.strip_prefix('v').unwrap_or(&self.tag_name);
+        Version::parse(tag).context("Failed to parse latest release version")
+    }
+
+    fn asset(&self, name: &str) -> Option<&ReleaseAsset> {
+        self.assets.iter().find(|a| a.name == name)
+    }
+
+    fn asset_names(&self) -> String {
+        self.assets
+            .iter()
+            .map(|a| a.name.as_str())
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+/// HTTP client used for release downloads
+pub fn http_client() -> Result<reqwest::Client> {
+    reqwest::Client::builder()
+        .user_agent(format!("rove/{}", env!("CARGO_PKG_VERSION")))
+        .build()
+        .context("Failed to create HTTP client")
+}
+
+/// Fetch the latest release description from `url`
+pub async fn fetch_latest(client: &reqwest::Client, url: &str) -> Result<Release> {
+    client
+        .get(url)
+        .header("Accept", "application/vnd.github+json")
+        .send()
+        .await?
+        .error_for_status()
+        .context("Failed to fetch latest release from GitHub")?
+        .json()
+        .await
+        .context("Failed to parse latest release")
+}
+
+/// Target triple of the running binary
+pub fn current_target() -> &'static str {
+    #[cfg(all(target_arch = "x86_64", target_os = "linux"))]
+    {
+        "x86_64-unknown-linux-gnu"
+    }
+
+    #[cfg(all(target_arch = "aarch64", target_os = "linux"))]
+    {
+        "aarch64-unknown-linux-gnu"
+    }
+
+    #[cfg(all(target_arch = "x86_64", target_os = "macos"))]
+    {
+        "x86_64-apple-darwin"
+    }
+
+    #[cfg(all(target_arch = "aarch64", target_os = "macos"))]
+    {
+        "aarch64-apple-darwin"
+    }
+
+    #[cfg(all(target_arch = "x86_64", target_os = "windows"))]
+    {
+        "x86_64-pc-windows-msvc"
+    }
+
+    #[cfg(all(target_arch = "aarch64", target_os = "windows"))]
+    {
+        "aarch64-pc-windows-msvc"
+    }
+}
+
+/// Installs verified releases over the current binary
+pub struct Updater {
+    client: reqwest::Client,
+    crypto: CryptoModule,
+    current: Version,
+}
+
+impl Updater {
+    pub fn new(client: reqwest::Client, crypto: CryptoModule, current: Version) -> Self {
+        Self {
+            client,
+            crypto,
+            current,
+        }
+    }
+
+    /// Verify `release` and install its binary for this target at `binary`
+    ///
+    /// Returns the installed version.
+    ///
+    /// # Errors
+    ///
+    /// Fails without touching `binary` if the release is not newer than the
+    /// current version, has no binary for this target, has no signed manifest,
+    /// the manifest signature is invalid, the signed version is not the
+    /// release's, or the binary's hash does not match.
+    pub async fn apply(&self, release: &Release, binary: &Path) -> Result<Version> {
+        let latest = release.version()?;
+        if latest <= self.current {
+            return Err(anyhow!(
+                "Refusing to install v{} over v{}: not a newer release",
+                latest,
+                self.current
+            ));
+        }
+
+        let asset_name = format!("rove-{}", current_target());
+        let asset = release
+            .asset(&asset_name)
+            .or_else(|| release.asset(&format!("{}.exe", asset_name)))
+            .ok_or_else(|| {
+                anyhow!(
+                    "No release asset for target '{}'. Available: {}",
+                    current_target(),
+                    release.asset_names()
+                )
+            })?;
+
+        // The manifest is checked before the binary is even downloaded
+        let manifest_asset = release.asset("manifest.json").ok_or_else(|| {
+            anyhow!(
+                "Release v{} has no signed manifest.json; refusing to install an unverified build",
+                latest
+            )
+        })?;
+        let manifest_bytes = self.download(manifest_asset, false).await?;
+        let (signed_version, expected_hash) =
+            self.verified_manifest(&manifest_bytes, &asset.name)?;
+        eprintln!("  Manifest signature: verified");
+
+        // The tag is not signed; only the manifest's version can be trusted
+        if signed_version != latest {
+            return Err(anyhow!(
+                "Release tag v{} does not match its signed manifest version v{}",
+                latest,
+                signed_version
+            ));
+        }
+
+        eprintln!(
+            "Downloading {} ({:.1} MB)...",
+            asset.name,
+            asset.size as f64 / 1_048_576.0
+        );
+        let bytes = self.download(asset, true).await?;
+
+        let computed_hash = CryptoModule::compute_hash(&bytes);
+        if !computed_hash.eq_ignore_ascii_case(&expected_hash) {
+            return Err(anyhow!(
+                "Binary hash mismatch! Expected: {}, Got: {}. Download may be corrupted or tampered.",
+                expected_hash,
+                computed_hash
+            ));
+        }
+        eprintln!(
+            "  Binary hash: verified (SHA-256: {}...)",
+            &computed_hash[..16]
+        );
+
+        install(&bytes, binary)?;
+        Ok(latest)
+    }
+
+    /// Verify the manifest signature and return its version and the expected
+    /// hash of `asset_name`
+    fn verified_manifest(
+        &self,
+        manifest_bytes: &[u8],
+        asset_name: &str,
+    ) -> Result<(Version, String)> {
+        let manifest: serde_json::Value =
+            serde_json::from_slice(manifest_bytes).context("Invalid release manifest JSON")?;
+
+        // Dev placeholders are never valid for a published release
+        let signature = manifest
+            .get("signature")
+            .and_then(|s| s.as_str())
+            .unwrap_or_default();
+        if signature.contains("PLACEHOLDER") || signature.contains("LOCAL_DEV") {
+            return Err(anyhow!("Release manifest is not signed"));
+        }
+        self.crypto
+            .verify_manifest_file(manifest_bytes)
+            .context("Release manifest signature verification failed")?;
+
+        let version = manifest
+            .get("version")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow!("Release manifest has no version"))?;
+        let version = Version::parse(version.strip_prefix('v').unwrap_or(version))
+            .context("Failed to parse release manifest version")?;
+
+        let hash = manifest
+            .get("binaries")
+            .and_then(|b| b.get(asset_name))
+            .and_then(|b| b.get("hash"))
+            .and_then(|h| h.as_str())
+            .or_else(|| {
+                // Older manifests list the binary under core_tools
+                manifest
+                    .get("core_tools")
+                    .and_then(|t| t.as_array())
+                    .and_then(|arr| {
+                        arr.iter().find_map(|entry| {
+                            let name = entry.get("id").and_then(|i| i.as_str())?;
+                            if name == asset_name {
+                                entry.get("hash").and_then(|h| h.as_str())
+                            } else {
+                                None
+                            }
+                        })
+                    })
+            })
+            .ok_or_else(|| anyhow!("Release manifest has no hash for '{}'", asset_name))?;
+
+        let hash = hash.strip_prefix("sha256:").unwrap_or(hash).to_string();
+        Ok((version, hash))
+    }
+
+    /// Download an asset into memory, optionally printing progress
+    async fn download(&self, asset: &ReleaseAsset, progress: bool) -> Result<Vec<u8>> {
+        let response = self
+            .client
+            .get(&asset.browser_download_url)
+            .send()
+            .await?
+            .error_for_status()
+            .with_context(|| format!("Failed to download {}", asset.name))?;
+
+        let total = response.content_length().unwrap_or(asset.size);
+        let mut bytes = Vec::with_capacity(total.min(MAX_PREALLOCATION) as usize);
+        let mut stream = response.bytes_stream();
+        let mut last_pct: u32 = 0;
+
+        while let Some(chunk) = stream.next().await {
+            let chunk = chunk.context("Error reading download stream")?;
+            bytes.extend_from_slice(&chunk);
+
+            if progress && total > 0 {
+                let pct = (bytes.len() as f64 / total as f64 * 100.0) as u32;
+                if pct / 10 > last_pct / 10 {
+                    eprint!("\r  Progress: {}%", pct);
+                    last_pct = pct;
+                }
+            }
+        }
+        if progress {
+            eprintln!("\r  Progress: 100%");
+        }
+
+        Ok(bytes)
+    }
+}
+
+/// Path the new binary is staged at before it replaces `binary`
+///
+/// Staging in the same directory keeps the final rename on one filesystem,
+/// which makes it atomic.
+pub fn staged_path(binary: &Path) -> PathBuf {
+    let mut name = binary.as_os_str().to_os_string();
+    name.push(".update");
+    PathBuf::from(name)
+}
+
+/// Stage `bytes` next to `binary` and swap it in
+fn install(bytes: &[u8], binary: &Path) -> Result<()> {
+    let staged = staged_path(binary);
+    let result = stage_and_swap(bytes, &staged, binary);
+    if result.is_err() {
+        let _ = std::fs::remove_file(&staged);
+    }
+    result
+}
+
+fn stage_and_swap(bytes: &[u8], staged: &Path, binary: &Path) -> Result<()> {
+    {
+        use std::io::Write;
+        let mut file = std::fs::File::create(staged).context("Failed to stage update")?;
+        file.write_all(bytes).context("Failed to stage update")?;
+        file.sync_all().context("Failed to stage update")?;
+    }
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(staged, std::fs::Permissions::from_mode(0o755))?;
+        std::fs::rename(staged, binary).context("Failed to replace the current binary")?;
+    }
+
+    // A running executable cannot be renamed over on Windows
+    #[cfg(not(unix))]
+    {
+        let _ = binary;
+        self_replace::self_replace(staged).context("Failed to replace the current binary")?;
+        let _ = std::fs::remove_file(staged);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ed25519_dalek::{Signer, SigningKey};
+    use tempfile::TempDir;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    const NEW_BINARY: &[u8] = b"\x7fELF new rove build";
+    const OLD_BINARY: &[u8] = b"\x7fELF old rove build";
+
+    fn signing_key() -> SigningKey {
+        SigningKey::from_bytes(&[42u8; 32])
+    }
+
+    fn updater(key: &SigningKey) -> Updater {
+        Updater::new(
+            http_client().unwrap(),
+            CryptoModule::with_key(key.verifying_key()),
+            Version::new(0, 1, 0),
+        )
+    }
+
+    fn asset_name() -> String {
+        format!("rove-{}", current_target())
+    }
+
+    /// Manifest listing the hash of `NEW_BINARY`, signed with `key`
+    fn signed_manifest(key: &SigningKey) -> Vec<u8> {
+        signed_manifest_for(key, "9.0.0")
+    }
+
+    fn signed_manifest_for(key: &SigningKey, version: &str) -> Vec<u8> {
+        let unsigned = serde_json::json!({
+            "version": version,
+            "binaries": {
+                asset_name(): { "hash": CryptoModule::compute_hash(NEW_BINARY) }
+            }
+        });
+        let canonical =
+            CryptoModule::canonicalize_manifest(unsigned.to_string().as_bytes()).unwrap();
+        let signature = hex::encode(key.sign(&canonical).to_bytes());
+
+        let mut signed = unsigned;
+        signed["signature"] = serde_json::json!(format!("ed25519:{}", signature));
+        signed.to_string().into_bytes()
+    }
+
+    /// Serve a release whose binary asset returns `artifact`
+    async fn mock_release(tag: &str, manifest: Vec<u8>, artifact: &[u8]) -> (MockServer, Release) {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/manifest.json"))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(manifest))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path(format!("/{}", asset_name())))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(artifact.to_vec()))
+            .mount(&server)
+            .await;
+
+        let release = Release {
+            tag_name: tag.to_string(),
+            html_url: format!("{}/release", server.uri()),
+            assets: vec![
+                ReleaseAsset {
+                    name: asset_name(),
+                    browser_download_url: format!("{}/{}", server.uri(), asset_name()),
+                    size: artifact.len() as u64,
+                },
+                ReleaseAsset {
+                    name: "manifest.json".to_string(),
+                    browser_download_url: format!("{}/manifest.json", server.uri()),
+                    size: 0,
+                },
+            ],
+        };
+        (server, release)
+    }
+
+    fn installed_binary() -> (TempDir, PathBuf) {
+        let dir = TempDir::new().unwrap();
+        let binary = dir.path().join("rove");
+        std::fs::write(&binary, OLD_BINARY).unwrap();
+        (dir, binary)
+    }
+
+    #[tokio::test]
+    async fn test_apply_verified_release() {
+        let key = signing_key();
+        let (_server, release) = mock_release("v9.0.0", signed_manifest(&key), NEW_BINARY).await;
+        let (_dir, binary) = installed_binary();
+
+        let installed = updater(&key).apply(&release, &binary).await.unwrap();
+
+        assert_eq!(installed, Version::new(9, 0, 0));
+        assert_eq!(std::fs::read(&binary).unwrap(), NEW_BINARY);
+        assert!(!staged_path(&binary).exists());
+    }
+
+    #[tokio::test]
+    async fn test_tampered_artifact_is_not_installed() {
+        let key = signing_key();
+        let (_server, release) =
+            mock_release("v9.0.0", signed_manifest(&key), b"\x7fELF tampered").await;
+        let (_dir, binary) = installed_binary();
+
+        let err = updater(&key).apply(&release, &binary).await.unwrap_err();
+
+        assert!(err.to_string().contains("hash mismatch"));
+        assert_eq!(std::fs::read(&binary).unwrap(), OLD_BINARY);
+        assert!(!staged_path(&binary).exists());
+    }
+
+    #[tokio::test]
+    async fn test_manifest_signed_by_other_key_is_rejected() {
+        let key = signing_key();
+        let forger = SigningKey::from_bytes(&[7u8; 32]);
+        let (_server, release) = mock_release("v9.0.0", signed_manifest(&forger), NEW_BINARY).await;
+        let (_dir, binary) = installed_binary();
+
+        assert!(updater(&key).apply(&release, &binary).await.is_err());
+        assert_eq!(std::fs::read(&binary).unwrap(), OLD_BINARY);
+    }
+
+    #[tokio::test]
+    async fn test_downgrade_is_refused() {
+        let key = signing_key();
+        let (server, release) = mock_release("v0.0.9", signed_manifest(&key), NEW_BINARY).await;
+        let (_dir, binary) = installed_binary();
+
+        let err = updater(&key).apply(&release, &binary).await.unwrap_err();
+
+        assert!(err.to_string().contains("not a newer release"));
+        assert_eq!(std::fs::read(&binary).unwrap(), OLD_BINARY);
+        assert!(server.received_requests().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_retagged_old_release_is_refused() {
+        let key = signing_key();
+        let (_server, release) =
+            mock_release("v9.0.0", signed_manifest_for(&key, "0.0.9"), NEW_BINARY).await;
+        let (_dir, binary) = installed_binary();
+
+        let err = updater(&key).apply(&release, &binary).await.unwrap_err();
+
+        assert!(err.to_string().contains("signed manifest version"));
+        assert_eq!(std::fs::read(&binary).unwrap(), OLD_BINARY);
+    }
+
+    #[test]
+    fn test_hash_for_another_asset_is_not_used() {
+        let key = signing_key();
+        let unsigned = serde_json::json!({
+            "version": "9.0.0",
+            "core_tools": [
+                { "id": "rove", "hash": CryptoModule::compute_hash(NEW_BINARY) }
+            ]
+        });
+        let canonical =
+            CryptoModule::canonicalize_manifest(unsigned.to_string().as_bytes()).unwrap();
+        let mut signed = unsigned;
+        signed["signature"] = serde_json::json!(format!(
+            "ed25519:{}",
+            hex::encode(key.sign(&canonical).to_bytes())
+        ));
+
+        let err = updater(&key)
+            .verified_manifest(signed.to_string().as_bytes(), &asset_name())
+            .unwrap_err();
+
+        assert!(err.to_string().contains("no hash for"));
+    }
+
+    #[tokio::test]
+    async fn test_release_without_manifest_is_refused() {
+        let key = signing_key();
+        let (_server, mut release) =
+            mock_release("v9.0.0", signed_manifest(&key), NEW_BINARY).await;
+        release.assets.retain(|a| a.name != "manifest.json");
+        let (_dir, binary) = installed_binary();
+
+        let err = updater(&key).apply(&release, &binary).await.unwrap_err();
+
+        assert!(err.to_string().contains("unverified build"));
+        assert_eq!(std::fs::read(&binary).unwrap(), OLD_BINARY);
+    }
+}
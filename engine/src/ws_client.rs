@@ -8,15 +8,25 @@
 //! - Auto-reconnect with configurable delay
 //! - JSON message protocol (submit_task, ping/pong, task results)
 //! - Optional auth_token sent on connect
+//! - Operation confirmations answered by the UI (`WebSocketConfirmer`)
 
+use async_trait::async_trait;
 use futures::stream::StreamExt;
 use futures::SinkExt;
 use serde::{Deserialize, Serialize};
-use tokio::sync::mpsc;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::{mpsc, oneshot};
 use tokio_tungstenite::tungstenite::Message as WsMessage;
 use tracing::{error, info, warn};
 
 use crate::config::WsClientConfig;
+use crate::confirmation::{Confirmer, Decision};
+use crate::risk_assessor::RiskTier;
+
+/// How long a `WebSocketConfirmer` waits for the UI to answer
+pub const DEFAULT_CONFIRMATION_TIMEOUT: Duration = Duration::from_secs(120);
 
 /// Inbound message received from the remote server.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -26,6 +36,8 @@ pub enum InboundMessage {
     SubmitTask { task_id: String, input: String },
     /// Server ping (Rove replies with pong).
     Ping,
+    /// The UI's answer to a `confirmation_request`.
+    ConfirmationResponse { request_id: String, approved: bool },
 }
 
 /// Outbound message sent by Rove to the remote server.
@@ -45,6 +57,15 @@ pub enum OutboundMessage {
     TaskFailed { task_id: String, error: String },
     /// Reply to a server ping.
     Pong,
+    /// Ask the UI whether an operation may run.
+    ConfirmationRequest {
+        request_id: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        task_id: Option<String>,
+        operation: String,
+        tier: u8,
+        reason: String,
+    },
 }
 
 /// A task received from the remote UI.
@@ -60,17 +81,122 @@ pub type TaskReceiver = mpsc::Receiver<RemoteTask>;
 /// Channel sender for outbound results (used by the agent after completing a task).
 pub type ResultSender = mpsc::Sender<OutboundMessage>;
 
+/// Confirmation requests awaiting the UI's answer, by request id.
+#[derive(Debug, Clone, Default)]
+pub struct PendingConfirmations {
+    waiting: Arc<Mutex<HashMap<String, oneshot::Sender<bool>>>>,
+}
+
+impl PendingConfirmations {
+    fn register(&self, request_id: &str) -> oneshot::Receiver<bool> {
+        let (tx, rx) = oneshot::channel();
+        self.lock().insert(request_id.to_string(), tx);
+        rx
+    }
+
+    fn cancel(&self, request_id: &str) {
+        self.lock().remove(request_id);
+    }
+
+    /// Deliver the UI's answer to the request waiting for it.
+    ///
+    /// Returns `false` if no request with that id is waiting.
+    pub fn resolve(&self, request_id: &str, approved: bool) -> bool {
+        match self.lock().remove(request_id) {
+            Some(tx) => tx.send(approved).is_ok(),
+            None => false,
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, oneshot::Sender<bool>>> {
+        self.waiting.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Confirms operations by asking the connected UI.
+///
+/// Each confirmation is sent as a `confirmation_request` and decided by the
+/// matching `confirmation_response`. If none arrives within the timeout, a
+/// Tier 1 operation proceeds, like the terminal countdown, and a Tier 2
+/// operation is denied. So is anything that can't be sent.
+#[derive(Debug, Clone)]
+pub struct WebSocketConfirmer {
+    outbound: ResultSender,
+    pending: PendingConfirmations,
+    task_id: Option<String>,
+    timeout: Duration,
+}
+
+impl WebSocketConfirmer {
+    pub fn new(outbound: ResultSender, pending: PendingConfirmations) -> Self {
+        Self {
+            outbound,
+            pending,
+            task_id: None,
+            timeout: DEFAULT_CONFIRMATION_TIMEOUT,
+        }
+    }
+
+    /// Tag requests with the remote task they belong to.
+    pub fn for_task(mut self, task_id: impl Into<String>) -> Self {
+        self.task_id = Some(task_id.into());
+        self
+    }
+
+    /// Wait at most `timeout` for each answer.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+}
+
+#[async_trait]
+impl Confirmer for WebSocketConfirmer {
+    async fn confirm(&self, operation: &str, tier: RiskTier, reason: &str) -> Decision {
+        let request_id = uuid::Uuid::new_v4().to_string();
+        let answer = self.pending.register(&request_id);
+        let request = OutboundMessage::ConfirmationRequest {
+            request_id: request_id.clone(),
+            task_id: self.task_id.clone(),
+            operation: operation.to_string(),
+            tier: tier as u8,
+            reason: reason.to_string(),
+        };
+        if let Err(e) = self.outbound.send(request).await {
+            warn!("Failed to send confirmation request: {}", e);
+            self.pending.cancel(&request_id);
+            return Decision::Abort;
+        }
+
+        match tokio::time::timeout(self.timeout, answer).await {
+            Ok(Ok(true)) => Decision::Proceed,
+            Ok(Ok(false)) | Ok(Err(_)) => Decision::Abort,
+            Err(_) => {
+                self.pending.cancel(&request_id);
+                warn!("No answer from the UI to confirm {}", operation);
+                if tier == RiskTier::Tier2 {
+                    Decision::Abort
+                } else {
+                    Decision::Proceed
+                }
+            }
+        }
+    }
+}
+
 /// Start the WebSocket client.
 ///
 /// Spawns an auto-reconnect loop in the background.
-/// Returns a receiver for incoming tasks and a sender for outbound results.
-pub fn start(config: WsClientConfig) -> (TaskReceiver, ResultSender) {
+/// Returns a receiver for incoming tasks, a sender for outbound results and
+/// the confirmations awaiting the UI, for building `WebSocketConfirmer`s.
+pub fn start(config: WsClientConfig) -> (TaskReceiver, ResultSender, PendingConfirmations) {
     let (task_tx, task_rx) = mpsc::channel::<RemoteTask>(64);
     let (result_tx, result_rx) = mpsc::channel::<OutboundMessage>(64);
+    let pending = PendingConfirmations::default();
 
-    tokio::spawn(reconnect_loop(config, task_tx, result_rx));
+    tokio::spawn(reconnect_loop(config, task_tx, result_rx, pending.clone()));
 
-    (task_rx, result_tx)
+    (task_rx, result_tx, pending)
 }
 
 /// Auto-reconnect loop. Keeps trying to maintain a connection.
@@ -78,6 +204,7 @@ async fn reconnect_loop(
     config: WsClientConfig,
     task_tx: mpsc::Sender<RemoteTask>,
     mut result_rx: mpsc::Receiver<OutboundMessage>,
+    pending: PendingConfirmations,
 ) {
     loop {
         info!("WS client connecting to {}", config.url);
@@ -105,7 +232,7 @@ async fn reconnect_loop(
                         msg = read.next() => {
                             match msg {
                                 Some(Ok(WsMessage::Text(text))) => {
-                                    handle_inbound(&text, &task_tx, &mut write).await;
+                                    handle_inbound(&text, &task_tx, &pending, &mut write).await;
                                 }
                                 Some(Ok(WsMessage::Ping(data))) => {
                                     let _ = write.send(WsMessage::Pong(data)).await;
@@ -157,8 +284,12 @@ async fn reconnect_loop(
 }
 
 /// Handle a single inbound text message from the server.
-async fn handle_inbound<S>(text: &str, task_tx: &mpsc::Sender<RemoteTask>, write: &mut S)
-where
+async fn handle_inbound<S>(
+    text: &str,
+    task_tx: &mpsc::Sender<RemoteTask>,
+    pending: &PendingConfirmations,
+    write: &mut S,
+) where
     S: SinkExt<WsMessage> + Unpin,
     S::Error: std::fmt::Display,
 {
@@ -197,5 +328,98 @@ where
                 }
             }
         }
+        InboundMessage::ConfirmationResponse {
+            request_id,
+            approved,
+        } => {
+            if !pending.resolve(&request_id, approved) {
+                warn!("No confirmation request {} is waiting", request_id);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// UI that answers each confirmation request through `handle_inbound`,
+    /// approving only `write_file`
+    fn spawn_mock_ui(
+        mut requests: mpsc::Receiver<OutboundMessage>,
+        pending: PendingConfirmations,
+    ) -> tokio::task::JoinHandle<Vec<serde_json::Value>> {
+        tokio::spawn(async move {
+            let (task_tx, _task_rx) = mpsc::channel(1);
+            let (mut write, _replies) = futures::channel::mpsc::unbounded::<WsMessage>();
+            let mut seen = Vec::new();
+            while let Some(request) = requests.recv().await {
+                let request = serde_json::to_value(&request).unwrap();
+                let answer = serde_json::json!({
+                    "type": "confirmation_response",
+                    "request_id": request["request_id"],
+                    "approved": request["operation"]
+                        .as_str()
+                        .unwrap()
+                        .starts_with("write_file"),
+                });
+                handle_inbound(&answer.to_string(), &task_tx, &pending, &mut write).await;
+                seen.push(request);
+            }
+            seen
+        })
+    }
+
+    #[tokio::test]
+    async fn test_ui_approves_and_denies() {
+        let (outbound, requests) = mpsc::channel(8);
+        let pending = PendingConfirmations::default();
+        let ui = spawn_mock_ui(requests, pending.clone());
+        let confirmer = WebSocketConfirmer::new(outbound, pending).for_task("task-1");
+
+        let approved = confirmer
+            .confirm(r#"write_file {"path":"a.txt"}"#, RiskTier::Tier1, "write")
+            .await;
+        let denied = confirmer
+            .confirm(
+                r#"run_command {"command":"rm -rf build"}"#,
+                RiskTier::Tier2,
+                "Deletes build",
+            )
+            .await;
+
+        assert_eq!(approved, Decision::Proceed);
+        assert_eq!(denied, Decision::Abort);
+        drop(confirmer);
+        let seen = ui.await.unwrap();
+        assert_eq!(seen.len(), 2);
+        assert_eq!(seen[1]["type"], "confirmation_request");
+        assert_eq!(seen[1]["task_id"], "task-1");
+        assert_eq!(seen[1]["tier"], 2);
+        assert_eq!(seen[1]["reason"], "Deletes build");
+    }
+
+    #[tokio::test]
+    async fn test_unanswered_confirmation_times_out() {
+        // The UI receives requests but never answers
+        let (outbound, _requests) = mpsc::channel(8);
+        let pending = PendingConfirmations::default();
+        let confirmer = WebSocketConfirmer::new(outbound, pending.clone())
+            .with_timeout(Duration::from_millis(50));
+
+        assert_eq!(
+            confirmer
+                .confirm("write_file", RiskTier::Tier1, "write")
+                .await,
+            Decision::Proceed
+        );
+        assert_eq!(
+            confirmer
+                .confirm("run_command", RiskTier::Tier2, "run")
+                .await,
+            Decision::Abort
+        );
+        assert!(pending.lock().is_empty());
+        assert!(!pending.resolve("unknown", true));
     }
 }
@@ -4,32 +4,54 @@
 //! - Max iterations limit
 //! - Result size limits
 //! - LLM timeout enforcement
+//! - Failure records for failed tasks
+//! - Plan-first mode with plan approval
+//! - Call-depth limit
+//! - Per-task token and cost accounting
 
 use serde_json::json;
 use std::sync::Arc;
 use std::time::Duration;
 use tempfile::TempDir;
 use wiremock::{
-    matchers::{method, path},
+    matchers::{body_string_contains, method, path},
     Mock, MockServer, ResponseTemplate,
 };
 
-use rove_engine::agent::core::{AgentCore, Task};
+use rove_engine::agent::core::{AgentCore, PlanApproval, Task};
+use rove_engine::conductor::ConductorPlan;
 use rove_engine::config::LLMConfig;
 use rove_engine::db::tasks::TaskRepository;
-use rove_engine::db::Database;
+use rove_engine::db::{Database, FailureReason, TaskStatus};
 use rove_engine::llm::{ollama::OllamaProvider, router::LLMRouter, LLMProvider};
 use rove_engine::rate_limiter::RateLimiter;
 use rove_engine::risk_assessor::{OperationSource, RiskAssessor};
 use sdk::errors::EngineError;
 
 async fn setup_agent(mock_uri: &str, temp_dir: &TempDir) -> AgentCore {
+    setup_agent_with_default(mock_uri, temp_dir, "ollama").await
+}
+
+async fn setup_agent_with_default(
+    mock_uri: &str,
+    temp_dir: &TempDir,
+    default_provider: &str,
+) -> AgentCore {
+    let provider = Box::new(OllamaProvider::new(mock_uri, "llama3.1:8b")) as Box<dyn LLMProvider>;
+    setup_agent_with_providers(vec![provider], temp_dir, default_provider).await
+}
+
+async fn setup_agent_with_providers(
+    providers: Vec<Box<dyn LLMProvider>>,
+    temp_dir: &TempDir,
+    default_provider: &str,
+) -> AgentCore {
     let db_path = temp_dir.path().join("test.db");
     let db = Database::new(&db_path).await.unwrap();
     let pool = db.pool().clone();
 
     let llm_config = Arc::new(LLMConfig {
-        default_provider: "ollama".to_string(),
+        default_provider: default_provider.to_string(),
         sensitivity_threshold: 0.5,
         complexity_threshold: 0.8,
         ollama: Default::default(),
@@ -37,10 +59,11 @@ async fn setup_agent(mock_uri: &str, temp_dir: &TempDir) -> AgentCore {
         anthropic: Default::default(),
         gemini: Default::default(),
         nvidia_nim: Default::default(),
+        provider_priority: Vec::new(),
+        circuit_breaker: Default::default(),
     });
 
-    let provider = Box::new(OllamaProvider::new(mock_uri, "llama3.1:8b")) as Box<dyn LLMProvider>;
-    let router = Arc::new(LLMRouter::new(vec![provider], llm_config));
+    let router = Arc::new(LLMRouter::new(providers, llm_config));
 
     let risk_assessor = RiskAssessor::new();
     let rate_limiter = Arc::new(RateLimiter::new(pool.clone()));
@@ -90,6 +113,49 @@ async fn test_property_agent_loop_iteration_limit() {
     );
 }
 
+// A task that runs out of iterations is recorded as failed with a
+// max_steps dead-letter record
+#[tokio::test]
+async fn test_max_steps_failure_is_recorded() {
+    let mock_server = MockServer::start().await;
+    let temp_dir = TempDir::new().unwrap();
+
+    let tool_call_response = json!({
+        "model": "llama3.1:8b",
+        "created_at": "2023-08-04T19:22:45.499127Z",
+        "message": {
+            "role": "assistant",
+            "content": "{\"function\": \"dummy_tool\", \"arguments\": {}}"
+        },
+        "done": true
+    });
+
+    Mock::given(method("POST"))
+        .and(path("/api/chat"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(tool_call_response))
+        .mount(&mock_server)
+        .await;
+
+    let mut agent = setup_agent(&mock_server.uri(), &temp_dir).await;
+    let task = Task::new("Loop forever", OperationSource::Local);
+    agent
+        .process_task(task)
+        .await
+        .expect_err("Agent should fail after exceeding max iterations");
+
+    let db_path = temp_dir.path().join("test.db");
+    let db = Database::new(&db_path).await.unwrap();
+    let tasks = db.tasks().get_recent_tasks(1).await.unwrap();
+    let task = tasks.first().expect("Failed task should be persisted");
+    assert_eq!(task.status, TaskStatus::Failed);
+
+    let failure = task.failure.as_ref().expect("Failure record missing");
+    assert_eq!(failure.reason, FailureReason::MaxSteps);
+    assert!(failure.message.contains("Max iterations exceeded"));
+    // User message plus a tool call and tool result per iteration
+    assert_eq!(failure.steps, 41);
+}
+
 // Property 2: LLM Call Timeout Enforcement
 // Validates: Requirements 2.3
 #[tokio::test]
@@ -228,6 +294,13 @@ fn test_property_task_serialization_round_trip() {
         duration_ms: Some(1500),
         created_at: 1600000000,
         completed_at: Some(1600000005),
+        total_tokens: 1200,
+        cost: 0.0024,
+        summary: None,
+        request_id: Some("req-1".to_string()),
+        remote: true,
+        owner_pid: None,
+        failure: None,
     };
 
     // Serialize to JSON
@@ -244,4 +317,254 @@ fn test_property_task_serialization_round_trip() {
     assert_eq!(original_task.duration_ms, deserialized.duration_ms);
     assert_eq!(original_task.created_at, deserialized.created_at);
     assert_eq!(original_task.completed_at, deserialized.completed_at);
+    assert_eq!(original_task.request_id, deserialized.request_id);
+}
+
+// A cloud provider selected as default without an API key is reported
+// up front with setup guidance instead of failing inside an HTTP call
+#[tokio::test]
+async fn test_missing_key_cloud_provider_returns_guidance() {
+    let mock_server = MockServer::start().await;
+    let temp_dir = TempDir::new().unwrap();
+
+    // No provider is registered; "openai" was skipped because it has no key
+    let mut agent = setup_agent_with_providers(Vec::new(), &temp_dir, "openai").await;
+    let task = Task::new("Any task", OperationSource::Local);
+    let result = agent.process_task(task).await;
+
+    let err = result.expect_err("Agent should refuse to run without the provider key");
+    let engine_error = err.root_cause().downcast_ref::<EngineError>();
+    match engine_error {
+        Some(EngineError::ProviderNotConfigured { provider, guidance }) => {
+            assert_eq!(provider, "openai");
+            assert!(guidance.contains("rove setup"));
+        }
+        _ => panic!("Expected ProviderNotConfigured, got: {:?}", err),
+    }
+
+    // No provider must have been contacted
+    let requests = mock_server.received_requests().await.unwrap();
+    assert!(requests.iter().all(|r| r.url.path() != "/api/chat"));
+}
+
+// With other providers registered, a default provider without an API key
+// is only a warning and the task runs on the fallbacks
+#[tokio::test]
+async fn test_missing_key_cloud_provider_falls_back() {
+    let mock_server = MockServer::start().await;
+    let temp_dir = TempDir::new().unwrap();
+
+    Mock::given(method("POST"))
+        .and(path("/api/chat"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "model": "llama3.1:8b",
+            "created_at": "2023-08-04T19:22:45.499127Z",
+            "message": {
+                "role": "assistant",
+                "content": "Done."
+            },
+            "done": true
+        })))
+        .mount(&mock_server)
+        .await;
+
+    // Only Ollama is registered; "openai" was skipped because it has no key
+    let mut agent = setup_agent_with_default(&mock_server.uri(), &temp_dir, "openai").await;
+    let task = Task::new("Any task", OperationSource::Local);
+    let result = agent.process_task(task).await.unwrap();
+
+    assert_eq!(result.answer, "Done.");
+    assert_eq!(result.provider_used, "ollama");
+}
+
+/// Mount a mock Ollama that answers planning turns with a two-step plan and
+/// every other turn with a final answer
+async fn mount_plan_then_answer(mock_server: &MockServer) {
+    let plan = json!([
+        {"description": "Read the README", "step_type": "Research", "dependencies": [], "expected_outcome": "Project understood"},
+        {"description": "Summarize it", "step_type": "Execute", "dependencies": ["step_1"], "expected_outcome": "Summary written"}
+    ]);
+
+    Mock::given(method("POST"))
+        .and(path("/api/chat"))
+        .and(body_string_contains("Conductor Planner"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "model": "llama3.1:8b",
+            "created_at": "2023-08-04T19:22:45.499127Z",
+            "message": {"role": "assistant", "content": plan.to_string()},
+            "done": true
+        })))
+        .mount(mock_server)
+        .await;
+
+    Mock::given(method("POST"))
+        .and(path("/api/chat"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "model": "llama3.1:8b",
+            "created_at": "2023-08-04T19:22:45.499127Z",
+            "message": {"role": "assistant", "content": "Summary: it is a project."},
+            "done": true
+        })))
+        .mount(mock_server)
+        .await;
+}
+
+/// Chat requests received by the mock, as request bodies
+async fn chat_bodies(mock_server: &MockServer) -> Vec<String> {
+    mock_server
+        .received_requests()
+        .await
+        .unwrap()
+        .iter()
+        .filter(|r| r.url.path() == "/api/chat")
+        .map(|r| String::from_utf8_lossy(&r.body).to_string())
+        .collect()
+}
+
+// Plan-first mode: the plan is produced and approved before execution runs
+#[tokio::test]
+async fn test_plan_first_executes_after_approval() {
+    let mock_server = MockServer::start().await;
+    let temp_dir = TempDir::new().unwrap();
+    mount_plan_then_answer(&mock_server).await;
+
+    let seen = Arc::new(std::sync::Mutex::new(Vec::new()));
+    let seen_by_approval = seen.clone();
+    let approval: PlanApproval = Arc::new(move |plan: &ConductorPlan| {
+        seen_by_approval.lock().unwrap().push(plan.steps.len());
+        true
+    });
+
+    let mut agent = setup_agent(&mock_server.uri(), &temp_dir)
+        .await
+        .with_plan_first(Some(approval));
+    let result = agent
+        .process_task(Task::new("Summarize the project", OperationSource::Local))
+        .await
+        .unwrap();
+
+    assert_eq!(result.answer, "Summary: it is a project.");
+    assert_eq!(*seen.lock().unwrap(), vec![2]);
+
+    // Planning turn first, then execution with the approved plan in context
+    let bodies = chat_bodies(&mock_server).await;
+    assert_eq!(bodies.len(), 2);
+    assert!(bodies[0].contains("Conductor Planner"));
+    assert!(bodies[1].contains("Read the README"));
+    assert!(!bodies[1].contains("Conductor Planner"));
+
+    // User message, plan and answer each have their own step order
+    let db = Database::new(&temp_dir.path().join("test.db"))
+        .await
+        .unwrap();
+    let steps = db.tasks().get_task_steps(&result.task_id).await.unwrap();
+    let orders: Vec<i64> = steps.iter().map(|s| s.step_order).collect();
+    assert_eq!(orders, vec![0, 1, 2]);
+}
+
+// A rejected plan stops the task before any execution turn
+#[tokio::test]
+async fn test_plan_first_rejection_stops_execution() {
+    let mock_server = MockServer::start().await;
+    let temp_dir = TempDir::new().unwrap();
+    mount_plan_then_answer(&mock_server).await;
+
+    let approval: PlanApproval = Arc::new(|_plan: &ConductorPlan| false);
+    let mut agent = setup_agent(&mock_server.uri(), &temp_dir)
+        .await
+        .with_plan_first(Some(approval));
+    let result = agent
+        .process_task(Task::new("Summarize the project", OperationSource::Local))
+        .await;
+
+    let err = result.expect_err("A rejected plan must not be executed");
+    assert!(err.to_string().contains("Plan rejected"));
+    assert_eq!(chat_bodies(&mock_server).await.len(), 1);
+}
+
+// Local providers contribute tokens but no cost
+#[tokio::test]
+async fn test_local_provider_usage_is_free() {
+    let mock_server = MockServer::start().await;
+    let temp_dir = TempDir::new().unwrap();
+
+    Mock::given(method("POST"))
+        .and(path("/api/chat"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "model": "llama3.1:8b",
+            "created_at": "2023-08-04T19:22:45.499127Z",
+            "message": {"role": "assistant", "content": "done"},
+            "done": true
+        })))
+        .mount(&mock_server)
+        .await;
+
+    let mut agent = setup_agent(&mock_server.uri(), &temp_dir).await;
+    let result = agent
+        .process_task(Task::new("Local task", OperationSource::Local))
+        .await
+        .unwrap();
+
+    assert!(result.total_tokens > 0);
+    assert_eq!(result.cost, 0.0);
+
+    let db = Database::new(&temp_dir.path().join("test.db"))
+        .await
+        .unwrap();
+    let stored = db.tasks().get_task(&result.task_id).await.unwrap().unwrap();
+    assert_eq!(stored.cost, 0.0);
+    assert_eq!(stored.total_tokens, result.total_tokens as i64);
+}
+
+// A tool call that would nest deeper than max_call_depth aborts the task.
+// The task itself is the first level, so a limit of 1 leaves no room for tools.
+#[tokio::test]
+async fn test_call_depth_limit_aborts_task() {
+    let mock_server = MockServer::start().await;
+    let temp_dir = TempDir::new().unwrap();
+
+    let tool_call_response = json!({
+        "model": "llama3.1:8b",
+        "created_at": "2023-08-04T19:22:45.499127Z",
+        "message": {
+            "role": "assistant",
+            "content": "{\"function\": \"dummy_tool\", \"arguments\": {}}"
+        },
+        "done": true
+    });
+
+    Mock::given(method("POST"))
+        .and(path("/api/chat"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(tool_call_response))
+        .mount(&mock_server)
+        .await;
+
+    let mut agent = setup_agent(&mock_server.uri(), &temp_dir)
+        .await
+        .with_max_call_depth(1);
+    let err = agent
+        .process_task(Task::new("Call a tool", OperationSource::Local))
+        .await
+        .expect_err("Tool call should exceed the call depth");
+
+    let engine_error = err
+        .chain()
+        .find_map(|cause| cause.downcast_ref::<EngineError>());
+    assert!(
+        matches!(
+            engine_error,
+            Some(EngineError::CallDepthExceeded { limit: 1 })
+        ),
+        "Unexpected error: {:?}",
+        err
+    );
+
+    let db = Database::new(&temp_dir.path().join("test.db"))
+        .await
+        .unwrap();
+    let task = db.tasks().get_recent_tasks(1).await.unwrap().remove(0);
+    assert_eq!(task.status, TaskStatus::Failed);
+    let failure = task.failure.expect("Failure record missing");
+    assert_eq!(failure.reason, FailureReason::MaxSteps);
+    assert!(failure.message.contains("Maximum call depth of 1 exceeded"));
 }
@@ -0,0 +1,66 @@
+//! Integration test for shutdown-reason reporting
+//!
+//! Kept in its own test binary because it sends SIGTERM to the test process,
+//! which would trip the signal handlers installed by other daemon tests.
+
+#![cfg(unix)]
+
+use nix::sys::signal::{kill, Signal};
+use nix::unistd::Pid;
+use rove_engine::config::Config;
+use rove_engine::daemon::{DaemonManager, ShutdownReason};
+use std::time::Duration;
+use tempfile::TempDir;
+
+fn create_test_config(temp_dir: &TempDir) -> Config {
+    let config_content = format!(
+        r#"
+[core]
+workspace = "{}"
+data_dir = "{}"
+
+[llm]
+default_provider = "ollama"
+
+[tools]
+
+[plugins]
+
+[security]
+"#,
+        temp_dir.path().display(),
+        temp_dir.path().display()
+    );
+
+    let config_path = temp_dir.path().join("config.toml");
+    std::fs::write(&config_path, config_content).unwrap();
+    Config::load_from_path(&config_path).unwrap()
+}
+
+#[tokio::test]
+async fn test_sigterm_records_signal_reason() {
+    let temp_dir = TempDir::new().unwrap();
+    let config = create_test_config(&temp_dir);
+
+    let mut manager = DaemonManager::new(&config).unwrap();
+    manager.start().await.unwrap();
+
+    kill(Pid::this(), Signal::SIGTERM).unwrap();
+    manager
+        .wait_for_shutdown(Duration::from_secs(5))
+        .await
+        .unwrap();
+
+    let expected = ShutdownReason::Signal {
+        signal: "SIGTERM".to_string(),
+    };
+    assert_eq!(manager.shutdown_reason(), Some(expected.clone()));
+
+    manager.graceful_shutdown(&config).await.unwrap();
+
+    let status = DaemonManager::status(&config).unwrap();
+    assert!(!status.is_running);
+    let last = status.last_shutdown.unwrap();
+    assert_eq!(last.reason, expected);
+    assert_eq!(last.reason.to_string(), "signal (SIGTERM)");
+}
@@ -67,6 +67,10 @@ async fn test_database_schema_complete() {
         tables.contains(&"rate_limits".to_string()),
         "rate_limits table missing"
     );
+    assert!(
+        tables.contains(&"task_failures".to_string()),
+        "task_failures table missing"
+    );
 
     // Verify all required indexes exist
     let indexes: Vec<String> = sqlx::query_scalar(
@@ -83,6 +87,7 @@ async fn test_database_schema_complete() {
     assert!(indexes.contains(&"idx_secrets_cache_expires_at".to_string()));
     assert!(indexes.contains(&"idx_rate_limits_timestamp".to_string()));
     assert!(indexes.contains(&"idx_rate_limits_source_tier".to_string()));
+    assert!(indexes.contains(&"idx_task_failures_reason".to_string()));
 
     db.close().await.unwrap();
 }
@@ -534,6 +539,102 @@ async fn test_fail_task() {
     db.close().await.unwrap();
 }
 
+#[tokio::test]
+async fn test_record_failure() {
+    use rove_engine::db::{FailureReason, TaskFailure};
+
+    let temp_dir = TempDir::new().unwrap();
+    let db_path = temp_dir.path().join("rove.db");
+
+    let db = Database::new(&db_path).await.unwrap();
+    let repo = db.tasks();
+
+    repo.create_task("task-1", "test input").await.unwrap();
+    repo.create_task("task-2", "other input").await.unwrap();
+    repo.complete_task("task-2", "ollama", 100).await.unwrap();
+
+    let failure = TaskFailure {
+        reason: FailureReason::ProviderError,
+        message: "LLM provider error: connection refused".to_string(),
+        steps: 3,
+        cost: 0.25,
+    };
+    repo.record_failure("task-1", &failure).await.unwrap();
+
+    // The failed task carries its dead-letter record
+    let task = repo.get_task("task-1").await.unwrap().unwrap();
+    assert_eq!(task.status, rove_engine::db::TaskStatus::Failed);
+    assert!(task.completed_at.is_some());
+    let recorded = task.failure.unwrap();
+    assert_eq!(recorded.reason, FailureReason::ProviderError);
+    assert_eq!(recorded.message, failure.message);
+    assert_eq!(recorded.steps, 3);
+    assert_eq!(recorded.cost, 0.25);
+
+    // Completed tasks have no failure record, and history includes both
+    let tasks = repo.get_recent_tasks(10).await.unwrap();
+    assert_eq!(tasks.len(), 2);
+    let completed = tasks.iter().find(|t| t.id == "task-2").unwrap();
+    assert!(completed.failure.is_none());
+    let failed = tasks.iter().find(|t| t.id == "task-1").unwrap();
+    assert_eq!(
+        failed.failure.as_ref().map(|f| f.reason),
+        Some(FailureReason::ProviderError)
+    );
+
+    db.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_record_usage_and_cost_summary() {
+    let temp_dir = TempDir::new().unwrap();
+    let db_path = temp_dir.path().join("rove.db");
+
+    let db = Database::new(&db_path).await.unwrap();
+    let repo = db.tasks();
+
+    repo.create_task("task-old", "last month").await.unwrap();
+    repo.create_task("task-cloud", "cloud").await.unwrap();
+    repo.create_task("task-local", "local").await.unwrap();
+    repo.record_usage("task-old", 5000, 0.5).await.unwrap();
+    repo.record_usage("task-cloud", 1500, 0.0045).await.unwrap();
+    repo.record_usage("task-local", 800, 0.0).await.unwrap();
+
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_secs() as i64;
+    sqlx::query("UPDATE tasks SET created_at = ? WHERE id = 'task-old'")
+        .bind(now - 30 * 24 * 60 * 60)
+        .execute(db.pool())
+        .await
+        .unwrap();
+
+    let task = repo.get_task("task-cloud").await.unwrap().unwrap();
+    assert_eq!(task.total_tokens, 1500);
+    assert_eq!(task.cost, 0.0045);
+
+    let all = repo.cost_summary(None).await.unwrap();
+    assert_eq!(all.tasks, 3);
+    assert_eq!(all.total_tokens, 7300);
+    assert!((all.cost - 0.5045).abs() < 1e-9);
+
+    let last_week = repo
+        .cost_summary(Some(now - 7 * 24 * 60 * 60))
+        .await
+        .unwrap();
+    assert_eq!(last_week.tasks, 2);
+    assert_eq!(last_week.total_tokens, 2300);
+    assert!((last_week.cost - 0.0045).abs() < 1e-9);
+
+    let future = repo.cost_summary(Some(now + 3600)).await.unwrap();
+    assert_eq!(future.tasks, 0);
+    assert_eq!(future.total_tokens, 0);
+    assert_eq!(future.cost, 0.0);
+
+    db.close().await.unwrap();
+}
+
 #[tokio::test]
 async fn test_get_recent_tasks() {
     let temp_dir = TempDir::new().unwrap();
@@ -1071,3 +1172,91 @@ async fn test_sql_injection_prevention_in_plugin_name() {
 
     db.close().await.unwrap();
 }
+
+#[tokio::test]
+async fn test_signature_cache_roundtrip() {
+    use rove_engine::crypto::{CryptoModule, VerifiedSignature};
+
+    let temp_dir = TempDir::new().unwrap();
+    let db_path = temp_dir.path().join("rove.db");
+
+    let db = Database::new(&db_path).await.unwrap();
+    let repo = db.signatures([7; 32]);
+    let crypto = CryptoModule::new().unwrap();
+
+    let ours = VerifiedSignature {
+        file_hash: "ab".repeat(32),
+        public_key: crypto.public_key_hex(),
+        signature: "cd".repeat(64),
+    };
+    let other_key = VerifiedSignature {
+        public_key: "ef".repeat(32),
+        ..ours.clone()
+    };
+
+    // Recording twice keeps a single row
+    repo.record(&ours).await.unwrap();
+    repo.record(&ours).await.unwrap();
+    repo.record(&other_key).await.unwrap();
+
+    let loaded = repo.load_for_key(&crypto.public_key_hex()).await.unwrap();
+    assert_eq!(loaded, vec![ours.clone()]);
+
+    // Only entries for the module's own key are loaded into it
+    assert_eq!(repo.load_into(&crypto).await.unwrap(), 1);
+    assert_eq!(crypto.verified_signatures(), vec![ours]);
+
+    repo.clear().await.unwrap();
+    assert!(repo
+        .load_for_key(&crypto.public_key_hex())
+        .await
+        .unwrap()
+        .is_empty());
+
+    // Saving writes the in-memory entries back
+    repo.save_from(&crypto).await.unwrap();
+    assert_eq!(
+        repo.load_for_key(&crypto.public_key_hex())
+            .await
+            .unwrap()
+            .len(),
+        1
+    );
+
+    db.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_signature_cache_ignores_unauthenticated_rows() {
+    use rove_engine::crypto::{CryptoModule, VerifiedSignature};
+
+    let temp_dir = TempDir::new().unwrap();
+    let db = Database::new(&temp_dir.path().join("rove.db"))
+        .await
+        .unwrap();
+    let crypto = CryptoModule::new().unwrap();
+    let entry = VerifiedSignature {
+        file_hash: "ab".repeat(32),
+        public_key: crypto.public_key_hex(),
+        signature: "cd".repeat(64),
+    };
+
+    // A row inserted straight into the database has no valid MAC
+    sqlx::query(
+        "INSERT INTO verified_signatures (file_hash, public_key, signature, verified_at) VALUES (?, ?, ?, 0)",
+    )
+    .bind(&entry.file_hash)
+    .bind(&entry.public_key)
+    .bind(&entry.signature)
+    .execute(db.pool())
+    .await
+    .unwrap();
+    assert_eq!(db.signatures([7; 32]).load_into(&crypto).await.unwrap(), 0);
+
+    // Nor does a row written with another key
+    db.signatures([8; 32]).record(&entry).await.unwrap();
+    assert_eq!(db.signatures([7; 32]).load_into(&crypto).await.unwrap(), 0);
+    assert_eq!(db.signatures([8; 32]).load_into(&crypto).await.unwrap(), 1);
+
+    db.close().await.unwrap();
+}
@@ -0,0 +1,50 @@
+;; Clock plugin for the WASM runtime tests
+;;
+;; `now` returns the `now_ms` host function result and `random` the
+;; `random_u64` result, each as an unsigned decimal string.
+(module
+  (import "extism:host/env" "alloc" (func $alloc (param i64) (result i64)))
+  (import "extism:host/env" "store_u8" (func $store_u8 (param i64 i32)))
+  (import "extism:host/env" "output_set" (func $output_set (param i64 i64)))
+  (import "extism:host/user" "now_ms" (func $now_ms (result i64)))
+  (import "extism:host/user" "random_u64" (func $random_u64 (result i64)))
+
+  (memory 1)
+
+  ;; Set the output to `value` in decimal. The digits are formatted
+  ;; backwards into bytes 0..20 of this module's memory, then copied into
+  ;; Extism memory.
+  (func $output_decimal (param $value i64)
+    (local $pos i32)
+    (local $len i64)
+    (local $out i64)
+    (local $i i64)
+    (local.set $pos (i32.const 20))
+    (loop $digits
+      (local.set $pos (i32.sub (local.get $pos) (i32.const 1)))
+      (i32.store8
+        (local.get $pos)
+        (i32.add
+          (i32.const 48)
+          (i32.wrap_i64 (i64.rem_u (local.get $value) (i64.const 10)))))
+      (local.set $value (i64.div_u (local.get $value) (i64.const 10)))
+      (br_if $digits (i64.ne (local.get $value) (i64.const 0))))
+    (local.set $len (i64.extend_i32_u (i32.sub (i32.const 20) (local.get $pos))))
+    (local.set $out (call $alloc (local.get $len)))
+    (block $done
+      (loop $copy
+        (br_if $done (i64.ge_u (local.get $i) (local.get $len)))
+        (call $store_u8
+          (i64.add (local.get $out) (local.get $i))
+          (i32.load8_u (i32.add (local.get $pos) (i32.wrap_i64 (local.get $i)))))
+        (local.set $i (i64.add (local.get $i) (i64.const 1)))
+        (br $copy)))
+    (call $output_set (local.get $out) (local.get $len)))
+
+  (func (export "now") (result i32)
+    (call $output_decimal (call $now_ms))
+    (i32.const 0))
+
+  (func (export "random") (result i32)
+    (call $output_decimal (call $random_u64))
+    (i32.const 0)))
@@ -0,0 +1,90 @@
+;; Relay plugin for the WASM runtime tests
+;;
+;; `relay` takes `<plugin_id> <function> <input>`, separated by the first
+;; two spaces, calls the function through the `call_plugin` host function
+;; and returns its result. `loop` calls its own `loop` the same way, so it
+;; recurses until the host stops it.
+(module
+  (import "extism:host/env" "input_length" (func $input_length (result i64)))
+  (import "extism:host/env" "input_load_u8" (func $input_load_u8 (param i64) (result i32)))
+  (import "extism:host/env" "alloc" (func $alloc (param i64) (result i64)))
+  (import "extism:host/env" "store_u8" (func $store_u8 (param i64 i32)))
+  (import "extism:host/env" "length" (func $length (param i64) (result i64)))
+  (import "extism:host/env" "output_set" (func $output_set (param i64 i64)))
+  (import "extism:host/user" "call_plugin"
+    (func $call_plugin (param i64 i64 i64) (result i64)))
+
+  (memory 1)
+  (data (i32.const 0) "relayloop{}")
+
+  ;; Input is copied here before it is split
+  (global $input i32 (i32.const 1024))
+
+  ;; Copy `len` bytes at `ptr` in this module's memory into a new Extism
+  ;; memory block and return its offset
+  (func $to_extism (param $ptr i32) (param $len i32) (result i64)
+    (local $out i64)
+    (local $i i32)
+    (local.set $out (call $alloc (i64.extend_i32_u (local.get $len))))
+    (block $done
+      (loop $copy
+        (br_if $done (i32.ge_u (local.get $i) (local.get $len)))
+        (call $store_u8
+          (i64.add (local.get $out) (i64.extend_i32_u (local.get $i)))
+          (i32.load8_u (i32.add (local.get $ptr) (local.get $i))))
+        (local.set $i (i32.add (local.get $i) (i32.const 1)))
+        (br $copy)))
+    (local.get $out))
+
+  ;; Index of the first space at or after `from`, or `len` if there is none
+  (func $find_space (param $from i32) (param $len i32) (result i32)
+    (block $done
+      (loop $scan
+        (br_if $done (i32.ge_u (local.get $from) (local.get $len)))
+        (br_if $done
+          (i32.eq
+            (i32.load8_u (i32.add (global.get $input) (local.get $from)))
+            (i32.const 32)))
+        (local.set $from (i32.add (local.get $from) (i32.const 1)))
+        (br $scan)))
+    (local.get $from))
+
+  ;; Set the output to the Extism memory block at `offset`
+  (func $output_block (param $offset i64)
+    (call $output_set (local.get $offset) (call $length (local.get $offset))))
+
+  (func (export "relay") (result i32)
+    (local $len i32)
+    (local $i i32)
+    (local $first i32)
+    (local $second i32)
+    (local.set $len (i32.wrap_i64 (call $input_length)))
+    (block $done
+      (loop $copy
+        (br_if $done (i32.ge_u (local.get $i) (local.get $len)))
+        (i32.store8
+          (i32.add (global.get $input) (local.get $i))
+          (call $input_load_u8 (i64.extend_i32_u (local.get $i))))
+        (local.set $i (i32.add (local.get $i) (i32.const 1)))
+        (br $copy)))
+    (local.set $first (call $find_space (i32.const 0) (local.get $len)))
+    (local.set $second
+      (call $find_space (i32.add (local.get $first) (i32.const 1)) (local.get $len)))
+    (call $output_block
+      (call $call_plugin
+        (call $to_extism (global.get $input) (local.get $first))
+        (call $to_extism
+          (i32.add (global.get $input) (i32.add (local.get $first) (i32.const 1)))
+          (i32.sub (i32.sub (local.get $second) (local.get $first)) (i32.const 1)))
+        (call $to_extism
+          (i32.add (global.get $input) (i32.add (local.get $second) (i32.const 1)))
+          (i32.sub (i32.sub (local.get $len) (local.get $second)) (i32.const 1)))))
+    (i32.const 0))
+
+  (func (export "loop") (result i32)
+    (call $output_block
+      (call $call_plugin
+        (call $to_extism (i32.const 0) (i32.const 5))
+        (call $to_extism (i32.const 5) (i32.const 4))
+        (call $to_extism (i32.const 9) (i32.const 2))))
+    (i32.const 0)))
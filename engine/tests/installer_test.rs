@@ -0,0 +1,410 @@
+//! Integration tests for the plugin download installer
+//!
+//! Covers range-resume after an interrupted transfer, retry after server
+//! errors, hash verification before a download is promoted, and re-consent
+//! for updates that request more permissions, including the database record
+//! of an installed plugin.
+
+use rove_engine::crypto::CryptoModule;
+use rove_engine::runtime::installer::{part_path, Installer, UpdateOutcome};
+use sdk::errors::EngineError;
+use sdk::manifest::{PluginEntry, PluginPermissions};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tempfile::TempDir;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use wiremock::{
+    matchers::{header, method, path},
+    Mock, MockServer, ResponseTemplate,
+};
+
+fn plugin_blob() -> Vec<u8> {
+    (0..64 * 1024).map(|i| (i % 251) as u8).collect()
+}
+
+fn installer() -> Installer {
+    Installer::new()
+        .with_max_retries(3)
+        .with_initial_backoff(Duration::from_millis(10))
+}
+
+/// Read an HTTP request head and return it lowercased
+async fn read_request(stream: &mut TcpStream) -> String {
+    let mut head = Vec::new();
+    let mut buf = [0u8; 1024];
+    while !head.windows(4).any(|w| w == b"\r\n\r\n") {
+        let n = stream.read(&mut buf).await.unwrap();
+        if n == 0 {
+            break;
+        }
+        head.extend_from_slice(&buf[..n]);
+    }
+    String::from_utf8_lossy(&head).to_lowercase()
+}
+
+/// Serve `blob` but drop the first connection halfway through the body
+///
+/// Returns the server URL and the request heads it received.
+async fn start_flaky_server(blob: Vec<u8>) -> (String, Arc<Mutex<Vec<String>>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}/plugin.wasm", listener.local_addr().unwrap());
+    let requests = Arc::new(Mutex::new(Vec::new()));
+    let seen = requests.clone();
+
+    tokio::spawn(async move {
+        let half = blob.len() / 2;
+
+        // First connection: advertise the full blob, send half, hang up
+        let (mut stream, _) = listener.accept().await.unwrap();
+        let request = read_request(&mut stream).await;
+        seen.lock().unwrap().push(request);
+        let head = format!(
+            "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+            blob.len()
+        );
+        stream.write_all(head.as_bytes()).await.unwrap();
+        stream.write_all(&blob[..half]).await.unwrap();
+        stream.shutdown().await.ok();
+        drop(stream);
+
+        // Second connection: answer the range request with the rest
+        let (mut stream, _) = listener.accept().await.unwrap();
+        let request = read_request(&mut stream).await;
+        seen.lock().unwrap().push(request);
+        let head = format!(
+            "HTTP/1.1 206 Partial Content\r\nContent-Length: {}\r\nContent-Range: bytes {}-{}/{}\r\nConnection: close\r\n\r\n",
+            blob.len() - half,
+            half,
+            blob.len() - 1,
+            blob.len()
+        );
+        stream.write_all(head.as_bytes()).await.unwrap();
+        stream.write_all(&blob[half..]).await.unwrap();
+        stream.shutdown().await.ok();
+    });
+
+    (url, requests)
+}
+
+#[tokio::test]
+async fn test_interrupted_download_resumes_and_verifies() {
+    let blob = plugin_blob();
+    let hash = CryptoModule::compute_hash(&blob);
+    let (url, requests) = start_flaky_server(blob.clone()).await;
+
+    let temp_dir = TempDir::new().unwrap();
+    let dest = temp_dir.path().join("plugin.wasm");
+
+    installer().download(&url, &dest, &hash).await.unwrap();
+
+    assert_eq!(std::fs::read(&dest).unwrap(), blob);
+    assert!(!part_path(&dest).exists());
+
+    let requests = requests.lock().unwrap();
+    assert_eq!(requests.len(), 2);
+    assert!(!requests[0].contains("range:"));
+    assert!(requests[1].contains(&format!("range: bytes={}-", blob.len() / 2)));
+}
+
+#[tokio::test]
+async fn test_resumes_existing_part_file() {
+    let blob = plugin_blob();
+    let hash = CryptoModule::compute_hash(&blob);
+    let offset = 1000;
+
+    let mock_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/plugin.wasm"))
+        .and(header("range", format!("bytes={}-", offset).as_str()))
+        .respond_with(
+            ResponseTemplate::new(206)
+                .insert_header(
+                    "content-range",
+                    format!("bytes {}-{}/{}", offset, blob.len() - 1, blob.len()).as_str(),
+                )
+                .set_body_bytes(blob[offset..].to_vec()),
+        )
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let temp_dir = TempDir::new().unwrap();
+    let dest = temp_dir.path().join("plugin.wasm");
+    std::fs::write(part_path(&dest), &blob[..offset]).unwrap();
+
+    let url = format!("{}/plugin.wasm", mock_server.uri());
+    installer()
+        .download(&url, &dest, &format!("sha256:{}", hash))
+        .await
+        .unwrap();
+
+    assert_eq!(std::fs::read(&dest).unwrap(), blob);
+    assert!(!part_path(&dest).exists());
+}
+
+#[tokio::test]
+async fn test_restarts_when_server_resumes_at_the_wrong_offset() {
+    let blob = plugin_blob();
+    let hash = CryptoModule::compute_hash(&blob);
+    let offset = 1000;
+
+    let mock_server = MockServer::start().await;
+    // Claims a partial response but sends the blob from the start
+    Mock::given(method("GET"))
+        .and(path("/plugin.wasm"))
+        .and(header("range", format!("bytes={}-", offset).as_str()))
+        .respond_with(
+            ResponseTemplate::new(206)
+                .insert_header(
+                    "content-range",
+                    format!("bytes 0-{}/{}", blob.len() - 1, blob.len()).as_str(),
+                )
+                .set_body_bytes(blob.clone()),
+        )
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/plugin.wasm"))
+        .respond_with(ResponseTemplate::new(200).set_body_bytes(blob.clone()))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let temp_dir = TempDir::new().unwrap();
+    let dest = temp_dir.path().join("plugin.wasm");
+    std::fs::write(part_path(&dest), &blob[..offset]).unwrap();
+
+    let url = format!("{}/plugin.wasm", mock_server.uri());
+    installer().download(&url, &dest, &hash).await.unwrap();
+
+    assert_eq!(std::fs::read(&dest).unwrap(), blob);
+    assert!(!part_path(&dest).exists());
+}
+
+#[tokio::test]
+async fn test_retries_after_server_errors() {
+    let blob = plugin_blob();
+    let hash = CryptoModule::compute_hash(&blob);
+
+    let mock_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/plugin.wasm"))
+        .respond_with(ResponseTemplate::new(503))
+        .up_to_n_times(2)
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/plugin.wasm"))
+        .respond_with(ResponseTemplate::new(200).set_body_bytes(blob.clone()))
+        .mount(&mock_server)
+        .await;
+
+    let temp_dir = TempDir::new().unwrap();
+    let dest = temp_dir.path().join("plugin.wasm");
+    let url = format!("{}/plugin.wasm", mock_server.uri());
+
+    installer().download(&url, &dest, &hash).await.unwrap();
+    assert_eq!(std::fs::read(&dest).unwrap(), blob);
+}
+
+#[tokio::test]
+async fn test_gives_up_after_max_retries() {
+    let mock_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/plugin.wasm"))
+        .respond_with(ResponseTemplate::new(503))
+        .expect(3)
+        .mount(&mock_server)
+        .await;
+
+    let temp_dir = TempDir::new().unwrap();
+    let dest = temp_dir.path().join("plugin.wasm");
+    let url = format!("{}/plugin.wasm", mock_server.uri());
+
+    let result = installer()
+        .with_max_retries(2)
+        .download(&url, &dest, &"0".repeat(64))
+        .await;
+
+    assert!(matches!(result, Err(EngineError::Network(_))));
+    assert!(!dest.exists());
+}
+
+#[tokio::test]
+async fn test_hash_mismatch_is_not_promoted() {
+    let mock_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/plugin.wasm"))
+        .respond_with(ResponseTemplate::new(200).set_body_bytes(b"tampered".to_vec()))
+        .mount(&mock_server)
+        .await;
+
+    let temp_dir = TempDir::new().unwrap();
+    let dest = temp_dir.path().join("plugin.wasm");
+    let url = format!("{}/plugin.wasm", mock_server.uri());
+    let expected = CryptoModule::compute_hash(&plugin_blob());
+
+    let result = installer().download(&url, &dest, &expected).await;
+
+    assert!(matches!(result, Err(EngineError::HashMismatch(_))));
+    assert!(!dest.exists());
+    assert!(!part_path(&dest).exists());
+}
+
+/// Manifest entry for version 0.2.0 of a plugin serving `blob`
+fn plugin_update(blob: &[u8], permissions: PluginPermissions) -> PluginEntry {
+    PluginEntry {
+        name: "fs-editor".to_string(),
+        version: "0.2.0".to_string(),
+        path: "plugins/fs-editor.wasm".to_string(),
+        hash: format!("sha256:{}", CryptoModule::compute_hash(blob)),
+        permissions,
+        output_schemas: HashMap::new(),
+        host_abi: None,
+    }
+}
+
+async fn mock_update(blob: Vec<u8>, downloads: u64) -> MockServer {
+    let mock_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/plugin.wasm"))
+        .respond_with(ResponseTemplate::new(200).set_body_bytes(blob))
+        .expect(downloads)
+        .mount(&mock_server)
+        .await;
+    mock_server
+}
+
+#[tokio::test]
+async fn test_update_with_unchanged_permissions_does_not_prompt() {
+    let blob = plugin_blob();
+    let mock_server = mock_update(blob.clone(), 1).await;
+    let temp_dir = TempDir::new().unwrap();
+    let dest = temp_dir.path().join("plugin.wasm");
+    std::fs::write(&dest, b"old version").unwrap();
+
+    let installed = PluginPermissions::default();
+    let entry = plugin_update(&blob, PluginPermissions::default());
+    let url = format!("{}/plugin.wasm", mock_server.uri());
+
+    let outcome = installer()
+        .update(&url, &dest, &installed, &entry, |_| {
+            panic!("unchanged permissions must not prompt")
+        })
+        .await
+        .unwrap();
+
+    assert_eq!(outcome, UpdateOutcome::Updated);
+    assert_eq!(std::fs::read(&dest).unwrap(), blob);
+}
+
+#[tokio::test]
+async fn test_update_adding_network_requires_consent() {
+    let blob = plugin_blob();
+    let mock_server = mock_update(blob.clone(), 1).await;
+    let temp_dir = TempDir::new().unwrap();
+    let dest = temp_dir.path().join("plugin.wasm");
+    std::fs::write(&dest, b"old version").unwrap();
+
+    let installed = PluginPermissions::default();
+    let entry = plugin_update(
+        &blob,
+        PluginPermissions {
+            network: true,
+            ..PluginPermissions::default()
+        },
+    );
+    let url = format!("{}/plugin.wasm", mock_server.uri());
+
+    // Declined: the old version stays and nothing is downloaded
+    let mut prompted = Vec::new();
+    let outcome = installer()
+        .update(&url, &dest, &installed, &entry, |added| {
+            prompted = added.to_vec();
+            false
+        })
+        .await
+        .unwrap();
+
+    assert_eq!(
+        outcome,
+        UpdateOutcome::ConsentRequired {
+            added: vec!["network access".to_string()]
+        }
+    );
+    assert_eq!(prompted, vec!["network access"]);
+    assert_eq!(std::fs::read(&dest).unwrap(), b"old version");
+
+    // Approved: the new version replaces the old one
+    let outcome = installer()
+        .update(&url, &dest, &installed, &entry, |_| true)
+        .await
+        .unwrap();
+
+    assert_eq!(outcome, UpdateOutcome::Updated);
+    assert_eq!(std::fs::read(&dest).unwrap(), blob);
+}
+
+#[tokio::test]
+async fn test_installed_plugin_update_is_recorded_only_when_granted() {
+    use rove_engine::db::{Database, PluginRepository};
+    use rove_engine::handlers::update_installed_plugin;
+
+    let blob = plugin_blob();
+    let mock_server = mock_update(blob.clone(), 1).await;
+    let url = format!("{}/plugin.wasm", mock_server.uri());
+    let temp_dir = TempDir::new().unwrap();
+    let dest = temp_dir.path().join("fs-editor.wasm");
+    std::fs::write(&dest, b"old version").unwrap();
+
+    let database = Database::new(&temp_dir.path().join("rove.db"))
+        .await
+        .unwrap();
+    let plugins = PluginRepository::new(database.pool().clone());
+    let installed = PluginEntry {
+        version: "0.1.0".to_string(),
+        ..plugin_update(b"old version", PluginPermissions::default())
+    };
+    let plugin = plugins
+        .register_plugin(
+            "fs-editor",
+            "fs-editor",
+            "0.1.0",
+            dest.to_str().unwrap(),
+            &installed.hash,
+            &serde_json::to_string(&installed).unwrap(),
+        )
+        .await
+        .unwrap();
+    let updated = plugin_update(
+        &blob,
+        PluginPermissions {
+            network: true,
+            ..PluginPermissions::default()
+        },
+    );
+
+    // The stored permissions are compared; declining keeps the old record
+    let outcome =
+        update_installed_plugin(&plugins, &installer(), &plugin, &url, &updated, |_| false)
+            .await
+            .unwrap();
+    assert!(matches!(outcome, UpdateOutcome::ConsentRequired { .. }));
+    let record = plugins.get_plugin("fs-editor").await.unwrap().unwrap();
+    assert_eq!(record.version, "0.1.0");
+
+    let outcome =
+        update_installed_plugin(&plugins, &installer(), &plugin, &url, &updated, |_| true)
+            .await
+            .unwrap();
+    assert_eq!(outcome, UpdateOutcome::Updated);
+    assert_eq!(std::fs::read(&dest).unwrap(), blob);
+    let record = plugins.get_plugin("fs-editor").await.unwrap().unwrap();
+    assert_eq!(record.version, "0.2.0");
+    assert_eq!(record.wasm_hash, updated.hash);
+    let stored: PluginEntry = serde_json::from_str(&record.manifest_json).unwrap();
+    assert!(stored.permissions.network);
+}
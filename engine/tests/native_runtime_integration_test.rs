@@ -9,7 +9,7 @@ use sdk::{
     manifest::{CoreToolEntry, Manifest},
     AgentHandle, AgentHandleImpl, BusHandle, BusHandleImpl, ConfigHandle, ConfigHandleImpl,
     CryptoHandle, CryptoHandleImpl, DbHandle, DbHandleImpl, EngineError, NetworkHandle,
-    NetworkHandleImpl,
+    NetworkHandleImpl, RateLimitHandle, RateLimitHandleImpl,
 };
 use serde_json::json;
 use std::sync::Arc;
@@ -21,6 +21,14 @@ impl AgentHandleImpl for MockAgentHandle {
         Ok("task-123".to_string())
     }
 
+    fn submit_task_for_request(
+        &self,
+        _task_input: String,
+        _request_id: &str,
+    ) -> Result<String, EngineError> {
+        Ok("task-123".to_string())
+    }
+
     fn get_task_status(&self, _task_id: &str) -> Result<String, EngineError> {
         Ok("completed".to_string())
     }
@@ -88,6 +96,21 @@ impl BusHandleImpl for MockBusHandle {
     }
 }
 
+struct MockRateLimitHandle;
+impl RateLimitHandleImpl for MockRateLimitHandle {
+    fn check_limit(&self, _source: &str, _operation: &str) -> Result<(), EngineError> {
+        Ok(())
+    }
+
+    fn record_operation(&self, _source: &str, _operation: &str) -> Result<(), EngineError> {
+        Ok(())
+    }
+
+    fn check_and_record(&self, _source: &str, _operation: &str) -> Result<(), EngineError> {
+        Ok(())
+    }
+}
+
 fn create_mock_context() -> CoreContext {
     let agent = AgentHandle::new(Arc::new(MockAgentHandle));
     let db = DbHandle::new(Arc::new(MockDbHandle));
@@ -95,8 +118,9 @@ fn create_mock_context() -> CoreContext {
     let crypto = CryptoHandle::new(Arc::new(MockCryptoHandle));
     let network = NetworkHandle::new(Arc::new(MockNetworkHandle));
     let bus = BusHandle::new(Arc::new(MockBusHandle));
+    let rate_limit = RateLimitHandle::new(Arc::new(MockRateLimitHandle));
 
-    CoreContext::new(agent, db, config, crypto, network, bus)
+    CoreContext::new(agent, db, config, crypto, network, bus, rate_limit)
 }
 
 #[test]
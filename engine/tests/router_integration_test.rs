@@ -61,6 +61,8 @@ async fn test_llm_router_failover_with_wiremock() {
         anthropic: Default::default(),
         gemini: Default::default(),
         nvidia_nim: Default::default(),
+        provider_priority: Vec::new(),
+        circuit_breaker: Default::default(),
     });
 
     let router = LLMRouter::new(vec![provider1, provider2], config);
@@ -118,6 +120,8 @@ async fn test_property_llm_router_provider_fallback_all_fail() {
         anthropic: Default::default(),
         gemini: Default::default(),
         nvidia_nim: Default::default(),
+        provider_priority: Vec::new(),
+        circuit_breaker: Default::default(),
     });
 
     let router = LLMRouter::new(vec![p1, p2], config);
@@ -0,0 +1,121 @@
+//! Integration tests for `rove run --no-daemon`
+//!
+//! Runs a task end to end against a mocked Ollama server and checks that the
+//! mode completes without touching the PID file or the shared database.
+
+use rove_engine::config::Config;
+use rove_engine::handlers::{handle_run, OutputFormat, RunOptions};
+use serde_json::json;
+use tempfile::TempDir;
+use wiremock::{
+    matchers::{method, path},
+    Mock, MockServer, ResponseTemplate,
+};
+
+fn write_config(temp_dir: &TempDir, ollama_url: &str) -> Config {
+    let root = temp_dir.path();
+    let config_content = format!(
+        r#"
+[core]
+workspace = "{workspace}"
+data_dir = "{data}"
+
+[llm]
+default_provider = "ollama"
+
+[llm.ollama]
+base_url = "{ollama_url}"
+
+[tools]
+
+[plugins]
+
+[security]
+
+[steering]
+auto_detect = false
+"#,
+        workspace = root.join("workspace").display(),
+        data = root.join("data").display(),
+        ollama_url = ollama_url,
+    );
+
+    std::fs::create_dir_all(root.join("workspace")).unwrap();
+    let config_path = root.join("config.toml");
+    std::fs::write(&config_path, config_content).unwrap();
+    Config::load_from_path(&config_path).unwrap()
+}
+
+#[tokio::test]
+async fn test_no_daemon_run_leaves_nothing_behind() {
+    let mock_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/api/tags"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "models": [] })))
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/api/chat"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "model": "llama3.1:8b",
+            "created_at": "2023-08-04T19:22:45.499127Z",
+            "message": {
+                "role": "assistant",
+                "content": "All done."
+            },
+            "done": true
+        })))
+        .mount(&mock_server)
+        .await;
+
+    let temp_dir = TempDir::new().unwrap();
+    let config = write_config(&temp_dir, &mock_server.uri());
+    // A daemon's PID file must be left alone
+    let data_dir = temp_dir.path().join("data");
+    std::fs::create_dir_all(&data_dir).unwrap();
+    std::fs::write(data_dir.join("rove.pid"), "4242").unwrap();
+
+    let options = RunOptions {
+        task: Some("say hello".to_string()),
+        no_daemon: true,
+        ..RunOptions::default()
+    };
+    handle_run(options, &config, OutputFormat::Json)
+        .await
+        .unwrap();
+
+    // The task's history went to a scratch database that is gone now
+    assert!(!data_dir.join("rove.db").exists());
+    assert_eq!(
+        std::fs::read_to_string(data_dir.join("rove.pid")).unwrap(),
+        "4242"
+    );
+    let requests = mock_server.received_requests().await.unwrap();
+    assert!(requests.iter().any(|r| r.url.path() == "/api/chat"));
+
+    // A normal run keeps its history in the shared database
+    let options = RunOptions {
+        task: Some("say hello".to_string()),
+        ..RunOptions::default()
+    };
+    handle_run(options, &config, OutputFormat::Json)
+        .await
+        .unwrap();
+    assert!(data_dir.join("rove.db").exists());
+}
+
+#[tokio::test]
+async fn test_no_daemon_cannot_resume() {
+    let temp_dir = TempDir::new().unwrap();
+    let config = write_config(&temp_dir, "http://127.0.0.1:9");
+
+    let options = RunOptions {
+        resume: Some("task-1".to_string()),
+        no_daemon: true,
+        ..RunOptions::default()
+    };
+    let err = handle_run(options, &config, OutputFormat::Json)
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("--no-daemon"));
+}
@@ -9,6 +9,7 @@
 
 use rove_engine::message_bus::{EventType, MessageBus};
 use sdk::manifest::{Manifest, PluginEntry, PluginPermissions};
+use std::collections::HashMap;
 use std::sync::Arc;
 
 /// Test that crash count is properly tracked
@@ -253,6 +254,8 @@ fn create_test_manifest_with_crashing_plugin() -> Manifest {
             version: "0.1.0".to_string(),
             path: "test-plugins/crash-test.wasm".to_string(),
             hash: "test_hash".to_string(),
+            output_schemas: HashMap::new(),
+            host_abi: None,
             permissions: PluginPermissions::default(),
         }],
     }
@@ -271,6 +274,8 @@ fn create_test_manifest_with_always_crashing_plugin() -> Manifest {
             version: "0.1.0".to_string(),
             path: "test-plugins/always-crash.wasm".to_string(),
             hash: "test_hash".to_string(),
+            output_schemas: HashMap::new(),
+            host_abi: None,
             permissions: PluginPermissions::default(),
         }],
     }
@@ -290,6 +295,8 @@ fn create_test_manifest_with_multiple_plugins() -> Manifest {
                 version: "0.1.0".to_string(),
                 path: "test-plugins/plugin-a.wasm".to_string(),
                 hash: "test_hash_a".to_string(),
+                output_schemas: HashMap::new(),
+                host_abi: None,
                 permissions: PluginPermissions::default(),
             },
             PluginEntry {
@@ -297,6 +304,8 @@ fn create_test_manifest_with_multiple_plugins() -> Manifest {
                 version: "0.1.0".to_string(),
                 path: "test-plugins/plugin-b-crashes.wasm".to_string(),
                 hash: "test_hash_b".to_string(),
+                output_schemas: HashMap::new(),
+                host_abi: None,
                 permissions: PluginPermissions::default(),
             },
             PluginEntry {
@@ -304,6 +313,8 @@ fn create_test_manifest_with_multiple_plugins() -> Manifest {
                 version: "0.1.0".to_string(),
                 path: "test-plugins/plugin-c.wasm".to_string(),
                 hash: "test_hash_c".to_string(),
+                output_schemas: HashMap::new(),
+                host_abi: None,
                 permissions: PluginPermissions::default(),
             },
         ],
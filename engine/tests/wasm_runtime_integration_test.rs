@@ -8,14 +8,37 @@
 //! - Requirement 5.3: Gate 2 - Verify file hash with BLAKE3
 //! - Requirement 5.4: Validate manifest contains no absolute paths
 
+use rove_engine::conductor::DEFAULT_MAX_CALL_DEPTH;
 use rove_engine::crypto::CryptoModule;
 use rove_engine::fs_guard::FileSystemGuard;
-use rove_engine::runtime::WasmRuntime;
+use rove_engine::runtime::{DeterministicEnv, HostFunctionPolicy, WasmRuntime, DEFAULT_POOL_SIZE};
 use sdk::errors::EngineError;
 use sdk::manifest::{Manifest, PluginEntry, PluginPermissions};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use tempfile::TempDir;
 
+/// Plugin whose `echo` export returns its input unchanged
+///
+/// Fixtures are WAT text, which the runtime compiles like a binary module.
+const ECHO_FIXTURE: &str = "tests/fixtures/echo.wat";
+
+/// Plugin whose `now` and `random` exports return the `now_ms` and
+/// `random_u64` host function results as decimal strings
+const CLOCK_FIXTURE: &str = "tests/fixtures/clock.wat";
+
+/// Plugin whose `relay` export forwards `<plugin_id> <function> <input>`
+/// through the `call_plugin` host function, and whose `loop` export calls
+/// itself the same way
+const RELAY_FIXTURE: &str = "tests/fixtures/relay.wat";
+
+/// SHA-256 hash of the fixture at `path`, as declared in a manifest
+fn fixture_hash(path: &str) -> String {
+    hex::encode(Sha256::digest(std::fs::read(path).unwrap()))
+}
+
 /// Helper function to create a test manifest with a plugin entry
 fn create_test_manifest(plugin_name: &str, plugin_path: &str, plugin_hash: &str) -> Manifest {
     Manifest {
@@ -29,6 +52,8 @@ fn create_test_manifest(plugin_name: &str, plugin_path: &str, plugin_hash: &str)
             version: "0.1.0".to_string(),
             path: plugin_path.to_string(),
             hash: plugin_hash.to_string(),
+            output_schemas: HashMap::new(),
+            host_abi: None,
             permissions: PluginPermissions {
                 allowed_paths: vec!["workspace".to_string()],
                 denied_paths: vec![".ssh".to_string(), ".env".to_string()],
@@ -37,6 +62,7 @@ fn create_test_manifest(plugin_name: &str, plugin_path: &str, plugin_hash: &str)
                 allowed_commands: None,
                 denied_flags: None,
                 max_execution_time: None,
+                network: false,
             },
         }],
     }
@@ -233,7 +259,7 @@ async fn test_call_plugin_not_loaded() {
 
     let crypto = Arc::new(CryptoModule::new().unwrap());
     let fs_guard = Arc::new(FileSystemGuard::new(workspace));
-    let mut runtime = WasmRuntime::new(manifest, crypto, fs_guard);
+    let runtime = WasmRuntime::new(manifest, crypto, fs_guard);
 
     // Attempt to call a plugin that's not loaded
     let result = runtime
@@ -336,3 +362,442 @@ async fn test_two_gate_verification_flow() {
     // Gate 2 and WASM loading would be tested with actual WASM files
     // in more comprehensive integration tests
 }
+
+#[test]
+fn test_pool_size_configuration() {
+    let temp_dir = TempDir::new().unwrap();
+    let workspace = temp_dir.path().to_path_buf();
+
+    let manifest = Manifest {
+        version: "1.0.0".to_string(),
+        team_public_key: "ed25519:test_key".to_string(),
+        signature: "ed25519:test_sig".to_string(),
+        generated_at: "2024-01-15T10:30:00Z".to_string(),
+        core_tools: vec![],
+        plugins: vec![],
+    };
+
+    let crypto = Arc::new(CryptoModule::new().unwrap());
+    let fs_guard = Arc::new(FileSystemGuard::new(workspace));
+    let mut runtime = WasmRuntime::new(manifest, crypto, fs_guard);
+
+    assert_eq!(runtime.pool_size(), DEFAULT_POOL_SIZE);
+
+    runtime.set_pool_size(8);
+    assert_eq!(runtime.pool_size(), 8);
+
+    // Zero would deadlock every call, so it is clamped to one
+    runtime.set_pool_size(0);
+    assert_eq!(runtime.pool_size(), 1);
+}
+
+#[test]
+fn test_plugin_handle_not_loaded() {
+    let temp_dir = TempDir::new().unwrap();
+    let workspace = temp_dir.path().to_path_buf();
+
+    let manifest = Manifest {
+        version: "1.0.0".to_string(),
+        team_public_key: "ed25519:test_key".to_string(),
+        signature: "ed25519:test_sig".to_string(),
+        generated_at: "2024-01-15T10:30:00Z".to_string(),
+        core_tools: vec![],
+        plugins: vec![],
+    };
+
+    let crypto = Arc::new(CryptoModule::new().unwrap());
+    let fs_guard = Arc::new(FileSystemGuard::new(workspace));
+    let runtime = WasmRuntime::new(manifest, crypto, fs_guard);
+
+    assert!(matches!(
+        runtime.plugin_handle("nonexistent"),
+        Err(EngineError::PluginNotLoaded(_))
+    ));
+}
+
+/// Test that concurrent calls to one plugin don't interfere
+///
+/// Each call checks out its own instance from the pool, so an echo plugin must
+/// return exactly the input it was given even when many calls overlap.
+#[tokio::test]
+async fn test_concurrent_calls_do_not_interfere() {
+    let workspace_dir = TempDir::new().unwrap();
+    let workspace = workspace_dir.path().to_path_buf();
+
+    let manifest = create_test_manifest("echo", ECHO_FIXTURE, &fixture_hash(ECHO_FIXTURE));
+    let crypto = Arc::new(CryptoModule::new().unwrap());
+    let fs_guard = Arc::new(FileSystemGuard::new(workspace));
+    let mut runtime = WasmRuntime::new(manifest, crypto, fs_guard);
+    runtime.set_pool_size(4);
+    runtime.load_plugin("echo").await.unwrap();
+
+    let handle = runtime.plugin_handle("echo").unwrap();
+    let mut tasks = Vec::new();
+    for i in 0..32 {
+        let handle = handle.clone();
+        tasks.push(tokio::spawn(async move {
+            let input = format!("{{\"call\":{}}}", i);
+            let output = handle.call("echo", input.as_bytes()).await.unwrap();
+            assert_eq!(output, input.as_bytes());
+        }));
+    }
+
+    for task in tasks {
+        task.await.unwrap();
+    }
+    assert_eq!(runtime.get_crash_count("echo"), Some(0));
+}
+
+#[tokio::test]
+async fn test_output_schema_validation() {
+    let workspace_dir = TempDir::new().unwrap();
+    let workspace = workspace_dir.path().to_path_buf();
+
+    // The echo plugin returns its input, so the input controls the output shape
+    let mut manifest = create_test_manifest("echo", ECHO_FIXTURE, &fixture_hash(ECHO_FIXTURE));
+    manifest.plugins[0].output_schemas.insert(
+        "echo".to_string(),
+        serde_json::json!({
+            "type": "object",
+            "required": ["call"],
+            "properties": { "call": { "type": "integer" } }
+        }),
+    );
+
+    let crypto = Arc::new(CryptoModule::new().unwrap());
+    let fs_guard = Arc::new(FileSystemGuard::new(workspace));
+    let mut runtime = WasmRuntime::new(manifest, crypto, fs_guard);
+    runtime.load_plugin("echo").await.unwrap();
+
+    // Valid shape passes through unchanged
+    let output = runtime
+        .call_plugin("echo", "echo", br#"{"call":1}"#)
+        .await
+        .unwrap();
+    assert_eq!(output, br#"{"call":1}"#);
+
+    // Wrong field type is reported by name
+    let result = runtime
+        .call_plugin("echo", "echo", br#"{"call":"one"}"#)
+        .await;
+    match result {
+        Err(EngineError::ToolError(msg)) => assert!(msg.contains("field 'call'")),
+        other => panic!("Expected ToolError, got {:?}", other),
+    }
+
+    // Missing required field is reported by name
+    let result = runtime.call_plugin("echo", "echo", b"{}").await;
+    match result {
+        Err(EngineError::ToolError(msg)) => assert!(msg.contains("'call'")),
+        other => panic!("Expected ToolError, got {:?}", other),
+    }
+}
+
+/// Test that plugin calls report the fuel they consumed
+///
+/// Echoing a large input copies more bytes through plugin memory than echoing
+/// a small one, so it must consume more fuel.
+#[tokio::test]
+async fn test_plugin_call_reports_fuel() {
+    let workspace_dir = TempDir::new().unwrap();
+    let workspace = workspace_dir.path().to_path_buf();
+
+    let manifest = create_test_manifest("echo", ECHO_FIXTURE, &fixture_hash(ECHO_FIXTURE));
+    let crypto = Arc::new(CryptoModule::new().unwrap());
+    let fs_guard = Arc::new(FileSystemGuard::new(workspace));
+    let mut runtime = WasmRuntime::new(manifest, crypto, fs_guard);
+    runtime.load_plugin("echo").await.unwrap();
+    let handle = runtime.plugin_handle("echo").unwrap();
+
+    let light = handle.call_metered("echo", b"{}").await.unwrap();
+    let heavy_input = format!("{{\"data\":\"{}\"}}", "x".repeat(64 * 1024));
+    let heavy = handle
+        .call_metered("echo", heavy_input.as_bytes())
+        .await
+        .unwrap();
+
+    assert_eq!(light.output, b"{}");
+    let light_fuel = light.fuel_consumed.expect("fuel metering enabled");
+    let heavy_fuel = heavy.fuel_consumed.expect("fuel metering enabled");
+    assert!(light_fuel > 0);
+    assert!(
+        heavy_fuel > light_fuel,
+        "heavy call used {} fuel, light call {}",
+        heavy_fuel,
+        light_fuel
+    );
+}
+
+/// Test that a plugin reading "now" sees the injected fixed time
+///
+/// The clock fixture exports `now`, which returns the `now_ms` host function
+/// result as a decimal string, and `random`, which does the same for
+/// `random_u64`.
+#[tokio::test]
+async fn test_deterministic_clock_and_seed() {
+    let workspace_dir = TempDir::new().unwrap();
+    let workspace = workspace_dir.path().to_path_buf();
+
+    let load = |seed: u64| {
+        let manifest = create_test_manifest("clock", CLOCK_FIXTURE, &fixture_hash(CLOCK_FIXTURE));
+        let crypto = Arc::new(CryptoModule::new().unwrap());
+        let fs_guard = Arc::new(FileSystemGuard::new(workspace.clone()));
+        WasmRuntime::new(manifest, crypto, fs_guard)
+            .with_deterministic_env(DeterministicEnv::new(1_700_000_000_000, seed))
+    };
+
+    let mut runtime = load(42);
+    runtime.load_plugin("clock").await.unwrap();
+
+    let now = runtime.call_plugin("clock", "now", b"").await.unwrap();
+    assert_eq!(String::from_utf8(now).unwrap(), "1700000000000");
+
+    // The same seed yields the same random sequence in a fresh runtime
+    let mut replay = load(42);
+    replay.load_plugin("clock").await.unwrap();
+    let mut sequence = Vec::new();
+    for _ in 0..3 {
+        let first = runtime.call_plugin("clock", "random", b"").await.unwrap();
+        let second = replay.call_plugin("clock", "random", b"").await.unwrap();
+        assert_eq!(first, second);
+        sequence.push(first);
+    }
+    // One sequence per plugin, not one restarted by every pooled instance
+    sequence.dedup();
+    assert_eq!(sequence.len(), 3);
+}
+
+/// Load the relay and echo fixtures into one runtime
+async fn load_relay_and_echo(workspace: std::path::PathBuf) -> WasmRuntime {
+    let mut manifest = create_test_manifest("echo", ECHO_FIXTURE, &fixture_hash(ECHO_FIXTURE));
+    let mut relay = manifest.plugins[0].clone();
+    relay.name = "relay".to_string();
+    relay.path = RELAY_FIXTURE.to_string();
+    relay.hash = fixture_hash(RELAY_FIXTURE);
+    manifest.plugins.push(relay);
+
+    let crypto = Arc::new(CryptoModule::new().unwrap());
+    let fs_guard = Arc::new(FileSystemGuard::new(workspace));
+    let mut runtime = WasmRuntime::new(manifest, crypto, fs_guard);
+    runtime.set_pool_size(DEFAULT_MAX_CALL_DEPTH + 1);
+    runtime.load_plugin("echo").await.unwrap();
+    runtime.load_plugin("relay").await.unwrap();
+    runtime
+}
+
+/// Test that one plugin can call another through the host
+#[tokio::test]
+async fn test_plugin_calls_another_plugin() {
+    let workspace_dir = TempDir::new().unwrap();
+    let runtime = load_relay_and_echo(workspace_dir.path().to_path_buf()).await;
+
+    let output = runtime
+        .call_plugin("relay", "relay", br#"echo echo {"call":7}"#)
+        .await
+        .unwrap();
+    assert_eq!(output, br#"{"call":7}"#);
+
+    // Unknown targets come back as an error result, not a crash
+    let output = runtime
+        .call_plugin("relay", "relay", b"missing echo {}")
+        .await
+        .unwrap();
+    let output: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    assert!(output["error"].as_str().unwrap().contains("missing"));
+    assert_eq!(runtime.get_crash_count("relay"), Some(0));
+}
+
+/// Test that a plugin calling itself stops at the depth limit
+#[tokio::test]
+async fn test_plugin_call_recursion_is_bounded() {
+    let workspace_dir = TempDir::new().unwrap();
+    let runtime = load_relay_and_echo(workspace_dir.path().to_path_buf()).await;
+
+    let output = runtime.call_plugin("relay", "loop", b"{}").await.unwrap();
+    let output = String::from_utf8(output).unwrap();
+    assert!(
+        output.contains(&format!("Maximum call depth of {}", DEFAULT_MAX_CALL_DEPTH)),
+        "got {}",
+        output
+    );
+}
+
+/// Test that a host function disabled by policy is refused to a plugin
+/// whose manifest entry allows it
+#[tokio::test]
+async fn test_disabled_host_function_is_refused_at_call_time() {
+    let workspace_dir = TempDir::new().unwrap();
+    let mut manifest = create_test_manifest("echo", ECHO_FIXTURE, &fixture_hash(ECHO_FIXTURE));
+    let mut relay = manifest.plugins[0].clone();
+    relay.name = "relay".to_string();
+    relay.path = RELAY_FIXTURE.to_string();
+    relay.hash = fixture_hash(RELAY_FIXTURE);
+    manifest.plugins.push(relay);
+
+    let crypto = Arc::new(CryptoModule::new().unwrap());
+    let fs_guard = Arc::new(FileSystemGuard::new(workspace_dir.path().to_path_buf()));
+    let mut runtime = WasmRuntime::new(manifest, crypto, fs_guard);
+    runtime.set_host_function_policy(HostFunctionPolicy::new(["call_plugin"]));
+    runtime.load_plugin("echo").await.unwrap();
+    runtime.load_plugin("relay").await.unwrap();
+
+    let output = runtime
+        .call_plugin("relay", "relay", br#"echo echo {"call":7}"#)
+        .await
+        .unwrap();
+    let output: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    assert_eq!(
+        output["error"],
+        "Host function 'call_plugin' is disabled by configuration"
+    );
+
+    // Echo itself is not disabled, only the relay's way of reaching it
+    let output = runtime.call_plugin("echo", "echo", b"{}").await.unwrap();
+    assert_eq!(output, b"{}");
+}
+
+/// Test that plugin dev mode loads an unsigned plugin missing from the manifest
+#[tokio::test]
+async fn test_dev_mode_loads_unsigned_plugin() {
+    if CryptoModule::is_production() {
+        return;
+    }
+
+    let workspace_dir = TempDir::new().unwrap();
+    let dev_dir = TempDir::new().unwrap();
+    std::fs::copy(ECHO_FIXTURE, dev_dir.path().join("echo.wasm")).unwrap();
+    let hash_file = dev_dir.path().join("echo.wasm.sha256");
+    std::fs::write(
+        &hash_file,
+        format!("{}  echo.wasm\n", fixture_hash(ECHO_FIXTURE)),
+    )
+    .unwrap();
+
+    let manifest = create_test_manifest("other", "plugins/other.wasm", "0");
+    let crypto = Arc::new(CryptoModule::new().unwrap());
+    let fs_guard = Arc::new(FileSystemGuard::new(workspace_dir.path().to_path_buf()));
+    let mut runtime = WasmRuntime::new(manifest, crypto, fs_guard);
+
+    // Not loadable the normal way: it isn't in the manifest
+    assert!(matches!(
+        runtime.load_plugin("echo").await,
+        Err(EngineError::PluginNotInManifest(_))
+    ));
+
+    let loaded = runtime.load_dev_plugins(dev_dir.path()).await.unwrap();
+    assert_eq!(loaded, vec!["echo"]);
+    let output = runtime
+        .call_plugin("echo", "echo", br#"{"dev":true}"#)
+        .await
+        .unwrap();
+    assert_eq!(output, br#"{"dev":true}"#);
+}
+
+/// Test that plugin dev mode refuses a module that doesn't match its hash file
+#[tokio::test]
+async fn test_dev_mode_checks_hash_file() {
+    if CryptoModule::is_production() {
+        return;
+    }
+
+    let workspace_dir = TempDir::new().unwrap();
+    let dev_dir = TempDir::new().unwrap();
+    std::fs::copy(ECHO_FIXTURE, dev_dir.path().join("echo.wasm")).unwrap();
+
+    let manifest = create_test_manifest("other", "plugins/other.wasm", "0");
+    let crypto = Arc::new(CryptoModule::new().unwrap());
+    let fs_guard = Arc::new(FileSystemGuard::new(workspace_dir.path().to_path_buf()));
+    let mut runtime = WasmRuntime::new(manifest, crypto, fs_guard);
+
+    // No hash file
+    assert!(matches!(
+        runtime.load_dev_plugins(dev_dir.path()).await,
+        Err(EngineError::Plugin(_))
+    ));
+
+    // A hash file left over from another build
+    std::fs::write(
+        dev_dir.path().join("echo.wasm.sha256"),
+        fixture_hash(CLOCK_FIXTURE),
+    )
+    .unwrap();
+    assert!(matches!(
+        runtime.load_dev_plugins(dev_dir.path()).await,
+        Err(EngineError::HashMismatch(_))
+    ));
+    assert!(!runtime.is_plugin_loaded("echo"));
+}
+
+/// Test that production builds refuse plugin dev mode
+#[cfg(feature = "production")]
+#[tokio::test]
+async fn test_dev_mode_refused_in_production() {
+    let workspace_dir = TempDir::new().unwrap();
+    let dev_dir = TempDir::new().unwrap();
+    std::fs::write(dev_dir.path().join("echo.wasm"), b"\0asm").unwrap();
+
+    let manifest = create_test_manifest("other", "plugins/other.wasm", "0");
+    let crypto = Arc::new(CryptoModule::new().unwrap());
+    let fs_guard = Arc::new(FileSystemGuard::new(workspace_dir.path().to_path_buf()));
+    let mut runtime = WasmRuntime::new(manifest, crypto, fs_guard);
+
+    let result = runtime.load_dev_plugins(dev_dir.path()).await;
+
+    assert!(matches!(result, Err(EngineError::Config(_))));
+    assert!(!runtime.is_plugin_loaded("echo"));
+}
+
+/// Path of `path` relative to the current directory
+///
+/// Manifest paths must not be absolute, so a temp dir is reached by climbing
+/// to the root with `..` and descending again.
+fn relative_to_cwd(path: &Path) -> PathBuf {
+    let cwd = std::env::current_dir().unwrap().canonicalize().unwrap();
+    let path = path.canonicalize().unwrap();
+    let mut relative: PathBuf = cwd.components().skip(1).map(|_| "..").collect();
+    relative.extend(path.components().skip(1));
+    relative
+}
+
+/// Write `bytes` as a plugin in a temp dir and declare it in a manifest
+fn manifest_with_blob(name: &str, bytes: &[u8]) -> (TempDir, Manifest) {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join(format!("{}.wasm", name));
+    std::fs::write(&path, bytes).unwrap();
+    let hash = hex::encode(Sha256::digest(bytes));
+    let path = relative_to_cwd(&path);
+    let manifest = create_test_manifest(name, path.to_str().unwrap(), &hash);
+    (dir, manifest)
+}
+
+/// Test that a well-formed plugin passes the doctor check and is unloaded again
+#[tokio::test]
+async fn test_check_plugin_accepts_loadable_blob() {
+    let workspace_dir = TempDir::new().unwrap();
+    // The smallest valid module: magic number and version, no sections
+    let (_plugins, manifest) = manifest_with_blob("empty", b"\0asm\x01\0\0\0");
+
+    let crypto = Arc::new(CryptoModule::new().unwrap());
+    let fs_guard = Arc::new(FileSystemGuard::new(workspace_dir.path().to_path_buf()));
+    let mut runtime = WasmRuntime::new(manifest, crypto, fs_guard);
+
+    runtime.check_plugin("empty").await.unwrap();
+    assert!(!runtime.is_plugin_loaded("empty"));
+}
+
+/// Test that a corrupt plugin blob fails the doctor check
+#[tokio::test]
+async fn test_check_plugin_rejects_broken_blob() {
+    let workspace_dir = TempDir::new().unwrap();
+    // Correct hash, but not a WASM module
+    let (_plugins, manifest) = manifest_with_blob("broken", b"\0asm\x01\0\0\0garbage");
+
+    let crypto = Arc::new(CryptoModule::new().unwrap());
+    let fs_guard = Arc::new(FileSystemGuard::new(workspace_dir.path().to_path_buf()));
+    let mut runtime = WasmRuntime::new(manifest, crypto, fs_guard);
+
+    let result = runtime.check_plugin("broken").await;
+
+    assert!(matches!(result, Err(EngineError::Plugin(_))));
+    assert!(!runtime.is_plugin_loaded("broken"));
+}
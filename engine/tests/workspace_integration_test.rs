@@ -0,0 +1,116 @@
+//! Integration tests for multiple workspaces
+//!
+//! Verifies that `[[workspaces]]` entries can be selected as the active
+//! workspace and that the file system guard and command working directory
+//! follow the selection.
+
+use rove_engine::config::Config;
+use rove_engine::fs_guard::FileSystemGuard;
+use rove_engine::tools::TerminalTool;
+use sdk::errors::EngineError;
+use std::path::Path;
+use tempfile::TempDir;
+
+fn write_config(temp_dir: &TempDir) -> std::path::PathBuf {
+    let root = temp_dir.path();
+    let config_content = format!(
+        r#"
+[core]
+workspace = "{main}"
+data_dir = "{data}"
+
+[llm]
+default_provider = "ollama"
+
+[tools]
+
+[plugins]
+
+[security]
+
+[[workspaces]]
+name = "alpha"
+path = "{alpha}"
+
+[[workspaces]]
+name = "beta"
+path = "{beta}"
+"#,
+        main = root.join("main").display(),
+        data = root.join("data").display(),
+        alpha = root.join("alpha").display(),
+        beta = root.join("beta").display(),
+    );
+
+    let config_path = root.join("config.toml");
+    std::fs::write(&config_path, config_content).unwrap();
+    config_path
+}
+
+fn load(config_path: &Path) -> Config {
+    Config::load_from_path(config_path).unwrap()
+}
+
+#[test]
+fn test_switching_workspace_persists_across_loads() {
+    let temp_dir = TempDir::new().unwrap();
+    let config_path = write_config(&temp_dir);
+
+    let mut config = load(&config_path);
+    assert_eq!(config.active_workspace_name(), "default");
+
+    config.use_workspace("alpha").unwrap();
+    let config = load(&config_path);
+    assert_eq!(config.active_workspace_name(), "alpha");
+    assert!(config.workspace().ends_with("alpha"));
+
+    let mut config = config;
+    config.use_workspace("beta").unwrap();
+    let config = load(&config_path);
+    assert_eq!(config.active_workspace_name(), "beta");
+    assert!(config.workspace().ends_with("beta"));
+}
+
+#[test]
+fn test_guard_tracks_active_workspace() {
+    let temp_dir = TempDir::new().unwrap();
+    let config_path = write_config(&temp_dir);
+
+    let mut config = load(&config_path);
+    config.use_workspace("alpha").unwrap();
+
+    let alpha_file = config.workspace().join("notes.txt");
+    std::fs::write(&alpha_file, "alpha").unwrap();
+    let main_file = config.core.workspace.join("main.txt");
+    std::fs::write(&main_file, "main").unwrap();
+
+    let guard = FileSystemGuard::new(config.workspace().to_path_buf());
+    assert!(guard.validate_path(&alpha_file).is_ok());
+    assert!(matches!(
+        guard.validate_path(&main_file),
+        Err(EngineError::PathOutsideWorkspace(_))
+    ));
+
+    // Back to default: the guard built from the new selection flips
+    config.use_workspace("default").unwrap();
+    let guard = FileSystemGuard::new(config.workspace().to_path_buf());
+    assert!(guard.validate_path(&main_file).is_ok());
+    assert!(matches!(
+        guard.validate_path(&alpha_file),
+        Err(EngineError::PathOutsideWorkspace(_))
+    ));
+}
+
+#[tokio::test]
+async fn test_command_cwd_follows_active_workspace() {
+    let temp_dir = TempDir::new().unwrap();
+    let config_path = write_config(&temp_dir);
+
+    let mut config = load(&config_path);
+    config.use_workspace("beta").unwrap();
+    std::fs::write(config.workspace().join("beta-marker.txt"), "").unwrap();
+
+    let terminal = TerminalTool::new(config.workspace().to_string_lossy().to_string());
+    let output = terminal.execute("ls").await.unwrap();
+    assert!(output.contains("beta-marker.txt"));
+}
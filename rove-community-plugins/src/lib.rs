@@ -6,6 +6,8 @@
 
 use rove_plugins::TrustTier;
 
+pub mod search;
+
 /// Community plugin metadata with consent tracking
 #[derive(Debug, Clone)]
 pub struct CommunityPlugin {
@@ -0,0 +1,206 @@
+//! Plugin search across the official and community registries
+//!
+//! `rove plugins search` lists matching plugins from both registries.
+//! Official entries come first; a community entry with the same id as an
+//! official one is dropped. Community results are marked as requiring
+//! consent before they can be installed.
+
+use anyhow::{anyhow, Result};
+use rove_plugins::registry::{self, RegistryEntry, RegistryManifest};
+use rove_plugins::TrustTier;
+use serde::Serialize;
+use std::collections::HashSet;
+use tracing::warn;
+
+/// Community plugin registry manifest
+pub const COMMUNITY_REGISTRY_URL: &str =
+    "https://raw.githubusercontent.com/OvrisHQ/rove/main/manifest/community-plugins.json";
+
+/// A plugin matching a search query
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SearchResult {
+    pub id: String,
+    pub name: String,
+    pub version: String,
+    pub author: String,
+    pub description: String,
+    pub trust: TrustTier,
+    pub requires_consent: bool,
+}
+
+impl SearchResult {
+    fn new(entry: &RegistryEntry, trust: TrustTier) -> Self {
+        Self {
+            id: entry.id.clone(),
+            name: entry.name.clone(),
+            version: entry.version.clone(),
+            author: entry.author.clone(),
+            description: entry.description.clone(),
+            trust,
+            requires_consent: trust.requires_consent(),
+        }
+    }
+}
+
+/// Merge the entries of both manifests that match `query`
+pub fn merge_results(
+    official: Option<&RegistryManifest>,
+    community: Option<&RegistryManifest>,
+    query: &str,
+) -> Vec<SearchResult> {
+    let mut results: Vec<SearchResult> = official
+        .map(|manifest| registry::search(manifest, query))
+        .unwrap_or_default()
+        .into_iter()
+        .map(|entry| SearchResult::new(entry, TrustTier::Official))
+        .collect();
+
+    let official_ids: HashSet<String> = results.iter().map(|r| r.id.clone()).collect();
+    if let Some(manifest) = community {
+        results.extend(
+            registry::search(manifest, query)
+                .into_iter()
+                .filter(|entry| !official_ids.contains(&entry.id))
+                .map(|entry| SearchResult::new(entry, TrustTier::Community)),
+        );
+    }
+    results
+}
+
+/// Query both registries for `query`
+///
+/// A registry that can't be reached is skipped with a warning.
+///
+/// # Errors
+///
+/// Fails only if neither registry could be fetched.
+pub async fn search(
+    official_url: &str,
+    community_url: &str,
+    query: &str,
+) -> Result<Vec<SearchResult>> {
+    let (official, community) = tokio::join!(
+        registry::fetch_manifest_from(official_url),
+        registry::fetch_manifest_from(community_url)
+    );
+
+    let official = official
+        .map_err(|e| warn!("Official plugin registry unavailable: {:#}", e))
+        .ok();
+    let community = community
+        .map_err(|e| warn!("Community plugin registry unavailable: {:#}", e))
+        .ok();
+    if official.is_none() && community.is_none() {
+        return Err(anyhow!("No plugin registry could be reached"));
+    }
+
+    Ok(merge_results(official.as_ref(), community.as_ref(), query))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn entry(id: &str, author: &str, description: &str) -> serde_json::Value {
+        json!({
+            "id": id,
+            "name": id,
+            "version": "1.2.0",
+            "author": author,
+            "description": description,
+            "hash": "",
+            "signature": "",
+            "download_url": format!("https://example.com/{}.wasm", id)
+        })
+    }
+
+    async fn mock_registry() -> MockServer {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/plugins.json"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "version": "1",
+                "plugins": [
+                    entry("git", "Rove Team", "Git operations"),
+                    entry("fs-editor", "Rove Team", "Edit files"),
+                ]
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/community-plugins.json"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "version": "1",
+                "plugins": [
+                    entry("git-lfs", "jdoe", "Git LFS helpers"),
+                    entry("git", "someone", "Shadows the official git plugin"),
+                    entry("weather", "jdoe", "Forecasts"),
+                ]
+            })))
+            .mount(&server)
+            .await;
+        server
+    }
+
+    #[tokio::test]
+    async fn test_search_merges_registries() {
+        let server = mock_registry().await;
+        let official = format!("{}/plugins.json", server.uri());
+        let community = format!("{}/community-plugins.json", server.uri());
+
+        let results = search(&official, &community, "git").await.unwrap();
+
+        assert_eq!(
+            results,
+            vec![
+                SearchResult {
+                    id: "git".to_string(),
+                    name: "git".to_string(),
+                    version: "1.2.0".to_string(),
+                    author: "Rove Team".to_string(),
+                    description: "Git operations".to_string(),
+                    trust: TrustTier::Official,
+                    requires_consent: false,
+                },
+                SearchResult {
+                    id: "git-lfs".to_string(),
+                    name: "git-lfs".to_string(),
+                    version: "1.2.0".to_string(),
+                    author: "jdoe".to_string(),
+                    description: "Git LFS helpers".to_string(),
+                    trust: TrustTier::Community,
+                    requires_consent: true,
+                },
+            ]
+        );
+
+        let ids: Vec<String> = search(&official, &community, "JDOE")
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|r| r.id)
+            .collect();
+        assert_eq!(ids, vec!["git-lfs", "weather"]);
+
+        assert!(search(&official, &community, "database")
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_search_with_one_registry_down() {
+        let server = mock_registry().await;
+        let official = format!("{}/plugins.json", server.uri());
+        let missing = format!("{}/missing.json", server.uri());
+
+        let results = search(&official, &missing, "edit").await.unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].id, "fs-editor");
+
+        assert!(search(&missing, &missing, "edit").await.is_err());
+    }
+}
@@ -9,7 +9,8 @@ pub mod installer;
 pub mod verifier;
 
 /// Plugin trust tier
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
 pub enum TrustTier {
     /// Signed by official plugin key — full permissions per manifest
     Official,
@@ -19,6 +20,21 @@ pub enum TrustTier {
     Unverified,
 }
 
+impl TrustTier {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TrustTier::Official => "official",
+            TrustTier::Community => "community",
+            TrustTier::Unverified => "unverified",
+        }
+    }
+
+    /// Whether installing a plugin of this tier needs the user's consent
+    pub fn requires_consent(&self) -> bool {
+        !matches!(self, TrustTier::Official)
+    }
+}
+
 /// Metadata for an installed plugin
 #[derive(Debug, Clone)]
 pub struct PluginInfo {
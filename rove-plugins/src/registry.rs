@@ -1,6 +1,7 @@
 //! Plugin registry — fetches manifests from CDN/GitHub
 
 use anyhow::{Context, Result};
+use sdk::manifest::PluginPermissions;
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 
@@ -10,12 +11,17 @@ pub struct RegistryEntry {
     pub id: String,
     pub name: String,
     pub version: String,
+    #[serde(default)]
+    pub author: String,
     pub description: String,
     pub hash: String,
     pub signature: String,
     pub download_url: String,
     #[serde(default)]
     pub min_engine_version: Option<String>,
+    /// Permissions this version requests (the sandbox default if omitted)
+    #[serde(default)]
+    pub permissions: PluginPermissions,
 }
 
 /// The full registry manifest
@@ -27,15 +33,33 @@ pub struct RegistryManifest {
     pub signature: String,
 }
 
-/// Fetch the plugin registry manifest
+impl RegistryEntry {
+    /// Whether `query` appears in the id, name, author or description
+    ///
+    /// Case-insensitive; an empty query matches every entry.
+    pub fn matches(&self, query: &str) -> bool {
+        let query = query.trim().to_lowercase();
+        [&self.id, &self.name, &self.author, &self.description]
+            .iter()
+            .any(|field| field.to_lowercase().contains(&query))
+    }
+}
+
+/// Official plugin registry manifest
+pub const OFFICIAL_REGISTRY_URL: &str =
+    "https://raw.githubusercontent.com/OvrisHQ/rove/main/manifest/plugins.json";
+
+/// Fetch the official plugin registry manifest
 pub async fn fetch_manifest() -> Result<RegistryManifest> {
+    fetch_manifest_from(OFFICIAL_REGISTRY_URL).await
+}
+
+/// Fetch a registry manifest from `url`
+pub async fn fetch_manifest_from(url: &str) -> Result<RegistryManifest> {
     let client = reqwest::Client::builder()
         .user_agent("rove-plugins/0.1.0")
         .build()?;
 
-    // Try GitHub raw first
-    let url = "https://raw.githubusercontent.com/OvrisHQ/rove/main/manifest/plugins.json";
-
     let response = client
         .get(url)
         .send()
@@ -83,7 +107,117 @@ pub async fn load_cached_manifest() -> Result<Option<RegistryManifest>> {
     Ok(Some(manifest))
 }
 
+/// Entries of the manifest matching `query` (see `RegistryEntry::matches`)
+pub fn search<'a>(manifest: &'a RegistryManifest, query: &str) -> Vec<&'a RegistryEntry> {
+    manifest
+        .plugins
+        .iter()
+        .filter(|p| p.matches(query))
+        .collect()
+}
+
+/// Registry version of `plugin_id` if it is newer than `installed`
+///
+/// Returns `None` when the plugin isn't in the registry or either version
+/// is not valid semver.
+pub fn available_update<'a>(
+    manifest: &'a RegistryManifest,
+    plugin_id: &str,
+    installed: &str,
+) -> Option<&'a str> {
+    let entry = manifest.plugins.iter().find(|p| p.id == plugin_id)?;
+    let latest = semver::Version::parse(&entry.version).ok()?;
+    let installed = semver::Version::parse(installed.trim_start_matches('v')).ok()?;
+    (latest > installed).then_some(entry.version.as_str())
+}
+
 /// Find a plugin entry by ID in the manifest
 pub fn find_plugin<'a>(manifest: &'a RegistryManifest, plugin_id: &str) -> Option<&'a RegistryEntry> {
     manifest.plugins.iter().find(|p| p.id == plugin_id)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(id: &str, author: &str, description: &str) -> RegistryEntry {
+        RegistryEntry {
+            id: id.to_string(),
+            name: id.to_string(),
+            version: "1.0.0".to_string(),
+            author: author.to_string(),
+            description: description.to_string(),
+            hash: String::new(),
+            signature: String::new(),
+            download_url: String::new(),
+            min_engine_version: None,
+            permissions: PluginPermissions::default(),
+        }
+    }
+
+    #[test]
+    fn test_search_matches_any_field() {
+        let manifest = RegistryManifest {
+            version: "1".to_string(),
+            plugins: vec![
+                entry("fs-editor", "Rove Team", "Edit files in the workspace"),
+                entry("git", "Rove Team", "Git operations"),
+                entry("screenshot", "Rove Team", "Capture the screen"),
+            ],
+            signature: String::new(),
+        };
+
+        let ids = |query| -> Vec<&str> {
+            search(&manifest, query)
+                .iter()
+                .map(|p| p.id.as_str())
+                .collect()
+        };
+        assert_eq!(ids("GIT"), vec!["git"]);
+        assert_eq!(ids("files"), vec!["fs-editor"]);
+        assert_eq!(ids("rove team").len(), 3);
+        assert_eq!(ids("").len(), 3);
+        assert!(ids("database").is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_available_update_from_registry() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        let mut git = entry("git", "Rove Team", "Git operations");
+        git.version = "1.2.0".to_string();
+        let manifest = RegistryManifest {
+            version: "1".to_string(),
+            plugins: vec![git, entry("fs-editor", "Rove Team", "Edit files")],
+            signature: String::new(),
+        };
+        Mock::given(method("GET"))
+            .and(path("/plugins.json"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(&manifest))
+            .mount(&server)
+            .await;
+
+        let fetched = fetch_manifest_from(&format!("{}/plugins.json", server.uri()))
+            .await
+            .unwrap();
+
+        assert_eq!(available_update(&fetched, "git", "1.0.0"), Some("1.2.0"));
+        assert_eq!(available_update(&fetched, "git", "v1.1.9"), Some("1.2.0"));
+        assert_eq!(available_update(&fetched, "git", "1.2.0"), None);
+        assert_eq!(available_update(&fetched, "git", "2.0.0"), None);
+        assert_eq!(available_update(&fetched, "fs-editor", "1.0.0"), None);
+        assert_eq!(available_update(&fetched, "terminal", "0.1.0"), None);
+        assert_eq!(available_update(&fetched, "git", "dev"), None);
+    }
+
+    #[test]
+    fn test_author_is_optional() {
+        let json = r#"{"version": "1", "plugins": [{"id": "git", "name": "Git",
+            "version": "1.0.0", "description": "Git operations", "hash": "",
+            "signature": "", "download_url": ""}]}"#;
+        let manifest: RegistryManifest = serde_json::from_str(json).unwrap();
+        assert_eq!(manifest.plugins[0].author, "");
+    }
+}
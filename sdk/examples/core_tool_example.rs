@@ -6,7 +6,7 @@
 use sdk::{
     AgentHandle, AgentHandleImpl, BusHandle, BusHandleImpl, ConfigHandle, ConfigHandleImpl,
     CoreContext, CoreTool, CryptoHandle, CryptoHandleImpl, DbHandle, DbHandleImpl, EngineError,
-    NetworkHandle, NetworkHandleImpl, ToolInput, ToolOutput,
+    NetworkHandle, NetworkHandleImpl, RateLimitHandle, RateLimitHandleImpl, ToolInput, ToolOutput,
 };
 use serde_json::json;
 use std::sync::Arc;
@@ -88,7 +88,7 @@ impl CoreTool for ExampleTool {
                     .map_err(|e| EngineError::Config(e.to_string()))?;
                 match ctx.config.get(&key) {
                     Some(value) => Ok(ToolOutput::json(json!({ "value": value }))),
-                    None => Ok(ToolOutput::error(&format!("Config key not found: {}", key))),
+                    None => Ok(ToolOutput::error(format!("Config key not found: {}", key))),
                 }
             }
 
@@ -122,7 +122,7 @@ impl CoreTool for ExampleTool {
                 Ok(ToolOutput::text("Event published"))
             }
 
-            _ => Ok(ToolOutput::error(&format!(
+            _ => Ok(ToolOutput::error(format!(
                 "Unknown method: {}",
                 input.method
             ))),
@@ -138,6 +138,18 @@ impl AgentHandleImpl for MockAgentHandle {
         Ok("task-123".to_string())
     }
 
+    fn submit_task_for_request(
+        &self,
+        task_input: String,
+        request_id: &str,
+    ) -> Result<String, EngineError> {
+        println!(
+            "Mock: Submitting task for request {}: {}",
+            request_id, task_input
+        );
+        Ok("task-123".to_string())
+    }
+
     fn get_task_status(&self, task_id: &str) -> Result<String, EngineError> {
         println!("Mock: Getting status for task: {}", task_id);
         Ok("completed".to_string())
@@ -220,6 +232,24 @@ impl BusHandleImpl for MockBusHandle {
     }
 }
 
+struct MockRateLimitHandle;
+impl RateLimitHandleImpl for MockRateLimitHandle {
+    fn check_limit(&self, source: &str, operation: &str) -> Result<(), EngineError> {
+        println!("Mock: Checking rate limit of {} for {}", operation, source);
+        Ok(())
+    }
+
+    fn record_operation(&self, source: &str, operation: &str) -> Result<(), EngineError> {
+        println!("Mock: Recording {} for {}", operation, source);
+        Ok(())
+    }
+
+    fn check_and_record(&self, source: &str, operation: &str) -> Result<(), EngineError> {
+        println!("Mock: Checking and recording {} for {}", operation, source);
+        Ok(())
+    }
+}
+
 fn main() {
     println!("=== CoreTool and CoreContext Example ===\n");
 
@@ -230,9 +260,10 @@ fn main() {
     let crypto = CryptoHandle::new(Arc::new(MockCryptoHandle));
     let network = NetworkHandle::new(Arc::new(MockNetworkHandle));
     let bus = BusHandle::new(Arc::new(MockBusHandle));
+    let rate_limit = RateLimitHandle::new(Arc::new(MockRateLimitHandle));
 
     // Create CoreContext
-    let ctx = CoreContext::new(agent, db, config, crypto, network, bus);
+    let ctx = CoreContext::new(agent, db, config, crypto, network, bus, rate_limit);
 
     // Create and initialize tool
     let mut tool = ExampleTool::new();
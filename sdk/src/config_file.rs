@@ -0,0 +1,232 @@
+//! Locked updates of `config.toml`
+//!
+//! The engine and core tools (e.g. the api-server saving its port) both
+//! read-modify-write the config file. `update_toml` serializes those writers
+//! with an exclusive advisory lock on a `<file>.lock` sidecar, and replaces
+//! the file atomically by renaming a temp file over it, so a concurrent reader
+//! never sees a half-written config and no writer's change is lost.
+//!
+//! The lock lives on a separate file because the rename replaces the config
+//! file's inode, which would silently drop a lock held on the file itself.
+
+use crate::errors::EngineError;
+use fs2::FileExt;
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// Path of the lock file guarding `path`
+pub fn lock_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".lock");
+    path.with_file_name(name)
+}
+
+/// Read `path` as TOML, apply `update`, and write the result back
+///
+/// The whole read-modify-write runs under the config lock. If `update` fails
+/// the file is left unchanged.
+///
+/// # Errors
+///
+/// Returns `EngineError::Config` if the file can't be locked, read, parsed or
+/// written, or the error returned by `update`.
+pub fn update_toml<F>(path: &Path, update: F) -> Result<(), EngineError>
+where
+    F: FnOnce(&mut toml::Value) -> Result<(), EngineError>,
+{
+    let _lock = ConfigLock::acquire(path)?;
+
+    let current = fs::read_to_string(path)
+        .map_err(|e| EngineError::Config(format!("Failed to read config file: {}", e)))?;
+    let mut value: toml::Value = toml::from_str(&current)
+        .map_err(|e| EngineError::Config(format!("Failed to parse config: {}", e)))?;
+
+    update(&mut value)?;
+
+    let updated = toml::to_string_pretty(&value)
+        .map_err(|e| EngineError::Config(format!("Failed to serialize config: {}", e)))?;
+    replace_file(path, updated.as_bytes())
+        .map_err(|e| EngineError::Config(format!("Failed to write config file: {}", e)))
+}
+
+/// Exclusive lock on a config file, released on drop
+struct ConfigLock {
+    file: File,
+}
+
+impl ConfigLock {
+    /// Block until the lock for `path` is held
+    fn acquire(path: &Path) -> Result<Self, EngineError> {
+        let lock_path = lock_path(path);
+        let file = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(&lock_path)
+            .map_err(|e| {
+                EngineError::Config(format!(
+                    "Failed to open config lock {}: {}",
+                    lock_path.display(),
+                    e
+                ))
+            })?;
+        file.lock_exclusive()
+            .map_err(|e| EngineError::Config(format!("Failed to lock config file: {}", e)))?;
+        Ok(Self { file })
+    }
+}
+
+impl Drop for ConfigLock {
+    fn drop(&mut self) {
+        let _ = FileExt::unlock(&self.file);
+    }
+}
+
+/// Write `contents` to a temp file next to `path` and rename it over `path`
+///
+/// The replacement keeps the permissions of the file it replaces. A new file
+/// is created readable by its owner only, since the config can hold secrets.
+fn replace_file(path: &Path, contents: &[u8]) -> std::io::Result<()> {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".tmp");
+    let tmp_path = path.with_file_name(name);
+
+    let result = (|| {
+        let mut tmp = create_private(&tmp_path)?;
+        if let Ok(metadata) = fs::metadata(path) {
+            tmp.set_permissions(metadata.permissions())?;
+        }
+        tmp.write_all(contents)?;
+        tmp.sync_all()?;
+        fs::rename(&tmp_path, path)
+    })();
+    if result.is_err() {
+        let _ = fs::remove_file(&tmp_path);
+    }
+    result
+}
+
+/// Create (or truncate) `path` with owner-only permissions
+fn create_private(path: &Path) -> std::io::Result<File> {
+    let mut options = OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    options.open(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Barrier};
+
+    fn set(path: &Path, section: &str, key: &str, value: i64) -> Result<(), EngineError> {
+        update_toml(path, |config| {
+            let table = config
+                .as_table_mut()
+                .ok_or_else(|| EngineError::Config("config is not a table".to_string()))?
+                .entry(section.to_string())
+                .or_insert(toml::Value::Table(toml::map::Map::new()));
+            if let Some(table) = table.as_table_mut() {
+                table.insert(key.to_string(), toml::Value::Integer(value));
+            }
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_lock_path() {
+        assert_eq!(
+            lock_path(Path::new("/home/u/.rove/config.toml")),
+            PathBuf::from("/home/u/.rove/config.toml.lock")
+        );
+    }
+
+    #[test]
+    fn test_update_preserves_other_settings() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        fs::write(&path, "[core]\nlog_level = \"info\"\n").unwrap();
+
+        set(&path, "api_server", "port", 47800).unwrap();
+
+        let config: toml::Value = toml::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(config["core"]["log_level"].as_str(), Some("info"));
+        assert_eq!(config["api_server"]["port"].as_integer(), Some(47800));
+        assert!(!dir.path().join("config.toml.tmp").exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_update_keeps_file_permissions() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        fs::write(&path, "[core]\nlog_level = \"info\"\n").unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o600)).unwrap();
+
+        set(&path, "api_server", "port", 47800).unwrap();
+
+        let mode = fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+
+    #[test]
+    fn test_failed_update_leaves_file_unchanged() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        let original = "[core]\nlog_level = \"info\"\n";
+        fs::write(&path, original).unwrap();
+
+        let result = update_toml(&path, |config| {
+            config
+                .as_table_mut()
+                .map(|table| table.insert("junk".to_string(), toml::Value::Boolean(true)));
+            Err(EngineError::Config("rejected".to_string()))
+        });
+
+        assert!(result.is_err());
+        assert_eq!(fs::read_to_string(&path).unwrap(), original);
+    }
+
+    #[test]
+    fn test_concurrent_writers_keep_both_changes() {
+        const WRITES: i64 = 50;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = Arc::new(dir.path().join("config.toml"));
+        fs::write(path.as_ref(), "[core]\nlog_level = \"info\"\n").unwrap();
+
+        let barrier = Arc::new(Barrier::new(2));
+        let writers: Vec<_> = [("api_server", "port"), ("security", "confirm_tier1_delay")]
+            .into_iter()
+            .map(|(section, key)| {
+                let path = Arc::clone(&path);
+                let barrier = Arc::clone(&barrier);
+                std::thread::spawn(move || {
+                    barrier.wait();
+                    for i in 1..=WRITES {
+                        set(&path, section, key, i).unwrap();
+                    }
+                })
+            })
+            .collect();
+        for writer in writers {
+            writer.join().unwrap();
+        }
+
+        let config: toml::Value =
+            toml::from_str(&fs::read_to_string(path.as_ref()).unwrap()).unwrap();
+        assert_eq!(config["core"]["log_level"].as_str(), Some("info"));
+        assert_eq!(config["api_server"]["port"].as_integer(), Some(WRITES));
+        assert_eq!(
+            config["security"]["confirm_tier1_delay"].as_integer(),
+            Some(WRITES)
+        );
+    }
+}
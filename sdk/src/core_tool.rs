@@ -2,9 +2,15 @@
 //!
 //! This module defines the CoreTool trait that all native core tools must implement,
 //! and the CoreContext that provides limited, controlled access to engine functionality.
+//!
+//! `RpcRouter` gives core tools a JSON-RPC-style `handle`: each method has typed
+//! params and result, and every failure is reported with the same `RpcError` shape.
 
 use crate::errors::EngineError;
-use crate::types::{ToolInput, ToolOutput};
+use crate::types::{ContentType, ToolInput, ToolOutput};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
 use std::sync::Arc;
 
 /// Trait that all core tools must implement
@@ -25,6 +31,168 @@ pub trait CoreTool: Send + Sync {
     fn handle(&self, input: ToolInput) -> Result<ToolOutput, EngineError>;
 }
 
+/// Error returned by an RPC method
+///
+/// Codes follow JSON-RPC 2.0. A failed call is returned as a `ToolOutput`
+/// with `success: false`, this error serialized in `data`, and its message
+/// in `error`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, thiserror::Error)]
+#[error("{message}")]
+pub struct RpcError {
+    pub code: i64,
+    pub message: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<serde_json::Value>,
+}
+
+impl RpcError {
+    /// The method does not exist
+    pub const METHOD_NOT_FOUND: i64 = -32601;
+    /// The params could not be parsed into the method's params type
+    pub const INVALID_PARAMS: i64 = -32602;
+    /// The method failed
+    pub const INTERNAL_ERROR: i64 = -32603;
+
+    /// Create an error with a custom code
+    pub fn new(code: i64, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+            data: None,
+        }
+    }
+
+    pub fn method_not_found(method: &str) -> Self {
+        Self::new(
+            Self::METHOD_NOT_FOUND,
+            format!("Unknown method: {}", method),
+        )
+    }
+
+    pub fn invalid_params(message: impl Into<String>) -> Self {
+        Self::new(Self::INVALID_PARAMS, message)
+    }
+
+    pub fn internal(message: impl Into<String>) -> Self {
+        Self::new(Self::INTERNAL_ERROR, message)
+    }
+
+    /// Attach structured details
+    pub fn with_data(mut self, data: serde_json::Value) -> Self {
+        self.data = Some(data);
+        self
+    }
+}
+
+impl From<RpcError> for ToolOutput {
+    fn from(error: RpcError) -> Self {
+        Self {
+            success: false,
+            error: Some(error.message.clone()),
+            data: serde_json::to_value(&error).unwrap_or(serde_json::Value::Null),
+            content_type: ContentType::Json,
+        }
+    }
+}
+
+/// Params of a method that takes none
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+pub struct NoParams {}
+
+type RpcMethod<T> =
+    Box<dyn Fn(&T, serde_json::Value) -> Result<serde_json::Value, RpcError> + Send + Sync>;
+
+/// Method table for a core tool's `handle`
+///
+/// ```
+/// use sdk::{NoParams, RpcError, RpcRouter, ToolInput};
+/// use serde::Deserialize;
+///
+/// struct Counter {
+///     start: i64,
+/// }
+///
+/// #[derive(Deserialize)]
+/// struct AddParams {
+///     amount: i64,
+/// }
+///
+/// let router = RpcRouter::new()
+///     .method("start", |tool: &Counter, _: NoParams| Ok(tool.start))
+///     .method("add", |tool: &Counter, p: AddParams| {
+///         tool.start
+///             .checked_add(p.amount)
+///             .ok_or_else(|| RpcError::internal("overflow"))
+///     });
+///
+/// let tool = Counter { start: 40 };
+/// let input = ToolInput::new("add").with_param("amount", serde_json::json!(2));
+/// assert_eq!(router.dispatch(&tool, input).data, serde_json::json!(42));
+/// ```
+pub struct RpcRouter<T> {
+    methods: HashMap<&'static str, RpcMethod<T>>,
+}
+
+impl<T> RpcRouter<T> {
+    pub fn new() -> Self {
+        Self {
+            methods: HashMap::new(),
+        }
+    }
+
+    /// Register `name`, replacing any method with the same name
+    ///
+    /// The handler's params are deserialized from `ToolInput::params`; its
+    /// result becomes `ToolOutput::data`.
+    pub fn method<P, R, F>(mut self, name: &'static str, handler: F) -> Self
+    where
+        P: DeserializeOwned,
+        R: Serialize,
+        F: Fn(&T, P) -> Result<R, RpcError> + Send + Sync + 'static,
+    {
+        let method: RpcMethod<T> = Box::new(move |tool: &T, params: serde_json::Value| {
+            let params = serde_json::from_value(params)
+                .map_err(|e| RpcError::invalid_params(format!("Invalid params: {}", e)))?;
+            let result = handler(tool, params)?;
+            serde_json::to_value(result)
+                .map_err(|e| RpcError::internal(format!("Failed to serialize result: {}", e)))
+        });
+        self.methods.insert(name, method);
+        self
+    }
+
+    /// Names of all registered methods, sorted
+    pub fn methods(&self) -> Vec<&'static str> {
+        let mut names: Vec<_> = self.methods.keys().copied().collect();
+        names.sort_unstable();
+        names
+    }
+
+    /// Call the method named by `input.method`
+    pub fn call(&self, tool: &T, input: ToolInput) -> Result<serde_json::Value, RpcError> {
+        let method = self
+            .methods
+            .get(input.method.as_str())
+            .ok_or_else(|| RpcError::method_not_found(&input.method))?;
+        let params = serde_json::Value::Object(input.params.into_iter().collect());
+        method(tool, params)
+    }
+
+    /// Call the method named by `input.method`, converting the outcome to a `ToolOutput`
+    pub fn dispatch(&self, tool: &T, input: ToolInput) -> ToolOutput {
+        match self.call(tool, input) {
+            Ok(data) => ToolOutput::json(data),
+            Err(error) => error.into(),
+        }
+    }
+}
+
+impl<T> Default for RpcRouter<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 /// Context provided to core tools for engine interaction.
 ///
 /// CoreContext is the sole API surface for core tools to interact with the engine.
@@ -51,6 +219,9 @@ pub struct CoreContext {
 
     /// Handle for message bus subscriptions and publishing
     pub bus: BusHandle,
+
+    /// Handle for rate limiting the operations of remote clients
+    pub rate_limit: RateLimitHandle,
 }
 
 impl CoreContext {
@@ -62,6 +233,7 @@ impl CoreContext {
         crypto: CryptoHandle,
         network: NetworkHandle,
         bus: BusHandle,
+        rate_limit: RateLimitHandle,
     ) -> Self {
         Self {
             agent,
@@ -70,6 +242,7 @@ impl CoreContext {
             crypto,
             network,
             bus,
+            rate_limit,
         }
     }
 }
@@ -93,10 +266,54 @@ impl AgentHandle {
         self.inner.submit_task(task_input)
     }
 
+    /// Submit a task on behalf of the request identified by `request_id`
+    ///
+    /// The engine tags the task's log lines and database row with the id.
+    pub fn submit_task_for_request(
+        &self,
+        task_input: String,
+        request_id: &str,
+    ) -> Result<String, EngineError> {
+        self.inner.submit_task_for_request(task_input, request_id)
+    }
+
     /// Get the status of a task by ID
     pub fn get_task_status(&self, task_id: &str) -> Result<String, EngineError> {
         self.inner.get_task_status(task_id)
     }
+
+    /// Readiness of the engine's components
+    pub fn health(&self) -> EngineHealth {
+        self.inner.health()
+    }
+}
+
+/// Readiness of the engine's components, as reported by `AgentHandle::health`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EngineHealth {
+    /// Availability of each configured LLM provider, by name
+    pub providers: BTreeMap<String, bool>,
+    /// Number of loaded plugins
+    pub plugins_loaded: usize,
+    /// Number of loaded core tools
+    pub tools_loaded: usize,
+    /// Whether the daemon is shutting down
+    pub shutting_down: bool,
+}
+
+/// Longest client-supplied request id that is accepted
+pub const MAX_REQUEST_ID_LENGTH: usize = 64;
+
+/// Whether a client-supplied request id may be used as-is
+///
+/// Request ids end up in log lines, so only short ids made of
+/// alphanumerics, `-` and `_` are accepted.
+pub fn is_valid_request_id(id: &str) -> bool {
+    !id.is_empty()
+        && id.len() <= MAX_REQUEST_ID_LENGTH
+        && id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
 }
 
 /// Trait for agent handle implementation (to be implemented by engine)
@@ -104,13 +321,28 @@ pub trait AgentHandleImpl: Send + Sync {
     /// Submit a task and return task ID
     fn submit_task(&self, task_input: String) -> Result<String, EngineError>;
 
+    /// Submit a task tagged with the id of the request that caused it
+    fn submit_task_for_request(
+        &self,
+        task_input: String,
+        request_id: &str,
+    ) -> Result<String, EngineError>;
+
     /// Get task status by ID
     fn get_task_status(&self, task_id: &str) -> Result<String, EngineError>;
+
+    /// Report component readiness
+    ///
+    /// Defaults to an empty report for implementations that don't track it.
+    fn health(&self) -> EngineHealth {
+        EngineHealth::default()
+    }
 }
 
 /// Handle for database access
 ///
-/// Provides read-only access to the database for querying task history and other data.
+/// Provides read access to the database for querying task history and other
+/// data, and writes through `execute` where the engine allows them.
 #[derive(Clone)]
 pub struct DbHandle {
     inner: Arc<dyn DbHandleImpl>,
@@ -132,6 +364,11 @@ impl DbHandle {
     ) -> Result<Vec<serde_json::Value>, EngineError> {
         self.inner.query(sql, params)
     }
+
+    /// Execute a write statement, returning the number of affected rows
+    pub fn execute(&self, sql: &str, params: Vec<serde_json::Value>) -> Result<u64, EngineError> {
+        self.inner.execute(sql, params)
+    }
 }
 
 /// Trait for database handle implementation (to be implemented by engine)
@@ -142,6 +379,17 @@ pub trait DbHandleImpl: Send + Sync {
         sql: &str,
         params: Vec<serde_json::Value>,
     ) -> Result<Vec<serde_json::Value>, EngineError>;
+
+    /// Execute a write statement
+    ///
+    /// Defaults to an error for read-only implementations.
+    fn execute(&self, sql: &str, params: Vec<serde_json::Value>) -> Result<u64, EngineError> {
+        let _ = params;
+        Err(EngineError::Database(format!(
+            "Database handle is read-only, cannot execute: {}",
+            sql
+        )))
+    }
 }
 
 /// Handle for configuration access
@@ -304,3 +552,137 @@ pub trait BusHandleImpl: Send + Sync {
     /// Publish event
     fn publish(&self, event_type: &str, payload: serde_json::Value) -> Result<(), EngineError>;
 }
+
+/// Handle for rate limiting
+///
+/// Core tools act on behalf of remote clients, so the engine assesses each
+/// operation's risk tier as a remote operation and applies that tier's
+/// limits to the client identified by `source`.
+#[derive(Clone)]
+pub struct RateLimitHandle {
+    inner: Arc<dyn RateLimitHandleImpl>,
+}
+
+impl RateLimitHandle {
+    /// Create a new RateLimitHandle with the given implementation
+    pub fn new(inner: Arc<dyn RateLimitHandleImpl>) -> Self {
+        Self { inner }
+    }
+
+    /// Check whether `source` may perform `operation` (e.g. `execute_task`)
+    ///
+    /// Returns `EngineError::RateLimitExceeded` or
+    /// `EngineError::CircuitBreakerTripped` if it may not.
+    pub fn check_limit(&self, source: &str, operation: &str) -> Result<(), EngineError> {
+        self.inner.check_limit(source, operation)
+    }
+
+    /// Record that `source` performed `operation`
+    pub fn record_operation(&self, source: &str, operation: &str) -> Result<(), EngineError> {
+        self.inner.record_operation(source, operation)
+    }
+
+    /// Check the limits of `operation` and record it if they allow it
+    ///
+    /// Unlike `check_limit` followed by `record_operation`, concurrent
+    /// callers can't both pass the check for the last slot in a window.
+    pub fn check_and_record(&self, source: &str, operation: &str) -> Result<(), EngineError> {
+        self.inner.check_and_record(source, operation)
+    }
+}
+
+/// Trait for rate limit handle implementation (to be implemented by engine)
+pub trait RateLimitHandleImpl: Send + Sync {
+    /// Check the limits of an operation
+    fn check_limit(&self, source: &str, operation: &str) -> Result<(), EngineError>;
+
+    /// Record an operation
+    fn record_operation(&self, source: &str, operation: &str) -> Result<(), EngineError>;
+
+    /// Atomically check the limits of an operation and record it
+    fn check_and_record(&self, source: &str, operation: &str) -> Result<(), EngineError>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    struct Greeter {
+        greeting: String,
+    }
+
+    #[derive(Deserialize)]
+    struct GreetParams {
+        name: String,
+        #[serde(default)]
+        shout: bool,
+    }
+
+    fn router() -> RpcRouter<Greeter> {
+        RpcRouter::new()
+            .method("greeting", |tool: &Greeter, _: NoParams| {
+                Ok(tool.greeting.clone())
+            })
+            .method("greet", |tool: &Greeter, p: GreetParams| {
+                if p.name.is_empty() {
+                    return Err(RpcError::invalid_params("name must not be empty"));
+                }
+                let text = format!("{}, {}!", tool.greeting, p.name);
+                Ok(json!({ "text": if p.shout { text.to_uppercase() } else { text } }))
+            })
+    }
+
+    fn greeter() -> Greeter {
+        Greeter {
+            greeting: "Hello".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_dispatches_to_method_with_typed_params() {
+        let router = router();
+        assert_eq!(router.methods(), vec!["greet", "greeting"]);
+
+        let output = router.dispatch(&greeter(), ToolInput::new("greeting"));
+        assert!(output.success);
+        assert_eq!(output.data, json!("Hello"));
+
+        let input = ToolInput::new("greet")
+            .with_param("name", json!("Ada"))
+            .with_param("shout", json!(true));
+        let output = router.dispatch(&greeter(), input);
+        assert!(output.success);
+        assert_eq!(output.data, json!({ "text": "HELLO, ADA!" }));
+    }
+
+    #[test]
+    fn test_unknown_method_error() {
+        let output = router().dispatch(&greeter(), ToolInput::new("wave"));
+        assert!(!output.success);
+        assert_eq!(output.error.as_deref(), Some("Unknown method: wave"));
+        assert_eq!(
+            output.data,
+            json!({ "code": RpcError::METHOD_NOT_FOUND, "message": "Unknown method: wave" })
+        );
+    }
+
+    #[test]
+    fn test_invalid_params_and_method_errors() {
+        let router = router();
+
+        let missing = router
+            .call(&greeter(), ToolInput::new("greet"))
+            .unwrap_err();
+        assert_eq!(missing.code, RpcError::INVALID_PARAMS);
+        assert!(missing.message.contains("name"));
+
+        let wrong_type = ToolInput::new("greet").with_param("name", json!(7));
+        let error = router.call(&greeter(), wrong_type).unwrap_err();
+        assert_eq!(error.code, RpcError::INVALID_PARAMS);
+
+        let empty = ToolInput::new("greet").with_param("name", json!(""));
+        let error = router.call(&greeter(), empty).unwrap_err();
+        assert_eq!(error, RpcError::invalid_params("name must not be empty"));
+    }
+}
@@ -96,6 +96,13 @@ pub enum EngineError {
     #[error("Path canonicalization failed for {0:?}: {1}")]
     PathCanonicalization(std::path::PathBuf, String),
 
+    #[error("File too large: {path:?} is {size} bytes, read limit is {limit} bytes")]
+    FileTooLarge {
+        path: std::path::PathBuf,
+        size: u64,
+        limit: u64,
+    },
+
     // Daemon errors
     #[error("Daemon already running")]
     DaemonAlreadyRunning,
@@ -104,6 +111,9 @@ pub enum EngineError {
     #[error("All LLM providers exhausted")]
     AllProvidersExhausted,
 
+    #[error("LLM provider '{provider}' is not configured: {guidance}")]
+    ProviderNotConfigured { provider: String, guidance: String },
+
     // Agent loop errors
     #[error("Max iterations exceeded")]
     MaxIterationsExceeded,
@@ -111,9 +121,15 @@ pub enum EngineError {
     #[error("LLM call timed out")]
     LLMTimeout,
 
+    #[error("Task timed out after {secs}s")]
+    TaskTimedOut { secs: u64 },
+
     #[error("Result size exceeded: {size} bytes > {limit} bytes")]
     ResultSizeExceeded { size: usize, limit: usize },
 
+    #[error("Maximum call depth of {limit} exceeded")]
+    CallDepthExceeded { limit: usize },
+
     // Tool errors
     #[error("Tool not found: {0}")]
     ToolNotFound(String),
@@ -152,6 +168,11 @@ pub enum EngineError {
     #[error("Dangerous pipe pattern detected")]
     DangerousPipeDetected,
 
+    /// A plugin, tool or host function that configuration turns off,
+    /// e.g. `FeatureDisabled("Plugin 'git'".into())`
+    #[error("{0} is disabled by configuration")]
+    FeatureDisabled(String),
+
     // Rate limiting errors
     #[error("Rate limit exceeded for {src} (Tier {tier}): {count}/{limit} operations in {window}")]
     RateLimitExceeded {
@@ -173,6 +194,10 @@ pub enum EngineError {
     #[error("Network error: {0}")]
     Network(String),
 
+    /// A request to a host not on `security.network.allowed_hosts`
+    #[error("Network egress to '{0}' is not allowed")]
+    EgressDenied(String),
+
     // Library loading errors
     #[error("Library load failed: {0}")]
     LibraryLoadFailed(String),
@@ -204,6 +229,9 @@ impl RoveErrorExt for EngineError {
             // LLM provider errors
             Self::LLMProvider(_) => "LLM provider unavailable. Check your API keys and network",
             Self::AllProvidersExhausted => "No LLM providers available. Check configuration",
+            Self::ProviderNotConfigured { .. } => {
+                "Run 'rove setup' to configure the provider, or change llm.provider_priority or llm.default_provider"
+            }
 
             // Plugin errors
             Self::Plugin(_) => "Plugin execution failed. Check plugin logs",
@@ -214,6 +242,9 @@ impl RoveErrorExt for EngineError {
             Self::PathDenied(_) => "Access to this path is not allowed",
             Self::PathOutsideWorkspace(_) => "Operation must be within workspace",
             Self::PathCanonicalization(_, _) => "Invalid path specified",
+            Self::FileTooLarge { .. } => {
+                "Read a smaller part of the file, or raise the limit for one run with 'rove run --max-file-size'"
+            }
 
             // Daemon errors
             Self::DaemonAlreadyRunning => "Stop the existing daemon first with 'rove stop'",
@@ -221,7 +252,13 @@ impl RoveErrorExt for EngineError {
             // Agent loop errors
             Self::MaxIterationsExceeded => "Task too complex. Try breaking it into smaller steps",
             Self::LLMTimeout => "LLM provider took too long to respond. Try again",
+            Self::TaskTimedOut { .. } => {
+                "Task hit its deadline. Raise it with 'rove run --timeout' or conductor.task_timeout_secs"
+            }
             Self::ResultSizeExceeded { .. } => "Result too large. Try a more specific query",
+            Self::CallDepthExceeded { .. } => {
+                "Tools or plugins called each other too deeply. Raise conductor.max_call_depth if this is expected"
+            }
 
             // Tool errors
             Self::ToolNotFound(_) => "The requested tool is not available",
@@ -238,6 +275,9 @@ impl RoveErrorExt for EngineError {
             Self::ShellInjectionAttempt => "Command rejected for security reasons",
             Self::ShellMetacharactersDetected(_) => "Command contains unsafe characters",
             Self::DangerousPipeDetected => "Command contains dangerous patterns",
+            Self::FeatureDisabled(_) => {
+                "Enable it in config.toml ([plugins] or security.disabled_host_functions)"
+            }
 
             // Rate limiting errors
             Self::RateLimitExceeded { .. } => {
@@ -250,6 +290,9 @@ impl RoveErrorExt for EngineError {
 
             // Network errors
             Self::Network(_) => "Network operation failed. Check your connection",
+            Self::EgressDenied(_) => {
+                "Add the host to security.network.allowed_hosts if it should be reachable"
+            }
 
             // Library loading errors
             Self::LibraryLoadFailed(_) => "Failed to load system component",
@@ -6,6 +6,9 @@
 /// Core tool trait and types
 pub mod core_tool;
 
+/// Locked read-modify-write of config.toml
+pub mod config_file;
+
 /// Error types and handling
 pub mod errors;
 
@@ -19,11 +22,13 @@ pub mod manifest;
 pub mod helpers;
 
 // Re-export commonly used types
+pub use core_tool::{is_valid_request_id, MAX_REQUEST_ID_LENGTH};
 pub use core_tool::{
     AgentHandle, AgentHandleImpl, BusHandle, BusHandleImpl, ConfigHandle, ConfigHandleImpl,
-    CoreContext, CoreTool, CryptoHandle, CryptoHandleImpl, DbHandle, DbHandleImpl, NetworkHandle,
-    NetworkHandleImpl,
+    CoreContext, CoreTool, CryptoHandle, CryptoHandleImpl, DbHandle, DbHandleImpl, EngineHealth,
+    NetworkHandle, NetworkHandleImpl, NoParams, RateLimitHandle, RateLimitHandleImpl, RpcError,
+    RpcRouter,
 };
 pub use errors::{EngineError, RoveErrorExt};
 pub use manifest::{CoreToolEntry, Manifest, PluginEntry, PluginPermissions};
-pub use types::{ToolError, ToolInput, ToolOutput};
+pub use types::{ContentType, ToolError, ToolInput, ToolOutput};
@@ -1,6 +1,7 @@
 //! Manifest types for plugin and core tool metadata
 
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 /// Main manifest structure
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -67,37 +68,81 @@ pub struct PluginEntry {
     pub path: String,
     pub hash: String,
     pub permissions: PluginPermissions,
+    /// Output schemas keyed by function name (a small JSON Schema subset).
+    /// Functions without a schema are not validated.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub output_schemas: HashMap<String, serde_json::Value>,
+    /// Host ABI version the plugin was built against. Entries without it
+    /// are treated as targeting ABI 1.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub host_abi: Option<u32>,
 }
 
 impl PluginEntry {
     /// Check if a path is allowed by this plugin's permissions
+    pub fn is_path_allowed(&self, path: &str) -> bool {
+        self.permissions.is_path_allowed(path)
+    }
+
+    /// Check if a command is allowed by this plugin's permissions
+    pub fn is_command_allowed(&self, command: &str) -> bool {
+        self.permissions.is_command_allowed(command)
+    }
+}
+
+/// Plugin permissions
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PluginPermissions {
+    /// Paths the plugin is allowed to access (e.g., ["workspace", "/tmp"])
+    pub allowed_paths: Vec<String>,
+    /// Paths the plugin is explicitly denied from accessing (e.g., [".ssh", ".env"])
+    pub denied_paths: Vec<String>,
+    /// Maximum file size the plugin can read/write (in bytes)
+    pub max_file_size: Option<u64>,
+    /// Whether the plugin can execute commands
+    pub can_execute: bool,
+    /// Commands the plugin is allowed to execute (e.g., ["git", "ls"])
+    pub allowed_commands: Option<Vec<String>>,
+    /// Command flags that are denied (e.g., ["--force", "-rf"])
+    pub denied_flags: Option<Vec<String>>,
+    /// Maximum execution time for commands (in seconds)
+    pub max_execution_time: Option<u64>,
+    /// Whether the plugin may make network requests
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub network: bool,
+}
+
+impl PluginPermissions {
+    /// Check if a path is allowed by these permissions
+    ///
+    /// `"workspace"` in `allowed_paths` allows every path; any other entry
+    /// allows the paths starting with it.
     pub fn is_path_allowed(&self, path: &str) -> bool {
         // Check denied paths first
-        for denied in &self.permissions.denied_paths {
+        for denied in &self.denied_paths {
             if path.contains(denied) {
                 return false;
             }
         }
 
         // Check allowed paths
-        if self.permissions.allowed_paths.is_empty() {
+        if self.allowed_paths.is_empty() {
             return true; // No restrictions
         }
 
-        self.permissions
-            .allowed_paths
+        self.allowed_paths
             .iter()
             .any(|allowed| path.starts_with(allowed) || allowed == "workspace")
     }
 
-    /// Check if a command is allowed by this plugin's permissions
+    /// Check if a command line is allowed by these permissions
     pub fn is_command_allowed(&self, command: &str) -> bool {
-        if !self.permissions.can_execute {
+        if !self.can_execute {
             return false;
         }
 
         // Check denied flags
-        if let Some(denied_flags) = &self.permissions.denied_flags {
+        if let Some(denied_flags) = &self.denied_flags {
             for flag in denied_flags {
                 if command.contains(flag) {
                     return false;
@@ -106,7 +151,7 @@ impl PluginEntry {
         }
 
         // Check allowed commands
-        if let Some(allowed_commands) = &self.permissions.allowed_commands {
+        if let Some(allowed_commands) = &self.allowed_commands {
             if allowed_commands.is_empty() {
                 return true; // No restrictions
             }
@@ -117,25 +162,75 @@ impl PluginEntry {
 
         true // No command restrictions
     }
+
+    /// Describe every grant `updated` has that `self` does not
+    ///
+    /// Used when a plugin is updated: an empty result means the new version
+    /// asks for nothing more, anything else needs the user's consent again.
+    pub fn expansions(&self, updated: &PluginPermissions) -> Vec<String> {
+        let mut added = Vec::new();
+
+        if updated.network && !self.network {
+            added.push("network access".to_string());
+        }
+        if updated.can_execute && !self.can_execute {
+            added.push("command execution".to_string());
+        }
+        match (&self.allowed_commands, &updated.allowed_commands) {
+            (Some(old), Some(new)) if !old.is_empty() && !new.is_empty() => {
+                for command in new.iter().filter(|c| !old.contains(c)) {
+                    added.push(format!("command '{}'", command));
+                }
+            }
+            (Some(old), _) if !old.is_empty() => added.push("any command".to_string()),
+            _ => {}
+        }
+        if !self.allowed_paths.is_empty() {
+            if updated.allowed_paths.is_empty() {
+                added.push("any path".to_string());
+            }
+            for path in updated
+                .allowed_paths
+                .iter()
+                .filter(|p| !self.allowed_paths.contains(p))
+            {
+                added.push(format!("path '{}'", path));
+            }
+        }
+        for path in self
+            .denied_paths
+            .iter()
+            .filter(|p| !updated.denied_paths.contains(p))
+        {
+            added.push(format!("no longer denied path '{}'", path));
+        }
+        let updated_flags = updated.denied_flags.as_deref().unwrap_or_default();
+        for flag in self
+            .denied_flags
+            .iter()
+            .flatten()
+            .filter(|f| !updated_flags.contains(f))
+        {
+            added.push(format!("no longer denied flag '{}'", flag));
+        }
+        if let Some(limit) = raised_limit(self.max_file_size, updated.max_file_size) {
+            added.push(format!("file size up to {}", limit));
+        }
+        if let Some(limit) = raised_limit(self.max_execution_time, updated.max_execution_time) {
+            added.push(format!("execution time up to {}", limit));
+        }
+
+        added
+    }
 }
 
-/// Plugin permissions
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct PluginPermissions {
-    /// Paths the plugin is allowed to access (e.g., ["workspace", "/tmp"])
-    pub allowed_paths: Vec<String>,
-    /// Paths the plugin is explicitly denied from accessing (e.g., [".ssh", ".env"])
-    pub denied_paths: Vec<String>,
-    /// Maximum file size the plugin can read/write (in bytes)
-    pub max_file_size: Option<u64>,
-    /// Whether the plugin can execute commands
-    pub can_execute: bool,
-    /// Commands the plugin is allowed to execute (e.g., ["git", "ls"])
-    pub allowed_commands: Option<Vec<String>>,
-    /// Command flags that are denied (e.g., ["--force", "-rf"])
-    pub denied_flags: Option<Vec<String>>,
-    /// Maximum execution time for commands (in seconds)
-    pub max_execution_time: Option<u64>,
+/// The new value of a limit, if it is looser than the old one
+fn raised_limit(old: Option<u64>, new: Option<u64>) -> Option<String> {
+    match (old, new) {
+        (Some(_), None) => Some("unlimited".to_string()),
+        (Some(old), Some(new)) if new > old => Some(new.to_string()),
+        _ => None,
+    }
 }
 
 impl Default for PluginPermissions {
@@ -159,6 +254,7 @@ impl Default for PluginPermissions {
                 "--hard".to_string(),
             ]),
             max_execution_time: Some(30), // 30 seconds default
+            network: false,
         }
     }
 }
@@ -221,6 +317,8 @@ mod tests {
                 version: "0.1.0".to_string(),
                 path: "plugins/fs-editor.wasm".to_string(),
                 hash: "sha256:def456".to_string(),
+                output_schemas: HashMap::new(),
+                host_abi: None,
                 permissions: PluginPermissions::default(),
             }],
         };
@@ -236,6 +334,8 @@ mod tests {
             version: "0.1.0".to_string(),
             path: "test.wasm".to_string(),
             hash: "sha256:test".to_string(),
+            output_schemas: HashMap::new(),
+            host_abi: None,
             permissions: PluginPermissions {
                 allowed_paths: vec!["workspace".to_string(), "/tmp".to_string()],
                 denied_paths: vec![".ssh".to_string(), ".env".to_string()],
@@ -244,6 +344,7 @@ mod tests {
                 allowed_commands: None,
                 denied_flags: None,
                 max_execution_time: None,
+                network: false,
             },
         };
 
@@ -263,6 +364,8 @@ mod tests {
             version: "0.1.0".to_string(),
             path: "test.wasm".to_string(),
             hash: "sha256:test".to_string(),
+            output_schemas: HashMap::new(),
+            host_abi: None,
             permissions: PluginPermissions {
                 allowed_paths: vec![],
                 denied_paths: vec![],
@@ -271,6 +374,7 @@ mod tests {
                 allowed_commands: Some(vec!["git".to_string(), "ls".to_string()]),
                 denied_flags: Some(vec!["--force".to_string(), "-rf".to_string()]),
                 max_execution_time: Some(30),
+                network: false,
             },
         };
 
@@ -293,6 +397,8 @@ mod tests {
             version: "0.1.0".to_string(),
             path: "test.wasm".to_string(),
             hash: "sha256:test".to_string(),
+            output_schemas: HashMap::new(),
+            host_abi: None,
             permissions: PluginPermissions {
                 allowed_paths: vec![],
                 denied_paths: vec![],
@@ -301,6 +407,7 @@ mod tests {
                 allowed_commands: Some(vec!["git".to_string()]),
                 denied_flags: None,
                 max_execution_time: None,
+                network: false,
             },
         };
 
@@ -328,5 +435,96 @@ mod tests {
 
         // Default should have execution time limit
         assert_eq!(perms.max_execution_time, Some(30));
+
+        // Default should not reach the network
+        assert!(!perms.network);
+    }
+
+    #[test]
+    fn test_permission_expansions() {
+        let old = PluginPermissions::default();
+        assert!(old.expansions(&old.clone()).is_empty());
+
+        // Tighter permissions are not an expansion
+        let tighter = PluginPermissions {
+            max_file_size: Some(1024),
+            ..PluginPermissions::default()
+        };
+        assert!(old.expansions(&tighter).is_empty());
+
+        let wider = PluginPermissions {
+            network: true,
+            allowed_paths: vec!["workspace".to_string(), "/tmp".to_string()],
+            denied_flags: Some(vec!["--force".to_string()]),
+            max_file_size: None,
+            ..PluginPermissions::default()
+        };
+        assert_eq!(
+            old.expansions(&wider),
+            vec![
+                "network access",
+                "path '/tmp'",
+                "no longer denied flag '-rf'",
+                "no longer denied flag '--delete'",
+                "no longer denied flag '--hard'",
+                "file size up to unlimited",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_network_permission_is_optional() {
+        let json = r#"{
+            "allowed_paths": [],
+            "denied_paths": [],
+            "max_file_size": null,
+            "can_execute": false,
+            "allowed_commands": null,
+            "denied_flags": null,
+            "max_execution_time": null
+        }"#;
+
+        // Manifests from before the network permission parse and re-serialize unchanged
+        let perms: PluginPermissions = serde_json::from_str(json).unwrap();
+        assert!(!perms.network);
+        assert!(!serde_json::to_string(&perms).unwrap().contains("network"));
+    }
+
+    #[test]
+    fn test_output_schemas_default_and_round_trip() {
+        let json = r#"{
+            "name": "fs-editor",
+            "version": "0.1.0",
+            "path": "plugins/fs-editor.wasm",
+            "hash": "sha256:def456",
+            "permissions": {
+                "allowed_paths": [],
+                "denied_paths": [],
+                "max_file_size": null,
+                "can_execute": false,
+                "allowed_commands": null,
+                "denied_flags": null,
+                "max_execution_time": null
+            }
+        }"#;
+
+        // Older manifests without schemas still parse
+        let mut plugin: PluginEntry = serde_json::from_str(json).unwrap();
+        assert!(plugin.output_schemas.is_empty());
+
+        // Empty schemas are not serialized, so existing signatures stay valid
+        let serialized = serde_json::to_string(&plugin).unwrap();
+        assert!(!serialized.contains("output_schemas"));
+
+        plugin.output_schemas.insert(
+            "read_file".to_string(),
+            serde_json::json!({"type": "object", "required": ["content"]}),
+        );
+        let serialized = serde_json::to_string(&plugin).unwrap();
+        let parsed: PluginEntry = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(
+            parsed.output_schemas.get("read_file"),
+            plugin.output_schemas.get("read_file")
+        );
     }
 }
@@ -76,12 +76,41 @@ impl ToolInput {
     }
 }
 
+/// What the `data` of a `ToolOutput` holds, so consumers can render it
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ContentType {
+    /// `{"text": ...}` to be shown as plain text
+    #[serde(rename = "text/plain")]
+    Text,
+    /// Arbitrary JSON
+    #[default]
+    #[serde(rename = "application/json")]
+    Json,
+    /// `{"path": ...}` pointing at a PNG image on disk
+    #[serde(rename = "image/png-path")]
+    ImagePath,
+}
+
+impl ContentType {
+    /// MIME-style name, as serialized
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ContentType::Text => "text/plain",
+            ContentType::Json => "application/json",
+            ContentType::ImagePath => "image/png-path",
+        }
+    }
+}
+
 /// Output from a tool function
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ToolOutput {
     pub success: bool,
     pub data: serde_json::Value,
     pub error: Option<String>,
+    /// Missing in output from older tools, which is read as JSON
+    #[serde(default)]
+    pub content_type: ContentType,
 }
 
 impl ToolOutput {
@@ -91,6 +120,7 @@ impl ToolOutput {
             success: true,
             data: serde_json::json!({ "text": text.into() }),
             error: None,
+            content_type: ContentType::Text,
         }
     }
 
@@ -100,6 +130,17 @@ impl ToolOutput {
             success: true,
             data,
             error: None,
+            content_type: ContentType::Json,
+        }
+    }
+
+    /// Create a successful output pointing at an image file
+    pub fn image_path(path: impl Into<String>) -> Self {
+        Self {
+            success: true,
+            data: serde_json::json!({ "path": path.into() }),
+            error: None,
+            content_type: ContentType::ImagePath,
         }
     }
 
@@ -109,6 +150,7 @@ impl ToolOutput {
             success: false,
             data: serde_json::Value::Null,
             error: Some(error.into()),
+            content_type: ContentType::Text,
         }
     }
 
@@ -118,6 +160,7 @@ impl ToolOutput {
             success: true,
             data: serde_json::Value::Null,
             error: None,
+            content_type: ContentType::Json,
         }
     }
 
@@ -205,7 +248,7 @@ mod tests {
 
         let result = input.param_bool("enabled");
         assert!(result.is_ok());
-        assert_eq!(result.unwrap(), true);
+        assert!(result.unwrap());
     }
 
     #[test]
@@ -293,6 +336,7 @@ mod tests {
         assert!(output.success);
         assert_eq!(output.data, json!({"text": "Hello, World!"}));
         assert!(output.error.is_none());
+        assert_eq!(output.content_type, ContentType::Text);
     }
 
     #[test]
@@ -302,6 +346,16 @@ mod tests {
         assert!(output.success);
         assert_eq!(output.data, data);
         assert!(output.error.is_none());
+        assert_eq!(output.content_type, ContentType::Json);
+    }
+
+    #[test]
+    fn test_tool_output_image_path() {
+        let output = ToolOutput::image_path("/workspace/screenshot.png");
+        assert!(output.success);
+        assert_eq!(output.data, json!({"path": "/workspace/screenshot.png"}));
+        assert!(output.error.is_none());
+        assert_eq!(output.content_type, ContentType::ImagePath);
     }
 
     #[test]
@@ -310,6 +364,7 @@ mod tests {
         assert!(!output.success);
         assert_eq!(output.data, serde_json::Value::Null);
         assert_eq!(output.error, Some("Something went wrong".to_string()));
+        assert_eq!(output.content_type, ContentType::Text);
     }
 
     #[test]
@@ -318,6 +373,7 @@ mod tests {
         assert!(output.success);
         assert_eq!(output.data, serde_json::Value::Null);
         assert!(output.error.is_none());
+        assert_eq!(output.content_type, ContentType::Json);
     }
 
     #[test]
@@ -337,6 +393,19 @@ mod tests {
         assert_eq!(output.success, deserialized.success);
         assert_eq!(output.data, deserialized.data);
         assert_eq!(output.error, deserialized.error);
+        assert_eq!(output.content_type, deserialized.content_type);
+    }
+
+    #[test]
+    fn test_content_type_serialization() {
+        let value = serde_json::to_value(ToolOutput::image_path("shot.png")).unwrap();
+        assert_eq!(value["content_type"], "image/png-path");
+        assert_eq!(ContentType::Text.as_str(), "text/plain");
+
+        // Output without a content type still deserializes
+        let legacy: ToolOutput =
+            serde_json::from_str(r#"{"success":true,"data":{"a":1},"error":null}"#).unwrap();
+        assert_eq!(legacy.content_type, ContentType::Json);
     }
 
     #[test]
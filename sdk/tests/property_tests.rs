@@ -17,6 +17,8 @@ proptest! {
             EngineError::ToolError(error_str.clone()),
             EngineError::PathDenied(std::path::PathBuf::from(&error_str)),
             EngineError::HashMismatch(error_str.clone()),
+            EngineError::FeatureDisabled(error_str.clone()),
+            EngineError::EgressDenied(error_str.clone()),
         ];
 
         for err in errs {
@@ -47,6 +49,7 @@ proptest! {
         path_allowed in "[a-z0-9/_-]+"
     ) {
         use sdk::manifest::{Manifest, CoreToolEntry, PluginEntry, PluginPermissions};
+        use std::collections::HashMap;
 
         // Construct a syntactically valid model from random inputs
         let manifest = Manifest {
@@ -70,6 +73,8 @@ proptest! {
                     version: version.clone(),
                     path: format!("plugins/{}.wasm", plugin_name),
                     hash: "sha256:somehash2".to_string(),
+                    output_schemas: HashMap::new(),
+                    host_abi: None,
                     permissions: PluginPermissions {
                         allowed_paths: vec![path_allowed],
                         ..Default::default()